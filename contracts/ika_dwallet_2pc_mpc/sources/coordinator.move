@@ -37,7 +37,9 @@ const EInvalidMigration: u64 = 1;
 
 // === Constants ===
 /// Flag to indicate the version of the ika system.
-const VERSION: u64 = 1;
+/// Version 2 parses V2 dWallet checkpoint messages, validators only emit them once the
+/// coordinator is migrated to it.
+const VERSION: u64 = 2;
 
 // === Structs ===
 
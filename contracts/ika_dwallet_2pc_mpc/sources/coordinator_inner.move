@@ -93,6 +93,9 @@ const SET_MAX_ACTIVE_SESSIONS_BUFFER_MESSAGE_TYPE: u32 = 10;
 const SET_GAS_FEE_REIMBURSEMENT_SUI_SYSTEM_CALL_VALUE_MESSAGE_TYPE: u32 = 11;
 const END_OF_EPOCH_MESSAGE_TYPE: u32 = 12;
 
+/// Starts a V2 dwallet checkpoint message, in place of the epoch that starts a V1 one.
+const CHECKPOINT_MESSAGE_V2_MARKER: u64 = 18446744073709551615;
+
 // === Errors ===
 
 /// dWallet parameters do not match expected values
@@ -4460,7 +4463,12 @@ fun process_checkpoint_message(
 
     let mut bcs_body = bcs::new(copy message);
 
-    let epoch = bcs_body.peel_u64();
+    // V2 checkpoints start with a marker, and carry commitments after the messages,
+    // which are ignored here.
+    let mut epoch = bcs_body.peel_u64();
+    if (epoch == CHECKPOINT_MESSAGE_V2_MARKER) {
+        epoch = bcs_body.peel_u64();
+    };
     assert!(epoch == self.current_epoch, EIncorrectEpochInCheckpoint);
 
     let sequence_number = bcs_body.peel_u64();
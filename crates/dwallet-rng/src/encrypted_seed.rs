@@ -0,0 +1,478 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Encrypted storage of the [`RootSeed`].
+//!
+//! The seed is encrypted with XChaCha20-Poly1305 under a 32-byte wrapping key, which is either
+//! derived from a passphrase with Argon2id, or is a separate key-encryption key kept apart from
+//! the encrypted seed file. The file is a versioned JSON document, so the format and the
+//! KDF parameters can be changed without breaking existing files.
+
+use crate::RootSeed;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use fastcrypto::encoding::{Base64, Encoding};
+use group::OsCsRng;
+use ika_types::dwallet_mpc_error::{DwalletMPCError, DwalletMPCResult};
+use rand_chacha::rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+const ENCRYPTED_ROOT_SEED_VERSION: u32 = 1;
+const WRAPPING_KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+/// Authenticated together with the seed, so a ciphertext can't be used in another context.
+const ASSOCIATED_DATA: &[u8] = b"ika root seed v1";
+
+/// A key used to encrypt the root seed directly, without a KDF.
+///
+/// SECURITY NOTICE: *MUST BE KEPT PRIVATE*, and stored apart from the encrypted seed file.
+#[derive(Clone, ZeroizeOnDrop)]
+pub struct KeyEncryptionKey([u8; WRAPPING_KEY_LENGTH]);
+
+impl KeyEncryptionKey {
+    /// Generates a cryptographically secure random key-encryption key.
+    pub fn random() -> Self {
+        let mut bytes = [0u8; WRAPPING_KEY_LENGTH];
+        OsCsRng.fill_bytes(&mut bytes);
+        KeyEncryptionKey(bytes)
+    }
+
+    /// Reads a key-encryption key (encoded in Base64) from a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> DwalletMPCResult<Self> {
+        let contents = Zeroizing::new(
+            std::fs::read_to_string(path)
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?,
+        );
+        let decoded = Zeroizing::new(
+            Base64::decode(contents.trim())
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?,
+        );
+        let bytes: [u8; WRAPPING_KEY_LENGTH] = decoded.as_slice().try_into().map_err(|_| {
+            DwalletMPCError::FailedToReadSeed(format!(
+                "a key-encryption key must be {WRAPPING_KEY_LENGTH} bytes long"
+            ))
+        })?;
+        Ok(KeyEncryptionKey(bytes))
+    }
+
+    /// Writes the key, encoded in Base64, to a file readable only by its owner.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> DwalletMPCResult<()> {
+        let contents = Zeroizing::new(Base64::encode(self.0));
+        write_secret_file(path.as_ref(), contents.as_bytes())
+    }
+}
+
+/// How to unlock an encrypted root seed file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RootSeedUnlock {
+    /// The name of an environment variable holding the passphrase.
+    PassphraseEnv(String),
+    /// A file holding the passphrase. A trailing newline is ignored.
+    PassphraseFile(PathBuf),
+    /// A file holding a [`KeyEncryptionKey`].
+    KeyEncryptionKeyFile(PathBuf),
+}
+
+impl RootSeedUnlock {
+    /// Reads the secret that encrypts the seed, from the environment or from a file.
+    pub fn load(&self) -> DwalletMPCResult<SeedEncryptionKey> {
+        match self {
+            RootSeedUnlock::PassphraseEnv(name) => {
+                let passphrase = std::env::var(name).map_err(|e| {
+                    DwalletMPCError::FailedToReadSeed(format!(
+                        "failed to read the root seed passphrase from `{name}`: {e}"
+                    ))
+                })?;
+                SeedEncryptionKey::passphrase(passphrase)
+            }
+            RootSeedUnlock::PassphraseFile(path) => {
+                let mut passphrase = std::fs::read_to_string(path).map_err(|e| {
+                    DwalletMPCError::FailedToReadSeed(format!(
+                        "failed to read the root seed passphrase from {}: {e}",
+                        path.display()
+                    ))
+                })?;
+                // Truncate in place, so the newline-less copy isn't left behind un-zeroized.
+                let len = passphrase.trim_end_matches(['\r', '\n']).len();
+                passphrase.truncate(len);
+                SeedEncryptionKey::passphrase(passphrase)
+            }
+            RootSeedUnlock::KeyEncryptionKeyFile(path) => Ok(SeedEncryptionKey::KeyEncryptionKey(
+                KeyEncryptionKey::from_file(path)?,
+            )),
+        }
+    }
+}
+
+/// The secret the root seed is encrypted with.
+pub enum SeedEncryptionKey {
+    Passphrase(Zeroizing<String>),
+    KeyEncryptionKey(KeyEncryptionKey),
+}
+
+impl SeedEncryptionKey {
+    pub fn passphrase(passphrase: String) -> DwalletMPCResult<Self> {
+        let passphrase = Zeroizing::new(passphrase);
+        if passphrase.is_empty() {
+            return Err(DwalletMPCError::FailedToReadSeed(
+                "the root seed passphrase is empty".to_string(),
+            ));
+        }
+        Ok(SeedEncryptionKey::Passphrase(passphrase))
+    }
+}
+
+/// The Argon2id parameters used for new encrypted seed files.
+/// These are the RFC 9106 "second recommended option", which uses 64 MiB of memory.
+const DEFAULT_ARGON2ID_MEMORY_KIB: u32 = 64 * 1024;
+const DEFAULT_ARGON2ID_ITERATIONS: u32 = 3;
+const DEFAULT_ARGON2ID_PARALLELISM: u32 = 4;
+
+/// The largest Argon2id parameters accepted from an encrypted seed file,
+/// so that a crafted file can't exhaust the memory or CPU of the node.
+const MAX_ARGON2ID_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ARGON2ID_ITERATIONS: u32 = 16;
+const MAX_ARGON2ID_PARALLELISM: u32 = 16;
+
+/// How the wrapping key of an encrypted seed was obtained.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "algorithm", rename_all = "kebab-case")]
+enum SeedKdf {
+    /// Derived from a passphrase.
+    #[serde(rename_all = "kebab-case")]
+    Argon2id {
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+        /// Base64 encoded.
+        salt: String,
+    },
+    /// A [`KeyEncryptionKey`] used as is.
+    KeyEncryptionKey,
+}
+
+impl SeedKdf {
+    fn argon2id() -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsCsRng.fill_bytes(&mut salt);
+        SeedKdf::Argon2id {
+            memory_kib: DEFAULT_ARGON2ID_MEMORY_KIB,
+            iterations: DEFAULT_ARGON2ID_ITERATIONS,
+            parallelism: DEFAULT_ARGON2ID_PARALLELISM,
+            salt: Base64::encode(salt),
+        }
+    }
+
+    fn wrapping_key(
+        &self,
+        key: &SeedEncryptionKey,
+    ) -> DwalletMPCResult<Zeroizing<[u8; WRAPPING_KEY_LENGTH]>> {
+        match (self, key) {
+            (
+                SeedKdf::Argon2id {
+                    memory_kib,
+                    iterations,
+                    parallelism,
+                    salt,
+                },
+                SeedEncryptionKey::Passphrase(passphrase),
+            ) => {
+                for (name, value, max) in [
+                    ("memory-kib", *memory_kib, MAX_ARGON2ID_MEMORY_KIB),
+                    ("iterations", *iterations, MAX_ARGON2ID_ITERATIONS),
+                    ("parallelism", *parallelism, MAX_ARGON2ID_PARALLELISM),
+                ] {
+                    if value > max {
+                        return Err(DwalletMPCError::FailedToReadSeed(format!(
+                            "Argon2id {name} {value} is above the maximum of {max}"
+                        )));
+                    }
+                }
+                let salt = Base64::decode(salt)
+                    .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
+                let params = Params::new(
+                    *memory_kib,
+                    *iterations,
+                    *parallelism,
+                    Some(WRAPPING_KEY_LENGTH),
+                )
+                .map_err(|e| {
+                    DwalletMPCError::FailedToReadSeed(format!("invalid Argon2id parameters: {e}"))
+                })?;
+                let mut wrapping_key = Zeroizing::new([0u8; WRAPPING_KEY_LENGTH]);
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut wrapping_key[..])
+                    .map_err(|e| {
+                        DwalletMPCError::FailedToReadSeed(format!(
+                            "failed to derive the root seed wrapping key: {e}"
+                        ))
+                    })?;
+                Ok(wrapping_key)
+            }
+            (SeedKdf::KeyEncryptionKey, SeedEncryptionKey::KeyEncryptionKey(key)) => {
+                Ok(Zeroizing::new(key.0))
+            }
+            (SeedKdf::Argon2id { .. }, SeedEncryptionKey::KeyEncryptionKey(_)) => {
+                Err(DwalletMPCError::FailedToReadSeed(
+                    "the root seed is encrypted with a passphrase, not a key-encryption key"
+                        .to_string(),
+                ))
+            }
+            (SeedKdf::KeyEncryptionKey, SeedEncryptionKey::Passphrase(_)) => {
+                Err(DwalletMPCError::FailedToReadSeed(
+                    "the root seed is encrypted with a key-encryption key, not a passphrase"
+                        .to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// The on-disk format of an encrypted [`RootSeed`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EncryptedRootSeed {
+    version: u32,
+    kdf: SeedKdf,
+    /// Base64 encoded XChaCha20-Poly1305 nonce.
+    nonce: String,
+    /// Base64 encoded.
+    ciphertext: String,
+}
+
+impl EncryptedRootSeed {
+    pub fn encrypt(seed: &RootSeed, key: &SeedEncryptionKey) -> DwalletMPCResult<Self> {
+        let kdf = match key {
+            SeedEncryptionKey::Passphrase(_) => SeedKdf::argon2id(),
+            SeedEncryptionKey::KeyEncryptionKey(_) => SeedKdf::KeyEncryptionKey,
+        };
+        Self::encrypt_with_kdf(seed, key, kdf)
+    }
+
+    fn encrypt_with_kdf(
+        seed: &RootSeed,
+        key: &SeedEncryptionKey,
+        kdf: SeedKdf,
+    ) -> DwalletMPCResult<Self> {
+        let wrapping_key = kdf
+            .wrapping_key(key)
+            .map_err(|e| DwalletMPCError::FailedToWriteSeed(e.to_string()))?;
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsCsRng.fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&wrapping_key[..]))
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &seed.0,
+                    aad: ASSOCIATED_DATA,
+                },
+            )
+            .map_err(|e| {
+                DwalletMPCError::FailedToWriteSeed(format!("failed to encrypt the root seed: {e}"))
+            })?;
+        Ok(EncryptedRootSeed {
+            version: ENCRYPTED_ROOT_SEED_VERSION,
+            kdf,
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, key: &SeedEncryptionKey) -> DwalletMPCResult<RootSeed> {
+        if self.version != ENCRYPTED_ROOT_SEED_VERSION {
+            return Err(DwalletMPCError::FailedToReadSeed(format!(
+                "unsupported encrypted root seed version {}",
+                self.version
+            )));
+        }
+        let wrapping_key = self.kdf.wrapping_key(key)?;
+        let nonce = Base64::decode(&self.nonce)
+            .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(DwalletMPCError::FailedToReadSeed(format!(
+                "the root seed nonce must be {NONCE_LENGTH} bytes long"
+            )));
+        }
+        let ciphertext = Base64::decode(&self.ciphertext)
+            .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
+        let plaintext = Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(&wrapping_key[..]))
+                .decrypt(
+                    XNonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: ASSOCIATED_DATA,
+                    },
+                )
+                .map_err(|_| {
+                    DwalletMPCError::FailedToReadSeed(
+                        "failed to decrypt the root seed, wrong secret or corrupted file"
+                            .to_string(),
+                    )
+                })?,
+        );
+        let seed: [u8; RootSeed::SEED_LENGTH] = plaintext.as_slice().try_into().map_err(|_| {
+            DwalletMPCError::FailedToReadSeed(format!(
+                "the decrypted root seed must be {} bytes long",
+                RootSeed::SEED_LENGTH
+            ))
+        })?;
+        Ok(RootSeed::new(seed))
+    }
+
+    /// Parses an encrypted seed, returning `None` if `contents` is not in the encrypted format.
+    pub(crate) fn parse(contents: &str) -> Option<Self> {
+        serde_json::from_str(contents).ok()
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> DwalletMPCResult<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
+        serde_json::from_str(&contents).map_err(|e| {
+            DwalletMPCError::FailedToReadSeed(format!("invalid encrypted root seed file: {e}"))
+        })
+    }
+
+    /// Writes the encrypted seed to a file readable only by its owner.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> DwalletMPCResult<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| DwalletMPCError::FailedToWriteSeed(e.to_string()))?;
+        write_secret_file(path.as_ref(), contents.as_bytes())
+    }
+}
+
+/// Writes a secret to a new file, or truncates an existing one,
+/// restricting its permissions to the owner on Unix.
+pub(crate) fn write_secret_file(path: &Path, contents: &[u8]) -> DwalletMPCResult<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| {
+            // The mode only applies to a new file, an existing one keeps its permissions.
+            // Restrict them before the secret is written.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            }
+            file.write_all(contents)
+        })
+        .map_err(|e| DwalletMPCError::FailedToWriteSeed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters, so the tests don't spend their time in the KDF.
+    fn test_argon2id_kdf() -> SeedKdf {
+        SeedKdf::Argon2id {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+            salt: Base64::encode([7u8; SALT_LENGTH]),
+        }
+    }
+
+    #[test]
+    fn test_passphrase_round_trip() {
+        let seed = RootSeed::random_seed();
+        let key =
+            SeedEncryptionKey::passphrase("correct horse battery staple".to_string()).unwrap();
+        let encrypted =
+            EncryptedRootSeed::encrypt_with_kdf(&seed, &key, test_argon2id_kdf()).unwrap();
+
+        let contents = serde_json::to_string_pretty(&encrypted).unwrap();
+        let parsed = EncryptedRootSeed::parse(&contents).unwrap();
+        assert_eq!(parsed.decrypt(&key).unwrap(), seed);
+
+        let wrong_key = SeedEncryptionKey::passphrase("wrong passphrase".to_string()).unwrap();
+        assert!(parsed.decrypt(&wrong_key).is_err());
+    }
+
+    #[test]
+    fn test_key_encryption_key_round_trip() {
+        let seed = RootSeed::random_seed();
+        let key = SeedEncryptionKey::KeyEncryptionKey(KeyEncryptionKey::random());
+        let encrypted = EncryptedRootSeed::encrypt(&seed, &key).unwrap();
+        assert_eq!(encrypted.decrypt(&key).unwrap(), seed);
+
+        let other_key = SeedEncryptionKey::KeyEncryptionKey(KeyEncryptionKey::random());
+        assert!(encrypted.decrypt(&other_key).is_err());
+        let passphrase = SeedEncryptionKey::passphrase("passphrase".to_string()).unwrap();
+        assert!(encrypted.decrypt(&passphrase).is_err());
+    }
+
+    #[test]
+    fn test_argon2id_parameters_are_bounded() {
+        let seed = RootSeed::random_seed();
+        let key = SeedEncryptionKey::passphrase("passphrase".to_string()).unwrap();
+        let encrypted =
+            EncryptedRootSeed::encrypt_with_kdf(&seed, &key, test_argon2id_kdf()).unwrap();
+
+        for kdf in [
+            SeedKdf::Argon2id {
+                memory_kib: u32::MAX,
+                iterations: 1,
+                parallelism: 1,
+                salt: Base64::encode([7u8; SALT_LENGTH]),
+            },
+            SeedKdf::Argon2id {
+                memory_kib: 64,
+                iterations: u32::MAX,
+                parallelism: 1,
+                salt: Base64::encode([7u8; SALT_LENGTH]),
+            },
+            SeedKdf::Argon2id {
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: MAX_ARGON2ID_PARALLELISM + 1,
+                salt: Base64::encode([7u8; SALT_LENGTH]),
+            },
+        ] {
+            let crafted = EncryptedRootSeed {
+                kdf,
+                ..encrypted.clone()
+            };
+            let Err(DwalletMPCError::FailedToReadSeed(error)) = crafted.decrypt(&key) else {
+                panic!("decrypted a seed with unbounded Argon2id parameters");
+            };
+            assert!(error.contains("above the maximum"), "{error}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_secret_file_restricts_existing_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("root-seed.key");
+        std::fs::write(&path, "old contents").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_secret_file(&path, b"secret").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"secret");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_plain_seed_is_not_parsed_as_encrypted() {
+        let contents = Base64::encode(RootSeed::random_seed().0);
+        assert!(EncryptedRootSeed::parse(&contents).is_none());
+    }
+}
@@ -6,7 +6,13 @@ use merlin::Transcript;
 use rand_chacha::ChaCha20Rng;
 use rand_chacha::rand_core::{RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
-use zeroize::ZeroizeOnDrop;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+mod encrypted_seed;
+mod seed_shares;
+
+pub use encrypted_seed::{EncryptedRootSeed, KeyEncryptionKey, RootSeedUnlock, SeedEncryptionKey};
+pub use seed_shares::RootSeedShare;
 
 /// The Root Seed for this validator, used to deterministically derive purpose-specific child seeds
 /// for all cryptographically-secure random generation operations.
@@ -30,14 +36,26 @@ impl RootSeed {
     }
 
     /// Reads a class group seed (encoded in Base64) from a file.
+    ///
+    /// Fails if the file holds an encrypted seed, use [`Self::from_encrypted_file`] to read it.
     pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> DwalletMPCResult<Self> {
-        let contents = std::fs::read_to_string(path)
-            .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
-        let decoded = Base64::decode(contents.as_str().trim())
-            .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?;
-        Ok(RootSeed::new(decoded.try_into().map_err(|e| {
+        let contents = Zeroizing::new(
+            std::fs::read_to_string(path)
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?,
+        );
+        if EncryptedRootSeed::parse(&contents).is_some() {
+            return Err(DwalletMPCError::FailedToReadSeed(
+                "the root seed file is encrypted, configure how to unlock it".to_string(),
+            ));
+        }
+        let decoded = Zeroizing::new(
+            Base64::decode(contents.as_str().trim())
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?,
+        );
+        let seed: [u8; Self::SEED_LENGTH] = decoded.as_slice().try_into().map_err(|e| {
             DwalletMPCError::FailedToReadSeed(format!("failed to read class group seed: {e:?}"))
-        })?))
+        })?;
+        Ok(RootSeed::new(seed))
     }
 
     /// Writes the seed, encoded in Base64,
@@ -52,6 +70,27 @@ impl RootSeed {
         Ok(contents)
     }
 
+    /// Reads an encrypted seed from a file, and decrypts it with the secret `unlock` points to.
+    pub fn from_encrypted_file<P: AsRef<std::path::Path>>(
+        path: P,
+        unlock: &RootSeedUnlock,
+    ) -> DwalletMPCResult<Self> {
+        let encrypted = EncryptedRootSeed::from_file(path)?;
+        encrypted.decrypt(&unlock.load()?)
+    }
+
+    /// Encrypts the seed with the secret `unlock` points to, and writes it to a file.
+    pub fn save_to_encrypted_file<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        unlock: &RootSeedUnlock,
+    ) -> DwalletMPCResult<()> {
+        let key = unlock
+            .load()
+            .map_err(|e| DwalletMPCError::FailedToWriteSeed(e.to_string()))?;
+        EncryptedRootSeed::encrypt(self, &key)?.save_to_file(path)
+    }
+
     /// Derive a seed for deterministically generating
     /// this validator's class-groups decryption key and proof [`ClassGroupsKeyPairAndProof`].
     ///
@@ -0,0 +1,313 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Backup of the [`RootSeed`] with Shamir secret sharing.
+//!
+//! The seed is split byte by byte over GF(2^8), so any `threshold` of the shares recover it,
+//! while fewer reveal nothing about it. Each share is encoded as text, with a version,
+//! the threshold, an identifier of the seed it belongs to, and a checksum,
+//! so that typos, and shares of different seeds, are detected before recovery.
+
+use crate::RootSeed;
+use crate::encrypted_seed::write_secret_file;
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::{HashFunction, Sha256};
+use group::OsCsRng;
+use ika_types::dwallet_mpc_error::{DwalletMPCError, DwalletMPCResult};
+use rand_chacha::rand_core::RngCore;
+use std::collections::HashSet;
+use std::path::Path;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+const SHARE_PREFIX: &str = "ika-root-seed-share:";
+const SHARE_VERSION: u8 = 1;
+const SEED_ID_LENGTH: usize = 4;
+const CHECKSUM_LENGTH: usize = 4;
+/// The version, the threshold, the index, the seed identifier and the share value.
+const SHARE_PAYLOAD_LENGTH: usize = 3 + SEED_ID_LENGTH + RootSeed::SEED_LENGTH;
+
+/// A share of the root seed.
+///
+/// SECURITY NOTICE: *MUST BE KEPT PRIVATE*, and apart from the other shares.
+#[derive(Clone, ZeroizeOnDrop)]
+pub struct RootSeedShare {
+    threshold: u8,
+    /// The x-coordinate of the share, never zero.
+    index: u8,
+    seed_id: [u8; SEED_ID_LENGTH],
+    value: [u8; RootSeed::SEED_LENGTH],
+}
+
+impl RootSeedShare {
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn encode(&self) -> Zeroizing<String> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SHARE_PAYLOAD_LENGTH + CHECKSUM_LENGTH));
+        bytes.extend_from_slice(&[SHARE_VERSION, self.threshold, self.index]);
+        bytes.extend_from_slice(&self.seed_id);
+        bytes.extend_from_slice(&self.value);
+        let checksum = share_checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        Zeroizing::new(format!("{SHARE_PREFIX}{}", Base64::encode(&*bytes)))
+    }
+
+    pub fn decode(encoded: &str) -> DwalletMPCResult<Self> {
+        let encoded = encoded.trim().strip_prefix(SHARE_PREFIX).ok_or_else(|| {
+            DwalletMPCError::InvalidRootSeedShares(format!(
+                "a share must start with `{SHARE_PREFIX}`"
+            ))
+        })?;
+        let bytes = Zeroizing::new(
+            Base64::decode(encoded)
+                .map_err(|e| DwalletMPCError::InvalidRootSeedShares(e.to_string()))?,
+        );
+        if bytes.len() != SHARE_PAYLOAD_LENGTH + CHECKSUM_LENGTH {
+            return Err(DwalletMPCError::InvalidRootSeedShares(format!(
+                "a share must be {} bytes long",
+                SHARE_PAYLOAD_LENGTH + CHECKSUM_LENGTH
+            )));
+        }
+        let (payload, checksum) = bytes.split_at(SHARE_PAYLOAD_LENGTH);
+        if share_checksum(payload) != checksum {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "the share checksum doesn't match, the share is corrupted".to_string(),
+            ));
+        }
+        if payload[0] != SHARE_VERSION {
+            return Err(DwalletMPCError::InvalidRootSeedShares(format!(
+                "unsupported share version {}",
+                payload[0]
+            )));
+        }
+        let (threshold, index) = (payload[1], payload[2]);
+        if threshold < 2 || index == 0 {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "the share threshold or index is invalid".to_string(),
+            ));
+        }
+        let mut seed_id = [0u8; SEED_ID_LENGTH];
+        seed_id.copy_from_slice(&payload[3..3 + SEED_ID_LENGTH]);
+        let mut value = [0u8; RootSeed::SEED_LENGTH];
+        value.copy_from_slice(&payload[3 + SEED_ID_LENGTH..]);
+        Ok(RootSeedShare {
+            threshold,
+            index,
+            seed_id,
+            value,
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> DwalletMPCResult<Self> {
+        let contents = Zeroizing::new(
+            std::fs::read_to_string(path)
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(e.to_string()))?,
+        );
+        Self::decode(&contents)
+    }
+
+    /// Writes the encoded share to a file readable only by its owner.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> DwalletMPCResult<()> {
+        write_secret_file(path.as_ref(), self.encode().as_bytes())
+    }
+}
+
+impl RootSeed {
+    /// Splits the seed into `total_shares` shares, any `threshold` of which recover it.
+    pub fn split_into_shares(
+        &self,
+        threshold: u8,
+        total_shares: u8,
+    ) -> DwalletMPCResult<Vec<RootSeedShare>> {
+        if threshold < 2 || threshold > total_shares {
+            return Err(DwalletMPCError::InvalidRootSeedShares(format!(
+                "the threshold must be at least 2 and at most the number of shares, \
+                 got {threshold}-of-{total_shares}"
+            )));
+        }
+        // The coefficients of a random polynomial per seed byte, whose constant term is the byte.
+        let mut coefficients =
+            Zeroizing::new(vec![[0u8; RootSeed::SEED_LENGTH]; threshold as usize - 1]);
+        for coefficient in coefficients.iter_mut() {
+            OsCsRng.fill_bytes(coefficient);
+        }
+        let seed_id = self.share_id();
+        Ok((1..=total_shares)
+            .map(|index| {
+                let mut value = [0u8; RootSeed::SEED_LENGTH];
+                for (byte, share_byte) in value.iter_mut().enumerate() {
+                    // Horner's method, from the highest degree coefficient.
+                    let higher_terms = coefficients.iter().rev().fold(0, |acc, coefficient| {
+                        gf256_mul(acc, index) ^ coefficient[byte]
+                    });
+                    *share_byte = gf256_mul(higher_terms, index) ^ self.0[byte];
+                }
+                RootSeedShare {
+                    threshold,
+                    index,
+                    seed_id,
+                    value,
+                }
+            })
+            .collect())
+    }
+
+    /// Recovers the seed from at least `threshold` shares of it.
+    pub fn combine_shares(shares: &[RootSeedShare]) -> DwalletMPCResult<Self> {
+        let Some(first) = shares.first() else {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "no shares were given".to_string(),
+            ));
+        };
+        if shares
+            .iter()
+            .any(|share| share.threshold != first.threshold || share.seed_id != first.seed_id)
+        {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "the shares belong to different seeds or splits".to_string(),
+            ));
+        }
+        let indices: HashSet<_> = shares.iter().map(|share| share.index).collect();
+        if indices.len() != shares.len() {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "the same share was given more than once".to_string(),
+            ));
+        }
+        if shares.len() < first.threshold as usize {
+            return Err(DwalletMPCError::InvalidRootSeedShares(format!(
+                "{} shares are required, but only {} were given",
+                first.threshold,
+                shares.len()
+            )));
+        }
+
+        // Lagrange interpolation at zero, where subtraction in GF(2^8) is XOR.
+        let shares = &shares[..first.threshold as usize];
+        let mut seed = [0u8; RootSeed::SEED_LENGTH];
+        for share in shares {
+            let basis = shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |acc, other| {
+                    gf256_mul(
+                        acc,
+                        gf256_mul(other.index, gf256_inv(other.index ^ share.index)),
+                    )
+                });
+            for (byte, value) in seed.iter_mut().zip(share.value.iter()) {
+                *byte ^= gf256_mul(basis, *value);
+            }
+        }
+        let seed = RootSeed::new(seed);
+        if seed.share_id() != first.seed_id {
+            return Err(DwalletMPCError::InvalidRootSeedShares(
+                "the recovered seed doesn't match the shares, a share is wrong".to_string(),
+            ));
+        }
+        Ok(seed)
+    }
+
+    /// A short public identifier of the seed, stored in its shares.
+    fn share_id(&self) -> [u8; SEED_ID_LENGTH] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"ika root seed share id");
+        hasher.update(self.0);
+        let mut seed_id = [0u8; SEED_ID_LENGTH];
+        seed_id.copy_from_slice(&hasher.finalize().digest[..SEED_ID_LENGTH]);
+        seed_id
+    }
+}
+
+fn share_checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"ika root seed share checksum");
+    hasher.update(payload);
+    let mut checksum = [0u8; CHECKSUM_LENGTH];
+    checksum.copy_from_slice(&hasher.finalize().digest[..CHECKSUM_LENGTH]);
+    checksum
+}
+
+/// Multiplication in GF(2^8) modulo the AES polynomial x^8 + x^4 + x^3 + x + 1.
+/// It doesn't branch on its inputs, since they are secret.
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// The multiplicative inverse in GF(2^8), as `a^254`.
+fn gf256_inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+    let mut exponent: u8 = 254;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = gf256_mul(result, power);
+        }
+        power = gf256_mul(power, power);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf256_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(gf256_mul(a, gf256_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_shares_recover_the_seed() {
+        let seed = RootSeed::random_seed();
+        let shares = seed.split_into_shares(3, 5).unwrap();
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let subset: Vec<_> = subset.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(RootSeed::combine_shares(&subset).unwrap(), seed);
+        }
+        assert!(RootSeed::combine_shares(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn test_share_encoding() {
+        let seed = RootSeed::random_seed();
+        let shares = seed.split_into_shares(2, 3).unwrap();
+        let decoded: Vec<_> = shares
+            .iter()
+            .map(|share| RootSeedShare::decode(&share.encode()).unwrap())
+            .collect();
+        assert_eq!(RootSeed::combine_shares(&decoded[1..]).unwrap(), seed);
+
+        // Flip a character of the Base64 payload.
+        let mut corrupted = shares[0].encode().to_string();
+        let position = SHARE_PREFIX.len() + 10;
+        let replacement = if &corrupted[position..=position] == "A" {
+            "B"
+        } else {
+            "A"
+        };
+        corrupted.replace_range(position..=position, replacement);
+        assert!(RootSeedShare::decode(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_shares_of_different_seeds_are_rejected() {
+        let shares = RootSeed::random_seed().split_into_shares(2, 2).unwrap();
+        let other_shares = RootSeed::random_seed().split_into_shares(2, 2).unwrap();
+        assert!(RootSeed::combine_shares(&[shares[0].clone(), other_shares[1].clone()]).is_err());
+    }
+}
@@ -18,7 +18,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use sui_types::base_types::{ObjectID, SuiAddress};
 
-use dwallet_rng::RootSeed;
+use dwallet_rng::{RootSeed, RootSeedUnlock};
 use ika_types::crypto::AuthorityPublicKeyBytes;
 use ika_types::crypto::KeypairTraits;
 use ika_types::crypto::NetworkKeyPair;
@@ -60,8 +60,15 @@ impl fmt::Display for SuiChainIdentifier {
 #[serde(rename_all = "kebab-case")]
 pub struct SuiConnectorConfig {
     /// Rpc url for Sui fullnode, used for query stuff and submit transactions.
+    /// New events are read on every checkpoint the fullnode announces over its gRPC API,
+    /// served on the same url, or polled if it doesn't serve it.
     #[serde(default = "default_sui_rpc_url")]
     pub sui_rpc_url: String,
+    /// Rpc urls of additional Sui fullnodes.
+    /// Requests are routed to the healthiest of `sui_rpc_url` and these endpoints,
+    /// and fail over to the next one when an endpoint errors or stalls.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_sui_rpc_urls: Vec<String>,
     /// The expected sui chain identifier connecting to.
     pub sui_chain_identifier: SuiChainIdentifier,
     /// The move package ID of ika (IKA) on sui.
@@ -82,6 +89,11 @@ pub struct SuiConnectorConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notifier_client_key_pair: Option<KeyPairWithPath>,
 
+    /// Only for sui connector notifiers, in place of `notifier_client_key_pair`.
+    /// The signing service that holds the notifier's sui client key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifier_remote_signer: Option<RemoteSignerConfig>,
+
     /// Override the last processed EventID for sui module `ika_system`.
     /// When set, SuiSyncer will start from this cursor (exclusively) instead of the one in storage.
     /// If the cursor is not found in storage or override, the query will start from genesis.
@@ -91,6 +103,18 @@ pub struct SuiConnectorConfig {
     /// Otherwise, it will miss one event because of fullnode Event query semantics.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sui_ika_system_module_last_processed_event_id_override: Option<EventID>,
+
+    /// Only for sui connector notifiers.
+    /// The number of gas coins the notifier splits its balance into,
+    /// so that independent transactions can be submitted to Sui in parallel.
+    #[serde(default = "default_notifier_gas_coin_count")]
+    pub notifier_gas_coin_count: usize,
+
+    /// Only for sui connector notifiers.
+    /// The total gas balance, in MIST, below which the notifier reports a low balance.
+    /// Defaults to the gas budget of a single transaction for each gas coin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifier_low_gas_balance_threshold: Option<u64>,
 }
 
 #[serde_as]
@@ -99,6 +123,12 @@ pub struct SuiConnectorConfig {
 pub struct NodeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_seed_key_pair: Option<RootSeedWithPath>,
+    /// The root seed of a class-groups key rotation, set from when its key is published
+    /// for the next epoch until the node switched to it.
+    /// At every epoch start, the node uses whichever of `root-seed-key-pair` and this seed
+    /// derives the validator's class-groups key in the epoch's committee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_root_seed_key_pair: Option<RootSeedWithPath>,
     #[serde(default = "default_authority_key_pair")]
     pub protocol_key_pair: AuthorityKeyPairWithPath,
     #[serde(default = "default_key_pair")]
@@ -139,6 +169,11 @@ pub struct NodeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metrics: Option<MetricsConfig>,
 
+    /// Export tracing spans to an OpenTelemetry collector over OTLP.
+    /// The `OTLP_ENDPOINT`, `TRACE_FILTER` and `SAMPLE_RATE` environment variables take precedence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otlp_tracing: Option<OtlpTracingConfig>,
+
     /// In a `ika-node` binary, this is set to SupportedProtocolVersions::SYSTEM_DEFAULT
     /// in ika-node/src/main.rs. It is present in the config so that it can be changed by tests in
     /// order to test protocol upgrades.
@@ -161,6 +196,47 @@ fn default_sui_rpc_url() -> String {
     LOCAL_DEFAULT_SUI_FULLNODE_RPC_URL.to_string()
 }
 
+impl SuiConnectorConfig {
+    /// All the configured Sui fullnode Rpc urls, starting with the primary `sui_rpc_url`.
+    pub fn sui_rpc_urls(&self) -> Vec<String> {
+        std::iter::once(self.sui_rpc_url.clone())
+            .chain(self.additional_sui_rpc_urls.iter().cloned())
+            .collect()
+    }
+}
+
+pub fn default_notifier_gas_coin_count() -> usize {
+    4
+}
+
+/// A signing service that holds a Sui key, so that the key is never loaded by the node.
+///
+/// The service is called over HTTP/JSON:
+/// - `GET {url}/v1/keys/{key_id}` returns `{"public_key": <Base64 flag || public key>}`.
+/// - `POST {url}/v1/keys/{key_id}/sign` with `{"intent_message": <Base64 BCS intent message>}`
+///   returns `{"signature": <Base64 flag || signature || public key>}`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteSignerConfig {
+    /// Base url of the signing service, e.g. `https://signer.internal:9443`.
+    pub url: String,
+    /// The ID of the key in the signing service.
+    pub key_id: String,
+    /// The Sui address of the key, checked against the public key the signing service returns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<SuiAddress>,
+    /// Path of a file holding a bearer token that authenticates the node to the signing service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_token_path: Option<PathBuf>,
+    /// Timeout of every request to the signing service.
+    #[serde(default = "default_remote_signer_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+pub fn default_remote_signer_request_timeout_ms() -> u64 {
+    10_000
+}
+
 fn default_grpc_address() -> Multiaddr {
     "/ip4/0.0.0.0/tcp/8080".parse().unwrap()
 }
@@ -330,6 +406,45 @@ pub struct MetricsConfig {
     pub push_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OtlpTracingConfig {
+    /// The OTLP/gRPC endpoint of the collector.
+    ///
+    /// If unspecified, this will default to `http://localhost:4317`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// An `EnvFilter` directive selecting the spans to export.
+    ///
+    /// If unspecified, this will default to `info`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// The fraction of traces to sample, between `0.0` and `1.0`.
+    ///
+    /// If unspecified, this will default to `1.0`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<f64>,
+}
+
+impl OtlpTracingConfig {
+    pub fn endpoint(&self) -> &str {
+        const DEFAULT_ENDPOINT: &str = "http://localhost:4317";
+        self.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT)
+    }
+
+    pub fn filter(&self) -> &str {
+        const DEFAULT_FILTER: &str = "info";
+        self.filter.as_deref().unwrap_or(DEFAULT_FILTER)
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        const DEFAULT_SAMPLE_RATE: f64 = 1.0;
+        self.sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArchiveReaderConfig {
     pub remote_store_config: ObjectStoreConfig,
@@ -550,6 +665,14 @@ pub fn read_authority_keypair_from_file(path: &PathBuf) -> AuthorityKeyPair {
 }
 
 /// Wrapper struct for RootSeed that can be deserialized from a file path.
+///
+/// An encrypted seed file also specifies how to unlock it, e.g.:
+/// ```yaml
+/// root-seed-key-pair:
+///   path: /opt/ika/key-pairs/root-seed.enc
+///   unlock:
+///     passphrase-env: IKA_ROOT_SEED_PASSPHRASE
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RootSeedWithPath {
     #[serde(flatten)]
@@ -562,8 +685,33 @@ pub struct RootSeedWithPath {
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq)]
 #[serde(untagged)]
 enum RootSeedLocation {
-    InPlace { value: RootSeed },
-    File { path: PathBuf },
+    InPlace {
+        value: RootSeed,
+    },
+    // Must come before `File`, which would otherwise match an encrypted file and ignore `unlock`.
+    EncryptedFile {
+        path: PathBuf,
+        unlock: RootSeedUnlock,
+    },
+    File {
+        path: PathBuf,
+    },
+}
+
+impl RootSeedLocation {
+    fn load(&self) -> anyhow::Result<RootSeed> {
+        match self {
+            RootSeedLocation::InPlace { value } => Ok(value.clone()),
+            RootSeedLocation::EncryptedFile { path, unlock } => {
+                RootSeed::from_encrypted_file(path, unlock).map_err(|e| {
+                    anyhow::anyhow!("failed to read the root seed from {}: {e}", path.display())
+                })
+            }
+            RootSeedLocation::File { path } => RootSeed::from_file(path).map_err(|e| {
+                anyhow::anyhow!("failed to read the root seed from {}: {e}", path.display())
+            }),
+        }
+    }
 }
 
 impl RootSeedWithPath {
@@ -588,14 +736,94 @@ impl RootSeedWithPath {
         }
     }
 
+    /// Like [`Self::root_seed`], but returns an error instead of panicking
+    /// if the root seed file can't be read.
+    pub fn try_root_seed(&self) -> anyhow::Result<&RootSeed> {
+        self.seed.get_or_try_init(|| self.location.load())
+    }
+
     pub fn root_seed(&self) -> &RootSeed {
-        self.seed.get_or_init(|| match &self.location {
-            RootSeedLocation::InPlace { value } => value.clone(),
-            RootSeedLocation::File { path } => {
-                // OK to unwrap panic because validator
-                // should not start without seed loaded.
-                RootSeed::from_file(path.clone()).unwrap()
-            }
-        })
+        // OK to unwrap panic because validator
+        // should not start without seed loaded.
+        self.seed.get_or_init(|| self.location.load().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_config_yaml(extra: &str) -> String {
+        let object_id = |byte| ObjectID::from_single_byte(byte).to_string();
+        format!(
+            "db-path: /opt/ika/db
+sui-connector-config:
+  sui-chain-identifier: testnet
+  ika-package-id: {}
+  ika-common-package-id: {}
+  ika-dwallet-2pc-mpc-package-id: {}
+  ika-system-package-id: {}
+  ika-system-object-id: {}
+  ika-dwallet-coordinator-object-id: {}
+{extra}",
+            object_id(1),
+            object_id(2),
+            object_id(3),
+            object_id(4),
+            object_id(5),
+            object_id(6),
+        )
+    }
+
+    fn round_trip(config: &NodeConfig) -> (String, NodeConfig) {
+        let yaml = serde_yaml::to_string(config).unwrap();
+        let config = serde_yaml::from_str(&yaml).unwrap();
+        (yaml, config)
+    }
+
+    #[test]
+    fn otlp_tracing_is_off_by_default() {
+        let config: NodeConfig = serde_yaml::from_str(&node_config_yaml("")).unwrap();
+        assert_eq!(config.otlp_tracing, None);
+
+        let (yaml, config) = round_trip(&config);
+        assert!(!yaml.contains("otlp-tracing"));
+        assert_eq!(config.otlp_tracing, None);
+    }
+
+    #[test]
+    fn otlp_tracing_config_round_trips() {
+        let config: NodeConfig = serde_yaml::from_str(&node_config_yaml(
+            "otlp-tracing:
+  endpoint: http://collector:4317
+  filter: ika_core=debug
+  sample-rate: 0.25
+",
+        ))
+        .unwrap();
+        let expected = OtlpTracingConfig {
+            endpoint: Some("http://collector:4317".to_string()),
+            filter: Some("ika_core=debug".to_string()),
+            sample_rate: Some(0.25),
+        };
+        assert_eq!(config.otlp_tracing, Some(expected.clone()));
+
+        let (_, config) = round_trip(&config);
+        assert_eq!(config.otlp_tracing, Some(expected));
+    }
+
+    #[test]
+    fn empty_otlp_tracing_section_uses_the_defaults() {
+        let config: NodeConfig =
+            serde_yaml::from_str(&node_config_yaml("otlp-tracing: {}\n")).unwrap();
+        let otlp_tracing = config.otlp_tracing.clone().unwrap();
+        assert_eq!(otlp_tracing, OtlpTracingConfig::default());
+        assert_eq!(otlp_tracing.endpoint(), "http://localhost:4317");
+        assert_eq!(otlp_tracing.filter(), "info");
+        assert_eq!(otlp_tracing.sample_rate(), 1.0);
+
+        // An enabled exporter with no settings stays enabled after a round trip.
+        let (_, config) = round_trip(&config);
+        assert_eq!(config.otlp_tracing, Some(OtlpTracingConfig::default()));
     }
 }
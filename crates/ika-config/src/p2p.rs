@@ -41,6 +41,10 @@ pub struct P2pConfig {
     /// If unspecified, this will default to 8 MiB.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub excessive_message_size: Option<usize>,
+    /// Controls which peers may call the StateSync and Discovery RPCs of this node,
+    /// and how often.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_control: Option<PeerAccessConfig>,
 }
 
 fn default_listen_address() -> SocketAddr {
@@ -58,6 +62,7 @@ impl Default for P2pConfig {
             state_sync: None,
             discovery: None,
             excessive_message_size: None,
+            access_control: None,
         }
     }
 }
@@ -92,6 +97,59 @@ pub struct AllowlistedPeer {
     pub address: Option<Multiaddr>,
 }
 
+/// Access controls of the inbound StateSync and Discovery RPCs.
+///
+/// Requests that are rejected by these controls fail without reaching the RPC handlers.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PeerAccessConfig {
+    /// If set, only these peers may call the RPCs of this node. The members of the committee,
+    /// and the seed peers, fixed peers and `allowlisted_peers` of the discovery config that have
+    /// a peer ID are always allowed.
+    ///
+    /// If unspecified, every peer that is not denylisted or banned is allowed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowlist: Option<Vec<anemo::PeerId>>,
+
+    /// Peers that may never call the RPCs of this node, and that it never dials or syncs from.
+    /// The denylist takes precedence over the allowlist.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub denylist: Vec<anemo::PeerId>,
+
+    /// Per-peer rate-limit (in requests/sec) shared by all the StateSync and Discovery RPCs.
+    /// It applies in addition to the per-RPC rate-limits of the state sync and discovery configs.
+    ///
+    /// If unspecified, this will default to no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_peer_rate_limit: Option<NonZeroU32>,
+
+    /// Per-peer limit on the number of inflight requests, shared by all the StateSync and
+    /// Discovery RPCs.
+    ///
+    /// If unspecified, this will default to no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_peer_inflight_limit: Option<usize>,
+
+    /// How long a peer that served an invalid checkpoint, or network encryption key data that
+    /// doesn't match the on-chain objects, is banned for. A banned peer is disconnected, is not
+    /// synced from, and its requests are rejected. Set to `0` to never ban.
+    ///
+    /// If unspecified, this will default to `600,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_checkpoint_ban_duration_ms: Option<u64>,
+}
+
+impl PeerAccessConfig {
+    pub fn invalid_checkpoint_ban_duration(&self) -> Duration {
+        const INVALID_CHECKPOINT_BAN_DURATION_MS: u64 = 600_000; // 10 minutes
+
+        Duration::from_millis(
+            self.invalid_checkpoint_ban_duration_ms
+                .unwrap_or(INVALID_CHECKPOINT_BAN_DURATION_MS),
+        )
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct StateSyncConfig {
@@ -128,6 +186,8 @@ pub struct StateSyncConfig {
     pub synced_dwallet_checkpoint_broadcast_channel_capacity: Option<usize>,
 
     /// Set the upper bound on the number of dwallet checkpoint headers to be downloaded concurrently.
+    /// The actual concurrency adapts to how fast peers serve the headers, and never exceeds this
+    /// bound.
     ///
     /// If unspecified, this will default to `400`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -189,6 +249,14 @@ pub struct StateSyncConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wait_interval_when_no_peer_to_sync_content_ms: Option<u64>,
 
+    /// How long a checkpoint header may take to download from peers before the download is
+    /// considered slow. Slow downloads lower the download concurrency and wake up the sync from
+    /// the archives instead of waiting for its next poll.
+    ///
+    /// If unspecified, this will default to `5,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_fallback_latency_ms: Option<u64>,
+
     /// List of "known-good" system checkpoints that state sync will be forced to use. State sync will
     /// skip verification of pinned system checkpoints, and reject system checkpoints with digests that don't
     /// match pinned values for a given sequence number.
@@ -205,6 +273,8 @@ pub struct StateSyncConfig {
     pub synced_system_checkpoint_broadcast_channel_capacity: Option<usize>,
 
     /// Set the upper bound on the number of system checkpoint headers to be downloaded concurrently.
+    /// The actual concurrency adapts to how fast peers serve the headers, and never exceeds this
+    /// bound.
     ///
     /// If unspecified, this will default to `400`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -332,6 +402,14 @@ impl StateSyncConfig {
             .unwrap_or(self.default_wait_interval_when_no_peer_to_sync_content())
     }
 
+    pub fn archive_fallback_latency(&self) -> Duration {
+        const DEFAULT_ARCHIVE_FALLBACK_LATENCY: Duration = Duration::from_secs(5);
+
+        self.archive_fallback_latency_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_ARCHIVE_FALLBACK_LATENCY)
+    }
+
     fn default_wait_interval_when_no_peer_to_sync_content(&self) -> Duration {
         if cfg!(msim) {
             Duration::from_secs(5)
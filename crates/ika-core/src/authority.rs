@@ -59,6 +59,7 @@ pub mod authority_per_epoch_store;
 
 pub mod authority_perpetual_tables;
 pub mod epoch_start_configuration;
+pub mod system_parameter_votes;
 
 #[allow(unused)]
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on.
@@ -700,6 +701,10 @@ impl AuthorityState {
         &self.committee_store
     }
 
+    pub fn perpetual_tables(&self) -> &Arc<AuthorityPerpetualTables> {
+        &self.perpetual_tables
+    }
+
     pub fn clone_committee_store(&self) -> Arc<CommitteeStore> {
         self.committee_store.clone()
     }
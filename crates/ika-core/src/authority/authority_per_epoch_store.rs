@@ -24,6 +24,8 @@ use typed_store::rocksdb::Options;
 use super::epoch_start_configuration::EpochStartConfigTrait;
 
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+pub use crate::authority::system_parameter_votes::SystemParameterVoteTally;
+use crate::authority::system_parameter_votes::SystemParameterVotes;
 use crate::authority::{AuthorityCapabilitiesVotingResults, AuthorityMetrics, AuthorityState};
 use crate::dwallet_checkpoints::{
     BuilderDWalletCheckpointMessage, DWalletCheckpointHeight, DWalletCheckpointServiceNotify,
@@ -53,7 +55,7 @@ use ika_types::message::DWalletCheckpointMessageKind;
 use ika_types::messages_consensus::Round;
 use ika_types::messages_consensus::{
     AuthorityCapabilitiesV1, ConsensusTransaction, ConsensusTransactionKey,
-    ConsensusTransactionKind,
+    ConsensusTransactionKind, SystemParameterVote,
 };
 use ika_types::messages_dwallet_checkpoint::{
     DWalletCheckpointMessage, DWalletCheckpointSequenceNumber, DWalletCheckpointSignatureMessage,
@@ -62,7 +64,7 @@ use ika_types::messages_dwallet_mpc::IkaNetworkConfig;
 use ika_types::messages_dwallet_mpc::{DWalletMPCMessage, DWalletMPCOutput};
 use ika_types::messages_system_checkpoints::{
     SystemCheckpointMessage, SystemCheckpointMessageKind, SystemCheckpointSequenceNumber,
-    SystemCheckpointSignatureMessage,
+    SystemCheckpointSignatureMessage, SystemParameterChange,
 };
 use ika_types::sui::epoch_start_system::{EpochStartSystem, EpochStartSystemTrait};
 use mpc::WeightedThresholdAccessStructure;
@@ -359,6 +361,8 @@ pub struct AuthorityPerEpochStore {
     pub packages_config: IkaNetworkConfig,
     reconfig_state: RwLock<ReconfigState>,
     end_of_publish: Mutex<StakeAggregator<(), true>>,
+    /// Stake-weighted votes for system parameter changes received in this epoch.
+    system_parameter_votes: Mutex<SystemParameterVotes>,
 }
 
 /// The reconfiguration state of the authority.
@@ -438,6 +442,9 @@ pub struct AuthorityEpochTables {
     /// Validators that sent a EndOfPublish message in this epoch.
     end_of_publish: DBMap<AuthorityName, ()>,
 
+    /// Votes for system parameter changes received in this epoch.
+    system_parameter_votes: DBMap<(SystemParameterChange, AuthorityName), ()>,
+
     /// Contains a single key, which overrides the value of
     /// ProtocolConfig::buffer_stake_for_protocol_upgrade_bps
     override_protocol_upgrade_buffer_stake: DBMap<u64, u64>,
@@ -507,6 +514,13 @@ impl AuthorityEpochTables {
         parent_path.join(format!("{EPOCH_DB_PREFIX}{epoch}"))
     }
 
+    /// Writes a consistent RocksDB checkpoint of all the epoch tables to `path`.
+    pub fn checkpoint_db(&self, path: &Path) -> IkaResult {
+        self.consensus_message_processed
+            .checkpoint_db(path)
+            .map_err(Into::into)
+    }
+
     pub fn get_all_pending_consensus_transactions(&self) -> IkaResult<Vec<ConsensusTransaction>> {
         Ok(self
             .pending_consensus_transactions
@@ -566,6 +580,11 @@ impl AuthorityPerEpochStore {
             ProtocolConfig::get_for_version(protocol_version, chain_identifier.chain());
         let end_of_publish =
             StakeAggregator::from_iter(committee.clone(), tables.end_of_publish.safe_iter())?;
+        let mut system_parameter_votes = SystemParameterVotes::new(committee.clone());
+        for item in tables.system_parameter_votes.safe_iter() {
+            let ((change, authority), ()) = item?;
+            system_parameter_votes.insert(&SystemParameterVote { authority, change });
+        }
         let s = Arc::new(Self {
             name,
             committee: committee.clone(),
@@ -587,6 +606,7 @@ impl AuthorityPerEpochStore {
                 status: ReconfigCertStatus::AcceptAllCerts,
             }),
             end_of_publish: Mutex::new(end_of_publish),
+            system_parameter_votes: Mutex::new(system_parameter_votes),
         });
 
         s.update_buffer_stake_metric();
@@ -856,6 +876,18 @@ impl AuthorityPerEpochStore {
         Ok(())
     }
 
+    pub fn record_system_parameter_vote(&self, vote: &SystemParameterVote) -> IkaResult {
+        self.tables()?
+            .system_parameter_votes
+            .insert(&(vote.change.clone(), vote.authority), &())?;
+        Ok(())
+    }
+
+    /// Returns the votes for system parameter changes received so far in this epoch.
+    pub fn get_system_parameter_votes(&self) -> Vec<SystemParameterVoteTally> {
+        self.system_parameter_votes.lock().tally()
+    }
+
     pub async fn user_certs_closed_notify(&self) {
         self.user_certs_closed_notify.wait().await
     }
@@ -1011,6 +1043,29 @@ impl AuthorityPerEpochStore {
                     return None;
                 }
             }
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::SystemParameterVote(vote),
+                ..
+            }) => {
+                if !self.protocol_config.system_parameter_governance() {
+                    warn!(
+                        "SystemParameterVote from {} received while system parameter governance is disabled",
+                        transaction.certificate_author_index
+                    );
+                    return None;
+                }
+                if transaction.sender_authority() != vote.authority {
+                    warn!(
+                        "SystemParameterVote authority {} does not match its author from consensus {}",
+                        vote.authority, transaction.certificate_author_index
+                    );
+                    return None;
+                }
+                if let Err(err) = vote.change.validate() {
+                    warn!(?vote, ?err, "ignoring invalid SystemParameterVote");
+                    return None;
+                }
+            }
         }
         Some(VerifiedSequencedConsensusTransaction(transaction))
     }
@@ -1387,7 +1442,41 @@ impl AuthorityPerEpochStore {
                 }
                 Ok(ConsensusCertificateResult::ConsensusMessage)
             }
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::SystemParameterVote(vote),
+                ..
+            }) => self.process_system_parameter_vote(vote),
+        }
+    }
+
+    /// Tallies a vote for a system parameter change, and emits the matching system checkpoint
+    /// message once the change reaches a quorum of stake.
+    /// Votes sequenced after EndOfPublish reached quorum are ignored, since a change reaching
+    /// quorum then could no longer be included in this epoch's system checkpoints.
+    fn process_system_parameter_vote(
+        &self,
+        vote: &SystemParameterVote,
+    ) -> IkaResult<ConsensusCertificateResult> {
+        let end_of_publish_reached = self.end_of_publish.lock().has_quorum();
+        let mut system_parameter_votes = self.system_parameter_votes.lock();
+        if let Some(rejection) = system_parameter_votes.rejection(vote, end_of_publish_reached) {
+            warn!(?vote, ?rejection, "ignoring SystemParameterVote");
+            return Ok(ConsensusCertificateResult::ConsensusMessage);
+        }
+
+        self.record_system_parameter_vote(vote)?;
+        debug!(?vote, "Received SystemParameterVote");
+        if system_parameter_votes.insert(vote) {
+            info!(
+                validator=?self.name,
+                change=%vote.change,
+                "System parameter change reached quorum",
+            );
+            return Ok(ConsensusCertificateResult::SystemTransaction(vec![
+                vote.change.clone().into(),
+            ]));
         }
+        Ok(ConsensusCertificateResult::ConsensusMessage)
     }
 
     pub fn get_pending_dwallet_checkpoints(
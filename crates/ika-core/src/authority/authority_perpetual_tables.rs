@@ -8,6 +8,7 @@ use typed_store::traits::Map;
 
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use ika_types::messages_dwallet_mpc::SessionIdentifier;
+use sui_types::event::EventID;
 use typed_store::DBMapUtils;
 use typed_store::rocks::{DBBatch, DBMap, MetricConf};
 use typed_store::rocksdb::Options;
@@ -24,6 +25,10 @@ pub struct AuthorityPerpetualTables {
     /// Holds the completed MPC session IDs, to avoid re-using them in the case of a bug
     /// or in the unlikely case of a malicious full-node/Move contract/Sui network.
     pub(crate) dwallet_mpc_computation_completed_sessions: DBMap<SessionIdentifier, ()>,
+
+    /// The last Sui event of each watched Move module that was delivered to the node,
+    /// with the epoch it was read in, so the events listener resumes from it after a restart.
+    pub(crate) sui_event_cursors: DBMap<String, (EpochId, EventID)>,
 }
 
 impl AuthorityPerpetualTables {
@@ -66,6 +71,13 @@ impl AuthorityPerpetualTables {
         Ok(self.pruned_checkpoint.get(&())?.unwrap_or_default())
     }
 
+    /// Writes a consistent RocksDB checkpoint of all the perpetual tables to `path`.
+    pub fn checkpoint_db(&self, path: &Path) -> IkaResult {
+        self.epoch_start_configuration
+            .checkpoint_db(path)
+            .map_err(Into::into)
+    }
+
     pub fn set_highest_pruned_checkpoint(
         &self,
         wb: &mut DBBatch,
@@ -119,4 +131,14 @@ impl AuthorityPerpetualTables {
         wb.write()?;
         Ok(())
     }
+
+    pub fn get_sui_event_cursor(&self, module: &str) -> IkaResult<Option<(EpochId, EventID)>> {
+        Ok(self.sui_event_cursors.get(&module.to_string())?)
+    }
+
+    pub fn set_sui_event_cursor(&self, module: &str, epoch: EpochId, cursor: EventID) -> IkaResult {
+        self.sui_event_cursors
+            .insert(&module.to_string(), &(epoch, cursor))?;
+        Ok(())
+    }
 }
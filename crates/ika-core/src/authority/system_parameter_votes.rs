@@ -0,0 +1,243 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::stake_aggregator::StakeAggregator;
+use ika_types::committee::{Committee, StakeUnit};
+use ika_types::crypto::AuthorityName;
+use ika_types::messages_consensus::SystemParameterVote;
+use ika_types::messages_system_checkpoints::SystemParameterChange;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The current tally of votes for a single system parameter change.
+#[derive(Clone, Debug)]
+pub struct SystemParameterVoteTally {
+    pub change: SystemParameterChange,
+    pub voters: Vec<AuthorityName>,
+    pub total_votes: StakeUnit,
+    pub quorum_threshold: StakeUnit,
+    pub quorum_reached: bool,
+}
+
+/// Why a system parameter vote is not counted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SystemParameterVoteRejection {
+    /// EndOfPublish already reached quorum, so a change reaching quorum now could not be
+    /// included in this epoch's system checkpoints.
+    AfterEndOfPublish,
+    /// The authority already voted for this change.
+    Duplicate,
+    /// The authority already voted for another value of the same parameter.
+    Conflicting(SystemParameterChange),
+}
+
+/// Stake-weighted votes for system parameter changes received in an epoch.
+/// Each authority may vote for a single value per parameter,
+/// so that conflicting values of the same parameter can never both reach quorum.
+pub struct SystemParameterVotes {
+    committee: Arc<Committee>,
+    votes: BTreeMap<SystemParameterChange, StakeAggregator<(), true>>,
+}
+
+impl SystemParameterVotes {
+    pub fn new(committee: Arc<Committee>) -> Self {
+        Self {
+            committee,
+            votes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns why `vote` must not be counted, or `None` if it may be.
+    pub fn rejection(
+        &self,
+        vote: &SystemParameterVote,
+        end_of_publish_reached: bool,
+    ) -> Option<SystemParameterVoteRejection> {
+        if end_of_publish_reached {
+            return Some(SystemParameterVoteRejection::AfterEndOfPublish);
+        }
+        self.votes.iter().find_map(|(change, votes)| {
+            if !change.is_same_parameter(&vote.change) || !votes.contains_key(&vote.authority) {
+                None
+            } else if *change == vote.change {
+                Some(SystemParameterVoteRejection::Duplicate)
+            } else {
+                Some(SystemParameterVoteRejection::Conflicting(change.clone()))
+            }
+        })
+    }
+
+    /// Counts `vote`, which the caller checked with [`Self::rejection`].
+    /// Returns `true` if the change reached quorum with this vote.
+    pub fn insert(&mut self, vote: &SystemParameterVote) -> bool {
+        let votes = self
+            .votes
+            .entry(vote.change.clone())
+            .or_insert_with(|| StakeAggregator::new(self.committee.clone()));
+        !votes.has_quorum() && votes.insert_generic(vote.authority, ()).is_quorum_reached()
+    }
+
+    pub fn tally(&self) -> Vec<SystemParameterVoteTally> {
+        let quorum_threshold = self.committee.quorum_threshold();
+        self.votes
+            .iter()
+            .map(|(change, votes)| SystemParameterVoteTally {
+                change: change.clone(),
+                voters: votes.keys().copied().collect(),
+                total_votes: votes.total_votes(),
+                quorum_threshold,
+                quorum_reached: votes.has_quorum(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn votes() -> (SystemParameterVotes, Vec<AuthorityName>) {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let authorities = committee.names().copied().collect();
+        (SystemParameterVotes::new(Arc::new(committee)), authorities)
+    }
+
+    fn vote(authority: AuthorityName, change: SystemParameterChange) -> SystemParameterVote {
+        SystemParameterVote { authority, change }
+    }
+
+    /// Counts `vote` the way the epoch store does, returning whether it reached quorum.
+    fn cast(
+        votes: &mut SystemParameterVotes,
+        vote: &SystemParameterVote,
+    ) -> Result<bool, SystemParameterVoteRejection> {
+        match votes.rejection(vote, false) {
+            Some(rejection) => Err(rejection),
+            None => Ok(votes.insert(vote)),
+        }
+    }
+
+    #[test]
+    fn test_quorum_reached() {
+        let (mut votes, authorities) = votes();
+        let change = SystemParameterChange::SetEpochDurationMs(3_600_000);
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[0], change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[1], change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[2], change.clone())),
+            Ok(true)
+        );
+        // A vote after quorum is counted, but does not emit the change again.
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[3], change.clone())),
+            Ok(false)
+        );
+
+        let tally = votes.tally();
+        assert_eq!(tally.len(), 1);
+        assert_eq!(tally[0].change, change);
+        assert_eq!(tally[0].voters.len(), 4);
+        assert!(tally[0].quorum_reached);
+    }
+
+    #[test]
+    fn test_quorum_not_reached() {
+        let (mut votes, authorities) = votes();
+        let change = SystemParameterChange::SetMinValidatorCount(5);
+        let other_change = SystemParameterChange::SetMinValidatorCount(6);
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[0], change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[1], change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[2], other_change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[3], other_change)),
+            Ok(false)
+        );
+
+        let tally = votes.tally();
+        assert_eq!(tally.len(), 2);
+        assert!(tally.iter().all(|tally| !tally.quorum_reached));
+        assert!(
+            tally
+                .iter()
+                .all(|tally| tally.total_votes < tally.quorum_threshold)
+        );
+    }
+
+    #[test]
+    fn test_vote_after_end_of_publish_is_rejected() {
+        let (votes, authorities) = votes();
+        let vote = vote(
+            authorities[0],
+            SystemParameterChange::SetStakeSubsidyRate(100),
+        );
+        assert_eq!(
+            votes.rejection(&vote, true),
+            Some(SystemParameterVoteRejection::AfterEndOfPublish)
+        );
+        assert_eq!(votes.rejection(&vote, false), None);
+    }
+
+    #[test]
+    fn test_duplicate_and_conflicting_votes() {
+        let (mut votes, authorities) = votes();
+        let change = SystemParameterChange::SetMaxValidatorCount(100);
+        let conflicting_change = SystemParameterChange::SetMaxValidatorCount(200);
+        let other_parameter = SystemParameterChange::SetMinValidatorCount(4);
+
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[0], change.clone())),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[0], change.clone())),
+            Err(SystemParameterVoteRejection::Duplicate)
+        );
+        assert_eq!(
+            cast(
+                &mut votes,
+                &vote(authorities[0], conflicting_change.clone())
+            ),
+            Err(SystemParameterVoteRejection::Conflicting(change.clone()))
+        );
+        // Votes for other parameters and from other authorities are unaffected.
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[0], other_parameter)),
+            Ok(false)
+        );
+        assert_eq!(
+            cast(&mut votes, &vote(authorities[1], conflicting_change)),
+            Ok(false)
+        );
+
+        // The rejected votes did not count towards either value.
+        let tally = votes.tally();
+        let voters = |change: &SystemParameterChange| {
+            tally
+                .iter()
+                .find(|tally| &tally.change == change)
+                .unwrap()
+                .voters
+                .clone()
+        };
+        assert_eq!(voters(&change), vec![authorities[0]]);
+        assert_eq!(
+            voters(&SystemParameterChange::SetMaxValidatorCount(200)),
+            vec![authorities[1]]
+        );
+    }
+}
@@ -16,6 +16,7 @@ pub use crate::dwallet_checkpoints::dwallet_checkpoint_output::{
     LogDWalletCheckpointOutput, SendDWalletCheckpointToStateSync,
     SubmitDWalletCheckpointToConsensus,
 };
+use crate::dwallet_mpc::session_latency::SessionLatencyTracker;
 use crate::stake_aggregator::{InsertResult, MultiStakeAggregator};
 use mysten_metrics::{monitored_future, monitored_scope};
 use parking_lot::Mutex;
@@ -23,13 +24,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 
+use ika_types::committee::{Committee, EpochId};
 use ika_types::crypto::AuthorityStrongQuorumSignInfo;
 use ika_types::digests::DWalletCheckpointMessageDigest;
 use ika_types::error::{IkaError, IkaResult};
 use ika_types::message::DWalletCheckpointMessageKind;
 use ika_types::message_envelope::Message;
 use ika_types::messages_dwallet_checkpoint::{
-    CertifiedDWalletCheckpointMessage, DWalletCheckpointMessage, DWalletCheckpointSequenceNumber,
+    CertifiedDWalletCheckpointMessage, DWalletCheckpointMessage,
+    DWalletCheckpointMessageInclusionProof, DWalletCheckpointSequenceNumber,
     DWalletCheckpointSignatureMessage, SignedDWalletCheckpointMessage,
     TrustedDWalletCheckpointMessage, VerifiedDWalletCheckpointMessage,
 };
@@ -37,6 +40,7 @@ use itertools::Itertools;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
 use tokio::{sync::Notify, task::JoinSet, time::timeout};
 use tracing::{debug, error, info, instrument, warn};
 use typed_store::DBMapUtils;
@@ -48,6 +52,10 @@ use typed_store::{
 
 pub type DWalletCheckpointHeight = u64;
 
+/// How long the builder waits for the next epoch committee before it retries the last
+/// checkpoint of the epoch.
+const NEXT_EPOCH_COMMITTEE_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub struct EpochStats {
     pub dwallet_checkpoint_count: u64,
     pub transaction_count: u64,
@@ -158,6 +166,11 @@ impl DWalletCheckpointStore {
         )
     }
 
+    /// Writes a consistent RocksDB checkpoint of all the dwallet_checkpoint tables to `path`.
+    pub fn checkpoint_db(&self, path: &Path) -> IkaResult {
+        self.watermarks.checkpoint_db(path).map_err(Into::into)
+    }
+
     pub fn get_dwallet_checkpoint_by_digest(
         &self,
         digest: &DWalletCheckpointMessageDigest,
@@ -181,6 +194,18 @@ impl DWalletCheckpointStore {
             .map(|maybe_checkpoint| maybe_checkpoint.map(|c| c.into()))
     }
 
+    /// Builds an inclusion proof for the message at `message_index` of the certified
+    /// dwallet checkpoint `sequence_number`, or `None` if that checkpoint is unknown.
+    pub fn get_message_inclusion_proof(
+        &self,
+        sequence_number: DWalletCheckpointSequenceNumber,
+        message_index: usize,
+    ) -> IkaResult<Option<DWalletCheckpointMessageInclusionProof>> {
+        self.get_dwallet_checkpoint_by_sequence_number(sequence_number)?
+            .map(|checkpoint| checkpoint.data().message_inclusion_proof(message_index))
+            .transpose()
+    }
+
     pub fn get_locally_computed_checkpoint(
         &self,
         sequence_number: DWalletCheckpointSequenceNumber,
@@ -397,6 +422,7 @@ pub struct DWalletCheckpointBuilder {
     max_messages_per_dwallet_checkpoint: usize,
     max_dwallet_checkpoint_size_bytes: usize,
     previous_epoch_last_checkpoint_sequence_number: u64,
+    next_epoch_committee_receiver: watch::Receiver<Committee>,
 }
 
 pub struct DWalletCheckpointAggregator {
@@ -408,6 +434,7 @@ pub struct DWalletCheckpointAggregator {
     previous_epoch_last_checkpoint_sequence_number: u64,
     state: Arc<AuthorityState>,
     metrics: Arc<DWalletCheckpointMetrics>,
+    session_latency: SessionLatencyTracker,
 }
 
 // This holds information to aggregate signatures for one dwallet_checkpoint.
@@ -434,6 +461,7 @@ impl DWalletCheckpointBuilder {
         max_messages_per_dwallet_checkpoint: usize,
         max_dwallet_checkpoint_size_bytes: usize,
         previous_epoch_last_checkpoint_sequence_number: u64,
+        next_epoch_committee_receiver: watch::Receiver<Committee>,
     ) -> Self {
         Self {
             state,
@@ -446,6 +474,7 @@ impl DWalletCheckpointBuilder {
             max_messages_per_dwallet_checkpoint,
             max_dwallet_checkpoint_size_bytes,
             previous_epoch_last_checkpoint_sequence_number,
+            next_epoch_committee_receiver,
         }
     }
 
@@ -464,13 +493,16 @@ impl DWalletCheckpointBuilder {
         }
 
         loop {
-            self.maybe_build_dwallet_checkpoints().await;
-
-            self.notify.notified().await;
+            // A failed checkpoint is retried without waiting for new pending checkpoints,
+            // which may never come once it's the last one of the epoch.
+            if self.maybe_build_dwallet_checkpoints().await {
+                self.notify.notified().await;
+            }
         }
     }
 
-    async fn maybe_build_dwallet_checkpoints(&mut self) {
+    /// Builds the pending checkpoints, returns `false` if one failed and must be retried.
+    async fn maybe_build_dwallet_checkpoints(&mut self) -> bool {
         let _scope = monitored_scope("BuildDWalletCheckpoints");
 
         // Collect info about the most recently built dwallet checkpoint.
@@ -505,9 +537,10 @@ impl DWalletCheckpointBuilder {
                 );
                 tokio::time::sleep(Duration::from_secs(1)).await;
                 self.metrics.dwallet_checkpoint_errors.inc();
-                return;
+                return false;
             }
         }
+        true
     }
 
     #[instrument(level = "debug", skip_all, fields(last_height = pending_checkpoints.last().unwrap().details().checkpoint_height))]
@@ -638,6 +671,34 @@ impl DWalletCheckpointBuilder {
         Ok(chunks)
     }
 
+    /// Validators only send `EndOfPublish` once the next epoch committee is set on Sui,
+    /// so waiting for it here does not hold back the last checkpoint of the epoch.
+    /// Gives up when the epoch ends, or after `NEXT_EPOCH_COMMITTEE_TIMEOUT` so the builder
+    /// retries the checkpoint.
+    async fn next_epoch_committee(&self, next_epoch: EpochId) -> anyhow::Result<Committee> {
+        let mut receiver = self.next_epoch_committee_receiver.clone();
+        let wait_for_committee = timeout(NEXT_EPOCH_COMMITTEE_TIMEOUT, async {
+            receiver
+                .wait_for(|committee| committee.epoch == next_epoch)
+                .await
+                .map(|committee| committee.clone())
+        });
+        match self
+            .epoch_store
+            .within_alive_epoch(wait_for_committee)
+            .await
+        {
+            Ok(Ok(committee)) => Ok(committee?),
+            Ok(Err(_)) => Err(anyhow::anyhow!(
+                "timed out after {NEXT_EPOCH_COMMITTEE_TIMEOUT:?} waiting for the committee \
+                 of epoch {next_epoch}"
+            )),
+            Err(()) => Err(anyhow::anyhow!(
+                "the epoch ended while waiting for the committee of epoch {next_epoch}"
+            )),
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn create_checkpoints(
         &self,
@@ -707,8 +768,23 @@ impl DWalletCheckpointBuilder {
                 "Creating a dwallet checkpoint"
             );
 
-            let checkpoint_message =
+            let mut checkpoint_message =
                 DWalletCheckpointMessage::new(epoch, sequence_number, messages);
+            if self
+                .epoch_store
+                .protocol_config()
+                .dwallet_checkpoint_messages_merkle_root()
+            {
+                checkpoint_message = checkpoint_message.with_messages_merkle_root()?;
+                if matches!(
+                    checkpoint_message.messages.last(),
+                    Some(DWalletCheckpointMessageKind::EndOfPublish)
+                ) {
+                    let next_epoch_committee = self.next_epoch_committee(epoch + 1).await?;
+                    checkpoint_message =
+                        checkpoint_message.with_next_epoch_committee(&next_epoch_committee)?;
+                }
+            }
             checkpoints.push(checkpoint_message);
             tokio::task::yield_now().await;
         }
@@ -820,6 +896,7 @@ impl DWalletCheckpointAggregator {
         previous_epoch_last_checkpoint_sequence_number: u64,
         state: Arc<AuthorityState>,
         metrics: Arc<DWalletCheckpointMetrics>,
+        session_latency: SessionLatencyTracker,
     ) -> Self {
         let current = None;
         Self {
@@ -831,6 +908,7 @@ impl DWalletCheckpointAggregator {
             previous_epoch_last_checkpoint_sequence_number,
             state,
             metrics,
+            session_latency,
         }
     }
 
@@ -978,6 +1056,8 @@ impl DWalletCheckpointAggregator {
                     self.metrics
                         .last_certified_dwallet_checkpoint
                         .set(current.checkpoint_message.sequence_number as i64);
+                    self.session_latency
+                        .record_checkpoint_certified(&current.checkpoint_message);
                     result.push(checkpoint_message.into_inner());
                     self.current = None;
                     continue 'outer;
@@ -1144,9 +1224,11 @@ impl DWalletCheckpointService {
         checkpoint_output: Box<dyn DWalletCheckpointOutput>,
         certified_checkpoint_output: Box<dyn CertifiedDWalletCheckpointMessageOutput>,
         metrics: Arc<DWalletCheckpointMetrics>,
+        session_latency: SessionLatencyTracker,
         max_messages_per_checkpoint: usize,
         max_checkpoint_size_bytes: usize,
         previous_epoch_last_checkpoint_sequence_number: u64,
+        next_epoch_committee_receiver: watch::Receiver<Committee>,
     ) -> (Arc<Self>, JoinSet<()> /* Handle to tasks */) {
         info!(
             max_messages_per_checkpoint,
@@ -1168,6 +1250,7 @@ impl DWalletCheckpointService {
             max_messages_per_checkpoint,
             max_checkpoint_size_bytes,
             previous_epoch_last_checkpoint_sequence_number,
+            next_epoch_committee_receiver,
         );
         tasks.spawn(monitored_future!(builder.run()));
 
@@ -1179,6 +1262,7 @@ impl DWalletCheckpointService {
             previous_epoch_last_checkpoint_sequence_number,
             state.clone(),
             metrics.clone(),
+            session_latency,
         );
         tasks.spawn(monitored_future!(aggregator.run()));
 
@@ -35,6 +35,7 @@ use ika_types::messages_dwallet_mpc::{
 use mpc::{GuaranteedOutputDeliveryRoundResult, WeightedThresholdAccessStructure};
 use rand_chacha::ChaCha20Rng;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use sui_types::base_types::ObjectID;
 use tokio::sync::oneshot;
 use tracing::error;
@@ -50,6 +51,18 @@ pub struct DwalletMPCNetworkKeys {
     /// This data is identical for all the Validator nodes.
     pub(crate) network_encryption_keys: HashMap<ObjectID, NetworkEncryptionKeyPublicData>,
     pub(crate) validator_private_dec_key_data: ValidatorPrivateDecryptionKeyData,
+    /// The outcome of the last decryption of this validator's shares of each key.
+    pub(crate) shares_decryptions: HashMap<ObjectID, NetworkKeySharesDecryption>,
+}
+
+/// The outcome of decrypting this validator's shares of a network key.
+#[derive(Clone, Debug)]
+pub struct NetworkKeySharesDecryption {
+    /// The epoch of the public output the shares were decrypted from.
+    pub epoch: u64,
+    pub duration: Duration,
+    /// The error the decryption failed with, `None` if it succeeded.
+    pub error: Option<String>,
 }
 
 /// Holds the private decryption key data for a validator node.
@@ -185,6 +198,7 @@ impl DwalletMPCNetworkKeys {
         Self {
             network_encryption_keys: Default::default(),
             validator_private_dec_key_data: node_context,
+            shares_decryptions: Default::default(),
         }
     }
 
@@ -195,9 +209,27 @@ impl DwalletMPCNetworkKeys {
         access_structure: &WeightedThresholdAccessStructure,
     ) -> DwalletMPCResult<()> {
         self.network_encryption_keys.insert(key_id, key.clone());
-        self.validator_private_dec_key_data
+        let start = Instant::now();
+        let result = self
+            .validator_private_dec_key_data
             .decrypt_and_store_secret_key_shares(key_id, key.clone(), access_structure)
-            .await
+            .await;
+        self.shares_decryptions.insert(
+            key_id,
+            NetworkKeySharesDecryption {
+                epoch: key.epoch,
+                duration: start.elapsed(),
+                error: result.as_ref().err().map(ToString::to_string),
+            },
+        );
+        result
+    }
+
+    /// Whether this validator holds its decryption key shares of the key.
+    pub fn has_decryption_key_shares(&self, key_id: &ObjectID) -> bool {
+        self.validator_private_dec_key_data
+            .validator_decryption_key_shares
+            .contains_key(key_id)
     }
 
     pub fn get_decryption_key_share_public_parameters(
@@ -27,7 +27,7 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, info_span};
 
 /// Channel size for cryptographic computations state updates.
 /// This channel should not reach a size even close to this.
@@ -107,6 +107,13 @@ impl CryptographicComputationsOrchestrator {
         })
     }
 
+    /// The computations that were spawned and have not completed yet.
+    pub(crate) fn currently_running_cryptographic_computations(
+        &self,
+    ) -> impl Iterator<Item = &ComputationId> {
+        self.currently_running_cryptographic_computations.iter()
+    }
+
     /// Check for completed computations, and return their results.
     pub(crate) fn receive_completed_computations(
         &mut self,
@@ -178,6 +185,11 @@ impl CryptographicComputationsOrchestrator {
         completed_computation_results
     }
 
+    /// The number of spawned cryptographic computations that haven't completed yet.
+    pub(crate) fn running_computations_count(&self) -> usize {
+        self.currently_running_cryptographic_computations.len()
+    }
+
     /// Check if sufficient CPU cores are available for computation.
     fn has_available_cores_to_perform_computation(&mut self) -> bool {
         self.currently_running_cryptographic_computations.len()
@@ -236,9 +248,20 @@ impl CryptographicComputationsOrchestrator {
             "Starting cryptographic computation",
         );
 
+        // Rayon threads don't inherit the caller's span, so it is created here and entered
+        // on the worker thread to keep the computation in the session's trace.
+        let span = info_span!(
+            "mpc_computation",
+            session_identifier=?computation_id.session_identifier,
+            protocol=%request_input,
+            mpc_round=computation_id.mpc_round,
+            attempt_number=computation_id.attempt_number,
+            party_id,
+        );
         let computation_channel_sender = self.completed_computation_sender.clone();
         let root_seed = self.root_seed.clone();
         rayon::spawn_fifo(move || {
+            let _entered = span.enter();
             let advance_start_time = Instant::now();
 
             let computation_result = computation_request.clone().compute(
@@ -0,0 +1,126 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Drain mode takes a validator out of MPC work before a maintenance restart.
+//!
+//! Restarting mid-session forces every active session to recompute, and if several operators
+//! restart at once, sessions may not reach the threshold. While draining, the node keeps
+//! participating in consensus and advancing the user sessions it already started computing,
+//! so that they don't lose its share, but doesn't start new user sessions, which the rest of
+//! the committee completes.
+//! System sessions (network DKG and reconfiguration) are still advanced, as the epoch depends on them.
+//!
+//! Once these sessions are done and no computation is running, the node reports it is ready
+//! to stop, and the Sui executor
+//! stops the node at its next safe point, after the certified checkpoints were executed on Sui.
+
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrainState {
+    /// The node participates in MPC sessions as usual.
+    #[default]
+    Inactive,
+    /// The node finishes the sessions it started, and doesn't start new user sessions.
+    Draining,
+    /// The node's MPC work is done, it will stop at the next safe point.
+    ReadyToStop,
+}
+
+impl fmt::Display for DrainState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            DrainState::Inactive => "inactive",
+            DrainState::Draining => "draining",
+            DrainState::ReadyToStop => "ready-to-stop",
+        };
+        write!(f, "{state}")
+    }
+}
+
+/// A handle to the drain mode of the node, shared between the admin API, the MPC service
+/// of every epoch and the Sui executor.
+#[derive(Clone)]
+pub struct DrainMode {
+    state: Arc<watch::Sender<DrainState>>,
+}
+
+impl Default for DrainMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrainMode {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(watch::Sender::new(DrainState::Inactive)),
+        }
+    }
+
+    /// Starts draining the node.
+    /// Returns `false` if the node was already draining, since a drain can't be cancelled.
+    pub fn start(&self) -> bool {
+        self.state.send_if_modified(|state| {
+            if *state == DrainState::Inactive {
+                *state = DrainState::Draining;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    pub fn state(&self) -> DrainState {
+        *self.state.borrow()
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.state() != DrainState::Inactive
+    }
+
+    pub fn is_ready_to_stop(&self) -> bool {
+        self.state() == DrainState::ReadyToStop
+    }
+
+    /// Records whether the MPC work of a draining node is done.
+    /// Does nothing if the node is not draining.
+    pub(crate) fn set_ready_to_stop(&self, ready_to_stop: bool) {
+        self.state.send_if_modified(|state| {
+            let new_state = match (*state, ready_to_stop) {
+                (DrainState::Inactive, _) => DrainState::Inactive,
+                (_, true) => DrainState::ReadyToStop,
+                (_, false) => DrainState::Draining,
+            };
+            let modified = *state != new_state;
+            *state = new_state;
+            modified
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_mode_transitions() {
+        let drain_mode = DrainMode::new();
+        drain_mode.set_ready_to_stop(true);
+        assert_eq!(drain_mode.state(), DrainState::Inactive);
+
+        assert!(drain_mode.start());
+        assert!(!drain_mode.start());
+        assert_eq!(drain_mode.state(), DrainState::Draining);
+
+        drain_mode.set_ready_to_stop(true);
+        assert!(drain_mode.is_ready_to_stop());
+
+        // A system session may start after the node was ready to stop.
+        drain_mode.set_ready_to_stop(false);
+        assert_eq!(drain_mode.state(), DrainState::Draining);
+        assert!(drain_mode.is_draining());
+    }
+}
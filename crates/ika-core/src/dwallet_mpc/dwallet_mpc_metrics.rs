@@ -19,12 +19,15 @@
 //! - **signature_algorithm**: The signature algorithm (e.g., "ECDSA")
 //! - **mpc_round**: The specific round number within a protocol session
 
+use crate::dwallet_mpc::network_dkg::NetworkKeySharesDecryption;
+use dwallet_mpc_types::dwallet_mpc::NetworkDecryptionKeyPublicOutputType;
 use ika_types::messages_dwallet_mpc::MPCRequestInput;
 use prometheus::{
     GaugeVec, IntGauge, IntGaugeVec, Registry, register_gauge_vec_with_registry,
     register_int_gauge_vec_with_registry, register_int_gauge_with_registry,
 };
 use std::sync::Arc;
+use sui_types::base_types::ObjectID;
 
 /// Prometheus metrics for DWallet MPC operations.
 ///
@@ -93,6 +96,36 @@ pub struct DWalletMPCMetrics {
     pub number_of_unexpected_sign_sessions: IntGauge,
     /// The last process MPC consensus round.
     pub last_process_mpc_consensus_round: IntGauge,
+
+    /// The epoch of the public output of each network encryption key this validator last
+    /// decrypted its shares from.
+    ///
+    /// Labels: key_id
+    network_key_epoch: IntGaugeVec,
+
+    /// Set to 1 for the protocol that produced the public output of each network encryption key,
+    /// and to 0 for the other.
+    ///
+    /// Labels: key_id, state
+    network_key_state: IntGaugeVec,
+
+    /// Set to 1 when this validator holds its decryption key shares of a network encryption key.
+    ///
+    /// Labels: key_id
+    network_key_shares_decrypted: IntGaugeVec,
+
+    /// The duration of the last decryption of this validator's shares of each network
+    /// encryption key.
+    ///
+    /// Labels: key_id
+    /// Value: Duration in milliseconds.
+    network_key_shares_decryption_duration: IntGaugeVec,
+
+    /// Tracks the number of failed decryptions of this validator's shares of each network
+    /// encryption key.
+    ///
+    /// Labels: key_id
+    network_key_shares_decryption_failures: IntGaugeVec,
 }
 
 impl DWalletMPCMetrics {
@@ -196,6 +229,41 @@ impl DWalletMPCMetrics {
                 registry
             )
             .unwrap(),
+            network_key_epoch: register_int_gauge_vec_with_registry!(
+                "dwallet_mpc_network_key_epoch",
+                "Epoch of the network key public output the decryption key shares were decrypted from",
+                &["key_id"],
+                registry
+            )
+            .unwrap(),
+            network_key_state: register_int_gauge_vec_with_registry!(
+                "dwallet_mpc_network_key_state",
+                "Set to 1 for the protocol that produced the network key public output",
+                &["key_id", "state"],
+                registry
+            )
+            .unwrap(),
+            network_key_shares_decrypted: register_int_gauge_vec_with_registry!(
+                "dwallet_mpc_network_key_shares_decrypted",
+                "Whether the decryption key shares of the network key are held",
+                &["key_id"],
+                registry
+            )
+            .unwrap(),
+            network_key_shares_decryption_duration: register_int_gauge_vec_with_registry!(
+                "dwallet_mpc_network_key_shares_decryption_duration",
+                "Duration of the last decryption of the network key shares in milliseconds",
+                &["key_id"],
+                registry
+            )
+            .unwrap(),
+            network_key_shares_decryption_failures: register_int_gauge_vec_with_registry!(
+                "dwallet_mpc_network_key_shares_decryption_failures",
+                "Number of failed decryptions of the network key shares",
+                &["key_id"],
+                registry
+            )
+            .unwrap(),
         })
     }
 }
@@ -386,6 +454,43 @@ impl DWalletMPCMetrics {
             ])
             .set(duration_ms);
     }
+
+    /// Records the outcome of decrypting this validator's shares of a network encryption key.
+    ///
+    /// # Arguments
+    /// * `key_id` — The ID of the network encryption key.
+    /// * `state` — The protocol that produced the public output the shares were decrypted from.
+    /// * `decryption` — The outcome of the decryption.
+    pub(crate) fn set_network_key_status(
+        &self,
+        key_id: &ObjectID,
+        state: &NetworkDecryptionKeyPublicOutputType,
+        decryption: &NetworkKeySharesDecryption,
+    ) {
+        let key_id = key_id.to_string();
+        self.network_key_epoch
+            .with_label_values(&[&key_id])
+            .set(decryption.epoch as i64);
+        for output_type in [
+            NetworkDecryptionKeyPublicOutputType::NetworkDkg,
+            NetworkDecryptionKeyPublicOutputType::Reconfiguration,
+        ] {
+            self.network_key_state
+                .with_label_values(&[&key_id, &format!("{output_type:?}")])
+                .set((output_type == *state) as i64);
+        }
+        self.network_key_shares_decrypted
+            .with_label_values(&[&key_id])
+            .set(decryption.error.is_none() as i64);
+        self.network_key_shares_decryption_duration
+            .with_label_values(&[&key_id])
+            .set(decryption.duration.as_millis() as i64);
+        if decryption.error.is_some() {
+            self.network_key_shares_decryption_failures
+                .with_label_values(&[&key_id])
+                .inc();
+        }
+    }
 }
 
 /// Calculating the variance using the Welford's method.
@@ -400,6 +505,80 @@ fn update_variance(old_mean: f64, new_mean: f64, old_variance: f64, new_value: f
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_set_network_key_status() {
+        let metrics = DWalletMPCMetrics::new(&Registry::new());
+        let key_id = ObjectID::from_single_byte(1);
+        let label = key_id.to_string();
+        let state = |output_type: &str| {
+            metrics
+                .network_key_state
+                .with_label_values(&[&label, output_type])
+                .get()
+        };
+
+        metrics.set_network_key_status(
+            &key_id,
+            &NetworkDecryptionKeyPublicOutputType::NetworkDkg,
+            &NetworkKeySharesDecryption {
+                epoch: 1,
+                duration: Duration::from_millis(40),
+                error: None,
+            },
+        );
+        assert_eq!(
+            metrics.network_key_epoch.with_label_values(&[&label]).get(),
+            1
+        );
+        assert_eq!((state("NetworkDkg"), state("Reconfiguration")), (1, 0));
+        assert_eq!(
+            metrics
+                .network_key_shares_decrypted
+                .with_label_values(&[&label])
+                .get(),
+            1
+        );
+        assert_eq!(
+            metrics
+                .network_key_shares_decryption_duration
+                .with_label_values(&[&label])
+                .get(),
+            40
+        );
+
+        // A failed decryption of the reconfigured key.
+        metrics.set_network_key_status(
+            &key_id,
+            &NetworkDecryptionKeyPublicOutputType::Reconfiguration,
+            &NetworkKeySharesDecryption {
+                epoch: 2,
+                duration: Duration::from_millis(7),
+                error: Some("invalid decryption key share".to_string()),
+            },
+        );
+        assert_eq!(
+            metrics.network_key_epoch.with_label_values(&[&label]).get(),
+            2
+        );
+        assert_eq!((state("NetworkDkg"), state("Reconfiguration")), (0, 1));
+        assert_eq!(
+            metrics
+                .network_key_shares_decrypted
+                .with_label_values(&[&label])
+                .get(),
+            0
+        );
+        assert_eq!(
+            metrics
+                .network_key_shares_decryption_failures
+                .with_label_values(&[&label])
+                .get(),
+            1
+        );
+    }
+
     #[test]
     fn test_update_variance() {
         // Case 1
@@ -18,10 +18,13 @@ use crate::dwallet_checkpoints::{
     PendingDWalletCheckpointV1,
 };
 use crate::dwallet_mpc::crytographic_computation::ComputationId;
+use crate::dwallet_mpc::drain::DrainMode;
 use crate::dwallet_mpc::dwallet_mpc_metrics::DWalletMPCMetrics;
+use crate::dwallet_mpc::mpc_inspection::{MPCInspectionHandle, MPCInspectionRequest};
 use crate::dwallet_mpc::mpc_manager::DWalletMPCManager;
 use crate::dwallet_mpc::mpc_session::MPCEventData;
 use crate::dwallet_mpc::party_ids_to_authority_names;
+use crate::dwallet_mpc::session_latency::SessionLatencyTracker;
 use crate::epoch::submit_to_consensus::DWalletMPCSubmitToConsensus;
 use dwallet_classgroups_types::ClassGroupsKeyPairAndProof;
 use dwallet_mpc_types::dwallet_mpc::MPCDataTrait;
@@ -49,7 +52,9 @@ use ika_types::messages_dwallet_mpc::{
     SessionIdentifier,
 };
 use ika_types::sui::{DWalletCoordinatorInner, EpochStartSystem};
-use ika_types::sui::{EpochStartSystemTrait, EpochStartValidatorInfoTrait};
+use ika_types::sui::{
+    EpochStartSystemTrait, EpochStartValidatorInfo, EpochStartValidatorInfoTrait,
+};
 use itertools::Itertools;
 use mpc::GuaranteedOutputDeliveryRoundResult;
 use prometheus::Registry;
@@ -59,9 +64,9 @@ use std::time::Duration;
 use sui_json_rpc_types::SuiEvent;
 use sui_types::base_types::ObjectID;
 use sui_types::messages_consensus::Round;
-use tokio::sync::watch;
 use tokio::sync::watch::error::RecvError;
 use tokio::sync::watch::{Receiver, Ref};
+use tokio::sync::{mpsc, watch};
 use tracing::{debug, error, info, warn};
 
 const DELAY_NO_ROUNDS_SEC: u64 = 2;
@@ -83,6 +88,8 @@ pub struct DWalletMPCService {
     pub epoch: EpochId,
     pub protocol_config: ProtocolConfig,
     pub committee: Arc<Committee>,
+    inspection_handle: MPCInspectionHandle,
+    inspection_requests: mpsc::Receiver<MPCInspectionRequest>,
 }
 
 impl DWalletMPCService {
@@ -90,9 +97,11 @@ impl DWalletMPCService {
         epoch_store: Arc<dyn AuthorityPerEpochStoreTrait>,
         exit: Receiver<()>,
         consensus_adapter: Arc<dyn DWalletMPCSubmitToConsensus>,
-        node_config: NodeConfig,
+        root_seed: RootSeed,
         dwallet_checkpoint_service: Arc<dyn DWalletCheckpointServiceNotify + Send + Sync>,
         dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
         state: Arc<AuthorityState>,
         sui_data_receivers: SuiDataReceivers,
         validator_name: AuthorityName,
@@ -108,18 +117,6 @@ impl DWalletMPCService {
         let decryption_key_reconfiguration_third_round_delay =
             protocol_config.decryption_key_reconfiguration_third_round_delay();
 
-        let root_seed = match node_config.root_seed_key_pair {
-            None => {
-                error!(
-                    "root_seed_key_pair is not set in the node config, cannot start DWallet MPC service"
-                );
-                panic!(
-                    "root_seed_key_pair is not set in the node config, cannot start DWallet MPC service"
-                );
-            }
-            Some(root_seed_keypair) => root_seed_keypair.root_seed().clone(),
-        };
-
         let dwallet_mpc_manager = DWalletMPCManager::new(
             validator_name,
             committee.clone(),
@@ -129,8 +126,11 @@ impl DWalletMPCService {
             network_dkg_third_round_delay,
             decryption_key_reconfiguration_third_round_delay,
             dwallet_mpc_metrics.clone(),
+            session_latency,
+            drain_mode,
             sui_data_receivers.clone(),
         );
+        let (inspection_handle, inspection_requests) = MPCInspectionHandle::new();
 
         Self {
             last_read_consensus_round: None,
@@ -147,6 +147,8 @@ impl DWalletMPCService {
             epoch: epoch_id,
             protocol_config,
             committee,
+            inspection_handle,
+            inspection_requests,
         }
     }
 
@@ -162,6 +164,7 @@ impl DWalletMPCService {
         ika_network_config: IkaNetworkConfig,
         sui_data_receivers: SuiDataReceivers,
     ) -> Self {
+        let (inspection_handle, inspection_requests) = MPCInspectionHandle::new();
         DWalletMPCService {
             last_read_consensus_round: Some(0),
             epoch_store,
@@ -177,6 +180,8 @@ impl DWalletMPCService {
                 0,
                 0,
                 DWalletMPCMetrics::new(&Registry::new()),
+                SessionLatencyTracker::new_for_testing(),
+                DrainMode::new(),
                 sui_data_receivers.clone(),
             ),
             exit: watch::channel(()).1,
@@ -187,6 +192,8 @@ impl DWalletMPCService {
             epoch: 1,
             protocol_config: ProtocolConfig::get_for_min_version(),
             committee: Arc::new(committee),
+            inspection_handle,
+            inspection_requests,
         }
     }
 
@@ -194,6 +201,11 @@ impl DWalletMPCService {
         &self.dwallet_mpc_manager
     }
 
+    /// Returns a handle to inspect the MPC sessions of this service, for as long as it runs.
+    pub fn inspection_handle(&self) -> MPCInspectionHandle {
+        self.inspection_handle.clone()
+    }
+
     async fn sync_last_session_to_complete_in_current_epoch(&mut self) {
         let (ika_current_epoch_on_sui, last_session_to_complete_in_current_epoch) = self
             .sui_data_receivers
@@ -275,6 +287,14 @@ impl DWalletMPCService {
         self.process_consensus_rounds_from_storage().await;
 
         self.process_cryptographic_computations().await;
+
+        self.answer_inspection_requests();
+    }
+
+    fn answer_inspection_requests(&mut self) {
+        while let Ok(request) = self.inspection_requests.try_recv() {
+            self.dwallet_mpc_manager.answer_inspection_request(request);
+        }
     }
 
     async fn process_cryptographic_computations(&mut self) {
@@ -285,11 +305,14 @@ impl DWalletMPCService {
 
         self.handle_computation_results_and_submit_to_consensus(completed_computation_results)
             .await;
+
+        // Only after the outputs of the completed computations were submitted to consensus.
+        self.dwallet_mpc_manager.update_drain_readiness();
     }
 
     async fn handle_new_events(&mut self) -> DwalletMPCResult<()> {
         let uncompleted_events = self.load_uncompleted_events().await;
-        let pulled_events = match self.receive_new_sui_events() {
+        let (pulled_events, last_pulled_event_id) = match self.receive_new_sui_events() {
             Ok(new_events) => new_events,
             Err(e) => {
                 error!(
@@ -339,6 +362,11 @@ impl DWalletMPCService {
         self.dwallet_mpc_manager
             .handle_mpc_event_batch(events)
             .await;
+        if let Some(last_pulled_event_id) = last_pulled_event_id {
+            self.sui_data_receivers
+                .handled_events_sender
+                .send_replace(Some(last_pulled_event_id));
+        }
         Ok(())
     }
 
@@ -581,6 +609,11 @@ impl DWalletMPCService {
             {
                 if session.status == MPCSessionStatus::Active {
                     if let Some(mpc_event_data) = session.mpc_event_data.clone() {
+                        if computation_result.is_ok() {
+                            self.dwallet_mpc_manager
+                                .session_latency
+                                .record_round_computed(&session_identifier, mpc_round);
+                        }
                         match computation_result {
                             Ok(GuaranteedOutputDeliveryRoundResult::Advance { message }) => {
                                 info!(
@@ -993,10 +1026,18 @@ impl DWalletMPCService {
         slices
     }
 
+    /// Verifies the validator's keys against its on-chain info, and returns the root seed
+    /// that derives its class-groups key in this epoch.
+    ///
+    /// During a class-groups key rotation, both the current and the next root seeds are configured.
+    /// The new key is published for the next epoch before its mid-epoch, and the mid-epoch
+    /// reconfiguration re-shares the network keys to the next committee, which holds the new key.
+    /// The node switches to the next seed at the start of the first epoch whose committee holds
+    /// the new key.
     pub fn verify_validator_keys(
         epoch_start_system: &EpochStartSystem,
         config: &NodeConfig,
-    ) -> DwalletMPCResult<()> {
+    ) -> DwalletMPCResult<RootSeed> {
         let authority_name = config.protocol_public_key();
         let Some(onchain_validator) = epoch_start_system
             .get_ika_validators()
@@ -1021,21 +1062,66 @@ impl DWalletMPCService {
 
         let root_seed = config
             .root_seed_key_pair
-            .clone()
-            .ok_or(DwalletMPCError::MissingRootSeed)?
-            .root_seed()
-            .clone();
+            .as_ref()
+            .ok_or(DwalletMPCError::MissingRootSeed)?;
+        Self::select_root_seed(
+            root_seed,
+            config.next_root_seed_key_pair.as_ref(),
+            |root_seed| Self::verify_class_groups_key(&onchain_validator, root_seed),
+        )
+    }
+
+    /// Returns whichever of `root_seed` and `next_root_seed` derives the validator's
+    /// class-groups key in this epoch, as checked by `verify_class_groups_key`.
+    fn select_root_seed(
+        root_seed: &RootSeedWithPath,
+        next_root_seed: Option<&RootSeedWithPath>,
+        verify_class_groups_key: impl Fn(&RootSeed) -> DwalletMPCResult<()>,
+    ) -> DwalletMPCResult<RootSeed> {
+        let load = |root_seed: &RootSeedWithPath| {
+            root_seed
+                .try_root_seed()
+                .cloned()
+                .map_err(|e| DwalletMPCError::FailedToReadSeed(format!("{e:#}")))
+        };
+        let root_seed = load(root_seed)?;
+        let Some(next_root_seed) = next_root_seed else {
+            verify_class_groups_key(&root_seed)?;
+            return Ok(root_seed);
+        };
+
+        if verify_class_groups_key(&root_seed).is_ok() {
+            info!(
+                "A class-groups key rotation is pending, using the current root seed in this epoch"
+            );
+            return Ok(root_seed);
+        }
+        let next_root_seed = load(next_root_seed)?;
+        verify_class_groups_key(&next_root_seed)?;
+        warn!(
+            "Switched to the next root seed, as its class-groups key is used from this epoch. \
+             Set it as `root-seed-key-pair`, remove `next-root-seed-key-pair`, \
+             and retire the previous root seed"
+        );
+        Ok(next_root_seed)
+    }
 
-        let class_groups_key_pair = ClassGroupsKeyPairAndProof::from_seed(&root_seed);
+    /// Verify that the class-groups key derived from `root_seed` is the
+    /// same as stored in the system state object onchain for `onchain_validator`.
+    /// This makes sure the seed we are using is the same seed we used at setup
+    /// to create the encryption key, and thus it assures we will generate the same decryption key too.
+    pub fn verify_class_groups_key(
+        onchain_validator: &EpochStartValidatorInfo,
+        root_seed: &RootSeed,
+    ) -> DwalletMPCResult<()> {
+        let Some(mpc_data) = onchain_validator.get_mpc_data() else {
+            return Err(DwalletMPCError::MPCManagerError(
+                "validator has no MPC data in the system state object".to_string(),
+            ));
+        };
+        let class_groups_key_pair = ClassGroupsKeyPairAndProof::from_seed(root_seed);
 
-        // Verify that the validators local class-groups key is the
-        // same as stored in the system state object onchain.
-        // This makes sure the seed we are using is the same seed we used at setup
-        // to create the encryption key, and thus it assures we will generate the same decryption key too.
-        if onchain_validator
-            .get_mpc_data()
-            .unwrap()
-            .class_groups_public_key_and_proof()
+        if mpc_data.class_groups_public_key_and_proof()
             != bcs::to_bytes(&class_groups_key_pair.encryption_key_and_proof())?
         {
             return Err(DwalletMPCError::MPCManagerError(
@@ -1046,3 +1132,92 @@ impl DWalletMPCService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks a seed against the seed whose class-groups key is in the epoch's committee,
+    /// without deriving the keys.
+    fn verify_against(
+        onchain_root_seed: &RootSeed,
+    ) -> impl Fn(&RootSeed) -> DwalletMPCResult<()> + '_ {
+        move |root_seed| {
+            if root_seed == onchain_root_seed {
+                Ok(())
+            } else {
+                Err(DwalletMPCError::MPCManagerError(
+                    "validator's class-groups key does not match".to_string(),
+                ))
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_root_seed_during_a_class_groups_key_rotation() {
+        let root_seed = RootSeed::random_seed();
+        let next_root_seed = RootSeed::random_seed();
+        let current = RootSeedWithPath::new(root_seed.clone());
+        let next = RootSeedWithPath::new(next_root_seed.clone());
+
+        // Before the epoch in which the new key takes effect, its committee holds the current key.
+        let selected =
+            DWalletMPCService::select_root_seed(&current, Some(&next), verify_against(&root_seed))
+                .unwrap();
+        assert_eq!(selected, root_seed);
+
+        // From the epoch in which the new key takes effect, until the rotation is completed.
+        let selected = DWalletMPCService::select_root_seed(
+            &current,
+            Some(&next),
+            verify_against(&next_root_seed),
+        )
+        .unwrap();
+        assert_eq!(selected, next_root_seed);
+
+        // After the rotation is completed, the next seed is the only one configured.
+        let selected =
+            DWalletMPCService::select_root_seed(&next, None, verify_against(&next_root_seed))
+                .unwrap();
+        assert_eq!(selected, next_root_seed);
+
+        // Neither seed derives the key in the committee.
+        let other_root_seed = RootSeed::random_seed();
+        assert!(
+            DWalletMPCService::select_root_seed(
+                &current,
+                Some(&next),
+                verify_against(&other_root_seed)
+            )
+            .is_err()
+        );
+        assert!(
+            DWalletMPCService::select_root_seed(&current, None, verify_against(&next_root_seed))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_select_root_seed_with_an_unreadable_next_root_seed() {
+        let root_seed = RootSeed::random_seed();
+        let current = RootSeedWithPath::new(root_seed.clone());
+        let directory = tempfile::tempdir().unwrap();
+        let next: RootSeedWithPath = serde_json::from_value(serde_json::json!({
+            "path": directory.path().join("missing.key"),
+        }))
+        .unwrap();
+
+        // The next seed isn't needed before the new key takes effect.
+        let selected =
+            DWalletMPCService::select_root_seed(&current, Some(&next), verify_against(&root_seed))
+                .unwrap();
+        assert_eq!(selected, root_seed);
+
+        let result = DWalletMPCService::select_root_seed(
+            &current,
+            Some(&next),
+            verify_against(&RootSeed::random_seed()),
+        );
+        assert!(matches!(result, Err(DwalletMPCError::FailedToReadSeed(_))));
+    }
+}
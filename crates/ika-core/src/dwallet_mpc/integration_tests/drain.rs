@@ -0,0 +1,130 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::dwallet_mpc::drain::DrainState;
+use crate::dwallet_mpc::dwallet_mpc_service::DWalletMPCService;
+use crate::dwallet_mpc::integration_tests::utils;
+use crate::dwallet_mpc::mpc_session::DWalletMPCSession;
+use ika_types::committee::Committee;
+use ika_types::messages_dwallet_mpc::IkaNetworkConfig;
+use tracing::info;
+
+/// The session of `dwallet_mpc_service` with the given sequence number, if it received its event.
+fn session_by_sequence_number(
+    dwallet_mpc_service: &DWalletMPCService,
+    session_sequence_number: u64,
+) -> Option<&DWalletMPCSession> {
+    dwallet_mpc_service
+        .dwallet_mpc_manager()
+        .mpc_sessions
+        .values()
+        .find(|session| {
+            session
+                .mpc_event_data
+                .as_ref()
+                .is_some_and(|mpc_event_data| {
+                    mpc_event_data.session_sequence_number == session_sequence_number
+                })
+        })
+}
+
+#[tokio::test]
+#[cfg(test)]
+async fn test_draining_node_finishes_in_flight_user_sessions() {
+    let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+    let (committee, _) = Committee::new_simple_test_committee();
+    let ika_network_config = IkaNetworkConfig::new_for_testing();
+    let epoch_id = 1;
+    let (
+        mut dwallet_mpc_services,
+        sui_data_senders,
+        mut sent_consensus_messages_collectors,
+        mut epoch_stores,
+        notify_services,
+    ) = utils::create_dwallet_mpc_services(4);
+    for sui_data_sender in &sui_data_senders {
+        sui_data_sender
+            .last_session_to_complete_in_current_epoch_sender
+            .send((epoch_id, 2))
+            .unwrap();
+    }
+
+    // A user session, which every party starts before the first party drains.
+    utils::send_configurable_start_network_dkg_event(
+        &ika_network_config,
+        epoch_id,
+        &sui_data_senders,
+        [1u8; 32],
+        1,
+        false,
+    );
+    let pending_checkpoint = utils::advance_all_parties_and_wait_for_completions(
+        &committee,
+        &mut dwallet_mpc_services,
+        &mut sent_consensus_messages_collectors,
+        &epoch_stores,
+        &notify_services,
+    )
+    .await;
+    assert!(pending_checkpoint.is_none());
+    assert!(
+        session_by_sequence_number(&dwallet_mpc_services[0], 1)
+            .unwrap()
+            .computation_started
+    );
+
+    let drain_mode = dwallet_mpc_services[0]
+        .dwallet_mpc_manager()
+        .drain_mode
+        .clone();
+    assert!(drain_mode.start());
+
+    // A user session that arrives after the draining party started draining is not started.
+    utils::send_configurable_start_network_dkg_event(
+        &ika_network_config,
+        epoch_id,
+        &sui_data_senders[..1],
+        [2u8; 32],
+        2,
+        false,
+    );
+    dwallet_mpc_services[0].run_service_loop_iteration().await;
+    assert!(
+        !session_by_sequence_number(&dwallet_mpc_services[0], 2)
+            .unwrap()
+            .computation_started
+    );
+    assert_eq!(drain_mode.state(), DrainState::Draining);
+
+    // The in-flight session keeps advancing on the draining party,
+    // as every party must submit its messages for each round to complete.
+    let mut mpc_round = 1;
+    loop {
+        utils::send_advance_results_between_parties(
+            &committee,
+            &mut sent_consensus_messages_collectors,
+            &mut epoch_stores,
+            mpc_round,
+        );
+        mpc_round += 1;
+
+        if let Some(pending_checkpoint) = utils::advance_all_parties_and_wait_for_completions(
+            &committee,
+            &mut dwallet_mpc_services,
+            &mut sent_consensus_messages_collectors,
+            &epoch_stores,
+            &notify_services,
+        )
+        .await
+        {
+            assert_eq!(mpc_round, 5, "Network DKG should complete after 4 rounds");
+            info!(?pending_checkpoint, "MPC flow completed successfully");
+            break;
+        }
+        assert_eq!(drain_mode.state(), DrainState::Draining);
+    }
+
+    // Only the session that was never started is left, so the draining party is ready to stop.
+    dwallet_mpc_services[0].run_service_loop_iteration().await;
+    assert_eq!(drain_mode.state(), DrainState::ReadyToStop);
+}
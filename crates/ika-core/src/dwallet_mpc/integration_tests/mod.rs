@@ -1,4 +1,6 @@
+mod drain;
 mod malicious_behavior;
+mod mpc_inspection;
 #[cfg(test)]
 mod network_dkg;
 mod threshold_not_reached;
@@ -0,0 +1,62 @@
+use crate::dwallet_mpc::integration_tests::utils;
+use dwallet_mpc_types::dwallet_mpc::MPCSessionStatus;
+use ika_types::messages_dwallet_mpc::IkaNetworkConfig;
+
+#[tokio::test]
+#[cfg(test)]
+async fn test_inspect_mpc_sessions() {
+    let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+    let ika_network_config = IkaNetworkConfig::new_for_testing();
+    let epoch_id = 1;
+    let (mut dwallet_mpc_services, mut sui_data_senders, _, _, _) =
+        utils::create_dwallet_mpc_services(4);
+    utils::send_start_network_dkg_event(&ika_network_config, epoch_id, &mut sui_data_senders);
+
+    let dwallet_mpc_service = dwallet_mpc_services.get_mut(0).unwrap();
+    let inspection = dwallet_mpc_service.inspection_handle();
+
+    // The service answers queries at the end of each loop iteration.
+    let (sessions, ()) = tokio::join!(
+        inspection.sessions(false),
+        dwallet_mpc_service.run_service_loop_iteration()
+    );
+    let sessions = sessions.unwrap();
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session.status, MPCSessionStatus::Active);
+    assert!(session.protocol.is_some());
+    assert_eq!(session.current_mpc_round, 1);
+    assert_eq!(session.attempt_number, 1);
+
+    let (found, ()) = tokio::join!(
+        inspection.session(session.session_identifier.into_bytes()),
+        dwallet_mpc_service.run_service_loop_iteration()
+    );
+    let found = found.unwrap().unwrap();
+    assert_eq!(found.session_identifier, session.session_identifier);
+
+    let (missing, ()) = tokio::join!(
+        inspection.session([0; 32]),
+        dwallet_mpc_service.run_service_loop_iteration()
+    );
+    assert!(missing.unwrap().is_none());
+
+    let (pending_events, ()) = tokio::join!(
+        inspection.pending_events(),
+        dwallet_mpc_service.run_service_loop_iteration()
+    );
+    let pending_events = pending_events.unwrap();
+    assert!(pending_events.pending_for_network_key.is_empty());
+    assert!(pending_events.pending_for_next_active_committee.is_empty());
+
+    // No network key is known before the network DKG completes.
+    let (network_keys, ()) = tokio::join!(
+        inspection.network_keys(),
+        dwallet_mpc_service.run_service_loop_iteration()
+    );
+    assert!(network_keys.unwrap().is_empty());
+
+    // Queries fail once the service is gone.
+    dwallet_mpc_services.clear();
+    assert!(inspection.sessions(true).await.is_none());
+}
@@ -509,15 +509,17 @@ pub(crate) fn send_start_network_dkg_event(
         sui_data_senders,
         [1u8; 32],
         1,
+        true,
     );
 }
 
 pub(crate) fn send_configurable_start_network_dkg_event(
     ika_network_config: &IkaNetworkConfig,
     epoch_id: EpochId,
-    sui_data_senders: &mut Vec<SuiDataSenders>,
+    sui_data_senders: &[SuiDataSenders],
     session_identifier_preimage: [u8; 32],
     session_sequence_number: u64,
+    is_system: bool,
 ) {
     let key_id = ObjectID::random();
     sui_data_senders.iter().for_each(|mut sui_data_sender| {
@@ -527,7 +529,7 @@ pub(crate) fn send_configurable_start_network_dkg_event(
                     &ika_network_config,
                 ),
                 contents: bcs::to_bytes(&new_dwallet_session_event(
-                    true,
+                    is_system,
                     session_sequence_number,
                     session_identifier_preimage.to_vec().clone(),
                     DWalletNetworkDKGEncryptionKeyRequestEvent {
@@ -16,8 +16,11 @@ pub mod mpc_manager;
 pub mod mpc_session;
 
 mod crytographic_computation;
+pub mod drain;
 pub mod dwallet_mpc_metrics;
 mod mpc_event;
+pub mod mpc_inspection;
+pub mod session_latency;
 
 #[cfg(test)]
 mod integration_tests;
@@ -30,9 +30,10 @@ use serde::de::DeserializeOwned;
 use std::mem;
 use std::time::Duration;
 use sui_types::dynamic_field::Field;
+use sui_types::event::EventID;
 use sui_types::id::ID;
 use tokio::sync::broadcast;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 impl DWalletMPCManager {
     /// Handle a batch of MPC events.
@@ -105,6 +106,15 @@ impl DWalletMPCManager {
     ///
     /// If there is no `session_request`, and we've got it in this call,
     /// we update that field in the open session.
+    #[instrument(
+        name = "mpc_event",
+        level = "info",
+        skip_all,
+        fields(
+            session_identifier = ?event.session_request.session_identifier,
+            protocol = %event.session_request.request_input,
+        )
+    )]
     fn handle_mpc_event(&mut self, event: DWalletMPCEvent) {
         let session_identifier = event.session_request.session_identifier;
 
@@ -122,6 +132,9 @@ impl DWalletMPCManager {
             return;
         }
 
+        self.session_latency
+            .record_event_observed(session_identifier);
+
         if event.session_request.requires_network_key_data {
             if let Some(network_encryption_key_id) = event
                 .session_request
@@ -207,6 +220,8 @@ impl DWalletMPCManager {
 
         self.dwallet_mpc_metrics
             .add_received_event_start(&mpc_event_data.request_input);
+        self.session_latency
+            .record_session_started(&session_identifier, &mpc_event_data.request_input);
 
         if let Some(session) = self.mpc_sessions.get_mut(&session_identifier) {
             session.mpc_event_data = Some(mpc_event_data.clone());
@@ -406,7 +421,10 @@ impl DWalletMPCService {
     }
 
     /// Read events from perpetual tables, remove them, and store in the current epoch tables.
-    pub(crate) fn receive_new_sui_events(&mut self) -> IkaResult<Vec<DBSuiEvent>> {
+    /// Also returns the ID of the last received event.
+    pub(crate) fn receive_new_sui_events(
+        &mut self,
+    ) -> IkaResult<(Vec<DBSuiEvent>, Option<EventID>)> {
         let pending_events = match self.sui_data_receivers.new_events_receiver.try_recv() {
             Ok(events) => {
                 for event in &events {
@@ -422,13 +440,14 @@ impl DWalletMPCService {
             }
             Err(broadcast::error::TryRecvError::Empty) => {
                 debug!("No new Sui events to process");
-                return Ok(vec![]);
+                return Ok((vec![], None));
             }
             Err(e) => {
                 return Err(IkaError::ReceiverError(e.to_string()));
             }
         };
 
+        let last_event_id = pending_events.last().map(|event| event.id);
         let events: Vec<_> = pending_events
             .into_iter()
             .map(|event| DBSuiEvent {
@@ -438,7 +457,7 @@ impl DWalletMPCService {
             })
             .collect();
 
-        Ok(events)
+        Ok((events, last_event_id))
     }
 }
 
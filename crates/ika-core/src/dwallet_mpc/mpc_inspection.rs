@@ -0,0 +1,475 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Read-only inspection of the MPC sessions and network encryption keys of the
+//! [`DWalletMPCManager`], for the admin interface.
+//!
+//! The manager is owned by the [`DWalletMPCService`](super::dwallet_mpc_service::DWalletMPCService)
+//! task, so the [`MPCInspectionHandle`] sends its queries to the service, which answers them
+//! between iterations of its loop.
+
+use crate::dwallet_mpc::mpc_manager::DWalletMPCManager;
+use crate::dwallet_mpc::mpc_session::DWalletMPCSession;
+use crate::dwallet_mpc::network_dkg::NetworkKeySharesDecryption;
+use dwallet_mpc_types::dwallet_mpc::{MPCSessionStatus, NetworkDecryptionKeyPublicOutputType};
+use group::PartyID;
+use ika_types::messages_dwallet_mpc::{
+    DWalletMPCEvent, DWalletNetworkEncryptionKeyData, DWalletNetworkEncryptionKeyState,
+    SessionIdentifier, SessionType,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+use sui_types::base_types::ObjectID;
+use tokio::sync::{mpsc, oneshot};
+
+/// The number of queries that may wait for the MPC service to answer them.
+const INSPECTION_REQUESTS_CHANNEL_SIZE: usize = 16;
+
+/// A snapshot of an MPC session.
+#[derive(Clone, Debug)]
+pub struct MPCSessionReport {
+    pub session_identifier: SessionIdentifier,
+    pub status: MPCSessionStatus,
+    /// The protocol of the session, unknown until the event requesting the session is received.
+    pub protocol: Option<String>,
+    pub session_type: Option<SessionType>,
+    pub session_sequence_number: Option<u64>,
+    pub current_mpc_round: u64,
+    pub attempt_number: u64,
+    /// The number of messages received from each party, by MPC round.
+    pub messages_received: BTreeMap<u64, BTreeMap<PartyID, usize>>,
+    /// The consensus rounds at which advancing failed because the threshold was not reached,
+    /// by MPC round.
+    pub threshold_not_reached: BTreeMap<u64, Vec<u64>>,
+    /// The cryptographic computations of the session that are currently running.
+    pub pending_computations: Vec<PendingComputationReport>,
+}
+
+/// A cryptographic computation that was spawned and has not completed yet.
+#[derive(Clone, Debug)]
+pub struct PendingComputationReport {
+    pub mpc_round: u64,
+    pub attempt_number: u64,
+    /// The last consensus round whose messages the computation advances with, if any.
+    pub consensus_round: Option<u64>,
+}
+
+/// An event that waits for data before its session can start.
+#[derive(Clone, Debug)]
+pub struct PendingEventReport {
+    pub session_identifier: SessionIdentifier,
+    pub protocol: String,
+    pub session_sequence_number: u64,
+}
+
+/// The events that wait for a network encryption key, or for the next active committee.
+#[derive(Clone, Debug, Default)]
+pub struct PendingEventsReport {
+    pub pending_for_network_key: BTreeMap<ObjectID, Vec<PendingEventReport>>,
+    pub pending_for_next_active_committee: Vec<PendingEventReport>,
+}
+
+/// The public metadata of a network encryption key, and whether this validator decrypted its
+/// shares of it. Never holds secret key material.
+#[derive(Clone, Debug)]
+pub struct NetworkKeyReport {
+    pub key_id: ObjectID,
+    /// The state of the key on Sui, unknown until the key is read from Sui.
+    pub on_chain_state: Option<DWalletNetworkEncryptionKeyState>,
+    /// The epoch of the key's public output on Sui.
+    pub on_chain_epoch: Option<u64>,
+    /// The protocol that produced the public output this validator instantiated the key from.
+    pub state: Option<NetworkDecryptionKeyPublicOutputType>,
+    /// The epoch of the public output this validator instantiated the key from.
+    pub epoch: Option<u64>,
+    /// Whether this validator holds its decryption key shares of the key.
+    pub shares_decrypted: bool,
+    /// The duration of the last decryption of this validator's shares of the key.
+    pub last_decryption_duration: Option<Duration>,
+    /// The error the last decryption of this validator's shares of the key failed with.
+    pub last_decryption_error: Option<String>,
+}
+
+pub(crate) enum MPCInspectionRequest {
+    Sessions {
+        include_completed: bool,
+        reply: oneshot::Sender<Vec<MPCSessionReport>>,
+    },
+    Session {
+        session_identifier: [u8; SessionIdentifier::LENGTH],
+        reply: oneshot::Sender<Option<MPCSessionReport>>,
+    },
+    PendingEvents {
+        reply: oneshot::Sender<PendingEventsReport>,
+    },
+    NetworkKeys {
+        reply: oneshot::Sender<Vec<NetworkKeyReport>>,
+    },
+}
+
+/// A handle to query the MPC sessions and network encryption keys of the current epoch.
+///
+/// Every query returns `None` once the MPC service of the epoch has stopped.
+#[derive(Clone, Debug)]
+pub struct MPCInspectionHandle {
+    sender: mpsc::Sender<MPCInspectionRequest>,
+}
+
+impl MPCInspectionHandle {
+    pub(crate) fn new() -> (Self, mpsc::Receiver<MPCInspectionRequest>) {
+        let (sender, receiver) = mpsc::channel(INSPECTION_REQUESTS_CHANNEL_SIZE);
+        (Self { sender }, receiver)
+    }
+
+    /// Returns the sessions of the epoch, ordered by their identifier.
+    pub async fn sessions(&self, include_completed: bool) -> Option<Vec<MPCSessionReport>> {
+        let (reply, receiver) = oneshot::channel();
+        self.sender
+            .send(MPCInspectionRequest::Sessions {
+                include_completed,
+                reply,
+            })
+            .await
+            .ok()?;
+        receiver.await.ok()
+    }
+
+    /// Returns the session whose identifier digest is `session_identifier`, if the MPC service
+    /// knows it.
+    pub async fn session(
+        &self,
+        session_identifier: [u8; SessionIdentifier::LENGTH],
+    ) -> Option<Option<MPCSessionReport>> {
+        let (reply, receiver) = oneshot::channel();
+        self.sender
+            .send(MPCInspectionRequest::Session {
+                session_identifier,
+                reply,
+            })
+            .await
+            .ok()?;
+        receiver.await.ok()
+    }
+
+    pub async fn pending_events(&self) -> Option<PendingEventsReport> {
+        let (reply, receiver) = oneshot::channel();
+        self.sender
+            .send(MPCInspectionRequest::PendingEvents { reply })
+            .await
+            .ok()?;
+        receiver.await.ok()
+    }
+
+    /// Returns the network encryption keys known on Sui or to the MPC service, ordered by their
+    /// ID.
+    pub async fn network_keys(&self) -> Option<Vec<NetworkKeyReport>> {
+        let (reply, receiver) = oneshot::channel();
+        self.sender
+            .send(MPCInspectionRequest::NetworkKeys { reply })
+            .await
+            .ok()?;
+        receiver.await.ok()
+    }
+}
+
+impl DWalletMPCManager {
+    /// Answers a query of an [`MPCInspectionHandle`].
+    /// The querier may have given up waiting, so failing to reply is ignored.
+    pub(crate) fn answer_inspection_request(&self, request: MPCInspectionRequest) {
+        match request {
+            MPCInspectionRequest::Sessions {
+                include_completed,
+                reply,
+            } => {
+                let mut sessions: Vec<_> = self
+                    .mpc_sessions
+                    .values()
+                    .filter(|session| {
+                        include_completed || session.status != MPCSessionStatus::Completed
+                    })
+                    .map(|session| self.session_report(session))
+                    .collect();
+                sessions.sort_by_key(|session| session.session_identifier);
+                let _ = reply.send(sessions);
+            }
+            MPCInspectionRequest::Session {
+                session_identifier,
+                reply,
+            } => {
+                let session = self
+                    .mpc_sessions
+                    .values()
+                    .find(|session| session.session_identifier.into_bytes() == session_identifier)
+                    .map(|session| self.session_report(session));
+                let _ = reply.send(session);
+            }
+            MPCInspectionRequest::PendingEvents { reply } => {
+                let _ = reply.send(self.pending_events_report());
+            }
+            MPCInspectionRequest::NetworkKeys { reply } => {
+                let _ = reply.send(self.network_keys_report());
+            }
+        }
+    }
+
+    fn session_report(&self, session: &DWalletMPCSession) -> MPCSessionReport {
+        let mut messages_received: BTreeMap<u64, BTreeMap<PartyID, usize>> = BTreeMap::new();
+        for messages_by_mpc_round in session.messages_by_consensus_round.values() {
+            for (mpc_round, messages_by_party) in messages_by_mpc_round {
+                let received = messages_received.entry(*mpc_round).or_default();
+                for party_id in messages_by_party.keys() {
+                    *received.entry(*party_id).or_default() += 1;
+                }
+            }
+        }
+
+        let threshold_not_reached = session
+            .mpc_round_to_threshold_not_reached_consensus_rounds
+            .iter()
+            .map(|(mpc_round, consensus_rounds)| {
+                let mut consensus_rounds: Vec<_> = consensus_rounds.iter().copied().collect();
+                consensus_rounds.sort();
+                (*mpc_round, consensus_rounds)
+            })
+            .collect();
+
+        let mut pending_computations: Vec<_> = self
+            .cryptographic_computations_orchestrator
+            .currently_running_cryptographic_computations()
+            .filter(|computation_id| {
+                computation_id.session_identifier == session.session_identifier
+            })
+            .map(|computation_id| PendingComputationReport {
+                mpc_round: computation_id.mpc_round,
+                attempt_number: computation_id.attempt_number,
+                consensus_round: computation_id.consensus_round,
+            })
+            .collect();
+        pending_computations
+            .sort_by_key(|computation| (computation.mpc_round, computation.attempt_number));
+
+        let mpc_event_data = session.mpc_event_data();
+        MPCSessionReport {
+            session_identifier: session.session_identifier,
+            status: session.status.clone(),
+            protocol: mpc_event_data.map(|event_data| event_data.request_input.to_string()),
+            session_type: mpc_event_data.map(|event_data| event_data.session_type),
+            session_sequence_number: mpc_event_data
+                .map(|event_data| event_data.session_sequence_number),
+            current_mpc_round: session.current_mpc_round,
+            attempt_number: session.get_attempt_number(),
+            messages_received,
+            threshold_not_reached,
+            pending_computations,
+        }
+    }
+
+    fn pending_events_report(&self) -> PendingEventsReport {
+        PendingEventsReport {
+            pending_for_network_key: self
+                .events_pending_for_network_key
+                .iter()
+                .map(|(key_id, events)| {
+                    (*key_id, events.iter().map(pending_event_report).collect())
+                })
+                .collect(),
+            pending_for_next_active_committee: self
+                .events_pending_for_next_active_committee
+                .iter()
+                .map(pending_event_report)
+                .collect(),
+        }
+    }
+
+    fn network_keys_report(&self) -> Vec<NetworkKeyReport> {
+        // Borrowing doesn't mark the keys as seen, so the service still updates them.
+        let on_chain_keys = self
+            .sui_data_receivers
+            .network_keys_receiver
+            .borrow()
+            .clone();
+        let network_keys = &self.network_keys;
+        let instantiated_keys = network_keys
+            .network_encryption_keys
+            .iter()
+            .map(|(key_id, key)| (*key_id, (key.state.clone(), key.epoch)))
+            .collect();
+        network_keys_report(
+            &on_chain_keys,
+            &instantiated_keys,
+            &network_keys.shares_decryptions,
+            |key_id| network_keys.has_decryption_key_shares(key_id),
+        )
+    }
+}
+
+/// Reports every network encryption key known on Sui or to this validator, ordered by ID.
+///
+/// `instantiated_keys` holds the protocol and epoch of the public output each key was
+/// instantiated from, and `has_shares` whether this validator holds its shares of a key.
+fn network_keys_report(
+    on_chain_keys: &HashMap<ObjectID, DWalletNetworkEncryptionKeyData>,
+    instantiated_keys: &HashMap<ObjectID, (NetworkDecryptionKeyPublicOutputType, u64)>,
+    shares_decryptions: &HashMap<ObjectID, NetworkKeySharesDecryption>,
+    has_shares: impl Fn(&ObjectID) -> bool,
+) -> Vec<NetworkKeyReport> {
+    let key_ids: BTreeSet<ObjectID> = on_chain_keys
+        .keys()
+        .chain(instantiated_keys.keys())
+        .chain(shares_decryptions.keys())
+        .copied()
+        .collect();
+
+    key_ids
+        .into_iter()
+        .map(|key_id| {
+            let on_chain_key = on_chain_keys.get(&key_id);
+            let instantiated_key = instantiated_keys.get(&key_id);
+            let decryption = shares_decryptions.get(&key_id);
+            NetworkKeyReport {
+                key_id,
+                on_chain_state: on_chain_key.map(|key_data| key_data.state.clone()),
+                on_chain_epoch: on_chain_key.map(|key_data| key_data.current_epoch),
+                state: instantiated_key.map(|(state, _)| state.clone()),
+                epoch: instantiated_key.map(|(_, epoch)| *epoch),
+                shares_decrypted: has_shares(&key_id),
+                last_decryption_duration: decryption.map(|decryption| decryption.duration),
+                last_decryption_error: decryption.and_then(|decryption| decryption.error.clone()),
+            }
+        })
+        .collect()
+}
+
+fn pending_event_report(event: &DWalletMPCEvent) -> PendingEventReport {
+    PendingEventReport {
+        session_identifier: event.session_request.session_identifier,
+        protocol: event.session_request.request_input.to_string(),
+        session_sequence_number: event.session_request.session_sequence_number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_chain_key(
+        id: ObjectID,
+        current_epoch: u64,
+        state: DWalletNetworkEncryptionKeyState,
+    ) -> DWalletNetworkEncryptionKeyData {
+        DWalletNetworkEncryptionKeyData {
+            id,
+            current_epoch,
+            current_reconfiguration_public_output: vec![],
+            network_dkg_public_output: vec![],
+            state,
+        }
+    }
+
+    #[test]
+    fn network_key_status_is_derived_from_sui_and_the_decrypted_shares() {
+        let [awaiting_dkg, decrypted, failed] = [3, 1, 2].map(ObjectID::from_single_byte);
+        let on_chain_keys = HashMap::from([
+            (
+                awaiting_dkg,
+                on_chain_key(
+                    awaiting_dkg,
+                    1,
+                    DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG,
+                ),
+            ),
+            (
+                decrypted,
+                on_chain_key(
+                    decrypted,
+                    2,
+                    DWalletNetworkEncryptionKeyState::NetworkDKGCompleted,
+                ),
+            ),
+        ]);
+        // The failed key is known to this validator only, e.g. after it was read from Sui in an
+        // earlier epoch.
+        let instantiated_keys = HashMap::from([
+            (
+                decrypted,
+                (NetworkDecryptionKeyPublicOutputType::NetworkDkg, 2),
+            ),
+            (
+                failed,
+                (NetworkDecryptionKeyPublicOutputType::Reconfiguration, 3),
+            ),
+        ]);
+        let shares_decryptions = HashMap::from([
+            (
+                decrypted,
+                NetworkKeySharesDecryption {
+                    epoch: 2,
+                    duration: Duration::from_millis(40),
+                    error: None,
+                },
+            ),
+            (
+                failed,
+                NetworkKeySharesDecryption {
+                    epoch: 3,
+                    duration: Duration::from_millis(7),
+                    error: Some("invalid decryption key share".to_string()),
+                },
+            ),
+        ]);
+
+        let report = network_keys_report(
+            &on_chain_keys,
+            &instantiated_keys,
+            &shares_decryptions,
+            |key_id| *key_id == decrypted,
+        );
+
+        // Ordered by key ID.
+        let key_ids: Vec<_> = report.iter().map(|key| key.key_id).collect();
+        assert_eq!(key_ids, vec![decrypted, failed, awaiting_dkg]);
+
+        let decrypted_report = &report[0];
+        assert_eq!(
+            decrypted_report.on_chain_state,
+            Some(DWalletNetworkEncryptionKeyState::NetworkDKGCompleted)
+        );
+        assert_eq!(decrypted_report.on_chain_epoch, Some(2));
+        assert_eq!(
+            decrypted_report.state,
+            Some(NetworkDecryptionKeyPublicOutputType::NetworkDkg)
+        );
+        assert_eq!(decrypted_report.epoch, Some(2));
+        assert!(decrypted_report.shares_decrypted);
+        assert_eq!(
+            decrypted_report.last_decryption_duration,
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(decrypted_report.last_decryption_error, None);
+
+        let failed_report = &report[1];
+        assert_eq!(failed_report.on_chain_state, None);
+        assert_eq!(failed_report.on_chain_epoch, None);
+        assert_eq!(
+            failed_report.state,
+            Some(NetworkDecryptionKeyPublicOutputType::Reconfiguration)
+        );
+        assert_eq!(failed_report.epoch, Some(3));
+        assert!(!failed_report.shares_decrypted);
+        assert_eq!(
+            failed_report.last_decryption_error.as_deref(),
+            Some("invalid decryption key share")
+        );
+
+        // A key whose network DKG hasn't completed is only known on Sui.
+        let awaiting_dkg_report = &report[2];
+        assert_eq!(
+            awaiting_dkg_report.on_chain_state,
+            Some(DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG)
+        );
+        assert_eq!(awaiting_dkg_report.state, None);
+        assert_eq!(awaiting_dkg_report.epoch, None);
+        assert!(!awaiting_dkg_report.shares_decrypted);
+        assert_eq!(awaiting_dkg_report.last_decryption_duration, None);
+    }
+}
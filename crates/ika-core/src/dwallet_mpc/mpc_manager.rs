@@ -6,10 +6,12 @@ use crate::dwallet_mpc::crytographic_computation::mpc_computations::build_messag
 use crate::dwallet_mpc::crytographic_computation::{
     ComputationId, ComputationRequest, CryptographicComputationsOrchestrator,
 };
+use crate::dwallet_mpc::drain::DrainMode;
 use crate::dwallet_mpc::dwallet_mpc_metrics::DWalletMPCMetrics;
 use crate::dwallet_mpc::mpc_session::{DWalletMPCSession, DWalletMPCSessionOutput, MPCEventData};
 use crate::dwallet_mpc::network_dkg::instantiate_dwallet_mpc_network_encryption_key_public_data_from_public_output;
 use crate::dwallet_mpc::network_dkg::{DwalletMPCNetworkKeys, ValidatorPrivateDecryptionKeyData};
+use crate::dwallet_mpc::session_latency::SessionLatencyTracker;
 use crate::dwallet_mpc::{
     authority_name_to_party_id_from_committee, generate_access_structure_from_committee,
     get_validators_class_groups_public_keys_and_proofs, party_id_to_authority_name,
@@ -38,7 +40,7 @@ use std::sync::Arc;
 use sui_types::base_types::ObjectID;
 use tokio::sync::watch;
 use tokio::sync::watch::Receiver;
-use tracing::{debug, error, info, warn};
+use tracing::{Span, debug, error, info, instrument, warn};
 
 /// The [`DWalletMPCManager`] manages MPC sessions:
 /// — Keeping track of all MPC sessions,
@@ -81,10 +83,12 @@ pub(crate) struct DWalletMPCManager {
     pub(crate) events_pending_for_next_active_committee: Vec<DWalletMPCEvent>,
     pub(crate) next_active_committee: Option<Committee>,
     pub(crate) dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+    pub(crate) session_latency: SessionLatencyTracker,
+    pub(crate) drain_mode: DrainMode,
 
     network_dkg_third_round_delay: u64,
     decryption_key_reconfiguration_third_round_delay: u64,
-    sui_data_receivers: SuiDataReceivers,
+    pub(crate) sui_data_receivers: SuiDataReceivers,
 }
 
 impl DWalletMPCManager {
@@ -97,6 +101,8 @@ impl DWalletMPCManager {
         network_dkg_third_round_delay: u64,
         decryption_key_reconfiguration_third_round_delay: u64,
         dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
         sui_data_receivers: SuiDataReceivers,
     ) -> Self {
         Self::try_new(
@@ -108,6 +114,8 @@ impl DWalletMPCManager {
             network_dkg_third_round_delay,
             decryption_key_reconfiguration_third_round_delay,
             dwallet_mpc_metrics,
+            session_latency,
+            drain_mode,
             sui_data_receivers,
         )
         .unwrap_or_else(|err| {
@@ -126,6 +134,8 @@ impl DWalletMPCManager {
         network_dkg_third_round_delay: u64,
         decryption_key_reconfiguration_third_round_delay: u64,
         dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
         sui_data_receivers: SuiDataReceivers,
     ) -> DwalletMPCResult<Self> {
         let access_structure = generate_access_structure_from_committee(&committee)?;
@@ -162,6 +172,8 @@ impl DWalletMPCManager {
             events_pending_for_next_active_committee: Vec::new(),
             events_pending_for_network_key: HashMap::new(),
             dwallet_mpc_metrics,
+            session_latency,
+            drain_mode,
             next_active_committee: None,
             validator_name,
             committee,
@@ -248,6 +260,17 @@ impl DWalletMPCManager {
     }
 
     /// Handles a message by forwarding it to the relevant MPC session.
+    #[instrument(
+        name = "mpc_message",
+        level = "debug",
+        skip_all,
+        fields(
+            session_identifier = ?message.session_identifier,
+            sender_authority = ?message.authority,
+            consensus_round,
+            mpc_round = tracing::field::Empty,
+        )
+    )]
     pub(crate) fn handle_message(&mut self, consensus_round: u64, message: DWalletMPCMessage) {
         let session_identifier = message.session_identifier;
         let sender_authority = message.authority;
@@ -279,6 +302,7 @@ impl DWalletMPCManager {
 
             return;
         };
+        Span::current().record("mpc_round", mpc_round_number);
 
         let Ok(sender_party_id) =
             authority_name_to_party_id_from_committee(&self.committee, &sender_authority)
@@ -385,6 +409,9 @@ impl DWalletMPCManager {
     /// System sessions are always advanced before any user session,
     /// and both system and user sessions are ordered internally by their sequence numbers.
     ///
+    /// While the node is draining, only the user sessions it already started computing are advanced,
+    /// so that in-flight sessions don't lose this node's share.
+    ///
     /// The messages to advance with are built on the spot, assuming they satisfy required conditions.
     /// They are put on a `ComputationRequest` and forwarded to the `orchestrator` for execution.
     ///
@@ -392,6 +419,7 @@ impl DWalletMPCManager {
     pub(crate) async fn perform_cryptographic_computation(
         &mut self,
     ) -> HashMap<ComputationId, DwalletMPCResult<mpc::GuaranteedOutputDeliveryRoundResult>> {
+        let draining = self.drain_mode.is_draining();
         let mut ready_to_advance_sessions: Vec<_> = self
             .mpc_sessions
             .iter()
@@ -400,11 +428,13 @@ impl DWalletMPCManager {
                 // Only sessions with MPC event data should be advanced
                 session.mpc_event_data.clone().and_then(|mpc_event_data| {
                     // Always advance system sessions, and only advance user session
-                    // if they come before the last session to complete in the current epoch (at the current time).
+                    // if they come before the last session to complete in the current epoch (at the current time),
+                    // and the node isn't draining or already started computing the session.
                     let should_advance = match mpc_event_data.session_type {
                         SessionType::User => {
-                            mpc_event_data.session_sequence_number
-                                <= self.last_session_to_complete_in_current_epoch
+                            (!draining || session.computation_started)
+                                && mpc_event_data.session_sequence_number
+                                    <= self.last_session_to_complete_in_current_epoch
                         }
                         SessionType::System => true,
                     };
@@ -473,6 +503,7 @@ impl DWalletMPCManager {
             .cryptographic_computations_orchestrator
             .receive_completed_computations(self.dwallet_mpc_metrics.clone());
         for (computation_id, computation_request) in computation_requests {
+            let session_identifier = computation_id.session_identifier;
             let computation_executing = self
                 .cryptographic_computations_orchestrator
                 .try_spawn_cryptographic_computation(
@@ -485,11 +516,40 @@ impl DWalletMPCManager {
             if !computation_executing {
                 return completed_computation_results;
             }
+
+            if let Some(session) = self.mpc_sessions.get_mut(&session_identifier) {
+                session.computation_started = true;
+            }
         }
 
         completed_computation_results
     }
 
+    /// Marks a draining node as ready to stop once no cryptographic computation is running,
+    /// and no system session or user session it started computing is active.
+    pub(crate) fn update_drain_readiness(&self) {
+        if !self.drain_mode.is_draining() {
+            return;
+        }
+
+        let has_active_sessions_to_finish = self.mpc_sessions.values().any(|session| {
+            session.status == MPCSessionStatus::Active
+                && session.mpc_event_data.as_ref().is_some_and(
+                    |mpc_event_data| match mpc_event_data.session_type {
+                        SessionType::System => true,
+                        SessionType::User => session.computation_started,
+                    },
+                )
+        });
+        let ready_to_stop = !has_active_sessions_to_finish
+            && self
+                .cryptographic_computations_orchestrator
+                .running_computations_count()
+                == 0;
+
+        self.drain_mode.set_ready_to_stop(ready_to_stop);
+    }
+
     pub(crate) fn try_receiving_next_active_committee(&mut self) -> bool {
         match self
             .sui_data_receivers
@@ -551,11 +611,17 @@ impl DWalletMPCManager {
                                     continue;
                                 }
                                 info!(key_id=?key_id, "Updating (decrypting new shares) network key for key_id");
-                                if let Err(e) = self
+                                let result = self
                                     .network_keys
                                     .update_network_key(key_id, &key, &self.access_structure)
-                                    .await
+                                    .await;
+                                if let Some(decryption) =
+                                    self.network_keys.shares_decryptions.get(&key_id)
                                 {
+                                    self.dwallet_mpc_metrics
+                                        .set_network_key_status(&key_id, &key.state, decryption);
+                                }
+                                if let Err(e) = result {
                                     error!(error=?e, key_id=?key_id, "failed to update the network key");
                                 } else {
                                     new_key_ids.push(key_id);
@@ -599,6 +665,16 @@ impl DWalletMPCManager {
             .collect()
     }
 
+    #[instrument(
+        name = "mpc_output",
+        level = "info",
+        skip_all,
+        fields(
+            session_identifier = ?output.session_identifier,
+            sender_authority = ?output.authority,
+            consensus_round,
+        )
+    )]
     pub(crate) fn handle_output(
         &mut self,
         consensus_round: u64,
@@ -649,6 +725,8 @@ impl DWalletMPCManager {
         match built_outputs_to_finalize {
             Some((malicious_authorities, majority_vote)) => {
                 self.malicious_actors.extend(malicious_authorities.clone());
+                self.session_latency
+                    .record_output_quorum(&session_identifier, &majority_vote);
 
                 Some((malicious_authorities, majority_vote))
             }
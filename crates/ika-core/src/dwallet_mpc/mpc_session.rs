@@ -47,6 +47,10 @@ pub(crate) struct DWalletMPCSession {
 
     pub(crate) mpc_event_data: Option<MPCEventData>,
 
+    /// Whether this node spawned a cryptographic computation for the session.
+    /// A draining node keeps advancing the user sessions it started, but doesn't start new ones.
+    pub(super) computation_started: bool,
+
     /// All the messages that have been received for this session from each party, by consensus round and then by MPC round.
     /// Used to build the input of messages to advance each round of the session.
     pub(super) messages_by_consensus_round: HashMap<u64, MPCRoundToMessagesHashMap>,
@@ -71,6 +75,7 @@ impl DWalletMPCSession {
             mpc_round_to_threshold_not_reached_consensus_rounds: HashMap::new(),
             party_id,
             mpc_event_data,
+            computation_started: false,
             validator_name,
         }
     }
@@ -0,0 +1,388 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! End-to-end latency of MPC sessions, as seen by the users who requested them.
+//!
+//! A session is tracked from the moment its request event is observed, through the MPC rounds
+//! and the output majority vote, until the dwallet checkpoint holding its output is certified
+//! and then processed on Sui. The [`SessionLatencyTracker`] is shared by the MPC manager, the
+//! dwallet checkpoint aggregator and the Sui executor, which each record the stage they observe.
+
+use ika_types::digests::MessageDigest;
+use ika_types::message::DWalletCheckpointMessageKind;
+use ika_types::messages_dwallet_checkpoint::{
+    DWalletCheckpointMessage, DWalletCheckpointSequenceNumber,
+};
+use ika_types::messages_dwallet_mpc::{MPCRequestInput, SessionIdentifier};
+use prometheus::{HistogramVec, Registry, register_histogram_vec_with_registry};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const SESSION_LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.1, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0, 7.5, 10., 15., 20., 30., 45., 60.,
+    90., 120., 180., 300., 600., 1200., 1800., 3600.,
+];
+
+/// Sessions whose output reaches Sui later than this after their request event are logged with a
+/// breakdown of where the time went.
+const SLOW_SESSION_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Sessions are forgotten this long after their request event, even if their output was never
+/// seen on Sui, e.g. because their checkpoint was certified by state sync rather than locally.
+const SESSION_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// How often sessions past their retention are looked for.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The label of sessions whose request event was not received.
+const UNKNOWN_LABEL: &str = "unknown";
+
+/// The stage a session reached, measured from the observation of its request event.
+const STARTED_STAGE: &str = "started";
+const OUTPUT_QUORUM_STAGE: &str = "output_quorum";
+const CHECKPOINT_CERTIFIED_STAGE: &str = "checkpoint_certified";
+const EXECUTED_ON_SUI_STAGE: &str = "executed_on_sui";
+
+/// The lifecycle timestamps of a single session.
+struct SessionLifecycle {
+    protocol: Option<String>,
+    curve: Option<String>,
+    /// When the request event was first observed.
+    event_observed: Instant,
+    /// When the data the session waits for, e.g. the network key, was available,
+    /// and the session was created.
+    started: Option<Instant>,
+    /// When this validator computed each MPC round, in order.
+    rounds_computed: Vec<(u64, Instant)>,
+    output_quorum: Option<Instant>,
+    /// The number of the session's checkpoint messages that were not certified yet.
+    uncertified_messages: usize,
+    /// The last checkpoint holding a message of the session, and when it was certified.
+    checkpoint_certified: Option<(DWalletCheckpointSequenceNumber, Instant)>,
+}
+
+impl SessionLifecycle {
+    fn labels(&self) -> [&str; 2] {
+        [
+            self.protocol.as_deref().unwrap_or(UNKNOWN_LABEL),
+            self.curve.as_deref().unwrap_or(UNKNOWN_LABEL),
+        ]
+    }
+
+    /// Where the time of the session went, stage by stage, e.g.
+    /// `waiting=1.2s round_1=3.4s round_2=2.1s output_quorum=0.8s certified=1.5s
+    /// executed_on_sui=4.2s`.
+    fn breakdown(&self, executed_on_sui: Instant) -> String {
+        let mut stages = vec![];
+        let mut previous = self.event_observed;
+        let mut push = |name: String, at: Instant| {
+            stages.push(format!(
+                "{name}={:?}",
+                at.saturating_duration_since(previous)
+            ));
+            previous = at;
+        };
+        if let Some(started) = self.started {
+            push("waiting".to_string(), started);
+        }
+        for (mpc_round, computed) in &self.rounds_computed {
+            push(format!("round_{mpc_round}"), *computed);
+        }
+        if let Some(output_quorum) = self.output_quorum {
+            push("output_quorum".to_string(), output_quorum);
+        }
+        if let Some((_, certified)) = self.checkpoint_certified {
+            push("certified".to_string(), certified);
+        }
+        push("executed_on_sui".to_string(), executed_on_sui);
+        stages.join(" ")
+    }
+}
+
+#[derive(Default)]
+struct Sessions {
+    sessions: HashMap<SessionIdentifier, SessionLifecycle>,
+    /// The session of each checkpoint message of a session that reached output quorum.
+    sessions_by_message: HashMap<MessageDigest, SessionIdentifier>,
+    /// The sessions with a message in each certified checkpoint that was not processed on Sui.
+    sessions_by_checkpoint: BTreeMap<DWalletCheckpointSequenceNumber, Vec<SessionIdentifier>>,
+    last_pruned: Option<Instant>,
+}
+
+impl Sessions {
+    fn prune(&mut self, now: Instant) {
+        if self
+            .last_pruned
+            .is_some_and(|last_pruned| now.duration_since(last_pruned) < PRUNE_INTERVAL)
+        {
+            return;
+        }
+        self.last_pruned = Some(now);
+        self.sessions.retain(|_, session| {
+            now.saturating_duration_since(session.event_observed) < SESSION_RETENTION
+        });
+        let sessions = &self.sessions;
+        self.sessions_by_message
+            .retain(|_, session_identifier| sessions.contains_key(session_identifier));
+        self.sessions_by_checkpoint
+            .retain(|_, session_identifiers| {
+                session_identifiers
+                    .retain(|session_identifier| sessions.contains_key(session_identifier));
+                !session_identifiers.is_empty()
+            });
+    }
+}
+
+struct SessionLatencyMetrics {
+    /// The time from the observation of a session's request event until it reached each stage.
+    ///
+    /// Labels: protocol_name, curve, stage
+    session_latency_sec: HistogramVec,
+}
+
+/// Records the lifecycle of MPC sessions, and exports their latencies.
+#[derive(Clone)]
+pub struct SessionLatencyTracker {
+    sessions: Arc<Mutex<Sessions>>,
+    metrics: Arc<SessionLatencyMetrics>,
+}
+
+impl SessionLatencyTracker {
+    pub fn new(registry: &Registry) -> Self {
+        let metrics = SessionLatencyMetrics {
+            session_latency_sec: register_histogram_vec_with_registry!(
+                "dwallet_mpc_session_latency_sec",
+                "Time from observing the request event of an MPC session until it reached each stage",
+                &["protocol_name", "curve", "stage"],
+                SESSION_LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        };
+        Self {
+            sessions: Default::default(),
+            metrics: Arc::new(metrics),
+        }
+    }
+
+    pub fn new_for_testing() -> Self {
+        Self::new(&Registry::new())
+    }
+
+    /// Records the observation of a session's request event.
+    /// Only the first observation counts, as events are read again until their session completes.
+    pub(crate) fn record_event_observed(&self, session_identifier: SessionIdentifier) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.prune(now);
+        sessions
+            .sessions
+            .entry(session_identifier)
+            .or_insert_with(|| SessionLifecycle {
+                protocol: None,
+                curve: None,
+                event_observed: now,
+                started: None,
+                rounds_computed: vec![],
+                output_quorum: None,
+                uncertified_messages: 0,
+                checkpoint_certified: None,
+            });
+    }
+
+    /// Records the creation of a session, once the data it waits for is available.
+    pub(crate) fn record_session_started(
+        &self,
+        session_identifier: &SessionIdentifier,
+        request_input: &MPCRequestInput,
+    ) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(session) = sessions.sessions.get_mut(session_identifier) else {
+            return;
+        };
+        if session.started.is_some() {
+            return;
+        }
+        session.protocol = Some(request_input.to_string());
+        session.curve = Some(request_input.get_curve());
+        session.started = Some(now);
+        self.observe(session, STARTED_STAGE, now);
+    }
+
+    pub(crate) fn record_round_computed(
+        &self,
+        session_identifier: &SessionIdentifier,
+        mpc_round: u64,
+    ) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.sessions.get_mut(session_identifier) {
+            session.rounds_computed.push((mpc_round, Instant::now()));
+        }
+    }
+
+    /// Records the majority vote on the output of a session, which results in `messages` being
+    /// added to the next dwallet checkpoint.
+    pub(crate) fn record_output_quorum(
+        &self,
+        session_identifier: &SessionIdentifier,
+        messages: &[DWalletCheckpointMessageKind],
+    ) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        let Sessions {
+            sessions,
+            sessions_by_message,
+            ..
+        } = &mut *sessions;
+        let Some(session) = sessions.get_mut(session_identifier) else {
+            return;
+        };
+        if session.output_quorum.is_some() {
+            return;
+        }
+        session.output_quorum = Some(now);
+        self.observe(session, OUTPUT_QUORUM_STAGE, now);
+        for message in messages {
+            if sessions_by_message
+                .insert(message.digest(), *session_identifier)
+                .is_none()
+            {
+                session.uncertified_messages += 1;
+            }
+        }
+    }
+
+    /// Records the certification of a dwallet checkpoint.
+    pub(crate) fn record_checkpoint_certified(&self, checkpoint: &DWalletCheckpointMessage) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        let Sessions {
+            sessions,
+            sessions_by_message,
+            sessions_by_checkpoint,
+            ..
+        } = &mut *sessions;
+        for message in &checkpoint.messages {
+            let Some(session_identifier) = sessions_by_message.remove(&message.digest()) else {
+                continue;
+            };
+            let Some(session) = sessions.get_mut(&session_identifier) else {
+                continue;
+            };
+            session.uncertified_messages = session.uncertified_messages.saturating_sub(1);
+            session.checkpoint_certified = Some((checkpoint.sequence_number, now));
+            if session.uncertified_messages == 0 {
+                self.observe(session, CHECKPOINT_CERTIFIED_STAGE, now);
+                sessions_by_checkpoint
+                    .entry(checkpoint.sequence_number)
+                    .or_default()
+                    .push(session_identifier);
+            }
+        }
+    }
+
+    /// Records that Sui processed every dwallet checkpoint up to `sequence_number`, completing
+    /// the sessions whose outputs they hold.
+    pub(crate) fn record_checkpoints_executed_on_sui(
+        &self,
+        sequence_number: DWalletCheckpointSequenceNumber,
+    ) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.prune(now);
+        let Sessions {
+            sessions,
+            sessions_by_checkpoint,
+            ..
+        } = &mut *sessions;
+        while let Some(entry) = sessions_by_checkpoint.first_entry() {
+            if *entry.key() > sequence_number {
+                break;
+            }
+            for session_identifier in entry.remove() {
+                let Some(session) = sessions.remove(&session_identifier) else {
+                    continue;
+                };
+                self.observe(&session, EXECUTED_ON_SUI_STAGE, now);
+                let latency = now.saturating_duration_since(session.event_observed);
+                if latency >= SLOW_SESSION_THRESHOLD {
+                    let [protocol, curve] = session.labels();
+                    warn!(
+                        ?session_identifier,
+                        protocol,
+                        curve,
+                        ?latency,
+                        breakdown = session.breakdown(now),
+                        "slow MPC session"
+                    );
+                }
+            }
+        }
+    }
+
+    fn observe(&self, session: &SessionLifecycle, stage: &str, at: Instant) {
+        let [protocol, curve] = session.labels();
+        self.metrics
+            .session_latency_sec
+            .with_label_values(&[protocol, curve, stage])
+            .observe(
+                at.saturating_duration_since(session.event_observed)
+                    .as_secs_f64(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ika_types::messages_dwallet_mpc::SessionType;
+
+    fn checkpoint(
+        sequence_number: DWalletCheckpointSequenceNumber,
+        messages: Vec<DWalletCheckpointMessageKind>,
+    ) -> DWalletCheckpointMessage {
+        DWalletCheckpointMessage {
+            epoch: 1,
+            sequence_number,
+            messages,
+            checkpoint_commitments: vec![],
+        }
+    }
+
+    #[test]
+    fn sessions_complete_when_their_last_checkpoint_is_executed_on_sui() {
+        let tracker = SessionLatencyTracker::new_for_testing();
+        let session_identifier = SessionIdentifier::new(SessionType::User, [1; 32]);
+        let messages = vec![
+            DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(1),
+            DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(2),
+        ];
+
+        tracker.record_event_observed(session_identifier);
+        tracker.record_round_computed(&session_identifier, 1);
+        tracker.record_output_quorum(&session_identifier, &messages);
+
+        // The session's messages were split across two checkpoints.
+        tracker.record_checkpoint_certified(&checkpoint(3, vec![messages[0].clone()]));
+        tracker.record_checkpoint_certified(&checkpoint(4, vec![messages[1].clone()]));
+
+        tracker.record_checkpoints_executed_on_sui(3);
+        assert!(
+            tracker
+                .sessions
+                .lock()
+                .unwrap()
+                .sessions
+                .contains_key(&session_identifier)
+        );
+
+        tracker.record_checkpoints_executed_on_sui(4);
+        let sessions = tracker.sessions.lock().unwrap();
+        assert!(sessions.sessions.is_empty());
+        assert!(sessions.sessions_by_message.is_empty());
+        assert!(sessions.sessions_by_checkpoint.is_empty());
+    }
+}
@@ -88,6 +88,17 @@ impl CommitteeStore {
         Ok(committee)
     }
 
+    /// The epoch of the earliest committee in the store, if any.
+    pub fn get_lowest_committee_epoch(&self) -> IkaResult<Option<EpochId>> {
+        Ok(self
+            .tables
+            .committee_map
+            .safe_iter()
+            .next()
+            .transpose()?
+            .map(|(epoch, _)| epoch))
+    }
+
     // todo - make use of cache or remove this method
     pub fn get_latest_committee(&self) -> IkaResult<Committee> {
         Ok(self
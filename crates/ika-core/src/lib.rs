@@ -10,8 +10,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use sui_json_rpc_types::SuiEvent;
 use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::event::EventID;
 use tokio::sync::broadcast;
-use tokio::sync::watch::Receiver;
+use tokio::sync::watch::{self, Receiver};
 
 pub mod authority;
 pub mod consensus_adapter;
@@ -41,6 +42,9 @@ pub struct SuiDataReceivers {
     pub last_session_to_complete_in_current_epoch_receiver: Receiver<(EpochId, u64)>,
     pub end_of_publish_receiver: Receiver<Option<u64>>,
     pub uncompleted_events_receiver: Receiver<(Vec<DBSuiEvent>, EpochId)>,
+    /// Reports the last event from `new_events_receiver` that was handled,
+    /// so the Sui syncer resumes reading after it on restart.
+    pub handled_events_sender: watch::Sender<Option<EventID>>,
 }
 
 impl Clone for SuiDataReceivers {
@@ -54,6 +58,7 @@ impl Clone for SuiDataReceivers {
                 .clone(),
             end_of_publish_receiver: self.end_of_publish_receiver.clone(),
             uncompleted_events_receiver: self.uncompleted_events_receiver.clone(),
+            handled_events_sender: self.handled_events_sender.clone(),
         }
     }
 }
@@ -68,6 +73,7 @@ pub struct SuiDataSenders {
         tokio::sync::watch::Sender<(EpochId, u64)>,
     pub end_of_publish_sender: tokio::sync::watch::Sender<Option<u64>>,
     pub uncompleted_events_sender: tokio::sync::watch::Sender<(Vec<DBSuiEvent>, EpochId)>,
+    pub handled_events_receiver: Receiver<Option<EventID>>,
 }
 
 #[cfg(test)]
@@ -85,6 +91,7 @@ impl SuiDataReceivers {
         let (end_of_publish_sender, end_of_publish_receiver) = tokio::sync::watch::channel(None);
         let (uncompleted_events_sender, uncompleted_events_receiver) =
             tokio::sync::watch::channel((Vec::new(), EpochId::default()));
+        let (handled_events_sender, handled_events_receiver) = tokio::sync::watch::channel(None);
         let senders = SuiDataSenders {
             network_keys_sender,
             new_events_sender,
@@ -92,6 +99,7 @@ impl SuiDataReceivers {
             last_session_to_complete_in_current_epoch_sender,
             end_of_publish_sender,
             uncompleted_events_sender,
+            handled_events_receiver,
         };
         (
             SuiDataReceivers {
@@ -101,6 +109,7 @@ impl SuiDataReceivers {
                 last_session_to_complete_in_current_epoch_receiver,
                 end_of_publish_receiver,
                 uncompleted_events_receiver,
+                handled_events_sender,
             },
             senders,
         )
@@ -94,8 +94,6 @@ impl<S: Clone + Eq, const STRENGTH: bool> StakeAggregator<S, STRENGTH> {
         }
     }
 
-    // todo(zeev): why is it not used?
-    #[allow(dead_code)]
     pub fn contains_key(&self, authority: &AuthorityName) -> bool {
         self.data.contains_key(authority)
     }
@@ -118,6 +118,12 @@ impl ReadStore for RocksDbStore {
         Ok(self.committee_store.get_committee(&epoch).unwrap())
     }
 
+    fn get_lowest_committee_epoch(
+        &self,
+    ) -> Result<Option<EpochId>, ika_types::storage::error::Error> {
+        Ok(self.committee_store.get_lowest_committee_epoch().unwrap())
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         self.dwallet_checkpoint_store
             .get_highest_executed_dwallet_checkpoint()
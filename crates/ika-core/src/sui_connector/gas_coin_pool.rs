@@ -0,0 +1,596 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! A pool of gas coins owned by the notifier key.
+//!
+//! Every transaction the notifier submits to Sui leases one coin from the pool, so
+//! the transactions of independent streams (the dwallet checkpoints, and the transactions that
+//! mutate the System object) can be in flight at the same time without competing over
+//! the version of a single gas coin.
+//! The pool splits the notifier's balance into `target_coin_count` coins and merges
+//! coins that are too small to pay for a transaction back into the largest one.
+
+use crate::sui_connector::metrics::SuiConnectorMetrics;
+use crate::sui_connector::{NOTIFIER_GAS_BUDGET, SuiNotifier};
+use ika_sui_client::{SuiClient, SuiClientInner};
+use ika_types::error::{IkaError, IkaResult};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, CallArg, Command, ObjectArg};
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// How often the pool re-reads the notifier's coins from Sui, to pick up
+/// balance changes it cannot derive from transaction effects (e.g. gas reimbursements or top-ups).
+const GAS_COIN_POOL_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long `acquire` waits for an in-flight coin to be returned before checking the pool again.
+const GAS_COIN_WAIT_INTERVAL: Duration = Duration::from_millis(500);
+
+struct GasCoinPoolState {
+    /// Coins that can pay for a transaction, with their last known balance.
+    available: Vec<(ObjectRef, u64)>,
+    /// Coins leased to a transaction that has not completed yet,
+    /// keyed by object ID, with the version and balance they were leased at.
+    in_flight: HashMap<ObjectID, (ObjectRef, u64)>,
+    /// Coins whose balance is below the gas budget, to be merged into a larger coin.
+    dust: Vec<(ObjectRef, u64)>,
+    /// Set when a leased coin was not returned with the effects of its transaction,
+    /// so its current version is unknown and the pool must be re-read from Sui.
+    stale: bool,
+    last_refresh: Option<Instant>,
+}
+
+pub(crate) struct GasCoinPool<C> {
+    owner: SuiAddress,
+    target_coin_count: usize,
+    low_balance_threshold: u64,
+    sui_client: Arc<SuiClient<C>>,
+    metrics: Arc<SuiConnectorMetrics>,
+    state: Mutex<GasCoinPoolState>,
+    refresh_lock: tokio::sync::Mutex<()>,
+    coin_returned: Notify,
+}
+
+/// A gas coin leased from the [`GasCoinPool`] for a single transaction.
+///
+/// Call [`GasCoinLease::complete`] with the transaction's response to return the coin at its new
+/// version. A lease dropped without completing marks the pool as stale, and the coin is
+/// re-read from Sui before it is leased again.
+pub(crate) struct GasCoinLease<'a, C> {
+    pool: &'a GasCoinPool<C>,
+    coin: Option<(ObjectRef, u64)>,
+}
+
+impl<C> GasCoinLease<'_, C> {
+    pub(crate) fn object_ref(&self) -> ObjectRef {
+        self.coin
+            .expect("a gas coin lease always holds a coin until it is released")
+            .0
+    }
+
+    /// Returns the coin to the pool at the version found in `response`'s effects.
+    pub(crate) fn complete(mut self, response: &SuiTransactionBlockResponse) {
+        let Some((coin, balance)) = self.coin.take() else {
+            return;
+        };
+        match response.effects.as_ref() {
+            Some(effects) => {
+                let gas_object = effects.gas_object().reference.to_object_ref();
+                let balance =
+                    balance.saturating_add_signed(-effects.gas_cost_summary().net_gas_usage());
+                self.pool.release(coin.0, Some((gas_object, balance)));
+            }
+            None => self.pool.release(coin.0, None),
+        }
+    }
+
+    /// Returns the coin to the pool as it was, for a transaction that was not submitted.
+    pub(crate) fn unused(mut self) {
+        if let Some((coin, balance)) = self.coin.take() {
+            self.pool.release(coin.0, Some((coin, balance)));
+        }
+    }
+}
+
+impl<C> Drop for GasCoinLease<'_, C> {
+    fn drop(&mut self) {
+        if let Some((coin, _)) = self.coin.take() {
+            self.pool.release(coin.0, None);
+        }
+    }
+}
+
+impl<C> GasCoinPool<C> {
+    fn release(&self, coin_id: ObjectID, updated_coin: Option<(ObjectRef, u64)>) {
+        let mut state = self.state.lock();
+        state.in_flight.remove(&coin_id);
+        match updated_coin {
+            Some((coin, balance)) => state.insert(coin, balance),
+            None => state.stale = true,
+        }
+        self.update_metrics(&state);
+        drop(state);
+        self.coin_returned.notify_waiters();
+    }
+
+    fn update_metrics(&self, state: &GasCoinPoolState) {
+        let min_balance = state
+            .coins()
+            .map(|(_, balance)| *balance)
+            .min()
+            .unwrap_or_default();
+        let total_balance = state.total_balance();
+        self.metrics.gas_coin_balance.set(total_balance as i64);
+        self.metrics.min_gas_coin_balance.set(min_balance as i64);
+        self.metrics
+            .available_gas_coins
+            .set(state.available.len() as i64);
+        self.metrics
+            .in_flight_gas_coins
+            .set(state.in_flight.len() as i64);
+        self.metrics
+            .gas_coin_balance_low
+            .set((total_balance < self.low_balance_threshold) as i64);
+    }
+}
+
+impl<C> GasCoinPool<C>
+where
+    C: SuiClientInner + 'static,
+{
+    pub(crate) fn new(
+        owner: SuiAddress,
+        target_coin_count: usize,
+        low_balance_threshold: Option<u64>,
+        sui_client: Arc<SuiClient<C>>,
+        metrics: Arc<SuiConnectorMetrics>,
+    ) -> Self {
+        let target_coin_count = target_coin_count.max(1);
+        Self {
+            owner,
+            target_coin_count,
+            low_balance_threshold: low_balance_threshold
+                .unwrap_or(target_coin_count as u64 * NOTIFIER_GAS_BUDGET),
+            sui_client,
+            metrics,
+            state: Mutex::new(GasCoinPoolState {
+                available: vec![],
+                in_flight: HashMap::new(),
+                dust: vec![],
+                stale: true,
+                last_refresh: None,
+            }),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            coin_returned: Notify::new(),
+        }
+    }
+
+    /// Leases the available coin with the highest balance,
+    /// waiting for an in-flight coin to be returned if none is available.
+    pub(crate) async fn acquire(
+        &self,
+        sui_notifier: &SuiNotifier,
+    ) -> IkaResult<GasCoinLease<'_, C>> {
+        loop {
+            if self.needs_refresh() {
+                self.refresh(sui_notifier).await?;
+            }
+            let coin_returned = self.coin_returned.notified();
+            {
+                let mut state = self.state.lock();
+                let highest_balance_coin = state
+                    .available
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, (_, balance))| *balance)
+                    .map(|(index, _)| index);
+                if let Some(index) = highest_balance_coin {
+                    let coin = state.available.swap_remove(index);
+                    state.in_flight.insert(coin.0.0, coin);
+                    self.update_metrics(&state);
+                    return Ok(GasCoinLease {
+                        pool: self,
+                        coin: Some(coin),
+                    });
+                }
+                if state.in_flight.is_empty() {
+                    return Err(IkaError::SuiConnectorInternalError(format!(
+                        "no gas coin with a balance of at least {NOTIFIER_GAS_BUDGET} found for {}",
+                        self.owner
+                    )));
+                }
+            }
+            let _ = tokio::time::timeout(GAS_COIN_WAIT_INTERVAL, coin_returned).await;
+        }
+    }
+
+    fn needs_refresh(&self) -> bool {
+        let state = self.state.lock();
+        state.stale
+            || (state.available.is_empty() && state.in_flight.is_empty())
+            || state
+                .last_refresh
+                .is_none_or(|last_refresh| last_refresh.elapsed() > GAS_COIN_POOL_REFRESH_INTERVAL)
+    }
+
+    /// Re-reads the notifier's coins from Sui, then splits and merges them
+    /// if the pool is not at its target shape.
+    async fn refresh(&self, sui_notifier: &SuiNotifier) -> IkaResult<()> {
+        let _refresh_guard = self.refresh_lock.lock().await;
+        if !self.needs_refresh() {
+            // Another task refreshed the pool while we were waiting.
+            return Ok(());
+        }
+        let coins = self.sui_client.get_gas_coins(self.owner).await?;
+        {
+            let mut state = self.state.lock();
+            let known_versions: HashMap<ObjectID, ObjectRef> = state
+                .available
+                .iter()
+                .chain(state.dust.iter())
+                .map(|(coin, _)| (coin.0, *coin))
+                .collect();
+            state.available.clear();
+            state.dust.clear();
+            for (coin, balance) in coins {
+                if state.in_flight.contains_key(&coin.0) {
+                    continue;
+                }
+                // The RPC node may lag behind the effects we already applied locally.
+                let coin = match known_versions.get(&coin.0) {
+                    Some(known) if known.1 > coin.1 => *known,
+                    _ => coin,
+                };
+                state.insert(coin, balance);
+            }
+            state.stale = false;
+            state.last_refresh = Some(Instant::now());
+            self.update_metrics(&state);
+            if state.total_balance() < self.low_balance_threshold {
+                warn!(
+                    owner=?self.owner,
+                    threshold=?self.low_balance_threshold,
+                    "The notifier's gas balance is low"
+                );
+            }
+        }
+        if let Err(err) = self.rebalance(sui_notifier).await {
+            // The pool can still lease the coins it has.
+            error!(error=?err, "failed to rebalance the notifier's gas coins");
+        }
+        Ok(())
+    }
+
+    /// Merges the dust coins into the largest available coin,
+    /// and splits it until the pool holds `target_coin_count` coins.
+    async fn rebalance(&self, sui_notifier: &SuiNotifier) -> IkaResult<()> {
+        let (largest, dust, split_amounts) = {
+            let mut state = self.state.lock();
+            let Some(index) = state
+                .available
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, (_, balance))| *balance)
+                .map(|(index, _)| index)
+            else {
+                return Ok(());
+            };
+            let largest_balance = state.available[index].1
+                + state.dust.iter().map(|(_, balance)| balance).sum::<u64>();
+            let coin_count = state.available.len() + state.in_flight.len();
+            let missing_coins = self.target_coin_count.saturating_sub(coin_count) as u64;
+            // Every coin, including the one being split, must keep enough to pay the gas budget.
+            let split_count =
+                missing_coins.min((largest_balance / NOTIFIER_GAS_BUDGET).saturating_sub(1));
+            if split_count == 0 && state.dust.is_empty() {
+                return Ok(());
+            }
+            let split_amount = largest_balance / (split_count + 1);
+            let largest = state.available.swap_remove(index);
+            state.in_flight.insert(largest.0.0, largest);
+            let dust = std::mem::take(&mut state.dust);
+            (largest, dust, vec![split_amount; split_count as usize])
+        };
+        info!(
+            coin=?largest.0,
+            merged_coins=?dust.len(),
+            new_coins=?split_amounts.len(),
+            "Rebalancing the notifier's gas coins"
+        );
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let dust_refs = dust.iter().map(|(coin, _)| *coin).collect::<Vec<_>>();
+        merge_gas_coins(&mut ptb, &dust_refs)?;
+        if !split_amounts.is_empty() {
+            let amounts = split_amounts
+                .iter()
+                .map(|amount| ptb.pure(*amount))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    IkaError::SuiConnectorSerializationError(format!(
+                        "can't serialize split amount: {e}"
+                    ))
+                })?;
+            let Argument::Result(split_result) =
+                ptb.command(Command::SplitCoins(Argument::GasCoin, amounts))
+            else {
+                unreachable!("a command always returns `Argument::Result`");
+            };
+            let new_coins = (0..split_amounts.len())
+                .map(|index| Argument::NestedResult(split_result, index as u16))
+                .collect();
+            ptb.transfer_args(self.owner, new_coins);
+        }
+
+        let transaction = super::build_sui_transaction(
+            ptb.finish(),
+            &self.sui_client,
+            vec![largest.0],
+            sui_notifier.signer.as_ref(),
+            &self.metrics,
+        )
+        .await;
+        let response = match transaction {
+            Ok(transaction) => {
+                self.sui_client
+                    .execute_transaction_block_with_effects(transaction)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+
+        let mut state = self.state.lock();
+        state.in_flight.remove(&largest.0.0);
+        let effects = match response {
+            Ok(response) => response.effects,
+            Err(err) => {
+                state.stale = true;
+                self.update_metrics(&state);
+                return Err(err);
+            }
+        };
+        let Some(effects) =
+            effects.filter(|effects| matches!(effects.status(), SuiExecutionStatus::Success))
+        else {
+            state.stale = true;
+            self.update_metrics(&state);
+            return Err(IkaError::SuiConnectorInternalError(
+                "the gas coins rebalance transaction failed".to_string(),
+            ));
+        };
+        let merged_balance = largest.1 + dust.iter().map(|(_, balance)| balance).sum::<u64>();
+        let split_balance = split_amounts.iter().sum::<u64>();
+        let gas_balance = (merged_balance - split_balance)
+            .saturating_add_signed(-effects.gas_cost_summary().net_gas_usage());
+        // Paying for the rebalance itself may leave the split coin below the gas budget.
+        state.insert(effects.gas_object().reference.to_object_ref(), gas_balance);
+        for (created, amount) in effects.created().iter().zip(split_amounts) {
+            state.insert(created.reference.to_object_ref(), amount);
+        }
+        self.update_metrics(&state);
+        drop(state);
+        self.coin_returned.notify_waiters();
+        Ok(())
+    }
+}
+
+impl GasCoinPoolState {
+    /// Adds a coin that is not leased, as available if it can pay for a transaction.
+    fn insert(&mut self, coin: ObjectRef, balance: u64) {
+        if balance >= NOTIFIER_GAS_BUDGET {
+            self.available.push((coin, balance));
+        } else {
+            self.dust.push((coin, balance));
+        }
+    }
+
+    fn coins(&self) -> impl Iterator<Item = &(ObjectRef, u64)> {
+        self.available
+            .iter()
+            .chain(self.in_flight.values())
+            .chain(self.dust.iter())
+    }
+
+    fn total_balance(&self) -> u64 {
+        self.coins().map(|(_, balance)| balance).sum()
+    }
+}
+
+/// Merge multiple gas coins into one by adding a `MergeCoins` command to the
+/// provided `ProgrammableTransactionBuilder`.
+/// If `gas_coins` is empty, the function is no‑op.
+fn merge_gas_coins(
+    ptb: &mut ProgrammableTransactionBuilder,
+    gas_coins: &[ObjectRef],
+) -> IkaResult<()> {
+    if gas_coins.is_empty() {
+        return Ok(());
+    }
+
+    let coins: IkaResult<Vec<_>> = gas_coins
+        .iter()
+        .map(|c| {
+            ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(*c)))
+                .map_err(|e| {
+                    IkaError::SuiConnectorInternalError(format!(
+                        "error merging coin ProgrammableTransactionBuilder::input: {e}"
+                    ))
+                })
+        })
+        .collect();
+
+    ptb.command(Command::MergeCoins(Argument::GasCoin, coins?));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ika_sui_client::signer::{FileSigner, Signer};
+    use ika_sui_client::sui_mock_client::SuiMockClient;
+    use ika_types::crypto::{AccountKeyPair, get_key_pair_from_rng};
+    use rand::rngs::OsRng;
+    use sui_types::crypto::SuiKeyPair;
+
+    struct TestPool {
+        mock: SuiMockClient,
+        sui_client: Arc<SuiClient<SuiMockClient>>,
+        sui_notifier: SuiNotifier,
+        pool: GasCoinPool<SuiMockClient>,
+    }
+
+    impl TestPool {
+        /// A pool of `target_coin_count` coins, whose owner holds a coin of each of `balances`.
+        fn new(target_coin_count: usize, balances: &[u64]) -> Self {
+            let (_, sui_key) = get_key_pair_from_rng::<AccountKeyPair, _>(&mut OsRng);
+            let signer = FileSigner::new(SuiKeyPair::Ed25519(sui_key));
+            let sui_address = signer.address();
+            let mock = SuiMockClient::new();
+            for balance in balances {
+                mock.add_gas_coin(sui_address, *balance);
+            }
+            let sui_client = Arc::new(SuiClient::new_for_testing(mock.clone()));
+            let pool = GasCoinPool::new(
+                sui_address,
+                target_coin_count,
+                None,
+                sui_client.clone(),
+                SuiConnectorMetrics::new_for_testing(),
+            );
+            Self {
+                mock,
+                sui_client,
+                sui_notifier: SuiNotifier {
+                    signer: Arc::new(signer),
+                    sui_address,
+                },
+                pool,
+            }
+        }
+
+        async fn acquire(&self) -> IkaResult<GasCoinLease<'_, SuiMockClient>> {
+            self.pool.acquire(&self.sui_notifier).await
+        }
+
+        /// The owner's coins on the mocked chain.
+        fn coins(&self) -> Vec<(ObjectRef, u64)> {
+            self.mock.gas_coins(self.sui_notifier.sui_address)
+        }
+
+        /// Executes an empty transaction paid for by `lease`, and returns the coin to the pool.
+        async fn execute(&self, lease: GasCoinLease<'_, SuiMockClient>) {
+            let transaction = crate::sui_connector::build_sui_transaction(
+                ProgrammableTransactionBuilder::new().finish(),
+                &self.sui_client,
+                vec![lease.object_ref()],
+                self.sui_notifier.signer.as_ref(),
+                &self.pool.metrics,
+            )
+            .await
+            .unwrap();
+            let response = self
+                .sui_client
+                .execute_transaction_block_with_effects(transaction)
+                .await
+                .unwrap();
+            lease.complete(&response);
+        }
+    }
+
+    #[tokio::test]
+    async fn leased_coins_are_returned_to_the_pool() {
+        let test = TestPool::new(2, &[20 * NOTIFIER_GAS_BUDGET, 10 * NOTIFIER_GAS_BUDGET]);
+
+        // The coin with the highest balance is leased first.
+        let first = test.acquire().await.unwrap();
+        let second = test.acquire().await.unwrap();
+        let coins = test.coins();
+        assert!(coins.contains(&(first.object_ref(), 20 * NOTIFIER_GAS_BUDGET)));
+        assert!(coins.contains(&(second.object_ref(), 10 * NOTIFIER_GAS_BUDGET)));
+
+        // While every coin is leased, acquiring waits for one to be returned.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), test.acquire())
+                .await
+                .is_err()
+        );
+
+        // An unused coin is returned as it was.
+        let second_coin = second.object_ref();
+        second.unused();
+        let second = test.acquire().await.unwrap();
+        assert_eq!(second.object_ref(), second_coin);
+
+        // A used coin is returned at the version and balance the transaction left it at.
+        test.execute(first).await;
+        let state = test.pool.state.lock();
+        assert_eq!(state.in_flight.len(), 1);
+        assert_eq!(state.available.len(), 1);
+        assert!(test.coins().contains(&state.available[0]));
+        assert!(state.available[0].1 < 20 * NOTIFIER_GAS_BUDGET);
+    }
+
+    #[tokio::test]
+    async fn rebalance_splits_the_largest_coin() {
+        let test = TestPool::new(4, &[40 * NOTIFIER_GAS_BUDGET]);
+
+        let _lease = test.acquire().await.unwrap();
+        let coins = test.coins();
+        assert_eq!(coins.len(), 4);
+        assert!(
+            coins
+                .iter()
+                .all(|(_, balance)| *balance >= NOTIFIER_GAS_BUDGET)
+        );
+        let state = test.pool.state.lock();
+        assert_eq!((state.available.len(), state.in_flight.len()), (3, 1));
+        assert!(state.dust.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rebalance_does_not_lease_coins_below_the_gas_budget() {
+        // Splitting leaves the gas coin with the gas budget, minus the gas of the split.
+        let test = TestPool::new(2, &[2 * NOTIFIER_GAS_BUDGET]);
+
+        let lease = test.acquire().await.unwrap();
+        assert_eq!(test.coins().len(), 2);
+        assert!(
+            test.coins()
+                .contains(&(lease.object_ref(), NOTIFIER_GAS_BUDGET))
+        );
+        {
+            let state = test.pool.state.lock();
+            assert!(state.available.is_empty());
+            assert_eq!(state.dust.len(), 1);
+        }
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), test.acquire())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn acquire_fails_when_the_pool_runs_out_of_coins() {
+        let test = TestPool::new(1, &[NOTIFIER_GAS_BUDGET / 2]);
+        assert!(test.acquire().await.is_err());
+
+        // Once the owner is topped up, the dust is merged into the new coin.
+        test.mock
+            .add_gas_coin(test.sui_notifier.sui_address, 2 * NOTIFIER_GAS_BUDGET);
+        let lease = test.acquire().await.unwrap();
+        let coins = test.coins();
+        assert_eq!(coins.len(), 1);
+        assert_eq!(coins[0].0, lease.object_ref());
+        assert!(coins[0].1 > 2 * NOTIFIER_GAS_BUDGET);
+    }
+}
@@ -2,12 +2,12 @@
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
 use prometheus::{
-    IntGauge, IntGaugeVec, Registry, register_int_gauge_vec_with_registry,
-    register_int_gauge_with_registry,
+    HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Registry,
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry,
 };
 use std::sync::Arc;
 
-#[allow(unused)]
 const FINE_GRAINED_LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.15, 0.2, 0.25, 0.3, 0.35, 0.4, 0.45, 0.5, 0.6, 0.7, 0.8, 0.9,
     1.0, 1.2, 1.4, 1.6, 1.8, 2.0, 2.5, 3.0, 3.5, 4.0, 5.0, 6.0, 6.5, 7.0, 7.5, 8.0, 8.5, 9.0, 9.5,
@@ -15,12 +15,47 @@ const FINE_GRAINED_LATENCY_SEC_BUCKETS: &[f64] = &[
     200., 250., 300., 350., 400.,
 ];
 
+const CHECKPOINTS_PER_TRANSACTION_BUCKETS: &[f64] = &[1., 2., 3., 4., 6., 8., 12., 16.];
+
 #[derive(Clone, Debug)]
 pub struct SuiConnectorMetrics {
     pub last_synced_sui_checkpoints: IntGaugeVec,
 
+    /// Set to 1 for each module whose events are read on every new Sui checkpoint,
+    /// and to 0 while they are polled.
+    pub(crate) sui_checkpoint_stream_active: IntGaugeVec,
+
+    /// Number of events of each module that were read again after startup,
+    /// because they were delivered before the node restarted.
+    pub(crate) sui_events_reprocessed_after_startup: IntCounterVec,
+
+    /// Number of the notifier's transactions that aborted on Move in a dry-run,
+    /// by module, abort code, and whether retrying them can succeed.
+    pub(crate) notifier_move_aborts: IntCounterVec,
+
+    /// Number of times a checkpoint submission or an epoch switch step aborted
+    /// deterministically and was parked, by step.
+    pub(crate) notifier_aborted_steps: IntCounterVec,
+
+    /// Set to 1 for each step that is parked after aborting deterministically,
+    /// until the on-chain state it reads changes.
+    pub(crate) notifier_parked_steps: IntGaugeVec,
+
+    /// Total balance of the notifier's gas coins, in MIST.
     pub gas_coin_balance: IntGauge,
 
+    /// Balance of the notifier's smallest gas coin, in MIST.
+    pub(crate) min_gas_coin_balance: IntGauge,
+
+    /// Number of the notifier's gas coins available for a new transaction.
+    pub(crate) available_gas_coins: IntGauge,
+
+    /// Number of the notifier's gas coins used by transactions that have not completed yet.
+    pub(crate) in_flight_gas_coins: IntGauge,
+
+    /// Set to 1 when the notifier's total gas balance is below the configured threshold.
+    pub(crate) gas_coin_balance_low: IntGauge,
+
     /// Sequence number of the next dwallet checkpoint to write to Sui.
     pub(crate) dwallet_checkpoint_sequence: IntGauge,
 
@@ -50,6 +85,25 @@ pub struct SuiConnectorMetrics {
 
     /// Total number of failed system checkpoint writes to Sui.
     pub(crate) system_checkpoint_writes_failure_total: IntGauge,
+
+    /// Number of certified dwallet checkpoints not yet processed on Sui.
+    pub(crate) dwallet_checkpoint_backlog: IntGauge,
+
+    /// Number of certified system checkpoints not yet processed on Sui.
+    pub(crate) system_checkpoint_backlog: IntGauge,
+
+    /// Number of checkpoints packed into each Sui transaction, by checkpoint type.
+    pub(crate) checkpoints_per_sui_transaction: HistogramVec,
+
+    /// Time from submitting a checkpoints transaction until it is executed on Sui,
+    /// by checkpoint type.
+    pub(crate) checkpoint_sui_transaction_latency_sec: HistogramVec,
+
+    /// The epoch whose epoch switch steps are reported by `epoch_switch_step_done`.
+    pub(crate) epoch_switch_epoch: IntGauge,
+
+    /// Set to 1 for each epoch switch step this node ran in the current epoch, by step.
+    pub(crate) epoch_switch_step_done: IntGaugeVec,
 }
 
 impl SuiConnectorMetrics {
@@ -62,9 +116,68 @@ impl SuiConnectorMetrics {
                 registry,
             )
             .unwrap(),
+            sui_checkpoint_stream_active: register_int_gauge_vec_with_registry!(
+                "sui_connector_sui_checkpoint_stream_active",
+                "Whether the events of each module are read on every new Sui checkpoint (1) or polled (0)",
+                &["module_name"],
+                registry,
+            )
+            .unwrap(),
+            sui_events_reprocessed_after_startup: register_int_counter_vec_with_registry!(
+                "sui_connector_sui_events_reprocessed_after_startup",
+                "The number of events of each module that were read again after startup, because they were delivered before the restart",
+                &["module_name"],
+                registry,
+            )
+            .unwrap(),
+            notifier_move_aborts: register_int_counter_vec_with_registry!(
+                "sui_connector_notifier_move_aborts",
+                "The number of notifier transactions that aborted on Move in a dry-run, by module and abort code",
+                &["module_name", "abort_code", "deterministic"],
+                registry,
+            )
+            .unwrap(),
+            notifier_aborted_steps: register_int_counter_vec_with_registry!(
+                "sui_connector_notifier_aborted_steps",
+                "The number of times a checkpoint submission or an epoch switch step aborted deterministically and was parked, by step",
+                &["step"],
+                registry,
+            )
+            .unwrap(),
+            notifier_parked_steps: register_int_gauge_vec_with_registry!(
+                "sui_connector_notifier_parked_steps",
+                "Set to 1 for each step that is parked after aborting deterministically, until the on-chain state it reads changes",
+                &["step"],
+                registry,
+            )
+            .unwrap(),
             gas_coin_balance: register_int_gauge_with_registry!(
                 "sui_connector_gas_coin_balance",
-                "Current balance of gas coin, in mist",
+                "Total balance of the notifier's gas coins, in mist",
+                registry,
+            )
+            .unwrap(),
+            min_gas_coin_balance: register_int_gauge_with_registry!(
+                "sui_connector_min_gas_coin_balance",
+                "Balance of the notifier's smallest gas coin, in mist",
+                registry,
+            )
+            .unwrap(),
+            available_gas_coins: register_int_gauge_with_registry!(
+                "sui_connector_available_gas_coins",
+                "Number of the notifier's gas coins available for a new transaction",
+                registry,
+            )
+            .unwrap(),
+            in_flight_gas_coins: register_int_gauge_with_registry!(
+                "sui_connector_in_flight_gas_coins",
+                "Number of the notifier's gas coins used by transactions that have not completed yet",
+                registry,
+            )
+            .unwrap(),
+            gas_coin_balance_low: register_int_gauge_with_registry!(
+                "sui_connector_gas_coin_balance_low",
+                "Set to 1 when the notifier's total gas balance is below the configured threshold",
                 registry,
             )
             .unwrap(),
@@ -133,6 +246,47 @@ impl SuiConnectorMetrics {
                 registry,
             )
             .unwrap(),
+            dwallet_checkpoint_backlog: register_int_gauge_with_registry!(
+                "sui_connector_dwallet_checkpoint_backlog",
+                "Number of certified dwallet checkpoints not yet processed on Sui",
+                registry,
+            )
+            .unwrap(),
+            system_checkpoint_backlog: register_int_gauge_with_registry!(
+                "sui_connector_system_checkpoint_backlog",
+                "Number of certified system checkpoints not yet processed on Sui",
+                registry,
+            )
+            .unwrap(),
+            checkpoints_per_sui_transaction: register_histogram_vec_with_registry!(
+                "sui_connector_checkpoints_per_sui_transaction",
+                "Number of checkpoints packed into each Sui transaction",
+                &["checkpoint_type"],
+                CHECKPOINTS_PER_TRANSACTION_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            checkpoint_sui_transaction_latency_sec: register_histogram_vec_with_registry!(
+                "sui_connector_checkpoint_sui_transaction_latency_sec",
+                "Time from submitting a checkpoints transaction until it is executed on Sui",
+                &["checkpoint_type"],
+                FINE_GRAINED_LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            epoch_switch_epoch: register_int_gauge_with_registry!(
+                "sui_connector_epoch_switch_epoch",
+                "The epoch whose epoch switch steps are reported",
+                registry,
+            )
+            .unwrap(),
+            epoch_switch_step_done: register_int_gauge_vec_with_registry!(
+                "sui_connector_epoch_switch_step_done",
+                "Set to 1 for each epoch switch step this node ran in the current epoch",
+                &["step"],
+                registry,
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
@@ -1,51 +1,80 @@
 // Copyright (c) dWallet Labs, Ltd.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
+use crate::authority::authority_perpetual_tables::AuthorityPerpetualTables;
 use crate::dwallet_checkpoints::DWalletCheckpointStore;
+use crate::dwallet_mpc::drain::DrainMode;
+use crate::dwallet_mpc::session_latency::SessionLatencyTracker;
 use crate::sui_connector::metrics::SuiConnectorMetrics;
-use crate::sui_connector::sui_executor::{StopReason, SuiExecutor};
+use crate::sui_connector::move_abort::MoveAbort;
+use crate::sui_connector::sui_executor::{EpochSwitchState, StopReason, SuiExecutor};
 use crate::sui_connector::sui_syncer::SuiSyncer;
 use crate::system_checkpoints::SystemCheckpointStore;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use futures::{StreamExt, future};
 use ika_config::node::{RunWithRange, SuiChainIdentifier, SuiConnectorConfig};
+use ika_network::network_keys;
+use ika_sui_client::multi_endpoint::MultiEndpointSuiClient;
+use ika_sui_client::signer::{FileSigner, RemoteSigner, Signer, sign_transaction};
 use ika_sui_client::{SuiClient, SuiClientInner};
 use ika_types::committee::{Committee, EpochId};
-use ika_types::error::IkaResult;
+use ika_types::error::{IkaError, IkaResult};
 use ika_types::messages_consensus::MovePackageDigest;
 use ika_types::messages_dwallet_mpc::{
     DBSuiEvent, DWalletNetworkEncryptionKeyData, SESSIONS_MANAGER_MODULE_NAME,
 };
-use shared_crypto::intent::{Intent, IntentMessage};
+use ika_types::sui::SYSTEM_MODULE_NAME;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use sui_json_rpc_types::{Coin, SuiEvent};
-use sui_sdk::SuiClient as SuiSdkClient;
+use sui_json_rpc_types::{Coin, SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_sdk::apis::CoinReadApi;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::crypto::{Signature, SuiKeyPair};
 use sui_types::digests::{get_mainnet_chain_identifier, get_testnet_chain_identifier};
+use sui_types::event::EventID;
 use sui_types::transaction::{ProgrammableTransaction, Transaction, TransactionData};
 use tokio::sync::watch;
 use tokio::sync::watch::{Receiver, Sender};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{error, info};
 
 pub mod end_of_publish_sender;
+pub mod gas_coin_pool;
 pub mod metrics;
+mod move_abort;
+mod parked_steps;
 pub mod sui_executor;
 pub mod sui_syncer;
 
+/// The gas budget of every transaction the notifier submits to Sui, in MIST.
+/// Each gas coin in the notifier's pool must hold at least this much.
+pub(crate) const NOTIFIER_GAS_BUDGET: u64 = 10_000_000_000;
+
+/// The margin added to the gas cost of a notifier transaction's dry-run
+/// to set its gas budget, in percent.
+const NOTIFIER_GAS_BUDGET_MARGIN_PERCENT: u64 = 20;
+
+/// The minimal gas budget of a Sui transaction, in units of the reference gas price.
+const MIN_GAS_BUDGET_IN_GAS_UNITS: u64 = 1_000;
+
+/// The total size of the checkpoint messages the notifier packs into a single Sui transaction.
+/// Matches the protocol's per-checkpoint size limit, so a batch never costs more computation
+/// than a single full checkpoint, while many small checkpoints can share one transaction.
+pub(crate) const MAX_CHECKPOINT_BATCH_MESSAGE_BYTES: usize = 50 * 1024;
+
+/// The maximum number of checkpoints the notifier packs into a single Sui transaction.
+/// Each checkpoint costs a BLS signature verification, so this bounds the transaction's gas.
+pub(crate) const MAX_CHECKPOINTS_PER_SUI_TRANSACTION: usize = 16;
+
 pub struct SuiNotifier {
-    sui_key: SuiKeyPair,
+    signer: Arc<dyn Signer>,
     sui_address: SuiAddress,
 }
 
 pub struct SuiConnectorService {
-    sui_client: Arc<SuiClient<SuiSdkClient>>,
-    sui_executor: SuiExecutor<SuiSdkClient>,
+    sui_client: Arc<SuiClient<MultiEndpointSuiClient>>,
+    sui_executor: SuiExecutor<MultiEndpointSuiClient>,
     network_keys_receiver: Receiver<Arc<HashMap<ObjectID, DWalletNetworkEncryptionKeyData>>>,
     // todo(zeev): this needs a refactor.
     #[allow(dead_code)]
@@ -60,15 +89,20 @@ impl SuiConnectorService {
     pub async fn new(
         checkpoint_store: Arc<DWalletCheckpointStore>,
         system_checkpoint_store: Arc<SystemCheckpointStore>,
-        sui_client: Arc<SuiClient<SuiSdkClient>>,
+        sui_client: Arc<SuiClient<MultiEndpointSuiClient>>,
         sui_connector_config: SuiConnectorConfig,
         sui_connector_metrics: Arc<SuiConnectorMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
+        perpetual_tables: Arc<AuthorityPerpetualTables>,
+        network_keys: network_keys::Handle,
         is_validator: bool,
         next_epoch_committee_sender: Sender<Committee>,
         new_events_sender: tokio::sync::broadcast::Sender<Vec<SuiEvent>>,
         end_of_publish_sender: Sender<Option<u64>>,
         last_session_to_complete_in_current_epoch_sender: Sender<(EpochId, u64)>,
         uncompleted_events_sender: Sender<(Vec<DBSuiEvent>, EpochId)>,
+        handled_events_receiver: watch::Receiver<Option<EventID>>,
     ) -> anyhow::Result<(
         Arc<Self>,
         watch::Receiver<Arc<HashMap<ObjectID, DWalletNetworkEncryptionKeyData>>>,
@@ -85,18 +119,29 @@ impl SuiConnectorService {
         let sui_executor = SuiExecutor::new(
             sui_connector_config.ika_system_package_id,
             sui_connector_config.ika_dwallet_2pc_mpc_package_id,
+            sui_connector_config.notifier_gas_coin_count,
+            sui_connector_config.notifier_low_gas_balance_threshold,
             checkpoint_store.clone(),
             system_checkpoint_store.clone(),
             sui_notifier,
             sui_client.clone(),
             sui_connector_metrics.clone(),
+            session_latency,
+            drain_mode,
         );
 
         let sui_modules_to_watch = vec![SESSIONS_MANAGER_MODULE_NAME.to_owned()];
         let task_handles = SuiSyncer::new(
             sui_client.clone(),
             sui_modules_to_watch,
+            perpetual_tables,
+            sui_connector_config
+                .sui_ika_system_module_last_processed_event_id_override
+                .map(|cursor| (SYSTEM_MODULE_NAME.to_owned(), cursor))
+                .into_iter()
+                .collect(),
             sui_connector_metrics.clone(),
+            network_keys,
         )
         .run(
             Duration::from_secs(2),
@@ -107,6 +152,7 @@ impl SuiConnectorService {
             end_of_publish_sender,
             last_session_to_complete_in_current_epoch_sender,
             uncompleted_events_sender,
+            handled_events_receiver,
         )
         .await
         .map_err(|e| anyhow::anyhow!("Failed to start sui syncer: {e}"))?;
@@ -133,17 +179,32 @@ impl SuiConnectorService {
             .await
     }
 
+    /// The epoch the Sui executor runs, and the epoch switch steps it already ran in it.
+    pub fn epoch_switch_state(&self) -> (EpochId, EpochSwitchState) {
+        self.sui_executor.epoch_switch_state()
+    }
+
     async fn prepare_for_sui(
         sui_connector_config: SuiConnectorConfig,
-        sui_client: Arc<SuiClient<SuiSdkClient>>,
+        sui_client: Arc<SuiClient<MultiEndpointSuiClient>>,
         _sui_connector_metrics: Arc<SuiConnectorMetrics>,
     ) -> anyhow::Result<Option<SuiNotifier>> {
-        let Some(sui_key_path) = sui_connector_config.notifier_client_key_pair else {
-            return Ok(None);
+        let signer: Arc<dyn Signer> = match (
+            &sui_connector_config.notifier_client_key_pair,
+            &sui_connector_config.notifier_remote_signer,
+        ) {
+            (None, None) => return Ok(None),
+            (Some(_), Some(_)) => anyhow::bail!(
+                "only one of `notifier-client-key-pair` and `notifier-remote-signer` can be set"
+            ),
+            (Some(sui_key_path), None) => Arc::new(FileSigner::from_key_file(sui_key_path)),
+            (None, Some(remote_signer)) => {
+                Arc::new(RemoteSigner::connect(remote_signer).await.map_err(|e| {
+                    anyhow!("Error connecting to the notifier's remote signer: {e:?}")
+                })?)
+            }
         };
 
-        let sui_key = sui_key_path.keypair().copy();
-
         // If sui chain id is Mainnet or Testnet, we expect to see chain
         // identifier to match accordingly.
         let sui_identifier = sui_client
@@ -174,9 +235,9 @@ impl SuiConnectorService {
             sui_connector_config.sui_chain_identifier, sui_identifier
         );
 
-        let sui_address = SuiAddress::from(&sui_key.public());
+        let sui_address = signer.address();
         Ok(Some(SuiNotifier {
-            sui_key,
+            signer,
             sui_address,
         }))
     }
@@ -213,29 +274,77 @@ impl CheckpointMessageSuiNotify for SuiConnectorService {
     }
 }
 
+/// Dry-runs `pt` and signs it with a gas budget sized from the dry-run's gas cost.
+/// A Move abort in the dry-run is returned as [`IkaError::SuiTransactionMoveAbort`],
+/// so it doesn't spend gas on-chain.
 pub(crate) async fn build_sui_transaction<C: SuiClientInner>(
-    signer: SuiAddress,
     pt: ProgrammableTransaction,
     sui_client: &Arc<SuiClient<C>>,
     gas_payment: Vec<ObjectRef>,
-    sui_key: &SuiKeyPair,
-) -> Transaction {
+    signer: &dyn Signer,
+    metrics: &SuiConnectorMetrics,
+) -> IkaResult<Transaction> {
     let computation_price = sui_client.get_reference_gas_price_until_success().await;
 
+    let dry_run_data = TransactionData::new_programmable(
+        signer.address(),
+        gas_payment.clone(),
+        pt.clone(),
+        NOTIFIER_GAS_BUDGET,
+        computation_price,
+    );
+    let effects = sui_client.dry_run_transaction_block(dry_run_data).await?;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        let Some(move_abort) = MoveAbort::parse(error) else {
+            return Err(IkaError::SuiConnectorInternalError(format!(
+                "dry-run of the notifier transaction failed: {error}"
+            )));
+        };
+        let deterministic = move_abort.is_deterministic();
+        metrics
+            .notifier_move_aborts
+            .with_label_values(&[
+                move_abort.module.as_str(),
+                &move_abort.abort_code.to_string(),
+                &deterministic.to_string(),
+            ])
+            .inc();
+        if deterministic {
+            error!(
+                module=?move_abort.module,
+                function=?move_abort.function,
+                abort_code=?move_abort.abort_code,
+                error=%error,
+                "the notifier transaction aborts deterministically, it will not be submitted"
+            );
+        } else {
+            info!(
+                module=?move_abort.module,
+                function=?move_abort.function,
+                abort_code=?move_abort.abort_code,
+                "the notifier transaction aborts until the on-chain state changes"
+            );
+        }
+        return Err(move_abort.into_error(error.clone()));
+    }
+
+    let gas_used = effects.gas_cost_summary();
+    let gas_budget = ((gas_used.computation_cost + gas_used.storage_cost)
+        * (100 + NOTIFIER_GAS_BUDGET_MARGIN_PERCENT)
+        / 100)
+        .max(MIN_GAS_BUDGET_IN_GAS_UNITS * computation_price)
+        .min(NOTIFIER_GAS_BUDGET);
     let tx_data = TransactionData::new_programmable(
-        signer,
+        signer.address(),
         gas_payment,
         pt,
-        10_000_000_000,
+        gas_budget,
         computation_price,
     );
 
-    let signature = Signature::new_secure(
-        &IntentMessage::new(Intent::sui_transaction(), &tx_data),
-        sui_key,
-    );
-
-    Transaction::from_data(tx_data, vec![signature])
+    sign_transaction(signer, tx_data).await.map_err(|e| {
+        IkaError::SuiConnectorInternalError(format!("failed to sign the notifier transaction: {e}"))
+    })
 }
 
 pub async fn pick_highest_balance_coin(
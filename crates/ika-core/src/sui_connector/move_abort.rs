@@ -0,0 +1,212 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Classification of the Move aborts of the notifier's transactions.
+//!
+//! Most aborts of the notifier's transactions mean the on-chain state is not ready for them yet,
+//! e.g. the epoch's mid time has not been reached, or the previous checkpoint was not processed.
+//! Retrying such a transaction later will succeed.
+//! Any other abort, e.g. a version mismatch after a package upgrade,
+//! fails the same transaction every time, so retrying it only burns gas.
+
+use ika_types::dwallet_mpc_error::DwalletMPCError;
+use ika_types::error::IkaError;
+
+/// The aborts that depend on on-chain state that changes over time,
+/// as `(module, abort code)`, with the name of the Move error constant.
+const TRANSIENT_ABORTS: &[(&str, u64, &str)] = &[
+    ("coordinator_inner", 15, "ECannotAdvanceEpoch"),
+    ("coordinator_inner", 23, "EIncorrectEpochInCheckpoint"),
+    ("coordinator_inner", 24, "EWrongCheckpointSequenceNumber"),
+    ("coordinator_inner", 26, "EHaveNotReachedMidEpochTime"),
+    ("coordinator_inner", 27, "EHaveNotReachedEndEpochTime"),
+    (
+        "coordinator_inner",
+        28,
+        "EAlreadyInitiatedMidEpochReconfiguration",
+    ),
+    (
+        "coordinator_inner",
+        29,
+        "EHaveNotInitiatedMidEpochReconfiguration",
+    ),
+    (
+        "coordinator_inner",
+        30,
+        "ENotAllNetworkEncryptionKeysReconfigurationCompleted",
+    ),
+    (
+        "pricing_and_fee_manager",
+        2,
+        "EPricingCalculationVotesHasNotBeenStarted",
+    ),
+    (
+        "pricing_and_fee_manager",
+        3,
+        "EPricingCalculationVotesMustBeCompleted",
+    ),
+    (
+        "sessions_manager",
+        6,
+        "ENotAllCurrentEpochSessionsCompleted",
+    ),
+    ("system_inner", 0, "EHaveNotReachedEndEpochTime"),
+    ("system_inner", 2, "EIncorrectEpochInSystemCheckpoint"),
+    ("system_inner", 3, "EWrongSystemCheckpointSequenceNumber"),
+    ("system_inner", 7, "EHaveNotReachedMidEpochTime"),
+    ("validator_set", 11, "EProcessMidEpochOnlyAfterAdvanceEpoch"),
+    ("validator_set", 12, "EAdvanceEpochOnlyAfterProcessMidEpoch"),
+];
+
+/// A Move abort parsed from the execution error of a Sui transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MoveAbort {
+    pub(crate) module: String,
+    pub(crate) function: Option<String>,
+    pub(crate) abort_code: u64,
+}
+
+impl MoveAbort {
+    /// Parses the `Debug` representation of `ExecutionFailureStatus::MoveAbort`
+    /// that Sui reports as the error of a failed transaction, e.g.
+    /// `MoveAbort(MoveLocation { module: ModuleId { address: 0x..., name: Identifier("system_inner") },
+    /// function: 3, instruction: 12, function_name: Some("advance_epoch") }, 0) in command 2`.
+    /// Returns `None` for any other failure.
+    pub(crate) fn parse(error: &str) -> Option<Self> {
+        let location = error.trim_start().strip_prefix("MoveAbort(")?;
+        let module = quoted_after(location, "name: Identifier(\"")?;
+        let function = quoted_after(location, "function_name: Some(\"");
+        let (_, abort_code) = location.rsplit_once("}, ")?;
+        let abort_code = abort_code
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        Some(Self {
+            module: module.to_string(),
+            function: function.map(str::to_string),
+            abort_code,
+        })
+    }
+
+    /// Whether the same transaction will abort again no matter when it is retried.
+    pub(crate) fn is_deterministic(&self) -> bool {
+        !TRANSIENT_ABORTS
+            .iter()
+            .any(|(module, abort_code, _)| *module == self.module && *abort_code == self.abort_code)
+    }
+
+    pub(crate) fn into_error(self, error: String) -> IkaError {
+        let deterministic = self.is_deterministic();
+        IkaError::SuiTransactionMoveAbort {
+            module: self.module,
+            function: self.function,
+            abort_code: self.abort_code,
+            deterministic,
+            error,
+        }
+    }
+}
+
+fn quoted_after<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let (_, rest) = text.split_once(prefix)?;
+    rest.split_once('"').map(|(quoted, _)| quoted)
+}
+
+/// The errors of the notifier's transactions, which may be a deterministic Move abort
+/// that must not be retried.
+pub(crate) trait NotifierError {
+    fn is_deterministic_move_abort(&self) -> bool;
+}
+
+impl NotifierError for IkaError {
+    fn is_deterministic_move_abort(&self) -> bool {
+        IkaError::is_deterministic_move_abort(self)
+    }
+}
+
+impl NotifierError for DwalletMPCError {
+    fn is_deterministic_move_abort(&self) -> bool {
+        matches!(self, DwalletMPCError::IkaError(error) if error.is_deterministic_move_abort())
+    }
+}
+
+impl NotifierError for anyhow::Error {
+    fn is_deterministic_move_abort(&self) -> bool {
+        if let Some(error) = self.downcast_ref::<DwalletMPCError>() {
+            return NotifierError::is_deterministic_move_abort(error);
+        }
+        self.downcast_ref::<IkaError>()
+            .is_some_and(IkaError::is_deterministic_move_abort)
+    }
+}
+
+/// Passed to `retry_with_max_elapsed_time!` to stop retrying deterministic Move aborts.
+pub(crate) fn is_deterministic_move_abort(error: &impl NotifierError) -> bool {
+    error.is_deterministic_move_abort()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WRONG_SEQUENCE_NUMBER: &str = "MoveAbort(MoveLocation { module: ModuleId { address: 0000000000000000000000000000000000000000000000000000000000000abc, name: Identifier(\"coordinator_inner\") }, function: 87, instruction: 41, function_name: Some(\"process_checkpoint_message\") }, 24) in command 0";
+
+    #[test]
+    fn parses_move_abort() {
+        assert_eq!(
+            MoveAbort::parse(WRONG_SEQUENCE_NUMBER),
+            Some(MoveAbort {
+                module: "coordinator_inner".to_string(),
+                function: Some("process_checkpoint_message".to_string()),
+                abort_code: 24,
+            })
+        );
+        assert_eq!(
+            MoveAbort::parse(
+                "MoveAbort(MoveLocation { module: ModuleId { address: 0x1, name: Identifier(\"system\") }, function: 2, instruction: 5, function_name: None }, 0)"
+            ),
+            Some(MoveAbort {
+                module: "system".to_string(),
+                function: None,
+                abort_code: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_other_failures() {
+        assert_eq!(MoveAbort::parse("InsufficientGas"), None);
+        assert_eq!(
+            MoveAbort::parse(
+                "CommandArgumentError { arg_idx: 1, kind: TypeMismatch } in command 0"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn classifies_aborts() {
+        let transient = MoveAbort::parse(WRONG_SEQUENCE_NUMBER).unwrap();
+        assert!(!transient.is_deterministic());
+
+        // `EWrongInnerVersion`, after the package was upgraded.
+        let wrong_version = MoveAbort {
+            module: "coordinator".to_string(),
+            function: Some("inner_mut".to_string()),
+            abort_code: 0,
+        };
+        assert!(wrong_version.is_deterministic());
+        let error = wrong_version.into_error(String::new());
+        assert!(is_deterministic_move_abort(&error));
+        assert!(is_deterministic_move_abort(&DwalletMPCError::from(
+            error.clone()
+        )));
+        assert!(is_deterministic_move_abort(&anyhow::Error::from(
+            DwalletMPCError::from(error.clone())
+        )));
+        assert!(is_deterministic_move_abort(&IkaError::from(
+            DwalletMPCError::from(error)
+        )));
+    }
+}
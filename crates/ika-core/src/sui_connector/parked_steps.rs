@@ -0,0 +1,162 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Parks the notifier's steps that abort deterministically.
+//!
+//! A step that aborts deterministically, e.g. a checkpoint submission after a package upgrade,
+//! aborts the same way until the on-chain state it reads changes.
+//! It is parked until that state changes, and in case the change is not one the notifier reads,
+//! it is retried with an exponential back-off.
+
+use ika_types::committee::EpochId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tokio::time::{Duration, Instant};
+
+/// How long a step is parked after it first aborts with the same inputs.
+const MIN_PARK_DURATION: Duration = Duration::from_secs(10);
+
+/// The longest a step is parked before it is retried with the same inputs.
+const MAX_PARK_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// The on-chain state the notifier's steps read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct StepInputs {
+    pub(crate) epoch: EpochId,
+    pub(crate) last_processed_system_checkpoint: u64,
+    pub(crate) last_processed_dwallet_checkpoint: u64,
+    /// The version of the `System` object, bumped when it is migrated to an upgraded package.
+    /// `None` until the step is parked, or if it could not be read.
+    pub(crate) system_version: Option<u64>,
+    /// The version of the `DWalletCoordinator` object, like `system_version`.
+    pub(crate) coordinator_version: Option<u64>,
+}
+
+impl StepInputs {
+    pub(crate) fn new(
+        epoch: EpochId,
+        last_processed_system_checkpoint: u64,
+        last_processed_dwallet_checkpoint: u64,
+    ) -> Self {
+        Self {
+            epoch,
+            last_processed_system_checkpoint,
+            last_processed_dwallet_checkpoint,
+            system_version: None,
+            coordinator_version: None,
+        }
+    }
+}
+
+struct ParkedStep {
+    inputs: StepInputs,
+    aborts: u32,
+    retry_at: Instant,
+}
+
+/// The steps that aborted deterministically, by step.
+#[derive(Default)]
+pub(crate) struct ParkedSteps {
+    steps: Mutex<HashMap<&'static str, ParkedStep>>,
+}
+
+impl ParkedSteps {
+    pub(crate) fn contains(&self, step: &str) -> bool {
+        self.steps.lock().contains_key(step)
+    }
+
+    /// Whether `step` should not run with `inputs`: it aborted with the same inputs,
+    /// and its back-off has not elapsed yet.
+    pub(crate) fn is_parked(&self, step: &str, inputs: &StepInputs) -> bool {
+        self.steps
+            .lock()
+            .get(step)
+            .is_some_and(|parked| parked.inputs == *inputs && Instant::now() < parked.retry_at)
+    }
+
+    /// Parks `step` after it aborted with `inputs`.
+    /// Returns how many times in a row it aborted with these inputs,
+    /// and how long it is parked for.
+    pub(crate) fn park(&self, step: &'static str, inputs: StepInputs) -> (u32, Duration) {
+        let mut steps = self.steps.lock();
+        let aborts = match steps.get(step) {
+            Some(parked) if parked.inputs == inputs => parked.aborts + 1,
+            _ => 1,
+        };
+        let park_duration = MIN_PARK_DURATION
+            .saturating_mul(2u32.saturating_pow(aborts - 1))
+            .min(MAX_PARK_DURATION);
+        steps.insert(
+            step,
+            ParkedStep {
+                inputs,
+                aborts,
+                retry_at: Instant::now() + park_duration,
+            },
+        );
+        (aborts, park_duration)
+    }
+
+    /// Unparks `step` once it succeeded. Returns whether it was parked.
+    pub(crate) fn unpark(&self, step: &str) -> bool {
+        self.steps.lock().remove(step).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: &str = "dwallet_checkpoint";
+
+    fn inputs(last_processed_dwallet_checkpoint: u64) -> StepInputs {
+        StepInputs {
+            system_version: Some(1),
+            coordinator_version: Some(1),
+            ..StepInputs::new(1, 0, last_processed_dwallet_checkpoint)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn aborted_steps_back_off_exponentially() {
+        let parked_steps = ParkedSteps::default();
+        assert!(!parked_steps.is_parked(STEP, &inputs(0)));
+
+        assert_eq!(parked_steps.park(STEP, inputs(0)), (1, MIN_PARK_DURATION));
+        assert!(parked_steps.is_parked(STEP, &inputs(0)));
+        tokio::time::advance(MIN_PARK_DURATION).await;
+        assert!(!parked_steps.is_parked(STEP, &inputs(0)));
+
+        assert_eq!(
+            parked_steps.park(STEP, inputs(0)),
+            (2, MIN_PARK_DURATION * 2)
+        );
+        tokio::time::advance(MIN_PARK_DURATION).await;
+        assert!(parked_steps.is_parked(STEP, &inputs(0)));
+
+        for _ in 0..10 {
+            parked_steps.park(STEP, inputs(0));
+        }
+        assert_eq!(parked_steps.park(STEP, inputs(0)).1, MAX_PARK_DURATION);
+
+        assert!(parked_steps.unpark(STEP));
+        assert!(!parked_steps.is_parked(STEP, &inputs(0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn aborted_steps_are_unparked_once_their_inputs_change() {
+        let parked_steps = ParkedSteps::default();
+        parked_steps.park(STEP, inputs(0));
+        parked_steps.park(STEP, inputs(0));
+
+        let upgraded = StepInputs {
+            coordinator_version: Some(2),
+            ..inputs(0)
+        };
+        assert!(!parked_steps.is_parked(STEP, &upgraded));
+        // The back-off starts over with the new inputs.
+        assert_eq!(parked_steps.park(STEP, upgraded), (1, MIN_PARK_DURATION));
+        assert!(parked_steps.is_parked(STEP, &upgraded));
+        assert!(!parked_steps.is_parked("system_checkpoint", &inputs(1)));
+    }
+}
@@ -5,8 +5,15 @@
 //! on Sui blockchain for `ika_system` package.
 
 use crate::dwallet_checkpoints::DWalletCheckpointStore;
-use crate::sui_connector::SuiNotifier;
+use crate::dwallet_mpc::drain::DrainMode;
+use crate::dwallet_mpc::session_latency::SessionLatencyTracker;
+use crate::sui_connector::gas_coin_pool::GasCoinPool;
 use crate::sui_connector::metrics::SuiConnectorMetrics;
+use crate::sui_connector::move_abort::{NotifierError, is_deterministic_move_abort};
+use crate::sui_connector::parked_steps::{ParkedSteps, StepInputs};
+use crate::sui_connector::{
+    MAX_CHECKPOINT_BATCH_MESSAGE_BYTES, MAX_CHECKPOINTS_PER_SUI_TRANSACTION, SuiNotifier,
+};
 use crate::system_checkpoints::SystemCheckpointStore;
 use fastcrypto::traits::ToFromBytes;
 use ika_config::node::RunWithRange;
@@ -46,19 +53,25 @@ use sui_macros::fail_point_async;
 use sui_types::MOVE_STDLIB_PACKAGE_ID;
 use sui_types::base_types::{ObjectID, TransactionDigest};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_types::transaction::{Argument, CallArg, ObjectArg, Transaction};
+use sui_types::transaction::{Argument, CallArg, ProgrammableTransaction, Transaction};
 use tokio::sync::watch;
-use tokio::time::{self, Duration};
+use tokio::time::{self, Duration, Instant};
 use tracing::{error, info, warn};
+use typed_store::TypedStoreError;
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum StopReason {
     EpochComplete(Box<SystemInner>, EpochStartSystem),
     RunWithRangeCondition,
+    /// The node drained its MPC work, and all the certified dWallet checkpoints were executed on Sui.
+    Drained,
 }
 
 const ONE_HOUR_IN_SECONDS: u64 = 60 * 60;
 
+const DWALLET_CHECKPOINT_LABEL: &str = "dwallet";
+const SYSTEM_CHECKPOINT_LABEL: &str = "system";
+
 pub struct SuiExecutor<C> {
     ika_system_package_id: ObjectID,
     ika_dwallet_2pc_mpc_package_id: ObjectID,
@@ -67,14 +80,51 @@ pub struct SuiExecutor<C> {
     sui_notifier: Option<SuiNotifier>,
     sui_client: Arc<SuiClient<C>>,
     metrics: Arc<SuiConnectorMetrics>,
-    notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+    gas_coin_pool: Option<GasCoinPool<C>>,
+    /// Transactions of the same stream are submitted one after the other, each waiting for
+    /// the previous one to be executed, while different streams are submitted in parallel.
+    /// The epoch switch calls and the system checkpoints all mutate the System object,
+    /// so they share a stream.
+    system_tx_lock: NotifierTxLock,
+    dwallet_checkpoint_tx_lock: NotifierTxLock,
+    /// The epoch switch steps this node ran in the epoch it currently runs.
+    epoch_switch_state: watch::Sender<(EpochId, EpochSwitchState)>,
+    /// The steps that aborted deterministically, which are not submitted again
+    /// until the on-chain state they read changes.
+    parked_steps: ParkedSteps,
+    session_latency: SessionLatencyTracker,
+    drain_mode: DrainMode,
 }
 
-struct EpochSwitchState {
-    ran_mid_epoch: bool,
-    ran_lock_last_session: bool,
-    ran_request_advance_epoch: bool,
-    calculated_protocol_pricing: bool,
+/// Holds the digest of the last transaction submitted by a stream of notifier transactions.
+type NotifierTxLock = Arc<tokio::sync::Mutex<Option<TransactionDigest>>>;
+
+/// The epoch switch steps this node already ran in the current epoch.
+/// A step is marked once it succeeded on Sui; a step that aborts is retried on a later run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EpochSwitchState {
+    /// `process_mid_epoch()` succeeded.
+    pub ran_mid_epoch: bool,
+    /// `lock_last_active_session_sequence_number()` succeeded.
+    pub ran_lock_last_session: bool,
+    /// `request_advance_epoch()` succeeded.
+    pub ran_request_advance_epoch: bool,
+    /// The network encryption keys' mid-epoch reconfiguration was requested
+    /// and the protocols' pricing was calculated.
+    pub calculated_protocol_pricing: bool,
+}
+
+impl EpochSwitchState {
+    /// The steps of the epoch switch, in the order they happen within an epoch,
+    /// and whether each of them ran.
+    pub fn steps(&self) -> [(&'static str, bool); 4] {
+        [
+            ("mid_epoch", self.ran_mid_epoch),
+            ("protocol_pricing", self.calculated_protocol_pricing),
+            ("lock_last_session", self.ran_lock_last_session),
+            ("request_advance_epoch", self.ran_request_advance_epoch),
+        ]
+    }
 }
 
 impl<C> SuiExecutor<C>
@@ -84,12 +134,25 @@ where
     pub fn new(
         ika_system_package_id: ObjectID,
         ika_dwallet_2pc_mpc_package_id: ObjectID,
+        notifier_gas_coin_count: usize,
+        notifier_low_gas_balance_threshold: Option<u64>,
         dwallet_checkpoint_store: Arc<DWalletCheckpointStore>,
         system_checkpoint_store: Arc<SystemCheckpointStore>,
         sui_notifier: Option<SuiNotifier>,
         sui_client: Arc<SuiClient<C>>,
         metrics: Arc<SuiConnectorMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
     ) -> Self {
+        let gas_coin_pool = sui_notifier.as_ref().map(|sui_notifier| {
+            GasCoinPool::new(
+                sui_notifier.sui_address,
+                notifier_gas_coin_count,
+                notifier_low_gas_balance_threshold,
+                sui_client.clone(),
+                metrics.clone(),
+            )
+        });
         Self {
             ika_system_package_id,
             ika_dwallet_2pc_mpc_package_id,
@@ -98,8 +161,31 @@ where
             sui_notifier,
             sui_client,
             metrics,
-            notifier_tx_lock: Arc::new(tokio::sync::Mutex::new(None)),
+            gas_coin_pool,
+            system_tx_lock: Arc::new(tokio::sync::Mutex::new(None)),
+            dwallet_checkpoint_tx_lock: Arc::new(tokio::sync::Mutex::new(None)),
+            epoch_switch_state: watch::Sender::new(Default::default()),
+            parked_steps: ParkedSteps::default(),
+            session_latency,
+            drain_mode,
+        }
+    }
+
+    /// The epoch this executor runs, and the epoch switch steps it already ran in it.
+    pub fn epoch_switch_state(&self) -> (EpochId, EpochSwitchState) {
+        *self.epoch_switch_state.borrow()
+    }
+
+    fn publish_epoch_switch_state(&self, epoch: EpochId, epoch_switch_state: EpochSwitchState) {
+        self.metrics.epoch_switch_epoch.set(epoch as i64);
+        for (step, done) in epoch_switch_state.steps() {
+            self.metrics
+                .epoch_switch_step_done
+                .with_label_values(&[step])
+                .set(done as i64);
         }
+        self.epoch_switch_state
+            .send_replace((epoch, epoch_switch_state));
     }
 
     /// Checks whether `process_mid_epoch`, `lock_last_active_session_sequence_number`, or
@@ -113,9 +199,11 @@ where
     async fn run_epoch_switch(
         &self,
         sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
         ika_system_state_inner: &SystemInner,
         network_encryption_key_ids: Vec<ObjectID>,
         epoch_switch_state: &mut EpochSwitchState,
+        inputs: StepInputs,
     ) {
         let Ok(clock) = self.sui_client.get_clock().await else {
             error!("failed to get clock when running epoch switch");
@@ -130,6 +218,7 @@ where
         if clock.timestamp_ms > mid_epoch_time
             && next_epoch_committee_is_empty
             && !epoch_switch_state.ran_mid_epoch
+            && !self.step_parked("mid_epoch", inputs).await
         {
             info!("Calling `process_mid_epoch()`");
             // After mid-epoch reconfiguration, the next epoch committee is set, and
@@ -139,19 +228,25 @@ where
                     self.ika_system_package_id,
                     self.ika_dwallet_2pc_mpc_package_id,
                     sui_notifier,
+                    gas_coin_pool,
                     &self.sui_client,
-                    self.notifier_tx_lock.clone(),
+                    &self.metrics,
+                    self.system_tx_lock.clone(),
                 ),
-                Duration::from_secs(ONE_HOUR_IN_SECONDS)
+                Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                is_deterministic_move_abort
             );
-            if response.is_err() {
-                panic!(
-                    "failed to submit mid-epoch for over an hour: {:?}",
-                    response.err()
-                );
+            match response {
+                Ok(_) => {
+                    info!("Successfully processed mid-epoch");
+                    epoch_switch_state.ran_mid_epoch = true;
+                    self.step_succeeded("mid_epoch");
+                }
+                Err(err) if err.is_deterministic_move_abort() => {
+                    self.step_aborted("mid_epoch", inputs, &err).await;
+                }
+                Err(err) => panic!("failed to submit mid-epoch for over an hour: {err:?}"),
             }
-            info!("Successfully processed mid-epoch");
-            epoch_switch_state.ran_mid_epoch = true;
         }
         let Ok(DWalletCoordinatorInner::V1(coordinator)) =
             self.sui_client.get_dwallet_coordinator_inner().await
@@ -169,6 +264,7 @@ where
             // network_encryption_key_ids holds only keys that finished dkg
             && coordinator.dwallet_network_encryption_keys.size == network_encryption_key_ids.len() as u64
             && !epoch_switch_state.calculated_protocol_pricing
+            && !self.step_parked("protocol_pricing", inputs).await
         {
             info!(
                 "Running network encryption key mid-epoch reconfiguration and Calculating protocol pricing"
@@ -179,18 +275,26 @@ where
                     self.ika_dwallet_2pc_mpc_package_id,
                     network_encryption_key_ids.clone(),
                     sui_notifier,
-                    self.notifier_tx_lock.clone(),
+                    gas_coin_pool,
+                    &self.metrics,
+                    self.system_tx_lock.clone(),
                 ),
-                Duration::from_secs(ONE_HOUR_IN_SECONDS)
+                Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                is_deterministic_move_abort
             );
-            if result.is_err() {
-                panic!(
-                    "failed to calculate protocols' pricing for over an hour: {:?}",
-                    result.err()
-                );
+            match result {
+                Ok(_) => {
+                    info!("Successfully calculated protocols pricing");
+                    epoch_switch_state.calculated_protocol_pricing = true;
+                    self.step_succeeded("protocol_pricing");
+                }
+                Err(err) if err.is_deterministic_move_abort() => {
+                    self.step_aborted("protocol_pricing", inputs, &err).await;
+                }
+                Err(err) => {
+                    panic!("failed to calculate protocols' pricing for over an hour: {err:?}")
+                }
             }
-            info!("Successfully calculated protocols pricing");
-            epoch_switch_state.calculated_protocol_pricing = true;
         }
 
         // The Epoch was finished.
@@ -202,6 +306,7 @@ where
         if clock.timestamp_ms > epoch_finish_time
             && epoch_not_locked
             && !epoch_switch_state.ran_lock_last_session
+            && !self.step_parked("lock_last_session", inputs).await
         {
             info!("Calling `lock_last_active_session_sequence_number()`");
             let response = retry_with_max_elapsed_time!(
@@ -209,23 +314,30 @@ where
                     self.ika_system_package_id,
                     self.ika_dwallet_2pc_mpc_package_id,
                     sui_notifier,
+                    gas_coin_pool,
                     &self.sui_client,
-                    self.notifier_tx_lock.clone(),
+                    &self.metrics,
+                    self.system_tx_lock.clone(),
                 ),
-                Duration::from_secs(ONE_HOUR_IN_SECONDS)
+                Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                is_deterministic_move_abort
             );
-            if response.is_err() {
-                panic!(
-                    "failed to submit lock-last session for over an hour: {:?}",
-                    response.err()
-                );
+            match response {
+                Ok(_) => {
+                    info!("Successfully locked last session in current epoch");
+                    epoch_switch_state.ran_lock_last_session = true;
+                    self.step_succeeded("lock_last_session");
+                }
+                Err(err) if err.is_deterministic_move_abort() => {
+                    self.step_aborted("lock_last_session", inputs, &err).await;
+                }
+                Err(err) => panic!("failed to submit lock-last session for over an hour: {err:?}"),
             }
-            epoch_switch_state.ran_lock_last_session = true;
-            info!("Successfully locked last session in current epoch");
         }
         if coordinator.received_end_of_publish
             && system_inner_v1.received_end_of_publish
             && !epoch_switch_state.ran_request_advance_epoch
+            && !self.step_parked("request_advance_epoch", inputs).await
         {
             info!("Calling `process_request_advance_epoch()`");
             let response = retry_with_max_elapsed_time!(
@@ -233,19 +345,99 @@ where
                     self.ika_system_package_id,
                     self.ika_dwallet_2pc_mpc_package_id,
                     sui_notifier,
+                    gas_coin_pool,
                     &self.sui_client.clone(),
-                    self.notifier_tx_lock.clone(),
+                    &self.metrics,
+                    self.system_tx_lock.clone(),
                 ),
-                Duration::from_secs(ONE_HOUR_IN_SECONDS)
+                Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                is_deterministic_move_abort
             );
-            if response.is_err() {
-                panic!(
-                    "failed to submit request advance epoch for over an hour: {:?}",
-                    response.err()
-                );
+            match response {
+                Ok(_) => {
+                    info!("Successfully requested advance epoch");
+                    epoch_switch_state.ran_request_advance_epoch = true;
+                    self.step_succeeded("request_advance_epoch");
+                }
+                Err(err) if err.is_deterministic_move_abort() => {
+                    self.step_aborted("request_advance_epoch", inputs, &err)
+                        .await;
+                }
+                Err(err) => {
+                    panic!("failed to submit request advance epoch for over an hour: {err:?}")
+                }
             }
-            info!("Successfully requested advance epoch");
-            epoch_switch_state.ran_request_advance_epoch = true;
+        }
+    }
+
+    /// Whether `step` aborted deterministically with the same `inputs`, and is still parked.
+    async fn step_parked(&self, step: &'static str, inputs: StepInputs) -> bool {
+        if !self.parked_steps.contains(step) {
+            return false;
+        }
+        let inputs = self.with_package_versions(inputs).await;
+        let parked = self.parked_steps.is_parked(step, &inputs);
+        if !parked {
+            info!(step, ?inputs, "retrying a parked step");
+        }
+        parked
+    }
+
+    /// `inputs` with the versions of the `System` and `DWalletCoordinator` objects,
+    /// which only change when they are migrated to an upgraded package.
+    async fn with_package_versions(&self, inputs: StepInputs) -> StepInputs {
+        StepInputs {
+            system_version: self.sui_client.get_system_version().await.ok(),
+            coordinator_version: self.sui_client.get_dwallet_coordinator_version().await.ok(),
+            ..inputs
+        }
+    }
+
+    /// Parks a step that aborted deterministically, so it is not submitted again until the
+    /// on-chain state it reads changes or its back-off elapses.
+    /// It is reported once for each state it aborts in,
+    /// so that a stream that keeps aborting is alerted on rather than stalling silently.
+    async fn step_aborted(
+        &self,
+        step: &'static str,
+        inputs: StepInputs,
+        error: &impl std::fmt::Debug,
+    ) {
+        self.metrics
+            .notifier_aborted_steps
+            .with_label_values(&[step])
+            .inc();
+        self.metrics
+            .notifier_parked_steps
+            .with_label_values(&[step])
+            .set(1);
+        let inputs = self.with_package_versions(inputs).await;
+        let (aborts, park_duration) = self.parked_steps.park(step, inputs);
+        if aborts == 1 {
+            error!(
+                step,
+                ?inputs,
+                ?error,
+                ?park_duration,
+                "step aborts deterministically, parking it until the on-chain state it reads changes"
+            );
+        } else {
+            warn!(
+                step,
+                aborts,
+                ?park_duration,
+                "parked step aborted again, the on-chain state it reads did not change"
+            );
+        }
+    }
+
+    fn step_succeeded(&self, step: &str) {
+        if self.parked_steps.unpark(step) {
+            info!(step, "parked step succeeded");
+            self.metrics
+                .notifier_parked_steps
+                .with_label_values(&[step])
+                .set(0);
         }
     }
 
@@ -253,7 +445,7 @@ where
         &self,
         epoch: EpochId,
         run_with_range: Option<RunWithRange>,
-        mut network_keys_receiver: watch::Receiver<
+        network_keys_receiver: watch::Receiver<
             Arc<HashMap<ObjectID, DWalletNetworkEncryptionKeyData>>,
         >,
     ) -> StopReason {
@@ -269,17 +461,37 @@ where
             return StopReason::RunWithRangeCondition;
         };
 
-        let mut interval = time::interval(Duration::from_millis(120));
+        // The dwallet checkpoints are submitted by their own loop, so a slow stream of
+        // transactions that mutate the System object doesn't hold them back, nor the other way
+        // around. The dwallet checkpoint loop finishes its in-flight submission before stopping.
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let (stop_reason, ()) = tokio::join!(
+            async {
+                let stop_reason = self.run_system_stream(epoch, network_keys_receiver).await;
+                stop_sender.send_replace(true);
+                stop_reason
+            },
+            self.run_dwallet_checkpoint_stream(stop_receiver),
+        );
+        stop_reason
+    }
 
-        let mut last_submitted_dwallet_checkpoint: Option<u64> = None;
+    /// Watches the epoch on Sui until it ends or the node drains, and submits the transactions
+    /// that mutate the System object: the epoch switch calls and the system checkpoints.
+    /// They are submitted one after the other, the epoch switch calls first,
+    /// so each of them sees the System object as the previous one left it.
+    async fn run_system_stream(
+        &self,
+        epoch: EpochId,
+        mut network_keys_receiver: watch::Receiver<
+            Arc<HashMap<ObjectID, DWalletNetworkEncryptionKeyData>>,
+        >,
+    ) -> StopReason {
+        let mut interval = time::interval(Duration::from_millis(120));
         let mut last_submitted_system_checkpoint: Option<u64> = None;
 
-        let mut epoch_switch_state = EpochSwitchState {
-            ran_mid_epoch: false,
-            ran_lock_last_session: false,
-            ran_request_advance_epoch: false,
-            calculated_protocol_pricing: false,
-        };
+        let mut epoch_switch_state = EpochSwitchState::default();
+        self.publish_epoch_switch_state(epoch, epoch_switch_state);
 
         loop {
             interval.tick().await;
@@ -306,171 +518,385 @@ where
                 .await;
             let last_processed_dwallet_checkpoint_sequence_number: u64 =
                 dwallet_coordinator_inner.last_processed_checkpoint_sequence_number;
-            let next_dwallet_checkpoint_sequence_number =
-                last_processed_dwallet_checkpoint_sequence_number + 1;
+            self.session_latency.record_checkpoints_executed_on_sui(
+                last_processed_dwallet_checkpoint_sequence_number,
+            );
+            if self.drain_mode.is_ready_to_stop()
+                && self.certified_dwallet_checkpoints_executed(
+                    last_processed_dwallet_checkpoint_sequence_number,
+                )
+            {
+                info!(epoch, "The node drained its MPC work, stopping");
+                return StopReason::Drained;
+            }
 
             let last_processed_system_checkpoint_sequence_number: u64 =
                 ika_system_state_inner.last_processed_checkpoint_sequence_number();
-            let next_system_checkpoint_sequence_number =
-                last_processed_system_checkpoint_sequence_number + 1;
-
-            if let Some(sui_notifier) = self.sui_notifier.as_ref() {
-                let network_encryption_key_ids = {
-                    network_keys_receiver
-                        .borrow_and_update()
-                        .clone()
-                        .keys()
-                        .cloned()
-                        .collect_vec()
-                };
-                self.run_epoch_switch(
-                    sui_notifier,
-                    &ika_system_state_inner,
-                    network_encryption_key_ids,
-                    &mut epoch_switch_state,
-                )
-                .await;
-                if Some(next_dwallet_checkpoint_sequence_number) > last_submitted_dwallet_checkpoint
-                {
-                    match self
-                        .dwallet_checkpoint_store
-                        .get_dwallet_checkpoint_by_sequence_number(
-                            next_dwallet_checkpoint_sequence_number,
-                        ) {
-                        Ok(Some(dwallet_checkpoint_message)) => {
-                            info!(
-                                ?next_dwallet_checkpoint_sequence_number,
-                                "Processing checkpoint sequence number"
-                            );
-                            self.metrics.dwallet_checkpoint_write_requests_total.inc();
-                            self.metrics
-                                .dwallet_checkpoint_sequence
-                                .set(next_dwallet_checkpoint_sequence_number as i64);
-
-                            let active_members: BlsCommittee = ika_system_state_inner
-                                .validator_set()
-                                .clone()
-                                .active_committee;
-                            let auth_sig = dwallet_checkpoint_message.auth_sig();
-                            let signature = auth_sig.signature.as_bytes().to_vec();
-                            let signers_bitmap = Self::calculate_signers_bitmap(
-                                &auth_sig.signers_map,
-                                &active_members,
-                            );
-                            let signers_len = auth_sig.signers_map.len();
-                            let message = bcs::to_bytes::<DWalletCheckpointMessage>(
-                                &dwallet_checkpoint_message.into_message(),
-                            )
-                            .expect("Serializing checkpoint message cannot fail");
-
-                            info!(
-                                signers_len=?signers_len,
-                                ?signers_bitmap,
-                                "Processing checkpoint with signers"
-                            );
 
-                            let response = retry_with_max_elapsed_time!(
-                                Self::handle_dwallet_checkpoint_execution_task(
-                                    self.ika_dwallet_2pc_mpc_package_id,
-                                    signature.clone(),
-                                    signers_bitmap.clone(),
-                                    message.clone(),
-                                    sui_notifier,
-                                    &self.sui_client.clone(),
-                                    &self.metrics.clone(),
-                                    self.notifier_tx_lock.clone().clone(),
-                                ),
-                                Duration::from_secs(ONE_HOUR_IN_SECONDS)
-                            );
-                            if response.is_err() {
-                                panic!(
-                                    "failed to submit dwallet checkpoint for over an hour, err: {:?}",
-                                    response.err()
+            let (Some(sui_notifier), Some(gas_coin_pool)) =
+                (self.sui_notifier.as_ref(), self.gas_coin_pool.as_ref())
+            else {
+                continue;
+            };
+            let network_encryption_key_ids = {
+                network_keys_receiver
+                    .borrow_and_update()
+                    .clone()
+                    .keys()
+                    .cloned()
+                    .collect_vec()
+            };
+            let inputs = StepInputs::new(
+                epoch_on_sui,
+                last_processed_system_checkpoint_sequence_number,
+                last_processed_dwallet_checkpoint_sequence_number,
+            );
+            self.run_epoch_switch(
+                sui_notifier,
+                gas_coin_pool,
+                &ika_system_state_inner,
+                network_encryption_key_ids,
+                &mut epoch_switch_state,
+                inputs,
+            )
+            .await;
+            self.publish_epoch_switch_state(epoch, epoch_switch_state);
+
+            let active_members: BlsCommittee = ika_system_state_inner
+                .validator_set()
+                .clone()
+                .active_committee;
+            self.submit_system_checkpoints(
+                sui_notifier,
+                gas_coin_pool,
+                inputs,
+                &active_members,
+                &mut last_submitted_system_checkpoint,
+            )
+            .await;
+        }
+    }
+
+    /// Submits the certified dwallet checkpoints to Sui until `stop_receiver` is set.
+    async fn run_dwallet_checkpoint_stream(&self, mut stop_receiver: watch::Receiver<bool>) {
+        let (Some(sui_notifier), Some(gas_coin_pool)) =
+            (self.sui_notifier.as_ref(), self.gas_coin_pool.as_ref())
+        else {
+            return;
+        };
+        let mut interval = time::interval(Duration::from_millis(120));
+        let mut last_submitted_dwallet_checkpoint: Option<u64> = None;
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = stop_receiver.wait_for(|stopped| *stopped) => return,
+            }
+            let ika_system_state_inner = self.sui_client.must_get_system_inner_object().await;
+            let last_processed_dwallet_checkpoint_sequence_number = self
+                .sui_client
+                .must_get_dwallet_coordinator_inner_v1()
+                .await
+                .last_processed_checkpoint_sequence_number;
+            let inputs = StepInputs::new(
+                ika_system_state_inner.epoch(),
+                ika_system_state_inner.last_processed_checkpoint_sequence_number(),
+                last_processed_dwallet_checkpoint_sequence_number,
+            );
+            let active_members: BlsCommittee = ika_system_state_inner
+                .validator_set()
+                .clone()
+                .active_committee;
+            self.submit_dwallet_checkpoints(
+                sui_notifier,
+                gas_coin_pool,
+                inputs,
+                &active_members,
+                &mut last_submitted_dwallet_checkpoint,
+            )
+            .await;
+        }
+    }
+
+    /// Whether all the dWallet checkpoints certified by this node were executed on Sui,
+    /// so the outputs of its MPC sessions reached the chain.
+    fn certified_dwallet_checkpoints_executed(&self, last_processed_sequence_number: u64) -> bool {
+        match self
+            .dwallet_checkpoint_store
+            .get_latest_certified_checkpoint()
+        {
+            Ok(latest) => latest.is_none_or(|checkpoint| {
+                checkpoint.sequence_number <= last_processed_sequence_number
+            }),
+            Err(err) => {
+                warn!(error=?err, "failed to read the latest certified dwallet checkpoint");
+                false
+            }
+        }
+    }
+
+    /// Submits the next batch of certified dwallet checkpoints to Sui,
+    /// unless it was already submitted and is waiting to be processed, or it is parked.
+    async fn submit_dwallet_checkpoints(
+        &self,
+        sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
+        inputs: StepInputs,
+        active_committee: &BlsCommittee,
+        last_submitted_checkpoint: &mut Option<u64>,
+    ) {
+        let last_processed_sequence_number = inputs.last_processed_dwallet_checkpoint;
+        let next_sequence_number = last_processed_sequence_number + 1;
+        if let Ok(Some(latest)) = self
+            .dwallet_checkpoint_store
+            .get_latest_certified_checkpoint()
+        {
+            self.metrics.dwallet_checkpoint_backlog.set(
+                latest
+                    .sequence_number
+                    .saturating_sub(last_processed_sequence_number) as i64,
+            );
+        }
+        if Some(next_sequence_number) > *last_submitted_checkpoint
+            && !self.step_parked("dwallet_checkpoint", inputs).await
+        {
+            match self.next_dwallet_checkpoint_batch(next_sequence_number, active_committee) {
+                Ok(mut batch) if !batch.is_empty() => {
+                    info!(
+                        first_sequence_number=?next_sequence_number,
+                        last_sequence_number=?batch.last().map(|checkpoint| checkpoint.sequence_number),
+                        "Processing dwallet checkpoints batch"
+                    );
+                    self.metrics
+                        .dwallet_checkpoint_sequence
+                        .set(next_sequence_number as i64);
+
+                    // A batch is submitted once. If it fails, e.g. because one of its checkpoints
+                    // aborts, only its first checkpoint is retried, so that a single failing
+                    // checkpoint can't hold back the checkpoints before it.
+                    if batch.len() > 1 {
+                        self.metrics
+                            .dwallet_checkpoint_write_requests_total
+                            .add(batch.len() as i64);
+                        match Self::handle_dwallet_checkpoint_execution_task(
+                            self.ika_dwallet_2pc_mpc_package_id,
+                            &batch,
+                            sui_notifier,
+                            gas_coin_pool,
+                            &self.sui_client,
+                            &self.metrics,
+                            self.dwallet_checkpoint_tx_lock.clone(),
+                        )
+                        .await
+                        {
+                            Ok(_) => {
+                                self.step_succeeded("dwallet_checkpoint");
+                                self.dwallet_checkpoints_submitted(
+                                    &batch,
+                                    last_submitted_checkpoint,
                                 );
+                                return;
+                            }
+                            Err(err) => {
+                                warn!(
+                                    first_sequence_number=?next_sequence_number,
+                                    batch_size=batch.len(),
+                                    error=?err,
+                                    "failed to submit dwallet checkpoints batch, submitting its first checkpoint on its own"
+                                );
+                                batch.truncate(1);
                             }
-                            info!(
-                                ?next_dwallet_checkpoint_sequence_number,
-                                "Successfully submitted dwallet checkpoint"
-                            );
-                            self.metrics.dwallet_checkpoint_writes_success_total.inc();
-                            self.metrics
-                                .last_written_dwallet_checkpoint_sequence
-                                .set(next_dwallet_checkpoint_sequence_number as i64);
-                            last_submitted_dwallet_checkpoint =
-                                Some(next_dwallet_checkpoint_sequence_number);
                         }
-                        Err(e) => {
-                            error!(
-                                sequence_number=?next_dwallet_checkpoint_sequence_number,
-                                error=?e,
-                                "failed to get checkpoint"
-                            );
+                    }
+                    self.metrics
+                        .dwallet_checkpoint_write_requests_total
+                        .add(batch.len() as i64);
+
+                    let response = retry_with_max_elapsed_time!(
+                        Self::handle_dwallet_checkpoint_execution_task(
+                            self.ika_dwallet_2pc_mpc_package_id,
+                            &batch,
+                            sui_notifier,
+                            gas_coin_pool,
+                            &self.sui_client.clone(),
+                            &self.metrics.clone(),
+                            self.dwallet_checkpoint_tx_lock.clone(),
+                        ),
+                        Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                        is_deterministic_move_abort
+                    );
+                    match response {
+                        Ok(_) => self.step_succeeded("dwallet_checkpoint"),
+                        Err(err) if err.is_deterministic_move_abort() => {
+                            self.step_aborted("dwallet_checkpoint", inputs, &err).await;
+                            *last_submitted_checkpoint = None;
+                            return;
                         }
-                        Ok(None) => {}
+                        Err(err) => panic!(
+                            "failed to submit dwallet checkpoint for over an hour, err: {err:?}"
+                        ),
                     }
+                    self.dwallet_checkpoints_submitted(&batch, last_submitted_checkpoint);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!(
+                        sequence_number=?next_sequence_number,
+                        error=?e,
+                        "failed to get checkpoint"
+                    );
                 }
+            }
+        }
+    }
 
-                if Some(next_system_checkpoint_sequence_number) > last_submitted_system_checkpoint {
-                    if let Ok(Some(system_checkpoint)) = self
-                        .system_checkpoint_store
-                        .get_system_checkpoint_by_sequence_number(
-                            next_system_checkpoint_sequence_number,
-                        )
-                    {
-                        self.metrics
-                            .system_checkpoint_sequence
-                            .set(next_dwallet_checkpoint_sequence_number as i64);
-
-                        let active_members: BlsCommittee = ika_system_state_inner
-                            .validator_set()
-                            .clone()
-                            .active_committee;
-                        let auth_sig = system_checkpoint.auth_sig();
-                        let signature = auth_sig.signature.as_bytes().to_vec();
-                        let signers_bitmap =
-                            Self::calculate_signers_bitmap(&auth_sig.signers_map, &active_members);
-                        let message = bcs::to_bytes::<SystemCheckpointMessage>(
-                            &system_checkpoint.into_message(),
-                        )
-                        .expect("Serializing `system_checkpoint` message cannot fail");
+    fn dwallet_checkpoints_submitted(
+        &self,
+        batch: &[CheckpointSubmission],
+        last_submitted_checkpoint: &mut Option<u64>,
+    ) {
+        let (Some(first), Some(last)) = (batch.first(), batch.last()) else {
+            return;
+        };
+        info!(
+            first_sequence_number=?first.sequence_number,
+            last_sequence_number=?last.sequence_number,
+            "Successfully submitted dwallet checkpoints batch"
+        );
+        self.metrics
+            .dwallet_checkpoint_writes_success_total
+            .add(batch.len() as i64);
+        self.metrics
+            .last_written_dwallet_checkpoint_sequence
+            .set(last.sequence_number as i64);
+        *last_submitted_checkpoint = Some(last.sequence_number);
+    }
 
-                        info!("Signers_bitmap: {:?}", signers_bitmap);
-                        self.metrics.system_checkpoint_write_requests_total.inc();
-                        let response = retry_with_max_elapsed_time!(
-                            Self::handle_system_checkpoint_execution_task(
-                                self.ika_system_package_id,
-                                signature.clone(),
-                                signers_bitmap.clone(),
-                                message.clone(),
-                                sui_notifier,
-                                &self.sui_client.clone(),
-                                &self.metrics.clone(),
-                                self.notifier_tx_lock.clone(),
-                            ),
-                            Duration::from_secs(ONE_HOUR_IN_SECONDS)
-                        );
-                        if response.is_err() {
-                            panic!(
-                                "failed to submit system checkpoint for over an hour, err: {:?}",
-                                response.err()
+    /// Submits the next batch of certified system checkpoints to Sui,
+    /// unless it was already submitted and is waiting to be processed, or it is parked.
+    async fn submit_system_checkpoints(
+        &self,
+        sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
+        inputs: StepInputs,
+        active_committee: &BlsCommittee,
+        last_submitted_checkpoint: &mut Option<u64>,
+    ) {
+        let last_processed_sequence_number = inputs.last_processed_system_checkpoint;
+        let next_sequence_number = last_processed_sequence_number + 1;
+        if let Ok(Some(latest)) = self
+            .system_checkpoint_store
+            .get_latest_certified_system_checkpoint()
+        {
+            self.metrics.system_checkpoint_backlog.set(
+                latest
+                    .sequence_number
+                    .saturating_sub(last_processed_sequence_number) as i64,
+            );
+        }
+        if Some(next_sequence_number) > *last_submitted_checkpoint
+            && !self.step_parked("system_checkpoint", inputs).await
+        {
+            let mut batch = self
+                .next_system_checkpoint_batch(next_sequence_number, active_committee)
+                .unwrap_or_else(|e| {
+                    error!(
+                        sequence_number=?next_sequence_number,
+                        error=?e,
+                        "failed to get system checkpoint"
+                    );
+                    vec![]
+                });
+            if !batch.is_empty() {
+                self.metrics
+                    .system_checkpoint_sequence
+                    .set(next_sequence_number as i64);
+                // Like dwallet checkpoints, a failed batch falls back to its first checkpoint.
+                if batch.len() > 1 {
+                    self.metrics
+                        .system_checkpoint_write_requests_total
+                        .add(batch.len() as i64);
+                    match Self::handle_system_checkpoint_execution_task(
+                        self.ika_system_package_id,
+                        &batch,
+                        sui_notifier,
+                        gas_coin_pool,
+                        &self.sui_client,
+                        &self.metrics,
+                        self.system_tx_lock.clone(),
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            self.step_succeeded("system_checkpoint");
+                            self.system_checkpoints_submitted(&batch, last_submitted_checkpoint);
+                            return;
+                        }
+                        Err(err) => {
+                            warn!(
+                                first_sequence_number=?next_sequence_number,
+                                batch_size=batch.len(),
+                                error=?err,
+                                "failed to submit system checkpoints batch, submitting its first checkpoint on its own"
                             );
+                            batch.truncate(1);
                         }
-                        self.metrics.system_checkpoint_writes_success_total.inc();
-                        self.metrics
-                            .last_written_system_checkpoint_sequence
-                            .set(next_dwallet_checkpoint_sequence_number as i64);
-                        last_submitted_system_checkpoint =
-                            Some(next_system_checkpoint_sequence_number);
-                        info!(
-                            "Sui transaction successfully executed for system_checkpoint sequence number: {}",
-                            next_system_checkpoint_sequence_number
-                        );
                     }
                 }
+                self.metrics
+                    .system_checkpoint_write_requests_total
+                    .add(batch.len() as i64);
+                let response = retry_with_max_elapsed_time!(
+                    Self::handle_system_checkpoint_execution_task(
+                        self.ika_system_package_id,
+                        &batch,
+                        sui_notifier,
+                        gas_coin_pool,
+                        &self.sui_client.clone(),
+                        &self.metrics.clone(),
+                        self.system_tx_lock.clone(),
+                    ),
+                    Duration::from_secs(ONE_HOUR_IN_SECONDS),
+                    is_deterministic_move_abort
+                );
+                match response {
+                    Ok(_) => self.step_succeeded("system_checkpoint"),
+                    Err(err) if err.is_deterministic_move_abort() => {
+                        self.step_aborted("system_checkpoint", inputs, &err).await;
+                        *last_submitted_checkpoint = None;
+                        return;
+                    }
+                    Err(err) => {
+                        panic!("failed to submit system checkpoint for over an hour, err: {err:?}")
+                    }
+                }
+                self.system_checkpoints_submitted(&batch, last_submitted_checkpoint);
             }
         }
     }
 
+    fn system_checkpoints_submitted(
+        &self,
+        batch: &[CheckpointSubmission],
+        last_submitted_checkpoint: &mut Option<u64>,
+    ) {
+        let (Some(first), Some(last)) = (batch.first(), batch.last()) else {
+            return;
+        };
+        self.metrics
+            .system_checkpoint_writes_success_total
+            .add(batch.len() as i64);
+        self.metrics
+            .last_written_system_checkpoint_sequence
+            .set(last.sequence_number as i64);
+        *last_submitted_checkpoint = Some(last.sequence_number);
+        info!(
+            first_sequence_number=?first.sequence_number,
+            last_sequence_number=?last.sequence_number,
+            "Sui transaction successfully executed for system checkpoints batch"
+        );
+    }
+
     fn calculate_signers_bitmap(
         signers_map: &RoaringBitmap,
         active_committee: &BlsCommittee,
@@ -529,12 +955,10 @@ where
         ika_dwallet_2pc_mpc_package_id: ObjectID,
         network_encryption_key_ids: Vec<ObjectID>,
         sui_notifier: &SuiNotifier,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        gas_coin_pool: &GasCoinPool<C>,
+        metrics: &SuiConnectorMetrics,
+        notifier_tx_lock: NotifierTxLock,
     ) -> anyhow::Result<SuiTransactionBlockResponse> {
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
         let mut ptb = ProgrammableTransactionBuilder::new();
         let zero = ptb.input(CallArg::Pure(bcs::to_bytes(&0u32)?))?;
         let zero_option = ptb.input(CallArg::Pure(bcs::to_bytes(&Some(0u32))?))?;
@@ -687,20 +1111,19 @@ where
                 sign_with_partial_user_signature_protocol_flag,
             ],
         );
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        Ok(Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        Ok(Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await?)
+        .await?)
     }
 
     async fn submit_tx_to_sui(
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        notifier_tx_lock: NotifierTxLock,
         transaction: Transaction,
         sui_client: &Arc<SuiClient<C>>,
     ) -> DwalletMPCResult<SuiTransactionBlockResponse> {
@@ -764,18 +1187,48 @@ where
         Ok(tx_response)
     }
 
+    /// Pays for `pt` with a coin leased from `gas_coin_pool` and submits it to Sui,
+    /// returning the coin to the pool at its new version once the transaction is executed.
+    async fn submit_with_gas_coin(
+        pt: ProgrammableTransaction,
+        sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
+        sui_client: &Arc<SuiClient<C>>,
+        metrics: &SuiConnectorMetrics,
+        notifier_tx_lock: NotifierTxLock,
+    ) -> DwalletMPCResult<SuiTransactionBlockResponse> {
+        let gas_coin = gas_coin_pool.acquire(sui_notifier).await?;
+        let transaction = super::build_sui_transaction(
+            pt,
+            sui_client,
+            vec![gas_coin.object_ref()],
+            sui_notifier.signer.as_ref(),
+            metrics,
+        )
+        .await;
+        let transaction = match transaction {
+            Ok(transaction) => transaction,
+            Err(err) => {
+                gas_coin.unused();
+                return Err(err.into());
+            }
+        };
+
+        let response = Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await?;
+        gas_coin.complete(&response);
+        Ok(response)
+    }
+
     async fn process_mid_epoch(
         ika_system_package_id: ObjectID,
         ika_dwallet_2pc_mpc_package_id: ObjectID,
         sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
         sui_client: &Arc<SuiClient<C>>,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        metrics: &SuiConnectorMetrics,
+        notifier_tx_lock: NotifierTxLock,
     ) -> IkaResult<SuiTransactionBlockResponse> {
         info!("Running `process_mid_epoch()`");
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
 
         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -825,30 +1278,27 @@ where
             vec![coordinator_arg, system_current_status_info],
         );
 
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        Ok(Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        Ok(Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await?)
+        .await?)
     }
 
     async fn lock_last_session_to_complete_in_current_epoch(
         ika_system_package_id: ObjectID,
         ika_dwallet_2pc_mpc_package_id: ObjectID,
         sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
         sui_client: &Arc<SuiClient<C>>,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        metrics: &SuiConnectorMetrics,
+        notifier_tx_lock: NotifierTxLock,
     ) -> IkaResult<SuiTransactionBlockResponse> {
         info!("Process `lock_last_active_session_sequence_number()`");
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
 
         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -891,30 +1341,27 @@ where
             vec![coordinator_arg, system_current_status_info],
         );
 
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        Ok(Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        Ok(Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await?)
+        .await?)
     }
 
     async fn process_request_advance_epoch(
         ika_system_package_id: ObjectID,
         ika_dwallet_2pc_mpc_package_id: ObjectID,
         sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
         sui_client: &Arc<SuiClient<C>>,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        metrics: &SuiConnectorMetrics,
+        notifier_tx_lock: NotifierTxLock,
     ) -> IkaResult<SuiTransactionBlockResponse> {
         info!("Running `process_request_advance_epoch()`");
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
 
         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -965,53 +1412,92 @@ where
             vec![system_arg, advance_epoch_approver, clock_arg],
         );
 
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        Ok(Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        Ok(Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await?)
+        .await?)
     }
 
-    async fn handle_dwallet_checkpoint_execution_task(
-        ika_dwallet_2pc_mpc_package_id: ObjectID,
-        signature: Vec<u8>,
-        signers_bitmap: Vec<u8>,
-        message: Vec<u8>,
-        sui_notifier: &SuiNotifier,
-        sui_client: &Arc<SuiClient<C>>,
-        metrics: &Arc<SuiConnectorMetrics>,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
-    ) -> IkaResult<SuiTransactionBlockResponse> {
-        let mut ptb = ProgrammableTransactionBuilder::new();
-
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        merge_gas_coins(&mut ptb, &gas_coins)?;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
-
-        let dwallet_2pc_mpc_coordinator_arg = sui_client
-            .get_mutable_dwallet_2pc_mpc_coordinator_arg_must_succeed()
-            .await;
+    /// Reads the longest run of consecutive certified dwallet checkpoints, starting at
+    /// `first_sequence_number`, that fits in a single Sui transaction.
+    fn next_dwallet_checkpoint_batch(
+        &self,
+        first_sequence_number: u64,
+        active_committee: &BlsCommittee,
+    ) -> Result<Vec<CheckpointSubmission>, TypedStoreError> {
+        collect_checkpoint_batch(first_sequence_number, |sequence_number| {
+            Ok(self
+                .dwallet_checkpoint_store
+                .get_dwallet_checkpoint_by_sequence_number(sequence_number)?
+                .map(|checkpoint| {
+                    let auth_sig = checkpoint.auth_sig();
+                    let signature = auth_sig.signature.as_bytes().to_vec();
+                    let signers_bitmap =
+                        Self::calculate_signers_bitmap(&auth_sig.signers_map, active_committee);
+                    let message = checkpoint.into_message();
+                    let epoch = message.epoch;
+                    let message = bcs::to_bytes::<DWalletCheckpointMessage>(&message)
+                        .expect("Serializing checkpoint message cannot fail");
+                    CheckpointSubmission {
+                        epoch,
+                        sequence_number,
+                        signature,
+                        signers_bitmap,
+                        message,
+                    }
+                }))
+        })
+    }
 
-        info!(
-            "`signers_bitmap` @ handle_execution_task: {:?}",
-            signers_bitmap
-        );
+    /// Reads the longest run of consecutive certified system checkpoints, starting at
+    /// `first_sequence_number`, that fits in a single Sui transaction.
+    fn next_system_checkpoint_batch(
+        &self,
+        first_sequence_number: u64,
+        active_committee: &BlsCommittee,
+    ) -> Result<Vec<CheckpointSubmission>, TypedStoreError> {
+        collect_checkpoint_batch(first_sequence_number, |sequence_number| {
+            Ok(self
+                .system_checkpoint_store
+                .get_system_checkpoint_by_sequence_number(sequence_number)?
+                .map(|checkpoint| {
+                    let auth_sig = checkpoint.auth_sig();
+                    let signature = auth_sig.signature.as_bytes().to_vec();
+                    let signers_bitmap =
+                        Self::calculate_signers_bitmap(&auth_sig.signers_map, active_committee);
+                    let message = checkpoint.into_message();
+                    let epoch = message.epoch;
+                    let message = bcs::to_bytes::<SystemCheckpointMessage>(&message)
+                        .expect("Serializing `system_checkpoint` message cannot fail");
+                    CheckpointSubmission {
+                        epoch,
+                        sequence_number,
+                        signature,
+                        signers_bitmap,
+                        message,
+                    }
+                }))
+        })
+    }
 
+    /// Adds the `signature`, `signers_bitmap` and `message` arguments of
+    /// `process_checkpoint_message_by_quorum` for `checkpoint` to the PTB.
+    fn checkpoint_args(
+        ptb: &mut ProgrammableTransactionBuilder,
+        checkpoint: &CheckpointSubmission,
+    ) -> IkaResult<Vec<Argument>> {
         let args = vec![
-            CallArg::Object(dwallet_2pc_mpc_coordinator_arg),
-            CallArg::Pure(bcs::to_bytes(&signature).map_err(|e| {
+            CallArg::Pure(bcs::to_bytes(&checkpoint.signature).map_err(|e| {
                 IkaError::SuiConnectorSerializationError(format!(
                     "can't serialize `signature`: {e}"
                 ))
             })?),
-            CallArg::Pure(bcs::to_bytes(&signers_bitmap).map_err(|e| {
+            CallArg::Pure(bcs::to_bytes(&checkpoint.signers_bitmap).map_err(|e| {
                 IkaError::SuiConnectorSerializationError(format!(
                     "can't serialize `signers_bitmap`: {e}"
                 ))
@@ -1028,33 +1514,80 @@ where
             .collect::<Result<Vec<_>, _>>()?;
 
         let message_arg =
-            Self::break_down_checkpoint_message_into_vector_arg(&mut ptb, message.clone());
+            Self::break_down_checkpoint_message_into_vector_arg(ptb, checkpoint.message.clone());
         args.push(message_arg?);
+        Ok(args)
+    }
 
-        let gas_fee_reimbursement_sui = ptb.programmable_move_call(
-            ika_dwallet_2pc_mpc_package_id,
-            DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME.into(),
-            PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME.into(),
-            vec![],
-            args,
-        );
+    async fn handle_dwallet_checkpoint_execution_task(
+        ika_dwallet_2pc_mpc_package_id: ObjectID,
+        checkpoints: &[CheckpointSubmission],
+        sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
+        sui_client: &Arc<SuiClient<C>>,
+        metrics: &Arc<SuiConnectorMetrics>,
+        notifier_tx_lock: NotifierTxLock,
+    ) -> IkaResult<SuiTransactionBlockResponse> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
 
-        ptb.command(sui_types::transaction::Command::MergeCoins(
-            Argument::GasCoin,
-            vec![gas_fee_reimbursement_sui],
-        ));
+        let dwallet_2pc_mpc_coordinator_arg = sui_client
+            .get_mutable_dwallet_2pc_mpc_coordinator_arg_must_succeed()
+            .await;
+        let coordinator_arg = ptb
+            .input(CallArg::Object(dwallet_2pc_mpc_coordinator_arg))
+            .map_err(|e| {
+                IkaError::SuiConnectorSerializationError(format!(
+                    "can't serialize `coordinator_arg`: {e}"
+                ))
+            })?;
 
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        // The checkpoints are processed in order, each call observing the coordinator state
+        // updated by the previous one.
+        for checkpoint in checkpoints {
+            info!(
+                sequence_number=?checkpoint.sequence_number,
+                signers_bitmap=?checkpoint.signers_bitmap,
+                "Adding dwallet checkpoint to the transaction"
+            );
+            let mut args = vec![coordinator_arg];
+            args.extend(Self::checkpoint_args(&mut ptb, checkpoint)?);
+
+            let gas_fee_reimbursement_sui = ptb.programmable_move_call(
+                ika_dwallet_2pc_mpc_package_id,
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME.into(),
+                PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME.into(),
+                vec![],
+                args,
+            );
+
+            ptb.command(sui_types::transaction::Command::MergeCoins(
+                Argument::GasCoin,
+                vec![gas_fee_reimbursement_sui],
+            ));
+        }
+
+        let start = Instant::now();
+        match Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        match Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await {
-            Ok(result) => Ok(result),
+        .await
+        {
+            Ok(result) => {
+                metrics
+                    .checkpoint_sui_transaction_latency_sec
+                    .with_label_values(&[DWALLET_CHECKPOINT_LABEL])
+                    .observe(start.elapsed().as_secs_f64());
+                metrics
+                    .checkpoints_per_sui_transaction
+                    .with_label_values(&[DWALLET_CHECKPOINT_LABEL])
+                    .observe(checkpoints.len() as f64);
+                Ok(result)
+            }
             Err(err) => {
                 error!(error=?err, "failed to submit dwallet checkpoint to sui",);
                 metrics.dwallet_checkpoint_writes_failure_total.inc();
@@ -1065,74 +1598,64 @@ where
 
     async fn handle_system_checkpoint_execution_task(
         ika_system_package_id: ObjectID,
-        signature: Vec<u8>,
-        signers_bitmap: Vec<u8>,
-        message: Vec<u8>,
+        checkpoints: &[CheckpointSubmission],
         sui_notifier: &SuiNotifier,
+        gas_coin_pool: &GasCoinPool<C>,
         sui_client: &Arc<SuiClient<C>>,
         metrics: &Arc<SuiConnectorMetrics>,
-        notifier_tx_lock: Arc<tokio::sync::Mutex<Option<TransactionDigest>>>,
+        notifier_tx_lock: NotifierTxLock,
     ) -> IkaResult<()> {
         let mut ptb = ProgrammableTransactionBuilder::new();
 
-        let gas_coins = sui_client.get_gas_objects(sui_notifier.sui_address).await;
-        merge_gas_coins(&mut ptb, &gas_coins)?;
-        let gas_coin = gas_coins
-            .first()
-            .ok_or_else(|| IkaError::SuiConnectorInternalError("no gas coin found".to_string()))?;
-
-        info!(
-            "`signers_bitmap` @ handle_execution_task: {:?}",
-            signers_bitmap
-        );
         let ika_system_state_arg = sui_client.get_mutable_system_arg_must_succeed().await;
-
-        let args = vec![
-            CallArg::Object(ika_system_state_arg),
-            CallArg::Pure(bcs::to_bytes(&signature).map_err(|e| {
-                IkaError::SuiConnectorSerializationError(format!(
-                    "can't serialize `signature`: {e}"
-                ))
-            })?),
-            CallArg::Pure(bcs::to_bytes(&signers_bitmap).map_err(|e| {
+        let system_arg = ptb
+            .input(CallArg::Object(ika_system_state_arg))
+            .map_err(|e| {
                 IkaError::SuiConnectorSerializationError(format!(
-                    "can't serialize `signers_bitmap`: {e}"
+                    "can't serialize `system_arg`: {e}"
                 ))
-            })?),
-        ];
-
-        let mut args = args
-            .into_iter()
-            .map(|arg| {
-                ptb.input(arg).map_err(|e| {
-                    IkaError::SuiConnectorSerializationError(format!("can't serialize `arg`: {e}"))
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            })?;
 
-        let message_arg =
-            Self::break_down_checkpoint_message_into_vector_arg(&mut ptb, message.clone());
-        args.push(message_arg?);
+        for checkpoint in checkpoints {
+            info!(
+                sequence_number=?checkpoint.sequence_number,
+                signers_bitmap=?checkpoint.signers_bitmap,
+                "Adding system checkpoint to the transaction"
+            );
+            let mut args = vec![system_arg];
+            args.extend(Self::checkpoint_args(&mut ptb, checkpoint)?);
 
-        ptb.programmable_move_call(
-            ika_system_package_id,
-            SYSTEM_MODULE_NAME.into(),
-            PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME.into(),
-            vec![],
-            args,
-        );
+            ptb.programmable_move_call(
+                ika_system_package_id,
+                SYSTEM_MODULE_NAME.into(),
+                PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME.into(),
+                vec![],
+                args,
+            );
+        }
 
-        let transaction = super::build_sui_transaction(
-            sui_notifier.sui_address,
+        let start = Instant::now();
+        match Self::submit_with_gas_coin(
             ptb.finish(),
+            sui_notifier,
+            gas_coin_pool,
             sui_client,
-            vec![*gas_coin],
-            &sui_notifier.sui_key,
+            metrics,
+            notifier_tx_lock,
         )
-        .await;
-
-        match Self::submit_tx_to_sui(notifier_tx_lock, transaction, sui_client).await {
-            Ok(_) => Ok(()),
+        .await
+        {
+            Ok(_) => {
+                metrics
+                    .checkpoint_sui_transaction_latency_sec
+                    .with_label_values(&[SYSTEM_CHECKPOINT_LABEL])
+                    .observe(start.elapsed().as_secs_f64());
+                metrics
+                    .checkpoints_per_sui_transaction
+                    .with_label_values(&[SYSTEM_CHECKPOINT_LABEL])
+                    .observe(checkpoints.len() as f64);
+                Ok(())
+            }
             Err(err) => {
                 error!(error=?err, "failed to submit a system checkpoint to consensus");
                 metrics.system_checkpoint_writes_failure_total.inc();
@@ -1142,38 +1665,626 @@ where
     }
 }
 
-/// Merge multiple gas coins into one by adding a `MergeCoins` command to the
-/// provided `ProgrammableTransactionBuilder`.
-/// If `gas_coins` has zero or one element, the function is no‑op.
-fn merge_gas_coins(
-    ptb: &mut ProgrammableTransactionBuilder,
-    gas_coins: &[sui_types::base_types::ObjectRef],
-) -> IkaResult<()> {
-    if gas_coins.len() <= 1 {
-        return Ok(());
+/// A certified checkpoint, encoded the way `process_checkpoint_message_by_quorum` expects it.
+struct CheckpointSubmission {
+    epoch: EpochId,
+    sequence_number: u64,
+    signature: Vec<u8>,
+    signers_bitmap: Vec<u8>,
+    message: Vec<u8>,
+}
+
+/// Collects consecutive checkpoints starting at `first_sequence_number` until `load` runs out of
+/// checkpoints, the batch limits are reached, or the next checkpoint belongs to another epoch,
+/// since Sui verifies each checkpoint against the committee of its epoch.
+/// The first checkpoint is always included, since a single checkpoint is already bounded by the
+/// protocol's `max_*_checkpoint_size_bytes`.
+fn collect_checkpoint_batch<F>(
+    first_sequence_number: u64,
+    mut load: F,
+) -> Result<Vec<CheckpointSubmission>, TypedStoreError>
+where
+    F: FnMut(u64) -> Result<Option<CheckpointSubmission>, TypedStoreError>,
+{
+    let mut batch: Vec<CheckpointSubmission> = Vec::new();
+    let mut batch_message_bytes = 0;
+    let mut sequence_number = first_sequence_number;
+    while batch.len() < MAX_CHECKPOINTS_PER_SUI_TRANSACTION {
+        let Some(checkpoint) = load(sequence_number)? else {
+            break;
+        };
+        if batch.first().is_some_and(|first| {
+            checkpoint.epoch != first.epoch
+                || batch_message_bytes + checkpoint.message.len()
+                    > MAX_CHECKPOINT_BATCH_MESSAGE_BYTES
+        }) {
+            break;
+        }
+        batch_message_bytes += checkpoint.message.len();
+        batch.push(checkpoint);
+        sequence_number += 1;
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui_connector::NOTIFIER_GAS_BUDGET;
+    use ika_sui_client::mock_signer_server::MockSignerServer;
+    use ika_sui_client::signer::{FileSigner, RemoteSigner, Signer};
+    use ika_sui_client::sui_mock_client::{
+        SuiMockClient, bls_committee_for_testing, dwallet_coordinator_inner_v1_for_testing,
+        move_calls, system_inner_v1_for_testing,
+    };
+    use ika_types::crypto::{AccountKeyPair, AuthorityStrongQuorumSignInfo, get_key_pair_from_rng};
+    use ika_types::messages_dwallet_checkpoint::{
+        CertifiedDWalletCheckpointMessage, VerifiedDWalletCheckpointMessage,
+    };
+    use ika_types::sui::system_inner_v1::{PricingInfo, PricingInfoCalculationVotes};
+    use move_core_types::identifier::IdentStr;
+    use rand::rngs::OsRng;
+    use sui_types::collection_types::VecMap;
+    use sui_types::crypto::SuiKeyPair;
+    use sui_types::transaction::TransactionDataAPI;
+    use tempfile::TempDir;
+
+    const EPOCH: EpochId = 1;
+    const HOUR_MS: u64 = 60 * 60 * 1000;
+
+    struct TestExecutor {
+        mock: SuiMockClient,
+        executor: SuiExecutor<SuiMockClient>,
+        committee: BlsCommittee,
+        _store_dir: TempDir,
     }
 
-    info!("More than one gas coin was found, merging them into one gas coin.");
+    impl TestExecutor {
+        /// An executor for `EPOCH`, which started at time zero and lasts a day,
+        /// whose notifier holds a single gas coin.
+        fn new() -> Self {
+            let (_, sui_key) = get_key_pair_from_rng::<AccountKeyPair, _>(&mut OsRng);
+            Self::with_signer(Arc::new(FileSigner::new(SuiKeyPair::Ed25519(sui_key))))
+        }
 
-    let coins: IkaResult<Vec<_>> = gas_coins
-        .iter()
-        .skip(1)
-        .map(|c| {
-            ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(*c)))
-                .map_err(|e| {
-                    IkaError::SuiConnectorInternalError(format!(
-                        "error merging coin ProgrammableTransactionBuilder::input: {e}"
-                    ))
+        /// Like [`Self::new`], with the notifier's transactions signed by `signer`.
+        fn with_signer(signer: Arc<dyn Signer>) -> Self {
+            let mock = SuiMockClient::new();
+            let committee = bls_committee_for_testing(4);
+            mock.set_system_inner(system_inner_v1_for_testing(EPOCH, committee.clone()));
+            mock.set_dwallet_coordinator_inner(dwallet_coordinator_inner_v1_for_testing(
+                EPOCH,
+                committee.clone(),
+            ));
+            let sui_address = signer.address();
+            mock.add_gas_coin(sui_address, 10 * NOTIFIER_GAS_BUDGET);
+
+            let store_dir = tempfile::tempdir().unwrap();
+            let executor = SuiExecutor::new(
+                ObjectID::random(),
+                ObjectID::random(),
+                1,
+                None,
+                DWalletCheckpointStore::new(&store_dir.path().join("dwallet_checkpoints")),
+                SystemCheckpointStore::new(&store_dir.path().join("system_checkpoints")),
+                Some(SuiNotifier {
+                    signer,
+                    sui_address,
+                }),
+                Arc::new(SuiClient::new_for_testing(mock.clone())),
+                SuiConnectorMetrics::new_for_testing(),
+                SessionLatencyTracker::new_for_testing(),
+                DrainMode::new(),
+            );
+            Self {
+                mock,
+                executor,
+                committee,
+                _store_dir: store_dir,
+            }
+        }
+
+        async fn run_epoch_switch(
+            &self,
+            network_encryption_key_ids: Vec<ObjectID>,
+            epoch_switch_state: &mut EpochSwitchState,
+        ) {
+            let system_inner = self
+                .executor
+                .sui_client
+                .must_get_system_inner_object()
+                .await;
+            let coordinator_inner = self
+                .executor
+                .sui_client
+                .must_get_dwallet_coordinator_inner_v1()
+                .await;
+            let inputs = StepInputs::new(
+                system_inner.epoch(),
+                system_inner.last_processed_checkpoint_sequence_number(),
+                coordinator_inner.last_processed_checkpoint_sequence_number,
+            );
+            self.executor
+                .run_epoch_switch(
+                    self.executor.sui_notifier.as_ref().unwrap(),
+                    self.executor.gas_coin_pool.as_ref().unwrap(),
+                    &system_inner,
+                    network_encryption_key_ids,
+                    epoch_switch_state,
+                    inputs,
+                )
+                .await;
+        }
+
+        async fn submit_dwallet_checkpoints(
+            &self,
+            last_processed_sequence_number: u64,
+            last_submitted_checkpoint: &mut Option<u64>,
+        ) {
+            self.executor
+                .submit_dwallet_checkpoints(
+                    self.executor.sui_notifier.as_ref().unwrap(),
+                    self.executor.gas_coin_pool.as_ref().unwrap(),
+                    StepInputs::new(EPOCH, 0, last_processed_sequence_number),
+                    &self.committee,
+                    last_submitted_checkpoint,
+                )
+                .await;
+        }
+
+        fn insert_certified_dwallet_checkpoint(&self, sequence_number: u64) {
+            let signers_map = RoaringBitmap::from_iter(0..3);
+            let checkpoint = VerifiedDWalletCheckpointMessage::new_unchecked(
+                CertifiedDWalletCheckpointMessage::new_from_data_and_sig(
+                    DWalletCheckpointMessage::new(EPOCH, sequence_number, vec![]),
+                    AuthorityStrongQuorumSignInfo {
+                        epoch: EPOCH,
+                        signature: Default::default(),
+                        signers_map,
+                    },
+                ),
+            );
+            self.executor
+                .dwallet_checkpoint_store
+                .insert_certified_checkpoint(&checkpoint)
+                .unwrap();
+        }
+
+        /// The number of calls to `module::function` in each executed transaction.
+        fn executed_calls(&self, module: &IdentStr, function: &IdentStr) -> Vec<usize> {
+            let call = format!("{module}::{function}");
+            self.mock
+                .executed_transactions()
+                .iter()
+                .map(|transaction| {
+                    move_calls(transaction)
+                        .iter()
+                        .filter(|move_call| **move_call == call)
+                        .count()
                 })
+                .collect()
+        }
+    }
+
+    fn new_epoch_switch_state() -> EpochSwitchState {
+        EpochSwitchState {
+            ran_mid_epoch: false,
+            ran_lock_last_session: false,
+            ran_request_advance_epoch: false,
+            calculated_protocol_pricing: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn epoch_switch_calls_follow_the_epoch_timeline() {
+        let test = TestExecutor::new();
+        let mut epoch_switch_state = new_epoch_switch_state();
+
+        test.mock.set_clock_timestamp_ms(HOUR_MS);
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert!(test.mock.executed_transactions().is_empty());
+
+        // Past the middle of the epoch, the mid-epoch reconfiguration is initiated once.
+        test.mock.set_clock_timestamp_ms(13 * HOUR_MS);
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert_eq!(
+            test.executed_calls(
+                SYSTEM_MODULE_NAME,
+                INITIATE_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME
+            ),
+            vec![1]
+        );
+
+        // Past the end of the epoch, the last session to complete is locked.
+        let committee = test.committee.clone();
+        test.mock.update_system_inner(|system_inner| {
+            system_inner.validator_set.next_epoch_committee = Some(committee);
+        });
+        test.mock.set_clock_timestamp_ms(25 * HOUR_MS);
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert_eq!(
+            test.executed_calls(
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                REQUEST_LOCK_EPOCH_SESSIONS_FUNCTION_NAME
+            ),
+            vec![0, 1]
+        );
+
+        // Once both objects received the end of publish, the epoch is advanced.
+        test.mock
+            .update_system_inner(|system_inner| system_inner.received_end_of_publish = true);
+        test.mock.update_dwallet_coordinator_inner(|coordinator| {
+            coordinator
+                .sessions_manager
+                .locked_last_user_initiated_session_to_complete_in_current_epoch = true;
+            coordinator.received_end_of_publish = true;
+        });
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert_eq!(
+            test.executed_calls(SYSTEM_MODULE_NAME, ADVANCE_EPOCH_FUNCTION_NAME),
+            vec![0, 0, 1]
+        );
+    }
+
+    #[tokio::test]
+    async fn epoch_switch_status_is_published_with_its_metrics() {
+        let test = TestExecutor::new();
+        let mut epoch_switch_state = new_epoch_switch_state();
+        let steps_done = || {
+            [
+                "mid_epoch",
+                "protocol_pricing",
+                "lock_last_session",
+                "request_advance_epoch",
+            ]
+            .map(|step| {
+                test.executor
+                    .metrics
+                    .epoch_switch_step_done
+                    .with_label_values(&[step])
+                    .get()
+            })
+        };
+
+        test.executor
+            .publish_epoch_switch_state(EPOCH, epoch_switch_state);
+        assert_eq!(
+            test.executor.epoch_switch_state(),
+            (EPOCH, EpochSwitchState::default())
+        );
+        assert_eq!(test.executor.metrics.epoch_switch_epoch.get(), EPOCH as i64);
+        assert_eq!(steps_done(), [0, 0, 0, 0]);
+
+        // Past the middle of the epoch, only the mid-epoch reconfiguration runs.
+        test.mock.set_clock_timestamp_ms(13 * HOUR_MS);
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        test.executor
+            .publish_epoch_switch_state(EPOCH, epoch_switch_state);
+        assert_eq!(
+            test.executor.epoch_switch_state(),
+            (
+                EPOCH,
+                EpochSwitchState {
+                    ran_mid_epoch: true,
+                    ..Default::default()
+                }
+            )
+        );
+        assert_eq!(steps_done(), [1, 0, 0, 0]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn aborted_epoch_switch_steps_are_parked_until_their_back_off_elapses() {
+        let test = TestExecutor::new();
+        let mut epoch_switch_state = new_epoch_switch_state();
+        test.mock.set_clock_timestamp_ms(13 * HOUR_MS);
+
+        test.mock.fail_next_dry_run(
+            "MoveAbort(MoveLocation { module: ModuleId { address: 0000000000000000000000000000000000000000000000000000000000000abc, name: Identifier(\"system\") }, function: 4, instruction: 9, function_name: Some(\"inner_mut\") }, 0) in command 0".to_string(),
+        );
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert!(!epoch_switch_state.ran_mid_epoch);
+        assert!(test.mock.executed_transactions().is_empty());
+        assert_eq!(
+            test.executor
+                .metrics
+                .notifier_aborted_steps
+                .with_label_values(&["mid_epoch"])
+                .get(),
+            1
+        );
+
+        // The step is not dry-run again while it is parked.
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert!(!epoch_switch_state.ran_mid_epoch);
+        assert_eq!(test.mock.calls("dry_run_transaction_block"), 1);
+        assert_eq!(
+            test.executor
+                .metrics
+                .notifier_parked_steps
+                .with_label_values(&["mid_epoch"])
+                .get(),
+            1
+        );
+
+        tokio::time::advance(Duration::from_secs(10 * 60)).await;
+        test.run_epoch_switch(vec![], &mut epoch_switch_state).await;
+        assert!(epoch_switch_state.ran_mid_epoch);
+        assert_eq!(
+            test.executor
+                .metrics
+                .notifier_parked_steps
+                .with_label_values(&["mid_epoch"])
+                .get(),
+            0
+        );
+        assert_eq!(
+            test.executed_calls(
+                SYSTEM_MODULE_NAME,
+                INITIATE_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME
+            ),
+            vec![1]
+        );
+    }
+
+    #[tokio::test]
+    async fn mid_epoch_reconfiguration_waits_for_all_network_keys() {
+        let test = TestExecutor::new();
+        let mut epoch_switch_state = new_epoch_switch_state();
+        epoch_switch_state.ran_mid_epoch = true;
+        let network_encryption_key_ids = vec![ObjectID::random(), ObjectID::random()];
+        let committee = test.committee.clone();
+        test.mock.update_dwallet_coordinator_inner(|coordinator| {
+            let no_pricing = || PricingInfo {
+                pricing_map: VecMap { contents: vec![] },
+            };
+            coordinator.pricing_and_fee_management.calculation_votes =
+                Some(PricingInfoCalculationVotes {
+                    bls_committee: committee.clone(),
+                    default_pricing: no_pricing(),
+                    working_pricing: no_pricing(),
+                });
+            coordinator.next_epoch_active_committee = Some(committee);
+            coordinator.dwallet_network_encryption_keys.size = 2;
+        });
+        test.mock.set_clock_timestamp_ms(13 * HOUR_MS);
+
+        // One of the keys has not finished its DKG yet.
+        test.run_epoch_switch(
+            network_encryption_key_ids[..1].to_vec(),
+            &mut epoch_switch_state,
+        )
+        .await;
+        assert!(test.mock.executed_transactions().is_empty());
+
+        test.run_epoch_switch(network_encryption_key_ids.clone(), &mut epoch_switch_state)
+            .await;
+        test.run_epoch_switch(network_encryption_key_ids, &mut epoch_switch_state)
+            .await;
+        assert_eq!(
+            test.executed_calls(
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                REQUEST_NETWORK_ENCRYPTION_KEY_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME
+            ),
+            vec![2]
+        );
+        assert!(epoch_switch_state.calculated_protocol_pricing);
+    }
+
+    #[tokio::test]
+    async fn certified_checkpoints_are_submitted_in_order_once() {
+        let test = TestExecutor::new();
+        for sequence_number in 1..=3 {
+            test.insert_certified_dwallet_checkpoint(sequence_number);
+        }
+        let mut last_submitted_checkpoint = None;
+
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(3));
+        // The batch is not re-submitted while it waits to be processed on Sui.
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(
+            test.executed_calls(
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME
+            ),
+            vec![3]
+        );
+
+        // A failed submission is retried with the gas coin re-read from Sui.
+        test.insert_certified_dwallet_checkpoint(4);
+        test.mock
+            .fail_next_calls("execute_transaction_block_with_effects", 1);
+        test.submit_dwallet_checkpoints(3, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(4));
+        assert_eq!(
+            test.executed_calls(
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME
+            ),
+            vec![3, 1]
+        );
+        assert_eq!(test.mock.calls("execute_transaction_block_with_effects"), 3);
+    }
+
+    #[tokio::test]
+    async fn checkpoints_are_signed_by_the_remote_signer() {
+        let (_, sui_key) = get_key_pair_from_rng::<AccountKeyPair, _>(&mut OsRng);
+        let server = MockSignerServer::start(
+            "notifier",
+            SuiKeyPair::Ed25519(sui_key),
+            Some("token".to_string()),
+        )
+        .await;
+        let signer = RemoteSigner::connect(&server.config()).await.unwrap();
+        let test = TestExecutor::with_signer(Arc::new(signer));
+        test.insert_certified_dwallet_checkpoint(1);
+        let mut last_submitted_checkpoint = None;
+
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(1));
+        assert_eq!(server.sign_requests(), 1);
+        assert_eq!(test.mock.executed_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn deterministic_move_aborts_are_parked_until_the_on_chain_state_changes() {
+        let test = TestExecutor::new();
+        test.insert_certified_dwallet_checkpoint(1);
+        let mut last_submitted_checkpoint = None;
+
+        // `EWrongInnerVersion`, as after the coordinator package was upgraded.
+        test.mock.fail_next_dry_run(
+            "MoveAbort(MoveLocation { module: ModuleId { address: 0000000000000000000000000000000000000000000000000000000000000abc, name: Identifier(\"coordinator\") }, function: 4, instruction: 9, function_name: Some(\"inner_mut\") }, 0) in command 0".to_string(),
+        );
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        // The checkpoint is not marked as submitted, so the stream does not stall on it.
+        assert_eq!(last_submitted_checkpoint, None);
+        assert_eq!(test.mock.calls("dry_run_transaction_block"), 1);
+        assert!(test.mock.executed_transactions().is_empty());
+        assert_eq!(
+            test.executor
+                .metrics
+                .notifier_move_aborts
+                .with_label_values(&["coordinator", "0", "true"])
+                .get(),
+            1
+        );
+        assert_eq!(
+            test.executor
+                .metrics
+                .notifier_aborted_steps
+                .with_label_values(&["dwallet_checkpoint"])
+                .get(),
+            1
+        );
+
+        // The checkpoint is not dry-run again until the on-chain state it reads changes.
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, None);
+        assert_eq!(test.mock.calls("dry_run_transaction_block"), 1);
+
+        // Once the coordinator is migrated to the upgraded package, the same checkpoint
+        // is submitted.
+        test.mock.set_dwallet_coordinator_version(2);
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(1));
+        assert_eq!(test.mock.executed_transactions().len(), 1);
+
+        // A transient abort is retried until the dry-run succeeds,
+        // and the transaction is submitted with a budget sized from the dry-run.
+        test.insert_certified_dwallet_checkpoint(2);
+        test.mock.fail_next_dry_run(
+            "MoveAbort(MoveLocation { module: ModuleId { address: 0000000000000000000000000000000000000000000000000000000000000abc, name: Identifier(\"coordinator_inner\") }, function: 87, instruction: 41, function_name: Some(\"process_checkpoint_message\") }, 24) in command 0".to_string(),
+        );
+        test.submit_dwallet_checkpoints(1, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(2));
+        assert_eq!(test.mock.calls("dry_run_transaction_block"), 4);
+        let executed_transactions = test.mock.executed_transactions();
+        assert_eq!(executed_transactions.len(), 2);
+        assert!(executed_transactions[1].transaction_data().gas_budget() < NOTIFIER_GAS_BUDGET);
+    }
+
+    fn checkpoint(sequence_number: u64, message_len: usize) -> CheckpointSubmission {
+        CheckpointSubmission {
+            epoch: EPOCH,
+            sequence_number,
+            signature: vec![],
+            signers_bitmap: vec![],
+            message: vec![0; message_len],
+        }
+    }
+
+    #[test]
+    fn batch_stops_at_first_missing_checkpoint() {
+        let batch = collect_checkpoint_batch(5, |sequence_number| {
+            Ok((sequence_number < 8).then(|| checkpoint(sequence_number, 100)))
+        })
+        .unwrap();
+        let sequence_numbers: Vec<_> = batch.iter().map(|c| c.sequence_number).collect();
+        assert_eq!(sequence_numbers, vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn batch_is_bounded_by_count_and_size() {
+        let batch = collect_checkpoint_batch(1, |sequence_number| {
+            Ok(Some(checkpoint(sequence_number, 1)))
+        })
+        .unwrap();
+        assert_eq!(batch.len(), MAX_CHECKPOINTS_PER_SUI_TRANSACTION);
+
+        let batch = collect_checkpoint_batch(1, |sequence_number| {
+            Ok(Some(checkpoint(
+                sequence_number,
+                MAX_CHECKPOINT_BATCH_MESSAGE_BYTES / 2,
+            )))
+        })
+        .unwrap();
+        assert_eq!(batch.len(), 2);
+
+        // A single oversized checkpoint is still submitted on its own.
+        let batch = collect_checkpoint_batch(1, |sequence_number| {
+            Ok(Some(checkpoint(
+                sequence_number,
+                MAX_CHECKPOINT_BATCH_MESSAGE_BYTES + 1,
+            )))
         })
-        .collect();
+        .unwrap();
+        assert_eq!(batch.len(), 1);
+    }
 
-    let coins = coins?;
+    #[test]
+    fn batch_stops_at_epoch_change() {
+        let batch = collect_checkpoint_batch(1, |sequence_number| {
+            Ok(Some(CheckpointSubmission {
+                epoch: if sequence_number < 3 {
+                    EPOCH
+                } else {
+                    EPOCH + 1
+                },
+                ..checkpoint(sequence_number, 1)
+            }))
+        })
+        .unwrap();
+        let sequence_numbers: Vec<_> = batch.iter().map(|c| c.sequence_number).collect();
+        assert_eq!(sequence_numbers, vec![1, 2]);
+    }
 
-    ptb.command(sui_types::transaction::Command::MergeCoins(
-        Argument::GasCoin,
-        coins,
-    ));
+    #[tokio::test]
+    async fn failed_batch_falls_back_to_single_checkpoints() {
+        let test = TestExecutor::new();
+        for sequence_number in 1..=3 {
+            test.insert_certified_dwallet_checkpoint(sequence_number);
+        }
+        let mut last_submitted_checkpoint = None;
+
+        // The batch fails, so only its first checkpoint is submitted.
+        test.mock
+            .fail_next_calls("execute_transaction_block_with_effects", 1);
+        test.submit_dwallet_checkpoints(0, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(1));
 
-    Ok(())
+        // The rest are batched again once the first one is processed.
+        test.submit_dwallet_checkpoints(1, &mut last_submitted_checkpoint)
+            .await;
+        assert_eq!(last_submitted_checkpoint, Some(3));
+        assert_eq!(
+            test.executed_calls(
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME
+            ),
+            vec![1, 2]
+        );
+    }
 }
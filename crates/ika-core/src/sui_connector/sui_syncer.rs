@@ -3,37 +3,60 @@
 
 //! The SuiSyncer module handles synchronizing Events emitted
 //! on the Sui blockchain from concerned modules of `ika_system` package.
+use crate::authority::authority_perpetual_tables::AuthorityPerpetualTables;
 use crate::sui_connector::metrics::SuiConnectorMetrics;
 use dwallet_mpc_types::dwallet_mpc::MPCDataTrait;
+use futures::FutureExt;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use ika_network::network_keys;
 use ika_sui_client::{SuiClient, SuiClientInner, retry_with_max_elapsed_time};
 use ika_types::committee::{ClassGroupsEncryptionKeyAndProof, Committee, EpochId, StakeUnit};
 use ika_types::crypto::AuthorityName;
 use ika_types::dwallet_mpc_error::{DwalletMPCError, DwalletMPCResult};
-use ika_types::error::IkaResult;
+use ika_types::error::{IkaError, IkaResult};
 use ika_types::messages_dwallet_mpc::{
     DBSuiEvent, DWalletNetworkEncryptionKey, DWalletNetworkEncryptionKeyData,
     DWalletNetworkEncryptionKeyState,
 };
 use ika_types::sui::{DWalletCoordinatorInner, SystemInner, SystemInnerTrait};
 use mysten_metrics::spawn_logged_monitored_task;
-use std::{collections::HashMap, sync::Arc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use sui_json_rpc_types::SuiEvent;
 use sui_types::base_types::ObjectID;
 use sui_types::{Identifier, event::EventID};
-use tokio::sync::watch::Sender;
+use tokio::sync::watch::{self, Sender};
 use tokio::{
     sync::Notify,
     task::JoinHandle,
-    time::{self, Duration},
+    time::{self, Duration, Instant},
 };
 use tracing::{debug, error, info, warn};
 
+/// How often the epoch start cursor is read from the system object.
+const EPOCH_START_CURSOR_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait before subscribing to the Sui checkpoints again after the subscription
+/// failed or was interrupted, while the events are polled.
+const CHECKPOINT_RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The most batches of events sent to the consumers that are remembered until they are handled.
+/// Nodes that don't handle events, or consumers that fall this far behind,
+/// resume from an earlier cursor after a restart.
+const MAX_UNHANDLED_EVENT_BATCHES: usize = 1_000;
+
 pub struct SuiSyncer<C> {
     sui_client: Arc<SuiClient<C>>,
     // The last transaction that the syncer has fully processed.
     // Syncer will resume posting this transaction (i.e., exclusive) when it starts.
     modules: Vec<Identifier>,
+    perpetual_tables: Arc<AuthorityPerpetualTables>,
+    /// Read the events of each module from its cursor after startup, instead of the persisted one.
+    cursor_overrides: HashMap<Identifier, EventID>,
     metrics: Arc<SuiConnectorMetrics>,
+    /// Fetches the public outputs of the network encryption keys from peers, and serves them.
+    network_keys: network_keys::Handle,
 }
 
 impl<C> SuiSyncer<C>
@@ -43,12 +66,26 @@ where
     pub fn new(
         sui_client: Arc<SuiClient<C>>,
         modules: Vec<Identifier>,
+        perpetual_tables: Arc<AuthorityPerpetualTables>,
+        cursor_overrides: HashMap<Identifier, EventID>,
         metrics: Arc<SuiConnectorMetrics>,
+        network_keys: network_keys::Handle,
     ) -> Self {
+        for module in cursor_overrides.keys() {
+            if !modules.contains(module) {
+                warn!(
+                    ?module,
+                    "Ignoring the Sui events cursor override of a module whose events are not read"
+                );
+            }
+        }
         Self {
             sui_client,
             modules,
+            perpetual_tables,
+            cursor_overrides,
             metrics,
+            network_keys,
         }
     }
 
@@ -62,6 +99,7 @@ where
         end_of_publish_sender: Sender<Option<u64>>,
         last_session_to_complete_in_current_epoch_sender: Sender<(EpochId, u64)>,
         uncompleted_events_sender: Sender<(Vec<DBSuiEvent>, EpochId)>,
+        handled_events_receiver: watch::Receiver<Option<EventID>>,
     ) -> IkaResult<Vec<JoinHandle<()>>> {
         info!("Starting SuiSyncer");
         let mut task_handles = vec![];
@@ -70,6 +108,7 @@ where
         info!("Starting network keys sync task");
         tokio::spawn(Self::sync_dwallet_network_keys(
             sui_client_clone.clone(),
+            self.network_keys.clone(),
             network_keys_sender,
         ));
         if is_validator {
@@ -99,13 +138,17 @@ where
             let metrics = self.metrics.clone();
             let sui_client_clone = self.sui_client.clone();
             let new_events_sender_clone = new_events_sender.clone();
+            let cursor_override = self.cursor_overrides.get(&module).copied();
             task_handles.push(spawn_logged_monitored_task!(
                 Self::run_event_listening_task(
                     module,
                     sui_client_clone,
+                    self.perpetual_tables.clone(),
+                    cursor_override,
                     query_interval,
                     metrics,
                     new_events_sender_clone,
+                    handled_events_receiver.clone(),
                 )
             ));
         }
@@ -271,6 +314,7 @@ where
     /// Sync the DwalletMPC network keys from the Sui client to the local store.
     async fn sync_dwallet_network_keys(
         sui_client: Arc<SuiClient<C>>,
+        network_keys: network_keys::Handle,
         network_keys_sender: Sender<Arc<HashMap<ObjectID, DWalletNetworkEncryptionKeyData>>>,
     ) {
         // Last fetched network keys (id to epoch) to avoid fetching the same keys repeatedly.
@@ -310,12 +354,13 @@ where
             let mut all_fetched_network_keys_data: HashMap<_, _> =
                 (*network_keys_sender.borrow().clone()).clone();
             for (key_id, network_dec_key_shares) in keys_to_fetch.into_iter() {
-                match sui_client
-                    .get_network_encryption_key_with_full_data_by_epoch(
-                        &network_dec_key_shares,
-                        current_epoch,
-                    )
-                    .await
+                match Self::get_network_encryption_key_data(
+                    &sui_client,
+                    &network_keys,
+                    &network_dec_key_shares,
+                    current_epoch,
+                )
+                .await
                 {
                     Ok(key_full_data) => {
                         all_fetched_network_keys_data.insert(key_id, key_full_data.clone());
@@ -338,6 +383,78 @@ where
         }
     }
 
+    /// Reads the data of the network encryption key `key` at `epoch`, and serves its public
+    /// outputs to peers.
+    async fn get_network_encryption_key_data(
+        sui_client: &SuiClient<C>,
+        network_keys: &network_keys::Handle,
+        key: &DWalletNetworkEncryptionKey,
+        epoch: EpochId,
+    ) -> IkaResult<DWalletNetworkEncryptionKeyData> {
+        let mut tables = HashMap::new();
+        let network_dkg_public_output = Self::read_public_output(
+            sui_client,
+            network_keys,
+            key.network_dkg_public_output.contents.id,
+            &mut tables,
+        )
+        .await?;
+
+        // A key created at the current epoch has no reconfiguration public output for it.
+        let current_reconfiguration_public_output = if key.dkg_at_epoch == epoch {
+            vec![]
+        } else {
+            let table_id = sui_client
+                .get_current_reconfiguration_public_output(
+                    epoch,
+                    key.reconfiguration_public_outputs.id,
+                )
+                .await?;
+            Self::read_public_output(sui_client, network_keys, table_id, &mut tables).await?
+        };
+
+        network_keys.serve_network_key(key.id, tables);
+        Ok(DWalletNetworkEncryptionKeyData {
+            id: key.id,
+            current_epoch: epoch,
+            current_reconfiguration_public_output,
+            network_dkg_public_output,
+            state: key.state.clone(),
+        })
+    }
+
+    /// Reads the public output held by the `TableVec` `table_id` from peers, verified against
+    /// the chunk digests on Sui, or from Sui if no peer serves it, and adds its chunks to `tables`.
+    async fn read_public_output(
+        sui_client: &SuiClient<C>,
+        network_keys: &network_keys::Handle,
+        table_id: ObjectID,
+        tables: &mut network_keys::TableVecs,
+    ) -> IkaResult<Vec<u8>> {
+        let chunk_refs = sui_client.get_table_vec_chunk_refs(table_id).await?;
+        let chunks = match network_keys.fetch_table_vec(table_id, &chunk_refs).await {
+            Some(chunks) => {
+                info!(
+                    ?table_id,
+                    "Fetched a network encryption key public output from peers"
+                );
+                chunks
+            }
+            None => {
+                debug!(
+                    ?table_id,
+                    "No peer serves the network encryption key public output, reading it from Sui"
+                );
+                sui_client.read_table_vec_chunks(table_id).await?
+            }
+        };
+        let public_output = network_keys::table_vec_bytes(&chunks).map_err(|e| {
+            IkaError::SuiClientInternalError(format!("invalid chunks of table {table_id}: {e}"))
+        })?;
+        tables.insert(table_id, Arc::new(chunks));
+        Ok(public_output)
+    }
+
     async fn sync_dwallet_end_of_publish(
         sui_client: Arc<SuiClient<C>>,
         end_of_publish_sender: Sender<Option<u64>>,
@@ -394,9 +511,12 @@ where
         // Module is always of ika system package.
         module: Identifier,
         sui_client: Arc<SuiClient<C>>,
+        perpetual_tables: Arc<AuthorityPerpetualTables>,
+        cursor_override: Option<EventID>,
         query_interval: Duration,
         metrics: Arc<SuiConnectorMetrics>,
         new_events_sender: tokio::sync::broadcast::Sender<Vec<SuiEvent>>,
+        handled_events_receiver: watch::Receiver<Option<EventID>>,
     ) {
         info!(?module, "Starting sui events listening task");
         let mut interval = time::interval(query_interval);
@@ -424,13 +544,81 @@ where
                 last_synced_sui_checkpoints_metric.set(latest_checkpoint_sequence_number as i64);
             }
         });
+        let checkpoint_stream_active_metric = metrics
+            .sui_checkpoint_stream_active
+            .with_label_values(&[&module.to_string()]);
+        let mut checkpoints: Option<BoxStream<'static, IkaResult<u64>>> = None;
+        let mut last_subscribe_attempt: Option<Instant> = None;
+        let reprocessed_events_metric = metrics
+            .sui_events_reprocessed_after_startup
+            .with_label_values(&[&module.to_string()]);
         let mut cursor: Option<EventID> = None;
+        // The epoch in which the events after `cursor` are read.
+        let mut cursor_epoch: EpochId = 0;
+        // The last event delivered before the restart, while the events up to it are read again,
+        // and the number of events read since startup that precede it.
+        let mut last_delivered_before_restart: Option<EventID> = None;
+        let mut events_read_before_last_delivered = 0;
         let mut start_epoch_cursor: Option<EventID> = None;
-        let mut loop_index: usize = 0;
+        let mut last_epoch_start_check: Option<Instant> = None;
+        // The last event of each batch sent to the consumers in `cursor_epoch`
+        // that they have not yet reported as handled.
+        let mut unhandled_batches: VecDeque<EventID> = VecDeque::new();
         loop {
+            if checkpoints.is_none()
+                && last_subscribe_attempt
+                    .is_none_or(|attempt| attempt.elapsed() >= CHECKPOINT_RESUBSCRIBE_INTERVAL)
+            {
+                last_subscribe_attempt = Some(Instant::now());
+                match sui_client.subscribe_checkpoints().await {
+                    Ok(stream) => {
+                        info!(
+                            ?module,
+                            "Reading the Sui events on every new Sui checkpoint"
+                        );
+                        checkpoints = Some(stream);
+                        checkpoint_stream_active_metric.set(1);
+                    }
+                    Err(err) => {
+                        warn!(
+                            ?module,
+                            error=?err,
+                            "failed to subscribe to the Sui checkpoints, polling for events instead"
+                        );
+                    }
+                }
+            }
+
+            match checkpoints.as_mut() {
+                Some(stream) => {
+                    if !Self::wait_for_next_checkpoint(stream, &mut interval).await {
+                        warn!(
+                            ?module,
+                            "the Sui checkpoints subscription was interrupted, \
+                             polling for events instead"
+                        );
+                        checkpoints = None;
+                        checkpoint_stream_active_metric.set(0);
+                    }
+                }
+                None => {
+                    interval.tick().await;
+                }
+            }
+
+            Self::persist_handled_cursor(
+                &module,
+                &perpetual_tables,
+                cursor_epoch,
+                &mut unhandled_batches,
+                &handled_events_receiver,
+            );
+
             // Fetching the epoch start TX digest less frequently
             // as it is unexpected to change often.
-            if loop_index % 10 == 0 {
+            if last_epoch_start_check
+                .is_none_or(|last_check| last_check.elapsed() >= EPOCH_START_CURSOR_CHECK_INTERVAL)
+            {
                 debug!("Querying epoch start cursor from Sui");
                 let SystemInner::V1(system_inner) = sui_client.must_get_system_inner_object().await;
                 let Ok(epoch_start_tx_digest) = system_inner.epoch_start_tx_digest.try_into()
@@ -439,32 +627,78 @@ where
                     error!("cloud not parse `epoch_start_tx_digest` - wrong length");
                     continue;
                 };
+                last_epoch_start_check = Some(Instant::now());
                 let start_epoch_event = EventID::from((epoch_start_tx_digest, 0));
                 if start_epoch_cursor != Some(start_epoch_event) {
+                    let is_startup = start_epoch_cursor.is_none();
                     start_epoch_cursor = Some(start_epoch_event);
+                    cursor_epoch = system_inner.epoch;
+                    // A new epoch re-reads its events from its start, as the events read by the
+                    // previous epoch were delivered to its consumers.
                     cursor = start_epoch_cursor;
+                    unhandled_batches.clear();
+                    last_delivered_before_restart = None;
+                    if is_startup {
+                        let (startup_cursor, last_delivered) = Self::startup_cursor(
+                            &module,
+                            &perpetual_tables,
+                            cursor_override,
+                            cursor_epoch,
+                            start_epoch_event,
+                        );
+                        cursor = Some(startup_cursor);
+                        last_delivered_before_restart = last_delivered;
+                        events_read_before_last_delivered = 0;
+                    }
                 }
             }
-            loop_index += 1;
-
-            interval.tick().await;
-            let Ok(Ok(events)) = retry_with_max_elapsed_time!(
-                sui_client.query_events_by_module(module.clone(), cursor),
-                Duration::from_secs(120)
-            ) else {
-                // todo(zeev): alert.
-                warn!("sui client failed to query events from the sui network — retrying");
-                continue;
-            };
 
-            let len = events.data.len();
-            if len != 0 {
+            // Read all the pages of events that were emitted since the last read.
+            // A new checkpoint only signals that new events may exist: they are always read
+            // from the cursor, so they are delivered in checkpoint order and none is skipped.
+            loop {
+                let Ok(Ok(events)) = retry_with_max_elapsed_time!(
+                    sui_client.query_events_by_module(module.clone(), cursor),
+                    Duration::from_secs(120)
+                ) else {
+                    // todo(zeev): alert.
+                    warn!("sui client failed to query events from the sui network — retrying");
+                    break;
+                };
+
+                let len = events.data.len();
+                if len == 0 {
+                    break;
+                }
+                if let Some(last_delivered) = last_delivered_before_restart {
+                    match events
+                        .data
+                        .iter()
+                        .position(|event| event.id == last_delivered)
+                    {
+                        Some(index) => {
+                            reprocessed_events_metric
+                                .inc_by((events_read_before_last_delivered + index + 1) as u64);
+                            last_delivered_before_restart = None;
+                        }
+                        // Caught up without passing it, so the reading started after it.
+                        None if !events.has_next_page => {
+                            last_delivered_before_restart = None;
+                        }
+                        None => events_read_before_last_delivered += len,
+                    }
+                }
                 if !events.has_next_page {
                     // If this is the last page, it means we have processed all
                     // events up to the latest checkpoint
                     // We can then update the latest checkpoint metric.
                     notify.notify_one();
                 }
+                // Recorded before sending, so a report that the batch was handled is not missed.
+                unhandled_batches.extend(events.data.last().map(|event| event.id));
+                if unhandled_batches.len() > MAX_UNHANDLED_EVENT_BATCHES {
+                    unhandled_batches.pop_front();
+                }
                 if let Err(e) = new_events_sender.send(events.data) {
                     error!(error=?e, ?module, "failed to send new events to the channel");
                 }
@@ -477,7 +711,412 @@ where
                     ?cursor,
                     "Observed {len} new events from Sui network"
                 );
+                if !events.has_next_page {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Waits until the Sui fullnode announces a new checkpoint, or for the next `interval` tick
+    /// in case the events of an announced checkpoint were not indexed yet when they were read.
+    /// Returns false if the subscription was interrupted.
+    async fn wait_for_next_checkpoint(
+        stream: &mut BoxStream<'static, IkaResult<u64>>,
+        interval: &mut time::Interval,
+    ) -> bool {
+        let mut checkpoint = tokio::select! {
+            checkpoint = stream.next() => checkpoint,
+            _ = interval.tick() => return true,
+        };
+        loop {
+            match checkpoint {
+                Some(Ok(_)) => {}
+                Some(Err(err)) => {
+                    warn!(error=?err, "received an error from the Sui checkpoints subscription");
+                    return false;
+                }
+                None => return false,
             }
+            // The checkpoints announced meanwhile are covered by a single read.
+            match stream.next().now_or_never() {
+                Some(next) => checkpoint = next,
+                None => break,
+            }
+        }
+        interval.reset();
+        true
+    }
+
+    /// Persists the last event that the consumers reported as handled as the cursor of `module`,
+    /// if it ends one of the `unhandled_batches`, which it removes up to it.
+    /// The cursor is persisted only once the events were handled,
+    /// so that events sent right before the node stopped are read again after it restarts.
+    fn persist_handled_cursor(
+        module: &Identifier,
+        perpetual_tables: &AuthorityPerpetualTables,
+        cursor_epoch: EpochId,
+        unhandled_batches: &mut VecDeque<EventID>,
+        handled_events_receiver: &watch::Receiver<Option<EventID>>,
+    ) {
+        let Some(handled) = *handled_events_receiver.borrow() else {
+            return;
+        };
+        // The consumers may report events of other modules, or of a previous epoch.
+        let Some(position) = unhandled_batches.iter().position(|last| *last == handled) else {
+            return;
+        };
+        unhandled_batches.drain(..=position);
+        if let Err(err) =
+            perpetual_tables.set_sui_event_cursor(module.as_str(), cursor_epoch, handled)
+        {
+            error!(error=?err, ?module, "failed to persist the Sui events cursor");
         }
     }
+
+    /// Picks the cursor to read the events of `module` from after startup: the configured
+    /// override, or else the cursor persisted in the current `epoch`, or else the epoch's start.
+    /// Also returns the last event delivered before the restart,
+    /// if the reading starts from an earlier cursor.
+    fn startup_cursor(
+        module: &Identifier,
+        perpetual_tables: &AuthorityPerpetualTables,
+        cursor_override: Option<EventID>,
+        epoch: EpochId,
+        epoch_start_cursor: EventID,
+    ) -> (EventID, Option<EventID>) {
+        let persisted_cursor = match perpetual_tables.get_sui_event_cursor(module.as_str()) {
+            Ok(Some((persisted_epoch, persisted_cursor))) if persisted_epoch == epoch => {
+                Some(persisted_cursor)
+            }
+            // A cursor of a previous epoch is reset to the start of the current one.
+            Ok(_) => None,
+            Err(err) => {
+                error!(error=?err, ?module, "failed to read the persisted Sui events cursor");
+                None
+            }
+        };
+        match (cursor_override, persisted_cursor) {
+            (Some(cursor_override), persisted_cursor) => {
+                info!(
+                    ?module,
+                    ?cursor_override,
+                    "Reading Sui events from the configured cursor"
+                );
+                (
+                    cursor_override,
+                    persisted_cursor
+                        .filter(|persisted_cursor| *persisted_cursor != cursor_override),
+                )
+            }
+            (None, Some(persisted_cursor)) => {
+                info!(
+                    ?module,
+                    ?persisted_cursor,
+                    "Resuming reading Sui events from the persisted cursor"
+                );
+                (persisted_cursor, None)
+            }
+            (None, None) => (epoch_start_cursor, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ika_sui_client::sui_mock_client::{
+        SuiMockClient, bls_committee_for_testing, system_inner_v1_for_testing,
+    };
+    use sui_json_rpc_types::BcsEvent;
+    use sui_types::base_types::{SuiAddress, TransactionDigest};
+    use tempfile::TempDir;
+    use tokio::sync::broadcast;
+
+    const EPOCH: EpochId = 1;
+    const QUERY_INTERVAL: Duration = Duration::from_millis(10);
+
+    struct TestListener {
+        mock: SuiMockClient,
+        sui_client: Arc<SuiClient<SuiMockClient>>,
+        perpetual_tables: Arc<AuthorityPerpetualTables>,
+        module: Identifier,
+        _store_dir: TempDir,
+    }
+
+    impl TestListener {
+        /// A mocked chain in `EPOCH`, whose start transaction emitted the first event of `module`.
+        fn new() -> Self {
+            let mock = SuiMockClient::new();
+            let sui_client = Arc::new(SuiClient::new_for_testing(mock.clone()));
+            let store_dir = tempfile::tempdir().unwrap();
+            let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(store_dir.path(), None));
+            let listener = Self {
+                mock,
+                sui_client,
+                perpetual_tables,
+                module: Identifier::new("sessions_manager").unwrap(),
+                _store_dir: store_dir,
+            };
+            let epoch_start_event = listener.event(TransactionDigest::random(), 0);
+            let mut system_inner = system_inner_v1_for_testing(EPOCH, bls_committee_for_testing(4));
+            system_inner.epoch_start_tx_digest = epoch_start_event.id.tx_digest.inner().to_vec();
+            listener.mock.set_system_inner(system_inner);
+            listener.mock.emit_event(epoch_start_event);
+            listener
+        }
+
+        fn event(&self, tx_digest: TransactionDigest, event_seq: u64) -> SuiEvent {
+            let package = self.sui_client.ika_dwallet_2pc_mpc_package_id();
+            SuiEvent {
+                id: EventID {
+                    tx_digest,
+                    event_seq,
+                },
+                package_id: package,
+                transaction_module: self.module.clone(),
+                sender: SuiAddress::random_for_testing_only(),
+                type_: move_core_types::language_storage::StructTag {
+                    address: package.into(),
+                    module: self.module.clone(),
+                    name: Identifier::new("Event").unwrap(),
+                    type_params: vec![],
+                },
+                parsed_json: Default::default(),
+                bcs: BcsEvent::new(vec![]),
+                timestamp_ms: None,
+            }
+        }
+
+        /// Emits `count` events in a single transaction, and returns their ids.
+        fn emit_events(&self, count: u64) -> Vec<EventID> {
+            let tx_digest = TransactionDigest::random();
+            (0..count)
+                .map(|event_seq| {
+                    let event = self.event(tx_digest, event_seq);
+                    let id = event.id;
+                    self.mock.emit_event(event);
+                    id
+                })
+                .collect()
+        }
+
+        fn spawn(
+            &self,
+        ) -> (
+            JoinHandle<()>,
+            broadcast::Receiver<Vec<SuiEvent>>,
+            watch::Sender<Option<EventID>>,
+        ) {
+            self.spawn_with_query_interval(QUERY_INTERVAL)
+        }
+
+        fn spawn_with_query_interval(
+            &self,
+            query_interval: Duration,
+        ) -> (
+            JoinHandle<()>,
+            broadcast::Receiver<Vec<SuiEvent>>,
+            watch::Sender<Option<EventID>>,
+        ) {
+            let (new_events_sender, new_events_receiver) = broadcast::channel(100);
+            let (handled_events_sender, handled_events_receiver) = watch::channel(None);
+            let task = tokio::spawn(SuiSyncer::<SuiMockClient>::run_event_listening_task(
+                self.module.clone(),
+                self.sui_client.clone(),
+                self.perpetual_tables.clone(),
+                None,
+                query_interval,
+                SuiConnectorMetrics::new_for_testing(),
+                new_events_sender,
+                handled_events_receiver,
+            ));
+            (task, new_events_receiver, handled_events_sender)
+        }
+
+        fn persisted_cursor(&self) -> Option<(EpochId, EventID)> {
+            self.perpetual_tables
+                .get_sui_event_cursor(self.module.as_str())
+                .unwrap()
+        }
+
+        /// Waits until the listener persists `cursor`.
+        async fn wait_for_persisted_cursor(&self, cursor: EventID) {
+            time::timeout(Duration::from_secs(10), async {
+                while self.persisted_cursor() != Some((EPOCH, cursor)) {
+                    time::sleep(QUERY_INTERVAL).await;
+                }
+            })
+            .await
+            .expect("the cursor was not persisted");
+        }
+    }
+
+    /// Receives the ids of the next `count` delivered events.
+    async fn receive_events(
+        receiver: &mut broadcast::Receiver<Vec<SuiEvent>>,
+        count: usize,
+    ) -> Vec<EventID> {
+        let mut ids = vec![];
+        while ids.len() < count {
+            let events = time::timeout(Duration::from_secs(10), receiver.recv())
+                .await
+                .expect("the events were not delivered")
+                .unwrap();
+            ids.extend(events.iter().map(|event| event.id));
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn polls_events_in_order_across_pages() {
+        let listener = TestListener::new();
+        // More than two pages of the mocked chain.
+        let emitted = listener.emit_events(120);
+        let (task, mut receiver, _handled_events_sender) = listener.spawn();
+
+        assert_eq!(receive_events(&mut receiver, 120).await, emitted);
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn polls_events_emitted_after_startup() {
+        let listener = TestListener::new();
+        let (task, mut receiver, _handled_events_sender) = listener.spawn();
+
+        let first = listener.emit_events(3);
+        assert_eq!(receive_events(&mut receiver, 3).await, first);
+        // Later polls read only the events emitted since.
+        let second = listener.emit_events(2);
+        assert_eq!(receive_events(&mut receiver, 2).await, second);
+        assert!(receiver.try_recv().is_err());
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn reads_events_on_every_new_checkpoint() {
+        let listener = TestListener::new();
+        // Only the first poll, at startup, happens during the test.
+        let (task, mut receiver, _handled_events_sender) =
+            listener.spawn_with_query_interval(Duration::from_secs(3600));
+
+        let first = listener.emit_events(3);
+        assert_eq!(receive_events(&mut receiver, 3).await, first);
+        let second = listener.emit_events(2);
+        assert_eq!(receive_events(&mut receiver, 2).await, second);
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn polls_events_when_checkpoints_cannot_be_subscribed_to() {
+        let listener = TestListener::new();
+        listener
+            .mock
+            .fail_next_calls("subscribe_checkpoints", usize::MAX);
+        let (task, mut receiver, _handled_events_sender) = listener.spawn();
+
+        let emitted = listener.emit_events(3);
+        assert_eq!(receive_events(&mut receiver, 3).await, emitted);
+        assert_eq!(listener.mock.calls("subscribe_checkpoints"), 1);
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn persists_the_cursor_once_the_events_were_handled() {
+        let listener = TestListener::new();
+        let emitted = listener.emit_events(120);
+        let (task, mut receiver, handled_events_sender) = listener.spawn();
+        receive_events(&mut receiver, 120).await;
+
+        // Sent but not handled events are read again after a restart.
+        time::sleep(QUERY_INTERVAL * 5).await;
+        assert_eq!(listener.persisted_cursor(), None);
+
+        // The first page was handled.
+        handled_events_sender.send_replace(Some(emitted[49]));
+        listener.wait_for_persisted_cursor(emitted[49]).await;
+        // An event that does not end a batch is not a cursor of this module.
+        handled_events_sender.send_replace(Some(emitted[50]));
+        time::sleep(QUERY_INTERVAL * 5).await;
+        assert_eq!(listener.persisted_cursor(), Some((EPOCH, emitted[49])));
+
+        handled_events_sender.send_replace(Some(*emitted.last().unwrap()));
+        listener
+            .wait_for_persisted_cursor(*emitted.last().unwrap())
+            .await;
+        task.abort();
+    }
+
+    fn startup_cursor(
+        listener: &TestListener,
+        cursor_override: Option<EventID>,
+    ) -> (EventID, Option<EventID>) {
+        SuiSyncer::<SuiMockClient>::startup_cursor(
+            &listener.module,
+            &listener.perpetual_tables,
+            cursor_override,
+            EPOCH,
+            epoch_start_cursor(),
+        )
+    }
+
+    fn epoch_start_cursor() -> EventID {
+        EventID::from((TransactionDigest::new([1; 32]), 0))
+    }
+
+    fn cursor(seed: u8) -> EventID {
+        EventID::from((TransactionDigest::new([seed; 32]), 0))
+    }
+
+    #[tokio::test]
+    async fn startup_cursor_defaults_to_the_epoch_start() {
+        let listener = TestListener::new();
+        assert_eq!(
+            startup_cursor(&listener, None),
+            (epoch_start_cursor(), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn startup_cursor_resumes_from_the_persisted_cursor_of_the_epoch() {
+        let listener = TestListener::new();
+        listener
+            .perpetual_tables
+            .set_sui_event_cursor(listener.module.as_str(), EPOCH, cursor(2))
+            .unwrap();
+        assert_eq!(startup_cursor(&listener, None), (cursor(2), None));
+
+        // A cursor of a previous epoch is not resumed from.
+        listener
+            .perpetual_tables
+            .set_sui_event_cursor(listener.module.as_str(), EPOCH - 1, cursor(2))
+            .unwrap();
+        assert_eq!(
+            startup_cursor(&listener, None),
+            (epoch_start_cursor(), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn startup_cursor_prefers_the_override() {
+        let listener = TestListener::new();
+        assert_eq!(
+            startup_cursor(&listener, Some(cursor(3))),
+            (cursor(3), None)
+        );
+
+        // The events up to the persisted cursor are counted as read again.
+        listener
+            .perpetual_tables
+            .set_sui_event_cursor(listener.module.as_str(), EPOCH, cursor(2))
+            .unwrap();
+        assert_eq!(
+            startup_cursor(&listener, Some(cursor(3))),
+            (cursor(3), Some(cursor(2)))
+        );
+        assert_eq!(
+            startup_cursor(&listener, Some(cursor(2))),
+            (cursor(2), None)
+        );
+    }
 }
@@ -155,6 +155,11 @@ impl SystemCheckpointStore {
         )
     }
 
+    /// Writes a consistent RocksDB checkpoint of all the system_checkpoint tables to `path`.
+    pub fn checkpoint_db(&self, path: &Path) -> IkaResult {
+        self.watermarks.checkpoint_db(path).map_err(Into::into)
+    }
+
     pub fn get_system_checkpoint_by_digest(
         &self,
         digest: &SystemCheckpointMessageDigest,
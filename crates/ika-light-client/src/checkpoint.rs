@@ -0,0 +1,408 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::FORMAT_VERSION;
+use crate::committee::{LightCommittee, LightCommitteeMember};
+use crate::error::{LightClientError, LightClientResult};
+use crate::verifier::{AggregateSignatureVerifier, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use crate::wire::{Reader, Writer};
+use alloc::vec::Vec;
+
+/// `ika_types::intent::IntentVersion::V0`.
+const INTENT_VERSION: u8 = 0;
+/// `ika_types::intent::AppId::Ika`.
+const INTENT_APP_ID: u8 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointKind {
+    DWallet,
+    System,
+}
+
+impl CheckpointKind {
+    /// The matching `ika_types::intent::IntentScope`.
+    fn intent_scope(self) -> u8 {
+        match self {
+            CheckpointKind::DWallet => 1,
+            CheckpointKind::System => 2,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            CheckpointKind::DWallet => 0,
+            CheckpointKind::System => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> LightClientResult<Self> {
+        match value {
+            0 => Ok(CheckpointKind::DWallet),
+            1 => Ok(CheckpointKind::System),
+            _ => Err(LightClientError::InvalidEncoding(alloc::format!(
+                "unknown checkpoint kind {value}"
+            ))),
+        }
+    }
+}
+
+/// A certified dWallet or system checkpoint in the light client format.
+///
+/// `message` holds the BCS bytes of the `DWalletCheckpointMessage` or
+/// `SystemCheckpointMessage` exactly as they were signed, which is also
+/// what is submitted to the Ika contracts on Sui.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LightCertifiedCheckpoint {
+    pub kind: CheckpointKind,
+    pub message: Vec<u8>,
+    /// The epoch of the aggregate signature.
+    pub epoch: u64,
+    pub signature: [u8; SIGNATURE_LENGTH],
+    /// Committee indices of the signers, strictly increasing.
+    pub signers: Vec<u32>,
+}
+
+impl LightCertifiedCheckpoint {
+    /// The bytes the committee signed: `bcs(IntentMessage { intent, message }) || bcs(epoch)`.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 + self.message.len() + 8);
+        bytes.extend_from_slice(&[self.kind.intent_scope(), INTENT_VERSION, INTENT_APP_ID]);
+        bytes.extend_from_slice(&self.message);
+        bytes.extend_from_slice(&self.epoch.to_le_bytes());
+        bytes
+    }
+
+    /// Verifies that a quorum of `committee` signed this checkpoint.
+    pub fn verify_signature<V: AggregateSignatureVerifier>(
+        &self,
+        committee: &LightCommittee,
+        verifier: &V,
+    ) -> LightClientResult {
+        if self.epoch != committee.epoch {
+            return Err(LightClientError::WrongEpoch {
+                expected: committee.epoch,
+                actual: self.epoch,
+            });
+        }
+        let mut voting_power: u64 = 0;
+        let mut public_keys = Vec::with_capacity(self.signers.len());
+        let mut previous_signer = None;
+        for &signer in &self.signers {
+            if previous_signer.is_some_and(|previous| signer <= previous) {
+                return Err(LightClientError::UnsortedSigners);
+            }
+            previous_signer = Some(signer);
+            let member = committee
+                .members
+                .get(signer as usize)
+                .ok_or(LightClientError::UnknownSigner(signer))?;
+            voting_power = voting_power.saturating_add(member.voting_power);
+            public_keys.push(member.protocol_public_key);
+        }
+        if voting_power < committee.quorum_threshold {
+            return Err(LightClientError::InsufficientVotingPower {
+                voting_power,
+                quorum_threshold: committee.quorum_threshold,
+            });
+        }
+        if !verifier.verify_aggregate(&public_keys, &self.signing_bytes(), &self.signature) {
+            return Err(LightClientError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.u8(FORMAT_VERSION);
+        writer.u8(self.kind.to_u8());
+        writer.u64(self.epoch);
+        writer.raw(&self.signature);
+        writer.uleb128(self.signers.len() as u32);
+        for signer in &self.signers {
+            writer.u32(*signer);
+        }
+        writer.bytes(&self.message);
+        writer.into_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> LightClientResult<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.u8()?;
+        if version != FORMAT_VERSION {
+            return Err(LightClientError::UnsupportedFormatVersion(version));
+        }
+        let kind = CheckpointKind::from_u8(reader.u8()?)?;
+        let epoch = reader.u64()?;
+        let signature = reader.take_array()?;
+        let signers_len = reader.uleb128()? as usize;
+        let mut signers = Vec::with_capacity(signers_len.min(reader.remaining() / 4));
+        for _ in 0..signers_len {
+            signers.push(reader.u32()?);
+        }
+        let message = reader.bytes()?.to_vec();
+        reader.finish()?;
+        Ok(Self {
+            kind,
+            message,
+            epoch,
+            signature,
+            signers,
+        })
+    }
+}
+
+/// `ika_types::messages_dwallet_checkpoint::DWALLET_CHECKPOINT_MESSAGE_V2_MARKER`, encoded in
+/// place of the epoch at the start of a V2 `DWalletCheckpointMessage`.
+pub(crate) const DWALLET_CHECKPOINT_MESSAGE_V2_MARKER: u64 = u64::MAX;
+
+/// The decoded header of a `DWalletCheckpointMessage`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DWalletCheckpointHeader {
+    pub epoch: u64,
+    pub sequence_number: u64,
+    pub message_count: u32,
+    /// The Merkle root committed to by V2 checkpoints, built with the
+    /// `dwallet_checkpoint_messages_merkle_root` protocol feature.
+    pub messages_merkle_root: Option<[u8; 32]>,
+    /// The next epoch committee, committed to by the last V2 checkpoint of an epoch.
+    pub next_epoch_committee: Option<LightCommittee>,
+}
+
+impl DWalletCheckpointHeader {
+    pub fn decode(message: &[u8]) -> LightClientResult<Self> {
+        let mut reader = Reader::new(message);
+        let first = reader.u64()?;
+        let is_v2 = first == DWALLET_CHECKPOINT_MESSAGE_V2_MARKER;
+        let epoch = if is_v2 { reader.u64()? } else { first };
+        let sequence_number = reader.u64()?;
+        let message_count = reader.uleb128()?;
+        for _ in 0..message_count {
+            skip_dwallet_checkpoint_message_kind(&mut reader)?;
+        }
+        let mut messages_merkle_root = None;
+        let mut next_epoch_committee = None;
+        if is_v2 {
+            let commitments = reader.uleb128()?;
+            if commitments == 0 {
+                return Err(LightClientError::InvalidEncoding(
+                    "a V2 dwallet checkpoint must have commitments".into(),
+                ));
+            }
+            for _ in 0..commitments {
+                match reader.uleb128()? {
+                    // `DWalletCheckpointCommitment::MessagesMerkleRoot(Digest)`,
+                    // a digest is BCS-encoded as length-prefixed bytes.
+                    0 => {
+                        let root = reader.bytes()?;
+                        messages_merkle_root = Some(root.try_into().map_err(|_| {
+                            LightClientError::InvalidEncoding("merkle root must be 32 bytes".into())
+                        })?);
+                    }
+                    // `DWalletCheckpointCommitment::NextEpochCommittee(NextEpochCommittee)`.
+                    1 => next_epoch_committee = Some(decode_next_epoch_committee(&mut reader)?),
+                    tag => {
+                        return Err(LightClientError::InvalidEncoding(alloc::format!(
+                            "unknown dwallet checkpoint commitment {tag}"
+                        )));
+                    }
+                }
+            }
+        }
+        reader.finish()?;
+        Ok(Self {
+            epoch,
+            sequence_number,
+            message_count,
+            messages_merkle_root,
+            next_epoch_committee,
+        })
+    }
+}
+
+/// Decodes an `ika_types::messages_dwallet_checkpoint::NextEpochCommittee`.
+fn decode_next_epoch_committee(reader: &mut Reader<'_>) -> LightClientResult<LightCommittee> {
+    let epoch = reader.u64()?;
+    let len = reader.uleb128()? as usize;
+    let mut members = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        // An `AuthorityName` is BCS-encoded as length-prefixed bytes.
+        let protocol_public_key = reader.bytes()?.try_into().map_err(|_| {
+            LightClientError::InvalidEncoding(alloc::format!(
+                "protocol public keys must be {PUBLIC_KEY_LENGTH} bytes"
+            ))
+        })?;
+        members.push(LightCommitteeMember {
+            protocol_public_key,
+            voting_power: reader.u64()?,
+        });
+    }
+    let quorum_threshold = reader.u64()?;
+    LightCommittee::new(epoch, members, quorum_threshold)
+}
+
+/// Skips one `ika_types::message::DWalletCheckpointMessageKind`.
+fn skip_dwallet_checkpoint_message_kind(reader: &mut Reader<'_>) -> LightClientResult {
+    match reader.uleb128()? {
+        // RespondDWalletDKGFirstRoundOutput
+        0 => skip_fields(
+            reader,
+            &[Field::Bytes, Field::Bytes, Field::Bool, Field::U64],
+        ),
+        // RespondDWalletDKGSecondRoundOutput
+        1 => skip_fields(
+            reader,
+            &[
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bool,
+                Field::U64,
+            ],
+        ),
+        // RespondDWalletEncryptedUserShare
+        2 => skip_fields(
+            reader,
+            &[Field::Bytes, Field::Bytes, Field::Bool, Field::U64],
+        ),
+        // RespondMakeDWalletUserSecretKeySharesPublic
+        3 => skip_fields(
+            reader,
+            &[Field::Bytes, Field::Bytes, Field::Bool, Field::U64],
+        ),
+        // RespondDWalletImportedKeyVerificationOutput
+        4 => skip_fields(
+            reader,
+            &[
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bool,
+                Field::U64,
+            ],
+        ),
+        // RespondDWalletPresign
+        5 => skip_fields(
+            reader,
+            &[
+                Field::OptionBytes,
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bool,
+                Field::U64,
+            ],
+        ),
+        // RespondDWalletSign
+        6 => skip_fields(
+            reader,
+            &[
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bytes,
+                Field::Bool,
+                Field::Bool,
+                Field::U64,
+            ],
+        ),
+        // RespondDWalletPartialSignatureVerificationOutput
+        7 => skip_fields(
+            reader,
+            &[Field::Bytes, Field::Bytes, Field::Bool, Field::U64],
+        ),
+        // RespondDWalletMPCNetworkDKGOutput, RespondDWalletMPCNetworkReconfigurationOutput
+        8 | 9 => skip_fields(
+            reader,
+            &[
+                Field::Bytes,
+                Field::Bytes,
+                Field::U32Vec,
+                Field::Bool,
+                Field::Bool,
+                Field::U64,
+            ],
+        ),
+        // SetMaxActiveSessionsBuffer, SetGasFeeReimbursementSuiSystemCallValue
+        10 | 11 => skip_fields(reader, &[Field::U64]),
+        // EndOfPublish
+        12 => Ok(()),
+        tag => Err(LightClientError::InvalidEncoding(alloc::format!(
+            "unknown dwallet checkpoint message kind {tag}"
+        ))),
+    }
+}
+
+/// The decoded header of a `SystemCheckpointMessage`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemCheckpointHeader {
+    pub epoch: u64,
+    pub sequence_number: u64,
+    pub message_count: u32,
+    /// Whether the checkpoint contains `SystemCheckpointMessageKind::EndOfPublish`,
+    /// i.e. it is the last system checkpoint of its epoch.
+    pub end_of_publish: bool,
+}
+
+/// `SystemCheckpointMessageKind::EndOfPublish`.
+pub(crate) const SYSTEM_END_OF_PUBLISH_TAG: u32 = 10;
+
+impl SystemCheckpointHeader {
+    pub fn decode(message: &[u8]) -> LightClientResult<Self> {
+        let mut reader = Reader::new(message);
+        let epoch = reader.u64()?;
+        let sequence_number = reader.u64()?;
+        let message_count = reader.uleb128()?;
+        let mut end_of_publish = false;
+        for _ in 0..message_count {
+            let tag = reader.uleb128()?;
+            match tag {
+                // SetNextConfigVersion, SetEpochDurationMs, SetStakeSubsidyStartEpoch
+                0..=2 => skip_fields(&mut reader, &[Field::U64]),
+                // SetStakeSubsidyRate
+                3 => skip_fields(&mut reader, &[Field::U16]),
+                // SetStakeSubsidyPeriodLength, SetMinValidatorCount, SetMaxValidatorCount,
+                // SetMinValidatorJoiningStake, SetMaxValidatorChangeCount, SetRewardSlashingRate
+                4..=9 => skip_fields(&mut reader, &[Field::U64]),
+                SYSTEM_END_OF_PUBLISH_TAG => {
+                    end_of_publish = true;
+                    Ok(())
+                }
+                // SetApprovedUpgrade
+                11 => skip_fields(&mut reader, &[Field::Bytes, Field::OptionBytes]),
+                // SetOrRemoveWitnessApprovingAdvanceEpochMessageType
+                12 => skip_fields(&mut reader, &[Field::Bytes, Field::Bool]),
+                tag => Err(LightClientError::InvalidEncoding(alloc::format!(
+                    "unknown system checkpoint message kind {tag}"
+                ))),
+            }?;
+        }
+        reader.finish()?;
+        Ok(Self {
+            epoch,
+            sequence_number,
+            message_count,
+            end_of_publish,
+        })
+    }
+}
+
+enum Field {
+    Bool,
+    U16,
+    U64,
+    Bytes,
+    OptionBytes,
+    U32Vec,
+}
+
+fn skip_fields(reader: &mut Reader<'_>, fields: &[Field]) -> LightClientResult {
+    for field in fields {
+        match field {
+            Field::Bool => reader.bool().map(|_| ())?,
+            Field::U16 => reader.u16().map(|_| ())?,
+            Field::U64 => reader.u64().map(|_| ())?,
+            Field::Bytes => reader.skip_bytes()?,
+            Field::OptionBytes => reader.skip_option_bytes()?,
+            Field::U32Vec => reader.skip_u32_vec()?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,478 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::checkpoint::{
+    CheckpointKind, DWalletCheckpointHeader, LightCertifiedCheckpoint, SystemCheckpointHeader,
+};
+use crate::committee::LightCommittee;
+use crate::error::{LightClientError, LightClientResult};
+use crate::verifier::AggregateSignatureVerifier;
+
+/// Verifies certified checkpoints and follows the committee across epochs.
+///
+/// The Ika committee of the next epoch is elected on Sui, and the last dWallet checkpoint of
+/// each epoch (the one carrying `EndOfPublish`) commits to it. The light client only hands over
+/// to a next committee that matches the commitment of a checkpoint certified by the current
+/// committee, so the embedder can take the next committee from any source, e.g. the relayer's
+/// own full node. Checkpoints built before the `dwallet_checkpoint_messages_merkle_root`
+/// protocol feature carry no such commitment, and a light client cannot hand over past them.
+pub struct LightClient<V> {
+    committee: LightCommittee,
+    verifier: V,
+    end_of_publish_seen: bool,
+    next_committee: Option<LightCommittee>,
+    highest_dwallet_checkpoint: Option<u64>,
+    highest_system_checkpoint: Option<u64>,
+}
+
+impl<V: AggregateSignatureVerifier> LightClient<V> {
+    /// Creates a light client trusting `committee`, e.g. the genesis committee
+    /// or a committee read from a trusted Sui checkpoint.
+    pub fn new(committee: LightCommittee, verifier: V) -> Self {
+        Self {
+            committee,
+            verifier,
+            end_of_publish_seen: false,
+            next_committee: None,
+            highest_dwallet_checkpoint: None,
+            highest_system_checkpoint: None,
+        }
+    }
+
+    pub fn committee(&self) -> &LightCommittee {
+        &self.committee
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.committee.epoch
+    }
+
+    /// Whether the final system checkpoint of the current epoch was verified.
+    pub fn is_epoch_ended(&self) -> bool {
+        self.end_of_publish_seen
+    }
+
+    /// The next epoch committee, once a certified checkpoint of the current epoch committed to it.
+    pub fn next_committee(&self) -> Option<&LightCommittee> {
+        self.next_committee.as_ref()
+    }
+
+    pub fn highest_dwallet_checkpoint(&self) -> Option<u64> {
+        self.highest_dwallet_checkpoint
+    }
+
+    pub fn highest_system_checkpoint(&self) -> Option<u64> {
+        self.highest_system_checkpoint
+    }
+
+    pub fn verify_dwallet_checkpoint(
+        &mut self,
+        checkpoint: &LightCertifiedCheckpoint,
+    ) -> LightClientResult<DWalletCheckpointHeader> {
+        self.expect_kind(checkpoint, CheckpointKind::DWallet)?;
+        let header = DWalletCheckpointHeader::decode(&checkpoint.message)?;
+        self.verify(checkpoint, header.epoch)?;
+        if let Some(next_committee) = &header.next_epoch_committee {
+            let expected = self.committee.epoch + 1;
+            if next_committee.epoch != expected {
+                return Err(LightClientError::UnexpectedNextCommitteeEpoch {
+                    expected,
+                    actual: next_committee.epoch,
+                });
+            }
+            self.next_committee = Some(next_committee.clone());
+        }
+        self.highest_dwallet_checkpoint = self
+            .highest_dwallet_checkpoint
+            .max(Some(header.sequence_number));
+        Ok(header)
+    }
+
+    pub fn verify_system_checkpoint(
+        &mut self,
+        checkpoint: &LightCertifiedCheckpoint,
+    ) -> LightClientResult<SystemCheckpointHeader> {
+        self.expect_kind(checkpoint, CheckpointKind::System)?;
+        let header = SystemCheckpointHeader::decode(&checkpoint.message)?;
+        self.verify(checkpoint, header.epoch)?;
+        self.highest_system_checkpoint = self
+            .highest_system_checkpoint
+            .max(Some(header.sequence_number));
+        if header.end_of_publish {
+            self.end_of_publish_seen = true;
+        }
+        Ok(header)
+    }
+
+    /// Moves to the committee of the next epoch, which must match the one committed to
+    /// by the last dWallet checkpoint of the current epoch.
+    pub fn handover(&mut self, next_committee: LightCommittee) -> LightClientResult {
+        let Some(committed) = &self.next_committee else {
+            return Err(LightClientError::EpochNotEnded(self.committee.epoch));
+        };
+        let expected = self.committee.epoch + 1;
+        if next_committee.epoch != expected {
+            return Err(LightClientError::UnexpectedNextCommitteeEpoch {
+                expected,
+                actual: next_committee.epoch,
+            });
+        }
+        if &next_committee != committed {
+            return Err(LightClientError::NextCommitteeMismatch(
+                next_committee.epoch,
+            ));
+        }
+        self.committee = next_committee;
+        self.next_committee = None;
+        self.end_of_publish_seen = false;
+        Ok(())
+    }
+
+    fn expect_kind(
+        &self,
+        checkpoint: &LightCertifiedCheckpoint,
+        kind: CheckpointKind,
+    ) -> LightClientResult {
+        if checkpoint.kind != kind {
+            return Err(LightClientError::InvalidEncoding(alloc::format!(
+                "expected a {kind:?} checkpoint, got a {:?} checkpoint",
+                checkpoint.kind
+            )));
+        }
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        checkpoint: &LightCertifiedCheckpoint,
+        message_epoch: u64,
+    ) -> LightClientResult {
+        if message_epoch != checkpoint.epoch {
+            return Err(LightClientError::WrongEpoch {
+                expected: checkpoint.epoch,
+                actual: message_epoch,
+            });
+        }
+        checkpoint.verify_signature(&self.committee, &self.verifier)
+    }
+}
+
+#[cfg(all(test, feature = "fastcrypto"))]
+mod tests {
+    use super::*;
+    use crate::checkpoint::{DWALLET_CHECKPOINT_MESSAGE_V2_MARKER, SYSTEM_END_OF_PUBLISH_TAG};
+    use crate::committee::LightCommitteeMember;
+    use crate::verifier::FastCryptoVerifier;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use fastcrypto::bls12381::min_pk::{BLS12381AggregateSignature, BLS12381KeyPair};
+    use fastcrypto::traits::{AggregateAuthenticator, KeyPair, Signer, ToFromBytes};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn key_pairs(count: usize) -> Vec<BLS12381KeyPair> {
+        let mut rng = StdRng::from_seed([7; 32]);
+        (0..count)
+            .map(|_| BLS12381KeyPair::generate(&mut rng))
+            .collect()
+    }
+
+    fn committee(epoch: u64, key_pairs: &[BLS12381KeyPair]) -> LightCommittee {
+        let members = key_pairs
+            .iter()
+            .map(|key_pair| LightCommitteeMember {
+                protocol_public_key: key_pair.public().as_bytes().try_into().unwrap(),
+                voting_power: 1,
+            })
+            .collect();
+        LightCommittee::new(epoch, members, 3).unwrap()
+    }
+
+    fn certify(
+        kind: CheckpointKind,
+        message: Vec<u8>,
+        epoch: u64,
+        key_pairs: &[BLS12381KeyPair],
+        signers: Vec<u32>,
+    ) -> LightCertifiedCheckpoint {
+        let mut checkpoint = LightCertifiedCheckpoint {
+            kind,
+            message,
+            epoch,
+            signature: [0; crate::SIGNATURE_LENGTH],
+            signers,
+        };
+        let signing_bytes = checkpoint.signing_bytes();
+        let signatures: Vec<_> = checkpoint
+            .signers
+            .iter()
+            .map(|signer| key_pairs[*signer as usize].sign(&signing_bytes))
+            .collect();
+        let aggregate = BLS12381AggregateSignature::aggregate(&signatures).unwrap();
+        checkpoint.signature = aggregate.as_bytes().try_into().unwrap();
+        checkpoint
+    }
+
+    fn system_message(epoch: u64, sequence_number: u64, end_of_publish: bool) -> Vec<u8> {
+        let mut message = Vec::new();
+        message.extend_from_slice(&epoch.to_le_bytes());
+        message.extend_from_slice(&sequence_number.to_le_bytes());
+        if end_of_publish {
+            message.extend_from_slice(&[1, SYSTEM_END_OF_PUBLISH_TAG as u8]);
+        } else {
+            // A single `SetEpochDurationMs(1000)`.
+            message.push(1);
+            message.push(1);
+            message.extend_from_slice(&1000u64.to_le_bytes());
+        }
+        message
+    }
+
+    /// A V1 checkpoint without `merkle_root`, and a V2 checkpoint committing to it otherwise.
+    fn dwallet_message(epoch: u64, sequence_number: u64, merkle_root: Option<[u8; 32]>) -> Vec<u8> {
+        let mut message = Vec::new();
+        if merkle_root.is_some() {
+            message.extend_from_slice(&DWALLET_CHECKPOINT_MESSAGE_V2_MARKER.to_le_bytes());
+        }
+        message.extend_from_slice(&epoch.to_le_bytes());
+        message.extend_from_slice(&sequence_number.to_le_bytes());
+        // `[SetMaxActiveSessionsBuffer(5), EndOfPublish]`.
+        message.push(2);
+        message.push(10);
+        message.extend_from_slice(&5u64.to_le_bytes());
+        message.push(12);
+        if let Some(root) = merkle_root {
+            message.extend_from_slice(&[1, 0, 32]);
+            message.extend_from_slice(&root);
+        }
+        message
+    }
+
+    /// The last V2 checkpoint of `epoch`, committing to `next_committee`.
+    fn end_of_epoch_dwallet_message(
+        epoch: u64,
+        sequence_number: u64,
+        next_committee: &LightCommittee,
+    ) -> Vec<u8> {
+        let mut message = dwallet_message(epoch, sequence_number, Some([9; 32]));
+        // Two commitments: the Merkle root, then `NextEpochCommittee`.
+        let commitments_count_index = message.len() - 32 - 3;
+        message[commitments_count_index] = 2;
+        message.push(1);
+        message.extend_from_slice(&next_committee.epoch.to_le_bytes());
+        message.push(next_committee.members.len() as u8);
+        for member in &next_committee.members {
+            message.push(crate::PUBLIC_KEY_LENGTH as u8);
+            message.extend_from_slice(&member.protocol_public_key);
+            message.extend_from_slice(&member.voting_power.to_le_bytes());
+        }
+        message.extend_from_slice(&next_committee.quorum_threshold.to_le_bytes());
+        message
+    }
+
+    #[test]
+    fn verifies_dwallet_checkpoints_with_and_without_merkle_root() {
+        let key_pairs = key_pairs(4);
+        let mut client = LightClient::new(committee(1, &key_pairs), FastCryptoVerifier);
+
+        let legacy = certify(
+            CheckpointKind::DWallet,
+            dwallet_message(1, 1, None),
+            1,
+            &key_pairs,
+            vec![0, 1, 2],
+        );
+        let header = client.verify_dwallet_checkpoint(&legacy).unwrap();
+        assert_eq!(header.sequence_number, 1);
+        assert_eq!(header.message_count, 2);
+        assert_eq!(header.messages_merkle_root, None);
+
+        let with_root = certify(
+            CheckpointKind::DWallet,
+            dwallet_message(1, 2, Some([9; 32])),
+            1,
+            &key_pairs,
+            vec![1, 2, 3],
+        );
+        let header = client.verify_dwallet_checkpoint(&with_root).unwrap();
+        assert_eq!(header.messages_merkle_root, Some([9; 32]));
+        assert_eq!(client.highest_dwallet_checkpoint(), Some(2));
+    }
+
+    #[test]
+    fn rejects_insufficient_quorum_and_tampered_messages() {
+        let key_pairs = key_pairs(4);
+        let mut client = LightClient::new(committee(1, &key_pairs), FastCryptoVerifier);
+
+        let below_quorum = certify(
+            CheckpointKind::DWallet,
+            dwallet_message(1, 1, None),
+            1,
+            &key_pairs,
+            vec![0, 1],
+        );
+        assert!(matches!(
+            client.verify_dwallet_checkpoint(&below_quorum),
+            Err(LightClientError::InsufficientVotingPower { .. })
+        ));
+
+        let mut tampered = certify(
+            CheckpointKind::DWallet,
+            dwallet_message(1, 1, None),
+            1,
+            &key_pairs,
+            vec![0, 1, 2],
+        );
+        tampered.message = dwallet_message(1, 7, None);
+        assert_eq!(
+            client.verify_dwallet_checkpoint(&tampered),
+            Err(LightClientError::InvalidSignature)
+        );
+
+        let mut claimed_extra_signer = certify(
+            CheckpointKind::DWallet,
+            dwallet_message(1, 1, None),
+            1,
+            &key_pairs,
+            vec![0, 1, 2],
+        );
+        claimed_extra_signer.signers.push(3);
+        assert_eq!(
+            client.verify_dwallet_checkpoint(&claimed_extra_signer),
+            Err(LightClientError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn hands_over_to_the_committed_next_committee() {
+        let epoch_1_keys = key_pairs(4);
+        let epoch_2_keys = key_pairs(5);
+        let next_committee = committee(2, &epoch_2_keys);
+        let mut client = LightClient::new(committee(1, &epoch_1_keys), FastCryptoVerifier);
+
+        assert_eq!(
+            client.handover(next_committee.clone()),
+            Err(LightClientError::EpochNotEnded(1))
+        );
+
+        let end_of_publish = certify(
+            CheckpointKind::System,
+            system_message(1, 1, true),
+            1,
+            &epoch_1_keys,
+            vec![0, 2, 3],
+        );
+        assert!(
+            client
+                .verify_system_checkpoint(&end_of_publish)
+                .unwrap()
+                .end_of_publish
+        );
+        assert!(client.is_epoch_ended());
+        // The end of publish system checkpoint alone does not commit to the next committee.
+        assert_eq!(
+            client.handover(next_committee.clone()),
+            Err(LightClientError::EpochNotEnded(1))
+        );
+
+        let last_dwallet_checkpoint = certify(
+            CheckpointKind::DWallet,
+            end_of_epoch_dwallet_message(1, 1, &next_committee),
+            1,
+            &epoch_1_keys,
+            vec![0, 1, 2],
+        );
+        let header = client
+            .verify_dwallet_checkpoint(&last_dwallet_checkpoint)
+            .unwrap();
+        assert_eq!(header.messages_merkle_root, Some([9; 32]));
+        assert_eq!(header.next_epoch_committee.as_ref(), Some(&next_committee));
+        assert_eq!(client.next_committee(), Some(&next_committee));
+
+        assert!(matches!(
+            client.handover(committee(3, &epoch_2_keys)),
+            Err(LightClientError::UnexpectedNextCommitteeEpoch { .. })
+        ));
+        assert_eq!(
+            client.handover(committee(2, &epoch_1_keys)),
+            Err(LightClientError::NextCommitteeMismatch(2))
+        );
+        client.handover(next_committee).unwrap();
+        assert_eq!(client.epoch(), 2);
+        assert_eq!(client.next_committee(), None);
+
+        // Old committee certificates no longer verify.
+        let stale = certify(
+            CheckpointKind::System,
+            system_message(1, 2, false),
+            1,
+            &epoch_1_keys,
+            vec![0, 1, 2],
+        );
+        assert!(matches!(
+            client.verify_system_checkpoint(&stale),
+            Err(LightClientError::WrongEpoch { .. })
+        ));
+        let next = certify(
+            CheckpointKind::System,
+            system_message(2, 2, false),
+            2,
+            &epoch_2_keys,
+            vec![0, 1, 4],
+        );
+        client.verify_system_checkpoint(&next).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_committed_committee_for_another_epoch() {
+        let key_pairs = key_pairs(4);
+        let mut client = LightClient::new(committee(1, &key_pairs), FastCryptoVerifier);
+        let checkpoint = certify(
+            CheckpointKind::DWallet,
+            end_of_epoch_dwallet_message(1, 1, &committee(3, &key_pairs)),
+            1,
+            &key_pairs,
+            vec![0, 1, 2],
+        );
+        assert_eq!(
+            client.verify_dwallet_checkpoint(&checkpoint),
+            Err(LightClientError::UnexpectedNextCommitteeEpoch {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(client.next_committee(), None);
+    }
+
+    #[test]
+    fn serialization_round_trips() {
+        let key_pairs = key_pairs(4);
+        let committee = committee(1, &key_pairs);
+        assert_eq!(
+            LightCommittee::from_bytes(&committee.to_bytes()).unwrap(),
+            committee
+        );
+
+        let checkpoint = certify(
+            CheckpointKind::System,
+            system_message(1, 1, true),
+            1,
+            &key_pairs,
+            vec![0, 1, 3],
+        );
+        let bytes = checkpoint.to_bytes();
+        assert_eq!(
+            LightCertifiedCheckpoint::from_bytes(&bytes).unwrap(),
+            checkpoint
+        );
+
+        let mut future_version = bytes;
+        future_version[0] = crate::FORMAT_VERSION + 1;
+        assert_eq!(
+            LightCertifiedCheckpoint::from_bytes(&future_version),
+            Err(LightClientError::UnsupportedFormatVersion(
+                crate::FORMAT_VERSION + 1
+            ))
+        );
+    }
+}
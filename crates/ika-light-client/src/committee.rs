@@ -0,0 +1,95 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::FORMAT_VERSION;
+use crate::error::{LightClientError, LightClientResult};
+use crate::verifier::PUBLIC_KEY_LENGTH;
+use crate::wire::{Reader, Writer};
+use alloc::vec::Vec;
+
+/// A committee member: its BLS12-381 (min-pk) protocol public key and voting power.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LightCommitteeMember {
+    pub protocol_public_key: [u8; PUBLIC_KEY_LENGTH],
+    pub voting_power: u64,
+}
+
+/// The subset of `ika_types::committee::Committee` needed to verify certificates.
+///
+/// Members must be in the committee order, since certificates refer to
+/// signers by their index in this list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LightCommittee {
+    pub epoch: u64,
+    pub members: Vec<LightCommitteeMember>,
+    pub quorum_threshold: u64,
+}
+
+impl LightCommittee {
+    pub fn new(
+        epoch: u64,
+        members: Vec<LightCommitteeMember>,
+        quorum_threshold: u64,
+    ) -> LightClientResult<Self> {
+        let committee = Self {
+            epoch,
+            members,
+            quorum_threshold,
+        };
+        committee.validate()?;
+        Ok(committee)
+    }
+
+    fn validate(&self) -> LightClientResult {
+        if self.members.is_empty() {
+            return Err(LightClientError::InvalidCommittee(
+                "committee has no members".into(),
+            ));
+        }
+        let total_voting_power = self
+            .members
+            .iter()
+            .try_fold(0u64, |total, member| total.checked_add(member.voting_power))
+            .ok_or_else(|| LightClientError::InvalidCommittee("voting power overflow".into()))?;
+        if self.quorum_threshold == 0 || self.quorum_threshold > total_voting_power {
+            return Err(LightClientError::InvalidCommittee(alloc::format!(
+                "quorum threshold {} is not reachable with total voting power {total_voting_power}",
+                self.quorum_threshold
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.u8(FORMAT_VERSION);
+        writer.u64(self.epoch);
+        writer.u64(self.quorum_threshold);
+        writer.uleb128(self.members.len() as u32);
+        for member in &self.members {
+            writer.raw(&member.protocol_public_key);
+            writer.u64(member.voting_power);
+        }
+        writer.into_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> LightClientResult<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.u8()?;
+        if version != FORMAT_VERSION {
+            return Err(LightClientError::UnsupportedFormatVersion(version));
+        }
+        let epoch = reader.u64()?;
+        let quorum_threshold = reader.u64()?;
+        let len = reader.uleb128()? as usize;
+        let mut members = Vec::with_capacity(len.min(reader.remaining()));
+        for _ in 0..len {
+            members.push(LightCommitteeMember {
+                protocol_public_key: reader.take_array()?,
+                voting_power: reader.u64()?,
+            });
+        }
+        reader.finish()?;
+        Self::new(epoch, members, quorum_threshold)
+    }
+}
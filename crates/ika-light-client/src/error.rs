@@ -0,0 +1,87 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use alloc::string::String;
+use core::fmt;
+
+pub type LightClientResult<T = ()> = Result<T, LightClientError>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LightClientError {
+    /// The input ended before the value was fully decoded.
+    UnexpectedEndOfInput,
+    /// Bytes were left over after decoding a value.
+    TrailingBytes,
+    /// The encoding is malformed.
+    InvalidEncoding(String),
+    /// The serialization format version is not supported by this build.
+    UnsupportedFormatVersion(u8),
+    /// The checkpoint was signed for an epoch other than the committee's.
+    WrongEpoch { expected: u64, actual: u64 },
+    /// The certificate references a signer that is not part of the committee.
+    UnknownSigner(u32),
+    /// The signers bitmap is not strictly increasing.
+    UnsortedSigners,
+    /// The signers do not hold a quorum of the committee's voting power.
+    InsufficientVotingPower {
+        voting_power: u64,
+        quorum_threshold: u64,
+    },
+    /// The aggregate signature did not verify.
+    InvalidSignature,
+    /// A committee handover was attempted before a certified checkpoint of the current
+    /// epoch committed to the next committee.
+    EpochNotEnded(u64),
+    /// The next committee is not for the epoch following the current one.
+    UnexpectedNextCommitteeEpoch { expected: u64, actual: u64 },
+    /// The next committee differs from the one committed to by the current committee.
+    NextCommitteeMismatch(u64),
+    /// The committee is empty or its quorum threshold is unreachable.
+    InvalidCommittee(String),
+}
+
+impl fmt::Display for LightClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LightClientError::UnexpectedEndOfInput => write!(f, "unexpected end of input"),
+            LightClientError::TrailingBytes => write!(f, "trailing bytes after decoded value"),
+            LightClientError::InvalidEncoding(error) => write!(f, "invalid encoding: {error}"),
+            LightClientError::UnsupportedFormatVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            LightClientError::WrongEpoch { expected, actual } => {
+                write!(f, "wrong epoch, expected {expected}, got {actual}")
+            }
+            LightClientError::UnknownSigner(index) => {
+                write!(f, "signer index {index} is not part of the committee")
+            }
+            LightClientError::UnsortedSigners => {
+                write!(f, "signers must be strictly increasing committee indices")
+            }
+            LightClientError::InsufficientVotingPower {
+                voting_power,
+                quorum_threshold,
+            } => write!(
+                f,
+                "signers voting power {voting_power} is below the quorum threshold {quorum_threshold}"
+            ),
+            LightClientError::InvalidSignature => write!(f, "invalid aggregate signature"),
+            LightClientError::EpochNotEnded(epoch) => write!(
+                f,
+                "no certified dwallet checkpoint committing to the next committee was seen for epoch {epoch}"
+            ),
+            LightClientError::UnexpectedNextCommitteeEpoch { expected, actual } => write!(
+                f,
+                "next committee must be for epoch {expected}, got epoch {actual}"
+            ),
+            LightClientError::NextCommitteeMismatch(epoch) => write!(
+                f,
+                "the committee of epoch {epoch} does not match the one committed to by the previous epoch"
+            ),
+            LightClientError::InvalidCommittee(error) => write!(f, "invalid committee: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LightClientError {}
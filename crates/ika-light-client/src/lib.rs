@@ -0,0 +1,41 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! A small, dependency-light verifier for Ika checkpoints.
+//!
+//! This crate lets bridges, relayers and other chains verify certified dWallet and system
+//! checkpoints against an Ika committee, without depending on `ika-core` or `ika-types`.
+//! It mirrors the signing logic of `ika-types` (`message_envelope`, `committee` and `crypto`):
+//! a checkpoint is certified when the BLS aggregate signature of its signers verifies over
+//! `intent || bcs(checkpoint) || bcs(epoch)` and the signers hold a quorum of the voting power.
+//!
+//! The crate is `no_std` + `alloc` when built without the default features; BLS verification
+//! is then provided by the embedder through [`AggregateSignatureVerifier`].
+//!
+//! All types that cross process boundaries ([`LightCommittee`], [`LightCertifiedCheckpoint`])
+//! have a versioned, BCS-compatible encoding, see [`LightCommittee::to_bytes`] and
+//! [`LightCertifiedCheckpoint::to_bytes`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod checkpoint;
+mod client;
+mod committee;
+mod error;
+mod verifier;
+mod wire;
+
+pub use checkpoint::{
+    CheckpointKind, DWalletCheckpointHeader, LightCertifiedCheckpoint, SystemCheckpointHeader,
+};
+pub use client::LightClient;
+pub use committee::{LightCommittee, LightCommitteeMember};
+pub use error::{LightClientError, LightClientResult};
+#[cfg(feature = "fastcrypto")]
+pub use verifier::FastCryptoVerifier;
+pub use verifier::{AggregateSignatureVerifier, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+
+/// Version of the serialization format of [`LightCommittee`] and [`LightCertifiedCheckpoint`].
+pub const FORMAT_VERSION: u8 = 1;
@@ -0,0 +1,49 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+/// Length of a compressed BLS12-381 (min-pk) public key.
+pub const PUBLIC_KEY_LENGTH: usize = 48;
+/// Length of a compressed BLS12-381 (min-pk) aggregate signature.
+pub const SIGNATURE_LENGTH: usize = 96;
+
+/// Verifies a BLS12-381 (min-pk) aggregate signature of `public_keys` over a single `message`.
+///
+/// Implement this to use a platform BLS implementation (e.g. a precompile) on `no_std` targets.
+pub trait AggregateSignatureVerifier {
+    fn verify_aggregate(
+        &self,
+        public_keys: &[[u8; PUBLIC_KEY_LENGTH]],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_LENGTH],
+    ) -> bool;
+}
+
+/// [`AggregateSignatureVerifier`] backed by `fastcrypto`, the library Ika validators sign with.
+#[cfg(feature = "fastcrypto")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FastCryptoVerifier;
+
+#[cfg(feature = "fastcrypto")]
+impl AggregateSignatureVerifier for FastCryptoVerifier {
+    fn verify_aggregate(
+        &self,
+        public_keys: &[[u8; PUBLIC_KEY_LENGTH]],
+        message: &[u8],
+        signature: &[u8; SIGNATURE_LENGTH],
+    ) -> bool {
+        use fastcrypto::bls12381::min_pk::{BLS12381AggregateSignature, BLS12381PublicKey};
+        use fastcrypto::traits::{AggregateAuthenticator, ToFromBytes};
+
+        let Ok(signature) = BLS12381AggregateSignature::from_bytes(signature) else {
+            return false;
+        };
+        let Ok(public_keys) = public_keys
+            .iter()
+            .map(|public_key| BLS12381PublicKey::from_bytes(public_key))
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return false;
+        };
+        signature.verify(&public_keys, message).is_ok()
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Minimal BCS reader and writer.
+//!
+//! `bcs` itself requires `std`, and the checkpoint messages only use a handful of
+//! primitives, so they are decoded here the same way the Move contracts peel them.
+
+use crate::error::{LightClientError, LightClientResult};
+use alloc::vec::Vec;
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub(crate) fn finish(self) -> LightClientResult {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(LightClientError::TrailingBytes)
+        }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> LightClientResult<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(LightClientError::UnexpectedEndOfInput);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub(crate) fn take_array<const N: usize>(&mut self) -> LightClientResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    pub(crate) fn u8(&mut self) -> LightClientResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> LightClientResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(LightClientError::InvalidEncoding(alloc::format!(
+                "invalid bool {value}"
+            ))),
+        }
+    }
+
+    pub(crate) fn u16(&mut self) -> LightClientResult<u16> {
+        Ok(u16::from_le_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> LightClientResult<u32> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> LightClientResult<u64> {
+        Ok(u64::from_le_bytes(self.take_array()?))
+    }
+
+    /// Reads a ULEB128-encoded length, as used by BCS for sequences and enum tags.
+    pub(crate) fn uleb128(&mut self) -> LightClientResult<u32> {
+        let mut value: u64 = 0;
+        for shift in (0..32).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                if shift > 0 && byte == 0 {
+                    return Err(LightClientError::InvalidEncoding(
+                        "non-canonical uleb128".into(),
+                    ));
+                }
+                return u32::try_from(value)
+                    .map_err(|_| LightClientError::InvalidEncoding("uleb128 overflow".into()));
+            }
+        }
+        Err(LightClientError::InvalidEncoding("uleb128 overflow".into()))
+    }
+
+    pub(crate) fn bytes(&mut self) -> LightClientResult<&'a [u8]> {
+        let len = self.uleb128()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn skip_bytes(&mut self) -> LightClientResult {
+        self.bytes().map(|_| ())
+    }
+
+    pub(crate) fn skip_option_bytes(&mut self) -> LightClientResult {
+        if self.bool()? {
+            self.skip_bytes()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn skip_u32_vec(&mut self) -> LightClientResult {
+        let len = self.uleb128()? as usize;
+        self.take(
+            len.checked_mul(4)
+                .ok_or(LightClientError::UnexpectedEndOfInput)?,
+        )
+        .map(|_| ())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub(crate) fn raw(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.raw(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.raw(&value.to_le_bytes());
+    }
+
+    pub(crate) fn uleb128(&mut self, mut value: u32) {
+        while value >= 0x80 {
+            self.u8((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.u8(value as u8);
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        self.uleb128(bytes.len() as u32);
+        self.raw(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uleb128_round_trip() {
+        for value in [0, 1, 127, 128, 300, 16_383, 16_384, u32::MAX] {
+            let mut writer = Writer::default();
+            writer.uleb128(value);
+            let bytes = writer.into_bytes();
+            let mut reader = Reader::new(&bytes);
+            assert_eq!(reader.uleb128().unwrap(), value);
+            reader.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_non_canonical_uleb128() {
+        let mut reader = Reader::new(&[0x80, 0x00]);
+        assert!(reader.uleb128().is_err());
+    }
+}
@@ -0,0 +1,460 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Checks the light client against `ika-types`, so that its hand-written decoding tables
+//! and the committed test vectors cannot drift from the checkpoints validators sign.
+//!
+//! After an intended change to the checkpoint encoding, rewrite the test vectors with:
+//! `IKA_LIGHT_CLIENT_UPDATE_TEST_VECTORS=1 cargo test -p ika-light-client --test ika_types_compatibility`
+
+#![cfg(feature = "fastcrypto")]
+
+use ika_light_client::{
+    CheckpointKind, DWalletCheckpointHeader, FastCryptoVerifier, LightCertifiedCheckpoint,
+    LightClient, LightCommittee, LightCommitteeMember, SystemCheckpointHeader,
+};
+use ika_types::committee::{Committee, ProtocolVersion};
+use ika_types::crypto::{
+    AuthorityKeyPair, AuthorityPrivateKey, AuthorityStrongQuorumSignInfo, KeypairTraits,
+    ToFromBytes,
+};
+use ika_types::message::{
+    DKGFirstRoundOutput, DKGSecondRoundOutput, DWalletCheckpointMessageKind,
+    DWalletImportedKeyVerificationOutput, EncryptedUserShareOutput, MPCNetworkDKGOutput,
+    MPCNetworkReconfigurationOutput, MakeDWalletUserSecretKeySharesPublicOutput,
+    PartialSignatureVerificationOutput, PresignOutput, SignOutput,
+};
+use ika_types::messages_dwallet_checkpoint::{
+    CertifiedDWalletCheckpointMessage, DWalletCheckpointMessage,
+};
+use ika_types::messages_system_checkpoints::{
+    CertifiedSystemCheckpointMessage, SystemCheckpointMessage, SystemCheckpointMessageKind,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const UPDATE_TEST_VECTORS_ENV: &str = "IKA_LIGHT_CLIENT_UPDATE_TEST_VECTORS";
+
+/// A deterministic key pair, so the test vectors can be rebuilt byte for byte.
+fn key_pair(byte: u8) -> AuthorityKeyPair {
+    AuthorityKeyPair::from(AuthorityPrivateKey::from_bytes(&[byte; 32]).unwrap())
+}
+
+/// A committee of `key_pairs` with a voting power of 1 each.
+fn committee(epoch: u64, key_pairs: &[AuthorityKeyPair], quorum_threshold: u64) -> Committee {
+    let voting_rights = key_pairs
+        .iter()
+        .map(|key_pair| (key_pair.public().into(), 1))
+        .collect();
+    Committee::new(epoch, voting_rights, HashMap::new(), quorum_threshold, 1)
+}
+
+fn light_committee(committee: &Committee) -> LightCommittee {
+    let members = committee
+        .voting_rights
+        .iter()
+        .map(|(name, voting_power)| LightCommitteeMember {
+            protocol_public_key: name.0,
+            voting_power: *voting_power,
+        })
+        .collect();
+    LightCommittee::new(committee.epoch, members, committee.quorum_threshold).unwrap()
+}
+
+fn light_checkpoint<T: Serialize>(
+    kind: CheckpointKind,
+    message: &T,
+    auth_sig: &AuthorityStrongQuorumSignInfo,
+) -> LightCertifiedCheckpoint {
+    LightCertifiedCheckpoint {
+        kind,
+        message: bcs::to_bytes(message).unwrap(),
+        epoch: auth_sig.epoch,
+        signature: auth_sig.signature.as_ref().try_into().unwrap(),
+        signers: auth_sig.signers_map.iter().collect(),
+    }
+}
+
+fn certify_dwallet_checkpoint(
+    message: DWalletCheckpointMessage,
+    key_pairs: &[AuthorityKeyPair],
+    committee: &Committee,
+) -> LightCertifiedCheckpoint {
+    let certified = CertifiedDWalletCheckpointMessage::new_from_keypairs_for_testing(
+        message, key_pairs, committee,
+    );
+    light_checkpoint(
+        CheckpointKind::DWallet,
+        certified.data(),
+        certified.auth_sig(),
+    )
+}
+
+fn certify_system_checkpoint(
+    message: SystemCheckpointMessage,
+    key_pairs: &[AuthorityKeyPair],
+    committee: &Committee,
+) -> LightCertifiedCheckpoint {
+    let certified = CertifiedSystemCheckpointMessage::new_from_keypairs_for_testing(
+        message, key_pairs, committee,
+    );
+    light_checkpoint(
+        CheckpointKind::System,
+        certified.data(),
+        certified.auth_sig(),
+    )
+}
+
+fn sign_message(session_sequence_number: u64) -> DWalletCheckpointMessageKind {
+    DWalletCheckpointMessageKind::RespondDWalletSign(SignOutput {
+        dwallet_id: vec![1; 32],
+        sign_id: vec![2; 32],
+        signature: vec![3; 64],
+        is_future_sign: false,
+        rejected: false,
+        session_sequence_number,
+    })
+}
+
+/// One message of every kind, in tag order.
+fn dwallet_checkpoint_message_kinds() -> Vec<DWalletCheckpointMessageKind> {
+    let kinds = vec![
+        DWalletCheckpointMessageKind::RespondDWalletDKGFirstRoundOutput(DKGFirstRoundOutput {
+            dwallet_id: vec![1; 32],
+            output: vec![2; 100],
+            rejected: false,
+            session_sequence_number: 1,
+        }),
+        DWalletCheckpointMessageKind::RespondDWalletDKGSecondRoundOutput(DKGSecondRoundOutput {
+            dwallet_id: vec![1; 32],
+            encrypted_secret_share_id: vec![2; 32],
+            output: vec![3; 100],
+            rejected: true,
+            session_sequence_number: 2,
+        }),
+        DWalletCheckpointMessageKind::RespondDWalletEncryptedUserShare(EncryptedUserShareOutput {
+            dwallet_id: vec![1; 32],
+            encrypted_user_secret_key_share_id: vec![2; 32],
+            rejected: false,
+            session_sequence_number: 3,
+        }),
+        DWalletCheckpointMessageKind::RespondMakeDWalletUserSecretKeySharesPublic(
+            MakeDWalletUserSecretKeySharesPublicOutput {
+                dwallet_id: vec![1; 32],
+                public_user_secret_key_shares: vec![2; 64],
+                rejected: false,
+                session_sequence_number: 4,
+            },
+        ),
+        DWalletCheckpointMessageKind::RespondDWalletImportedKeyVerificationOutput(
+            DWalletImportedKeyVerificationOutput {
+                dwallet_id: vec![1; 32],
+                public_output: vec![2; 100],
+                encrypted_user_secret_key_share_id: vec![3; 32],
+                rejected: false,
+                session_sequence_number: 5,
+            },
+        ),
+        DWalletCheckpointMessageKind::RespondDWalletPresign(PresignOutput {
+            dwallet_id: Some(vec![1; 32]),
+            presign_id: vec![2; 32],
+            presign: vec![3; 100],
+            rejected: false,
+            session_sequence_number: 6,
+        }),
+        sign_message(7),
+        DWalletCheckpointMessageKind::RespondDWalletPartialSignatureVerificationOutput(
+            PartialSignatureVerificationOutput {
+                dwallet_id: vec![1; 32],
+                partial_centralized_signed_message_id: vec![2; 32],
+                rejected: false,
+                session_sequence_number: 8,
+            },
+        ),
+        DWalletCheckpointMessageKind::RespondDWalletMPCNetworkDKGOutput(MPCNetworkDKGOutput {
+            dwallet_network_encryption_key_id: vec![1; 32],
+            public_output: vec![2; 100],
+            supported_curves: vec![0, 1],
+            is_last: false,
+            rejected: false,
+            session_sequence_number: 9,
+        }),
+        DWalletCheckpointMessageKind::RespondDWalletMPCNetworkReconfigurationOutput(
+            MPCNetworkReconfigurationOutput {
+                dwallet_network_encryption_key_id: vec![1; 32],
+                public_output: vec![2; 100],
+                supported_curves: vec![0],
+                is_last: true,
+                rejected: false,
+                session_sequence_number: 10,
+            },
+        ),
+        DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(100),
+        DWalletCheckpointMessageKind::SetGasFeeReimbursementSuiSystemCallValue(1_000),
+        DWalletCheckpointMessageKind::EndOfPublish,
+    ];
+    // Fails to compile when a kind is added, so that it is added above as well.
+    let tags: Vec<u8> = kinds
+        .iter()
+        .map(|kind| match kind {
+            DWalletCheckpointMessageKind::RespondDWalletDKGFirstRoundOutput(_) => 0,
+            DWalletCheckpointMessageKind::RespondDWalletDKGSecondRoundOutput(_) => 1,
+            DWalletCheckpointMessageKind::RespondDWalletEncryptedUserShare(_) => 2,
+            DWalletCheckpointMessageKind::RespondMakeDWalletUserSecretKeySharesPublic(_) => 3,
+            DWalletCheckpointMessageKind::RespondDWalletImportedKeyVerificationOutput(_) => 4,
+            DWalletCheckpointMessageKind::RespondDWalletPresign(_) => 5,
+            DWalletCheckpointMessageKind::RespondDWalletSign(_) => 6,
+            DWalletCheckpointMessageKind::RespondDWalletPartialSignatureVerificationOutput(_) => 7,
+            DWalletCheckpointMessageKind::RespondDWalletMPCNetworkDKGOutput(_) => 8,
+            DWalletCheckpointMessageKind::RespondDWalletMPCNetworkReconfigurationOutput(_) => 9,
+            DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(_) => 10,
+            DWalletCheckpointMessageKind::SetGasFeeReimbursementSuiSystemCallValue(_) => 11,
+            DWalletCheckpointMessageKind::EndOfPublish => 12,
+        })
+        .collect();
+    for (kind, tag) in kinds.iter().zip(&tags) {
+        assert_eq!(bcs::to_bytes(kind).unwrap()[0], *tag);
+    }
+    assert_eq!(tags, (0..=12).collect::<Vec<u8>>());
+    kinds
+}
+
+/// At least one message of every kind, with `EndOfPublish` last as in a real checkpoint.
+fn system_checkpoint_message_kinds() -> Vec<SystemCheckpointMessageKind> {
+    let kinds = vec![
+        SystemCheckpointMessageKind::SetNextConfigVersion(ProtocolVersion::new(2)),
+        SystemCheckpointMessageKind::SetEpochDurationMs(86_400_000),
+        SystemCheckpointMessageKind::SetStakeSubsidyStartEpoch(3),
+        SystemCheckpointMessageKind::SetStakeSubsidyRate(1_000),
+        SystemCheckpointMessageKind::SetStakeSubsidyPeriodLength(10),
+        SystemCheckpointMessageKind::SetMinValidatorCount(4),
+        SystemCheckpointMessageKind::SetMaxValidatorCount(150),
+        SystemCheckpointMessageKind::SetMinValidatorJoiningStake(1_000_000),
+        SystemCheckpointMessageKind::SetMaxValidatorChangeCount(10),
+        SystemCheckpointMessageKind::SetRewardSlashingRate(500),
+        SystemCheckpointMessageKind::SetApprovedUpgrade {
+            package_id: vec![1; 32],
+            digest: Some(vec![2; 32]),
+        },
+        SystemCheckpointMessageKind::SetApprovedUpgrade {
+            package_id: vec![1; 32],
+            digest: None,
+        },
+        SystemCheckpointMessageKind::SetOrRemoveWitnessApprovingAdvanceEpochMessageType {
+            witness_type: "0x1::witness::Witness".to_string(),
+            remove: true,
+        },
+        SystemCheckpointMessageKind::EndOfPublish,
+    ];
+    // Fails to compile when a kind is added, so that it is added above as well.
+    let tags: Vec<u8> = kinds
+        .iter()
+        .map(|kind| match kind {
+            SystemCheckpointMessageKind::SetNextConfigVersion(_) => 0,
+            SystemCheckpointMessageKind::SetEpochDurationMs(_) => 1,
+            SystemCheckpointMessageKind::SetStakeSubsidyStartEpoch(_) => 2,
+            SystemCheckpointMessageKind::SetStakeSubsidyRate(_) => 3,
+            SystemCheckpointMessageKind::SetStakeSubsidyPeriodLength(_) => 4,
+            SystemCheckpointMessageKind::SetMinValidatorCount(_) => 5,
+            SystemCheckpointMessageKind::SetMaxValidatorCount(_) => 6,
+            SystemCheckpointMessageKind::SetMinValidatorJoiningStake(_) => 7,
+            SystemCheckpointMessageKind::SetMaxValidatorChangeCount(_) => 8,
+            SystemCheckpointMessageKind::SetRewardSlashingRate(_) => 9,
+            SystemCheckpointMessageKind::EndOfPublish => 10,
+            SystemCheckpointMessageKind::SetApprovedUpgrade { .. } => 11,
+            SystemCheckpointMessageKind::SetOrRemoveWitnessApprovingAdvanceEpochMessageType {
+                ..
+            } => 12,
+        })
+        .collect();
+    for (kind, tag) in kinds.iter().zip(&tags) {
+        assert_eq!(bcs::to_bytes(kind).unwrap()[0], *tag);
+    }
+    let mut distinct_tags = tags;
+    distinct_tags.sort();
+    distinct_tags.dedup();
+    assert_eq!(distinct_tags, (0..=12).collect::<Vec<u8>>());
+    kinds
+}
+
+#[test]
+fn decodes_every_dwallet_checkpoint_message_kind() {
+    let kinds = dwallet_checkpoint_message_kinds();
+    for kind in &kinds {
+        let single = DWalletCheckpointMessage::new(1, 7, vec![kind.clone()]);
+        let header = DWalletCheckpointHeader::decode(&bcs::to_bytes(&single).unwrap())
+            .unwrap_or_else(|e| panic!("failed to decode {}: {e}", kind.name()));
+        assert_eq!(header.message_count, 1);
+    }
+
+    let v1 = DWalletCheckpointMessage::new(1, 7, kinds.clone());
+    let header = DWalletCheckpointHeader::decode(&bcs::to_bytes(&v1).unwrap()).unwrap();
+    assert_eq!(
+        header,
+        DWalletCheckpointHeader {
+            epoch: 1,
+            sequence_number: 7,
+            message_count: kinds.len() as u32,
+            messages_merkle_root: None,
+            next_epoch_committee: None,
+        }
+    );
+
+    let next_committee = committee(2, &[key_pair(5), key_pair(6), key_pair(7)], 2);
+    let v2 = DWalletCheckpointMessage::new(1, 7, kinds.clone())
+        .with_messages_merkle_root()
+        .unwrap()
+        .with_next_epoch_committee(&next_committee)
+        .unwrap();
+    let header = DWalletCheckpointHeader::decode(&bcs::to_bytes(&v2).unwrap()).unwrap();
+    assert_eq!(
+        header,
+        DWalletCheckpointHeader {
+            epoch: 1,
+            sequence_number: 7,
+            message_count: kinds.len() as u32,
+            messages_merkle_root: Some(*v2.messages_merkle_root().unwrap().inner()),
+            next_epoch_committee: Some(light_committee(&next_committee)),
+        }
+    );
+}
+
+#[test]
+fn decodes_every_system_checkpoint_message_kind() {
+    let kinds = system_checkpoint_message_kinds();
+    let message = SystemCheckpointMessage::new(1, 7, kinds.clone());
+    let header = SystemCheckpointHeader::decode(&bcs::to_bytes(&message).unwrap()).unwrap();
+    assert_eq!(
+        header,
+        SystemCheckpointHeader {
+            epoch: 1,
+            sequence_number: 7,
+            message_count: kinds.len() as u32,
+            end_of_publish: true,
+        }
+    );
+
+    let without_end_of_publish = SystemCheckpointMessage::new(1, 8, kinds[..1].to_vec());
+    let header =
+        SystemCheckpointHeader::decode(&bcs::to_bytes(&without_end_of_publish).unwrap()).unwrap();
+    assert!(!header.end_of_publish);
+}
+
+fn test_vectors_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_vectors")
+}
+
+/// Builds the test vectors: epoch 1 with a V1 dwallet checkpoint, an end of publish system
+/// checkpoint and a last V2 dwallet checkpoint committing to the epoch 2 committee, followed
+/// by a V2 dwallet checkpoint of epoch 2.
+fn build_test_vectors() -> Vec<(&'static str, Vec<u8>)> {
+    let epoch_1_key_pairs: Vec<_> = (1..=4).map(key_pair).collect();
+    let epoch_2_key_pairs: Vec<_> = (5..=9).map(key_pair).collect();
+    let epoch_1_committee = committee(1, &epoch_1_key_pairs, 3);
+    let epoch_2_committee = committee(2, &epoch_2_key_pairs, 4);
+
+    let dwallet_checkpoint_1 = certify_dwallet_checkpoint(
+        DWalletCheckpointMessage::new(1, 1, vec![sign_message(1)]),
+        &epoch_1_key_pairs[..3],
+        &epoch_1_committee,
+    );
+    let dwallet_checkpoint_2 = certify_dwallet_checkpoint(
+        DWalletCheckpointMessage::new(
+            1,
+            2,
+            vec![
+                DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(5),
+                DWalletCheckpointMessageKind::EndOfPublish,
+            ],
+        )
+        .with_messages_merkle_root()
+        .unwrap()
+        .with_next_epoch_committee(&epoch_2_committee)
+        .unwrap(),
+        &epoch_1_key_pairs[..3],
+        &epoch_1_committee,
+    );
+    let system_checkpoint_1 = certify_system_checkpoint(
+        SystemCheckpointMessage::new(
+            1,
+            1,
+            vec![
+                SystemCheckpointMessageKind::SetEpochDurationMs(86_400_000),
+                SystemCheckpointMessageKind::EndOfPublish,
+            ],
+        ),
+        &epoch_1_key_pairs[..3],
+        &epoch_1_committee,
+    );
+    let dwallet_checkpoint_3 = certify_dwallet_checkpoint(
+        DWalletCheckpointMessage::new(2, 3, vec![sign_message(2)])
+            .with_messages_merkle_root()
+            .unwrap(),
+        &epoch_2_key_pairs[1..],
+        &epoch_2_committee,
+    );
+
+    vec![
+        (
+            "committee_epoch_1.bin",
+            light_committee(&epoch_1_committee).to_bytes(),
+        ),
+        (
+            "committee_epoch_2.bin",
+            light_committee(&epoch_2_committee).to_bytes(),
+        ),
+        ("dwallet_checkpoint_1.bin", dwallet_checkpoint_1.to_bytes()),
+        ("dwallet_checkpoint_2.bin", dwallet_checkpoint_2.to_bytes()),
+        ("dwallet_checkpoint_3.bin", dwallet_checkpoint_3.to_bytes()),
+        ("system_checkpoint_1.bin", system_checkpoint_1.to_bytes()),
+    ]
+}
+
+#[test]
+fn test_vectors_match_ika_types() {
+    let update = std::env::var_os(UPDATE_TEST_VECTORS_ENV).is_some();
+    for (name, bytes) in build_test_vectors() {
+        let path = test_vectors_dir().join(name);
+        if update {
+            std::fs::write(&path, &bytes).unwrap();
+        } else {
+            assert_eq!(
+                std::fs::read(&path).unwrap(),
+                bytes,
+                "{name} does not match ika-types, set {UPDATE_TEST_VECTORS_ENV} to rewrite it"
+            );
+        }
+    }
+}
+
+#[test]
+fn verifies_ika_types_certificates() {
+    let key_pairs: Vec<_> = (1..=4).map(key_pair).collect();
+    let committee = committee(1, &key_pairs, 3);
+    let mut client = LightClient::new(light_committee(&committee), FastCryptoVerifier);
+
+    let dwallet_checkpoint = certify_dwallet_checkpoint(
+        DWalletCheckpointMessage::new(1, 1, dwallet_checkpoint_message_kinds())
+            .with_messages_merkle_root()
+            .unwrap(),
+        &key_pairs[1..],
+        &committee,
+    );
+    assert_eq!(dwallet_checkpoint.signers, vec![1, 2, 3]);
+    client
+        .verify_dwallet_checkpoint(&dwallet_checkpoint)
+        .unwrap();
+
+    let system_checkpoint = certify_system_checkpoint(
+        SystemCheckpointMessage::new(1, 1, system_checkpoint_message_kinds()),
+        &key_pairs,
+        &committee,
+    );
+    assert!(
+        client
+            .verify_system_checkpoint(&system_checkpoint)
+            .unwrap()
+            .end_of_publish
+    );
+}
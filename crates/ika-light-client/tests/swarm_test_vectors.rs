@@ -0,0 +1,88 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Verifies the committee handover certified by a swarm, so the light client is checked
+//! against what a real network signs and not only against hand-built checkpoints.
+//!
+//! `crates/ika-swarm/tests/light_client_test_vectors.rs` generates these vectors, see its
+//! documentation to regenerate them after a change to the checkpoint encoding.
+
+#![cfg(feature = "fastcrypto")]
+
+use ika_light_client::{
+    FastCryptoVerifier, LightCertifiedCheckpoint, LightClient, LightClientError, LightCommittee,
+};
+use std::path::PathBuf;
+
+fn read_test_vector(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test_vectors")
+        .join("swarm")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "failed to read {}: {e}, generate the swarm test vectors with the \
+             `light_client_test_vectors` test of ika-swarm",
+            path.display()
+        )
+    })
+}
+
+fn committee(name: &str) -> LightCommittee {
+    let bytes = read_test_vector(name);
+    let committee = LightCommittee::from_bytes(&bytes).unwrap();
+    assert_eq!(committee.to_bytes(), bytes);
+    committee
+}
+
+fn checkpoint(name: &str) -> LightCertifiedCheckpoint {
+    let bytes = read_test_vector(name);
+    let checkpoint = LightCertifiedCheckpoint::from_bytes(&bytes).unwrap();
+    assert_eq!(checkpoint.to_bytes(), bytes);
+    checkpoint
+}
+
+#[test]
+fn verifies_the_swarm_committee_handover() {
+    let committee_epoch_1 = committee("committee_epoch_1.bin");
+    let committee_epoch_2 = committee("committee_epoch_2.bin");
+    assert_eq!(committee_epoch_1.epoch, 1);
+    let mut client = LightClient::new(committee_epoch_1, FastCryptoVerifier);
+
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint("dwallet_checkpoint_epoch_1_first.bin"))
+        .unwrap();
+    assert_eq!(header.epoch, 1);
+    assert!(header.messages_merkle_root.is_some());
+
+    let header = client
+        .verify_system_checkpoint(&checkpoint("system_checkpoint_epoch_1_last.bin"))
+        .unwrap();
+    assert_eq!(header.epoch, 1);
+    assert!(header.end_of_publish);
+    assert!(client.is_epoch_ended());
+
+    // The handover is refused until the last dWallet checkpoint commits to the next committee.
+    assert_eq!(
+        client.handover(committee_epoch_2.clone()),
+        Err(LightClientError::EpochNotEnded(1))
+    );
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint("dwallet_checkpoint_epoch_1_last.bin"))
+        .unwrap();
+    assert_eq!(header.epoch, 1);
+    assert_eq!(
+        header.next_epoch_committee.as_ref(),
+        Some(&committee_epoch_2)
+    );
+
+    client.handover(committee_epoch_2).unwrap();
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint("dwallet_checkpoint_epoch_2_first.bin"))
+        .unwrap();
+    assert_eq!(header.epoch, 2);
+    assert_eq!(
+        client.highest_dwallet_checkpoint(),
+        Some(header.sequence_number)
+    );
+}
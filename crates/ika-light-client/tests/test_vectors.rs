@@ -0,0 +1,94 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Verifies the committed test vectors, which `ika_types_compatibility.rs` builds with the
+//! `ika-types` checkpoint types and signing code.
+
+#![cfg(feature = "fastcrypto")]
+
+use ika_light_client::{
+    FastCryptoVerifier, LightCertifiedCheckpoint, LightClient, LightClientError, LightCommittee,
+};
+
+const COMMITTEE_EPOCH_1: &[u8] = include_bytes!("../test_vectors/committee_epoch_1.bin");
+const COMMITTEE_EPOCH_2: &[u8] = include_bytes!("../test_vectors/committee_epoch_2.bin");
+const DWALLET_CHECKPOINT_1: &[u8] = include_bytes!("../test_vectors/dwallet_checkpoint_1.bin");
+const DWALLET_CHECKPOINT_2: &[u8] = include_bytes!("../test_vectors/dwallet_checkpoint_2.bin");
+const DWALLET_CHECKPOINT_3: &[u8] = include_bytes!("../test_vectors/dwallet_checkpoint_3.bin");
+const SYSTEM_CHECKPOINT_1: &[u8] = include_bytes!("../test_vectors/system_checkpoint_1.bin");
+
+fn checkpoint(bytes: &[u8]) -> LightCertifiedCheckpoint {
+    let checkpoint = LightCertifiedCheckpoint::from_bytes(bytes).unwrap();
+    assert_eq!(checkpoint.to_bytes(), bytes);
+    checkpoint
+}
+
+#[test]
+fn verifies_the_test_vectors_across_a_committee_handover() {
+    let committee_epoch_1 = LightCommittee::from_bytes(COMMITTEE_EPOCH_1).unwrap();
+    let committee_epoch_2 = LightCommittee::from_bytes(COMMITTEE_EPOCH_2).unwrap();
+    assert_eq!(committee_epoch_1.to_bytes(), COMMITTEE_EPOCH_1);
+    let mut client = LightClient::new(committee_epoch_1, FastCryptoVerifier);
+
+    // A V1 checkpoint.
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint(DWALLET_CHECKPOINT_1))
+        .unwrap();
+    assert_eq!(
+        (header.epoch, header.sequence_number, header.message_count),
+        (1, 1, 1)
+    );
+    assert_eq!(header.messages_merkle_root, None);
+    assert_eq!(header.next_epoch_committee, None);
+
+    let header = client
+        .verify_system_checkpoint(&checkpoint(SYSTEM_CHECKPOINT_1))
+        .unwrap();
+    assert_eq!((header.epoch, header.message_count), (1, 2));
+    assert!(header.end_of_publish);
+
+    // The last, V2, checkpoint of epoch 1 commits to the epoch 2 committee.
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint(DWALLET_CHECKPOINT_2))
+        .unwrap();
+    assert_eq!(
+        (header.epoch, header.sequence_number, header.message_count),
+        (1, 2, 2)
+    );
+    assert!(header.messages_merkle_root.is_some());
+    assert_eq!(
+        header.next_epoch_committee.as_ref(),
+        Some(&committee_epoch_2)
+    );
+
+    client.handover(committee_epoch_2).unwrap();
+    let header = client
+        .verify_dwallet_checkpoint(&checkpoint(DWALLET_CHECKPOINT_3))
+        .unwrap();
+    assert_eq!((header.epoch, header.sequence_number), (2, 3));
+    assert!(header.messages_merkle_root.is_some());
+    assert_eq!(client.highest_dwallet_checkpoint(), Some(3));
+}
+
+#[test]
+fn rejects_tampered_test_vectors() {
+    let committee_epoch_1 = LightCommittee::from_bytes(COMMITTEE_EPOCH_1).unwrap();
+    let mut client = LightClient::new(committee_epoch_1, FastCryptoVerifier);
+
+    let mut tampered = checkpoint(DWALLET_CHECKPOINT_1);
+    // The last byte is the session sequence number of the signed message.
+    *tampered.message.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        client.verify_dwallet_checkpoint(&tampered),
+        Err(LightClientError::InvalidSignature)
+    );
+
+    assert!(matches!(
+        client.verify_dwallet_checkpoint(&checkpoint(DWALLET_CHECKPOINT_3)),
+        Err(LightClientError::WrongEpoch { .. })
+    ));
+    assert_eq!(
+        client.handover(LightCommittee::from_bytes(COMMITTEE_EPOCH_2).unwrap()),
+        Err(LightClientError::EpochNotEnded(1))
+    );
+}
@@ -109,7 +109,21 @@ fn build_anemo_services(out_dir: &Path) {
                 .build(),
         )
         .build();
+
+    let network_keys = anemo_build::manual::Service::builder()
+        .name("NetworkKeys")
+        .package("ika")
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("get_table_vec_chunks")
+                .route_name("GetTableVecChunks")
+                .request_type("crate::network_keys::GetTableVecChunksRequest")
+                .response_type("Option<Vec<sui_types::object::Object>>")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .build();
     anemo_build::manual::Builder::new()
         .out_dir(out_dir)
-        .compile(&[discovery, state_sync]);
+        .compile(&[discovery, state_sync, network_keys]);
 }
@@ -0,0 +1,544 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Access control of the inbound StateSync and Discovery RPCs.
+//!
+//! A single [`PeerAccessControl`] is shared by both services, so a peer that is banned while
+//! syncing checkpoints is also rejected by discovery. Every RPC is wrapped in a
+//! [`PeerAccessLayer`], which rejects the requests of peers that are not allowlisted, are
+//! denylisted or are banned, and enforces the per-peer rate and inflight limits shared by all
+//! the RPCs.
+
+use anemo::{PeerId, Request, Response, rpc::Status, types::response::StatusCode};
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use ika_config::p2p::P2pConfig;
+use prometheus::{
+    IntCounterVec, IntGauge, Registry, register_int_counter_vec_with_registry,
+    register_int_gauge_with_registry,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tap::Pipe;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+/// The number of peers tracked by the per-peer limits above which idle peers are pruned.
+const PRUNE_THRESHOLD: usize = 5_000;
+
+/// Why the requests of a peer are rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// An allowlist is configured and the peer is not in it.
+    NotAllowlisted,
+    /// The peer is in the configured denylist.
+    Denylisted,
+    /// The peer is temporarily banned for misbehaving.
+    Banned,
+}
+
+impl Rejection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Rejection::NotAllowlisted => "not_allowlisted",
+            Rejection::Denylisted => "denylisted",
+            Rejection::Banned => "banned",
+        }
+    }
+}
+
+/// Why a peer is banned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BanReason {
+    /// The peer served a checkpoint with a different sequence number than the one requested.
+    WrongSequenceNumber,
+    /// The peer served a checkpoint whose digest doesn't match the pinned digest.
+    PinnedDigestMismatch,
+    /// The peer served a checkpoint that is not certified by the committee of its epoch.
+    InvalidSignatures,
+    /// The peer served network encryption key data that doesn't match the on-chain object digests.
+    NetworkKeyDigestMismatch,
+}
+
+impl BanReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BanReason::WrongSequenceNumber => "wrong_sequence_number",
+            BanReason::PinnedDigestMismatch => "pinned_digest_mismatch",
+            BanReason::InvalidSignatures => "invalid_signatures",
+            BanReason::NetworkKeyDigestMismatch => "network_key_digest_mismatch",
+        }
+    }
+}
+
+/// The peers allowed to call the RPCs of this node, and the limits on their requests.
+///
+/// Cloning it shares the bans and the per-peer limits.
+#[derive(Clone)]
+pub struct PeerAccessControl(Arc<Inner>);
+
+struct Inner {
+    /// `None` if every peer that is not denylisted or banned is allowed.
+    allowlist: Option<RwLock<HashSet<PeerId>>>,
+    denylist: HashSet<PeerId>,
+    ban_duration: Duration,
+    /// The time each banned peer is banned until.
+    bans: Mutex<HashMap<PeerId, Instant>>,
+    rate_limiter: Option<DefaultKeyedRateLimiter<PeerId>>,
+    inflight_limit: Option<usize>,
+    inflight: DashMap<PeerId, Arc<Semaphore>>,
+    metrics: Metrics,
+}
+
+impl PeerAccessControl {
+    pub fn new(config: &P2pConfig, registry: &Registry) -> Self {
+        Self::new_with_metrics(config, Metrics::enabled(registry))
+    }
+
+    fn new_with_metrics(config: &P2pConfig, metrics: Metrics) -> Self {
+        let access_config = config.access_control.clone().unwrap_or_default();
+        let ban_duration = access_config.invalid_checkpoint_ban_duration();
+        let configured_peers = config
+            .seed_peers
+            .iter()
+            .chain(config.fixed_peers.iter().flatten())
+            .filter_map(|peer| peer.peer_id)
+            .chain(
+                config
+                    .discovery
+                    .iter()
+                    .flat_map(|discovery| &discovery.allowlisted_peers)
+                    .map(|peer| peer.peer_id),
+            );
+        let allowlist = access_config
+            .allowlist
+            .map(|allowlist| RwLock::new(allowlist.into_iter().chain(configured_peers).collect()));
+
+        Inner {
+            allowlist,
+            denylist: access_config.denylist.into_iter().collect(),
+            ban_duration,
+            bans: Mutex::new(HashMap::new()),
+            rate_limiter: access_config
+                .per_peer_rate_limit
+                .map(|limit| RateLimiter::keyed(Quota::per_second(limit))),
+            inflight_limit: access_config.per_peer_inflight_limit,
+            inflight: DashMap::new(),
+            metrics,
+        }
+        .pipe(Arc::new)
+        .pipe(Self)
+    }
+
+    /// Checks whether `peer_id` may call the RPCs of this node.
+    pub fn check(&self, peer_id: &PeerId) -> Result<(), Rejection> {
+        if self.0.denylist.contains(peer_id) {
+            return Err(Rejection::Denylisted);
+        }
+        if self.is_banned(peer_id) {
+            return Err(Rejection::Banned);
+        }
+        match &self.0.allowlist {
+            Some(allowlist) if !allowlist.read().unwrap().contains(peer_id) => {
+                Err(Rejection::NotAllowlisted)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds trusted peers, i.e. the members of the committee, to the allowlist, if one is
+    /// configured, so that validators can always sync from each other.
+    pub fn allow_trusted_peers(&self, peer_ids: impl IntoIterator<Item = PeerId>) {
+        if let Some(allowlist) = &self.0.allowlist {
+            allowlist.write().unwrap().extend(peer_ids);
+        }
+    }
+
+    /// Whether this node may stay connected to, dial and sync from `peer_id`.
+    ///
+    /// Unlike [`Self::check`], the allowlist only restricts the requests of other peers,
+    /// so that a node serving a few peers can still sync from the rest of the network.
+    pub fn can_connect_to(&self, peer_id: &PeerId) -> bool {
+        !self.0.denylist.contains(peer_id) && !self.is_banned(peer_id)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        let mut bans = self.0.bans.lock().unwrap();
+        match bans.get(peer_id) {
+            Some(banned_until) if *banned_until > Instant::now() => true,
+            Some(_) => {
+                bans.remove(peer_id);
+                self.0.metrics.set_banned_peers(bans.len());
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Bans `peer_id` for the configured ban duration.
+    /// Returns `false` if bans are disabled.
+    pub fn ban(&self, peer_id: PeerId, reason: BanReason) -> bool {
+        if self.0.ban_duration.is_zero() {
+            return false;
+        }
+        warn!(
+            peer_id = %peer_id.short_display(4),
+            reason = reason.as_str(),
+            ban_duration = ?self.0.ban_duration,
+            "Banning peer"
+        );
+        let mut bans = self.0.bans.lock().unwrap();
+        bans.insert(peer_id, Instant::now() + self.0.ban_duration);
+        self.0.metrics.inc_peer_bans(reason);
+        self.0.metrics.set_banned_peers(bans.len());
+        true
+    }
+
+    /// A [`tower::Layer`] that enforces the access controls on the requests of the `rpc` RPC.
+    pub fn layer(&self, rpc: &'static str) -> PeerAccessLayer {
+        PeerAccessLayer {
+            access_control: self.clone(),
+            rpc,
+        }
+    }
+
+    /// Admits a request of `peer_id` to the `rpc` RPC, returning the inflight permit to hold
+    /// until the response is sent, if there is a per-peer inflight limit.
+    fn admit(
+        &self,
+        rpc: &'static str,
+        peer_id: &PeerId,
+    ) -> Result<Option<OwnedSemaphorePermit>, Status> {
+        if let Err(rejection) = self.check(peer_id) {
+            self.0.metrics.inc_rejected_requests(rpc, rejection);
+            return Err(Status::new_with_message(
+                StatusCode::BadRequest,
+                format!("request rejected: {}", rejection.as_str()),
+            ));
+        }
+
+        if let Some(rate_limiter) = &self.0.rate_limiter {
+            if rate_limiter.len() >= PRUNE_THRESHOLD {
+                rate_limiter.retain_recent();
+            }
+            if rate_limiter.check_key(peer_id).is_err() {
+                self.0.metrics.inc_rate_limited_requests(rpc);
+                return Err(Status::new(StatusCode::TooManyRequests));
+            }
+        }
+
+        let Some(inflight_limit) = self.0.inflight_limit else {
+            return Ok(None);
+        };
+        if self.0.inflight.len() >= PRUNE_THRESHOLD {
+            self.0
+                .inflight
+                .retain(|_, semaphore| semaphore.available_permits() < inflight_limit);
+        }
+        let semaphore = self
+            .0
+            .inflight
+            .entry(*peer_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(inflight_limit)))
+            .value()
+            .clone();
+        match semaphore.try_acquire_owned() {
+            Ok(permit) => Ok(Some(permit)),
+            Err(_) => {
+                self.0.metrics.inc_inflight_limited_requests(rpc);
+                Err(Status::new(StatusCode::TooManyRequests))
+            }
+        }
+    }
+}
+
+impl Default for PeerAccessControl {
+    /// Allows every peer without limits, and doesn't report metrics.
+    fn default() -> Self {
+        Self::new_with_metrics(&P2pConfig::default(), Metrics::disabled())
+    }
+}
+
+/// [`tower::Layer`] for enforcing a [`PeerAccessControl`] on the requests of an RPC.
+#[derive(Clone)]
+pub struct PeerAccessLayer {
+    access_control: PeerAccessControl,
+    rpc: &'static str,
+}
+
+impl<S> tower::layer::Layer<S> for PeerAccessLayer {
+    type Service = PeerAccess<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PeerAccess {
+            inner,
+            access_control: self.access_control.clone(),
+            rpc: self.rpc,
+        }
+    }
+}
+
+/// Middleware for enforcing a [`PeerAccessControl`] on the requests of an RPC.
+#[derive(Clone)]
+pub struct PeerAccess<S> {
+    inner: S,
+    access_control: PeerAccessControl,
+    rpc: &'static str,
+}
+
+impl<S, T, U> tower::Service<Request<T>> for PeerAccess<S>
+where
+    S: tower::Service<Request<T>, Response = Response<U>, Error = Status> + 'static + Clone + Send,
+    S::Future: Send,
+    T: 'static + Send,
+{
+    type Response = Response<U>;
+    type Error = Status;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        let access_control = self.access_control.clone();
+        let rpc = self.rpc;
+        let mut inner = self.inner.clone();
+
+        let fut = async move {
+            let peer_id = req
+                .peer_id()
+                .copied()
+                .ok_or_else(|| Status::internal("unable to query sender's PeerId"))?;
+            let permit = access_control.admit(rpc, &peer_id)?;
+
+            struct SemaphoreExtension(#[allow(unused)] OwnedSemaphorePermit);
+            inner.call(req).await.map(move |mut response| {
+                if let Some(permit) = permit {
+                    // Insert permit as extension so it's not dropped until the response is sent.
+                    response
+                        .extensions_mut()
+                        .insert(Arc::new(SemaphoreExtension(permit)));
+                }
+                response
+            })
+        };
+        Box::pin(fut)
+    }
+}
+
+#[derive(Clone)]
+struct Metrics(Option<Arc<MetricsInner>>);
+
+impl Metrics {
+    fn enabled(registry: &Registry) -> Self {
+        Metrics(Some(MetricsInner::new(registry)))
+    }
+
+    fn disabled() -> Self {
+        Metrics(None)
+    }
+
+    fn inc_rejected_requests(&self, rpc: &str, rejection: Rejection) {
+        if let Some(inner) = &self.0 {
+            inner
+                .p2p_rejected_requests
+                .with_label_values(&[rpc, rejection.as_str()])
+                .inc();
+        }
+    }
+
+    fn inc_rate_limited_requests(&self, rpc: &str) {
+        if let Some(inner) = &self.0 {
+            inner
+                .p2p_rate_limited_requests
+                .with_label_values(&[rpc])
+                .inc();
+        }
+    }
+
+    fn inc_inflight_limited_requests(&self, rpc: &str) {
+        if let Some(inner) = &self.0 {
+            inner
+                .p2p_inflight_limited_requests
+                .with_label_values(&[rpc])
+                .inc();
+        }
+    }
+
+    fn inc_peer_bans(&self, reason: BanReason) {
+        if let Some(inner) = &self.0 {
+            inner
+                .p2p_peer_bans
+                .with_label_values(&[reason.as_str()])
+                .inc();
+        }
+    }
+
+    fn set_banned_peers(&self, banned_peers: usize) {
+        if let Some(inner) = &self.0 {
+            inner.p2p_banned_peers.set(banned_peers as i64);
+        }
+    }
+}
+
+struct MetricsInner {
+    p2p_rejected_requests: IntCounterVec,
+    p2p_rate_limited_requests: IntCounterVec,
+    p2p_inflight_limited_requests: IntCounterVec,
+    p2p_peer_bans: IntCounterVec,
+    p2p_banned_peers: IntGauge,
+}
+
+impl MetricsInner {
+    fn new(registry: &Registry) -> Arc<Self> {
+        Self {
+            p2p_rejected_requests: register_int_counter_vec_with_registry!(
+                "p2p_rejected_requests",
+                "Number of p2p requests rejected because the peer is not allowlisted, is denylisted or is banned",
+                &["rpc", "reason"],
+                registry
+            )
+            .unwrap(),
+            p2p_rate_limited_requests: register_int_counter_vec_with_registry!(
+                "p2p_rate_limited_requests",
+                "Number of p2p requests rejected by the per-peer rate limit",
+                &["rpc"],
+                registry
+            )
+            .unwrap(),
+            p2p_inflight_limited_requests: register_int_counter_vec_with_registry!(
+                "p2p_inflight_limited_requests",
+                "Number of p2p requests rejected by the per-peer inflight limit",
+                &["rpc"],
+                registry
+            )
+            .unwrap(),
+            p2p_peer_bans: register_int_counter_vec_with_registry!(
+                "p2p_peer_bans",
+                "Number of times a peer was banned for misbehaving",
+                &["reason"],
+                registry
+            )
+            .unwrap(),
+            p2p_banned_peers: register_int_gauge_with_registry!(
+                "p2p_banned_peers",
+                "Number of currently banned peers",
+                registry
+            )
+            .unwrap(),
+        }
+        .pipe(Arc::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ika_config::p2p::{PeerAccessConfig, SeedPeer};
+    use std::num::NonZeroU32;
+
+    fn config(access_control: PeerAccessConfig) -> P2pConfig {
+        P2pConfig {
+            access_control: Some(access_control),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn allowlist_and_denylist() {
+        let allowed = PeerId([1; 32]);
+        let seed = PeerId([2; 32]);
+        let denied = PeerId([3; 32]);
+        let other = PeerId([4; 32]);
+
+        let mut p2p_config = config(PeerAccessConfig {
+            allowlist: Some(vec![allowed, denied]),
+            denylist: vec![denied],
+            ..Default::default()
+        });
+        p2p_config.seed_peers.push(SeedPeer {
+            peer_id: Some(seed),
+            address: "/dns/localhost/udp/8080".parse().unwrap(),
+        });
+        let access_control = PeerAccessControl::new(&p2p_config, &Registry::new());
+
+        assert_eq!(access_control.check(&allowed), Ok(()));
+        assert_eq!(access_control.check(&seed), Ok(()));
+        assert_eq!(access_control.check(&denied), Err(Rejection::Denylisted));
+        assert_eq!(access_control.check(&other), Err(Rejection::NotAllowlisted));
+        assert!(access_control.can_connect_to(&other));
+        assert!(!access_control.can_connect_to(&denied));
+
+        access_control.allow_trusted_peers([other]);
+        assert_eq!(access_control.check(&other), Ok(()));
+
+        let access_control = PeerAccessControl::default();
+        assert_eq!(access_control.check(&PeerId([5; 32])), Ok(()));
+    }
+
+    #[test]
+    fn bans_expire() {
+        let peer = PeerId([1; 32]);
+        let access_control = PeerAccessControl::new(
+            &config(PeerAccessConfig {
+                invalid_checkpoint_ban_duration_ms: Some(100),
+                ..Default::default()
+            }),
+            &Registry::new(),
+        );
+
+        assert!(access_control.ban(peer, BanReason::WrongSequenceNumber));
+        assert_eq!(access_control.check(&peer), Err(Rejection::Banned));
+        assert!(!access_control.can_connect_to(&peer));
+
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(access_control.check(&peer), Ok(()));
+
+        let never_bans = PeerAccessControl::new(
+            &config(PeerAccessConfig {
+                invalid_checkpoint_ban_duration_ms: Some(0),
+                ..Default::default()
+            }),
+            &Registry::new(),
+        );
+        assert!(!never_bans.ban(peer, BanReason::PinnedDigestMismatch));
+        assert_eq!(never_bans.check(&peer), Ok(()));
+    }
+
+    #[test]
+    fn per_peer_limits() {
+        let peer = PeerId([1; 32]);
+        let other = PeerId([2; 32]);
+        let access_control = PeerAccessControl::new(
+            &config(PeerAccessConfig {
+                per_peer_inflight_limit: Some(1),
+                ..Default::default()
+            }),
+            &Registry::new(),
+        );
+
+        let permit = access_control.admit("rpc", &peer).unwrap();
+        assert!(permit.is_some());
+        assert!(access_control.admit("rpc", &peer).is_err());
+        // The limit is per peer.
+        assert!(access_control.admit("rpc", &other).is_ok());
+        drop(permit);
+        assert!(access_control.admit("rpc", &peer).is_ok());
+
+        let access_control = PeerAccessControl::new(
+            &config(PeerAccessConfig {
+                per_peer_rate_limit: Some(NonZeroU32::new(1).unwrap()),
+                ..Default::default()
+            }),
+            &Registry::new(),
+        );
+        assert!(access_control.admit("rpc", &peer).is_ok());
+        assert!(access_control.admit("rpc", &peer).is_err());
+        assert!(access_control.admit("rpc", &other).is_ok());
+    }
+}
@@ -4,6 +4,7 @@
 use super::{
     Discovery, DiscoveryEventLoop, DiscoveryServer, State, metrics::Metrics, server::Server,
 };
+use crate::access_control::PeerAccessControl;
 use crate::discovery::TrustedPeerChangeEvent;
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::rate_limit;
@@ -25,6 +26,7 @@ pub struct Builder {
     config: Option<P2pConfig>,
     metrics: Option<Metrics>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
+    access_control: Option<PeerAccessControl>,
 }
 
 impl Builder {
@@ -34,6 +36,7 @@ impl Builder {
             config: None,
             metrics: None,
             trusted_peer_change_rx,
+            access_control: None,
         }
     }
 
@@ -47,6 +50,12 @@ impl Builder {
         self
     }
 
+    /// Shares the access controls of the peers with the other p2p services.
+    pub fn access_control(mut self, access_control: PeerAccessControl) -> Self {
+        self.access_control = Some(access_control);
+        self
+    }
+
     pub fn build(self) -> (UnstartedDiscovery, DiscoveryServer<impl Discovery>) {
         let discovery_config = self
             .config
@@ -65,6 +74,9 @@ impl Builder {
                 )),
             );
         }
+        discovery_server = discovery_server.add_layer_for_get_known_peers_v2(
+            InboundRequestLayer::new(builder.access_control.layer("get_known_peers_v2")),
+        );
         (builder, discovery_server)
     }
 
@@ -73,9 +85,11 @@ impl Builder {
             config,
             metrics,
             trusted_peer_change_rx,
+            access_control,
         } = self;
         let config = config.unwrap();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
+        let access_control = access_control.unwrap_or_default();
         let (sender, receiver) = oneshot::channel();
 
         let handle = Handle {
@@ -102,6 +116,7 @@ impl Builder {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                access_control,
             },
             server,
         )
@@ -116,6 +131,7 @@ pub struct UnstartedDiscovery {
     pub(super) state: Arc<RwLock<State>>,
     pub(super) trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     pub(super) metrics: Metrics,
+    pub(super) access_control: PeerAccessControl,
 }
 
 impl UnstartedDiscovery {
@@ -131,6 +147,7 @@ impl UnstartedDiscovery {
             state,
             trusted_peer_change_rx,
             metrics,
+            access_control,
         } = self;
 
         let discovery_config = config.discovery.clone().unwrap_or_default();
@@ -160,6 +177,7 @@ impl UnstartedDiscovery {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                access_control,
             },
             handle,
         )
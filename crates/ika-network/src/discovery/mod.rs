@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
+use crate::access_control::PeerAccessControl;
 use anemo::types::PeerInfo;
 use anemo::{Network, Peer, PeerId, Request, Response, types::PeerEvent};
 use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
@@ -125,6 +126,7 @@ struct DiscoveryEventLoop {
     state: Arc<RwLock<State>>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     metrics: Metrics,
+    access_control: PeerAccessControl,
 }
 
 impl DiscoveryEventLoop {
@@ -229,6 +231,10 @@ impl DiscoveryEventLoop {
                 .collect(),
         };
         for (peer_id, address) in initial_peers.into_iter() {
+            if !self.access_control.can_connect_to(&peer_id) {
+                warn!(?peer_id, "Skipping a denylisted preferred peer");
+                continue;
+            }
             let anemo_address = if let Some(address) = address {
                 let Ok(address) = address.to_anemo_address() else {
                     warn!(p2p_address=?address, "Can't convert a p2p address to anemo address");
@@ -270,6 +276,12 @@ impl DiscoveryEventLoop {
         &mut self,
         trusted_peer_change_event: TrustedPeerChangeEvent,
     ) {
+        self.access_control.allow_trusted_peers(
+            trusted_peer_change_event
+                .new_peers
+                .iter()
+                .map(|peer_info| peer_info.peer_id),
+        );
         for peer_info in trusted_peer_change_event.new_peers {
             info!(?peer_info, "Add committee member as preferred peer.");
             self.network.known_peers().insert(peer_info);
@@ -279,6 +291,11 @@ impl DiscoveryEventLoop {
     fn handle_peer_event(&mut self, peer_event: Result<PeerEvent, RecvError>) {
         match peer_event {
             Ok(PeerEvent::NewPeer(peer_id)) => {
+                if !self.access_control.can_connect_to(&peer_id) {
+                    debug!(?peer_id, "Disconnecting from a denylisted or banned peer");
+                    let _ = self.network.disconnect(peer_id);
+                    return;
+                }
                 if let Some(peer) = self.network.peer(peer_id) {
                     self.state
                         .write()
@@ -358,6 +375,7 @@ impl DiscoveryEventLoop {
                 !info.addresses.is_empty() // Peer has addresses we can dial
                 && !state.connected_peers.contains_key(peer_id) // We're not already connected
                 && !self.pending_dials.contains_key(peer_id) // There is no pending dial to this node
+                && self.access_control.can_connect_to(peer_id) // The peer is not denylisted or banned
             })
             .collect::<Vec<_>>();
 
@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
 use super::*;
+use crate::access_control::BanReason;
 use crate::utils::{build_network_and_key, build_network_with_anemo_config};
 use anemo::Result;
 use anemo::types::PeerAffinity;
 use fastcrypto::ed25519::Ed25519PublicKey;
 use futures::stream::FuturesUnordered;
-use ika_config::p2p::AllowlistedPeer;
+use ika_config::p2p::{AllowlistedPeer, PeerAccessConfig};
 use std::collections::HashSet;
 use tokio::time::timeout;
 
@@ -143,6 +144,61 @@ async fn make_connection_to_seed_peer_with_peer_id() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn denylisted_peers_are_rejected() -> Result<()> {
+    let (builder, server) = Builder::new(create_test_channel().1)
+        .config(P2pConfig::default())
+        .build();
+    let (network_2, key_2) = build_network_and_key(|router| router.add_rpc_service(server));
+    let (_event_loop_2, _handle_2) = builder.build(network_2.clone(), key_2);
+
+    let (builder, server) = Builder::new(create_test_channel().1)
+        .config(P2pConfig::default())
+        .build();
+    let (network_3, key_3) = build_network_and_key(|router| router.add_rpc_service(server));
+    let (_event_loop_3, _handle_3) = builder.build(network_3.clone(), key_3);
+
+    // Node 1 denylists node 2.
+    let config = P2pConfig {
+        access_control: Some(PeerAccessConfig {
+            denylist: vec![network_2.peer_id()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let access_control = PeerAccessControl::new(&config, &prometheus::Registry::new());
+    let (builder, server) = Builder::new(create_test_channel().1)
+        .config(config)
+        .access_control(access_control.clone())
+        .build();
+    let (network_1, key_1) = build_network_and_key(|router| router.add_rpc_service(server));
+    let (mut event_loop_1, _handle_1) = builder.build(network_1.clone(), key_1);
+    event_loop_1.construct_our_info();
+
+    let peer_id_1 = network_2.connect(network_1.local_addr()).await?;
+    DiscoveryClient::new(network_2.peer(peer_id_1).unwrap())
+        .get_known_peers_v2(Request::new(()))
+        .await
+        .unwrap_err();
+
+    let peer_id_1 = network_3.connect(network_1.local_addr()).await?;
+    let response = DiscoveryClient::new(network_3.peer(peer_id_1).unwrap())
+        .get_known_peers_v2(Request::new(()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.own_info.peer_id, network_1.peer_id());
+
+    // Once banned, node 3 is rejected as well.
+    access_control.ban(network_3.peer_id(), BanReason::WrongSequenceNumber);
+    DiscoveryClient::new(network_3.peer(peer_id_1).unwrap())
+        .get_known_peers_v2(Request::new(()))
+        .await
+        .unwrap_err();
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "current_thread", start_paused = true)]
 async fn three_nodes_can_connect_via_discovery() -> Result<()> {
     // Setup the peer that will be the seed for the other two
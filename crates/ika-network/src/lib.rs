@@ -4,8 +4,10 @@
 use mysten_network::config::Config;
 use std::time::Duration;
 
+pub mod access_control;
 pub mod api;
 pub mod discovery;
+pub mod network_keys;
 pub mod state_sync;
 pub mod utils;
 
@@ -0,0 +1,89 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use super::{Handle, NetworkKeys, NetworkKeysServer, Store, metrics::Metrics, server::Server};
+use crate::access_control::PeerAccessControl;
+use anemo::codegen::InboundRequestLayer;
+
+/// Network Keys Service Builder.
+pub struct Builder {
+    metrics: Option<Metrics>,
+    access_control: Option<PeerAccessControl>,
+}
+
+impl Builder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            metrics: None,
+            access_control: None,
+        }
+    }
+
+    pub fn with_metrics(mut self, registry: &prometheus::Registry) -> Self {
+        self.metrics = Some(Metrics::enabled(registry));
+        self
+    }
+
+    /// Shares the access controls of the peers with the other p2p services.
+    pub fn access_control(mut self, access_control: PeerAccessControl) -> Self {
+        self.access_control = Some(access_control);
+        self
+    }
+
+    pub fn build(self) -> (UnstartedNetworkKeys, NetworkKeysServer<impl NetworkKeys>) {
+        let (builder, server) = self.build_internal();
+        let network_keys_server = NetworkKeysServer::new(server)
+            .add_layer_for_get_table_vec_chunks(InboundRequestLayer::new(
+                builder.access_control.layer("get_table_vec_chunks"),
+            ));
+        (builder, network_keys_server)
+    }
+
+    pub(super) fn build_internal(self) -> (UnstartedNetworkKeys, Server) {
+        let Builder {
+            metrics,
+            access_control,
+        } = self;
+        let metrics = metrics.unwrap_or_else(Metrics::disabled);
+        let access_control = access_control.unwrap_or_default();
+        let store = Store::default();
+
+        let server = Server {
+            store: store.clone(),
+            metrics: metrics.clone(),
+        };
+
+        (
+            UnstartedNetworkKeys {
+                store,
+                metrics,
+                access_control,
+            },
+            server,
+        )
+    }
+}
+
+/// Handle to an unstarted network keys service.
+pub struct UnstartedNetworkKeys {
+    pub(super) store: Store,
+    pub(super) metrics: Metrics,
+    pub(super) access_control: PeerAccessControl,
+}
+
+impl UnstartedNetworkKeys {
+    pub fn start(self, network: anemo::Network) -> Handle {
+        let Self {
+            store,
+            metrics,
+            access_control,
+        } = self;
+        Handle {
+            network,
+            store,
+            metrics,
+            access_control,
+        }
+    }
+}
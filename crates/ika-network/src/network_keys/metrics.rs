@@ -0,0 +1,70 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use prometheus::{IntCounterVec, Registry, register_int_counter_vec_with_registry};
+use std::sync::Arc;
+use tap::Pipe;
+
+#[derive(Clone)]
+pub(super) struct Metrics(Option<Arc<Inner>>);
+
+impl std::fmt::Debug for Metrics {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Metrics").finish()
+    }
+}
+
+impl Metrics {
+    pub fn enabled(registry: &Registry) -> Self {
+        Metrics(Some(Inner::new(registry)))
+    }
+
+    pub fn disabled() -> Self {
+        Metrics(None)
+    }
+
+    pub fn inc_table_vec_fetches(&self, result: &str) {
+        if let Some(inner) = &self.0 {
+            inner
+                .network_key_table_vec_fetches
+                .with_label_values(&[result])
+                .inc();
+        }
+    }
+
+    pub fn inc_table_vec_requests_served(&self, found: bool) {
+        if let Some(inner) = &self.0 {
+            inner
+                .network_key_table_vec_requests_served
+                .with_label_values(&[if found { "found" } else { "not_found" }])
+                .inc();
+        }
+    }
+}
+
+struct Inner {
+    network_key_table_vec_fetches: IntCounterVec,
+    network_key_table_vec_requests_served: IntCounterVec,
+}
+
+impl Inner {
+    pub fn new(registry: &Registry) -> Arc<Self> {
+        Self {
+            network_key_table_vec_fetches: register_int_counter_vec_with_registry!(
+                "network_key_table_vec_fetches",
+                "Number of network encryption key public output tables requested from peers, by result",
+                &["result"],
+                registry
+            )
+            .unwrap(),
+            network_key_table_vec_requests_served: register_int_counter_vec_with_registry!(
+                "network_key_table_vec_requests_served",
+                "Number of requests of peers for network encryption key public output tables, by whether the table was found",
+                &["result"],
+                registry
+            )
+            .unwrap(),
+        }
+        .pipe(Arc::new)
+    }
+}
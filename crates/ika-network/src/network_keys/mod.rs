@@ -0,0 +1,188 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Peer-to-peer distribution of the public outputs of the network encryption keys.
+//!
+//! The network DKG and reconfiguration public outputs of a network encryption key are stored on
+//! Sui as `TableVec`s of byte chunks, and amount to megabytes. Every node that has read them
+//! serves their chunk objects to its peers, so that a bootstrapping node can fetch them from the
+//! network rather than reading every chunk from Sui.
+//!
+//! Listing the chunks of a `TableVec` on Sui is cheap, and yields the reference, including the
+//! digest, of every chunk object. The chunks served by a peer are only accepted if each of them
+//! hashes to the digest of the on-chain chunk with the same ID, so peers can't alter the outputs.
+//! A peer that serves chunks that don't match is banned.
+
+use crate::access_control::{BanReason, PeerAccessControl};
+use anemo::{PeerId, Request};
+use rand::seq::IteratorRandom;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::dynamic_field::Field;
+use sui_types::object::Object;
+use tracing::{debug, warn};
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/ika.NetworkKeys.rs"));
+}
+mod builder;
+mod metrics;
+mod server;
+#[cfg(test)]
+mod tests;
+
+use self::metrics::Metrics;
+pub use builder::{Builder, UnstartedNetworkKeys};
+pub use generated::{
+    network_keys_client::NetworkKeysClient,
+    network_keys_server::{NetworkKeys, NetworkKeysServer},
+};
+pub use server::GetTableVecChunksRequest;
+
+/// The number of peers asked for a `TableVec` before giving up on the peers.
+const MAX_PEERS_TO_QUERY: usize = 3;
+
+/// How long to wait for a peer to serve a `TableVec`.
+const GET_TABLE_VEC_CHUNKS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The chunk objects of `TableVec`s, by table ID.
+pub type TableVecs = HashMap<ObjectID, Arc<Vec<Object>>>;
+
+/// The `TableVec`s served to peers, by network encryption key.
+#[derive(Clone, Default)]
+struct Store(Arc<RwLock<HashMap<ObjectID, TableVecs>>>);
+
+impl Store {
+    fn get(&self, table_id: &ObjectID) -> Option<Arc<Vec<Object>>> {
+        self.0
+            .read()
+            .unwrap()
+            .values()
+            .find_map(|tables| tables.get(table_id).cloned())
+    }
+
+    fn insert(&self, key_id: ObjectID, tables: TableVecs) {
+        self.0.write().unwrap().insert(key_id, tables);
+    }
+}
+
+/// A handle to the network keys service, to fetch public outputs from peers and to serve them.
+#[derive(Clone)]
+pub struct Handle {
+    network: anemo::Network,
+    store: Store,
+    metrics: Metrics,
+    access_control: PeerAccessControl,
+}
+
+impl Handle {
+    /// Serves `tables`, the chunk objects of the public outputs of the network encryption key
+    /// `key_id` by table ID, replacing the tables served for the key before.
+    pub fn serve_network_key(&self, key_id: ObjectID, tables: TableVecs) {
+        self.store.insert(key_id, tables);
+    }
+
+    /// Fetches the chunk objects of the `TableVec` `table_id` from a few peers, in random order.
+    ///
+    /// `chunk_refs` are the references of the chunks of the table on Sui, which the served
+    /// chunks must match. Returns `None` if no peer served valid chunks.
+    pub async fn fetch_table_vec(
+        &self,
+        table_id: ObjectID,
+        chunk_refs: &[ObjectRef],
+    ) -> Option<Vec<Object>> {
+        let peer_ids = self
+            .network
+            .peers()
+            .into_iter()
+            .filter(|peer_id| self.access_control.can_connect_to(peer_id))
+            .choose_multiple(&mut rand::thread_rng(), MAX_PEERS_TO_QUERY);
+        for peer_id in peer_ids {
+            let Some(peer) = self.network.peer(peer_id) else {
+                continue;
+            };
+            let request = Request::new(GetTableVecChunksRequest { table_id })
+                .with_timeout(GET_TABLE_VEC_CHUNKS_TIMEOUT);
+            let chunks = match NetworkKeysClient::new(peer)
+                .get_table_vec_chunks(request)
+                .await
+            {
+                Ok(response) => response.into_inner(),
+                Err(error) => {
+                    debug!(peer_id = %peer_id.short_display(4), ?table_id, %error, "Failed to fetch table vec from peer");
+                    self.metrics.inc_table_vec_fetches("failed");
+                    continue;
+                }
+            };
+            let Some(chunks) = chunks else {
+                self.metrics.inc_table_vec_fetches("not_found");
+                continue;
+            };
+            if let Err(error) = verify_table_vec_chunks(chunk_refs, &chunks) {
+                warn!(peer_id = %peer_id.short_display(4), ?table_id, %error, "Peer served an invalid table vec");
+                self.metrics.inc_table_vec_fetches("invalid");
+                self.ban_peer(peer_id);
+                continue;
+            }
+            self.metrics.inc_table_vec_fetches("fetched");
+            return Some(chunks);
+        }
+        None
+    }
+
+    fn ban_peer(&self, peer_id: PeerId) {
+        if self
+            .access_control
+            .ban(peer_id, BanReason::NetworkKeyDigestMismatch)
+        {
+            let _ = self.network.disconnect(peer_id);
+        }
+    }
+}
+
+/// Checks that `chunks` are exactly the chunk objects referenced by `chunk_refs`.
+pub fn verify_table_vec_chunks(chunk_refs: &[ObjectRef], chunks: &[Object]) -> Result<(), String> {
+    if chunks.len() != chunk_refs.len() {
+        return Err(format!(
+            "expected {} chunks, got {}",
+            chunk_refs.len(),
+            chunks.len()
+        ));
+    }
+    let mut expected: HashMap<ObjectID, ObjectRef> = chunk_refs
+        .iter()
+        .map(|chunk_ref| (chunk_ref.0, *chunk_ref))
+        .collect();
+    for chunk in chunks {
+        let chunk_ref = chunk.compute_object_reference();
+        if expected.remove(&chunk_ref.0) != Some(chunk_ref) {
+            return Err(format!(
+                "chunk {} doesn't match the on-chain object",
+                chunk_ref.0
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Concatenates the bytes held by the chunk objects of a `TableVec<vector<u8>>`, in index order.
+pub fn table_vec_bytes(chunks: &[Object]) -> anyhow::Result<Vec<u8>> {
+    let mut indexed_chunks = chunks
+        .iter()
+        .map(|chunk| {
+            let move_object = chunk
+                .data
+                .try_as_move()
+                .ok_or_else(|| anyhow::anyhow!("chunk {} is not a Move object", chunk.id()))?;
+            let field = bcs::from_bytes::<Field<u64, Vec<u8>>>(move_object.contents())?;
+            Ok((field.name, field.value))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    indexed_chunks.sort_by_key(|(index, _)| *index);
+    Ok(indexed_chunks
+        .into_iter()
+        .flat_map(|(_, bytes)| bytes)
+        .collect())
+}
@@ -0,0 +1,34 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use super::{NetworkKeys, Store, metrics::Metrics};
+use anemo::{Request, Response, rpc::Status};
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::ObjectID;
+use sui_types::object::Object;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetTableVecChunksRequest {
+    /// The ID of the `TableVec` holding a public output of a network encryption key.
+    pub table_id: ObjectID,
+}
+
+pub(super) struct Server {
+    pub(super) store: Store,
+    pub(super) metrics: Metrics,
+}
+
+#[anemo::async_trait]
+impl NetworkKeys for Server {
+    async fn get_table_vec_chunks(
+        &self,
+        request: Request<GetTableVecChunksRequest>,
+    ) -> Result<Response<Option<Vec<Object>>>, Status> {
+        let chunks = self
+            .store
+            .get(&request.body().table_id)
+            .map(|chunks| chunks.as_ref().clone());
+        self.metrics.inc_table_vec_requests_served(chunks.is_some());
+        Ok(Response::new(chunks))
+    }
+}
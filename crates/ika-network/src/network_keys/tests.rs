@@ -0,0 +1,97 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use super::*;
+use crate::utils::build_network;
+use anemo::Result;
+use sui_types::base_types::SuiAddress;
+
+fn random_chunks(count: usize) -> Vec<Object> {
+    (0..count)
+        .map(|_| {
+            Object::with_id_owner_for_testing(
+                ObjectID::random(),
+                SuiAddress::random_for_testing_only(),
+            )
+        })
+        .collect()
+}
+
+fn chunk_refs(chunks: &[Object]) -> Vec<ObjectRef> {
+    chunks
+        .iter()
+        .map(Object::compute_object_reference)
+        .collect()
+}
+
+fn start_node(access_control: PeerAccessControl) -> (anemo::Network, Handle) {
+    let (unstarted, server) = Builder::new().access_control(access_control).build();
+    let network = build_network(|router| router.add_rpc_service(server));
+    let handle = unstarted.start(network.clone());
+    (network, handle)
+}
+
+#[tokio::test]
+async fn fetch_table_vec_from_peer() -> Result<()> {
+    let (network_1, handle_1) = start_node(PeerAccessControl::default());
+    let (network_2, handle_2) = start_node(PeerAccessControl::default());
+    network_2.connect(network_1.local_addr()).await?;
+
+    let table_id = ObjectID::random();
+    let chunks = random_chunks(3);
+    let refs = chunk_refs(&chunks);
+
+    // The peer doesn't serve the table yet.
+    assert_eq!(handle_2.fetch_table_vec(table_id, &refs).await, None);
+
+    handle_1.serve_network_key(
+        ObjectID::random(),
+        HashMap::from([(table_id, Arc::new(chunks.clone()))]),
+    );
+    assert_eq!(
+        handle_2.fetch_table_vec(table_id, &refs).await,
+        Some(chunks)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn peers_serving_invalid_chunks_are_banned() -> Result<()> {
+    let (network_1, handle_1) = start_node(PeerAccessControl::default());
+    let access_control = PeerAccessControl::default();
+    let (network_2, handle_2) = start_node(access_control.clone());
+    network_2.connect(network_1.local_addr()).await?;
+
+    let table_id = ObjectID::random();
+    let chunks = random_chunks(2);
+    handle_1.serve_network_key(
+        ObjectID::random(),
+        HashMap::from([(table_id, Arc::new(random_chunks(2)))]),
+    );
+
+    assert_eq!(
+        handle_2
+            .fetch_table_vec(table_id, &chunk_refs(&chunks))
+            .await,
+        None
+    );
+    assert!(access_control.is_banned(&network_1.peer_id()));
+    Ok(())
+}
+
+#[test]
+fn verify_chunks() {
+    let chunks = random_chunks(3);
+    let refs = chunk_refs(&chunks);
+    verify_table_vec_chunks(&refs, &chunks).unwrap();
+
+    // A missing chunk.
+    verify_table_vec_chunks(&refs, &chunks[..2]).unwrap_err();
+    // A duplicated chunk in place of another.
+    let duplicated = vec![chunks[0].clone(), chunks[0].clone(), chunks[1].clone()];
+    verify_table_vec_chunks(&refs, &duplicated).unwrap_err();
+    // A chunk that differs from the on-chain one.
+    let mut other_refs = refs.clone();
+    other_refs[1] = chunk_refs(&random_chunks(1))[0];
+    verify_table_vec_chunks(&other_refs, &chunks).unwrap_err();
+}
@@ -4,8 +4,10 @@
 use super::{
     Handle, PeerHeights, StateSync, StateSyncEventLoop, StateSyncMessage, StateSyncServer,
     metrics::Metrics,
+    peer_scores::{DownloadConcurrency, PeerScores},
     server::{CheckpointMessageDownloadLimitLayer, Server},
 };
+use crate::access_control::PeerAccessControl;
 use crate::state_sync::server::SystemCheckpointDownloadLimitLayer;
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::{inflight_limit, rate_limit};
@@ -21,7 +23,7 @@ use std::{
 };
 use tap::Pipe;
 use tokio::{
-    sync::{broadcast, mpsc},
+    sync::{Notify, broadcast, mpsc},
     task::JoinSet,
 };
 
@@ -31,6 +33,7 @@ pub struct Builder<S> {
     metrics: Option<Metrics>,
     archive_readers: Option<ArchiveReaderBalancer>,
     chain_identifier: Option<ChainIdentifier>,
+    access_control: Option<PeerAccessControl>,
 }
 
 impl Builder<()> {
@@ -42,6 +45,7 @@ impl Builder<()> {
             metrics: None,
             archive_readers: None,
             chain_identifier: None,
+            access_control: None,
         }
     }
 }
@@ -54,6 +58,7 @@ impl<S> Builder<S> {
             metrics: self.metrics,
             archive_readers: self.archive_readers,
             chain_identifier: self.chain_identifier,
+            access_control: self.access_control,
         }
     }
 
@@ -71,6 +76,12 @@ impl<S> Builder<S> {
         self.archive_readers = Some(archive_readers);
         self
     }
+
+    /// Shares the access controls of the peers with the other p2p services.
+    pub fn access_control(mut self, access_control: PeerAccessControl) -> Self {
+        self.access_control = Some(access_control);
+        self
+    }
 }
 
 impl<S> Builder<S>
@@ -113,6 +124,61 @@ where
             state_sync_server = state_sync_server
                 .add_layer_for_get_dwallet_checkpoint_message(InboundRequestLayer::new(layer));
         }
+        if let Some(limit) = state_sync_config.push_system_checkpoint_message_rate_limit {
+            state_sync_server = state_sync_server.add_layer_for_push_system_checkpoint(
+                InboundRequestLayer::new(rate_limit::RateLimitLayer::new(
+                    governor::Quota::per_second(limit),
+                    rate_limit::WaitMode::Block,
+                )),
+            );
+        }
+        if let Some(limit) = state_sync_config.get_system_checkpoint_message_rate_limit {
+            state_sync_server = state_sync_server.add_layer_for_get_system_checkpoint(
+                InboundRequestLayer::new(rate_limit::RateLimitLayer::new(
+                    governor::Quota::per_second(limit),
+                    rate_limit::WaitMode::Block,
+                )),
+            );
+        }
+        if let Some(limit) = state_sync_config.get_system_checkpoint_message_inflight_limit {
+            state_sync_server = state_sync_server.add_layer_for_get_system_checkpoint(
+                InboundRequestLayer::new(inflight_limit::InflightLimitLayer::new(
+                    limit,
+                    inflight_limit::WaitMode::ReturnError,
+                )),
+            );
+        }
+        if let Some(limit) = state_sync_config.get_system_checkpoint_message_per_checkpoint_limit {
+            let layer = SystemCheckpointDownloadLimitLayer::new(limit);
+            builder.system_checkpoint_download_limit_layer = Some(layer.clone());
+            state_sync_server = state_sync_server
+                .add_layer_for_get_system_checkpoint(InboundRequestLayer::new(layer));
+        }
+
+        // Apply the access controls of the peers to every RPC.
+        let access_control = &builder.access_control;
+        state_sync_server = state_sync_server
+            .add_layer_for_push_dwallet_checkpoint_message(InboundRequestLayer::new(
+                access_control.layer("push_dwallet_checkpoint_message"),
+            ))
+            .add_layer_for_get_dwallet_checkpoint_message(InboundRequestLayer::new(
+                access_control.layer("get_dwallet_checkpoint_message"),
+            ))
+            .add_layer_for_get_dwallet_checkpoint_availability(InboundRequestLayer::new(
+                access_control.layer("get_dwallet_checkpoint_availability"),
+            ))
+            .add_layer_for_push_system_checkpoint(InboundRequestLayer::new(
+                access_control.layer("push_system_checkpoint"),
+            ))
+            .add_layer_for_get_system_checkpoint(InboundRequestLayer::new(
+                access_control.layer("get_system_checkpoint"),
+            ))
+            .add_layer_for_get_system_checkpoint_availability(InboundRequestLayer::new(
+                access_control.layer("get_system_checkpoint_availability"),
+            ))
+            .add_layer_for_get_chain_identifier(InboundRequestLayer::new(
+                access_control.layer("get_chain_identifier"),
+            ));
 
         (builder, state_sync_server)
     }
@@ -124,12 +190,14 @@ where
             metrics,
             archive_readers,
             chain_identifier,
+            access_control,
         } = self;
         let store = store.unwrap();
         let config = config.unwrap_or_default();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
         let archive_readers = archive_readers.unwrap_or_default();
         let chain_identifier = chain_identifier.unwrap_or_default();
+        let access_control = access_control.unwrap_or_default();
 
         let (sender, mailbox) = mpsc::channel(config.mailbox_capacity());
         let (dwallet_checkpoint_event_sender, _receiver) =
@@ -137,10 +205,12 @@ where
         let (system_checkpoint_event_sender, _receiver) =
             broadcast::channel(config.synced_system_checkpoint_broadcast_channel_capacity());
         let weak_sender = sender.downgrade();
+        let peer_scores = PeerScores::default();
         let handle = Handle {
             sender,
             dwallet_checkpoint_event_sender: dwallet_checkpoint_event_sender.clone(),
             system_checkpoint_event_sender: system_checkpoint_event_sender.clone(),
+            peer_scores: peer_scores.clone(),
         };
         let peer_heights = PeerHeights {
             peers: HashMap::new(),
@@ -175,6 +245,8 @@ where
                 archive_readers,
                 chain_identifier,
                 system_checkpoint_download_limit_layer: None,
+                access_control,
+                peer_scores,
             },
             server,
         )
@@ -194,6 +266,8 @@ pub struct UnstartedStateSync<S> {
     pub(super) metrics: Metrics,
     pub(super) archive_readers: ArchiveReaderBalancer,
     pub(crate) chain_identifier: ChainIdentifier,
+    pub(super) access_control: PeerAccessControl,
+    pub(super) peer_scores: PeerScores,
 }
 
 impl<S> UnstartedStateSync<S>
@@ -218,7 +292,13 @@ where
             metrics,
             archive_readers,
             chain_identifier,
+            access_control,
+            peer_scores,
         } = self;
+        let dwallet_checkpoint_download_concurrency =
+            DownloadConcurrency::new(config.dwallet_checkpoint_header_download_concurrency());
+        let system_checkpoint_download_concurrency =
+            DownloadConcurrency::new(config.system_checkpoint_header_download_concurrency());
 
         (
             StateSyncEventLoop {
@@ -227,10 +307,13 @@ where
                 mailbox,
                 weak_sender: handle.sender.downgrade(),
                 tasks: JoinSet::new(),
+                sync_tasks: JoinSet::new(),
                 sync_checkpoint_messages_task: None,
+                dwallet_checkpoint_sync_retry_at: None,
                 download_limit_layer,
                 system_checkpoint_event_sender,
                 sync_system_checkpoints_task: None,
+                system_checkpoint_sync_retry_at: None,
                 system_checkpoint_download_limit_layer,
                 store,
                 peer_heights,
@@ -241,6 +324,12 @@ where
                 sync_checkpoint_from_archive_task: None,
                 chain_identifier,
                 sync_system_checkpoint_from_archive_task: None,
+                access_control,
+                peer_scores,
+                dwallet_checkpoint_download_concurrency,
+                system_checkpoint_download_concurrency,
+                dwallet_checkpoint_archive_fallback: Arc::new(Notify::new()),
+                system_checkpoint_archive_fallback: Arc::new(Notify::new()),
             },
             handle,
         )
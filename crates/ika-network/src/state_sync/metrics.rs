@@ -80,6 +80,22 @@ impl Metrics {
         }
     }
 
+    pub fn set_dwallet_checkpoint_download_concurrency(&self, concurrency: usize) {
+        if let Some(inner) = &self.0 {
+            inner
+                .dwallet_checkpoint_download_concurrency
+                .set(concurrency as i64);
+        }
+    }
+
+    pub fn set_system_checkpoint_download_concurrency(&self, concurrency: usize) {
+        if let Some(inner) = &self.0 {
+            inner
+                .system_checkpoint_download_concurrency
+                .set(concurrency as i64);
+        }
+    }
+
     pub fn set_highest_synced_system_checkpoint(
         &self,
         sequence_number: SystemCheckpointSequenceNumber,
@@ -100,6 +116,9 @@ struct Inner {
     highest_known_system_checkpoint: IntGauge,
     highest_verified_system_checkpoint: IntGauge,
     highest_synced_system_checkpoint: IntGauge,
+
+    dwallet_checkpoint_download_concurrency: IntGauge,
+    system_checkpoint_download_concurrency: IntGauge,
 }
 
 impl Inner {
@@ -144,6 +163,19 @@ impl Inner {
                 registry
             )
             .unwrap(),
+
+            dwallet_checkpoint_download_concurrency: register_int_gauge_with_registry!(
+                "dwallet_checkpoint_download_concurrency",
+                "Current limit on the dwallet checkpoints downloaded concurrently from peers",
+                registry
+            )
+            .unwrap(),
+            system_checkpoint_download_concurrency: register_int_gauge_with_registry!(
+                "system_checkpoint_download_concurrency",
+                "Current limit on the system checkpoints downloaded concurrently from peers",
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
@@ -51,22 +51,24 @@ use anemo::{PeerId, Request, Response, Result, types::PeerEvent};
 use futures::{FutureExt, StreamExt};
 use ika_config::p2p::StateSyncConfig;
 use ika_types::{
+    committee::EpochId,
     digests::DWalletCheckpointMessageDigest,
+    error::IkaResult,
     messages_dwallet_checkpoint::{
         CertifiedDWalletCheckpointMessage, DWalletCheckpointSequenceNumber,
         VerifiedDWalletCheckpointMessage,
     },
-    storage::WriteStore,
+    storage::{ReadStore, WriteStore},
 };
 use rand::Rng;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{Arc, RwLock},
-    time::Duration,
+    time::{Duration, Instant},
 };
-use tap::{Pipe, TapFallible, TapOptional};
-use tokio::sync::oneshot;
+use tap::Pipe;
+use tokio::sync::{Notify, oneshot};
 use tokio::{
     sync::{broadcast, mpsc},
     task::{AbortHandle, JoinSet},
@@ -78,9 +80,17 @@ mod generated {
 }
 mod builder;
 mod metrics;
+mod peer_scores;
 mod server;
+#[cfg(test)]
+mod tests;
 
-use self::{metrics::Metrics, server::CheckpointMessageDownloadLimitLayer};
+use self::{
+    metrics::Metrics,
+    peer_scores::{DownloadConcurrency, PeerScores},
+    server::CheckpointMessageDownloadLimitLayer,
+};
+use crate::access_control::{BanReason, PeerAccessControl};
 pub use crate::state_sync::server::GetChainIdentifierResponse;
 use crate::state_sync::server::{
     GetSystemCheckpointAvailabilityResponse, GetSystemCheckpointRequest,
@@ -97,9 +107,13 @@ use ika_types::messages_system_checkpoints::{
     CertifiedSystemCheckpointMessage, SystemCheckpointSequenceNumber,
     VerifiedSystemCheckpointMessage,
 };
+pub use peer_scores::PeerSyncStats;
 pub use server::GetCheckpointMessageRequest;
 pub use server::GetDWalletCheckpointAvailabilityResponse;
 
+/// How often the archives are polled for checkpoints when peers serve them fast enough.
+const ARCHIVE_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
 /// A handle to the StateSync subsystem.
 ///
 /// This handle can be cloned and shared. Once all copies of a StateSync system's Handle have been
@@ -109,6 +123,7 @@ pub struct Handle {
     sender: mpsc::Sender<StateSyncMessage>,
     dwallet_checkpoint_event_sender: broadcast::Sender<VerifiedDWalletCheckpointMessage>,
     system_checkpoint_event_sender: broadcast::Sender<VerifiedSystemCheckpointMessage>,
+    peer_scores: PeerScores,
 }
 
 impl Handle {
@@ -150,6 +165,11 @@ impl Handle {
     ) -> broadcast::Receiver<VerifiedSystemCheckpointMessage> {
         self.system_checkpoint_event_sender.subscribe()
     }
+
+    /// Returns the statistics of the checkpoint downloads from each peer, best peers first.
+    pub fn peer_sync_stats(&self) -> Vec<PeerSyncStats> {
+        self.peer_scores.stats()
+    }
 }
 
 struct PeerHeights {
@@ -165,7 +185,6 @@ struct PeerHeights {
     sequence_number_to_digest_system_checkpoint:
         HashMap<SystemCheckpointSequenceNumber, SystemCheckpointMessageDigest>,
 
-    #[allow(unused)]
     // The amount of time to wait before retry if there are no peers to sync content from.
     wait_interval_when_no_peer_to_sync_content: Duration,
 }
@@ -400,13 +419,13 @@ impl PeerHeights {
         self.unprocessed_system_checkpoint.get(digest)
     }
 
-    #[allow(unused)]
     pub fn wait_interval_when_no_peer_to_sync_content(&self) -> Duration {
         self.wait_interval_when_no_peer_to_sync_content
     }
 }
 
-// PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
+// PeerBalancer is an Iterator that selects peers based on their past latency and failures, or on
+// RTT for peers we haven't synced from yet, with some added randomness.
 #[derive(Clone)]
 struct PeerBalancer {
     peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
@@ -415,19 +434,28 @@ struct PeerBalancer {
 }
 
 impl PeerBalancer {
-    pub fn new(network: &anemo::Network, peer_heights: Arc<RwLock<PeerHeights>>) -> Self {
+    pub fn new(
+        network: &anemo::Network,
+        peer_heights: Arc<RwLock<PeerHeights>>,
+        access_control: &PeerAccessControl,
+        peer_scores: &PeerScores,
+    ) -> Self {
         let mut peers: Vec<_> = peer_heights
             .read()
             .unwrap()
             .peers_on_same_chain()
+            // Filter out any peers who are denylisted or banned.
+            .filter(|(peer_id, _info)| access_control.can_connect_to(peer_id))
             // Filter out any peers who we aren't connected with.
             .filter_map(|(peer_id, info)| {
-                network
-                    .peer(*peer_id)
-                    .map(|peer| (peer.connection_rtt(), peer, *info))
+                network.peer(*peer_id).map(|peer| {
+                    let expected_latency =
+                        peer_scores.expected_latency(peer_id, peer.connection_rtt());
+                    (expected_latency, peer, *info)
+                })
             })
             .collect();
-        peers.sort_by(|(rtt_a, _, _), (rtt_b, _, _)| rtt_a.cmp(rtt_b));
+        peers.sort_by(|(latency_a, _, _), (latency_b, _, _)| latency_a.cmp(latency_b));
         Self {
             peers: peers
                 .into_iter()
@@ -473,6 +501,26 @@ impl Iterator for PeerBalancer {
     }
 }
 
+/// The checkpoints a sync task syncs from the peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SyncTarget {
+    DWalletCheckpoints,
+    SystemCheckpoints,
+}
+
+/// Returned by a sync task when no peer could serve one of the checkpoints,
+/// so that the event loop waits before starting the next sync task.
+#[derive(Debug)]
+struct NoPeerToSyncFrom(u64);
+
+impl std::fmt::Display for NoPeerToSyncFrom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no peers were able to help sync checkpoint {}", self.0)
+    }
+}
+
+impl std::error::Error for NoPeerToSyncFrom {}
+
 #[allow(unused)]
 #[derive(Clone, Debug)]
 enum StateSyncMessage {
@@ -499,7 +547,11 @@ struct StateSyncEventLoop<S> {
     weak_sender: mpsc::WeakSender<StateSyncMessage>,
 
     tasks: JoinSet<()>,
+    /// The checkpoint sync tasks, whose results schedule the next sync task.
+    sync_tasks: JoinSet<(SyncTarget, Result<()>)>,
     sync_checkpoint_messages_task: Option<AbortHandle>,
+    /// The next dwallet checkpoint sync task doesn't start before this time.
+    dwallet_checkpoint_sync_retry_at: Option<Instant>,
     download_limit_layer: Option<CheckpointMessageDownloadLimitLayer>,
 
     store: S,
@@ -514,8 +566,19 @@ struct StateSyncEventLoop<S> {
 
     system_checkpoint_event_sender: broadcast::Sender<VerifiedSystemCheckpointMessage>,
     sync_system_checkpoints_task: Option<AbortHandle>,
+    /// The next system checkpoint sync task doesn't start before this time.
+    system_checkpoint_sync_retry_at: Option<Instant>,
     system_checkpoint_download_limit_layer: Option<SystemCheckpointDownloadLimitLayer>,
     sync_system_checkpoint_from_archive_task: Option<AbortHandle>,
+    access_control: PeerAccessControl,
+
+    peer_scores: PeerScores,
+    dwallet_checkpoint_download_concurrency: DownloadConcurrency,
+    system_checkpoint_download_concurrency: DownloadConcurrency,
+    /// Wakes up the sync of dwallet checkpoints from the archives when peers are slow.
+    dwallet_checkpoint_archive_fallback: Arc<Notify>,
+    /// Wakes up the sync of system checkpoints from the archives when peers are slow.
+    system_checkpoint_archive_fallback: Arc<Notify>,
 }
 
 impl<S> StateSyncEventLoop<S>
@@ -563,14 +626,18 @@ where
         // stuck until restart when it ends up with a different set of peers. Once the discovery
         // mechanism can dynamically identify and connect to other peers on the network, we will rely
         // on sync from archive as a fall back.
-        let task =
-            sync_checkpoint_messages_from_archive(self.archive_readers.clone(), self.store.clone());
+        let task = sync_checkpoint_messages_from_archive(
+            self.archive_readers.clone(),
+            self.store.clone(),
+            self.dwallet_checkpoint_archive_fallback.clone(),
+        );
         let task_handle = self.tasks.spawn(task);
         self.sync_checkpoint_from_archive_task = Some(task_handle);
 
         let task = sync_system_checkpoint_messages_from_archive(
             self.archive_readers.clone(),
             self.store.clone(),
+            self.system_checkpoint_archive_fallback.clone(),
         );
         let task_handle = self.tasks.spawn(task);
         self.sync_system_checkpoint_from_archive_task = Some(task_handle);
@@ -608,22 +675,24 @@ where
                         },
                     };
 
-                    if matches!(&self.sync_checkpoint_messages_task, Some(t) if t.is_finished()) {
-                        self.sync_checkpoint_messages_task = None;
-                    }
-
                     if matches!(&self.sync_checkpoint_from_archive_task, Some(t) if t.is_finished()) {
                         panic!("sync_checkpoint_from_archive task unexpectedly terminated")
                     }
 
-                    if matches!(&self.sync_system_checkpoints_task, Some(t) if t.is_finished()) {
-                        self.sync_system_checkpoints_task = None;
-                    }
-
                     if matches!(&self.sync_system_checkpoint_from_archive_task, Some(t) if t.is_finished()) {
                         panic!("sync_system_checkpoint_from_archive task unexpectedly terminated")
                     }
                 },
+                Some(task_result) = self.sync_tasks.join_next() => {
+                    match task_result {
+                        Ok((target, result)) => self.handle_sync_task_result(target, result),
+                        // avoid crashing on ungraceful shutdown
+                        Err(e) if e.is_cancelled() => {},
+                        // propagate panics.
+                        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                        Err(e) => panic!("sync task failed: {e}"),
+                    }
+                },
             }
 
             if self.is_notifier {
@@ -750,12 +819,13 @@ where
 
         match peer_event {
             Ok(PeerEvent::NewPeer(peer_id)) => {
-                if self.is_notifier {
+                if self.is_notifier && self.access_control.can_connect_to(&peer_id) {
                     self.spawn_get_latest_from_peer(peer_id);
                 }
             }
             Ok(PeerEvent::LostPeer(peer_id, _)) => {
                 self.peer_heights.write().unwrap().peers.remove(&peer_id);
+                self.peer_scores.remove(&peer_id);
             }
 
             Err(RecvError::Closed) => {
@@ -814,11 +884,45 @@ where
         }
     }
 
+    fn handle_sync_task_result(&mut self, target: SyncTarget, result: Result<()>) {
+        let (task, retry_at) = match target {
+            SyncTarget::DWalletCheckpoints => (
+                &mut self.sync_checkpoint_messages_task,
+                &mut self.dwallet_checkpoint_sync_retry_at,
+            ),
+            SyncTarget::SystemCheckpoints => (
+                &mut self.sync_system_checkpoints_task,
+                &mut self.system_checkpoint_sync_retry_at,
+            ),
+        };
+        *task = None;
+        let Err(e) = result else {
+            return;
+        };
+        debug!("error syncing {target:?}: {e}");
+        if e.is::<NoPeerToSyncFrom>() {
+            // Give the peers, and the sync from the archives, time to catch up before the next
+            // sync task, which starts on the first event after this time.
+            let wait_interval = self
+                .peer_heights
+                .read()
+                .unwrap()
+                .wait_interval_when_no_peer_to_sync_content();
+            *retry_at = Some(Instant::now() + wait_interval);
+        }
+    }
+
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
         // Only run one sync task at a time
         if self.sync_checkpoint_messages_task.is_some() {
             return;
         }
+        if self
+            .dwallet_checkpoint_sync_retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return;
+        }
 
         let highest_processed_checkpoint = self
             .store
@@ -845,19 +949,18 @@ where
                 self.store.clone(),
                 self.peer_heights.clone(),
                 self.metrics.clone(),
+                self.access_control.clone(),
                 self.config.pinned_dwallet_checkpoints.clone(),
-                self.config.dwallet_checkpoint_header_download_concurrency(),
+                self.peer_scores.clone(),
+                self.dwallet_checkpoint_download_concurrency.clone(),
                 self.config.timeout(),
+                self.config.archive_fallback_latency(),
+                self.dwallet_checkpoint_archive_fallback.clone(),
                 // The if condition should ensure that this is Some
                 highest_known_checkpoint.unwrap(),
             )
-            .map(|result| match result {
-                Ok(()) => {}
-                Err(e) => {
-                    debug!("error syncing checkpoint {e}");
-                }
-            });
-            let task_handle = self.tasks.spawn(task);
+            .map(|result| (SyncTarget::DWalletCheckpoints, result));
+            let task_handle = self.sync_tasks.spawn(task);
             self.sync_checkpoint_messages_task = Some(task_handle);
         }
     }
@@ -867,6 +970,12 @@ where
         if self.sync_system_checkpoints_task.is_some() {
             return;
         }
+        if self
+            .system_checkpoint_sync_retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return;
+        }
 
         let highest_processed_system_checkpoint = self
             .store
@@ -893,19 +1002,18 @@ where
                 self.store.clone(),
                 self.peer_heights.clone(),
                 self.metrics.clone(),
+                self.access_control.clone(),
                 self.config.pinned_system_checkpoints.clone(),
-                self.config.system_checkpoint_header_download_concurrency(),
+                self.peer_scores.clone(),
+                self.system_checkpoint_download_concurrency.clone(),
                 self.config.timeout(),
+                self.config.archive_fallback_latency(),
+                self.system_checkpoint_archive_fallback.clone(),
                 // The if condition should ensure that this is Some
                 highest_known_system_checkpoint.unwrap(),
             )
-            .map(|result| match result {
-                Ok(()) => {}
-                Err(e) => {
-                    debug!("error syncing system_checkpoint {e}");
-                }
-            });
-            let task_handle = self.tasks.spawn(task);
+            .map(|result| (SyncTarget::SystemCheckpoints, result));
+            let task_handle = self.sync_tasks.spawn(task);
             self.sync_system_checkpoints_task = Some(task_handle);
         }
     }
@@ -1118,12 +1226,16 @@ async fn sync_to_checkpoint<S>(
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
     metrics: Metrics,
+    access_control: PeerAccessControl,
     pinned_checkpoints: Vec<(
         DWalletCheckpointSequenceNumber,
         DWalletCheckpointMessageDigest,
     )>,
-    checkpoint_header_download_concurrency: usize,
+    peer_scores: PeerScores,
+    download_concurrency: DownloadConcurrency,
     timeout: Duration,
+    archive_fallback_latency: Duration,
+    archive_fallback: Arc<Notify>,
     checkpoint: CertifiedDWalletCheckpointMessage,
 ) -> Result<()>
 where
@@ -1143,74 +1255,153 @@ where
         ));
     }
 
-    let peer_balancer = PeerBalancer::new(&network, peer_heights.clone());
+    let peer_balancer = PeerBalancer::new(
+        &network,
+        peer_heights.clone(),
+        &access_control,
+        &peer_scores,
+    );
     // range of the next sequence_numbers to fetch
-    let mut request_stream = (current_sequence_number.map(|s| s.checked_add(1).expect("exhausted u64")).unwrap_or(1)
-        ..=*checkpoint.sequence_number())
+    let mut request_stream = (current_sequence_number
+        .map(|s| s.checked_add(1).expect("exhausted u64"))
+        .unwrap_or(1)..=*checkpoint.sequence_number())
         .map(|next| {
             let peers = peer_balancer.clone().with_checkpoint(next);
             let peer_heights = peer_heights.clone();
             let pinned_checkpoints = &pinned_checkpoints;
+            let network = &network;
+            let access_control = &access_control;
+            let peer_scores = &peer_scores;
+            let download_concurrency = &download_concurrency;
+            let archive_fallback = &archive_fallback;
+            let store = &store;
             async move {
-                if let Some(checkpoint) = peer_heights
+                let pushed_checkpoint = peer_heights
                     .read()
                     .unwrap()
                     .get_dwallet_checkpoint_by_sequence_number(next)
-                {
-                    return (Some(checkpoint.to_owned()), next, None);
+                    .cloned();
+                if let Some(checkpoint) = pushed_checkpoint {
+                    // A checkpoint pushed by a peer is verified like a downloaded one.
+                    let digest = *checkpoint.digest();
+                    match verify_dwallet_checkpoint(store, checkpoint) {
+                        Some(Ok(checkpoint)) => return (Some(checkpoint), next, None),
+                        Some(Err(_)) => peer_heights.write().unwrap().remove_checkpoint(&digest),
+                        None => {}
+                    }
                 }
 
+                let permit = download_concurrency.acquire().await;
+                let started = Instant::now();
+
                 // Iterate through peers trying each one in turn until we're able to
                 // successfully get the target checkpoint
                 for mut peer in peers {
+                    let peer_id = peer.inner().peer_id();
                     let request = Request::new(GetCheckpointMessageRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
-                    if let Some(checkpoint) = peer
-                        .get_dwallet_checkpoint_message(request)
-                        .await
-                        .tap_err(|e| trace!("{e:?}"))
-                        .ok()
-                        .and_then(Response::into_inner)
-                        .tap_none(|| trace!("peer unable to help sync"))
+                    let request_started = Instant::now();
+                    let response = peer.get_dwallet_checkpoint_message(request).await;
+                    let checkpoint = match response.map(Response::into_inner) {
+                        Ok(Some(checkpoint)) => checkpoint,
+                        Ok(None) => {
+                            trace!("peer unable to help sync");
+                            peer_scores.record_failure(peer_id);
+                            continue;
+                        }
+                        Err(e) => {
+                            trace!("{e:?}");
+                            peer_scores.record_failure(peer_id);
+                            continue;
+                        }
+                    };
+
+                    // peer didn't give us a checkpoint with the height that we requested
+                    if *checkpoint.sequence_number() != next {
+                        debug!(
+                            "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
+                            checkpoint.sequence_number()
+                        );
+                        peer_scores.record_bad_response(peer_id);
+                        ban_peer(
+                            network,
+                            access_control,
+                            peer_id,
+                            BanReason::WrongSequenceNumber,
+                        );
+                        continue;
+                    }
+
+                    // peer gave us a checkpoint whose digest does not match pinned digest
+                    let checkpoint_digest = checkpoint.digest();
+                    if let Ok(pinned_digest_index) = pinned_checkpoints
+                        .binary_search_by_key(checkpoint.sequence_number(), |(seq_num, _digest)| {
+                            *seq_num
+                        })
                     {
-                        // peer didn't give us a checkpoint with the height that we requested
-                        if *checkpoint.sequence_number() != next {
+                        if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
                             debug!(
-                                "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
-                                checkpoint.sequence_number()
+                                "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
+                                pinned_checkpoints[pinned_digest_index].1,
+                                checkpoint_digest
+                            );
+                            peer_scores.record_bad_response(peer_id);
+                            ban_peer(
+                                network,
+                                access_control,
+                                peer_id,
+                                BanReason::PinnedDigestMismatch,
                             );
                             continue;
                         }
+                    }
 
-                        // peer gave us a checkpoint whose digest does not match pinned digest
-                        let checkpoint_digest = checkpoint.digest();
-                        if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
-                            checkpoint.sequence_number(),
-                            |(seq_num, _digest)| *seq_num
-                        ) {
-                            if pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
-                                debug!(
-                                    "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
-                                    pinned_checkpoints[pinned_digest_index].1,
-                                    checkpoint_digest
-                                );
-                                continue;
-                            }
+                    // peer gave us a checkpoint that is not certified by the committee of its epoch
+                    let checkpoint = match verify_dwallet_checkpoint(store, checkpoint) {
+                        Some(Ok(checkpoint)) => checkpoint,
+                        Some(Err(e)) => {
+                            debug!("peer returned checkpoint {next} with invalid signatures: {e}");
+                            peer_scores.record_bad_response(peer_id);
+                            ban_peer(
+                                network,
+                                access_control,
+                                peer_id,
+                                BanReason::InvalidSignatures,
+                            );
+                            continue;
+                        }
+                        None => {
+                            // No peer can serve a checkpoint we can verify yet.
+                            debug!("the committee of the epoch of checkpoint {next} is not known yet");
+                            break;
                         }
+                    };
 
-                        // Insert in our store in the event that things fail and we need to retry
-                        peer_heights
-                            .write()
-                            .unwrap()
-                            .insert_checkpoint(checkpoint.clone());
-                        return (Some(checkpoint), next, Some(peer.inner().peer_id()));
+                    peer_scores.record_success(peer_id, request_started.elapsed());
+                    if started.elapsed() < archive_fallback_latency {
+                        permit.fast();
+                    } else {
+                        permit.slow();
+                        archive_fallback.notify_one();
                     }
+
+                    // Insert in our store in the event that things fail and we need to retry
+                    peer_heights
+                        .write()
+                        .unwrap()
+                        .insert_checkpoint(checkpoint.inner().clone());
+                    return (Some(checkpoint), next, Some(peer_id));
                 }
+                permit.slow();
+                archive_fallback.notify_one();
                 (None, next, None)
             }
         })
         .pipe(futures::stream::iter)
-        .buffered(checkpoint_header_download_concurrency);
+        // Every queued download waits for a permit before it sends a request, so the adaptive
+        // `limit()` bounds the requests in flight, while queueing up to `max()` downloads lets
+        // the concurrency grow during the sync.
+        .buffered(download_concurrency.max());
 
     while let Some((maybe_checkpoint, next, _maybe_peer_id)) = request_stream.next().await {
         assert_eq!(
@@ -1220,10 +1411,12 @@ where
             next
         );
 
-        // We can't verify the checkpoint
-        let checkpoint = maybe_checkpoint
-            .map(VerifiedDWalletCheckpointMessage::new_unchecked)
-            .ok_or_else(|| anyhow::anyhow!("no peers were able to help sync checkpoint {next}"))?;
+        metrics.set_dwallet_checkpoint_download_concurrency(download_concurrency.limit());
+
+        // The signatures of the checkpoint were verified when it was downloaded.
+        let Some(checkpoint) = maybe_checkpoint else {
+            return Err(NoPeerToSyncFrom(next).into());
+        };
 
         debug!(checkpoint_seq = ?checkpoint.sequence_number(), "verified checkpoint summary");
 
@@ -1243,8 +1436,68 @@ where
     Ok(())
 }
 
-async fn sync_checkpoint_messages_from_archive<S>(archive_readers: ArchiveReaderBalancer, store: S)
-where
+/// Verifies the signatures of `checkpoint` with the committee of its epoch.
+/// Returns `None` if that committee is not known yet.
+///
+/// A checkpoint of an epoch before the earliest committee in the store, synced by a node that
+/// joined after that epoch, has no committee to be verified with and is trusted as is.
+fn verify_dwallet_checkpoint<S: ReadStore>(
+    store: &S,
+    checkpoint: CertifiedDWalletCheckpointMessage,
+) -> Option<IkaResult<VerifiedDWalletCheckpointMessage>> {
+    match store
+        .get_committee(checkpoint.epoch())
+        .expect("store operation should not fail")
+    {
+        Some(committee) => Some(checkpoint.try_into_verified(&committee)),
+        None if predates_known_committees(store, checkpoint.epoch()) => Some(Ok(
+            VerifiedDWalletCheckpointMessage::new_unchecked(checkpoint),
+        )),
+        None => None,
+    }
+}
+
+/// Like [`verify_dwallet_checkpoint`], for a system checkpoint.
+fn verify_system_checkpoint<S: ReadStore>(
+    store: &S,
+    system_checkpoint: CertifiedSystemCheckpointMessage,
+) -> Option<IkaResult<VerifiedSystemCheckpointMessage>> {
+    match store
+        .get_committee(system_checkpoint.epoch())
+        .expect("store operation should not fail")
+    {
+        Some(committee) => Some(system_checkpoint.try_into_verified(&committee)),
+        None if predates_known_committees(store, system_checkpoint.epoch()) => Some(Ok(
+            VerifiedSystemCheckpointMessage::new_unchecked(system_checkpoint),
+        )),
+        None => None,
+    }
+}
+
+fn predates_known_committees<S: ReadStore>(store: &S, epoch: EpochId) -> bool {
+    store
+        .get_lowest_committee_epoch()
+        .expect("store operation should not fail")
+        .is_some_and(|lowest_epoch| epoch < lowest_epoch)
+}
+
+/// Bans a peer that served an invalid checkpoint, and disconnects from it.
+fn ban_peer(
+    network: &anemo::Network,
+    access_control: &PeerAccessControl,
+    peer_id: PeerId,
+    reason: BanReason,
+) {
+    if access_control.ban(peer_id, reason) {
+        let _ = network.disconnect(peer_id);
+    }
+}
+
+async fn sync_checkpoint_messages_from_archive<S>(
+    archive_readers: ArchiveReaderBalancer,
+    store: S,
+    archive_fallback: Arc<Notify>,
+) where
     S: WriteStore + Clone + Send + Sync + 'static,
 {
     loop {
@@ -1284,7 +1537,12 @@ where
         } else {
             debug!("Failed to find an archive reader to complete the state sync request");
         }
-        tokio::time::sleep(Duration::from_secs(5)).await;
+        // Poll the archives again after a while, or as soon as peers fail to serve checkpoints
+        // fast enough.
+        tokio::select! {
+            _ = tokio::time::sleep(ARCHIVE_SYNC_INTERVAL) => {},
+            _ = archive_fallback.notified() => {},
+        }
     }
 }
 
@@ -1471,12 +1729,16 @@ async fn sync_to_system_checkpoint<S>(
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
     metrics: Metrics,
+    access_control: PeerAccessControl,
     pinned_system_checkpoints: Vec<(
         SystemCheckpointSequenceNumber,
         SystemCheckpointMessageDigest,
     )>,
-    system_checkpoint_header_download_concurrency: usize,
+    peer_scores: PeerScores,
+    download_concurrency: DownloadConcurrency,
     timeout: Duration,
+    archive_fallback_latency: Duration,
+    archive_fallback: Arc<Notify>,
     system_checkpoint: CertifiedSystemCheckpointMessage,
 ) -> Result<()>
 where
@@ -1496,74 +1758,161 @@ where
         ));
     }
 
-    let peer_balancer = PeerBalancer::new(&network, peer_heights.clone());
+    let peer_balancer = PeerBalancer::new(
+        &network,
+        peer_heights.clone(),
+        &access_control,
+        &peer_scores,
+    );
     // range of the next sequence_numbers to fetch
-    let mut request_stream = (current_sequence_number.map(|s| s.checked_add(1).expect("exhausted u64")).unwrap_or(1)
-        ..=*system_checkpoint.sequence_number())
+    let mut request_stream = (current_sequence_number
+        .map(|s| s.checked_add(1).expect("exhausted u64"))
+        .unwrap_or(1)..=*system_checkpoint.sequence_number())
         .map(|next| {
             let peers = peer_balancer.clone().with_system_checkpoint(next);
             let peer_heights = peer_heights.clone();
             let pinned_system_checkpoints = &pinned_system_checkpoints;
+            let network = &network;
+            let access_control = &access_control;
+            let peer_scores = &peer_scores;
+            let download_concurrency = &download_concurrency;
+            let archive_fallback = &archive_fallback;
+            let store = &store;
             async move {
-                if let Some(system_checkpoint) = peer_heights
+                let pushed_system_checkpoint = peer_heights
                     .read()
                     .unwrap()
                     .get_system_checkpoint_by_sequence_number(next)
-                {
-                    return (Some(system_checkpoint.to_owned()), next, None);
+                    .cloned();
+                if let Some(system_checkpoint) = pushed_system_checkpoint {
+                    // A system_checkpoint pushed by a peer is verified like a downloaded one.
+                    let digest = *system_checkpoint.digest();
+                    match verify_system_checkpoint(store, system_checkpoint) {
+                        Some(Ok(system_checkpoint)) => return (Some(system_checkpoint), next, None),
+                        Some(Err(_)) => peer_heights
+                            .write()
+                            .unwrap()
+                            .remove_system_checkpoint(&digest),
+                        None => {}
+                    }
                 }
 
+                let permit = download_concurrency.acquire().await;
+                let started = Instant::now();
+
                 // Iterate through peers trying each one in turn until we're able to
                 // successfully get the target system_checkpoint
                 for mut peer in peers {
+                    let peer_id = peer.inner().peer_id();
                     let request = Request::new(GetSystemCheckpointRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
-                    if let Some(system_checkpoint) = peer
-                        .get_system_checkpoint(request)
-                        .await
-                        .tap_err(|e| trace!("{e:?}"))
-                        .ok()
-                        .and_then(Response::into_inner)
-                        .tap_none(|| trace!("peer unable to help sync"))
-                    {
-                        // peer didn't give us a system_checkpoint with the height that we requested
-                        if *system_checkpoint.sequence_number() != next {
+                    let request_started = Instant::now();
+                    let response = peer.get_system_checkpoint(request).await;
+                    let system_checkpoint = match response.map(Response::into_inner) {
+                        Ok(Some(system_checkpoint)) => system_checkpoint,
+                        Ok(None) => {
+                            trace!("peer unable to help sync");
+                            peer_scores.record_failure(peer_id);
+                            continue;
+                        }
+                        Err(e) => {
+                            trace!("{e:?}");
+                            peer_scores.record_failure(peer_id);
+                            continue;
+                        }
+                    };
+
+                    // peer didn't give us a system_checkpoint with the height that we requested
+                    if *system_checkpoint.sequence_number() != next {
+                        debug!(
+                            "peer returned system_checkpoint with wrong sequence number: expected {next}, got {}",
+                            system_checkpoint.sequence_number()
+                        );
+                        peer_scores.record_bad_response(peer_id);
+                        ban_peer(
+                            network,
+                            access_control,
+                            peer_id,
+                            BanReason::WrongSequenceNumber,
+                        );
+                        continue;
+                    }
+
+                    // peer gave us a system_checkpoint whose digest does not match pinned digest
+                    let system_checkpoint_digest = system_checkpoint.digest();
+                    if let Ok(pinned_digest_index) = pinned_system_checkpoints.binary_search_by_key(
+                        system_checkpoint.sequence_number(),
+                        |(seq_num, _digest)| *seq_num,
+                    ) {
+                        let pinned_digest = pinned_system_checkpoints[pinned_digest_index].1;
+                        if pinned_digest != *system_checkpoint_digest {
                             debug!(
-                                "peer returned system_checkpoint with wrong sequence number: expected {next}, got {}",
-                                system_checkpoint.sequence_number()
+                                "peer returned system_checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
+                                pinned_digest, system_checkpoint_digest
+                            );
+                            peer_scores.record_bad_response(peer_id);
+                            ban_peer(
+                                network,
+                                access_control,
+                                peer_id,
+                                BanReason::PinnedDigestMismatch,
                             );
                             continue;
                         }
+                    }
 
-                        // peer gave us a system_checkpoint whose digest does not match pinned digest
-                        let system_checkpoint_digest = system_checkpoint.digest();
-                        if let Ok(pinned_digest_index) = pinned_system_checkpoints.binary_search_by_key(
-                            system_checkpoint.sequence_number(),
-                            |(seq_num, _digest)| *seq_num
-                        ) {
-                            if pinned_system_checkpoints[pinned_digest_index].1 != *system_checkpoint_digest {
+                    // peer gave us a system_checkpoint that is not certified by the committee of
+                    // its epoch
+                    let system_checkpoint =
+                        match verify_system_checkpoint(store, system_checkpoint) {
+                            Some(Ok(system_checkpoint)) => system_checkpoint,
+                            Some(Err(e)) => {
                                 debug!(
-                                    "peer returned system_checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
-                                    pinned_system_checkpoints[pinned_digest_index].1,
-                                    system_checkpoint_digest
+                                    "peer returned system_checkpoint {next} with invalid signatures: {e}"
+                                );
+                                peer_scores.record_bad_response(peer_id);
+                                ban_peer(
+                                    network,
+                                    access_control,
+                                    peer_id,
+                                    BanReason::InvalidSignatures,
                                 );
                                 continue;
                             }
-                        }
+                            None => {
+                                // No peer can serve a system_checkpoint we can verify yet.
+                                debug!(
+                                    "the committee of the epoch of system_checkpoint {next} is not known yet"
+                                );
+                                break;
+                            }
+                        };
 
-                        // Insert in our store in the event that things fail and we need to retry
-                        peer_heights
-                            .write()
-                            .unwrap()
-                            .insert_system_checkpoint(system_checkpoint.clone());
-                        return (Some(system_checkpoint), next, Some(peer.inner().peer_id()));
+                    peer_scores.record_success(peer_id, request_started.elapsed());
+                    if started.elapsed() < archive_fallback_latency {
+                        permit.fast();
+                    } else {
+                        permit.slow();
+                        archive_fallback.notify_one();
                     }
+
+                    // Insert in our store in the event that things fail and we need to retry
+                    peer_heights
+                        .write()
+                        .unwrap()
+                        .insert_system_checkpoint(system_checkpoint.inner().clone());
+                    return (Some(system_checkpoint), next, Some(peer_id));
                 }
+                permit.slow();
+                archive_fallback.notify_one();
                 (None, next, None)
             }
         })
         .pipe(futures::stream::iter)
-        .buffered(system_checkpoint_header_download_concurrency);
+        // Every queued download waits for a permit before it sends a request, so the adaptive
+        // `limit()` bounds the requests in flight, while queueing up to `max()` downloads lets
+        // the concurrency grow during the sync.
+        .buffered(download_concurrency.max());
 
     while let Some((maybe_system_checkpoint, next, _maybe_peer_id)) = request_stream.next().await {
         assert_eq!(
@@ -1573,12 +1922,12 @@ where
             next
         );
 
-        // We can't verify the system_checkpoint
-        let system_checkpoint = maybe_system_checkpoint
-            .map(VerifiedSystemCheckpointMessage::new_unchecked)
-            .ok_or_else(|| {
-                anyhow::anyhow!("no peers were able to help sync system_checkpoint {next}")
-            })?;
+        metrics.set_system_checkpoint_download_concurrency(download_concurrency.limit());
+
+        // The signatures of the system_checkpoint were verified when it was downloaded.
+        let Some(system_checkpoint) = maybe_system_checkpoint else {
+            return Err(NoPeerToSyncFrom(next).into());
+        };
 
         debug!(system_checkpoint_seq = ?system_checkpoint.sequence_number(), "verified system_checkpoint summary");
 
@@ -1601,6 +1950,7 @@ where
 async fn sync_system_checkpoint_messages_from_archive<S>(
     archive_readers: ArchiveReaderBalancer,
     store: S,
+    archive_fallback: Arc<Notify>,
 ) where
     S: WriteStore + Clone + Send + Sync + 'static,
 {
@@ -1641,7 +1991,12 @@ async fn sync_system_checkpoint_messages_from_archive<S>(
         } else {
             debug!("Failed to find an archive reader to complete the state sync request");
         }
-        tokio::time::sleep(Duration::from_secs(5)).await;
+        // Poll the archives again after a while, or as soon as peers fail to serve checkpoints
+        // fast enough.
+        tokio::select! {
+            _ = tokio::time::sleep(ARCHIVE_SYNC_INTERVAL) => {},
+            _ = archive_fallback.notified() => {},
+        }
     }
 }
 
@@ -0,0 +1,307 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Scoring of the peers checkpoints are downloaded from, and the adaptive download concurrency.
+
+use anemo::PeerId;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::sync::Notify;
+
+/// The weight of the latest sample in the moving averages of a peer's latency and failure rate.
+const EWMA_WEIGHT: f64 = 0.2;
+
+/// How many times slower a peer that always fails is considered, compared to its latency.
+const FAILURE_PENALTY: f64 = 10.0;
+
+/// The download concurrency starts at this value, unless the configured bound is lower.
+const INITIAL_DOWNLOAD_CONCURRENCY: usize = 32;
+
+/// The download concurrency is halved at most once per this interval, so that a burst of failed
+/// downloads that were in flight together only counts once.
+const DOWNLOAD_CONCURRENCY_DECREASE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Statistics of the checkpoint downloads from a single peer.
+#[derive(Clone, Debug)]
+pub struct PeerSyncStats {
+    pub peer_id: PeerId,
+    /// Number of checkpoints the peer served.
+    pub successes: u64,
+    /// Number of requests the peer failed, timed out on, or could not serve.
+    pub failures: u64,
+    /// Number of checkpoints the peer served that failed validation.
+    pub bad_responses: u64,
+    /// Moving average of the time the peer took to serve a checkpoint.
+    pub latency: Option<Duration>,
+    /// Moving average of the rate of failed requests, between `0` and `1`.
+    pub failure_rate: f64,
+    pub last_success: Option<SystemTime>,
+    pub last_failure: Option<SystemTime>,
+}
+
+impl PeerSyncStats {
+    fn new(peer_id: PeerId) -> Self {
+        Self {
+            peer_id,
+            successes: 0,
+            failures: 0,
+            bad_responses: 0,
+            latency: None,
+            failure_rate: 0.0,
+            last_success: None,
+            last_failure: None,
+        }
+    }
+
+    /// The expected cost of requesting a checkpoint from this peer: its latency, penalized by its
+    /// failure rate. `rtt` stands in for the latency of a peer that has not served a checkpoint
+    /// yet.
+    pub fn expected_latency(&self, rtt: Duration) -> Duration {
+        self.latency
+            .unwrap_or(rtt)
+            .mul_f64(1.0 + FAILURE_PENALTY * self.failure_rate)
+    }
+
+    fn record_outcome(&mut self, failed: bool) {
+        let sample = if failed { 1.0 } else { 0.0 };
+        self.failure_rate += EWMA_WEIGHT * (sample - self.failure_rate);
+    }
+}
+
+/// The scores of the peers state sync downloads checkpoints from, shared between the sync tasks
+/// and the [`Handle`](super::Handle).
+#[derive(Clone, Debug, Default)]
+pub(super) struct PeerScores(Arc<Mutex<HashMap<PeerId, PeerSyncStats>>>);
+
+impl PeerScores {
+    pub fn record_success(&self, peer_id: PeerId, latency: Duration) {
+        let mut peers = self.0.lock().unwrap();
+        let stats = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerSyncStats::new(peer_id));
+        stats.successes += 1;
+        stats.latency = Some(match stats.latency {
+            Some(average) => average.mul_f64(1.0 - EWMA_WEIGHT) + latency.mul_f64(EWMA_WEIGHT),
+            None => latency,
+        });
+        stats.record_outcome(false);
+        stats.last_success = Some(SystemTime::now());
+    }
+
+    pub fn record_failure(&self, peer_id: PeerId) {
+        let mut peers = self.0.lock().unwrap();
+        let stats = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerSyncStats::new(peer_id));
+        stats.failures += 1;
+        stats.record_outcome(true);
+        stats.last_failure = Some(SystemTime::now());
+    }
+
+    /// Records a response that failed validation. Such a peer is penalized as if every recent
+    /// request to it had failed, until it serves enough valid checkpoints.
+    pub fn record_bad_response(&self, peer_id: PeerId) {
+        let mut peers = self.0.lock().unwrap();
+        let stats = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerSyncStats::new(peer_id));
+        stats.bad_responses += 1;
+        stats.failure_rate = 1.0;
+        stats.last_failure = Some(SystemTime::now());
+    }
+
+    pub fn expected_latency(&self, peer_id: &PeerId, rtt: Duration) -> Duration {
+        self.0
+            .lock()
+            .unwrap()
+            .get(peer_id)
+            .map_or(rtt, |stats| stats.expected_latency(rtt))
+    }
+
+    pub fn remove(&self, peer_id: &PeerId) {
+        self.0.lock().unwrap().remove(peer_id);
+    }
+
+    /// The statistics of every scored peer, best peers first.
+    pub fn stats(&self) -> Vec<PeerSyncStats> {
+        let mut stats: Vec<_> = self.0.lock().unwrap().values().cloned().collect();
+        // Peers that never served a checkpoint have no latency to rank them by.
+        stats.sort_by_key(|stats| {
+            (
+                stats.latency.is_none(),
+                stats.expected_latency(Duration::ZERO),
+            )
+        });
+        stats
+    }
+}
+
+/// Limits the number of checkpoint headers downloaded concurrently, adapting the limit to the
+/// throughput of the peers: the limit grows by one after a limit's worth of fast downloads, and
+/// halves after a failed or slow download.
+#[derive(Clone, Debug)]
+pub(super) struct DownloadConcurrency(Arc<DownloadConcurrencyInner>);
+
+#[derive(Debug)]
+struct DownloadConcurrencyInner {
+    max: usize,
+    state: Mutex<DownloadConcurrencyState>,
+    released: Notify,
+}
+
+#[derive(Debug)]
+struct DownloadConcurrencyState {
+    limit: usize,
+    in_flight: usize,
+    fast_downloads: usize,
+    last_decrease: Option<Instant>,
+}
+
+impl DownloadConcurrency {
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self(Arc::new(DownloadConcurrencyInner {
+            max,
+            state: Mutex::new(DownloadConcurrencyState {
+                limit: max.min(INITIAL_DOWNLOAD_CONCURRENCY),
+                in_flight: 0,
+                fast_downloads: 0,
+                last_decrease: None,
+            }),
+            released: Notify::new(),
+        }))
+    }
+
+    /// The bound the limit never exceeds.
+    pub fn max(&self) -> usize {
+        self.0.max
+    }
+
+    pub fn limit(&self) -> usize {
+        self.0.state.lock().unwrap().limit
+    }
+
+    /// Waits until one more download fits under the current limit.
+    pub async fn acquire(&self) -> DownloadPermit {
+        loop {
+            {
+                let mut state = self.0.state.lock().unwrap();
+                if state.in_flight < state.limit {
+                    state.in_flight += 1;
+                    return DownloadPermit(self.clone());
+                }
+            }
+            self.0.released.notified().await;
+        }
+    }
+
+    fn record(&self, fast: bool) {
+        let mut state = self.0.state.lock().unwrap();
+        if fast {
+            state.fast_downloads += 1;
+            if state.fast_downloads >= state.limit {
+                state.fast_downloads = 0;
+                state.limit = (state.limit + 1).min(self.0.max);
+            }
+        } else if state
+            .last_decrease
+            .is_none_or(|last| last.elapsed() >= DOWNLOAD_CONCURRENCY_DECREASE_INTERVAL)
+        {
+            state.fast_downloads = 0;
+            state.limit = (state.limit / 2).max(1);
+            state.last_decrease = Some(Instant::now());
+        }
+    }
+}
+
+/// A slot for a single download, released when dropped.
+pub(super) struct DownloadPermit(DownloadConcurrency);
+
+impl DownloadPermit {
+    /// Records a download that completed within the slow download threshold.
+    pub fn fast(&self) {
+        self.0.record(true);
+    }
+
+    /// Records a download that failed or exceeded the slow download threshold.
+    pub fn slow(&self) {
+        self.0.record(false);
+    }
+}
+
+impl Drop for DownloadPermit {
+    fn drop(&mut self) {
+        self.0.0.state.lock().unwrap().in_flight -= 1;
+        // `notify_one` stores a permit when nobody is waiting, so a waiter that checked the limit
+        // right before this release doesn't miss it.
+        self.0.0.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_peers_rank_after_slower_peers() {
+        let scores = PeerScores::default();
+        let fast = PeerId([1; 32]);
+        let slow = PeerId([2; 32]);
+        let bad = PeerId([3; 32]);
+
+        scores.record_success(fast, Duration::from_millis(10));
+        scores.record_success(slow, Duration::from_millis(100));
+        scores.record_success(bad, Duration::from_millis(50));
+        scores.record_bad_response(bad);
+
+        let ranked: Vec<_> = scores.stats().iter().map(|stats| stats.peer_id).collect();
+        assert_eq!(ranked, vec![fast, slow, bad]);
+
+        for _ in 0..10 {
+            scores.record_failure(fast);
+        }
+        assert!(
+            scores.expected_latency(&fast, Duration::ZERO)
+                > scores.expected_latency(&slow, Duration::ZERO)
+        );
+
+        // Unknown peers are ranked by their round-trip time.
+        let unknown = PeerId([4; 32]);
+        assert_eq!(
+            scores.expected_latency(&unknown, Duration::from_millis(7)),
+            Duration::from_millis(7)
+        );
+    }
+
+    #[tokio::test]
+    async fn download_concurrency_adapts_to_outcomes() {
+        let concurrency = DownloadConcurrency::new(40);
+        assert_eq!(concurrency.limit(), INITIAL_DOWNLOAD_CONCURRENCY);
+
+        for _ in 0..INITIAL_DOWNLOAD_CONCURRENCY {
+            concurrency.acquire().await.fast();
+        }
+        assert_eq!(concurrency.limit(), INITIAL_DOWNLOAD_CONCURRENCY + 1);
+
+        // Failures that happen together only halve the limit once.
+        concurrency.acquire().await.slow();
+        concurrency.acquire().await.slow();
+        assert_eq!(concurrency.limit(), (INITIAL_DOWNLOAD_CONCURRENCY + 1) / 2);
+
+        // Downloads past the limit wait for a permit to be released.
+        let permits: Vec<_> =
+            futures::future::join_all((0..concurrency.limit()).map(|_| concurrency.acquire()))
+                .await;
+        let waiting = tokio::spawn({
+            let concurrency = concurrency.clone();
+            async move { concurrency.acquire().await.fast() }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        drop(permits);
+        waiting.await.unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use super::*;
+use crate::access_control::Rejection;
+use crate::utils::build_network;
+use ika_types::committee::Committee;
+use ika_types::crypto::AuthorityKeyPair;
+use ika_types::messages_dwallet_checkpoint::DWalletCheckpointMessage;
+use ika_types::storage::SharedInMemoryStore;
+use std::sync::LazyLock;
+
+/// The committee that certifies the checkpoints, of epoch 0.
+static COMMITTEE: LazyLock<(Committee, Vec<AuthorityKeyPair>)> =
+    LazyLock::new(Committee::new_simple_test_committee);
+
+fn checkpoint_signed_by(
+    sequence_number: u64,
+    (committee, key_pairs): &(Committee, Vec<AuthorityKeyPair>),
+) -> CertifiedDWalletCheckpointMessage {
+    CertifiedDWalletCheckpointMessage::new_from_keypairs_for_testing(
+        DWalletCheckpointMessage::new(committee.epoch(), sequence_number, vec![]),
+        key_pairs,
+        committee,
+    )
+}
+
+fn certified_checkpoint(sequence_number: u64) -> VerifiedDWalletCheckpointMessage {
+    checkpoint_signed_by(sequence_number, &COMMITTEE)
+        .try_into_verified(&COMMITTEE.0)
+        .unwrap()
+}
+
+/// A store that knows the committee certifying the checkpoints.
+fn store() -> SharedInMemoryStore {
+    let store = SharedInMemoryStore::default();
+    store.insert_committee(COMMITTEE.0.clone()).unwrap();
+    store
+}
+
+/// Starts a peer that serves the dwallet checkpoints `1..=height`.
+fn start_peer(height: u64) -> anemo::Network {
+    start_peer_serving((1..=height).map(certified_checkpoint))
+}
+
+fn start_peer_serving(
+    checkpoints: impl IntoIterator<Item = VerifiedDWalletCheckpointMessage>,
+) -> anemo::Network {
+    let store = SharedInMemoryStore::default();
+    for checkpoint in checkpoints {
+        store.insert_certified_checkpoint(&checkpoint);
+    }
+    let (_, server) = Builder::new().store(store).build();
+    build_network(|router| router.add_rpc_service(server))
+}
+
+/// Peer heights in which every peer in `peers` is on our chain, at its dwallet checkpoint height.
+fn peer_heights(peers: &[(PeerId, u64)]) -> Arc<RwLock<PeerHeights>> {
+    let peers = peers
+        .iter()
+        .map(|(peer_id, height)| {
+            let info = PeerStateSyncInfo {
+                chain_identifier: ChainIdentifier::default(),
+                on_same_chain_as_us: true,
+                dwallet_checkpoint_height: Some(*height),
+                system_checkpoint_height: None,
+            };
+            (*peer_id, info)
+        })
+        .collect();
+    Arc::new(RwLock::new(PeerHeights {
+        peers,
+        unprocessed_checkpoints: HashMap::new(),
+        sequence_number_to_digest: HashMap::new(),
+        unprocessed_system_checkpoint: HashMap::new(),
+        sequence_number_to_digest_system_checkpoint: HashMap::new(),
+        wait_interval_when_no_peer_to_sync_content: Duration::from_secs(60),
+    }))
+}
+
+async fn sync(
+    network: &anemo::Network,
+    store: &SharedInMemoryStore,
+    peer_heights: Arc<RwLock<PeerHeights>>,
+    peer_scores: &PeerScores,
+    target: u64,
+) -> Result<()> {
+    sync_with_access_control(
+        network,
+        store,
+        peer_heights,
+        peer_scores,
+        &PeerAccessControl::default(),
+        target,
+    )
+    .await
+}
+
+async fn sync_with_access_control(
+    network: &anemo::Network,
+    store: &SharedInMemoryStore,
+    peer_heights: Arc<RwLock<PeerHeights>>,
+    peer_scores: &PeerScores,
+    access_control: &PeerAccessControl,
+    target: u64,
+) -> Result<()> {
+    sync_to_checkpoint(
+        network.clone(),
+        store.clone(),
+        peer_heights,
+        Metrics::disabled(),
+        access_control.clone(),
+        vec![],
+        peer_scores.clone(),
+        DownloadConcurrency::new(4),
+        Duration::from_secs(5),
+        Duration::from_secs(5),
+        Arc::new(Notify::new()),
+        certified_checkpoint(target).into_inner(),
+    )
+    .await
+}
+
+fn highest_verified_checkpoint(store: &SharedInMemoryStore) -> Option<u64> {
+    store
+        .get_highest_verified_dwallet_checkpoint()
+        .unwrap()
+        .map(|checkpoint| *checkpoint.sequence_number())
+}
+
+#[tokio::test]
+async fn sync_to_checkpoint_downloads_checkpoints_in_order() {
+    let peer = start_peer(5);
+    let network = build_network(|router| router);
+    network.connect(peer.local_addr()).await.unwrap();
+    let store = store();
+    let peer_scores = PeerScores::default();
+
+    sync(
+        &network,
+        &store,
+        peer_heights(&[(peer.peer_id(), 5)]),
+        &peer_scores,
+        5,
+    )
+    .await
+    .unwrap();
+    assert_eq!(highest_verified_checkpoint(&store), Some(5));
+    let stats = peer_scores.stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].peer_id, peer.peer_id());
+    assert_eq!(stats[0].successes, 5);
+}
+
+#[tokio::test]
+async fn sync_to_checkpoint_fails_without_waiting_when_no_peer_serves_a_checkpoint() {
+    // The peer claims checkpoint 5, but only serves up to checkpoint 2.
+    let peer = start_peer(2);
+    let network = build_network(|router| router);
+    network.connect(peer.local_addr()).await.unwrap();
+    let store = store();
+    let peer_scores = PeerScores::default();
+
+    // The sync task returns right away, rather than waiting out the 60 seconds to retry.
+    let error = tokio::time::timeout(
+        Duration::from_secs(10),
+        sync(
+            &network,
+            &store,
+            peer_heights(&[(peer.peer_id(), 5)]),
+            &peer_scores,
+            5,
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap_err();
+    assert_eq!(
+        error
+            .downcast_ref::<NoPeerToSyncFrom>()
+            .map(|error| error.0),
+        Some(3)
+    );
+    // The checkpoints before the missing one are kept.
+    assert_eq!(highest_verified_checkpoint(&store), Some(2));
+}
+
+#[tokio::test]
+async fn peers_serving_forged_checkpoints_are_banned() {
+    let forged_committee = Committee::new_simple_test_committee();
+    let forger = start_peer_serving((1..=3).map(|sequence_number| {
+        VerifiedDWalletCheckpointMessage::new_unchecked(checkpoint_signed_by(
+            sequence_number,
+            &forged_committee,
+        ))
+    }));
+    let network = build_network(|router| router);
+    network.connect(forger.local_addr()).await.unwrap();
+    let store = store();
+    let peer_scores = PeerScores::default();
+    let access_control = PeerAccessControl::default();
+
+    let error = sync_with_access_control(
+        &network,
+        &store,
+        peer_heights(&[(forger.peer_id(), 3)]),
+        &peer_scores,
+        &access_control,
+        3,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error
+            .downcast_ref::<NoPeerToSyncFrom>()
+            .map(|error| error.0),
+        Some(1)
+    );
+    assert_eq!(highest_verified_checkpoint(&store), None);
+    assert_eq!(
+        access_control.check(&forger.peer_id()),
+        Err(Rejection::Banned)
+    );
+    assert!(peer_scores.stats()[0].bad_responses > 0);
+
+    // The checkpoints are synced from an honest peer, and not from the banned one.
+    let peer = start_peer(3);
+    network.connect(peer.local_addr()).await.unwrap();
+    sync_with_access_control(
+        &network,
+        &store,
+        peer_heights(&[(forger.peer_id(), 3), (peer.peer_id(), 3)]),
+        &peer_scores,
+        &access_control,
+        3,
+    )
+    .await
+    .unwrap();
+    assert_eq!(highest_verified_checkpoint(&store), Some(3));
+}
+
+#[tokio::test]
+async fn event_loop_delays_the_next_sync_after_no_peer_served_a_checkpoint() {
+    let config = StateSyncConfig {
+        wait_interval_when_no_peer_to_sync_content_ms: Some(60_000),
+        ..Default::default()
+    };
+    let (unstarted, _server) = Builder::new()
+        .store(SharedInMemoryStore::default())
+        .config(config)
+        .build();
+    let (mut event_loop, _handle) = unstarted.build(build_network(|router| router), true);
+    {
+        // A peer we are not connected to knows of checkpoint 5.
+        let mut peer_heights = event_loop.peer_heights.write().unwrap();
+        peer_heights.peers.insert(
+            PeerId([1; 32]),
+            PeerStateSyncInfo {
+                chain_identifier: ChainIdentifier::default(),
+                on_same_chain_as_us: true,
+                dwallet_checkpoint_height: Some(5),
+                system_checkpoint_height: None,
+            },
+        );
+        peer_heights.insert_checkpoint(certified_checkpoint(5).into_inner());
+    }
+
+    // Other failures don't delay the next sync.
+    event_loop.handle_sync_task_result(
+        SyncTarget::DWalletCheckpoints,
+        Err(anyhow::anyhow!(
+            "target checkpoint is older than highest verified checkpoint"
+        )),
+    );
+    assert_eq!(event_loop.dwallet_checkpoint_sync_retry_at, None);
+
+    event_loop.maybe_start_checkpoint_summary_sync_task();
+    assert!(event_loop.sync_checkpoint_messages_task.is_some());
+    let (target, result) = event_loop.sync_tasks.join_next().await.unwrap().unwrap();
+    assert_eq!(target, SyncTarget::DWalletCheckpoints);
+    assert!(result.as_ref().unwrap_err().is::<NoPeerToSyncFrom>());
+    event_loop.handle_sync_task_result(target, result);
+    assert!(event_loop.sync_checkpoint_messages_task.is_none());
+
+    // The next sync task waits for the retry time, without holding up the system checkpoints.
+    event_loop.maybe_start_checkpoint_summary_sync_task();
+    assert!(event_loop.sync_checkpoint_messages_task.is_none());
+    assert_eq!(event_loop.system_checkpoint_sync_retry_at, None);
+
+    event_loop.dwallet_checkpoint_sync_retry_at = Some(Instant::now());
+    event_loop.maybe_start_checkpoint_summary_sync_task();
+    assert!(event_loop.sync_checkpoint_messages_task.is_some());
+}
@@ -9,9 +9,15 @@ use axum::{
     routing::{get, post},
 };
 use humantime::parse_duration;
+use ika_core::dwallet_mpc::mpc_inspection::MPCInspectionHandle;
+use ika_types::messages_dwallet_mpc::SessionIdentifier;
+use ika_types::messages_system_checkpoints::SystemParameterChange;
 use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use sui_types::base_types::ConciseableName;
 use telemetry_subscribers::TracingHandle;
 use tracing::info;
 
@@ -34,10 +40,46 @@ use tracing::info;
 //
 //   $ curl 'http://127.0.0.1:1337/capabilities'
 //
+// Vote to set the epoch duration to one day, and view the votes received in the current epoch:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/propose-system-parameter?parameter=epoch_duration_ms&value=86400000'
+//   $ curl 'http://127.0.0.1:1337/system-parameter-votes'
+//
 // View the node config (private keys will be masked):
 //
 //   $ curl 'http://127.0.0.1:1337/node-config'
 //
+// View the statistics of the checkpoint downloads from each state sync peer, best peers first:
+//
+//   $ curl 'http://127.0.0.1:1337/state-sync-peers'
+//
+// List the MPC sessions of the current epoch that are not completed (or all of them with
+// `all=true`), view a single session by the hex of its session identifier, and view the events
+// that wait for a network encryption key or for the next active committee:
+//
+//   $ curl 'http://127.0.0.1:1337/mpc-sessions?all=true'
+//   $ curl 'http://127.0.0.1:1337/mpc-session?session_identifier=0x1234...'
+//   $ curl 'http://127.0.0.1:1337/mpc-pending-events'
+//
+// View the network encryption keys, their state on Sui and whether this validator decrypted its
+// shares of them (no secret key material is shown), and the epoch switch steps the notifier ran in
+// the current epoch:
+//
+//   $ curl 'http://127.0.0.1:1337/network-keys'
+//   $ curl 'http://127.0.0.1:1337/epoch-switch-status'
+//
+// Drain the validator before a maintenance restart, and view the drain state. The node finishes
+// the MPC sessions it started, doesn't start new user sessions, and shuts down once
+// it is `ready-to-stop`:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/drain'
+//   $ curl 'http://127.0.0.1:1337/drain-status'
+//
+// Write a snapshot of the node databases to a file on the node machine, while the node keeps
+// running. It's restored with `ika-node restore`:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/snapshot?output_path=/backup/ika-snapshot.tar.gz'
+//
 // Set a time-limited tracing config. After the duration expires, tracing will be disabled
 // automatically.
 //
@@ -58,6 +100,20 @@ const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 #[allow(dead_code)]
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const PROPOSE_SYSTEM_PARAMETER: &str = "/propose-system-parameter";
+const SYSTEM_PARAMETER_VOTES: &str = "/system-parameter-votes";
+const STATE_SYNC_PEERS: &str = "/state-sync-peers";
+const MPC_SESSIONS: &str = "/mpc-sessions";
+const MPC_SESSION: &str = "/mpc-session";
+const MPC_PENDING_EVENTS: &str = "/mpc-pending-events";
+const NETWORK_KEYS: &str = "/network-keys";
+const EPOCH_SWITCH_STATUS: &str = "/epoch-switch-status";
+const DRAIN: &str = "/drain";
+const DRAIN_STATUS: &str = "/drain-status";
+const SNAPSHOT: &str = "/snapshot";
+
+/// How long to wait for the MPC service to answer an inspection query.
+const MPC_INSPECTION_TIMEOUT: Duration = Duration::from_secs(10);
 
 struct AppState {
     node: Arc<IkaNode>,
@@ -76,6 +132,17 @@ pub async fn run_admin_server(node: Arc<IkaNode>, port: u16, tracing_handle: Tra
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(SYSTEM_PARAMETER_VOTES, get(system_parameter_votes))
+        .route(STATE_SYNC_PEERS, get(state_sync_peers))
+        .route(MPC_SESSIONS, get(mpc_sessions))
+        .route(MPC_SESSION, get(mpc_session))
+        .route(MPC_PENDING_EVENTS, get(mpc_pending_events))
+        .route(NETWORK_KEYS, get(network_keys))
+        .route(EPOCH_SWITCH_STATUS, get(epoch_switch_status))
+        .route(DRAIN_STATUS, get(drain_status))
+        .route(DRAIN, post(drain))
+        .route(SNAPSHOT, post(snapshot))
+        .route(PROPOSE_SYSTEM_PARAMETER, post(propose_system_parameter))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -223,6 +290,291 @@ async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String)
     (StatusCode::OK, format!("{node_config:#?}\n"))
 }
 
+#[derive(Deserialize)]
+struct ProposeSystemParameter {
+    parameter: String,
+    value: u64,
+}
+
+async fn propose_system_parameter(
+    State(state): State<Arc<AppState>>,
+    args: Query<ProposeSystemParameter>,
+) -> (StatusCode, String) {
+    let Query(ProposeSystemParameter { parameter, value }) = args;
+
+    let change = match SystemParameterChange::new(&parameter, value) {
+        Ok(change) => change,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match state
+        .node
+        .submit_system_parameter_vote(change.clone())
+        .await
+    {
+        Ok(()) => (
+            StatusCode::OK,
+            format!("vote for '{change}' submitted to consensus\n"),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn system_parameter_votes(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+
+    let mut output = String::new();
+    for tally in epoch_store.get_system_parameter_votes() {
+        let voters = tally
+            .voters
+            .iter()
+            .map(|voter| format!("{:?}", voter.concise()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!(
+            "{}: stake {}/{} quorum_reached={} voters=[{}]\n",
+            tally.change, tally.total_votes, tally.quorum_threshold, tally.quorum_reached, voters
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+async fn state_sync_peers(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let format_time = |time: Option<std::time::SystemTime>| {
+        time.map_or_else(
+            || "-".to_string(),
+            |time| humantime::format_rfc3339_seconds(time).to_string(),
+        )
+    };
+
+    let mut output = String::new();
+    for stats in state.node.state_sync_handle.peer_sync_stats() {
+        let latency = stats
+            .latency
+            .map_or_else(|| "-".to_string(), |latency| format!("{latency:?}"));
+        output.push_str(&format!(
+            "{}: successes={} failures={} bad_responses={} latency={} failure_rate={:.2} last_success={} last_failure={}\n",
+            stats.peer_id,
+            stats.successes,
+            stats.failures,
+            stats.bad_responses,
+            latency,
+            stats.failure_rate,
+            format_time(stats.last_success),
+            format_time(stats.last_failure),
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+/// Runs an inspection query against the MPC service of the current epoch.
+async fn inspect_mpc<T, F>(
+    state: &AppState,
+    query: impl FnOnce(MPCInspectionHandle) -> F,
+) -> Result<T, (StatusCode, String)>
+where
+    F: Future<Output = Option<T>>,
+{
+    let Some(inspection) = state.node.dwallet_mpc_inspection().await else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            "MPC sessions are only available on validators\n".to_string(),
+        ));
+    };
+    match tokio::time::timeout(MPC_INSPECTION_TIMEOUT, query(inspection)).await {
+        Ok(Some(result)) => Ok(result),
+        Ok(None) => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "the MPC service of the current epoch is not running\n".to_string(),
+        )),
+        Err(_) => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "timed out waiting for the MPC service to answer\n".to_string(),
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+struct MPCSessions {
+    all: Option<bool>,
+}
+
+async fn mpc_sessions(
+    State(state): State<Arc<AppState>>,
+    args: Query<MPCSessions>,
+) -> (StatusCode, String) {
+    let Query(MPCSessions { all }) = args;
+    let include_completed = all.unwrap_or(false);
+
+    let sessions = match inspect_mpc(&state, |inspection| async move {
+        inspection.sessions(include_completed).await
+    })
+    .await
+    {
+        Ok(sessions) => sessions,
+        Err(err) => return err,
+    };
+
+    let mut output = String::new();
+    for session in sessions {
+        output.push_str(&format!(
+            "0x{}: status={} protocol={} sequence_number={} mpc_round={} attempt={} pending_computations={}\n",
+            hex::encode(session.session_identifier.into_bytes()),
+            session.status,
+            session.protocol.as_deref().unwrap_or("-"),
+            session
+                .session_sequence_number
+                .map_or_else(|| "-".to_string(), |number| number.to_string()),
+            session.current_mpc_round,
+            session.attempt_number,
+            session.pending_computations.len(),
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct MPCSession {
+    session_identifier: String,
+}
+
+async fn mpc_session(
+    State(state): State<Arc<AppState>>,
+    args: Query<MPCSession>,
+) -> (StatusCode, String) {
+    let Query(MPCSession { session_identifier }) = args;
+
+    let session_identifier = match hex::decode(session_identifier.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| <[u8; SessionIdentifier::LENGTH]>::try_from(bytes).ok())
+    {
+        Some(session_identifier) => session_identifier,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "session_identifier must be the hex of {} bytes\n",
+                    SessionIdentifier::LENGTH
+                ),
+            );
+        }
+    };
+
+    match inspect_mpc(&state, |inspection| async move {
+        inspection.session(session_identifier).await
+    })
+    .await
+    {
+        Ok(Some(session)) => (StatusCode::OK, format!("{session:#?}\n")),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            "no such session in the current epoch\n".to_string(),
+        ),
+        Err(err) => err,
+    }
+}
+
+async fn mpc_pending_events(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match inspect_mpc(&state, |inspection| async move {
+        inspection.pending_events().await
+    })
+    .await
+    {
+        Ok(pending_events) => (StatusCode::OK, format!("{pending_events:#?}\n")),
+        Err(err) => err,
+    }
+}
+
+async fn network_keys(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let network_keys = match inspect_mpc(&state, |inspection| async move {
+        inspection.network_keys().await
+    })
+    .await
+    {
+        Ok(network_keys) => network_keys,
+        Err(err) => return err,
+    };
+
+    let mut output = String::new();
+    for key in network_keys {
+        output.push_str(&format!(
+            "{}: on_chain_state={} on_chain_epoch={} state={} epoch={} shares_decrypted={} last_decryption_duration={} last_decryption_error={}\n",
+            key.key_id,
+            format_optional(&key.on_chain_state),
+            format_optional(&key.on_chain_epoch),
+            format_optional(&key.state),
+            format_optional(&key.epoch),
+            key.shares_decrypted,
+            format_optional(&key.last_decryption_duration),
+            key.last_decryption_error.as_deref().unwrap_or("-"),
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+fn format_optional<T: std::fmt::Debug>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "-".to_string(), |value| format!("{value:?}"))
+}
+
+async fn epoch_switch_status(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let (epoch, epoch_switch_state) = state.node.sui_connector_service.epoch_switch_state();
+    let mut output = format!("epoch: {epoch}\n");
+    for (step, done) in epoch_switch_state.steps() {
+        output.push_str(&format!("{step}: {done}\n"));
+    }
+    (StatusCode::OK, output)
+}
+
+async fn drain(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.node.start_drain().await {
+        Ok(true) => (StatusCode::OK, "draining the node\n".to_string()),
+        Ok(false) => (
+            StatusCode::OK,
+            format!(
+                "the node is already draining: {}\n",
+                state.node.drain_state()
+            ),
+        ),
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+async fn drain_status(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    (StatusCode::OK, format!("{}\n", state.node.drain_state()))
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    output_path: PathBuf,
+}
+
+async fn snapshot(
+    State(state): State<Arc<AppState>>,
+    args: Query<Snapshot>,
+) -> (StatusCode, String) {
+    let Query(Snapshot { output_path }) = args;
+    if !output_path.is_absolute() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "output_path must be an absolute path\n".to_string(),
+        );
+    }
+
+    match state.node.create_snapshot(output_path.clone()).await {
+        Ok(manifest) => (
+            StatusCode::OK,
+            format!("Wrote snapshot {}\n{manifest}\n", output_path.display()),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}\n")),
+    }
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
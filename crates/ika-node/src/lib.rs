@@ -61,8 +61,9 @@ use ika_core::epoch::committee_store::CommitteeStore;
 use ika_core::epoch::consensus_store_pruner::ConsensusStorePruner;
 use ika_core::epoch::epoch_metrics::EpochMetrics;
 use ika_core::storage::RocksDbStore;
+use ika_network::access_control::PeerAccessControl;
 use ika_network::discovery::TrustedPeerChangeEvent;
-use ika_network::{discovery, state_sync};
+use ika_network::{discovery, network_keys, state_sync};
 use ika_protocol_config::{ProtocolConfig, ProtocolVersion};
 use mysten_metrics::{RegistryService, spawn_monitored_task};
 use sui_json_rpc_types::SuiEvent;
@@ -72,8 +73,9 @@ use sui_types::base_types::EpochId;
 
 use ika_types::committee::Committee;
 use ika_types::crypto::AuthorityName;
-use ika_types::error::IkaResult;
+use ika_types::error::{IkaError, IkaResult};
 use ika_types::messages_consensus::{AuthorityCapabilitiesV1, ConsensusTransaction};
+use ika_types::messages_system_checkpoints::SystemParameterChange;
 use ika_types::sui::SystemInnerTrait;
 use ika_types::sui::epoch_start_system::EpochStartSystem;
 use ika_types::sui::epoch_start_system::EpochStartSystemTrait;
@@ -85,10 +87,13 @@ use typed_store::DBMetrics;
 use typed_store::rocks::default_db_options;
 
 use crate::metrics::IkaNodeMetrics;
+use crate::snapshot::{NodeDatabases, SnapshotManifest};
 
 pub mod admin;
 mod handle;
 pub mod metrics;
+pub mod preflight;
+pub mod snapshot;
 
 pub struct ValidatorComponents {
     consensus_manager: Arc<ConsensusManager>,
@@ -103,6 +108,7 @@ pub struct ValidatorComponents {
 
     dwallet_mpc_service_exit: watch::Sender<()>,
     dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+    dwallet_mpc_inspection: MPCInspectionHandle,
 }
 
 pub struct P2pComponents {
@@ -110,6 +116,7 @@ pub struct P2pComponents {
     known_peers: HashMap<PeerId, String>,
     discovery_handle: discovery::Handle,
     state_sync_handle: state_sync::Handle,
+    network_keys_handle: network_keys::Handle,
 }
 
 #[cfg(msim)]
@@ -167,8 +174,11 @@ mod simulator {
 use ika_core::SuiDataReceivers;
 use ika_core::authority::authority_perpetual_tables::AuthorityPerpetualTables;
 use ika_core::consensus_handler::ConsensusHandlerInitializer;
+use ika_core::dwallet_mpc::drain::{DrainMode, DrainState};
 use ika_core::dwallet_mpc::dwallet_mpc_metrics::DWalletMPCMetrics;
 use ika_core::dwallet_mpc::dwallet_mpc_service::DWalletMPCService;
+use ika_core::dwallet_mpc::mpc_inspection::MPCInspectionHandle;
+use ika_core::dwallet_mpc::session_latency::SessionLatencyTracker;
 use ika_core::epoch::submit_to_consensus::EpochStoreSubmitToConsensus;
 use ika_core::sui_connector::SuiConnectorService;
 use ika_core::sui_connector::end_of_publish_sender::EndOfPublishSender;
@@ -211,11 +221,16 @@ pub struct IkaNode {
     sim_state: SimState,
 
     sui_connector_service: Arc<SuiConnectorService>,
+    session_latency: SessionLatencyTracker,
+    drain_mode: DrainMode,
 
     _state_archive_handle: Option<broadcast::Sender<()>>,
 
     shutdown_channel_tx: broadcast::Sender<Option<RunWithRange>>,
     system_checkpoint_store: Arc<SystemCheckpointStore>,
+
+    /// The version of the node binary, recorded in the snapshots it writes.
+    software_version: &'static str,
 }
 
 impl fmt::Debug for IkaNode {
@@ -240,7 +255,7 @@ impl IkaNode {
     pub async fn start_async(
         config: NodeConfig,
         registry_service: RegistryService,
-        _software_version: &'static str,
+        software_version: &'static str,
     ) -> Result<Arc<IkaNode>> {
         NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(&config);
         let mut config = config.clone();
@@ -271,7 +286,7 @@ impl IkaNode {
 
         let sui_client = Arc::new(
             SuiClient::new(
-                &config.sui_connector_config.sui_rpc_url,
+                &config.sui_connector_config.sui_rpc_urls(),
                 sui_client_metrics,
                 config.sui_connector_config.ika_package_id,
                 config.sui_connector_config.ika_common_package_id,
@@ -301,6 +316,8 @@ impl IkaNode {
 
         let secret = Arc::pin(config.protocol_key_pair().copy());
         let committee_store = Arc::new(CommitteeStore::new(config.db_path().join("epochs"), None));
+        // State sync verifies the checkpoints of this epoch with its committee.
+        committee_store.insert_new_committee(&committee)?;
         let perpetual_tables_options = default_db_options().optimize_db_for_write_throughput(4);
         let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(
             &config.db_path().join("store"),
@@ -334,6 +351,8 @@ impl IkaNode {
         );
 
         let dwallet_mpc_metrics = DWalletMPCMetrics::new(&registry_service.default_registry());
+        let session_latency = SessionLatencyTracker::new(&registry_service.default_registry());
+        let drain_mode = DrainMode::new();
 
         let epoch_store = AuthorityPerEpochStore::new(
             config.protocol_public_key(),
@@ -395,6 +414,7 @@ impl IkaNode {
             known_peers,
             discovery_handle,
             state_sync_handle,
+            network_keys_handle,
         } = Self::create_p2p_network(
             &config,
             state_sync_store.clone(),
@@ -447,18 +467,24 @@ impl IkaNode {
         ) = watch::channel((0, 0));
         let (uncompleted_events_sender, uncompleted_events_receiver) =
             watch::channel((Vec::new(), 0));
+        let (handled_events_sender, handled_events_receiver) = watch::channel(None);
         let (sui_connector_service, network_keys_receiver) = SuiConnectorService::new(
             dwallet_checkpoint_store.clone(),
             system_checkpoint_store.clone(),
             sui_client.clone(),
             config.sui_connector_config.clone(),
             sui_connector_metrics,
+            session_latency.clone(),
+            drain_mode.clone(),
+            perpetual_tables.clone(),
+            network_keys_handle,
             state.is_validator(&epoch_store),
             next_epoch_committee_sender,
             new_events_sender,
             end_of_publish_sender.clone(),
             last_session_to_complete_in_current_epoch_sender,
             uncompleted_events_sender,
+            handled_events_receiver,
         )
         .await?;
 
@@ -503,6 +529,7 @@ impl IkaNode {
             last_session_to_complete_in_current_epoch_receiver,
             end_of_publish_receiver,
             uncompleted_events_receiver,
+            handled_events_sender,
         };
         let validator_components = if state.is_validator(&epoch_store) {
             let components = Self::construct_validator_components(
@@ -520,6 +547,8 @@ impl IkaNode {
                 previous_epoch_last_system_checkpoint_sequence_number,
                 sui_client.clone(),
                 dwallet_mpc_metrics.clone(),
+                session_latency.clone(),
+                drain_mode.clone(),
                 sui_data_receivers.clone(),
             )
             .await?;
@@ -555,8 +584,11 @@ impl IkaNode {
             sim_state: Default::default(),
 
             sui_connector_service,
+            session_latency,
+            drain_mode,
             _state_archive_handle: state_archive_handle,
             shutdown_channel_tx: shutdown_channel,
+            software_version,
         };
 
         info!("IkaNode started!");
@@ -609,6 +641,84 @@ impl IkaNode {
             .set_override_protocol_upgrade_buffer_stake(epoch, buffer_stake_bps)
     }
 
+    /// Submits this validator's vote for a system parameter change to consensus.
+    pub async fn submit_system_parameter_vote(&self, change: SystemParameterChange) -> IkaResult {
+        change.validate()?;
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        if !epoch_store.protocol_config().system_parameter_governance() {
+            return Err(IkaError::WrongMessageVersion {
+                error: "system parameter governance is not enabled at the current protocol version"
+                    .to_string(),
+            });
+        }
+        let Some(components) = &*self.validator_components.lock().await else {
+            return Err(IkaError::GenericAuthorityError {
+                error: "only validators can vote on system parameters".to_string(),
+            });
+        };
+        let transaction = ConsensusTransaction::new_system_parameter_vote(self.state.name, change);
+        info!(
+            ?transaction,
+            "submitting system parameter vote to consensus"
+        );
+        components
+            .consensus_adapter
+            .submit_to_consensus(&[transaction], &epoch_store)
+            .await
+    }
+
+    /// Starts draining this validator before a maintenance restart: it finishes the MPC sessions
+    /// it started, doesn't start new user sessions, and shuts down once its MPC work is done.
+    ///
+    /// Returns `false` if the node was already draining.
+    pub async fn start_drain(&self) -> IkaResult<bool> {
+        if self.validator_components.lock().await.is_none() {
+            return Err(IkaError::GenericAuthorityError {
+                error: "only validators can be drained".to_string(),
+            });
+        }
+        let started = self.drain_mode.start();
+        if started {
+            info!("Draining the node, it will shut down once its MPC work is done");
+        }
+        Ok(started)
+    }
+
+    pub fn drain_state(&self) -> DrainState {
+        self.drain_mode.state()
+    }
+
+    /// Writes a snapshot of the node databases to `output_path` while the node keeps running,
+    /// from RocksDB checkpoints of the database handles it has open.
+    pub async fn create_snapshot(&self, output_path: PathBuf) -> Result<SnapshotManifest> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task().clone();
+        let databases = NodeDatabases {
+            perpetual_tables: self.state.perpetual_tables().clone(),
+            epoch: epoch_store.epoch(),
+            epoch_tables: epoch_store.tables()?,
+            committee_store: self.state.committee_store().clone(),
+            dwallet_checkpoint_store: self.dwallet_checkpoint_store.clone(),
+            system_checkpoint_store: self.system_checkpoint_store.clone(),
+        };
+        let chain_identifier =
+            ChainIdentifier::from(self.config.sui_connector_config.ika_system_object_id);
+        let software_version = self.software_version;
+        tokio::task::spawn_blocking(move || {
+            snapshot::create_snapshot(&databases, chain_identifier, &output_path, software_version)
+        })
+        .await?
+    }
+
+    /// Returns a handle to inspect the MPC sessions of the current epoch, if this node is a
+    /// validator.
+    pub async fn dwallet_mpc_inspection(&self) -> Option<MPCInspectionHandle> {
+        self.validator_components
+            .lock()
+            .await
+            .as_ref()
+            .map(|components| components.dwallet_mpc_inspection.clone())
+    }
+
     async fn start_state_archival(
         config: &NodeConfig,
         prometheus_registry: &Registry,
@@ -645,15 +755,23 @@ impl IkaNode {
         prometheus_registry: &Registry,
         is_notifier: bool,
     ) -> Result<P2pComponents> {
+        let access_control = PeerAccessControl::new(&config.p2p_config, prometheus_registry);
         let (state_sync, state_sync_server) = state_sync::Builder::new()
             .config(config.p2p_config.state_sync.clone().unwrap_or_default())
             .store(state_sync_store)
             .archive_readers(archive_readers)
+            .access_control(access_control.clone())
             .with_metrics(prometheus_registry)
             .build();
 
         let (discovery, discovery_server) = discovery::Builder::new(trusted_peer_change_rx)
             .config(config.p2p_config.clone())
+            .access_control(access_control.clone())
+            .build();
+
+        let (network_keys, network_keys_server) = network_keys::Builder::new()
+            .access_control(access_control)
+            .with_metrics(prometheus_registry)
             .build();
 
         let discovery_config = config.p2p_config.discovery.clone().unwrap_or_default();
@@ -671,7 +789,8 @@ impl IkaNode {
         let p2p_network = {
             let routes = anemo::Router::new()
                 .add_rpc_service(discovery_server)
-                .add_rpc_service(state_sync_server);
+                .add_rpc_service(state_sync_server)
+                .add_rpc_service(network_keys_server);
             let inbound_network_metrics =
                 consensus_core::NetworkRouteMetrics::new("ika", "inbound", prometheus_registry);
             let outbound_network_metrics =
@@ -768,12 +887,14 @@ impl IkaNode {
         let discovery_handle =
             discovery.start(p2p_network.clone(), config.network_key_pair().copy());
         let state_sync_handle = state_sync.start(p2p_network.clone(), is_notifier);
+        let network_keys_handle = network_keys.start(p2p_network.clone());
 
         Ok(P2pComponents {
             p2p_network,
             known_peers,
             discovery_handle,
             state_sync_handle,
+            network_keys_handle,
         })
     }
 
@@ -792,6 +913,8 @@ impl IkaNode {
         previous_epoch_last_system_checkpoint_sequence_number: u64,
         sui_client: Arc<SuiConnectorClient>,
         dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
         sui_data_receivers: SuiDataReceivers,
     ) -> Result<ValidatorComponents> {
         let mut config_clone = config.clone();
@@ -844,6 +967,8 @@ impl IkaNode {
             consensus_store_pruner,
             dwallet_checkpoint_metrics,
             dwallet_mpc_metrics,
+            session_latency,
+            drain_mode,
             system_checkpoint_metrics,
             ika_node_metrics,
             ika_tx_validator_metrics,
@@ -866,6 +991,8 @@ impl IkaNode {
         consensus_store_pruner: ConsensusStorePruner,
         dwallet_checkpoint_metrics: Arc<DWalletCheckpointMetrics>,
         dwallet_mpc_metrics: Arc<DWalletMPCMetrics>,
+        session_latency: SessionLatencyTracker,
+        drain_mode: DrainMode,
         system_checkpoint_metrics: Arc<SystemCheckpointMetrics>,
         _ika_node_metrics: Arc<IkaNodeMetrics>,
         ika_tx_validator_metrics: Arc<IkaTxValidatorMetrics>,
@@ -881,7 +1008,9 @@ impl IkaNode {
             state.clone(),
             state_sync_handle.clone(),
             dwallet_checkpoint_metrics.clone(),
+            session_latency.clone(),
             previous_epoch_last_dwallet_checkpoint_sequence_number,
+            sui_data_receivers.next_epoch_committee_receiver.clone(),
         );
 
         let (system_checkpoint_service, system_checkpoint_service_tasks) =
@@ -898,20 +1027,25 @@ impl IkaNode {
 
         let (dwallet_mpc_service_exit_sender, dwallet_mpc_service_exit_receiver) =
             watch::channel(());
-        if let Err(e) =
-            DWalletMPCService::verify_validator_keys(epoch_store.epoch_start_state(), config)
-        {
-            error!(error = ?e, "Failed to verify validator keys");
-            panic!("Failed to verify validator keys: {e}");
-        };
+        let root_seed =
+            match DWalletMPCService::verify_validator_keys(epoch_store.epoch_start_state(), config)
+            {
+                Ok(root_seed) => root_seed,
+                Err(e) => {
+                    error!(error = ?e, "Failed to verify validator keys");
+                    panic!("Failed to verify validator keys: {e}");
+                }
+            };
 
         let mut dwallet_mpc_service = DWalletMPCService::new(
             epoch_store.clone(),
             dwallet_mpc_service_exit_receiver,
             EpochStoreSubmitToConsensus::new(epoch_store.clone(), consensus_adapter.clone()),
-            config.clone(),
+            root_seed,
             checkpoint_service.clone(),
             dwallet_mpc_metrics.clone(),
+            session_latency,
+            drain_mode,
             state.clone(),
             sui_data_receivers,
             epoch_store.name,
@@ -981,6 +1115,7 @@ impl IkaNode {
 
         // Spawn the dWallet MPC Service now that we are done with bootstrapping both
         // from storage and from the consensus.
+        let dwallet_mpc_inspection = dwallet_mpc_service.inspection_handle();
         spawn_monitored_task!(dwallet_mpc_service.spawn(replay_waiter));
 
         Ok(ValidatorComponents {
@@ -994,6 +1129,7 @@ impl IkaNode {
             ika_tx_validator_metrics,
             dwallet_mpc_metrics,
             dwallet_mpc_service_exit: dwallet_mpc_service_exit_sender,
+            dwallet_mpc_inspection,
         })
     }
 
@@ -1005,7 +1141,9 @@ impl IkaNode {
         state: Arc<AuthorityState>,
         state_sync_handle: state_sync::Handle,
         checkpoint_metrics: Arc<DWalletCheckpointMetrics>,
+        session_latency: SessionLatencyTracker,
         previous_epoch_last_dwallet_checkpoint_sequence_number: u64,
+        next_epoch_committee_receiver: watch::Receiver<Committee>,
     ) -> (Arc<DWalletCheckpointService>, JoinSet<()>) {
         let epoch_start_timestamp_ms = epoch_store.epoch_start_state().epoch_start_timestamp_ms();
         let epoch_duration_ms = epoch_store.epoch_start_state().epoch_duration_ms();
@@ -1037,9 +1175,11 @@ impl IkaNode {
             checkpoint_output,
             Box::new(certified_checkpoint_output),
             checkpoint_metrics,
+            session_latency,
             max_tx_per_checkpoint,
             max_dwallet_checkpoint_size_bytes,
             previous_epoch_last_dwallet_checkpoint_sequence_number,
+            next_epoch_committee_receiver,
         )
     }
 
@@ -1125,6 +1265,14 @@ impl IkaNode {
         self.state.committee_store().clone()
     }
 
+    pub fn dwallet_checkpoint_store(&self) -> &Arc<DWalletCheckpointStore> {
+        &self.dwallet_checkpoint_store
+    }
+
+    pub fn system_checkpoint_store(&self) -> &Arc<SystemCheckpointStore> {
+        &self.system_checkpoint_store
+    }
+
     /*
     pub fn clone_authority_store(&self) -> Arc<AuthorityStore> {
         self.state.db()
@@ -1152,15 +1300,47 @@ impl IkaNode {
                 .current_protocol_version
                 .set(config.version.as_u64() as i64);
 
+            let chain = cur_epoch_store.get_chain_identifier().chain();
+            let supported_protocol_versions = self
+                .config
+                .supported_protocol_versions
+                .expect("Supported versions should be populated")
+                // no need to send digests of versions less than the current version
+                .truncate_below(config.version);
+            // Only vote for protocol versions whose checkpoints the on-chain coordinator accepts.
+            let supported_protocol_versions =
+                match sui_client.get_dwallet_coordinator_version().await {
+                    Ok(coordinator_version) => {
+                        let accepted = supported_protocol_versions
+                            .truncate_above_dwallet_coordinator_version(coordinator_version, chain);
+                        if accepted.max < supported_protocol_versions.max {
+                            info!(
+                                coordinator_version,
+                                max_protocol_version = ?accepted.max,
+                                "not voting for newer protocol versions until the dWallet \
+                                 coordinator is migrated to them"
+                            );
+                        }
+                        accepted
+                    }
+                    Err(err) => {
+                        warn!(
+                            ?err,
+                            "failed to read the dWallet coordinator version, \
+                             only supporting the current protocol version"
+                        );
+                        SupportedProtocolVersions {
+                            min: config.version,
+                            max: config.version,
+                        }
+                    }
+                };
+
             let transaction =
                 ConsensusTransaction::new_capability_notification_v1(AuthorityCapabilitiesV1::new(
                     self.state.name,
-                    cur_epoch_store.get_chain_identifier().chain(),
-                    self.config
-                        .supported_protocol_versions
-                        .expect("Supported versions should be populated")
-                        // no need to send digests of versions less than the current version
-                        .truncate_below(config.version),
+                    chain,
+                    supported_protocol_versions,
                     vec![],
                     // Note: this is a temp fix, we will handle package upgrades later.
                     // sui_client
@@ -1213,6 +1393,14 @@ impl IkaNode {
                         .expect("RunWithRangeCondition met but failed to send shutdown message");
                     return Ok(());
                 }
+                StopReason::Drained => {
+                    info!("The node finished draining, shutting down");
+                    IkaNode::shutdown(&self).await;
+                    self.shutdown_channel_tx
+                        .send(None)
+                        .expect("drain completed but failed to send shutdown message");
+                    return Ok(());
+                }
             };
             end_of_publish_sender_handle.map(|handle| {
                 handle.abort();
@@ -1295,6 +1483,7 @@ impl IkaNode {
                 ika_tx_validator_metrics,
                 dwallet_mpc_metrics,
                 dwallet_mpc_service_exit,
+                dwallet_mpc_inspection: _,
             }) = self.validator_components.lock().await.take()
             {
                 info!("Reconfiguring the validator.");
@@ -1358,6 +1547,8 @@ impl IkaNode {
                             consensus_store_pruner,
                             checkpoint_metrics,
                             dwallet_mpc_metrics,
+                            self.session_latency.clone(),
+                            self.drain_mode.clone(),
                             system_checkpoint_metrics,
                             self.metrics.clone(),
                             ika_tx_validator_metrics,
@@ -1400,6 +1591,8 @@ impl IkaNode {
                             // safe to unwrap because we are a validator
                             sui_client.clone(),
                             dwallet_mpc_metrics.clone(),
+                            self.session_latency.clone(),
+                            self.drain_mode.clone(),
                             sui_data_receivers.clone(),
                         )
                         .await?,
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, Subcommand};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,7 +9,7 @@ use tokio::sync::broadcast;
 use tokio::time::sleep;
 use tracing::{error, info};
 
-use ika_config::node::RunWithRange;
+use ika_config::node::{OtlpTracingConfig, RunWithRange};
 use ika_config::{Config, NodeConfig};
 use ika_core::runtime::IkaRuntimes;
 use ika_telemetry::send_telemetry_event;
@@ -41,6 +41,77 @@ struct Args {
 
     #[clap(long, group = "exclusive")]
     run_with_range_checkpoint: Option<DWalletCheckpointSequenceNumber>,
+
+    #[clap(
+        long,
+        help = "Check the node config and its environment, print a report and exit"
+    )]
+    check_config: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+enum Command {
+    /// Write a snapshot of the node databases, the node must be stopped.
+    /// A running node is snapshotted through the admin `/snapshot` route.
+    Snapshot {
+        #[clap(long)]
+        output_path: PathBuf,
+    },
+    /// Restore the node databases from a snapshot, they must not exist yet.
+    Restore {
+        #[clap(long)]
+        snapshot_path: PathBuf,
+    },
+}
+
+/// Runs the subcommand and exits the process.
+fn run_command(command: Command, config: &NodeConfig) -> ! {
+    let result = match command {
+        Command::Snapshot { output_path } => {
+            ika_node::snapshot::create_snapshot_of_stopped_node(config, &output_path, VERSION)
+                .map(|manifest| format!("Wrote snapshot {}\n{manifest}", output_path.display()))
+        }
+        Command::Restore { snapshot_path } => tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(ika_node::snapshot::restore_snapshot(config, &snapshot_path))
+            .map(|restored| {
+                format!(
+                    "Restored {} from snapshot {}\n{restored}",
+                    config.db_path().display(),
+                    snapshot_path.display()
+                )
+            }),
+    };
+    match result {
+        Ok(message) => {
+            println!("{message}");
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Enables OTLP span export in the telemetry subscriber, which reads its exporter
+/// settings from the environment. Variables already set by the operator are kept.
+fn configure_otlp_tracing(otlp_tracing: &OtlpTracingConfig) {
+    let sample_rate = otlp_tracing.sample_rate().to_string();
+    for (key, value) in [
+        ("OTLP_ENDPOINT", otlp_tracing.endpoint()),
+        ("TRACE_FILTER", otlp_tracing.filter()),
+        ("SAMPLE_RATE", sample_rate.as_str()),
+    ] {
+        if std::env::var_os(key).is_none() {
+            // SAFETY: called from `main` before any other thread is spawned.
+            unsafe { std::env::set_var(key, value) };
+        }
+    }
 }
 
 fn main() {
@@ -49,6 +120,14 @@ fn main() {
     // ProtocolConfig::poison_get_for_min_version();
 
     let args = Args::parse();
+    if args.check_config {
+        let report = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(ika_node::preflight::check_config(&args.config_path));
+        println!("{report}");
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+
     let mut config = NodeConfig::load(&args.config_path).unwrap();
     assert!(
         config.supported_protocol_versions.is_none(),
@@ -56,6 +135,10 @@ fn main() {
     );
     config.supported_protocol_versions = Some(SupportedProtocolVersions::SYSTEM_DEFAULT);
 
+    if let Some(command) = args.command {
+        run_command(command, &config);
+    }
+
     // Match run_with_range args
     // this means that we always modify the config used to start the node
     // for run_with_range.
@@ -70,6 +153,11 @@ fn main() {
         _ => config.run_with_range = None,
     };
 
+    // Must run before any other thread is spawned, as it modifies the process environment.
+    if let Some(otlp_tracing) = &config.otlp_tracing {
+        configure_otlp_tracing(otlp_tracing);
+    }
+
     let runtimes = IkaRuntimes::new(&config);
     let metrics_rt = runtimes.metrics.enter();
     let registry_service = mysten_metrics::start_prometheus_server(config.metrics_address);
@@ -0,0 +1,859 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! The `ika-node --check-config` preflight checks.
+//!
+//! A misconfigured node otherwise fails late: a root seed that doesn't match the on-chain MPC data
+//! panics when the MPC service of the first epoch starts, and unreadable key files panic when they
+//! are first used. The preflight checks the config and the environment of the node without
+//! starting it, and reports all the problems it finds at once.
+
+use ika_config::node::SuiChainIdentifier;
+use ika_config::{Config, NodeConfig};
+use ika_core::dwallet_mpc::dwallet_mpc_service::DWalletMPCService;
+use ika_sui_client::SuiClient;
+use ika_sui_client::metrics::SuiClientMetrics;
+use ika_sui_client::signer::{RemoteSigner, Signer};
+use ika_types::crypto::{AuthorityKeyPair, EncodeDecodeBase64, KeypairTraits};
+use ika_types::sui::{EpochStartSystemTrait, EpochStartValidatorInfoTrait};
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sui_types::crypto::SuiKeyPair;
+use sui_types::digests::{get_mainnet_chain_identifier, get_testnet_chain_identifier};
+
+/// How long to wait for the Sui fullnodes to answer.
+const SUI_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Below this much free disk space in the DB path the check fails.
+const MIN_FREE_DISK_SPACE_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+/// Below this much free disk space in the DB path the check warns.
+const RECOMMENDED_FREE_DISK_SPACE_BYTES: u64 = 100 * 1024 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Warning => "WARN",
+            CheckStatus::Failed => "FAIL",
+            CheckStatus::Skipped => "SKIP",
+        };
+        f.pad(status)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    /// Whether none of the checks failed. Warnings don't fail the preflight.
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Failed)
+    }
+
+    fn record(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(PreflightCheck {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    fn ok(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.record(name, CheckStatus::Ok, detail);
+    }
+
+    fn warn(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.record(name, CheckStatus::Warning, detail);
+    }
+
+    fn fail(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.record(name, CheckStatus::Failed, detail);
+    }
+
+    fn skip(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.record(name, CheckStatus::Skipped, detail);
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or_default();
+        for check in &self.checks {
+            writeln!(
+                f,
+                "[{:<4}] {:<name_width$}  {}",
+                check.status, check.name, check.detail
+            )?;
+        }
+        let count = |status| {
+            self.checks
+                .iter()
+                .filter(|check| check.status == status)
+                .count()
+        };
+        write!(
+            f,
+            "{} checks: {} ok, {} warnings, {} failed, {} skipped",
+            self.checks.len(),
+            count(CheckStatus::Ok),
+            count(CheckStatus::Warning),
+            count(CheckStatus::Failed),
+            count(CheckStatus::Skipped),
+        )
+    }
+}
+
+/// The location of a key in the node config, which is either a `path` or an in-place `value`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct KeyLocation {
+    path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct NotifierKeyLocation {
+    notifier_client_key_pair: Option<KeyLocation>,
+}
+
+/// The paths of the key files in the node config.
+/// Loading the keys through [`NodeConfig`] panics on an unreadable key file,
+/// so the preflight reads the files itself.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct KeyFilePaths {
+    protocol_key_pair: Option<KeyLocation>,
+    consensus_key_pair: Option<KeyLocation>,
+    account_key_pair: Option<KeyLocation>,
+    network_key_pair: Option<KeyLocation>,
+    #[serde(default)]
+    sui_connector_config: NotifierKeyLocation,
+}
+
+impl Config for KeyFilePaths {}
+
+/// Runs all the preflight checks for the node config at `config_path`.
+pub async fn check_config(config_path: &Path) -> PreflightReport {
+    let mut report = PreflightReport::default();
+
+    let config = match NodeConfig::load(config_path) {
+        Ok(config) => {
+            report.ok("config", format!("loaded {}", config_path.display()));
+            config
+        }
+        Err(err) => {
+            report.fail(
+                "config",
+                format!("failed to load {}: {err:#}", config_path.display()),
+            );
+            return report;
+        }
+    };
+    let is_validator = config.consensus_config.is_some();
+
+    let keys_readable = match KeyFilePaths::load(config_path) {
+        Ok(key_file_paths) => check_key_files(&mut report, &config, &key_file_paths, is_validator),
+        Err(err) => {
+            report.fail(
+                "key-files",
+                format!("failed to read the key paths: {err:#}"),
+            );
+            false
+        }
+    };
+    check_notifier_remote_signer(&mut report, &config).await;
+    check_ports(&mut report, &config);
+    check_disk_space(&mut report, &config);
+    check_sui(&mut report, &config, is_validator, keys_readable).await;
+
+    report
+}
+
+/// Checks that every key file is readable and holds a key of the expected scheme.
+/// Returns whether all the keys of the node can be loaded.
+fn check_key_files(
+    report: &mut PreflightReport,
+    config: &NodeConfig,
+    key_file_paths: &KeyFilePaths,
+    is_validator: bool,
+) -> bool {
+    let mut keys_readable = true;
+
+    match &key_file_paths.protocol_key_pair {
+        Some(KeyLocation { path: Some(path) }) => {
+            match read_key_file(path, AuthorityKeyPair::decode_base64) {
+                Ok(_) => report.ok("protocol-key", format!("read {}", path.display())),
+                Err(err) => {
+                    keys_readable = false;
+                    report.fail("protocol-key", err);
+                }
+            }
+        }
+        Some(_) => report.ok("protocol-key", "set in the config"),
+        None if is_validator => {
+            keys_readable = false;
+            report.fail("protocol-key", "not set, a random key would be generated");
+        }
+        None => report.skip("protocol-key", "not set, a random key will be generated"),
+    }
+
+    for (name, location, ed25519_only) in [
+        ("consensus-key", &key_file_paths.consensus_key_pair, true),
+        ("network-key", &key_file_paths.network_key_pair, true),
+        ("account-key", &key_file_paths.account_key_pair, false),
+        (
+            "notifier-key",
+            &key_file_paths.sui_connector_config.notifier_client_key_pair,
+            false,
+        ),
+    ] {
+        match location {
+            Some(KeyLocation { path: Some(path) }) => {
+                match read_key_file(path, SuiKeyPair::decode_base64) {
+                    Ok(SuiKeyPair::Ed25519(_)) => {
+                        report.ok(name, format!("read {}", path.display()))
+                    }
+                    Ok(_) if ed25519_only => {
+                        keys_readable = false;
+                        report.fail(
+                            name,
+                            format!("{} is not an Ed25519 key pair", path.display()),
+                        );
+                    }
+                    Ok(_) => report.ok(name, format!("read {}", path.display())),
+                    Err(err) => {
+                        keys_readable = false;
+                        report.fail(name, err);
+                    }
+                }
+            }
+            Some(_) => report.ok(name, "set in the config"),
+            None if name == "notifier-key" => {
+                match &config.sui_connector_config.notifier_remote_signer {
+                    Some(remote_signer) => report.ok(
+                        name,
+                        format!("held by the remote signer at {}", remote_signer.url),
+                    ),
+                    None => report.skip(name, "not a notifier"),
+                }
+            }
+            None if is_validator => {
+                keys_readable = false;
+                report.fail(name, "not set, a random key would be generated");
+            }
+            None => report.skip(name, "not set, a random key will be generated"),
+        }
+    }
+
+    match &config.root_seed_key_pair {
+        Some(root_seed) => match root_seed.try_root_seed() {
+            Ok(_) => report.ok("root-seed", "loaded"),
+            Err(err) => {
+                keys_readable = false;
+                report.fail("root-seed", format!("{err:#}"));
+            }
+        },
+        None if is_validator => {
+            keys_readable = false;
+            report.fail("root-seed", "not set, it is required for validators");
+        }
+        None => report.skip("root-seed", "not set, not a validator"),
+    }
+    if let Some(next_root_seed) = &config.next_root_seed_key_pair {
+        match next_root_seed.try_root_seed() {
+            Ok(_) => report.ok(
+                "next-root-seed",
+                "loaded, a class-groups key rotation is set",
+            ),
+            Err(err) => {
+                keys_readable = false;
+                report.fail("next-root-seed", format!("{err:#}"));
+            }
+        }
+    }
+
+    keys_readable
+}
+
+/// Checks that the notifier's remote signer is reachable,
+/// and that the key it holds has the configured address.
+async fn check_notifier_remote_signer(report: &mut PreflightReport, config: &NodeConfig) {
+    let Some(remote_signer) = &config.sui_connector_config.notifier_remote_signer else {
+        return;
+    };
+    match RemoteSigner::connect(remote_signer).await {
+        Ok(signer) if remote_signer.address.is_some() => report.ok(
+            "notifier-remote-signer",
+            format!(
+                "reached {}, its key has the configured address {}",
+                remote_signer.url,
+                signer.address()
+            ),
+        ),
+        Ok(signer) => report.warn(
+            "notifier-remote-signer",
+            format!(
+                "reached {}, set its `address` to {} to check the key it holds",
+                remote_signer.url,
+                signer.address()
+            ),
+        ),
+        Err(err) => report.fail("notifier-remote-signer", format!("{err:#}")),
+    }
+}
+
+fn read_key_file<T, E: fmt::Display>(
+    path: &Path,
+    decode: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    decode(contents.trim()).map_err(|err| format!("invalid key file {}: {err}", path.display()))
+}
+
+/// Checks that the ports the node listens on are free.
+fn check_ports(report: &mut PreflightReport, config: &NodeConfig) {
+    for (name, address) in [
+        ("metrics-port", config.metrics_address),
+        (
+            "admin-port",
+            SocketAddr::from((Ipv4Addr::LOCALHOST, config.admin_interface_port)),
+        ),
+    ] {
+        match TcpListener::bind(address) {
+            Ok(_) => report.ok(name, format!("tcp {address} is free")),
+            Err(err) => report.fail(name, format!("can't listen on tcp {address}: {err}")),
+        }
+    }
+
+    let p2p_address = config.p2p_config.listen_address;
+    match UdpSocket::bind(p2p_address) {
+        Ok(_) => report.ok("p2p-port", format!("udp {p2p_address} is free")),
+        Err(err) => report.fail(
+            "p2p-port",
+            format!("can't listen on udp {p2p_address}: {err}"),
+        ),
+    }
+}
+
+/// Checks the free disk space where the node stores its DBs.
+fn check_disk_space(report: &mut PreflightReport, config: &NodeConfig) {
+    // The DB path is created when the node starts, so check the closest existing directory.
+    let Some(existing_path) = config.db_path.ancestors().find(|path| path.exists()) else {
+        report.fail(
+            "disk-space",
+            format!("no parent of {} exists", config.db_path.display()),
+        );
+        return;
+    };
+
+    match fs2::available_space(existing_path) {
+        Ok(available) => {
+            let detail = format!(
+                "{} GiB available in {}",
+                available / (1024 * 1024 * 1024),
+                existing_path.display()
+            );
+            if available < MIN_FREE_DISK_SPACE_BYTES {
+                report.fail("disk-space", detail);
+            } else if available < RECOMMENDED_FREE_DISK_SPACE_BYTES {
+                report.warn("disk-space", detail);
+            } else {
+                report.ok("disk-space", detail);
+            }
+        }
+        Err(err) => report.fail(
+            "disk-space",
+            format!(
+                "failed to read the free space of {}: {err}",
+                existing_path.display()
+            ),
+        ),
+    }
+}
+
+/// Checks the connection to Sui, the chain identifier, the Ika objects and packages,
+/// and that the validator keys match its on-chain info.
+async fn check_sui(
+    report: &mut PreflightReport,
+    config: &NodeConfig,
+    is_validator: bool,
+    keys_readable: bool,
+) {
+    let sui_connector_config = &config.sui_connector_config;
+    let sui_client = match tokio::time::timeout(
+        SUI_CONNECTION_TIMEOUT,
+        SuiClient::new(
+            &sui_connector_config.sui_rpc_urls(),
+            SuiClientMetrics::new(&Registry::new()),
+            sui_connector_config.ika_package_id,
+            sui_connector_config.ika_common_package_id,
+            sui_connector_config.ika_dwallet_2pc_mpc_package_id,
+            sui_connector_config.ika_system_package_id,
+            sui_connector_config.ika_system_object_id,
+            sui_connector_config.ika_dwallet_coordinator_object_id,
+        ),
+    )
+    .await
+    {
+        Ok(Ok(sui_client)) => {
+            report.ok(
+                "sui-connection",
+                format!(
+                    "connected to {}",
+                    sui_connector_config.sui_rpc_urls().join(", ")
+                ),
+            );
+            sui_client
+        }
+        Ok(Err(err)) => {
+            report.fail("sui-connection", format!("{err:#}"));
+            return;
+        }
+        Err(_) => {
+            report.fail(
+                "sui-connection",
+                format!("timed out after {SUI_CONNECTION_TIMEOUT:?}"),
+            );
+            return;
+        }
+    };
+
+    match sui_client.get_chain_identifier().await {
+        Ok(chain_identifier) => {
+            let expected = match sui_connector_config.sui_chain_identifier {
+                SuiChainIdentifier::Mainnet => Some(get_mainnet_chain_identifier().to_string()),
+                SuiChainIdentifier::Testnet => Some(get_testnet_chain_identifier().to_string()),
+                SuiChainIdentifier::Custom => None,
+            };
+            match expected {
+                Some(expected) if expected != chain_identifier => report.fail(
+                    "sui-chain-identifier",
+                    format!(
+                        "expected the sui chain {} ({expected}), but connected to {chain_identifier}",
+                        sui_connector_config.sui_chain_identifier
+                    ),
+                ),
+                _ => report.ok(
+                    "sui-chain-identifier",
+                    format!(
+                        "{chain_identifier} ({})",
+                        sui_connector_config.sui_chain_identifier
+                    ),
+                ),
+            }
+        }
+        Err(err) => report.fail("sui-chain-identifier", err.to_string()),
+    }
+
+    match sui_client.get_available_move_packages().await {
+        Ok(packages) if !packages.is_empty() => report.ok(
+            "ika-packages",
+            format!("found {} versions of the ika packages", packages.len()),
+        ),
+        Ok(_) => report.fail(
+            "ika-packages",
+            "no versions of the ika packages were found, check the package IDs",
+        ),
+        Err(err) => report.fail("ika-packages", err.to_string()),
+    }
+
+    match sui_client.get_dwallet_coordinator_inner().await {
+        Ok(_) => report.ok(
+            "dwallet-coordinator",
+            sui_connector_config
+                .ika_dwallet_coordinator_object_id
+                .to_string(),
+        ),
+        Err(err) => report.fail("dwallet-coordinator", err.to_string()),
+    }
+
+    let system_inner = match sui_client.get_system_inner().await {
+        Ok(system_inner) => {
+            report.ok(
+                "ika-system",
+                sui_connector_config.ika_system_object_id.to_string(),
+            );
+            system_inner
+        }
+        Err(err) => {
+            report.fail("ika-system", err.to_string());
+            return;
+        }
+    };
+
+    if !is_validator {
+        report.skip("validator-info", "not a validator");
+        return;
+    }
+    if !keys_readable {
+        report.skip("validator-info", "the keys of the node can't be loaded");
+        return;
+    }
+
+    let epoch_start_system = match sui_client.get_epoch_start_system(&system_inner).await {
+        Ok(epoch_start_system) => epoch_start_system,
+        Err(err) => {
+            report.fail("validator-info", err.to_string());
+            return;
+        }
+    };
+    let authority_name = config.protocol_public_key();
+    let Some(onchain_validator) = epoch_start_system
+        .get_ika_validators()
+        .into_iter()
+        .find(|validator| validator.authority_name() == authority_name)
+    else {
+        report.warn(
+            "validator-info",
+            format!(
+                "{authority_name} is not in the active committee of epoch {}, \
+                 the keys can't be compared with its on-chain info",
+                epoch_start_system.epoch()
+            ),
+        );
+        return;
+    };
+    report.ok(
+        "validator-info",
+        format!(
+            "{} is in the active committee of epoch {}",
+            onchain_validator.get_name(),
+            epoch_start_system.epoch()
+        ),
+    );
+
+    for (name, local, onchain) in [
+        (
+            "onchain-network-key",
+            config.network_key_pair().public(),
+            onchain_validator.get_network_pubkey(),
+        ),
+        (
+            "onchain-consensus-key",
+            config.consensus_key_pair().public(),
+            onchain_validator.get_consensus_pubkey(),
+        ),
+    ] {
+        if *local == onchain {
+            report.ok(name, "matches the on-chain validator info");
+        } else {
+            report.fail(name, "doesn't match the on-chain validator info");
+        }
+    }
+
+    // The root seeds were loaded by the key files check.
+    let Some(root_seed) = config
+        .root_seed_key_pair
+        .as_ref()
+        .and_then(|root_seed| root_seed.try_root_seed().ok())
+    else {
+        return;
+    };
+    let next_root_seed = config
+        .next_root_seed_key_pair
+        .as_ref()
+        .and_then(|root_seed| root_seed.try_root_seed().ok());
+    match DWalletMPCService::verify_class_groups_key(&onchain_validator, root_seed) {
+        Ok(()) => report.ok(
+            "onchain-class-groups-key",
+            "the key derived from the root seed matches the on-chain MPC data",
+        ),
+        Err(err) => match next_root_seed.map(|next_root_seed| {
+            DWalletMPCService::verify_class_groups_key(&onchain_validator, next_root_seed)
+        }) {
+            Some(Ok(())) => report.warn(
+                "onchain-class-groups-key",
+                "the key derived from the next root seed matches the on-chain MPC data, \
+                 complete the class-groups key rotation by retiring the previous root seed",
+            ),
+            _ => report.fail("onchain-class-groups-key", err.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::secp256k1::Secp256k1KeyPair;
+    use ika_sui_client::mock_signer_server::MockSignerServer;
+    use ika_types::crypto::random_committee_key_pairs_of_size;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::crypto::{AccountKeyPair, get_key_pair};
+
+    fn ed25519_key_pair() -> SuiKeyPair {
+        SuiKeyPair::Ed25519(get_key_pair::<AccountKeyPair>().1)
+    }
+
+    fn ed25519_key() -> String {
+        ed25519_key_pair().encode_base64()
+    }
+
+    fn secp256k1_key() -> String {
+        SuiKeyPair::Secp256k1(get_key_pair::<Secp256k1KeyPair>().1).encode_base64()
+    }
+
+    fn protocol_key() -> String {
+        random_committee_key_pairs_of_size(1)
+            .pop()
+            .unwrap()
+            .encode_base64()
+    }
+
+    /// Writes a node config whose keys are read from the given files,
+    /// and loads it the way [`check_config`] does.
+    fn load_config(
+        directory: &Path,
+        key_files: &[(&str, Option<String>)],
+    ) -> (NodeConfig, KeyFilePaths) {
+        let mut key_pairs = String::new();
+        for (key_pair, contents) in key_files {
+            let path = directory.join(format!("{key_pair}.key"));
+            if let Some(contents) = contents {
+                std::fs::write(&path, contents).unwrap();
+            }
+            key_pairs.push_str(&format!("{key_pair}:\n  path: {}\n", path.display()));
+        }
+        let object_id = |byte| ObjectID::from_single_byte(byte).to_string();
+        let config = format!(
+            "db-path: {}
+sui-connector-config:
+  sui-chain-identifier: custom
+  ika-package-id: {}
+  ika-common-package-id: {}
+  ika-dwallet-2pc-mpc-package-id: {}
+  ika-system-package-id: {}
+  ika-system-object-id: {}
+  ika-dwallet-coordinator-object-id: {}
+{key_pairs}",
+            directory.join("db").display(),
+            object_id(1),
+            object_id(2),
+            object_id(3),
+            object_id(4),
+            object_id(5),
+            object_id(6),
+        );
+        let config_path = directory.join("ika-node.yaml");
+        std::fs::write(&config_path, config).unwrap();
+        (
+            NodeConfig::load(&config_path).unwrap(),
+            KeyFilePaths::load(&config_path).unwrap(),
+        )
+    }
+
+    fn status(report: &PreflightReport, name: &str) -> CheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap_or_else(|| panic!("no {name} check"))
+            .status
+    }
+
+    fn detail<'a>(report: &'a PreflightReport, name: &str) -> &'a str {
+        &report
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap()
+            .detail
+    }
+
+    #[test]
+    fn test_report_display() {
+        let mut report = PreflightReport::default();
+        report.ok("config", "loaded ika-node.yaml");
+        report.warn("disk-space", "50 GiB available in /opt/ika");
+        report.skip("root-seed", "not set, not a validator");
+        assert!(report.passed());
+
+        report.fail("p2p-port", "can't listen on udp 0.0.0.0:8084");
+        assert!(!report.passed());
+        assert_eq!(
+            report.to_string(),
+            "[OK  ] config      loaded ika-node.yaml
+[WARN] disk-space  50 GiB available in /opt/ika
+[SKIP] root-seed   not set, not a validator
+[FAIL] p2p-port    can't listen on udp 0.0.0.0:8084
+4 checks: 1 ok, 1 warnings, 1 failed, 1 skipped"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_config_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let report = check_config(&directory.path().join("ika-node.yaml")).await;
+        assert!(!report.passed());
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(status(&report, "config"), CheckStatus::Failed);
+    }
+
+    #[test]
+    fn test_check_key_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let (config, key_file_paths) = load_config(
+            directory.path(),
+            &[
+                ("protocol-key-pair", Some(protocol_key())),
+                ("consensus-key-pair", Some(ed25519_key())),
+                ("network-key-pair", Some(ed25519_key())),
+                // Account keys aren't limited to Ed25519.
+                ("account-key-pair", Some(secp256k1_key())),
+            ],
+        );
+        let mut report = PreflightReport::default();
+        assert!(check_key_files(
+            &mut report,
+            &config,
+            &key_file_paths,
+            false
+        ));
+        assert!(report.passed());
+        for name in [
+            "protocol-key",
+            "consensus-key",
+            "network-key",
+            "account-key",
+        ] {
+            assert_eq!(status(&report, name), CheckStatus::Ok, "{name}");
+        }
+        assert_eq!(status(&report, "notifier-key"), CheckStatus::Skipped);
+        assert_eq!(status(&report, "root-seed"), CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn test_check_key_files_reports_unreadable_keys() {
+        let directory = tempfile::tempdir().unwrap();
+        let (config, key_file_paths) = load_config(
+            directory.path(),
+            &[
+                // An Ed25519 key is not a protocol key.
+                ("protocol-key-pair", Some(ed25519_key())),
+                ("consensus-key-pair", None),
+                ("network-key-pair", Some(secp256k1_key())),
+                ("account-key-pair", Some("not a key".to_string())),
+            ],
+        );
+        let mut report = PreflightReport::default();
+        assert!(!check_key_files(
+            &mut report,
+            &config,
+            &key_file_paths,
+            false
+        ));
+        assert!(!report.passed());
+
+        assert_eq!(status(&report, "protocol-key"), CheckStatus::Failed);
+        assert!(detail(&report, "protocol-key").starts_with("invalid key file"));
+        assert_eq!(status(&report, "consensus-key"), CheckStatus::Failed);
+        assert!(detail(&report, "consensus-key").starts_with("failed to read"));
+        assert_eq!(status(&report, "network-key"), CheckStatus::Failed);
+        assert!(detail(&report, "network-key").ends_with("is not an Ed25519 key pair"));
+        assert_eq!(status(&report, "account-key"), CheckStatus::Failed);
+        assert!(detail(&report, "account-key").starts_with("invalid key file"));
+    }
+
+    #[test]
+    fn test_check_key_files_requires_validator_keys() {
+        let directory = tempfile::tempdir().unwrap();
+        let (config, key_file_paths) = load_config(directory.path(), &[]);
+
+        let mut report = PreflightReport::default();
+        assert!(check_key_files(
+            &mut report,
+            &config,
+            &key_file_paths,
+            false
+        ));
+        assert_eq!(status(&report, "protocol-key"), CheckStatus::Skipped);
+
+        let mut report = PreflightReport::default();
+        assert!(!check_key_files(
+            &mut report,
+            &config,
+            &key_file_paths,
+            true
+        ));
+        for name in [
+            "protocol-key",
+            "consensus-key",
+            "network-key",
+            "account-key",
+            "root-seed",
+        ] {
+            assert_eq!(status(&report, name), CheckStatus::Failed, "{name}");
+        }
+        assert_eq!(status(&report, "notifier-key"), CheckStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_check_notifier_remote_signer() {
+        let directory = tempfile::tempdir().unwrap();
+        let (mut config, key_file_paths) = load_config(directory.path(), &[]);
+        let server = MockSignerServer::start("notifier", ed25519_key_pair(), None).await;
+        config.sui_connector_config.notifier_remote_signer = Some(server.config());
+
+        let mut report = PreflightReport::default();
+        check_key_files(&mut report, &config, &key_file_paths, false);
+        assert_eq!(status(&report, "notifier-key"), CheckStatus::Ok);
+        check_notifier_remote_signer(&mut report, &config).await;
+        assert_eq!(status(&report, "notifier-remote-signer"), CheckStatus::Ok);
+
+        let mut wrong_address = server.config();
+        wrong_address.address = Some(SuiAddress::from(&ed25519_key_pair().public()));
+        config.sui_connector_config.notifier_remote_signer = Some(wrong_address);
+        let mut report = PreflightReport::default();
+        check_notifier_remote_signer(&mut report, &config).await;
+        assert_eq!(
+            status(&report, "notifier-remote-signer"),
+            CheckStatus::Failed
+        );
+
+        let mut no_address = server.config();
+        no_address.address = None;
+        config.sui_connector_config.notifier_remote_signer = Some(no_address);
+        let mut report = PreflightReport::default();
+        check_notifier_remote_signer(&mut report, &config).await;
+        assert_eq!(
+            status(&report, "notifier-remote-signer"),
+            CheckStatus::Warning
+        );
+
+        drop(server);
+        let mut report = PreflightReport::default();
+        check_notifier_remote_signer(&mut report, &config).await;
+        assert_eq!(
+            status(&report, "notifier-remote-signer"),
+            CheckStatus::Failed
+        );
+    }
+}
@@ -0,0 +1,699 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Snapshots of the node databases, taken through the admin `/snapshot` route of a running
+//! node or with the `ika-node snapshot` command of a stopped one, and restored with the
+//! `ika-node restore` command.
+//!
+//! A snapshot is a gzipped tarball of consistent RocksDB checkpoints of the node databases,
+//! with a `manifest.json` that records the chain and epoch they belong to and the SHA-256 of
+//! every file. It lets an operator move a validator to a new machine, or recover from a corrupted
+//! disk, without replaying the chain from genesis.
+//!
+//! The consensus database isn't included, consensus catches up from the other validators.
+
+use anyhow::{Context, anyhow, bail};
+use fastcrypto::hash::{HashFunction, Sha256};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use ika_config::NodeConfig;
+use ika_core::authority::authority_per_epoch_store::AuthorityEpochTables;
+use ika_core::authority::authority_perpetual_tables::AuthorityPerpetualTables;
+use ika_core::dwallet_checkpoints::DWalletCheckpointStore;
+use ika_core::epoch::committee_store::CommitteeStore;
+use ika_core::system_checkpoints::SystemCheckpointStore;
+use ika_sui_client::SuiClient;
+use ika_sui_client::metrics::SuiClientMetrics;
+use ika_types::digests::ChainIdentifier;
+use ika_types::sui::SystemInnerTrait;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_types::committee::EpochId;
+use tempfile::TempDir;
+
+const SNAPSHOT_VERSION: u32 = 1;
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// The directory of the snapshot tarball that holds the databases, laid out as in `db_path()`.
+const SNAPSHOT_DB_DIR: &str = "db";
+
+/// How long to wait for the Sui fullnodes to answer when validating a restore.
+const SUI_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub node_version: String,
+    pub chain_identifier: ChainIdentifier,
+    /// The epoch the node was in when the snapshot was taken.
+    pub epoch: EpochId,
+    pub created_at_ms: u64,
+    /// The files of the snapshot, by their path relative to the databases directory.
+    pub files: BTreeMap<String, SnapshotFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SnapshotFile {
+    pub size: u64,
+    /// The hex encoded SHA-256 of the file.
+    pub sha256: String,
+}
+
+impl SnapshotManifest {
+    pub fn total_size(&self) -> u64 {
+        self.files.values().map(|file| file.size).sum()
+    }
+}
+
+impl fmt::Display for SnapshotManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Chain identifier: {}", self.chain_identifier)?;
+        writeln!(f, "Epoch: {}", self.epoch)?;
+        writeln!(f, "Node version: {}", self.node_version)?;
+        write!(
+            f,
+            "Files: {} ({} bytes)",
+            self.files.len(),
+            self.total_size()
+        )
+    }
+}
+
+/// The result of a successful restore.
+#[derive(Clone, Debug)]
+pub struct RestoredSnapshot {
+    pub manifest: SnapshotManifest,
+    pub onchain_epoch: EpochId,
+}
+
+impl fmt::Display for RestoredSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.manifest)?;
+        write!(f, "On-chain epoch: {}", self.onchain_epoch)?;
+        if self.manifest.epoch < self.onchain_epoch {
+            write!(
+                f,
+                "\nThe snapshot is {} epoch(s) behind the chain, \
+                 the node will catch up when it starts",
+                self.onchain_epoch - self.manifest.epoch
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The open databases of a running node, which a snapshot is taken from.
+pub struct NodeDatabases {
+    pub perpetual_tables: Arc<AuthorityPerpetualTables>,
+    /// The current epoch of the node.
+    pub epoch: EpochId,
+    pub epoch_tables: Arc<AuthorityEpochTables>,
+    pub committee_store: Arc<CommitteeStore>,
+    pub dwallet_checkpoint_store: Arc<DWalletCheckpointStore>,
+    pub system_checkpoint_store: Arc<SystemCheckpointStore>,
+}
+
+impl NodeDatabases {
+    /// Opens the databases of a stopped node under `db_path`, at the epoch it stopped in.
+    pub fn open(db_path: &Path) -> anyhow::Result<Self> {
+        let store_path = db_path.join("store");
+        if !AuthorityPerpetualTables::path(&store_path).is_dir() {
+            bail!("{} holds no node databases", db_path.display());
+        }
+        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(&store_path, None));
+        let epoch = perpetual_tables.get_recovery_epoch_at_restart()?;
+        Ok(Self::open_at_epoch(db_path, perpetual_tables, epoch))
+    }
+
+    /// Opens the databases under `db_path`, where `IkaNode` opens them.
+    fn open_at_epoch(
+        db_path: &Path,
+        perpetual_tables: Arc<AuthorityPerpetualTables>,
+        epoch: EpochId,
+    ) -> Self {
+        Self {
+            epoch_tables: Arc::new(AuthorityEpochTables::open(
+                epoch,
+                &db_path.join("store"),
+                None,
+            )),
+            perpetual_tables,
+            epoch,
+            committee_store: Arc::new(CommitteeStore::new(db_path.join("epochs"), None)),
+            dwallet_checkpoint_store: DWalletCheckpointStore::new(
+                &db_path.join("dwallet_checkpoints"),
+            ),
+            system_checkpoint_store: SystemCheckpointStore::new(
+                &db_path.join("system_checkpoints"),
+            ),
+        }
+    }
+}
+
+/// Writes a snapshot of `databases` to `output_path`.
+///
+/// The snapshot is made of RocksDB checkpoints of the open databases, so the node doesn't have to
+/// stop. This blocks on disk IO.
+pub fn create_snapshot(
+    databases: &NodeDatabases,
+    chain_identifier: ChainIdentifier,
+    output_path: &Path,
+    node_version: &str,
+) -> anyhow::Result<SnapshotManifest> {
+    if output_path.exists() {
+        bail!("{} already exists", output_path.display());
+    }
+
+    let output_dir = parent_dir(output_path);
+    let staging = tempfile::Builder::new()
+        .prefix(".ika-snapshot-")
+        .tempdir_in(output_dir)
+        .with_context(|| format!("failed to create a staging directory in {output_dir:?}"))?;
+    let staged_db_path = staging.path().join(SNAPSHOT_DB_DIR);
+    let staged_store_path = staged_db_path.join("store");
+    std::fs::create_dir_all(&staged_store_path)?;
+
+    // RocksDB checkpoints are consistent point-in-time copies, hard linked where possible.
+    let epoch = databases.epoch;
+    databases
+        .epoch_tables
+        .checkpoint_db(&AuthorityEpochTables::path(epoch, &staged_store_path))?;
+    databases
+        .perpetual_tables
+        .checkpoint_db(&AuthorityPerpetualTables::path(&staged_store_path))?;
+    // A reconfiguration during the checkpoints would pair the perpetual tables of the next epoch
+    // with the epoch tables of this one.
+    let perpetual_tables_epoch = databases.perpetual_tables.get_recovery_epoch_at_restart()?;
+    if perpetual_tables_epoch != epoch {
+        bail!(
+            "the node moved from epoch {epoch} to epoch {perpetual_tables_epoch} \
+             while taking the snapshot, try again"
+        );
+    }
+    databases
+        .committee_store
+        .checkpoint_db(&staged_db_path.join("epochs"))?;
+    databases
+        .dwallet_checkpoint_store
+        .checkpoint_db(&staged_db_path.join("dwallet_checkpoints"))?;
+    databases
+        .system_checkpoint_store
+        .checkpoint_db(&staged_db_path.join("system_checkpoints"))?;
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        node_version: node_version.to_string(),
+        chain_identifier,
+        epoch,
+        created_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system time is before the unix epoch")
+            .as_millis() as u64,
+        files: hash_files(&staged_db_path)?,
+    };
+    let manifest_path = staging.path().join(MANIFEST_FILE_NAME);
+    serde_json::to_writer_pretty(File::create(&manifest_path)?, &manifest)?;
+
+    // Write to a temporary file first, so a failed snapshot never leaves a partial tarball.
+    let tarball = tempfile::Builder::new()
+        .prefix(".ika-snapshot-")
+        .tempfile_in(output_dir)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(
+        BufWriter::new(tarball.as_file()),
+        Compression::default(),
+    ));
+    builder.append_path_with_name(&manifest_path, MANIFEST_FILE_NAME)?;
+    builder.append_dir_all(SNAPSHOT_DB_DIR, &staged_db_path)?;
+    builder
+        .into_inner()?
+        .finish()?
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    tarball
+        .persist_noclobber(output_path)
+        .map_err(|e| anyhow!("failed to write {}: {}", output_path.display(), e.error))?;
+
+    Ok(manifest)
+}
+
+/// Writes a snapshot of the databases of the stopped node of `config` to `output_path`.
+///
+/// A running node holds its databases open, its snapshot is taken through the admin
+/// `/snapshot` route instead.
+pub fn create_snapshot_of_stopped_node(
+    config: &NodeConfig,
+    output_path: &Path,
+    node_version: &str,
+) -> anyhow::Result<SnapshotManifest> {
+    let databases = NodeDatabases::open(&config.db_path())?;
+    let chain_identifier = ChainIdentifier::from(config.sui_connector_config.ika_system_object_id);
+    create_snapshot(&databases, chain_identifier, output_path, node_version)
+}
+
+/// Restores the databases of the node from the snapshot at `snapshot_path`.
+///
+/// The databases of the node must not exist yet. The snapshot is verified against its manifest,
+/// the chain of the node config and the current on-chain epoch before it's moved in place.
+pub async fn restore_snapshot(
+    config: &NodeConfig,
+    snapshot_path: &Path,
+) -> anyhow::Result<RestoredSnapshot> {
+    let db_path = config.db_path();
+    let chain_identifier = ChainIdentifier::from(config.sui_connector_config.ika_system_object_id);
+    let staged = StagedSnapshot::extract(&db_path, chain_identifier, snapshot_path)?;
+    let onchain_epoch = get_onchain_epoch(config).await?;
+    if staged.manifest.epoch > onchain_epoch {
+        bail!(
+            "the snapshot is at epoch {}, ahead of the on-chain epoch {onchain_epoch}",
+            staged.manifest.epoch
+        );
+    }
+    let manifest = staged.install(&db_path)?;
+
+    Ok(RestoredSnapshot {
+        manifest,
+        onchain_epoch,
+    })
+}
+
+/// A snapshot extracted and verified next to the databases of the node, not yet in place.
+struct StagedSnapshot {
+    staging: TempDir,
+    manifest: SnapshotManifest,
+}
+
+impl StagedSnapshot {
+    /// Extracts the snapshot at `snapshot_path` next to `db_path`, and verifies it against its
+    /// manifest and `chain_identifier`.
+    fn extract(
+        db_path: &Path,
+        chain_identifier: ChainIdentifier,
+        snapshot_path: &Path,
+    ) -> anyhow::Result<Self> {
+        if db_path.exists() && std::fs::read_dir(db_path)?.next().is_some() {
+            bail!(
+                "{} is not empty, move the existing databases away before restoring",
+                db_path.display()
+            );
+        }
+
+        let staging_dir = parent_dir(db_path);
+        std::fs::create_dir_all(staging_dir)?;
+        let staging = tempfile::Builder::new()
+            .prefix(".ika-restore-")
+            .tempdir_in(staging_dir)
+            .with_context(|| format!("failed to create a staging directory in {staging_dir:?}"))?;
+        let snapshot = File::open(snapshot_path)
+            .with_context(|| format!("failed to open {}", snapshot_path.display()))?;
+        tar::Archive::new(GzDecoder::new(BufReader::new(snapshot)))
+            .unpack(staging.path())
+            .context("failed to extract the snapshot")?;
+
+        let manifest: SnapshotManifest = serde_json::from_reader(BufReader::new(
+            File::open(staging.path().join(MANIFEST_FILE_NAME))
+                .context("the snapshot has no manifest")?,
+        ))
+        .context("failed to parse the snapshot manifest")?;
+        if manifest.version != SNAPSHOT_VERSION {
+            bail!("unsupported snapshot version {}", manifest.version);
+        }
+        if manifest.chain_identifier != chain_identifier {
+            bail!(
+                "the snapshot is of chain {}, but the node is configured for chain {}",
+                manifest.chain_identifier,
+                chain_identifier
+            );
+        }
+
+        let staged_db_path = staging.path().join(SNAPSHOT_DB_DIR);
+        verify_files(&manifest, &hash_files(&staged_db_path)?)?;
+
+        let staged_store_path = staged_db_path.join("store");
+        if !AuthorityPerpetualTables::path(&staged_store_path).exists() {
+            bail!("the snapshot has no perpetual tables");
+        }
+        let snapshot_epoch = AuthorityPerpetualTables::open(&staged_store_path, None)
+            .get_recovery_epoch_at_restart()?;
+        if snapshot_epoch != manifest.epoch {
+            bail!(
+                "the snapshot databases are at epoch {snapshot_epoch}, \
+                 but its manifest says epoch {}",
+                manifest.epoch
+            );
+        }
+
+        Ok(Self { staging, manifest })
+    }
+
+    /// Moves the databases of the snapshot to `db_path`.
+    fn install(self, db_path: &Path) -> anyhow::Result<SnapshotManifest> {
+        let staged_db_path = self.staging.path().join(SNAPSHOT_DB_DIR);
+        if db_path.exists() {
+            std::fs::remove_dir(db_path)?;
+        }
+        std::fs::rename(&staged_db_path, db_path).with_context(|| {
+            format!(
+                "failed to move the restored databases to {}",
+                db_path.display()
+            )
+        })?;
+        Ok(self.manifest)
+    }
+}
+
+async fn get_onchain_epoch(config: &NodeConfig) -> anyhow::Result<EpochId> {
+    let sui_connector_config = &config.sui_connector_config;
+    let sui_client = tokio::time::timeout(
+        SUI_CONNECTION_TIMEOUT,
+        SuiClient::new(
+            &sui_connector_config.sui_rpc_urls(),
+            SuiClientMetrics::new(&Registry::new()),
+            sui_connector_config.ika_package_id,
+            sui_connector_config.ika_common_package_id,
+            sui_connector_config.ika_dwallet_2pc_mpc_package_id,
+            sui_connector_config.ika_system_package_id,
+            sui_connector_config.ika_system_object_id,
+            sui_connector_config.ika_dwallet_coordinator_object_id,
+        ),
+    )
+    .await
+    .map_err(|_| anyhow!("timed out connecting to Sui after {SUI_CONNECTION_TIMEOUT:?}"))??;
+    let system_inner = sui_client
+        .get_system_inner()
+        .await
+        .context("failed to read the Ika system object")?;
+    Ok(system_inner.epoch())
+}
+
+fn verify_files(
+    manifest: &SnapshotManifest,
+    files: &BTreeMap<String, SnapshotFile>,
+) -> anyhow::Result<()> {
+    for (path, expected) in &manifest.files {
+        match files.get(path) {
+            None => bail!("the snapshot is missing {path}"),
+            Some(file) if file != expected => bail!("{path} doesn't match the snapshot manifest"),
+            Some(_) => {}
+        }
+    }
+    if let Some(path) = files
+        .keys()
+        .find(|path| !manifest.files.contains_key(*path))
+    {
+        bail!("{path} is not in the snapshot manifest");
+    }
+    Ok(())
+}
+
+/// The size and SHA-256 of every file under `root`, by their `/` separated relative path.
+fn hash_files(root: &Path) -> anyhow::Result<BTreeMap<String, SnapshotFile>> {
+    let mut files = BTreeMap::new();
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            let relative_path = path
+                .strip_prefix(root)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(relative_path, hash_file(&path)?);
+        }
+    }
+    Ok(files)
+}
+
+fn hash_file(path: &Path) -> anyhow::Result<SnapshotFile> {
+    let mut file = File::open(path).with_context(|| format!("failed to open {path:?}"))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    let mut size = 0;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok(SnapshotFile {
+        size,
+        sha256: hex::encode(hasher.finalize().digest),
+    })
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ika_core::authority::epoch_start_configuration::EpochStartConfiguration;
+    use ika_types::committee::Committee;
+    use ika_types::messages_dwallet_mpc::{SessionIdentifier, SessionType};
+    use ika_types::sui::epoch_start_system::EpochStartSystem;
+    use sui_types::base_types::ObjectID;
+    use sui_types::digests::TransactionDigest;
+    use sui_types::event::EventID;
+
+    /// Opens the node databases under `db_path` at `epoch`, which they don't record yet.
+    fn open_databases(db_path: &Path, epoch: EpochId) -> NodeDatabases {
+        let perpetual_tables =
+            Arc::new(AuthorityPerpetualTables::open(&db_path.join("store"), None));
+        NodeDatabases::open_at_epoch(db_path, perpetual_tables, epoch)
+    }
+
+    #[test]
+    fn test_create_and_restore_snapshot() {
+        let epoch = 3;
+        let chain_identifier = ChainIdentifier::default();
+        let directory = tempfile::tempdir().unwrap();
+        let databases = open_databases(&directory.path().join("node/live"), epoch);
+
+        let perpetual_tables = &databases.perpetual_tables;
+        perpetual_tables
+            .set_epoch_start_configuration(
+                &EpochStartConfiguration::new(EpochStartSystem::new_for_testing_with_epoch(epoch))
+                    .unwrap(),
+            )
+            .unwrap();
+        let completed_session = SessionIdentifier::new(SessionType::User, [1; 32]);
+        let other_session = SessionIdentifier::new(SessionType::User, [2; 32]);
+        perpetual_tables
+            .insert_dwallet_mpc_computation_completed_sessions(&[completed_session])
+            .unwrap();
+        let cursor = EventID::from((TransactionDigest::new([3; 32]), 0));
+        perpetual_tables
+            .set_sui_event_cursor("coordinator", epoch, cursor)
+            .unwrap();
+        perpetual_tables
+            .set_highest_pruned_checkpoint_without_wb(5)
+            .unwrap();
+        let (committee, _) = Committee::new_simple_test_committee();
+        databases
+            .committee_store
+            .init_genesis_committee(committee.clone())
+            .unwrap();
+
+        // The snapshot is taken while the databases are open.
+        let snapshot_path = directory.path().join("snapshot.tar.gz");
+        let manifest =
+            create_snapshot(&databases, chain_identifier, &snapshot_path, "test").unwrap();
+        assert_eq!(manifest.epoch, epoch);
+        assert!(
+            manifest
+                .files
+                .keys()
+                .any(|path| path.starts_with("store/perpetual/"))
+        );
+        assert!(
+            create_snapshot(&databases, chain_identifier, &snapshot_path, "test").is_err(),
+            "an existing snapshot must not be overwritten"
+        );
+
+        let restored_db_path = directory.path().join("restored/live");
+        let staged =
+            StagedSnapshot::extract(&restored_db_path, chain_identifier, &snapshot_path).unwrap();
+        assert_eq!(
+            staged.install(&restored_db_path).unwrap().files,
+            manifest.files
+        );
+        assert!(
+            StagedSnapshot::extract(&restored_db_path, chain_identifier, &snapshot_path).is_err(),
+            "a snapshot must not be restored over existing databases"
+        );
+
+        let restored_store_path = restored_db_path.join("store");
+        for path in [
+            AuthorityPerpetualTables::path(&restored_store_path),
+            AuthorityEpochTables::path(epoch, &restored_store_path),
+            restored_db_path.join("epochs"),
+            restored_db_path.join("dwallet_checkpoints"),
+            restored_db_path.join("system_checkpoints"),
+        ] {
+            assert!(path.is_dir(), "{path:?} was not restored");
+        }
+        let restored = open_databases(&restored_db_path, epoch);
+        let restored_perpetual_tables = &restored.perpetual_tables;
+        assert_eq!(
+            restored_perpetual_tables
+                .get_recovery_epoch_at_restart()
+                .unwrap(),
+            epoch
+        );
+        assert_eq!(
+            restored_perpetual_tables
+                .get_dwallet_mpc_sessions_completed_status(vec![completed_session, other_session])
+                .unwrap(),
+            perpetual_tables
+                .get_dwallet_mpc_sessions_completed_status(vec![completed_session, other_session])
+                .unwrap()
+        );
+        assert_eq!(
+            restored_perpetual_tables
+                .get_sui_event_cursor("coordinator")
+                .unwrap(),
+            Some((epoch, cursor))
+        );
+        assert_eq!(
+            restored_perpetual_tables
+                .get_highest_pruned_checkpoint()
+                .unwrap(),
+            5
+        );
+        assert_eq!(
+            restored
+                .committee_store
+                .get_committee(&committee.epoch)
+                .unwrap()
+                .as_deref(),
+            Some(&committee)
+        );
+    }
+
+    #[test]
+    fn test_create_snapshot_of_stopped_node() {
+        let epoch = 2;
+        let chain_identifier = ChainIdentifier::default();
+        let directory = tempfile::tempdir().unwrap();
+        let db_path = directory.path().join("node/live");
+        assert!(NodeDatabases::open(&db_path).is_err());
+
+        let databases = open_databases(&db_path, epoch);
+        databases
+            .perpetual_tables
+            .set_epoch_start_configuration(
+                &EpochStartConfiguration::new(EpochStartSystem::new_for_testing_with_epoch(epoch))
+                    .unwrap(),
+            )
+            .unwrap();
+        let cursor = EventID::from((TransactionDigest::new([4; 32]), 1));
+        databases
+            .perpetual_tables
+            .set_sui_event_cursor("system", epoch, cursor)
+            .unwrap();
+        drop(databases);
+
+        // The stopped node's databases are reopened at the epoch it stopped in.
+        let databases = NodeDatabases::open(&db_path).unwrap();
+        assert_eq!(databases.epoch, epoch);
+        let snapshot_path = directory.path().join("snapshot.tar.gz");
+        let manifest =
+            create_snapshot(&databases, chain_identifier, &snapshot_path, "test").unwrap();
+        assert_eq!(manifest.epoch, epoch);
+
+        let restored_db_path = directory.path().join("restored/live");
+        StagedSnapshot::extract(&restored_db_path, chain_identifier, &snapshot_path)
+            .unwrap()
+            .install(&restored_db_path)
+            .unwrap();
+        let restored = NodeDatabases::open(&restored_db_path).unwrap();
+        assert_eq!(restored.epoch, epoch);
+        assert_eq!(
+            restored
+                .perpetual_tables
+                .get_sui_event_cursor("system")
+                .unwrap(),
+            Some((epoch, cursor))
+        );
+    }
+
+    #[test]
+    fn test_restore_snapshot_of_another_chain() {
+        let epoch = 1;
+        let directory = tempfile::tempdir().unwrap();
+        let databases = open_databases(&directory.path().join("node/live"), epoch);
+        databases
+            .perpetual_tables
+            .set_epoch_start_configuration(
+                &EpochStartConfiguration::new(EpochStartSystem::new_for_testing_with_epoch(epoch))
+                    .unwrap(),
+            )
+            .unwrap();
+        let snapshot_path = directory.path().join("snapshot.tar.gz");
+        create_snapshot(
+            &databases,
+            ChainIdentifier::default(),
+            &snapshot_path,
+            "test",
+        )
+        .unwrap();
+
+        let other_chain = ChainIdentifier::from(ObjectID::from_single_byte(1));
+        let restored_db_path = directory.path().join("restored/live");
+        assert!(StagedSnapshot::extract(&restored_db_path, other_chain, &snapshot_path).is_err());
+        assert!(!restored_db_path.exists());
+    }
+
+    #[test]
+    fn test_verify_files() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(directory.path().join("store/perpetual")).unwrap();
+        std::fs::write(
+            directory.path().join("store/perpetual/CURRENT"),
+            b"MANIFEST",
+        )
+        .unwrap();
+        std::fs::write(directory.path().join("OPTIONS"), b"options").unwrap();
+        let files = hash_files(directory.path()).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["OPTIONS", "store/perpetual/CURRENT"]
+        );
+
+        let manifest = SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            node_version: "test".to_string(),
+            chain_identifier: ChainIdentifier::default(),
+            epoch: 0,
+            created_at_ms: 0,
+            files,
+        };
+        verify_files(&manifest, &hash_files(directory.path()).unwrap()).unwrap();
+
+        std::fs::write(directory.path().join("OPTIONS"), b"corrupted").unwrap();
+        assert!(verify_files(&manifest, &hash_files(directory.path()).unwrap()).is_err());
+        std::fs::write(directory.path().join("OPTIONS"), b"options").unwrap();
+        std::fs::write(directory.path().join("LOCK"), b"").unwrap();
+        assert!(verify_files(&manifest, &hash_files(directory.path()).unwrap()).is_err());
+    }
+}
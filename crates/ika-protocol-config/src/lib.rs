@@ -16,11 +16,13 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-const MAX_PROTOCOL_VERSION: u64 = 1;
+const MAX_PROTOCOL_VERSION: u64 = 3;
 
 // Record history of protocol version allocations here:
 //
 // Version 1: Original version.
+// Version 2: dWallet checkpoints commit to a Merkle root of their messages.
+// Version 3: Validators can vote on system parameter changes through consensus.
 
 #[derive(Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProtocolVersion(u64);
@@ -133,6 +135,19 @@ struct FeatureFlags {
     // If true, enforces checkpoint timestamps are non-decreasing.
     #[serde(skip_serializing_if = "is_false")]
     enforce_checkpoint_timestamp_monotonicity: bool,
+
+    // If true, dWallet checkpoints are built as V2 messages, which include a Merkle root over
+    // their messages, allowing inclusion proofs for individual messages. The last dWallet
+    // checkpoint of an epoch also commits to the next epoch committee, for light clients.
+    // Requires a coordinator that skips the V2 marker when parsing checkpoints, see
+    // `min_dwallet_coordinator_version`.
+    #[serde(skip_serializing_if = "is_false")]
+    dwallet_checkpoint_messages_merkle_root: bool,
+
+    // If true, validators may vote on system parameter changes through consensus,
+    // and changes that reach quorum are emitted in system checkpoints.
+    #[serde(skip_serializing_if = "is_false")]
+    system_parameter_governance: bool,
 }
 
 #[allow(unused)]
@@ -248,6 +263,12 @@ pub struct ProtocolConfig {
     consensus_gc_depth: Option<u32>,
     decryption_key_reconfiguration_third_round_delay: Option<u64>,
     network_dkg_third_round_delay: Option<u64>,
+
+    /// The lowest version of the on-chain `DWalletCoordinator` object that accepts the
+    /// checkpoints of this protocol version. Validators don't vote for a protocol version
+    /// before the coordinator is migrated to it, so the network never submits checkpoints
+    /// that the chain rejects. Unset means any coordinator version.
+    min_dwallet_coordinator_version: Option<u64>,
 }
 
 // feature flags
@@ -299,6 +320,14 @@ impl ProtocolConfig {
     pub fn consensus_zstd_compression(&self) -> bool {
         self.feature_flags.consensus_zstd_compression
     }
+
+    pub fn dwallet_checkpoint_messages_merkle_root(&self) -> bool {
+        self.feature_flags.dwallet_checkpoint_messages_merkle_root
+    }
+
+    pub fn system_parameter_governance(&self) -> bool {
+        self.feature_flags.system_parameter_governance
+    }
 }
 
 #[cfg(not(msim))]
@@ -463,6 +492,7 @@ impl ProtocolConfig {
             // The delay is measured in consensus rounds.
             decryption_key_reconfiguration_third_round_delay: Some(10),
             network_dkg_third_round_delay: Some(10),
+            min_dwallet_coordinator_version: None,
         };
 
         cfg.feature_flags.mysticeti_num_leaders_per_round = Some(1);
@@ -475,6 +505,14 @@ impl ProtocolConfig {
         for cur in 2..=version.0 {
             match cur {
                 1 => unreachable!(),
+                2 => {
+                    cfg.feature_flags.dwallet_checkpoint_messages_merkle_root = true;
+                    // V2 checkpoints are only parsed by coordinator version 2.
+                    cfg.min_dwallet_coordinator_version = Some(2);
+                }
+                3 => {
+                    cfg.feature_flags.system_parameter_governance = true;
+                }
                 // Use this template when making changes:
                 //
                 //     // modify an existing constant.
@@ -529,6 +567,14 @@ impl ProtocolConfig {
     pub fn set_enforce_checkpoint_timestamp_monotonicity_for_testing(&mut self, val: bool) {
         self.feature_flags.enforce_checkpoint_timestamp_monotonicity = val;
     }
+
+    pub fn set_dwallet_checkpoint_messages_merkle_root_for_testing(&mut self, val: bool) {
+        self.feature_flags.dwallet_checkpoint_messages_merkle_root = val;
+    }
+
+    pub fn set_system_parameter_governance_for_testing(&mut self, val: bool) {
+        self.feature_flags.system_parameter_governance = val;
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
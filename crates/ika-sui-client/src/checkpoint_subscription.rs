@@ -0,0 +1,41 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Subscribes to the checkpoints a Sui fullnode executes through its gRPC API,
+//! which the fullnode serves on the same address as its JSON-RPC API.
+
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use sui_rpc_api::proto::rpc::v2alpha::SubscribeCheckpointsRequest;
+use sui_rpc_api::proto::rpc::v2alpha::subscription_service_client::SubscriptionServiceClient;
+use sui_sdk::error::Error;
+
+/// Streams the sequence number of every checkpoint the fullnode at `url` executes from now on,
+/// in order. Fails if the fullnode doesn't serve the gRPC subscription service.
+pub(crate) async fn subscribe_checkpoints(
+    url: &str,
+) -> Result<BoxStream<'static, Result<u64, Error>>, Error> {
+    let mut client = SubscriptionServiceClient::connect(url.to_string())
+        .await
+        .map_err(|e| Error::DataError(format!("Can't connect to the gRPC API of {url}: {e}")))?;
+    // Only the cursor of each response, the sequence number of its checkpoint, is used.
+    let checkpoints = client
+        .subscribe_checkpoints(SubscribeCheckpointsRequest::default())
+        .await
+        .map_err(|status| {
+            Error::DataError(format!(
+                "Can't subscribe to the checkpoints of {url}: {status}"
+            ))
+        })?
+        .into_inner();
+    Ok(checkpoints
+        .map(|response| {
+            let response = response.map_err(|status| {
+                Error::DataError(format!("The checkpoint subscription failed: {status}"))
+            })?;
+            response.cursor.ok_or_else(|| {
+                Error::DataError("A subscribed checkpoint has no sequence number".to_string())
+            })
+        })
+        .boxed())
+}
@@ -1,3 +1,4 @@
+use crate::signer::{FileSigner, Signer, sign_transaction};
 use anyhow::{Error, bail};
 use dwallet_mpc_types::dwallet_mpc::VersionedMPCData;
 use fastcrypto::traits::ToFromBytes;
@@ -34,18 +35,16 @@ use move_core_types::ident_str;
 use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::{StructTag, TypeTag};
 use serde::Serialize;
-use shared_crypto::intent::Intent;
 use sui::client_commands::{SuiClientCommandResult, execute_dry_run};
 use sui::fire_drill::get_gas_obj_ref;
 use sui_json_rpc_types::{ObjectChange, SuiTransactionBlockResponse};
 use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
-use sui_keys::keystore::AccountKeystore;
 use sui_sdk::wallet_context::WalletContext;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::collection_types::Entry;
 use sui_types::object::Owner;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_types::transaction::{Argument, CallArg, ObjectArg, Transaction, TransactionKind};
+use sui_types::transaction::{Argument, CallArg, ObjectArg, TransactionKind};
 use sui_types::transaction::{Command, TransactionData};
 use sui_types::{MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_PACKAGE_ID};
 #[derive(Serialize)]
@@ -94,6 +93,7 @@ fn store_mcp_data_in_table_vec(
 /// Request to add a validator candidate transaction
 pub async fn request_add_validator_candidate(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     validator_initialization_metadata: &ValidatorInfo,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
@@ -208,7 +208,7 @@ pub async fn request_add_validator_candidate(
         ],
     );
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
     let Argument::Result(validator_caps_index) = validator_caps else {
         panic!("Failed to get validator caps index");
     };
@@ -224,7 +224,7 @@ pub async fn request_add_validator_candidate(
 
     let tx = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    let response = execute_transaction(context, tx).await?;
+    let response = execute_transaction_with_signer(context, signer, tx).await?;
 
     let object_changes = response
         .object_changes
@@ -324,6 +324,7 @@ pub async fn request_add_validator_candidate(
 
 pub async fn stake_ika(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     ika_supply_id: ObjectID,
@@ -349,7 +350,7 @@ pub async fn stake_ika(
     let validator = ptb.input(CallArg::Pure(bcs::to_bytes(&validator_id)?))?;
     let call_args = vec![stake, validator];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     let staked_ika = add_ika_system_command_to_ptb(
         context,
@@ -365,11 +366,12 @@ pub async fn stake_ika(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 pub async fn request_add_validator(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -386,7 +388,7 @@ pub async fn request_add_validator(
         validator_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -400,11 +402,12 @@ pub async fn request_add_validator(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 pub async fn request_remove_validator(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -423,6 +426,7 @@ pub async fn request_remove_validator(
 
     call_ika_system(
         context,
+        signer,
         REQUEST_REMOVE_VALIDATOR_FUNCTION_NAME,
         call_args,
         gas_budget,
@@ -436,6 +440,7 @@ pub async fn request_remove_validator(
 /// Request to remove a validator candidate transaction
 pub async fn request_remove_validator_candidate(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -452,7 +457,7 @@ pub async fn request_remove_validator_candidate(
         validator_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -466,12 +471,13 @@ pub async fn request_remove_validator_candidate(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next commission rate for a validator
 pub async fn set_next_commission(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -493,7 +499,7 @@ pub async fn set_next_commission(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -507,12 +513,13 @@ pub async fn set_next_commission(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Withdraw stake from a validator's staking pool
 pub async fn withdraw_stake(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     staked_ika_id: ObjectID,
@@ -527,7 +534,7 @@ pub async fn withdraw_stake(
     let mut ptb = ProgrammableTransactionBuilder::new();
     let call_args = vec![ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(staked_ika_ref)))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -543,12 +550,13 @@ pub async fn withdraw_stake(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Request to withdraw stake from a validator's staking pool
 pub async fn request_withdraw_stake(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     staked_ika_id: ObjectID,
@@ -563,7 +571,7 @@ pub async fn request_withdraw_stake(
     let mut ptb = ProgrammableTransactionBuilder::new();
     let call_args = vec![ptb.input(CallArg::Object(ObjectArg::ImmOrOwnedObject(staked_ika_ref)))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -577,12 +585,13 @@ pub async fn request_withdraw_stake(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Report a validator as a bad or non-performant actor
 pub async fn report_validator(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -604,7 +613,7 @@ pub async fn report_validator(
         reportee_id,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -618,12 +627,13 @@ pub async fn report_validator(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Undo a report_validator action
 pub async fn undo_report_validator(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -645,7 +655,7 @@ pub async fn undo_report_validator(
         reportee_id,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -659,12 +669,13 @@ pub async fn undo_report_validator(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Rotate operation cap for a validator
 pub async fn rotate_operation_cap(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -681,7 +692,7 @@ pub async fn rotate_operation_cap(
         validator_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -697,7 +708,7 @@ pub async fn rotate_operation_cap(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 async fn construct_unsigned_ika_system_txn(
@@ -790,18 +801,22 @@ pub(crate) async fn construct_unsigned_txn(
     ))
 }
 
+/// Signs `tx_data` with the key of the wallet's active address, and executes it.
 pub async fn execute_transaction(
     context: &mut WalletContext,
     tx_data: TransactionData,
 ) -> anyhow::Result<SuiTransactionBlockResponse> {
-    let sender = context.active_address()?;
-
-    let signature =
-        context
-            .config
-            .keystore
-            .sign_secure(&sender, &tx_data, Intent::sui_transaction())?;
-    let transaction = Transaction::from_data(tx_data, vec![signature]);
+    let signer = FileSigner::from_wallet(context)?;
+    execute_transaction_with_signer(context, &signer, tx_data).await
+}
+
+/// Signs `tx_data` with `signer`, and executes it.
+pub async fn execute_transaction_with_signer(
+    context: &mut WalletContext,
+    signer: &dyn Signer,
+    tx_data: TransactionData,
+) -> anyhow::Result<SuiTransactionBlockResponse> {
+    let transaction = sign_transaction(signer, tx_data).await?;
     let sui_client = context.get_client().await?;
     sui_client
         .quorum_driver_api()
@@ -819,6 +834,7 @@ pub async fn execute_transaction(
 
 pub async fn call_ika_system(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     function: &'static IdentStr,
     call_args: Vec<Argument>,
     gas_budget: u64,
@@ -826,7 +842,7 @@ pub async fn call_ika_system(
     ika_system_package_id: ObjectID,
     ptb: ProgrammableTransactionBuilder,
 ) -> anyhow::Result<SuiTransactionBlockResponse> {
-    let sender = context.active_address()?;
+    let sender = signer.address();
     let tx_data = construct_unsigned_ika_system_txn(
         context,
         sender,
@@ -838,12 +854,13 @@ pub async fn call_ika_system(
         ptb,
     )
     .await?;
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Rotate commission cap for a validator
 pub async fn rotate_commission_cap(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -860,7 +877,7 @@ pub async fn rotate_commission_cap(
         validator_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -876,12 +893,13 @@ pub async fn rotate_commission_cap(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Collect commission from a validator
 pub async fn collect_commission(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_commission_cap_id: ObjectID,
@@ -903,7 +921,7 @@ pub async fn collect_commission(
         amount,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -919,12 +937,13 @@ pub async fn collect_commission(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set validator name
 pub async fn set_validator_name(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -946,7 +965,7 @@ pub async fn set_validator_name(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -960,12 +979,13 @@ pub async fn set_validator_name(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Get validator metadata
 pub async fn validator_metadata(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_id: ObjectID,
@@ -975,7 +995,7 @@ pub async fn validator_metadata(
     let validator_id = ptb.input(CallArg::Pure(bcs::to_bytes(&validator_id)?))?;
     let call_args = vec![validator_id];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -989,12 +1009,13 @@ pub async fn validator_metadata(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set validator metadata
 pub async fn set_validator_metadata(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1016,7 +1037,7 @@ pub async fn set_validator_metadata(
         metadata,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1030,12 +1051,13 @@ pub async fn set_validator_metadata(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch network address
 pub async fn set_next_epoch_network_address(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1057,7 +1079,7 @@ pub async fn set_next_epoch_network_address(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1071,12 +1093,13 @@ pub async fn set_next_epoch_network_address(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch p2p address
 pub async fn set_next_epoch_p2p_address(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1098,7 +1121,7 @@ pub async fn set_next_epoch_p2p_address(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1112,12 +1135,13 @@ pub async fn set_next_epoch_p2p_address(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch consensus address
 pub async fn set_next_epoch_consensus_address(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1139,7 +1163,7 @@ pub async fn set_next_epoch_consensus_address(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1153,12 +1177,13 @@ pub async fn set_next_epoch_consensus_address(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch protocol pubkey bytes
 pub async fn set_next_epoch_protocol_pubkey_bytes(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1184,7 +1209,7 @@ pub async fn set_next_epoch_protocol_pubkey_bytes(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1198,12 +1223,13 @@ pub async fn set_next_epoch_protocol_pubkey_bytes(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch network pubkey bytes
 pub async fn set_next_epoch_network_pubkey_bytes(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1225,7 +1251,7 @@ pub async fn set_next_epoch_network_pubkey_bytes(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1239,12 +1265,13 @@ pub async fn set_next_epoch_network_pubkey_bytes(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set next epoch consensus pubkey bytes
 pub async fn set_next_epoch_consensus_pubkey_bytes(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1267,7 +1294,7 @@ pub async fn set_next_epoch_consensus_pubkey_bytes(
         )))?,
     ];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1281,12 +1308,13 @@ pub async fn set_next_epoch_consensus_pubkey_bytes(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Verify validator cap
 pub async fn verify_validator_cap(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_cap_id: ObjectID,
@@ -1303,7 +1331,7 @@ pub async fn verify_validator_cap(
         validator_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1317,12 +1345,13 @@ pub async fn verify_validator_cap(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Verify operation cap
 pub async fn verify_operation_cap(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1339,7 +1368,7 @@ pub async fn verify_operation_cap(
         validator_operation_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1353,12 +1382,13 @@ pub async fn verify_operation_cap(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Verify commission cap
 pub async fn verify_commission_cap(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_commission_cap_id: ObjectID,
@@ -1375,7 +1405,7 @@ pub async fn verify_commission_cap(
         validator_commission_cap_ref,
     )))?];
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     add_ika_system_command_to_ptb(
         context,
@@ -1389,7 +1419,7 @@ pub async fn verify_commission_cap(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 pub async fn ptb_set_next_epoch_mpc_data_bytes_inner(
     context: &mut WalletContext,
@@ -1466,6 +1496,7 @@ pub async fn new_ptb_set_next_epoch_mpc_data_bytes_with_drop(
 /// Set next epoch MPC data bytes
 pub async fn set_next_epoch_mpc_data_bytes(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     validator_operation_cap_id: ObjectID,
@@ -1489,7 +1520,7 @@ pub async fn set_next_epoch_mpc_data_bytes(
     )
     .await?;
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     let construct_result = construct_unsigned_txn(context, sender, gas_budget, ptb).await;
 
@@ -1521,12 +1552,13 @@ pub async fn set_next_epoch_mpc_data_bytes(
         }
     };
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 /// Set pricing vote for DWallet operations
 pub async fn set_pricing_vote(
     context: &mut WalletContext,
+    signer: &dyn Signer,
     ika_system_package_id: ObjectID,
     ika_system_object_id: ObjectID,
     ika_dwallet_2pc_mpc_package_id: ObjectID,
@@ -1556,7 +1588,7 @@ pub async fn set_pricing_vote(
     )
     .await?;
 
-    let sender = context.active_address()?;
+    let sender = signer.address();
 
     let dwallet_2pc_mpc_coordinator = ptb.input(
         get_dwallet_2pc_mpc_coordinator_call_arg(
@@ -1584,7 +1616,7 @@ pub async fn set_pricing_vote(
 
     let tx_data = construct_unsigned_txn(context, sender, gas_budget, ptb).await?;
 
-    execute_transaction(context, tx_data).await
+    execute_transaction_with_signer(context, signer, tx_data).await
 }
 
 pub(crate) async fn new_pricing_info(
@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
 use crate::metrics::SuiClientMetrics;
-use anyhow::anyhow;
+use crate::multi_endpoint::{MultiEndpointSuiClient, pin_endpoint};
 use async_trait::async_trait;
 use core::panic;
 use dwallet_mpc_types::dwallet_mpc::VersionedMPCData;
+use futures::StreamExt;
+use futures::stream::BoxStream;
 use ika_types::error::{IkaError, IkaResult};
 use ika_types::messages_consensus::MovePackageDigest;
 use ika_types::messages_dwallet_mpc::{
@@ -24,21 +26,21 @@ use std::sync::Arc;
 use std::time::Duration;
 use sui_json_rpc_types::{EventFilter, Page, SuiEvent};
 use sui_json_rpc_types::{
-    EventPage, SuiObjectDataOptions, SuiTransactionBlockResponse,
+    EventPage, SuiObjectDataOptions, SuiTransactionBlockEffects, SuiTransactionBlockResponse,
     SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiData, SuiObjectDataFilter, SuiObjectResponseQuery};
+use sui_sdk::SuiClient as SuiSdkClient;
 use sui_sdk::error::Error;
-use sui_sdk::{SuiClient as SuiSdkClient, SuiClientBuilder};
 use sui_types::TypeTag;
 use sui_types::base_types::{EpochId, ObjectRef};
 use sui_types::clock::Clock;
 use sui_types::collection_types::{Entry, Table};
 use sui_types::dynamic_field::Field;
 use sui_types::gas_coin::GasCoin;
-use sui_types::object::Owner;
+use sui_types::object::{Object, Owner};
 use sui_types::transaction::ObjectArg;
-use sui_types::transaction::Transaction;
+use sui_types::transaction::{Transaction, TransactionData};
 use sui_types::{
     Identifier,
     base_types::{ObjectID, SuiAddress},
@@ -48,14 +50,27 @@ use sui_types::{
 use tokio::sync::OnceCell;
 use tracing::{debug, error, info, warn};
 
+mod checkpoint_subscription;
 #[cfg(feature = "protocol-commands")]
 pub mod ika_protocol_transactions;
 pub mod ika_validator_transactions;
+#[cfg(feature = "local-sui")]
+pub mod local_sui;
 pub mod metrics;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock_signer_server;
+pub mod multi_endpoint;
+pub mod signer;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod sui_mock_client;
 
 #[macro_export]
 macro_rules! retry_with_max_elapsed_time {
     ($func:expr, $max_elapsed_time:expr) => {{
+        $crate::retry_with_max_elapsed_time!($func, $max_elapsed_time, |_| false)
+    }};
+    // `$is_permanent` is called with each error, and stops the retries on the first one it accepts.
+    ($func:expr, $max_elapsed_time:expr, $is_permanent:expr) => {{
         // The following delay sequence (in secs) will be used, applied with jitter
         // 0.4, 0.8, 1.6, 3.2, 6.4, 12.8, 25.6, 30, 60, 120, 120 ...
         let backoff = backoff::ExponentialBackoff {
@@ -74,7 +89,10 @@ macro_rules! retry_with_max_elapsed_time {
                         return Ok(result);
                     }
                     Err(err) => {
-          // For simplicity we treat every error as transient so we can retry until max_elapsed_time
+                        if ($is_permanent)(&err) {
+                            return Err(backoff::Error::permanent(err));
+                        }
+          // For simplicity we treat every other error as transient so we can retry until max_elapsed_time
           warn!(error=?err, "retrying with max elapsed time");
                         return Err(backoff::Error::transient(err));
                     }
@@ -98,11 +116,12 @@ pub struct SuiClient<P> {
     ika_dwallet_coordinator_object_id: ObjectID,
 }
 
-pub type SuiConnectorClient = SuiClient<SuiSdkClient>;
+pub type SuiConnectorClient = SuiClient<MultiEndpointSuiClient>;
 
 impl SuiConnectorClient {
+    /// Connects to the Sui fullnodes at `rpc_urls`, the first of which is the primary one.
     pub async fn new(
-        rpc_url: &str,
+        rpc_urls: &[String],
         sui_client_metrics: Arc<SuiClientMetrics>,
         ika_package_id: ObjectID,
         ika_common_package_id: ObjectID,
@@ -111,12 +130,7 @@ impl SuiConnectorClient {
         ika_system_object_id: ObjectID,
         ika_dwallet_coordinator_object_id: ObjectID,
     ) -> anyhow::Result<Self> {
-        let inner = SuiClientBuilder::default()
-            .build(rpc_url)
-            .await
-            .map_err(|e| {
-                anyhow!("Can't establish connection with Sui Rpc {rpc_url}. Error: {e}")
-            })?;
+        let inner = MultiEndpointSuiClient::new(rpc_urls, sui_client_metrics.clone()).await?;
         let self_ = Self {
             inner,
             sui_client_metrics,
@@ -131,8 +145,10 @@ impl SuiConnectorClient {
         Ok(self_)
     }
 
-    pub fn sui_client(&self) -> &SuiSdkClient {
-        &self.inner
+    /// The client of the currently healthiest Sui fullnode,
+    /// or `None` when connected to a local Sui network.
+    pub fn sui_client(&self) -> Option<&SuiSdkClient> {
+        self.inner.best_client()
     }
 }
 
@@ -161,6 +177,11 @@ where
     pub async fn pull_dwallet_mpc_uncompleted_events(
         &self,
     ) -> IkaResult<(Vec<DBSuiEvent>, EpochId)> {
+        // The events bags are read from the endpoint that served the coordinator holding them.
+        pin_endpoint(self.read_dwallet_mpc_uncompleted_events()).await
+    }
+
+    async fn read_dwallet_mpc_uncompleted_events(&self) -> IkaResult<(Vec<DBSuiEvent>, EpochId)> {
         loop {
             let dwallet_coordinator_inner = self.must_get_dwallet_coordinator_inner_v1().await;
             let user_missed_events = self
@@ -231,6 +252,11 @@ where
         }
     }
 
+    /// The package whose events are read by [`Self::query_events_by_module`].
+    pub fn ika_dwallet_2pc_mpc_package_id(&self) -> ObjectID {
+        self.ika_dwallet_2pc_mpc_package_id
+    }
+
     // TODO assert chain identifier
     async fn describe(&self) -> anyhow::Result<()> {
         let chain_id = self.inner.get_chain_identifier().await?;
@@ -242,17 +268,33 @@ where
     }
 
     pub async fn get_dwallet_coordinator_inner(&self) -> IkaResult<DWalletCoordinatorInner> {
+        // The inner object is read at the version of the wrapper read first.
+        pin_endpoint(self.read_dwallet_coordinator_inner()).await
+    }
+
+    /// The version of the on-chain `DWalletCoordinator` object, which is bumped when the
+    /// coordinator is migrated to an upgraded package.
+    pub async fn get_dwallet_coordinator_version(&self) -> IkaResult<u64> {
+        Ok(self.read_dwallet_coordinator().await?.version)
+    }
+
+    async fn read_dwallet_coordinator(&self) -> IkaResult<DWalletCoordinator> {
         let result = self
             .inner
             .get_dwallet_coordinator(self.ika_dwallet_coordinator_object_id)
             .await
             .map_err(|e| IkaError::SuiClientInternalError(format!("Can't get Coordinator: {e}")))?;
-        let wrapper = bcs::from_bytes::<DWalletCoordinator>(&result).map_err(|e| {
+        bcs::from_bytes::<DWalletCoordinator>(&result).map_err(|e| {
             IkaError::SuiClientSerializationError(format!("Can't serialize Coordinator: {e}"))
-        })?;
+        })
+    }
+
+    async fn read_dwallet_coordinator_inner(&self) -> IkaResult<DWalletCoordinatorInner> {
+        let wrapper = self.read_dwallet_coordinator().await?;
 
+        // Version 2 only changed how the coordinator parses checkpoints, not its inner object.
         match wrapper.version {
-            1 => {
+            1 | 2 => {
                 let result = self
                     .inner
                     .get_dwallet_coordinator_inner(
@@ -285,14 +327,29 @@ where
     }
 
     pub async fn get_system_inner(&self) -> IkaResult<SystemInner> {
+        // The inner object is read at the version of the wrapper read first.
+        pin_endpoint(self.read_system_inner()).await
+    }
+
+    /// The version of the on-chain `System` object, which is bumped when the system is
+    /// migrated to an upgraded package.
+    pub async fn get_system_version(&self) -> IkaResult<u64> {
+        Ok(self.read_system().await?.version)
+    }
+
+    async fn read_system(&self) -> IkaResult<System> {
         let result = self
             .inner
             .get_system(self.ika_system_object_id)
             .await
             .map_err(|e| IkaError::SuiClientInternalError(format!("Can't get System: {e}")))?;
-        let wrapper = bcs::from_bytes::<System>(&result).map_err(|e| {
+        bcs::from_bytes::<System>(&result).map_err(|e| {
             IkaError::SuiClientSerializationError(format!("Can't serialize System: {e}"))
-        })?;
+        })
+    }
+
+    async fn read_system_inner(&self) -> IkaResult<SystemInner> {
+        let wrapper = self.read_system().await?;
 
         match wrapper.version {
             1 => {
@@ -357,6 +414,14 @@ where
     pub async fn get_epoch_start_system(
         &self,
         ika_system_state_inner: &SystemInner,
+    ) -> IkaResult<EpochStartSystem> {
+        // The MPC data is read from the validators read first.
+        pin_endpoint(self.read_epoch_start_system(ika_system_state_inner)).await
+    }
+
+    async fn read_epoch_start_system(
+        &self,
+        ika_system_state_inner: &SystemInner,
     ) -> IkaResult<EpochStartSystem> {
         match ika_system_state_inner {
             SystemInner::V1(ika_system_state_inner) => {
@@ -558,6 +623,25 @@ where
         Ok(events)
     }
 
+    /// Subscribe to the sequence numbers of the checkpoints the fullnode executes from now on.
+    /// Fails if the fullnode doesn't serve checkpoint subscriptions.
+    ///
+    /// A checkpoint only signals that new events may have been emitted: they are read with
+    /// [`Self::query_events_by_module`], which returns them in checkpoint order from a
+    /// resumable cursor, so no event is missed when the subscription is interrupted.
+    pub async fn subscribe_checkpoints(&self) -> IkaResult<BoxStream<'static, IkaResult<u64>>> {
+        let checkpoints = self.inner.subscribe_checkpoints().await.map_err(|e| {
+            IkaError::SuiClientInternalError(format!("Can't subscribe_checkpoints: {e}"))
+        })?;
+        Ok(checkpoints
+            .map(|checkpoint| {
+                checkpoint.map_err(|e| {
+                    IkaError::SuiClientInternalError(format!("Checkpoint subscription failed: {e}"))
+                })
+            })
+            .boxed())
+    }
+
     pub async fn get_chain_identifier(&self) -> IkaResult<String> {
         self.inner.get_chain_identifier().await.map_err(|e| {
             IkaError::SuiClientInternalError(format!("Can't get_chain_identifier: {e}"))
@@ -599,6 +683,19 @@ where
         self.inner.execute_transaction_block_with_effects(tx).await
     }
 
+    /// Simulates `tx_data` against the latest Sui state, without signing or executing it.
+    pub async fn dry_run_transaction_block(
+        &self,
+        tx_data: TransactionData,
+    ) -> IkaResult<SuiTransactionBlockEffects> {
+        self.inner
+            .dry_run_transaction_block(tx_data)
+            .await
+            .map_err(|e| {
+                IkaError::SuiClientInternalError(format!("Can't dry run transaction: {e}"))
+            })
+    }
+
     pub async fn must_get_system_inner_object(&self) -> SystemInner {
         loop {
             match retry_with_max_elapsed_time!(self.get_system_inner(), Duration::from_secs(30)) {
@@ -660,6 +757,41 @@ where
             })
     }
 
+    /// The ID of the `TableVec` holding the reconfiguration public output of `epoch`,
+    /// in the reconfiguration public outputs table `table_id` of a network encryption key.
+    pub async fn get_current_reconfiguration_public_output(
+        &self,
+        epoch: EpochId,
+        table_id: ObjectID,
+    ) -> IkaResult<ObjectID> {
+        self.inner
+            .get_current_reconfiguration_public_output(epoch, table_id)
+            .await
+            .map_err(|e| {
+                IkaError::SuiClientInternalError(format!(
+                    "Can't get_current_reconfiguration_public_output: {e}"
+                ))
+            })
+    }
+
+    pub async fn get_table_vec_chunk_refs(&self, table_id: ObjectID) -> IkaResult<Vec<ObjectRef>> {
+        self.inner
+            .get_table_vec_chunk_refs(table_id)
+            .await
+            .map_err(|e| {
+                IkaError::SuiClientInternalError(format!("Can't get_table_vec_chunk_refs: {e}"))
+            })
+    }
+
+    pub async fn read_table_vec_chunks(&self, table_id: ObjectID) -> IkaResult<Vec<Object>> {
+        self.inner
+            .read_table_vec_chunks(table_id)
+            .await
+            .map_err(|e| {
+                IkaError::SuiClientInternalError(format!("Can't read_table_vec_chunks: {e}"))
+            })
+    }
+
     pub async fn must_get_dwallet_coordinator_inner(&self) -> DWalletCoordinatorInner {
         loop {
             match retry_with_max_elapsed_time!(
@@ -729,6 +861,17 @@ where
     pub async fn get_gas_objects(&self, address: SuiAddress) -> Vec<ObjectRef> {
         self.inner.get_gas_objects(address).await
     }
+
+    /// Returns the reference and balance of every SUI coin owned by `address`.
+    pub async fn get_gas_coins(&self, address: SuiAddress) -> IkaResult<Vec<(ObjectRef, u64)>> {
+        self.inner.get_gas_coins(address).await.map_err(|e| {
+            self.sui_client_metrics
+                .sui_rpc_errors
+                .with_label_values(&["get_gas_coins"])
+                .inc();
+            IkaError::SuiClientInternalError(format!("Can't get_gas_coins: {e}"))
+        })
+    }
 }
 
 /// Use a trait to abstract over the SuiSDKClient and SuiMockClient for testing.
@@ -752,6 +895,11 @@ pub trait SuiClientInner: Send + Sync {
 
     async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, Self::Error>;
 
+    /// Streams the sequence number of every checkpoint executed from now on, in order.
+    async fn subscribe_checkpoints(
+        &self,
+    ) -> Result<BoxStream<'static, Result<u64, Self::Error>>, Self::Error>;
+
     async fn get_system(&self, ika_system_object_id: ObjectID) -> Result<Vec<u8>, Self::Error>;
 
     async fn get_clock(&self, clock_obj_id: ObjectID) -> Result<Vec<u8>, Self::Error>;
@@ -789,6 +937,16 @@ pub trait SuiClientInner: Send + Sync {
     async fn read_table_vec_as_raw_bytes(&self, table_id: ObjectID)
     -> Result<Vec<u8>, self::Error>;
 
+    /// Lists the references of the chunk objects of the `TableVec` `table_id`,
+    /// without reading the chunks.
+    async fn get_table_vec_chunk_refs(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<ObjectRef>, Self::Error>;
+
+    /// Reads the chunk objects of the `TableVec` `table_id`.
+    async fn read_table_vec_chunks(&self, table_id: ObjectID) -> Result<Vec<Object>, Self::Error>;
+
     async fn get_system_inner(
         &self,
         ika_system_object_id: ObjectID,
@@ -830,8 +988,18 @@ pub trait SuiClientInner: Send + Sync {
         tx: Transaction,
     ) -> Result<SuiTransactionBlockResponse, IkaError>;
 
+    async fn dry_run_transaction_block(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockEffects, Self::Error>;
+
     async fn get_gas_objects(&self, address: SuiAddress) -> Vec<ObjectRef>;
 
+    async fn get_gas_coins(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<(ObjectRef, u64)>, Self::Error>;
+
     /// Fetch events for which no output was received (weren't completed.)
     /// Completed events are removed from the SessionManagement in Move,
     /// so querying all the values assures we query uncompleted events exclusively.
@@ -876,6 +1044,15 @@ impl SuiClientInner for SuiSdkClient {
             .await
     }
 
+    async fn subscribe_checkpoints(
+        &self,
+    ) -> Result<BoxStream<'static, Result<u64, Self::Error>>, Self::Error> {
+        // Subscriptions are served over gRPC, see `MultiEndpointSuiClient`.
+        Err(Error::DataError(
+            "The JSON-RPC client can't subscribe to checkpoints".to_string(),
+        ))
+    }
+
     async fn get_system(&self, ika_system_object_id: ObjectID) -> Result<Vec<u8>, Self::Error> {
         self.read_api()
             .get_move_object_bcs(ika_system_object_id)
@@ -1180,6 +1357,56 @@ impl SuiClientInner for SuiSdkClient {
             }))
     }
 
+    async fn get_table_vec_chunk_refs(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<ObjectRef>, Self::Error> {
+        let mut chunk_refs = Vec::new();
+        let mut cursor = None;
+        loop {
+            let dynamic_fields = self
+                .read_api()
+                .get_dynamic_fields(table_id, cursor, None)
+                .await
+                .map_err(|e| {
+                    Error::DataError(format!(
+                        "can't get dynamic fields of table {table_id:?}: {e:?}"
+                    ))
+                })?;
+            chunk_refs.extend(
+                dynamic_fields
+                    .data
+                    .iter()
+                    .map(|df| (df.object_id, df.version, df.digest)),
+            );
+
+            cursor = dynamic_fields.next_cursor;
+            if !dynamic_fields.has_next_page {
+                break;
+            }
+        }
+        Ok(chunk_refs)
+    }
+
+    async fn read_table_vec_chunks(&self, table_id: ObjectID) -> Result<Vec<Object>, Self::Error> {
+        let mut chunks = Vec::new();
+        for (object_id, _, _) in self.get_table_vec_chunk_refs(table_id).await? {
+            let object_data = self
+                .read_api()
+                .get_object_with_options(object_id, SuiObjectDataOptions::bcs_lossless())
+                .await?
+                .into_object()
+                .map_err(|e| {
+                    Error::DataError(format!("can't get bcs of object {object_id:?}: {e:?}"))
+                })?;
+            let chunk: Object = object_data
+                .try_into()
+                .map_err(|e: anyhow::Error| Error::DataError(e.to_string()))?;
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+
     async fn get_system_inner(
         &self,
         ika_system_object_id: ObjectID,
@@ -1448,6 +1675,17 @@ impl SuiClientInner for SuiSdkClient {
         }
     }
 
+    async fn dry_run_transaction_block(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockEffects, Self::Error> {
+        Ok(self
+            .read_api()
+            .dry_run_transaction_block(tx_data)
+            .await?
+            .effects)
+    }
+
     async fn get_gas_objects(&self, address: SuiAddress) -> Vec<ObjectRef> {
         loop {
             let results = self
@@ -1478,4 +1716,27 @@ impl SuiClientInner for SuiSdkClient {
             }
         }
     }
+
+    async fn get_gas_coins(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<(ObjectRef, u64)>, Self::Error> {
+        let mut coins = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self
+                .coin_read_api()
+                .get_coins(address, None, cursor, None)
+                .await?;
+            coins.extend(
+                page.data
+                    .into_iter()
+                    .map(|coin| (coin.object_ref(), coin.balance)),
+            );
+            if !page.has_next_page {
+                return Ok(coins);
+            }
+            cursor = page.next_cursor;
+        }
+    }
 }
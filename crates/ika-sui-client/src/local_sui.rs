@@ -0,0 +1,1607 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! An embedded stand-in for the Sui network, so a swarm or a localnet can run without
+//! a Sui fullnode and without publishing the Ika Move packages.
+//!
+//! A [`LocalSuiNetwork`] serves a single Ika deployment through a [`SuiMockClient`],
+//! and applies the transactions the Ika nodes submit the way the Move contracts would:
+//! it processes checkpoint messages, completes the sessions they answer, stores network
+//! encryption key outputs, and walks through the mid-epoch reconfiguration and the
+//! epoch switch. Checkpoint certificates are verified against the active committee like
+//! the Move `bls_committee::verify_certificate` does, but fees and staking are not modeled,
+//! and only the Move calls the Ika nodes make are interpreted.
+//!
+//! Networks are registered under a `local-sui://<name>` URL,
+//! which [`crate::multi_endpoint::MultiEndpointSuiClient`] resolves in-process.
+//! Tests drive the network by requesting sessions and inspecting the outputs the nodes wrote.
+
+use crate::sui_mock_client::{
+    SuiMockClient, dwallet_coordinator_inner_v1_for_testing, system_inner_v1_for_testing,
+};
+use dwallet_mpc_types::dwallet_mpc::VersionedMPCData;
+use fastcrypto::traits::{AggregateAuthenticator, ToFromBytes};
+use ika_types::crypto::{AggregateAuthoritySignature, AuthorityPublicKey};
+use ika_types::intent::{Intent, IntentScope};
+use ika_types::message::DWalletCheckpointMessageKind;
+use ika_types::messages_dwallet_checkpoint::DWalletCheckpointMessage;
+use ika_types::messages_dwallet_mpc::test_helpers::new_dwallet_session_event;
+use ika_types::messages_dwallet_mpc::{
+    DBSuiEvent, DWalletEncryptionKeyReconfigurationRequestEvent,
+    DWalletNetworkDKGEncryptionKeyRequestEvent, DWalletNetworkEncryptionKey,
+    DWalletNetworkEncryptionKeyData, DWalletNetworkEncryptionKeyState, DWalletSessionEvent,
+    DWalletSessionEventTrait, IkaNetworkConfig, SessionIdentifier, SessionType,
+};
+use ika_types::messages_system_checkpoints::{
+    SystemCheckpointMessage, SystemCheckpointMessageKind,
+};
+use ika_types::sui::staking::{StakingPool, ValidatorInfo};
+use ika_types::sui::system_inner_v1::{
+    BlsCommittee, BlsCommitteeMember, DWalletCoordinatorInnerV1, PricingInfoCalculationVotes,
+    SessionsManager, SystemInnerV1,
+};
+use ika_types::sui::{
+    ADVANCE_EPOCH_FUNCTION_NAME, APPEND_VECTOR_FUNCTION_NAME,
+    DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME, Element, INITIATE_ADVANCE_EPOCH_FUNCTION_NAME,
+    INITIATE_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME,
+    PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME, REQUEST_LOCK_EPOCH_SESSIONS_FUNCTION_NAME,
+    REQUEST_NETWORK_ENCRYPTION_KEY_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME, SYSTEM_MODULE_NAME,
+    VECTOR_MODULE_NAME,
+};
+use move_core_types::identifier::{IdentStr, Identifier};
+use move_core_types::language_storage::StructTag;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_json_rpc_types::{BcsEvent, SuiEvent};
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, SuiAddress};
+use sui_types::collection_types::{Table, TableVec};
+use sui_types::digests::TransactionDigest;
+use sui_types::dynamic_field::derive_dynamic_field_id;
+use sui_types::event::EventID;
+use sui_types::transaction::{
+    Argument, CallArg, Command, ProgrammableMoveCall, ProgrammableTransaction, Transaction,
+    TransactionData, TransactionDataAPI, TransactionKind,
+};
+use sui_types::{MOVE_STDLIB_PACKAGE_ID, TypeTag};
+
+/// The URL scheme of the Sui RPC URLs that point to a [`LocalSuiNetwork`].
+pub const LOCAL_SUI_URL_SCHEME: &str = "local-sui://";
+
+const LOCAL_SUI_CHAIN_IDENTIFIER: &str = "local-sui";
+
+/// The self-stake of every validator; the stand-in gives all validators equal voting power.
+const VALIDATOR_STAKE: u64 = 1;
+
+/// How often [`LocalSuiNetwork::wait_for_session_output`] checks for the output.
+const SESSION_OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static LOCAL_SUI_NETWORKS: LazyLock<Mutex<HashMap<String, LocalSuiNetwork>>> =
+    LazyLock::new(Default::default);
+
+/// Whether `url` points to a [`LocalSuiNetwork`] rather than to a Sui fullnode.
+pub fn is_local_sui_url(url: &str) -> bool {
+    url.starts_with(LOCAL_SUI_URL_SCHEME)
+}
+
+/// The on-chain registration of a validator in the genesis committee.
+#[derive(Clone, Debug)]
+pub struct LocalSuiValidator {
+    pub name: String,
+    pub protocol_pubkey_bytes: Vec<u8>,
+    pub network_pubkey_bytes: Vec<u8>,
+    pub consensus_pubkey_bytes: Vec<u8>,
+    pub network_address: String,
+    pub p2p_address: String,
+    pub consensus_address: String,
+    pub mpc_data: VersionedMPCData,
+}
+
+/// A handle to an embedded Sui network; every clone refers to the same network.
+#[derive(Clone)]
+pub struct LocalSuiNetwork {
+    inner: Arc<LocalSuiNetworkInner>,
+}
+
+impl std::fmt::Debug for LocalSuiNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSuiNetwork")
+            .field("url", &self.inner.url)
+            .finish_non_exhaustive()
+    }
+}
+
+struct LocalSuiNetworkInner {
+    url: String,
+    client: SuiMockClient,
+    ika_network_config: IkaNetworkConfig,
+    state: Mutex<LocalSuiState>,
+}
+
+/// The state of the Ika Move objects, published to the [`SuiMockClient`] after every change.
+#[derive(Clone)]
+struct LocalSuiState {
+    system: SystemInnerV1,
+    coordinator: DWalletCoordinatorInnerV1,
+    user_session_events: BTreeMap<u64, DBSuiEvent>,
+    system_session_events: BTreeMap<u64, DBSuiEvent>,
+    network_keys: BTreeMap<ObjectID, LocalNetworkKey>,
+    session_outputs: HashMap<(SessionType, u64), DWalletCheckpointMessageKind>,
+    /// Every transaction and session request is counted as a Sui checkpoint.
+    latest_checkpoint_sequence_number: u64,
+}
+
+#[derive(Clone)]
+struct LocalNetworkKey {
+    key: DWalletNetworkEncryptionKey,
+    network_dkg_public_output: Vec<u8>,
+    /// The reconfiguration output of the key for each epoch, by the epoch it is used in.
+    reconfiguration_public_outputs: BTreeMap<EpochId, Vec<u8>>,
+}
+
+/// Rust version of the Move `ika_system::system_inner::SystemEpochInfoEvent` type.
+#[derive(Serialize)]
+struct SystemEpochInfoEvent {
+    epoch: u64,
+    protocol_version: u64,
+    total_stake: u64,
+    stake_subsidy_amount: u64,
+    total_computation_fees: u64,
+    total_stake_rewards_distributed: u64,
+}
+
+/// Rust version of the Move `ika_dwallet_2pc_mpc::coordinator_inner::DWalletCheckpointInfoEvent` type.
+#[derive(Serialize)]
+struct DWalletCheckpointInfoEvent {
+    epoch: u64,
+    sequence_number: u64,
+}
+
+/// An event emitted by a Move call, before it is assigned its ID.
+type EmittedEvent = (StructTag, Vec<u8>);
+
+impl LocalSuiNetwork {
+    /// Starts a network at epoch 1 with `validators` as its active committee,
+    /// and registers it under `local-sui://<name>`.
+    pub fn start(
+        name: &str,
+        validators: Vec<LocalSuiValidator>,
+        epoch_duration_ms: u64,
+        protocol_version: u64,
+    ) -> anyhow::Result<Self> {
+        let url = format!("{LOCAL_SUI_URL_SCHEME}{name}");
+        let mut networks = LOCAL_SUI_NETWORKS.lock();
+        if networks.contains_key(&url) {
+            anyhow::bail!("a local Sui network is already registered under {url}");
+        }
+
+        let ika_network_config = IkaNetworkConfig::new(
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+        );
+        let client = SuiMockClient::new();
+        client.set_chain_identifier(LOCAL_SUI_CHAIN_IDENTIFIER.to_string());
+        client.use_system_clock();
+
+        let epoch = 1;
+        let mut members = Vec::with_capacity(validators.len());
+        for validator in validators {
+            let validator_id = ObjectID::random();
+            members.push(BlsCommitteeMember {
+                validator_id,
+                protocol_pubkey: Element::new(validator.protocol_pubkey_bytes.clone()),
+            });
+            let validator_info = ValidatorInfo::new(
+                validator.name,
+                validator_id,
+                validator.network_address,
+                validator.p2p_address,
+                validator.consensus_address,
+                validator.protocol_pubkey_bytes,
+                validator.network_pubkey_bytes,
+                validator.consensus_pubkey_bytes,
+                TableVec {
+                    contents: new_table(),
+                },
+            );
+            client.add_validator(StakingPool::new_active(
+                validator_info,
+                epoch,
+                VALIDATOR_STAKE,
+            ));
+            client.set_mpc_data(validator_id, validator.mpc_data);
+        }
+        let committee_size = members.len() as u64;
+        let committee = BlsCommittee {
+            members,
+            aggregated_protocol_pubkey: Element::new(vec![]),
+            quorum_threshold: (2 * committee_size) / 3 + 1,
+            validity_threshold: committee_size / 3 + 1,
+        };
+
+        let genesis_digest = TransactionDigest::random();
+        let mut system = system_inner_v1_for_testing(epoch, committee.clone());
+        system.epoch_start_tx_digest = genesis_digest.inner().to_vec();
+        system.epoch_start_timestamp_ms = now_ms();
+        system.epoch_duration_ms = epoch_duration_ms;
+        system.protocol_version = protocol_version;
+        system.validator_set.validators.size = committee_size;
+        system.validator_set.total_stake = committee_size * VALIDATOR_STAKE;
+        let mut coordinator = dwallet_coordinator_inner_v1_for_testing(epoch, committee);
+        coordinator
+            .sessions_manager
+            .locked_last_user_initiated_session_to_complete_in_current_epoch = false;
+
+        let network = Self {
+            inner: Arc::new(LocalSuiNetworkInner {
+                url: url.clone(),
+                client,
+                ika_network_config,
+                state: Mutex::new(LocalSuiState {
+                    system,
+                    coordinator,
+                    user_session_events: BTreeMap::new(),
+                    system_session_events: BTreeMap::new(),
+                    network_keys: BTreeMap::new(),
+                    session_outputs: HashMap::new(),
+                    latest_checkpoint_sequence_number: 0,
+                }),
+            }),
+        };
+        network.publish(&network.inner.state.lock());
+
+        // The nodes start reading events from the transaction that started the epoch.
+        let (type_, contents) = network.epoch_info_event(&network.inner.state.lock());
+        network.inner.client.emit_event(sui_event(
+            EventID {
+                tx_digest: genesis_digest,
+                event_seq: 0,
+            },
+            SuiAddress::ZERO,
+            type_,
+            contents,
+        ));
+
+        // The client holds the handler, so it only holds a weak reference back to the network.
+        let weak = Arc::downgrade(&network.inner);
+        network
+            .inner
+            .client
+            .set_execution_handler(Arc::new(
+                move |transaction: &Transaction| match Weak::upgrade(&weak) {
+                    Some(inner) => Self { inner }.execute(transaction),
+                    None => Err("the local Sui network was shut down".to_string()),
+                },
+            ));
+        let weak = Arc::downgrade(&network.inner);
+        network
+            .inner
+            .client
+            .set_dry_run_handler(Arc::new(
+                move |tx_data: &TransactionData| match Weak::upgrade(&weak) {
+                    Some(inner) => Self { inner }.dry_run(tx_data),
+                    None => Err("the local Sui network was shut down".to_string()),
+                },
+            ));
+
+        networks.insert(url, network.clone());
+        Ok(network)
+    }
+
+    /// The network registered under `url`, if any.
+    pub fn connect(url: &str) -> Option<Self> {
+        LOCAL_SUI_NETWORKS.lock().get(url).cloned()
+    }
+
+    /// Deregisters the network; nodes that already connected keep their handle.
+    pub fn shutdown(&self) {
+        LOCAL_SUI_NETWORKS.lock().remove(&self.inner.url);
+    }
+
+    pub fn url(&self) -> &str {
+        &self.inner.url
+    }
+
+    pub fn ika_network_config(&self) -> &IkaNetworkConfig {
+        &self.inner.ika_network_config
+    }
+
+    /// The client the Ika nodes read the network through.
+    pub fn client(&self) -> SuiMockClient {
+        self.inner.client.clone()
+    }
+
+    pub fn epoch(&self) -> EpochId {
+        self.inner.state.lock().system.epoch
+    }
+
+    /// Creates a gas coin of `balance` MIST owned by `owner`.
+    pub fn fund(&self, owner: SuiAddress, balance: u64) -> ObjectRef {
+        self.inner.client.add_gas_coin(owner, balance)
+    }
+
+    /// Starts a session in the current epoch, as the coordinator does when a user
+    /// or the system requests one, returning its sequence number.
+    pub fn request_session<E>(&self, session_type: SessionType, event_data: E) -> u64
+    where
+        E: DWalletSessionEventTrait + Serialize,
+    {
+        self.request_session_with_identifier(
+            session_type,
+            ObjectID::random().into_bytes(),
+            event_data,
+        )
+    }
+
+    /// Like [`Self::request_session`], with the session identifier pre-image the user
+    /// registered, for protocols whose user-side step commits to the session identifier.
+    pub fn request_session_with_identifier<E>(
+        &self,
+        session_type: SessionType,
+        session_identifier_preimage: [u8; SessionIdentifier::LENGTH],
+        event_data: E,
+    ) -> u64
+    where
+        E: DWalletSessionEventTrait + Serialize,
+    {
+        let mut state = self.inner.state.lock();
+        let (session_sequence_number, event) = self.initiate_session(
+            &mut state,
+            session_type,
+            session_identifier_preimage,
+            event_data,
+        );
+        state.latest_checkpoint_sequence_number += 1;
+        self.publish(&state);
+        let (type_, contents) = event;
+        self.inner.client.emit_event(sui_event(
+            EventID {
+                tx_digest: TransactionDigest::random(),
+                event_seq: 0,
+            },
+            SuiAddress::ZERO,
+            type_,
+            contents,
+        ));
+        session_sequence_number
+    }
+
+    /// Creates a network encryption key and requests its network DKG, returning the key ID.
+    pub fn request_network_encryption_key_dkg(&self) -> ObjectID {
+        let key_id = ObjectID::random();
+        {
+            let mut state = self.inner.state.lock();
+            let epoch = state.coordinator.current_epoch;
+            state.coordinator.dwallet_network_encryption_keys.size += 1;
+            state.network_keys.insert(
+                key_id,
+                LocalNetworkKey {
+                    key: DWalletNetworkEncryptionKey {
+                        id: key_id,
+                        dkg_at_epoch: epoch,
+                        network_dkg_public_output: TableVec {
+                            contents: new_table(),
+                        },
+                        reconfiguration_public_outputs: new_table(),
+                        dkg_params_for_network: vec![],
+                        supported_curves: vec![],
+                        state: DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG,
+                    },
+                    network_dkg_public_output: vec![],
+                    reconfiguration_public_outputs: BTreeMap::new(),
+                },
+            );
+        }
+        self.request_session(
+            SessionType::System,
+            DWalletNetworkDKGEncryptionKeyRequestEvent {
+                dwallet_network_encryption_key_id: key_id,
+                params_for_network: vec![],
+            },
+        );
+        key_id
+    }
+
+    /// The IDs of the network encryption keys.
+    pub fn network_encryption_key_ids(&self) -> Vec<ObjectID> {
+        self.inner
+            .state
+            .lock()
+            .network_keys
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// The network DKG public output of the key, once its network DKG completed.
+    pub fn network_dkg_public_output(&self, key_id: ObjectID) -> Option<Vec<u8>> {
+        let state = self.inner.state.lock();
+        let key = state.network_keys.get(&key_id)?;
+        (key.key.state != DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG)
+            .then(|| key.network_dkg_public_output.clone())
+    }
+
+    pub fn network_encryption_key_state(
+        &self,
+        key_id: ObjectID,
+    ) -> Option<DWalletNetworkEncryptionKeyState> {
+        self.inner
+            .state
+            .lock()
+            .network_keys
+            .get(&key_id)
+            .map(|key| key.key.state.clone())
+    }
+
+    /// The checkpoint message that completed the session, if it was completed.
+    pub fn session_output(
+        &self,
+        session_type: SessionType,
+        session_sequence_number: u64,
+    ) -> Option<DWalletCheckpointMessageKind> {
+        self.inner
+            .state
+            .lock()
+            .session_outputs
+            .get(&(session_type, session_sequence_number))
+            .cloned()
+    }
+
+    /// Waits up to `timeout` for the session to be completed, returning its output.
+    pub async fn wait_for_session_output(
+        &self,
+        session_type: SessionType,
+        session_sequence_number: u64,
+        timeout: Duration,
+    ) -> Option<DWalletCheckpointMessageKind> {
+        tokio::time::timeout(timeout, async {
+            loop {
+                if let Some(output) = self.session_output(session_type, session_sequence_number) {
+                    return output;
+                }
+                tokio::time::sleep(SESSION_OUTPUT_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .ok()
+    }
+
+    /// Applies `transaction` to a copy of the state, which replaces the state only
+    /// if every Move call succeeded, so a failed transaction has no effects, as on Sui.
+    fn execute(&self, transaction: &Transaction) -> Result<Vec<SuiEvent>, String> {
+        let tx_data = transaction.data().transaction_data();
+        let digest = *transaction.digest();
+        let mut state = self.inner.state.lock();
+        let Some((next_state, events)) = self.simulate(&state, tx_data, digest)? else {
+            return Ok(vec![]);
+        };
+        *state = next_state;
+        state.latest_checkpoint_sequence_number += 1;
+        self.publish(&state);
+
+        let sender = tx_data.sender();
+        Ok(events
+            .into_iter()
+            .enumerate()
+            .map(|(event_seq, (type_, contents))| {
+                sui_event(
+                    EventID {
+                        tx_digest: digest,
+                        event_seq: event_seq as u64,
+                    },
+                    sender,
+                    type_,
+                    contents,
+                )
+            })
+            .collect())
+    }
+
+    /// Runs `tx_data` against the current state without committing it,
+    /// failing the same way [`Self::execute`] would.
+    fn dry_run(&self, tx_data: &TransactionData) -> Result<(), String> {
+        let state = self.inner.state.lock();
+        self.simulate(&state, tx_data, tx_data.digest()).map(|_| ())
+    }
+
+    /// The state and events that applying `tx_data` to `state` would produce,
+    /// or `None` if it is not a programmable transaction.
+    fn simulate(
+        &self,
+        state: &LocalSuiState,
+        tx_data: &TransactionData,
+        digest: TransactionDigest,
+    ) -> Result<Option<(LocalSuiState, Vec<EmittedEvent>)>, String> {
+        let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+            return Ok(None);
+        };
+        let mut next_state = state.clone();
+        let mut events = vec![];
+        for (index, command) in pt.commands.iter().enumerate() {
+            if let Command::MoveCall(call) = command {
+                self.apply_move_call(&mut next_state, pt, index, call, digest, &mut events)?;
+            }
+        }
+        Ok(Some((next_state, events)))
+    }
+
+    fn apply_move_call(
+        &self,
+        state: &mut LocalSuiState,
+        pt: &ProgrammableTransaction,
+        command_index: usize,
+        call: &ProgrammableMoveCall,
+        digest: TransactionDigest,
+        events: &mut Vec<EmittedEvent>,
+    ) -> Result<(), String> {
+        let packages = &self.inner.ika_network_config.packages;
+        let coordinator_call = |function: &IdentStr| {
+            is_call(
+                call,
+                packages.ika_dwallet_2pc_mpc_package_id,
+                DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME,
+                function,
+            )
+        };
+        let system_call = |function: &IdentStr| {
+            is_call(
+                call,
+                packages.ika_system_package_id,
+                SYSTEM_MODULE_NAME,
+                function,
+            )
+        };
+
+        if coordinator_call(PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME) {
+            let message = vector_argument(pt, command_index, call.arguments.get(3))?;
+            verify_certificate(
+                &state.coordinator.active_committee,
+                IntentScope::DWalletCheckpointMessage,
+                state.coordinator.current_epoch,
+                &pure_argument::<Vec<u8>>(pt, call.arguments.get(1))?,
+                &pure_argument::<Vec<u8>>(pt, call.arguments.get(2))?,
+                &message,
+            )?;
+            let message = bcs::from_bytes::<DWalletCheckpointMessage>(&message)
+                .map_err(|e| format!("invalid dWallet checkpoint message: {e}"))?;
+            self.process_dwallet_checkpoint(state, message, events)
+        } else if system_call(PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME) {
+            let message = vector_argument(pt, command_index, call.arguments.get(3))?;
+            verify_certificate(
+                &state.system.validator_set.active_committee,
+                IntentScope::SystemCheckpointMessage,
+                state.system.epoch,
+                &pure_argument::<Vec<u8>>(pt, call.arguments.get(1))?,
+                &pure_argument::<Vec<u8>>(pt, call.arguments.get(2))?,
+                &message,
+            )?;
+            let message = bcs::from_bytes::<SystemCheckpointMessage>(&message)
+                .map_err(|e| format!("invalid system checkpoint message: {e}"))?;
+            process_system_checkpoint(state, message)
+        } else if system_call(INITIATE_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME) {
+            let system = &mut state.system;
+            if now_ms() < system.epoch_start_timestamp_ms + system.epoch_duration_ms / 2 {
+                return Err("EHaveNotReachedMidEpochTime".to_string());
+            }
+            if system.validator_set.next_epoch_committee.is_some() {
+                return Err("EAlreadyInitiatedMidEpochReconfiguration".to_string());
+            }
+            // The stand-in has no staking, so the committee carries over to the next epoch.
+            system.validator_set.next_epoch_committee =
+                Some(system.validator_set.active_committee.clone());
+            Ok(())
+        } else if coordinator_call(INITIATE_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME) {
+            let Some(next_epoch_committee) =
+                state.system.validator_set.next_epoch_committee.clone()
+            else {
+                return Err("EHaveNotReachedMidEpochTime".to_string());
+            };
+            let coordinator = &mut state.coordinator;
+            if coordinator.next_epoch_active_committee.is_some() {
+                return Err("EAlreadyInitiatedMidEpochReconfiguration".to_string());
+            }
+            let pricing = &mut coordinator.pricing_and_fee_management;
+            pricing.calculation_votes = Some(PricingInfoCalculationVotes {
+                bls_committee: next_epoch_committee.clone(),
+                default_pricing: pricing.default.clone(),
+                working_pricing: pricing.default.clone(),
+            });
+            coordinator.next_epoch_active_committee = Some(next_epoch_committee);
+            Ok(())
+        } else if coordinator_call(
+            REQUEST_NETWORK_ENCRYPTION_KEY_MID_EPOCH_RECONFIGURATION_FUNCTION_NAME,
+        ) {
+            let key_id = pure_argument::<ObjectID>(pt, call.arguments.get(1))?;
+            self.request_network_encryption_key_reconfiguration(state, key_id, events)
+        } else if coordinator_call(CALCULATE_PRICING_VOTES_FUNCTION_NAME) {
+            // Without validator pricing votes the calculation keeps the default pricing.
+            // The notifier calculates every protocol in one transaction, so the first call
+            // completes the calculation and the rest find nothing left to calculate.
+            let pricing = &mut state.coordinator.pricing_and_fee_management;
+            if let Some(votes) = pricing.calculation_votes.take() {
+                pricing.current = votes.working_pricing;
+            }
+            Ok(())
+        } else if coordinator_call(REQUEST_LOCK_EPOCH_SESSIONS_FUNCTION_NAME) {
+            if !is_end_epoch_time(&state.system) {
+                return Err("EHaveNotReachedEndEpochTime".to_string());
+            }
+            state
+                .coordinator
+                .sessions_manager
+                .locked_last_user_initiated_session_to_complete_in_current_epoch = true;
+            Ok(())
+        } else if system_call(INITIATE_ADVANCE_EPOCH_FUNCTION_NAME) {
+            if !is_end_epoch_time(&state.system) {
+                return Err("EHaveNotReachedEndEpochTime".to_string());
+            }
+            Ok(())
+        } else if coordinator_call(ADVANCE_EPOCH_FUNCTION_NAME) {
+            let new_epoch = state.system.epoch + 1;
+            advance_coordinator_epoch(state, new_epoch)
+        } else if system_call(ADVANCE_EPOCH_FUNCTION_NAME) {
+            advance_system_epoch(&mut state.system, digest)?;
+            events.push(self.epoch_info_event(state));
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn process_dwallet_checkpoint(
+        &self,
+        state: &mut LocalSuiState,
+        message: DWalletCheckpointMessage,
+        events: &mut Vec<EmittedEvent>,
+    ) -> Result<(), String> {
+        let coordinator = &mut state.coordinator;
+        if message.epoch != coordinator.current_epoch {
+            return Err("EIncorrectEpochInCheckpoint".to_string());
+        }
+        if coordinator.last_processed_checkpoint_sequence_number + 1 != message.sequence_number {
+            return Err("EWrongCheckpointSequenceNumber".to_string());
+        }
+        coordinator.last_processed_checkpoint_sequence_number = message.sequence_number;
+        coordinator.total_messages_processed += message.messages.len() as u64;
+        events.push((
+            self.dwallet_2pc_mpc_event_type(
+                COORDINATOR_INNER_MODULE_NAME,
+                "DWalletCheckpointInfoEvent",
+            ),
+            bcs_bytes(&DWalletCheckpointInfoEvent {
+                epoch: message.epoch,
+                sequence_number: message.sequence_number,
+            }),
+        ));
+
+        for kind in message.messages {
+            match &kind {
+                DWalletCheckpointMessageKind::RespondDWalletMPCNetworkDKGOutput(output) => {
+                    let key_id = object_id_from_bytes(&output.dwallet_network_encryption_key_id)?;
+                    let key = network_key(state, key_id)?;
+                    if key.key.state != DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG {
+                        return Err("EWrongState".to_string());
+                    }
+                    if output.rejected {
+                        // A rejected network DKG is restarted from scratch.
+                        key.network_dkg_public_output.clear();
+                        key.key.network_dkg_public_output.contents.size = 0;
+                        let params_for_network = key.key.dkg_params_for_network.clone();
+                        complete_session(
+                            state,
+                            SessionType::System,
+                            output.session_sequence_number,
+                        )?;
+                        let (_, event) = self.initiate_session(
+                            state,
+                            SessionType::System,
+                            ObjectID::random().into_bytes(),
+                            DWalletNetworkDKGEncryptionKeyRequestEvent {
+                                dwallet_network_encryption_key_id: key_id,
+                                params_for_network,
+                            },
+                        );
+                        events.push(event);
+                    } else {
+                        key.network_dkg_public_output
+                            .extend_from_slice(&output.public_output);
+                        key.key.network_dkg_public_output.contents.size += 1;
+                        // Only the last chunk completes the session.
+                        if output.is_last {
+                            key.key.supported_curves = output.supported_curves.clone();
+                            key.key.state = DWalletNetworkEncryptionKeyState::NetworkDKGCompleted;
+                            complete_session(
+                                state,
+                                SessionType::System,
+                                output.session_sequence_number,
+                            )?;
+                            state.session_outputs.insert(
+                                (SessionType::System, output.session_sequence_number),
+                                kind.clone(),
+                            );
+                        }
+                    }
+                }
+                DWalletCheckpointMessageKind::RespondDWalletMPCNetworkReconfigurationOutput(
+                    output,
+                ) => {
+                    let key_id = object_id_from_bytes(&output.dwallet_network_encryption_key_id)?;
+                    let next_epoch = state.coordinator.current_epoch + 1;
+                    let key = network_key(state, key_id)?;
+                    if key.key.state
+                        != DWalletNetworkEncryptionKeyState::AwaitingNetworkReconfiguration
+                    {
+                        return Err("EWrongState".to_string());
+                    }
+                    if output.rejected {
+                        key.reconfiguration_public_outputs
+                            .insert(next_epoch, vec![]);
+                        complete_session(
+                            state,
+                            SessionType::System,
+                            output.session_sequence_number,
+                        )?;
+                        let (_, event) = self.initiate_session(
+                            state,
+                            SessionType::System,
+                            ObjectID::random().into_bytes(),
+                            DWalletEncryptionKeyReconfigurationRequestEvent {
+                                dwallet_network_encryption_key_id: key_id,
+                            },
+                        );
+                        events.push(event);
+                    } else {
+                        key.reconfiguration_public_outputs
+                            .entry(next_epoch)
+                            .or_default()
+                            .extend_from_slice(&output.public_output);
+                        if output.is_last {
+                            key.key.supported_curves = output.supported_curves.clone();
+                            key.key.state =
+                                DWalletNetworkEncryptionKeyState::NetworkReconfigurationCompleted;
+                            state
+                                .coordinator
+                                .epoch_dwallet_network_encryption_keys_reconfiguration_completed +=
+                                1;
+                            complete_session(
+                                state,
+                                SessionType::System,
+                                output.session_sequence_number,
+                            )?;
+                            state.session_outputs.insert(
+                                (SessionType::System, output.session_sequence_number),
+                                kind.clone(),
+                            );
+                        }
+                    }
+                }
+                DWalletCheckpointMessageKind::SetMaxActiveSessionsBuffer(buffer) => {
+                    state
+                        .coordinator
+                        .sessions_manager
+                        .max_active_sessions_buffer = *buffer;
+                }
+                DWalletCheckpointMessageKind::SetGasFeeReimbursementSuiSystemCallValue(value) => {
+                    state
+                        .coordinator
+                        .pricing_and_fee_management
+                        .gas_fee_reimbursement_sui_system_call_value = *value;
+                }
+                DWalletCheckpointMessageKind::EndOfPublish => {
+                    state.coordinator.received_end_of_publish = true;
+                }
+                _ => {
+                    let session_sequence_number = user_session_sequence_number(&kind)
+                        .ok_or_else(|| format!("unexpected checkpoint message {}", kind.name()))?;
+                    complete_session(state, SessionType::User, session_sequence_number)?;
+                    state
+                        .session_outputs
+                        .insert((SessionType::User, session_sequence_number), kind);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn request_network_encryption_key_reconfiguration(
+        &self,
+        state: &mut LocalSuiState,
+        key_id: ObjectID,
+        events: &mut Vec<EmittedEvent>,
+    ) -> Result<(), String> {
+        if state.coordinator.next_epoch_active_committee.is_none() {
+            return Err("EHaveNotInitiatedMidEpochReconfiguration".to_string());
+        }
+        let next_epoch = state.coordinator.current_epoch + 1;
+        let key = network_key(state, key_id)?;
+        key.key.state = match key.key.state {
+            DWalletNetworkEncryptionKeyState::NetworkDKGCompleted
+            | DWalletNetworkEncryptionKeyState::NetworkReconfigurationCompleted => {
+                DWalletNetworkEncryptionKeyState::AwaitingNetworkReconfiguration
+            }
+            _ => return Err("EWrongState".to_string()),
+        };
+        key.key.reconfiguration_public_outputs.size += 1;
+        key.reconfiguration_public_outputs
+            .insert(next_epoch, vec![]);
+        let (_, event) = self.initiate_session(
+            state,
+            SessionType::System,
+            ObjectID::random().into_bytes(),
+            DWalletEncryptionKeyReconfigurationRequestEvent {
+                dwallet_network_encryption_key_id: key_id,
+            },
+        );
+        events.push(event);
+        Ok(())
+    }
+
+    /// Adds a session to its sessions keeper, like `sessions_manager::initiate_session`,
+    /// returning its sequence number and the event announcing it.
+    fn initiate_session<E>(
+        &self,
+        state: &mut LocalSuiState,
+        session_type: SessionType,
+        session_identifier_preimage: [u8; SessionIdentifier::LENGTH],
+        event_data: E,
+    ) -> (u64, EmittedEvent)
+    where
+        E: DWalletSessionEventTrait + Serialize,
+    {
+        let epoch = state.coordinator.current_epoch;
+        let sessions_manager = &mut state.coordinator.sessions_manager;
+        let keeper = match session_type {
+            SessionType::User => &mut sessions_manager.user_sessions_keeper,
+            SessionType::System => &mut sessions_manager.system_sessions_keeper,
+        };
+        let session_sequence_number = keeper.next_session_sequence_number;
+        keeper.next_session_sequence_number += 1;
+        keeper.started_sessions_count += 1;
+        keeper.sessions.size += 1;
+        keeper.session_events.size += 1;
+
+        let mut event = new_dwallet_session_event(
+            session_type == SessionType::System,
+            session_sequence_number,
+            session_identifier_preimage.to_vec(),
+            event_data,
+        );
+        event.epoch = epoch;
+        let type_ = DWalletSessionEvent::<E>::type_(&self.inner.ika_network_config);
+        let contents = bcs_bytes(&event);
+        let session_events = match session_type {
+            SessionType::User => {
+                update_last_user_initiated_session_to_complete(sessions_manager);
+                &mut state.user_session_events
+            }
+            SessionType::System => &mut state.system_session_events,
+        };
+        session_events.insert(
+            session_sequence_number,
+            DBSuiEvent {
+                type_: type_.clone(),
+                contents: contents.clone(),
+                pulled: true,
+            },
+        );
+        (session_sequence_number, (type_, contents))
+    }
+
+    /// Serves `state` through the mocked client.
+    fn publish(&self, state: &LocalSuiState) {
+        let client = &self.inner.client;
+        client.set_latest_checkpoint_sequence_number(state.latest_checkpoint_sequence_number);
+        client.set_system_inner(state.system.clone());
+        client.set_dwallet_coordinator_inner(state.coordinator.clone());
+        let sessions_manager = &state.coordinator.sessions_manager;
+        client.set_uncompleted_events(
+            sessions_manager
+                .user_sessions_keeper
+                .session_events
+                .id
+                .id
+                .bytes,
+            state.user_session_events.values().cloned().collect(),
+        );
+        client.set_uncompleted_events(
+            sessions_manager
+                .system_sessions_keeper
+                .session_events
+                .id
+                .id
+                .bytes,
+            state.system_session_events.values().cloned().collect(),
+        );
+        let epoch = state.coordinator.current_epoch;
+        for key in state.network_keys.values() {
+            // As on Sui, the key data of an epoch holds the reconfiguration output for
+            // that epoch, unless the key was created in it.
+            let current_reconfiguration_public_output = if key.key.dkg_at_epoch == epoch {
+                vec![]
+            } else {
+                key.reconfiguration_public_outputs
+                    .get(&epoch)
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            // The outputs are also served chunk by chunk, as read by the nodes.
+            client.set_table_vec(
+                key.key.network_dkg_public_output.contents.id,
+                key.network_dkg_public_output.clone(),
+            );
+            for (output_epoch, output) in &key.reconfiguration_public_outputs {
+                let outputs_table_id = key.key.reconfiguration_public_outputs.id;
+                let output_table_id =
+                    reconfiguration_public_output_table_id(outputs_table_id, *output_epoch);
+                client.set_reconfiguration_public_output(
+                    outputs_table_id,
+                    *output_epoch,
+                    output_table_id,
+                );
+                client.set_table_vec(output_table_id, output.clone());
+            }
+            client.add_network_encryption_key(
+                key.key.clone(),
+                DWalletNetworkEncryptionKeyData {
+                    id: key.key.id,
+                    current_epoch: epoch,
+                    current_reconfiguration_public_output,
+                    network_dkg_public_output: key.network_dkg_public_output.clone(),
+                    state: key.key.state.clone(),
+                },
+            );
+        }
+    }
+
+    fn epoch_info_event(&self, state: &LocalSuiState) -> EmittedEvent {
+        let packages = &self.inner.ika_network_config.packages;
+        (
+            StructTag {
+                address: *packages.ika_system_package_id,
+                module: Identifier::new(SYSTEM_INNER_MODULE_NAME)
+                    .expect("the module name is a valid identifier"),
+                name: Identifier::new("SystemEpochInfoEvent")
+                    .expect("the event name is a valid identifier"),
+                type_params: vec![],
+            },
+            bcs_bytes(&SystemEpochInfoEvent {
+                epoch: state.system.epoch,
+                protocol_version: state.system.protocol_version,
+                total_stake: state.system.validator_set.total_stake,
+                stake_subsidy_amount: 0,
+                total_computation_fees: 0,
+                total_stake_rewards_distributed: 0,
+            }),
+        )
+    }
+
+    fn dwallet_2pc_mpc_event_type(&self, module: &str, name: &str) -> StructTag {
+        StructTag {
+            address: *self
+                .inner
+                .ika_network_config
+                .packages
+                .ika_dwallet_2pc_mpc_package_id,
+            module: Identifier::new(module).expect("the module name is a valid identifier"),
+            name: Identifier::new(name).expect("the event name is a valid identifier"),
+            type_params: vec![],
+        }
+    }
+}
+
+const CALCULATE_PRICING_VOTES_FUNCTION_NAME: &IdentStr =
+    move_core_types::ident_str!("calculate_pricing_votes");
+const COORDINATOR_INNER_MODULE_NAME: &str = "coordinator_inner";
+const SYSTEM_INNER_MODULE_NAME: &str = "system_inner";
+
+fn process_system_checkpoint(
+    state: &mut LocalSuiState,
+    message: SystemCheckpointMessage,
+) -> Result<(), String> {
+    let system = &mut state.system;
+    if message.epoch != system.epoch {
+        return Err("EIncorrectEpochInSystemCheckpoint".to_string());
+    }
+    if system.last_processed_checkpoint_sequence_number + 1 != message.sequence_number {
+        return Err("EWrongSystemCheckpointSequenceNumber".to_string());
+    }
+    system.last_processed_checkpoint_sequence_number = message.sequence_number;
+    system.total_messages_processed += message.messages.len() as u64;
+    for kind in message.messages {
+        match kind {
+            SystemCheckpointMessageKind::SetNextConfigVersion(version) => {
+                system.next_protocol_version = Some(version.as_u64());
+            }
+            SystemCheckpointMessageKind::SetEpochDurationMs(epoch_duration_ms) => {
+                system.epoch_duration_ms = epoch_duration_ms;
+            }
+            SystemCheckpointMessageKind::EndOfPublish => {
+                system.received_end_of_publish = true;
+            }
+            // The stand-in does not model staking, rewards or package upgrades.
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Mirrors `coordinator_inner::advance_epoch`.
+fn advance_coordinator_epoch(state: &mut LocalSuiState, new_epoch: EpochId) -> Result<(), String> {
+    let coordinator = &mut state.coordinator;
+    if !coordinator.received_end_of_publish {
+        return Err("ECannotAdvanceEpoch".to_string());
+    }
+    if coordinator.epoch_dwallet_network_encryption_keys_reconfiguration_completed
+        != coordinator.dwallet_network_encryption_keys.size
+    {
+        return Err("ENotAllNetworkEncryptionKeysReconfigurationCompleted".to_string());
+    }
+    if coordinator
+        .pricing_and_fee_management
+        .calculation_votes
+        .is_some()
+    {
+        return Err("EPricingCalculationVotesMustBeCompleted".to_string());
+    }
+    let sessions_manager = &mut coordinator.sessions_manager;
+    let all_sessions_completed = sessions_manager
+        .locked_last_user_initiated_session_to_complete_in_current_epoch
+        && sessions_manager
+            .user_sessions_keeper
+            .completed_sessions_count
+            == sessions_manager.last_user_initiated_session_to_complete_in_current_epoch
+        && sessions_manager
+            .system_sessions_keeper
+            .completed_sessions_count
+            == sessions_manager
+                .system_sessions_keeper
+                .started_sessions_count;
+    if !all_sessions_completed {
+        return Err("ENotAllCurrentEpochSessionsCompleted".to_string());
+    }
+    let Some(next_epoch_active_committee) = coordinator.next_epoch_active_committee.take() else {
+        return Err("EHaveNotInitiatedMidEpochReconfiguration".to_string());
+    };
+    coordinator.received_end_of_publish = false;
+    coordinator.epoch_dwallet_network_encryption_keys_reconfiguration_completed = 0;
+    coordinator.previous_epoch_last_checkpoint_sequence_number =
+        coordinator.last_processed_checkpoint_sequence_number;
+    sessions_manager.locked_last_user_initiated_session_to_complete_in_current_epoch = false;
+    update_last_user_initiated_session_to_complete(sessions_manager);
+    coordinator.current_epoch = new_epoch;
+    coordinator.active_committee = next_epoch_active_committee;
+    Ok(())
+}
+
+/// Mirrors `system_inner::advance_epoch`, without distributing rewards.
+fn advance_system_epoch(
+    system: &mut SystemInnerV1,
+    digest: TransactionDigest,
+) -> Result<(), String> {
+    if !system.received_end_of_publish {
+        return Err("EHaveNotReachedEndEpochTime".to_string());
+    }
+    let Some(next_epoch_committee) = system.validator_set.next_epoch_committee.take() else {
+        return Err("EHaveNotInitiatedMidEpochReconfiguration".to_string());
+    };
+    system.received_end_of_publish = false;
+    system.epoch_start_tx_digest = digest.inner().to_vec();
+    system.epoch_start_timestamp_ms = now_ms();
+    system.previous_epoch_last_checkpoint_sequence_number =
+        system.last_processed_checkpoint_sequence_number;
+    system.epoch += 1;
+    if let Some(next_protocol_version) = system.next_protocol_version.take() {
+        system.protocol_version = next_protocol_version;
+    }
+    system.validator_set.previous_committee = std::mem::replace(
+        &mut system.validator_set.active_committee,
+        next_epoch_committee,
+    );
+    Ok(())
+}
+
+/// Removes a completed session from its sessions keeper, like `sessions_manager::complete_session`.
+fn complete_session(
+    state: &mut LocalSuiState,
+    session_type: SessionType,
+    session_sequence_number: u64,
+) -> Result<(), String> {
+    let session_events = match session_type {
+        SessionType::User => &mut state.user_session_events,
+        SessionType::System => &mut state.system_session_events,
+    };
+    if session_events.remove(&session_sequence_number).is_none() {
+        return Err(format!(
+            "{session_type:?} session {session_sequence_number} does not exist"
+        ));
+    }
+    let sessions_manager = &mut state.coordinator.sessions_manager;
+    let keeper = match session_type {
+        SessionType::User => &mut sessions_manager.user_sessions_keeper,
+        SessionType::System => &mut sessions_manager.system_sessions_keeper,
+    };
+    keeper.completed_sessions_count += 1;
+    keeper.sessions.size -= 1;
+    keeper.session_events.size -= 1;
+    if session_type == SessionType::User {
+        update_last_user_initiated_session_to_complete(sessions_manager);
+    }
+    Ok(())
+}
+
+/// Mirrors `sessions_manager::update_last_user_initiated_session_to_complete_in_current_epoch`.
+fn update_last_user_initiated_session_to_complete(sessions_manager: &mut SessionsManager) {
+    if sessions_manager.locked_last_user_initiated_session_to_complete_in_current_epoch {
+        return;
+    }
+    let keeper = &sessions_manager.user_sessions_keeper;
+    let last_session_to_complete = (keeper.completed_sessions_count
+        + sessions_manager.max_active_sessions_buffer)
+        .min(keeper.next_session_sequence_number - 1);
+    if sessions_manager.last_user_initiated_session_to_complete_in_current_epoch
+        < last_session_to_complete
+    {
+        sessions_manager.last_user_initiated_session_to_complete_in_current_epoch =
+            last_session_to_complete;
+    }
+}
+
+/// The sequence number of the user session that `kind` completes.
+fn user_session_sequence_number(kind: &DWalletCheckpointMessageKind) -> Option<u64> {
+    match kind {
+        DWalletCheckpointMessageKind::RespondDWalletDKGFirstRoundOutput(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletDKGSecondRoundOutput(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletEncryptedUserShare(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondMakeDWalletUserSecretKeySharesPublic(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletImportedKeyVerificationOutput(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletPresign(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletSign(output) => {
+            Some(output.session_sequence_number)
+        }
+        DWalletCheckpointMessageKind::RespondDWalletPartialSignatureVerificationOutput(output) => {
+            Some(output.session_sequence_number)
+        }
+        _ => None,
+    }
+}
+
+fn network_key(
+    state: &mut LocalSuiState,
+    key_id: ObjectID,
+) -> Result<&mut LocalNetworkKey, String> {
+    state
+        .network_keys
+        .get_mut(&key_id)
+        .ok_or_else(|| "EDWalletNetworkEncryptionKeyNotExist".to_string())
+}
+
+fn is_end_epoch_time(system: &SystemInnerV1) -> bool {
+    now_ms() >= system.epoch_start_timestamp_ms + system.epoch_duration_ms
+}
+
+fn is_call(
+    call: &ProgrammableMoveCall,
+    package: ObjectID,
+    module: &IdentStr,
+    function: &IdentStr,
+) -> bool {
+    call.package == package
+        && call.module.as_str() == module.as_str()
+        && call.function.as_str() == function.as_str()
+}
+
+/// Verifies the quorum certificate of a checkpoint `message` of `epoch` the way the Move
+/// `bls_committee::verify_certificate` does: the members set in `signers_bitmap` must reach
+/// the quorum threshold of `committee`, and `signature` must be their aggregate signature over
+/// `intent || message || epoch`.
+fn verify_certificate(
+    committee: &BlsCommittee,
+    scope: IntentScope,
+    epoch: EpochId,
+    signature: &[u8],
+    signers_bitmap: &[u8],
+    message: &[u8],
+) -> Result<(), String> {
+    let members = &committee.members;
+    if signers_bitmap.len() != members.len().div_ceil(8) {
+        return Err("EInvalidBitmap".to_string());
+    }
+    let mut signers = vec![];
+    for (byte_index, byte) in signers_bitmap.iter().enumerate() {
+        for bit_index in 0..8 {
+            if (byte >> bit_index) & 1 == 0 {
+                continue;
+            }
+            // Bits past the last member must be unset, so that a certificate has one bitmap.
+            let member = members
+                .get(byte_index * 8 + bit_index)
+                .ok_or_else(|| "EInvalidBitmap".to_string())?;
+            signers.push(member);
+        }
+    }
+    if (signers.len() as u64) < committee.quorum_threshold {
+        return Err("ENotEnoughStake".to_string());
+    }
+
+    let signature = AggregateAuthoritySignature::from_bytes(signature)
+        .map_err(|_| "EInvalidSignatureLength".to_string())?;
+    let public_keys = signers
+        .iter()
+        .map(|member| AuthorityPublicKey::from_bytes(member.protocol_pubkey.bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("invalid committee member protocol public key: {e}"))?;
+    let mut intent_bytes = Intent::ika_app(scope).to_bytes().to_vec();
+    intent_bytes.extend_from_slice(message);
+    intent_bytes.extend(bcs_bytes(&epoch));
+    signature
+        .verify(&public_keys, &intent_bytes)
+        .map_err(|_| "EInvalidSignature".to_string())
+}
+
+/// Decodes the pure input `argument` refers to.
+fn pure_argument<T: DeserializeOwned>(
+    pt: &ProgrammableTransaction,
+    argument: Option<&Argument>,
+) -> Result<T, String> {
+    match argument {
+        Some(Argument::Input(index)) => match pt.inputs.get(*index as usize) {
+            Some(CallArg::Pure(bytes)) => {
+                bcs::from_bytes(bytes).map_err(|e| format!("invalid pure argument: {e}"))
+            }
+            input => Err(format!("expected a pure input, got {input:?}")),
+        },
+        _ => Err(format!("expected an input argument, got {argument:?}")),
+    }
+}
+
+/// The value of the `vector<u8>` `argument` of the command at `command_index`,
+/// including the chunks appended to it by the `vector::append` calls before that command.
+fn vector_argument(
+    pt: &ProgrammableTransaction,
+    command_index: usize,
+    argument: Option<&Argument>,
+) -> Result<Vec<u8>, String> {
+    let mut bytes = pure_argument::<Vec<u8>>(pt, argument)?;
+    let appended_chunks = pt.commands[..command_index]
+        .iter()
+        .filter_map(|command| match command {
+            Command::MoveCall(call)
+                if is_call(
+                    call,
+                    MOVE_STDLIB_PACKAGE_ID,
+                    VECTOR_MODULE_NAME,
+                    APPEND_VECTOR_FUNCTION_NAME,
+                ) && call.arguments.first() == argument =>
+            {
+                Some(call.arguments.get(1))
+            }
+            _ => None,
+        });
+    for chunk in appended_chunks {
+        bytes.extend(pure_argument::<Vec<u8>>(pt, chunk)?);
+    }
+    Ok(bytes)
+}
+
+fn object_id_from_bytes(bytes: &[u8]) -> Result<ObjectID, String> {
+    ObjectID::from_bytes(bytes).map_err(|e| format!("invalid object ID: {e}"))
+}
+
+fn bcs_bytes<T: Serialize>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).expect("serializing a Move event cannot fail")
+}
+
+fn new_table() -> Table {
+    Table {
+        id: ObjectID::random(),
+        size: 0,
+    }
+}
+
+/// The ID of the `TableVec` holding the reconfiguration public output of `epoch`
+/// in the reconfiguration public outputs table `table_id`.
+fn reconfiguration_public_output_table_id(table_id: ObjectID, epoch: EpochId) -> ObjectID {
+    derive_dynamic_field_id(table_id, &TypeTag::U64, &bcs_bytes(&epoch))
+        .expect("deriving the ID of a table entry cannot fail")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is after the Unix epoch")
+        .as_millis() as u64
+}
+
+fn sui_event(id: EventID, sender: SuiAddress, type_: StructTag, contents: Vec<u8>) -> SuiEvent {
+    SuiEvent {
+        id,
+        package_id: ObjectID::from(type_.address),
+        transaction_module: type_.module.clone(),
+        sender,
+        type_,
+        parsed_json: Default::default(),
+        bcs: BcsEvent::new(contents),
+        timestamp_ms: Some(now_ms()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuiClientInner;
+    use dwallet_mpc_types::dwallet_mpc::MPCDataV1;
+    use fastcrypto::traits::{KeyPair, Signer};
+    use ika_types::crypto::{AuthorityKeyPair, random_committee_key_pairs_of_size};
+    use ika_types::message::MPCNetworkDKGOutput;
+    use shared_crypto::intent::{Intent, IntentMessage};
+    use sui_json_rpc_types::{EventFilter, SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
+    use sui_types::crypto::{AccountKeyPair, Signature, get_key_pair};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::TransactionData;
+
+    const GAS_BUDGET: u64 = 1_000_000_000;
+    const COMMITTEE_SIZE: usize = 4;
+
+    /// The protocol key pairs of the committee, generated from a fixed seed.
+    fn committee_key_pairs() -> Vec<AuthorityKeyPair> {
+        random_committee_key_pairs_of_size(COMMITTEE_SIZE)
+    }
+
+    fn start_network(name: &str) -> LocalSuiNetwork {
+        let validators = committee_key_pairs()
+            .iter()
+            .enumerate()
+            .map(|(index, key_pair)| LocalSuiValidator {
+                name: format!("validator-{index}"),
+                protocol_pubkey_bytes: key_pair.public().as_bytes().to_vec(),
+                network_pubkey_bytes: vec![],
+                consensus_pubkey_bytes: vec![],
+                network_address: String::new(),
+                p2p_address: String::new(),
+                consensus_address: String::new(),
+                mpc_data: VersionedMPCData::V1(MPCDataV1 {
+                    class_groups_public_key_and_proof: vec![],
+                }),
+            })
+            .collect();
+        LocalSuiNetwork::start(name, validators, 60_000, 1).unwrap()
+    }
+
+    /// Signs `intent || message || epoch` with the key pairs of the committee members in
+    /// `signers`, returning the aggregate signature and the signers bitmap.
+    fn certify(epoch: EpochId, message: &[u8], signers: &[usize]) -> (Vec<u8>, Vec<u8>) {
+        let key_pairs = committee_key_pairs();
+        let mut intent_bytes =
+            ika_types::intent::Intent::ika_app(IntentScope::DWalletCheckpointMessage)
+                .to_bytes()
+                .to_vec();
+        intent_bytes.extend_from_slice(message);
+        intent_bytes.extend(bcs::to_bytes(&epoch).unwrap());
+        let signatures = signers
+            .iter()
+            .map(|signer| key_pairs[*signer].sign(&intent_bytes))
+            .collect::<Vec<_>>();
+        let signature = AggregateAuthoritySignature::aggregate(&signatures).unwrap();
+        let mut signers_bitmap = vec![0u8; COMMITTEE_SIZE.div_ceil(8)];
+        for signer in signers {
+            signers_bitmap[signer / 8] |= 1 << (signer % 8);
+        }
+        (signature.as_bytes().to_vec(), signers_bitmap)
+    }
+
+    /// Submits a `coordinator::process_checkpoint_message_by_quorum` call with `message`,
+    /// `signature` and `signers_bitmap`, returning whether it succeeded.
+    async fn submit_dwallet_checkpoint(
+        network: &LocalSuiNetwork,
+        message: &[u8],
+        signature: Vec<u8>,
+        signers_bitmap: Vec<u8>,
+    ) -> bool {
+        let (sender, key): (SuiAddress, AccountKeyPair) = get_key_pair();
+        let gas = network.fund(sender, GAS_BUDGET * 10);
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        // The coordinator object is not read by the stand-in.
+        let arguments = vec![
+            ptb.pure(0u64).unwrap(),
+            ptb.pure(signature).unwrap(),
+            ptb.pure(signers_bitmap).unwrap(),
+            ptb.pure(message.to_vec()).unwrap(),
+        ];
+        ptb.programmable_move_call(
+            network
+                .ika_network_config()
+                .packages
+                .ika_dwallet_2pc_mpc_package_id,
+            DWALLET_2PC_MPC_COORDINATOR_MODULE_NAME.into(),
+            PROCESS_CHECKPOINT_MESSAGE_BY_QUORUM_FUNCTION_NAME.into(),
+            vec![],
+            arguments,
+        );
+        let tx_data =
+            TransactionData::new_programmable(sender, vec![gas], ptb.finish(), GAS_BUDGET, 1000);
+        let signature = Signature::new_secure(
+            &IntentMessage::new(Intent::sui_transaction(), &tx_data),
+            &key,
+        );
+        let response = network
+            .client()
+            .execute_transaction_block_with_effects(Transaction::from_data(
+                tx_data,
+                vec![signature],
+            ))
+            .await
+            .unwrap();
+        matches!(
+            response.effects.unwrap().status(),
+            SuiExecutionStatus::Success
+        )
+    }
+
+    /// Submits `message` certified by the whole committee, returning whether it succeeded.
+    async fn process_dwallet_checkpoint(
+        network: &LocalSuiNetwork,
+        message: &DWalletCheckpointMessage,
+    ) -> bool {
+        let message = bcs::to_bytes(message).unwrap();
+        let signers = (0..COMMITTEE_SIZE).collect::<Vec<_>>();
+        let (signature, signers_bitmap) = certify(1, &message, &signers);
+        submit_dwallet_checkpoint(network, &message, signature, signers_bitmap).await
+    }
+
+    #[tokio::test]
+    async fn requested_sessions_are_announced_and_pending() {
+        let network = start_network("requested-sessions");
+        let client = network.client();
+        let key_id = network.request_network_encryption_key_dkg();
+
+        let coordinator = network.inner.state.lock().coordinator.clone();
+        let system_sessions = &coordinator.sessions_manager.system_sessions_keeper;
+        assert_eq!(system_sessions.started_sessions_count, 1);
+        let pending = client
+            .get_uncompleted_events(system_sessions.session_events.id.id.bytes)
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        let event: DWalletSessionEvent<DWalletNetworkDKGEncryptionKeyRequestEvent> =
+            bcs::from_bytes(&pending[0].contents).unwrap();
+        assert_eq!(event.event_data.dwallet_network_encryption_key_id, key_id);
+        assert_eq!(event.session_sequence_number, 1);
+
+        let package = network
+            .ika_network_config()
+            .packages
+            .ika_dwallet_2pc_mpc_package_id;
+        let announced = client
+            .query_events(
+                EventFilter::MoveEventModule {
+                    package,
+                    module: Identifier::new("sessions_manager").unwrap(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(announced.data.len(), 1);
+        network.shutdown();
+    }
+
+    #[tokio::test]
+    async fn dwallet_checkpoint_completes_network_dkg() {
+        let network = start_network("network-dkg");
+        let key_id = network.request_network_encryption_key_dkg();
+        let output = |is_last| {
+            DWalletCheckpointMessageKind::RespondDWalletMPCNetworkDKGOutput(MPCNetworkDKGOutput {
+                dwallet_network_encryption_key_id: key_id.to_vec(),
+                public_output: vec![1, 2, 3],
+                supported_curves: vec![0],
+                is_last,
+                rejected: false,
+                session_sequence_number: 1,
+            })
+        };
+        let checkpoint = |sequence_number, messages| DWalletCheckpointMessage {
+            epoch: 1,
+            sequence_number,
+            messages,
+            checkpoint_commitments: vec![],
+        };
+
+        // Checkpoints must be processed in order, and a failed one has no effect.
+        assert!(!process_dwallet_checkpoint(&network, &checkpoint(2, vec![output(true)])).await);
+        assert_eq!(
+            network.network_encryption_key_state(key_id),
+            Some(DWalletNetworkEncryptionKeyState::AwaitingNetworkDKG)
+        );
+
+        assert!(process_dwallet_checkpoint(&network, &checkpoint(1, vec![output(false)])).await);
+        assert_eq!(network.session_output(SessionType::System, 1), None);
+        assert!(process_dwallet_checkpoint(&network, &checkpoint(2, vec![output(true)])).await);
+        assert_eq!(
+            network.network_encryption_key_state(key_id),
+            Some(DWalletNetworkEncryptionKeyState::NetworkDKGCompleted)
+        );
+        assert_eq!(
+            network
+                .wait_for_session_output(SessionType::System, 1, Duration::from_secs(1))
+                .await,
+            Some(output(true))
+        );
+
+        let state = network.inner.state.lock();
+        assert_eq!(
+            state.coordinator.last_processed_checkpoint_sequence_number,
+            2
+        );
+        let system_sessions = &state.coordinator.sessions_manager.system_sessions_keeper;
+        assert_eq!(system_sessions.completed_sessions_count, 1);
+        assert!(state.system_session_events.is_empty());
+        assert_eq!(
+            state.network_keys[&key_id].network_dkg_public_output,
+            vec![1, 2, 3, 1, 2, 3]
+        );
+        drop(state);
+        network.shutdown();
+    }
+
+    #[tokio::test]
+    async fn dwallet_checkpoints_need_a_quorum_certificate() {
+        let network = start_network("checkpoint-certificate");
+        let message = bcs::to_bytes(&DWalletCheckpointMessage {
+            epoch: 1,
+            sequence_number: 1,
+            messages: vec![],
+            checkpoint_commitments: vec![],
+        })
+        .unwrap();
+        let processed = || {
+            network
+                .inner
+                .state
+                .lock()
+                .coordinator
+                .last_processed_checkpoint_sequence_number
+        };
+
+        // Two of four validators are below the quorum threshold of three.
+        let (signature, signers_bitmap) = certify(1, &message, &[0, 1]);
+        assert!(!submit_dwallet_checkpoint(&network, &message, signature, signers_bitmap).await);
+
+        // The bitmap must match the validators that signed.
+        let (signature, _) = certify(1, &message, &[0, 1, 2]);
+        let (_, signers_bitmap) = certify(1, &message, &[1, 2, 3]);
+        assert!(!submit_dwallet_checkpoint(&network, &message, signature, signers_bitmap).await);
+
+        // The signature commits to the epoch.
+        let (signature, signers_bitmap) = certify(2, &message, &[0, 1, 2]);
+        assert!(!submit_dwallet_checkpoint(&network, &message, signature, signers_bitmap).await);
+
+        // Bits past the last committee member must be unset.
+        let (signature, _) = certify(1, &message, &[0, 1, 2]);
+        assert!(!submit_dwallet_checkpoint(&network, &message, signature, vec![0b1000_0111]).await);
+        assert_eq!(processed(), 0);
+
+        let (signature, signers_bitmap) = certify(1, &message, &[0, 1, 2]);
+        assert!(submit_dwallet_checkpoint(&network, &message, signature, signers_bitmap).await);
+        assert_eq!(processed(), 1);
+        network.shutdown();
+    }
+}
@@ -1,12 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
-use prometheus::{IntCounterVec, Registry, register_int_counter_vec_with_registry};
+use prometheus::{
+    HistogramVec, IntCounterVec, IntGaugeVec, Registry, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry,
+};
 use std::sync::Arc;
 
+const LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
 #[derive(Clone, Debug)]
 pub struct SuiClientMetrics {
     pub sui_rpc_errors: IntCounterVec,
+    pub sui_rpc_endpoint_latency_sec: HistogramVec,
+    pub sui_rpc_endpoint_errors: IntCounterVec,
+    pub sui_rpc_endpoint_checkpoint_lag: IntGaugeVec,
+    pub sui_rpc_endpoint_consecutive_errors: IntGaugeVec,
 }
 
 impl SuiClientMetrics {
@@ -19,6 +30,35 @@ impl SuiClientMetrics {
                 registry,
             )
             .unwrap(),
+            sui_rpc_endpoint_latency_sec: register_histogram_vec_with_registry!(
+                "sui_client_sui_rpc_endpoint_latency_sec",
+                "Latency of successful sui RPC requests, by endpoint",
+                &["endpoint"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            sui_rpc_endpoint_errors: register_int_counter_vec_with_registry!(
+                "sui_client_sui_rpc_endpoint_errors",
+                "Total number of failed sui RPC requests, by endpoint and RPC method",
+                &["endpoint", "method"],
+                registry,
+            )
+            .unwrap(),
+            sui_rpc_endpoint_checkpoint_lag: register_int_gauge_vec_with_registry!(
+                "sui_client_sui_rpc_endpoint_checkpoint_lag",
+                "Number of checkpoints each endpoint lags behind the most up-to-date endpoint",
+                &["endpoint"],
+                registry,
+            )
+            .unwrap(),
+            sui_rpc_endpoint_consecutive_errors: register_int_gauge_vec_with_registry!(
+                "sui_client_sui_rpc_endpoint_consecutive_errors",
+                "Number of consecutive failed requests to each endpoint",
+                &["endpoint"],
+                registry,
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
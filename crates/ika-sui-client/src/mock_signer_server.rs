@@ -0,0 +1,154 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! A local signing service that speaks the [`crate::signer::RemoteSigner`] protocol,
+//! for tests of the code that signs with a remote signer.
+
+use crate::signer::{PublicKeyResponse, SignRequest, SignResponse};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use fastcrypto::encoding::{Base64, Encoding};
+use ika_config::node::{RemoteSignerConfig, default_remote_signer_request_timeout_ms};
+use parking_lot::Mutex;
+use shared_crypto::intent::IntentMessage;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, Signature, SuiKeyPair};
+use sui_types::transaction::TransactionData;
+use tokio::task::JoinHandle;
+
+struct MockSignerState {
+    key_id: String,
+    /// The key whose public key is advertised.
+    key: SuiKeyPair,
+    /// The key that signs, which differs from `key` to simulate a misbehaving service.
+    signing_key: Option<SuiKeyPair>,
+    auth_token: Option<String>,
+    sign_requests: usize,
+}
+
+/// A signing service holding a single key, listening on a local port until dropped.
+pub struct MockSignerServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockSignerState>>,
+    auth_token_file: Option<tempfile::NamedTempFile>,
+    handle: JoinHandle<()>,
+}
+
+impl MockSignerServer {
+    /// Serves `key` under `key_id`, accepting only requests that carry `auth_token`, if set.
+    pub async fn start(key_id: &str, key: SuiKeyPair, auth_token: Option<String>) -> Self {
+        let auth_token_file = auth_token.as_ref().map(|auth_token| {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(file.path(), auth_token).unwrap();
+            file
+        });
+        let state = Arc::new(Mutex::new(MockSignerState {
+            key_id: key_id.to_string(),
+            key,
+            signing_key: None,
+            auth_token,
+            sign_requests: 0,
+        }));
+        let app = Router::new()
+            .route("/v1/keys/{key_id}", get(public_key))
+            .route("/v1/keys/{key_id}/sign", post(sign))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Self {
+            address,
+            state,
+            auth_token_file,
+            handle,
+        }
+    }
+
+    /// The configuration of a [`crate::signer::RemoteSigner`] for this server.
+    pub fn config(&self) -> RemoteSignerConfig {
+        let state = self.state.lock();
+        RemoteSignerConfig {
+            url: format!("http://{}", self.address),
+            key_id: state.key_id.clone(),
+            address: Some(SuiAddress::from(&state.key.public())),
+            auth_token_path: self
+                .auth_token_file
+                .as_ref()
+                .map(|file| file.path().to_path_buf()),
+            request_timeout_ms: default_remote_signer_request_timeout_ms(),
+        }
+    }
+
+    /// Signs from now on with `key` rather than with the advertised key.
+    pub fn sign_with(&self, key: SuiKeyPair) {
+        self.state.lock().signing_key = Some(key);
+    }
+
+    /// The number of signing requests served.
+    pub fn sign_requests(&self) -> usize {
+        self.state.lock().sign_requests
+    }
+}
+
+impl Drop for MockSignerServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+type SignerState = State<Arc<Mutex<MockSignerState>>>;
+
+fn authorize(state: &MockSignerState, key_id: &str, headers: &HeaderMap) -> Result<(), StatusCode> {
+    if key_id != state.key_id {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let Some(auth_token) = &state.auth_token else {
+        return Ok(());
+    };
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if authorization == Some(format!("Bearer {auth_token}").as_str()) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+async fn public_key(
+    State(state): SignerState,
+    Path(key_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<PublicKeyResponse>, StatusCode> {
+    let state = state.lock();
+    authorize(&state, &key_id, &headers)?;
+    Ok(Json(PublicKeyResponse {
+        public_key: state.key.public().encode_base64(),
+    }))
+}
+
+async fn sign(
+    State(state): SignerState,
+    Path(key_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<SignRequest>,
+) -> Result<Json<SignResponse>, StatusCode> {
+    let mut state = state.lock();
+    authorize(&state, &key_id, &headers)?;
+    let intent_message: IntentMessage<TransactionData> = Base64::decode(&request.intent_message)
+        .ok()
+        .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    state.sign_requests += 1;
+    let key = state.signing_key.as_ref().unwrap_or(&state.key);
+    let signature = Signature::new_secure(&intent_message, key);
+    Ok(Json(SignResponse {
+        signature: Base64::encode(signature.as_ref()),
+    }))
+}
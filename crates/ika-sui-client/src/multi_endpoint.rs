@@ -0,0 +1,940 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! A [`SuiClientInner`] that spreads requests over several Sui fullnodes.
+//!
+//! Every request is routed to the healthiest endpoint, ranked by its recent latency,
+//! consecutive errors and how far its latest checkpoint lags behind the other endpoints.
+//! A failed request is retried on the next endpoint in the ranking, so a single stalled
+//! fullnode does not stop the validator from reading events or submitting transactions.
+//!
+//! An endpoint that can't be reached is kept as an unhealthy one, and the health checks
+//! connect to it once it can be reached and serves the same chain as the other endpoints.
+//!
+//! Fullnodes may lag behind each other, so reads that must see a single chain state are
+//! grouped with [`pin_endpoint`], which serves all of them from the same endpoint.
+
+use crate::SuiClientInner;
+use crate::checkpoint_subscription;
+#[cfg(feature = "local-sui")]
+use crate::local_sui::{LocalSuiNetwork, is_local_sui_url};
+use crate::metrics::SuiClientMetrics;
+#[cfg(any(test, feature = "local-sui"))]
+use crate::sui_mock_client::SuiMockClient;
+use async_trait::async_trait;
+use dwallet_mpc_types::dwallet_mpc::VersionedMPCData;
+use futures::stream::BoxStream;
+use ika_types::committee::EpochId;
+use ika_types::error::IkaError;
+use ika_types::messages_consensus::MovePackageDigest;
+use ika_types::messages_dwallet_mpc::{
+    DBSuiEvent, DWalletNetworkEncryptionKey, DWalletNetworkEncryptionKeyData,
+};
+use ika_types::sui::Validator;
+use ika_types::sui::staking::StakingPool;
+use ika_types::sui::system_inner_v1::DWalletCoordinatorInnerV1;
+use parking_lot::Mutex;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use sui_json_rpc_types::{
+    EventFilter, EventPage, SuiEvent, SuiTransactionBlockEffects, SuiTransactionBlockResponse,
+};
+use sui_sdk::{SuiClient as SuiSdkClient, SuiClientBuilder};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::event::EventID;
+use sui_types::object::Object;
+use sui_types::transaction::{ObjectArg, Transaction, TransactionData};
+use tracing::{error, info, warn};
+
+/// How often every endpoint is probed for its latest checkpoint.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to retry reading the gas objects while every endpoint fails.
+const GAS_OBJECTS_MAX_ELAPSED_TIME: Duration = Duration::from_secs(120);
+
+/// The weight of the latest request in an endpoint's average latency.
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.2;
+
+/// The score penalty, in milliseconds of latency, for each consecutive error of an endpoint.
+const ERROR_PENALTY_MS: f64 = 5_000.0;
+
+/// The score penalty, in milliseconds of latency, for each checkpoint an endpoint lags behind
+/// the most up-to-date endpoint.
+const CHECKPOINT_LAG_PENALTY_MS: f64 = 250.0;
+
+tokio::task_local! {
+    /// The index of the endpoint that serves the current logical operation,
+    /// once the operation's first request succeeded.
+    static PINNED_ENDPOINT: Cell<Option<usize>>;
+}
+
+/// Runs `operation` with all of its requests served by the same endpoint,
+/// so that consecutive reads see a single chain state.
+/// The endpoint is the one that served the operation's first successful request.
+/// A request that fails on it does not fail over, so the operation can be retried as a whole.
+/// Nested operations keep the endpoint of the outermost one.
+pub async fn pin_endpoint<F: Future>(operation: F) -> F::Output {
+    if PINNED_ENDPOINT.try_with(|_| ()).is_ok() {
+        return operation.await;
+    }
+    PINNED_ENDPOINT.scope(Cell::new(None), operation).await
+}
+
+#[derive(Clone, Debug, Default)]
+struct EndpointHealth {
+    average_latency_ms: f64,
+    consecutive_errors: u32,
+    latest_checkpoint: u64,
+}
+
+impl EndpointHealth {
+    /// Lower is healthier.
+    fn score(&self, highest_checkpoint: u64) -> f64 {
+        let checkpoint_lag = highest_checkpoint.saturating_sub(self.latest_checkpoint);
+        self.average_latency_ms
+            + self.consecutive_errors as f64 * ERROR_PENALTY_MS
+            + checkpoint_lag as f64 * CHECKPOINT_LAG_PENALTY_MS
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.average_latency_ms = if self.average_latency_ms == 0.0 {
+            latency_ms
+        } else {
+            LATENCY_SMOOTHING_FACTOR * latency_ms
+                + (1.0 - LATENCY_SMOOTHING_FACTOR) * self.average_latency_ms
+        };
+        self.consecutive_errors = 0;
+    }
+}
+
+/// The client of an endpoint: a Sui fullnode, or a [`LocalSuiNetwork`] running in-process.
+enum EndpointClient {
+    /// Set once the fullnode could be reached.
+    Rpc(OnceLock<SuiSdkClient>),
+    #[cfg(any(test, feature = "local-sui"))]
+    Local(SuiMockClient),
+}
+
+impl EndpointClient {
+    fn as_inner(&self) -> Option<&dyn SuiClientInner<Error = sui_sdk::error::Error>> {
+        match self {
+            EndpointClient::Rpc(client) => client
+                .get()
+                .map(|client| client as &dyn SuiClientInner<Error = sui_sdk::error::Error>),
+            #[cfg(any(test, feature = "local-sui"))]
+            EndpointClient::Local(client) => Some(client),
+        }
+    }
+}
+
+struct SuiRpcEndpoint {
+    url: String,
+    client: EndpointClient,
+    /// Whether the endpoint was reached and serves the chain of the other endpoints,
+    /// so requests can be sent to it.
+    connected: AtomicBool,
+    health: Mutex<EndpointHealth>,
+}
+
+impl SuiRpcEndpoint {
+    fn new(url: String, client: EndpointClient) -> Self {
+        Self {
+            url,
+            client,
+            connected: AtomicBool::new(false),
+            health: Mutex::new(EndpointHealth::default()),
+        }
+    }
+
+    /// The client of the endpoint, once it is connected.
+    fn connected_client(&self) -> Option<&dyn SuiClientInner<Error = sui_sdk::error::Error>> {
+        if !self.connected.load(Ordering::Acquire) {
+            return None;
+        }
+        self.client.as_inner()
+    }
+
+    /// Reaches the endpoint and reads the chain it serves, without connecting to it yet.
+    async fn read_chain_identifier(&self) -> Result<String, sui_sdk::error::Error> {
+        let client = match &self.client {
+            EndpointClient::Rpc(client) => match client.get() {
+                Some(client) => client,
+                None => {
+                    let connected = SuiClientBuilder::default().build(&self.url).await?;
+                    client.get_or_init(|| connected)
+                }
+            },
+            #[cfg(any(test, feature = "local-sui"))]
+            EndpointClient::Local(client) => client,
+        };
+        SuiClientInner::get_chain_identifier(client).await
+    }
+}
+
+pub struct MultiEndpointSuiClient {
+    endpoints: Arc<Vec<SuiRpcEndpoint>>,
+    metrics: Arc<SuiClientMetrics>,
+}
+
+/// Calls `$call` with the client of each endpoint, from the healthiest to the least healthy,
+/// until one of them succeeds, recording the outcome in the endpoints' health and metrics.
+/// Within [`pin_endpoint`], only the pinned endpoint is called once it is chosen.
+macro_rules! with_failover {
+    ($self:ident, $method:literal, |$client:ident| $call:expr) => {{
+        let mut last_error = None;
+        for (index, endpoint) in $self.request_endpoints() {
+            // The endpoints that are not connected yet are connected by the health checks.
+            let Some($client) = endpoint.connected_client() else {
+                continue;
+            };
+            let start = Instant::now();
+            match $call.await {
+                Ok(result) => {
+                    $self.record_success(endpoint, start.elapsed());
+                    // Outside `pin_endpoint` there is nothing to pin.
+                    let _ = PINNED_ENDPOINT.try_with(|pinned| pinned.set(Some(index)));
+                    return Ok(result);
+                }
+                Err(err) => {
+                    warn!(
+                        endpoint=?endpoint.url,
+                        method=$method,
+                        error=?err,
+                        "Sui RPC request failed, trying the next endpoint"
+                    );
+                    $self.record_error(endpoint, $method);
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.expect("a multi-endpoint Sui client always has a connected endpoint"))
+    }};
+}
+
+impl MultiEndpointSuiClient {
+    /// Connects to every endpoint in `rpc_urls` and checks that they all serve the same chain.
+    /// The first endpoint is the primary one,
+    /// which is preferred while all endpoints are equally healthy.
+    /// Endpoints that can't be reached are kept as unhealthy ones, as long as one of them is
+    /// reachable, and are connected by the health checks once they can be reached.
+    pub async fn new(rpc_urls: &[String], metrics: Arc<SuiClientMetrics>) -> anyhow::Result<Self> {
+        let mut endpoints = Vec::with_capacity(rpc_urls.len());
+        for url in rpc_urls {
+            #[cfg(feature = "local-sui")]
+            if is_local_sui_url(url) {
+                let network = LocalSuiNetwork::connect(url)
+                    .ok_or_else(|| anyhow::anyhow!("No local Sui network is running at {url}"))?;
+                endpoints.push(SuiRpcEndpoint::new(
+                    url.clone(),
+                    EndpointClient::Local(network.client()),
+                ));
+                continue;
+            }
+            endpoints.push(SuiRpcEndpoint::new(
+                url.clone(),
+                EndpointClient::Rpc(OnceLock::new()),
+            ));
+        }
+        Self::connect(endpoints, metrics).await
+    }
+
+    /// Connects to the reachable `endpoints`, and fails if none of them can be reached
+    /// or if they don't all serve the chain of the first reachable one.
+    async fn connect(
+        endpoints: Vec<SuiRpcEndpoint>,
+        metrics: Arc<SuiClientMetrics>,
+    ) -> anyhow::Result<Self> {
+        let mut chain_identifier: Option<(String, String)> = None;
+        for endpoint in &endpoints {
+            let url = &endpoint.url;
+            let endpoint_chain_identifier = match endpoint.read_chain_identifier().await {
+                Ok(endpoint_chain_identifier) => endpoint_chain_identifier,
+                Err(err) => {
+                    warn!(
+                        endpoint=?url,
+                        error=?err,
+                        "can't connect to the Sui RPC endpoint, retrying in the health checks"
+                    );
+                    endpoint.health.lock().consecutive_errors += 1;
+                    metrics
+                        .sui_rpc_endpoint_errors
+                        .with_label_values(&[url.as_str(), "connect"])
+                        .inc();
+                    continue;
+                }
+            };
+            match &chain_identifier {
+                Some((first_url, first_chain_identifier))
+                    if *first_chain_identifier != endpoint_chain_identifier =>
+                {
+                    anyhow::bail!(
+                        "Sui Rpc {url} serves chain {endpoint_chain_identifier}, but {first_url} serves chain {first_chain_identifier}"
+                    );
+                }
+                Some(_) => {}
+                None => chain_identifier = Some((url.clone(), endpoint_chain_identifier)),
+            }
+            endpoint.connected.store(true, Ordering::Release);
+        }
+        let Some((_, chain_identifier)) = chain_identifier else {
+            anyhow::bail!(
+                "Can't establish connection with any Sui Rpc of {:?}",
+                endpoints
+                    .iter()
+                    .map(|endpoint| &endpoint.url)
+                    .collect::<Vec<_>>()
+            );
+        };
+        info!(
+            endpoints=?endpoints.iter().map(|endpoint| &endpoint.url).collect::<Vec<_>>(),
+            %chain_identifier,
+            "Connected to Sui RPC endpoints"
+        );
+        let this = Self {
+            endpoints: Arc::new(endpoints),
+            metrics,
+        };
+        if this.endpoints.len() > 1 {
+            tokio::spawn(Self::run_health_checks(
+                this.endpoints.clone(),
+                chain_identifier,
+                this.metrics.clone(),
+            ));
+        }
+        Ok(this)
+    }
+
+    /// A client of the mocked fullnodes `clients`, the first of which is the primary one.
+    #[cfg(test)]
+    async fn new_for_testing(clients: Vec<SuiMockClient>) -> anyhow::Result<Self> {
+        let endpoints = clients
+            .into_iter()
+            .enumerate()
+            .map(|(index, client)| {
+                SuiRpcEndpoint::new(format!("mock-{index}"), EndpointClient::Local(client))
+            })
+            .collect();
+        Self::connect(endpoints, SuiClientMetrics::new_for_testing()).await
+    }
+
+    /// The client of the currently healthiest endpoint,
+    /// or `None` if it is a local Sui network rather than a Sui fullnode.
+    pub fn best_client(&self) -> Option<&SuiSdkClient> {
+        let (_, endpoint) = self
+            .ranked_endpoints()
+            .into_iter()
+            .find(|(_, endpoint)| endpoint.connected_client().is_some())?;
+        match &endpoint.client {
+            EndpointClient::Rpc(client) => client.get(),
+            #[cfg(any(test, feature = "local-sui"))]
+            EndpointClient::Local(_) => None,
+        }
+    }
+
+    /// The endpoints to send a request to, in order: the pinned endpoint of the current
+    /// operation if there is one, or else all endpoints from the healthiest to the least healthy.
+    fn request_endpoints(&self) -> Vec<(usize, &SuiRpcEndpoint)> {
+        match PINNED_ENDPOINT.try_with(Cell::get).ok().flatten() {
+            Some(index) => vec![(index, &self.endpoints[index])],
+            None => self.ranked_endpoints(),
+        }
+    }
+
+    fn ranked_endpoints(&self) -> Vec<(usize, &SuiRpcEndpoint)> {
+        let healths = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.health.lock().clone())
+            .collect::<Vec<_>>();
+        let highest_checkpoint = healths
+            .iter()
+            .map(|health| health.latest_checkpoint)
+            .max()
+            .unwrap_or_default();
+        let mut ranked = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .zip(healths)
+            .collect::<Vec<_>>();
+        // A stable sort keeps the configured order between equally healthy endpoints.
+        ranked.sort_by(|(_, a), (_, b)| {
+            a.score(highest_checkpoint)
+                .total_cmp(&b.score(highest_checkpoint))
+        });
+        ranked.into_iter().map(|(endpoint, _)| endpoint).collect()
+    }
+
+    fn record_success(&self, endpoint: &SuiRpcEndpoint, latency: Duration) {
+        endpoint.health.lock().record_success(latency);
+        self.metrics
+            .sui_rpc_endpoint_latency_sec
+            .with_label_values(&[&endpoint.url])
+            .observe(latency.as_secs_f64());
+    }
+
+    fn record_error(&self, endpoint: &SuiRpcEndpoint, method: &str) {
+        endpoint.health.lock().consecutive_errors += 1;
+        self.metrics
+            .sui_rpc_endpoint_errors
+            .with_label_values(&[&endpoint.url, method])
+            .inc();
+    }
+
+    /// Periodically reads the latest checkpoint of every endpoint,
+    /// so lagging endpoints are ranked lower even when they answer quickly,
+    /// and connects to the endpoints that could not be reached so far.
+    async fn run_health_checks(
+        endpoints: Arc<Vec<SuiRpcEndpoint>>,
+        chain_identifier: String,
+        metrics: Arc<SuiClientMetrics>,
+    ) {
+        loop {
+            tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+            for endpoint in endpoints.iter() {
+                let Some(client) = endpoint.connected_client() else {
+                    Self::reconnect(endpoint, &chain_identifier, &metrics).await;
+                    continue;
+                };
+                let start = Instant::now();
+                match client.get_latest_checkpoint_sequence_number().await {
+                    Ok(checkpoint) => {
+                        let mut health = endpoint.health.lock();
+                        health.record_success(start.elapsed());
+                        health.latest_checkpoint = checkpoint;
+                    }
+                    Err(err) => {
+                        warn!(endpoint=?endpoint.url, error=?err, "Sui RPC endpoint health check failed");
+                        endpoint.health.lock().consecutive_errors += 1;
+                        metrics
+                            .sui_rpc_endpoint_errors
+                            .with_label_values(&[&endpoint.url, "health_check"])
+                            .inc();
+                    }
+                }
+            }
+            let highest_checkpoint = endpoints
+                .iter()
+                .map(|endpoint| endpoint.health.lock().latest_checkpoint)
+                .max()
+                .unwrap_or_default();
+            for endpoint in endpoints.iter() {
+                let health = endpoint.health.lock().clone();
+                metrics
+                    .sui_rpc_endpoint_checkpoint_lag
+                    .with_label_values(&[&endpoint.url])
+                    .set(highest_checkpoint.saturating_sub(health.latest_checkpoint) as i64);
+                metrics
+                    .sui_rpc_endpoint_consecutive_errors
+                    .with_label_values(&[&endpoint.url])
+                    .set(health.consecutive_errors as i64);
+            }
+        }
+    }
+
+    /// Connects to `endpoint` if it can be reached and serves `chain_identifier`.
+    async fn reconnect(
+        endpoint: &SuiRpcEndpoint,
+        chain_identifier: &str,
+        metrics: &SuiClientMetrics,
+    ) {
+        let url = endpoint.url.as_str();
+        match endpoint.read_chain_identifier().await {
+            Ok(endpoint_chain_identifier) if endpoint_chain_identifier == chain_identifier => {
+                info!(endpoint=?url, "Connected to the Sui RPC endpoint");
+                endpoint.health.lock().consecutive_errors = 0;
+                endpoint.connected.store(true, Ordering::Release);
+            }
+            Ok(endpoint_chain_identifier) => {
+                error!(
+                    endpoint=?url,
+                    %endpoint_chain_identifier,
+                    %chain_identifier,
+                    "the Sui RPC endpoint serves another chain, not sending requests to it"
+                );
+                metrics
+                    .sui_rpc_endpoint_errors
+                    .with_label_values(&[url, "chain_identifier"])
+                    .inc();
+            }
+            Err(err) => {
+                warn!(endpoint=?url, error=?err, "still can't connect to the Sui RPC endpoint");
+                endpoint.health.lock().consecutive_errors += 1;
+                metrics
+                    .sui_rpc_endpoint_errors
+                    .with_label_values(&[url, "connect"])
+                    .inc();
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SuiClientInner for MultiEndpointSuiClient {
+    type Error = sui_sdk::error::Error;
+
+    async fn query_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+    ) -> Result<EventPage, Self::Error> {
+        with_failover!(self, "query_events", |client| SuiClientInner::query_events(
+            client,
+            query.clone(),
+            cursor
+        ))
+    }
+
+    async fn get_events_by_tx_digest(
+        &self,
+        tx_digest: TransactionDigest,
+    ) -> Result<Vec<SuiEvent>, Self::Error> {
+        with_failover!(self, "get_events_by_tx_digest", |client| {
+            SuiClientInner::get_events_by_tx_digest(client, tx_digest)
+        })
+    }
+
+    async fn get_chain_identifier(&self) -> Result<String, Self::Error> {
+        with_failover!(self, "get_chain_identifier", |client| {
+            SuiClientInner::get_chain_identifier(client)
+        })
+    }
+
+    async fn get_reference_gas_price(&self) -> Result<u64, Self::Error> {
+        with_failover!(self, "get_reference_gas_price", |client| {
+            SuiClientInner::get_reference_gas_price(client)
+        })
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, Self::Error> {
+        with_failover!(self, "get_latest_checkpoint_sequence_number", |client| {
+            SuiClientInner::get_latest_checkpoint_sequence_number(client)
+        })
+    }
+
+    async fn subscribe_checkpoints(
+        &self,
+    ) -> Result<BoxStream<'static, Result<u64, Self::Error>>, Self::Error> {
+        // Fullnodes serve subscriptions over gRPC, which the JSON-RPC clients don't speak.
+        let mut last_error = None;
+        for (_, endpoint) in self.ranked_endpoints() {
+            if endpoint.connected_client().is_none() {
+                continue;
+            }
+            let subscription = match &endpoint.client {
+                EndpointClient::Rpc(_) => {
+                    checkpoint_subscription::subscribe_checkpoints(&endpoint.url).await
+                }
+                #[cfg(any(test, feature = "local-sui"))]
+                EndpointClient::Local(client) => client.subscribe_checkpoints().await,
+            };
+            match subscription {
+                Ok(checkpoints) => {
+                    info!(endpoint=?endpoint.url, "Subscribed to the Sui checkpoints");
+                    return Ok(checkpoints);
+                }
+                Err(err) => {
+                    warn!(
+                        endpoint=?endpoint.url,
+                        error=?err,
+                        "can't subscribe to the Sui checkpoints, trying the next endpoint"
+                    );
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.expect("a multi-endpoint Sui client always has an endpoint"))
+    }
+
+    async fn get_system(&self, ika_system_object_id: ObjectID) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "get_system", |client| SuiClientInner::get_system(
+            client,
+            ika_system_object_id
+        ))
+    }
+
+    async fn get_clock(&self, clock_obj_id: ObjectID) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "get_clock", |client| SuiClientInner::get_clock(
+            client,
+            clock_obj_id
+        ))
+    }
+
+    async fn get_dwallet_coordinator(
+        &self,
+        dwallet_coordinator_id: ObjectID,
+    ) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "get_dwallet_coordinator", |client| {
+            SuiClientInner::get_dwallet_coordinator(client, dwallet_coordinator_id)
+        })
+    }
+
+    async fn get_mpc_data_from_validators_pool(
+        &self,
+        validators: &Vec<StakingPool>,
+        read_next_epoch_mpc_data: bool,
+    ) -> Result<HashMap<ObjectID, VersionedMPCData>, Self::Error> {
+        with_failover!(self, "get_mpc_data_from_validators_pool", |client| {
+            SuiClientInner::get_mpc_data_from_validators_pool(
+                client,
+                validators,
+                read_next_epoch_mpc_data,
+            )
+        })
+    }
+
+    async fn get_network_encryption_keys(
+        &self,
+        dwallet_coordinator_inner: &DWalletCoordinatorInnerV1,
+    ) -> Result<HashMap<ObjectID, DWalletNetworkEncryptionKey>, Self::Error> {
+        with_failover!(self, "get_network_encryption_keys", |client| {
+            SuiClientInner::get_network_encryption_keys(client, dwallet_coordinator_inner)
+        })
+    }
+
+    async fn get_network_encryption_key_with_full_data_by_epoch(
+        &self,
+        network_decryption_key: &DWalletNetworkEncryptionKey,
+        epoch: EpochId,
+    ) -> Result<DWalletNetworkEncryptionKeyData, Self::Error> {
+        with_failover!(
+            self,
+            "get_network_encryption_key_with_full_data_by_epoch",
+            |client| SuiClientInner::get_network_encryption_key_with_full_data_by_epoch(
+                client,
+                network_decryption_key,
+                epoch
+            )
+        )
+    }
+
+    async fn get_current_reconfiguration_public_output(
+        &self,
+        epoch_id: EpochId,
+        table_id: ObjectID,
+    ) -> Result<ObjectID, Self::Error> {
+        with_failover!(
+            self,
+            "get_current_reconfiguration_public_output",
+            |client| {
+                SuiClientInner::get_current_reconfiguration_public_output(
+                    client, epoch_id, table_id,
+                )
+            }
+        )
+    }
+
+    async fn read_table_vec_as_raw_bytes(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "read_table_vec_as_raw_bytes", |client| {
+            SuiClientInner::read_table_vec_as_raw_bytes(client, table_id)
+        })
+    }
+
+    async fn get_table_vec_chunk_refs(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<ObjectRef>, Self::Error> {
+        with_failover!(self, "get_table_vec_chunk_refs", |client| {
+            SuiClientInner::get_table_vec_chunk_refs(client, table_id)
+        })
+    }
+
+    async fn read_table_vec_chunks(&self, table_id: ObjectID) -> Result<Vec<Object>, Self::Error> {
+        with_failover!(self, "read_table_vec_chunks", |client| {
+            SuiClientInner::read_table_vec_chunks(client, table_id)
+        })
+    }
+
+    async fn get_system_inner(
+        &self,
+        ika_system_object_id: ObjectID,
+        version: u64,
+    ) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "get_system_inner", |client| {
+            SuiClientInner::get_system_inner(client, ika_system_object_id, version)
+        })
+    }
+
+    async fn get_dwallet_coordinator_inner(
+        &self,
+        dwallet_coordinator_id: ObjectID,
+        version: u64,
+    ) -> Result<Vec<u8>, Self::Error> {
+        with_failover!(self, "get_dwallet_coordinator_inner", |client| {
+            SuiClientInner::get_dwallet_coordinator_inner(client, dwallet_coordinator_id, version)
+        })
+    }
+
+    async fn get_validators(
+        &self,
+        validator_ids: Vec<ObjectID>,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        with_failover!(self, "get_validators", |client| {
+            SuiClientInner::get_validators(client, validator_ids.clone())
+        })
+    }
+
+    async fn get_validator_inners(
+        &self,
+        validators: Vec<Validator>,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        with_failover!(self, "get_validator_inners", |client| {
+            SuiClientInner::get_validator_inners(client, validators.clone())
+        })
+    }
+
+    async fn get_mutable_shared_arg(
+        &self,
+        ika_system_object_id: ObjectID,
+    ) -> Result<ObjectArg, Self::Error> {
+        with_failover!(self, "get_mutable_shared_arg", |client| {
+            SuiClientInner::get_mutable_shared_arg(client, ika_system_object_id)
+        })
+    }
+
+    async fn get_shared_arg(&self, obj_id: ObjectID) -> Result<ObjectArg, Self::Error> {
+        with_failover!(self, "get_shared_arg", |client| {
+            SuiClientInner::get_shared_arg(client, obj_id)
+        })
+    }
+
+    async fn get_available_move_packages(
+        &self,
+        ika_package_id: ObjectID,
+        ika_system_package_id: ObjectID,
+    ) -> Result<Vec<(ObjectID, MovePackageDigest)>, Self::Error> {
+        with_failover!(self, "get_available_move_packages", |client| {
+            SuiClientInner::get_available_move_packages(
+                client,
+                ika_package_id,
+                ika_system_package_id,
+            )
+        })
+    }
+
+    async fn execute_transaction_block_with_effects(
+        &self,
+        tx: Transaction,
+    ) -> Result<SuiTransactionBlockResponse, IkaError> {
+        // Re-submitting the same signed transaction to another endpoint is safe,
+        // as it can only be executed once.
+        with_failover!(self, "execute_transaction_block_with_effects", |client| {
+            SuiClientInner::execute_transaction_block_with_effects(client, tx.clone())
+        })
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockEffects, Self::Error> {
+        with_failover!(self, "dry_run_transaction_block", |client| {
+            SuiClientInner::dry_run_transaction_block(client, tx_data.clone())
+        })
+    }
+
+    async fn get_gas_objects(&self, address: SuiAddress) -> Vec<ObjectRef> {
+        // The clients' `get_gas_objects` retries until it succeeds, which would not fail over,
+        // so the gas coins are read instead.
+        match crate::retry_with_max_elapsed_time!(
+            SuiClientInner::get_gas_coins(self, address),
+            GAS_OBJECTS_MAX_ELAPSED_TIME
+        ) {
+            Ok(Ok(coins)) => coins.into_iter().map(|(coin, _)| coin).collect(),
+            Ok(Err(err)) | Err(err) => {
+                error!(error=?err, %address, "can't get gas objects from any Sui RPC endpoint");
+                vec![]
+            }
+        }
+    }
+
+    async fn get_gas_coins(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<(ObjectRef, u64)>, Self::Error> {
+        with_failover!(self, "get_gas_coins", |client| {
+            SuiClientInner::get_gas_coins(client, address)
+        })
+    }
+
+    async fn get_uncompleted_events(
+        &self,
+        events_bag_id: ObjectID,
+    ) -> Result<Vec<DBSuiEvent>, Self::Error> {
+        with_failover!(self, "get_uncompleted_events", |client| {
+            SuiClientInner::get_uncompleted_events(client, events_bag_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lagging_and_failing_endpoints_score_worse() {
+        let healthy = EndpointHealth {
+            average_latency_ms: 200.0,
+            consecutive_errors: 0,
+            latest_checkpoint: 1_000,
+        };
+        let fast_but_lagging = EndpointHealth {
+            average_latency_ms: 20.0,
+            consecutive_errors: 0,
+            latest_checkpoint: 900,
+        };
+        let fast_but_failing = EndpointHealth {
+            average_latency_ms: 20.0,
+            consecutive_errors: 1,
+            latest_checkpoint: 1_000,
+        };
+        assert!(healthy.score(1_000) < fast_but_lagging.score(1_000));
+        assert!(healthy.score(1_000) < fast_but_failing.score(1_000));
+    }
+
+    #[test]
+    fn success_resets_errors_and_smooths_latency() {
+        let mut health = EndpointHealth {
+            average_latency_ms: 100.0,
+            consecutive_errors: 3,
+            latest_checkpoint: 0,
+        };
+        health.record_success(Duration::from_millis(200));
+        assert_eq!(health.consecutive_errors, 0);
+        assert!((health.average_latency_ms - 120.0).abs() < 1e-9);
+    }
+
+    /// Two mocked fullnodes with different reference gas prices, to tell which one answered.
+    fn two_mocks() -> (SuiMockClient, SuiMockClient) {
+        let primary = SuiMockClient::new();
+        primary.set_reference_gas_price(1_000);
+        let secondary = SuiMockClient::new();
+        secondary.set_reference_gas_price(2_000);
+        (primary, secondary)
+    }
+
+    async fn two_endpoints() -> (MultiEndpointSuiClient, SuiMockClient, SuiMockClient) {
+        let (primary, secondary) = two_mocks();
+        let client =
+            MultiEndpointSuiClient::new_for_testing(vec![primary.clone(), secondary.clone()])
+                .await
+                .unwrap();
+        (client, primary, secondary)
+    }
+
+    #[tokio::test]
+    async fn fails_over_when_an_endpoint_is_down() {
+        let (client, primary, secondary) = two_endpoints().await;
+        primary.fail_next_calls("get_reference_gas_price", usize::MAX);
+
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 2_000);
+        // The failed endpoint is ranked last, so the next request is not sent to it.
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 2_000);
+        assert_eq!(primary.calls("get_reference_gas_price"), 1);
+        assert_eq!(secondary.calls("get_reference_gas_price"), 2);
+
+        secondary.fail_next_calls("get_reference_gas_price", usize::MAX);
+        assert!(client.get_reference_gas_price().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn gas_objects_fail_over() {
+        let (client, primary, secondary) = two_endpoints().await;
+        let owner = SuiAddress::random_for_testing_only();
+        let coin = secondary.add_gas_coin(owner, 1_000);
+        primary.fail_next_calls("get_gas_coins", usize::MAX);
+
+        assert_eq!(client.get_gas_objects(owner).await, vec![coin]);
+    }
+
+    #[tokio::test]
+    async fn pinned_operation_reads_from_a_single_endpoint() {
+        let (client, primary, _secondary) = two_endpoints().await;
+
+        pin_endpoint(async {
+            assert_eq!(client.get_reference_gas_price().await.unwrap(), 1_000);
+            // Another endpoint becomes healthier in the middle of the operation.
+            client.endpoints[0].health.lock().consecutive_errors = 10;
+            assert_eq!(client.get_reference_gas_price().await.unwrap(), 1_000);
+            assert_eq!(
+                pin_endpoint(client.get_reference_gas_price())
+                    .await
+                    .unwrap(),
+                1_000
+            );
+            // A failure of the pinned endpoint fails the request rather than reading another
+            // chain state.
+            primary.fail_next_calls("get_reference_gas_price", 1);
+            assert!(client.get_reference_gas_price().await.is_err());
+        })
+        .await;
+
+        // A new operation is served by the healthiest endpoint.
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 2_000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn connects_to_unreachable_endpoints_once_they_are_reachable() {
+        let (primary, secondary) = two_mocks();
+        primary.fail_next_calls("get_chain_identifier", 1);
+        let client =
+            MultiEndpointSuiClient::new_for_testing(vec![primary.clone(), secondary.clone()])
+                .await
+                .unwrap();
+
+        // Requests are not sent to the endpoint before it is connected.
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 2_000);
+        assert_eq!(primary.calls("get_reference_gas_price"), 0);
+
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL * 2).await;
+        assert!(client.endpoints[0].connected_client().is_some());
+        client.endpoints[1].health.lock().consecutive_errors = 10;
+        assert_eq!(client.get_reference_gas_price().await.unwrap(), 1_000);
+    }
+
+    #[tokio::test]
+    async fn rejects_endpoints_of_another_chain() {
+        let (primary, secondary) = two_mocks();
+        secondary.set_chain_identifier("another chain".to_string());
+        assert!(
+            MultiEndpointSuiClient::new_for_testing(vec![primary, secondary])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn does_not_connect_to_a_reachable_endpoint_of_another_chain() {
+        let (primary, secondary) = two_mocks();
+        secondary.fail_next_calls("get_chain_identifier", 1);
+        secondary.set_chain_identifier("another chain".to_string());
+        let client =
+            MultiEndpointSuiClient::new_for_testing(vec![primary.clone(), secondary.clone()])
+                .await
+                .unwrap();
+
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL * 2).await;
+        assert!(secondary.calls("get_chain_identifier") > 1);
+        assert!(client.endpoints[1].connected_client().is_none());
+    }
+
+    #[tokio::test]
+    async fn fails_when_no_endpoint_is_reachable() {
+        let (primary, secondary) = two_mocks();
+        primary.fail_next_calls("get_chain_identifier", 1);
+        secondary.fail_next_calls("get_chain_identifier", 1);
+        assert!(
+            MultiEndpointSuiClient::new_for_testing(vec![primary, secondary])
+                .await
+                .is_err()
+        );
+    }
+}
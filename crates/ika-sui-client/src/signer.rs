@@ -0,0 +1,303 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Signers of the Sui transactions sent by the notifier and by the `ika validator` commands.
+//!
+//! A [`FileSigner`] holds the key in memory, as loaded from a key file or a Sui keystore.
+//! A [`RemoteSigner`] holds only the public key, and asks a signing service,
+//! configured by [`RemoteSignerConfig`], to sign every transaction.
+
+use anyhow::{Context, anyhow, bail};
+use async_trait::async_trait;
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::ToFromBytes;
+use ika_config::node::{KeyPairWithPath, RemoteSignerConfig};
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::fmt;
+use std::time::Duration;
+use sui_keys::keystore::AccountKeystore;
+use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature};
+use sui_types::transaction::{Transaction, TransactionData};
+
+/// Signs Sui transactions with a single key.
+#[async_trait]
+pub trait Signer: Send + Sync + fmt::Debug {
+    /// The Sui address of the key, which pays for and sends the signed transactions.
+    fn address(&self) -> SuiAddress;
+
+    /// Signs `tx_data` with the Sui transaction intent.
+    async fn sign_transaction(&self, tx_data: &TransactionData) -> anyhow::Result<Signature>;
+}
+
+/// Signs `tx_data` with `signer` into a transaction that can be submitted to Sui.
+pub async fn sign_transaction(
+    signer: &dyn Signer,
+    tx_data: TransactionData,
+) -> anyhow::Result<Transaction> {
+    let signature = signer.sign_transaction(&tx_data).await?;
+    Ok(Transaction::from_data(tx_data, vec![signature]))
+}
+
+/// Signs with a key held in memory.
+pub struct FileSigner {
+    key: SuiKeyPair,
+    address: SuiAddress,
+}
+
+impl FileSigner {
+    pub fn new(key: SuiKeyPair) -> Self {
+        let address = SuiAddress::from(&key.public());
+        Self { key, address }
+    }
+
+    pub fn from_key_file(key_pair: &KeyPairWithPath) -> Self {
+        Self::new(key_pair.keypair().copy())
+    }
+
+    /// Signs with the key of the active address of the Sui wallet.
+    pub fn from_wallet(context: &WalletContext) -> anyhow::Result<Self> {
+        let address = context.active_address()?;
+        let key = context.config.keystore.get_key(&address)?;
+        Ok(Self::new(key.copy()))
+    }
+}
+
+impl fmt::Debug for FileSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileSigner")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Signer for FileSigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx_data: &TransactionData) -> anyhow::Result<Signature> {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(Intent::sui_transaction(), tx_data),
+            &self.key,
+        ))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PublicKeyResponse {
+    /// Base64 of the signature scheme flag followed by the public key.
+    pub(crate) public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignRequest {
+    /// Base64 of the BCS-serialized intent message.
+    pub(crate) intent_message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignResponse {
+    /// Base64 of the signature scheme flag, the signature, and the public key.
+    pub(crate) signature: String,
+}
+
+/// Signs with a key held by a signing service.
+///
+/// Every signature it returns is verified against the key's public key,
+/// so a misbehaving service can't make the node submit invalid transactions.
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    key_url: String,
+    auth_token: Option<String>,
+    address: SuiAddress,
+}
+
+impl RemoteSigner {
+    /// Fetches the public key of the configured key from the signing service.
+    pub async fn connect(config: &RemoteSignerConfig) -> anyhow::Result<Self> {
+        let auth_token = config
+            .auth_token_path
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .with_context(|| format!("failed to read the signer auth token from {path:?}"))
+            })
+            .transpose()?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.request_timeout_ms))
+            .build()?;
+        let key_url = format!(
+            "{}/v1/keys/{}",
+            config.url.trim_end_matches('/'),
+            config.key_id
+        );
+
+        let mut request = client.get(&key_url);
+        if let Some(auth_token) = &auth_token {
+            request = request.bearer_auth(auth_token);
+        }
+        let response: PublicKeyResponse = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("invalid public key response from {key_url}"))?;
+        let public_key = PublicKey::decode_base64(&response.public_key)
+            .map_err(|e| anyhow!("invalid public key from {key_url}: {e}"))?;
+        let address = SuiAddress::from(&public_key);
+        if let Some(expected_address) = config.address {
+            if address != expected_address {
+                bail!(
+                    "the key of {key_url} has the address {address}, expected {expected_address}"
+                );
+            }
+        }
+
+        Ok(Self {
+            client,
+            key_url,
+            auth_token,
+            address,
+        })
+    }
+}
+
+impl fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("key_url", &self.key_url)
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx_data: &TransactionData) -> anyhow::Result<Signature> {
+        let intent_message = IntentMessage::new(Intent::sui_transaction(), tx_data);
+        let sign_url = format!("{}/sign", self.key_url);
+        let mut request = self.client.post(&sign_url).json(&SignRequest {
+            intent_message: Base64::encode(bcs::to_bytes(&intent_message)?),
+        });
+        if let Some(auth_token) = &self.auth_token {
+            request = request.bearer_auth(auth_token);
+        }
+        let response: SignResponse = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("invalid signature response from {sign_url}"))?;
+
+        let signature_bytes = Base64::decode(&response.signature)
+            .map_err(|e| anyhow!("invalid signature encoding from {sign_url}: {e}"))?;
+        let signature = Signature::from_bytes(&signature_bytes)
+            .map_err(|e| anyhow!("invalid signature from {sign_url}: {e}"))?;
+        if let Err(e) = signature.verify_secure(&intent_message, self.address, signature.scheme()) {
+            bail!(
+                "the signature from {sign_url} doesn't verify for {}: {e}",
+                self.address
+            );
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_signer_server::MockSignerServer;
+    use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+    use sui_types::crypto::{AccountKeyPair, get_key_pair};
+    use sui_types::digests::ObjectDigest;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::signature::GenericSignature;
+
+    fn new_key() -> SuiKeyPair {
+        SuiKeyPair::Ed25519(get_key_pair::<AccountKeyPair>().1)
+    }
+
+    fn tx_data(sender: SuiAddress) -> TransactionData {
+        let gas_coin: ObjectRef = (
+            ObjectID::random(),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        );
+        TransactionData::new_programmable(
+            sender,
+            vec![gas_coin],
+            ProgrammableTransactionBuilder::new().finish(),
+            1_000_000,
+            1_000,
+        )
+    }
+
+    fn assert_verifies(transaction: &Transaction, signer: &dyn Signer) {
+        let intent_message =
+            IntentMessage::new(Intent::sui_transaction(), transaction.transaction_data());
+        let GenericSignature::Signature(signature) = &transaction.tx_signatures()[0] else {
+            panic!("expected a simple signature");
+        };
+        signature
+            .verify_secure(&intent_message, signer.address(), signature.scheme())
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_signer_signs_with_its_key() {
+        let signer = FileSigner::new(new_key());
+        let transaction = sign_transaction(&signer, tx_data(signer.address()))
+            .await
+            .unwrap();
+        assert_verifies(&transaction, &signer);
+    }
+
+    #[tokio::test]
+    async fn remote_signer_signs_through_the_signing_service() {
+        let key = new_key();
+        let address = SuiAddress::from(&key.public());
+        let server = MockSignerServer::start("notifier", key, Some("secret".to_string())).await;
+        let signer = RemoteSigner::connect(&server.config()).await.unwrap();
+        assert_eq!(signer.address(), address);
+
+        let transaction = sign_transaction(&signer, tx_data(address)).await.unwrap();
+        assert_verifies(&transaction, &signer);
+        assert_eq!(server.sign_requests(), 1);
+
+        let mut unauthenticated = server.config();
+        unauthenticated.auth_token_path = None;
+        assert!(RemoteSigner::connect(&unauthenticated).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_signer_rejects_a_key_of_another_address() {
+        let server = MockSignerServer::start("notifier", new_key(), None).await;
+        let mut config = server.config();
+        config.address = Some(SuiAddress::from(&new_key().public()));
+        assert!(RemoteSigner::connect(&config).await.is_err());
+
+        config.address = None;
+        assert!(RemoteSigner::connect(&config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn remote_signer_rejects_signatures_of_another_key() {
+        let server = MockSignerServer::start("notifier", new_key(), None).await;
+        let signer = RemoteSigner::connect(&server.config()).await.unwrap();
+
+        server.sign_with(new_key());
+        let result = signer.sign_transaction(&tx_data(signer.address())).await;
+        assert!(result.is_err());
+    }
+}
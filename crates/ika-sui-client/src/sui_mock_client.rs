@@ -1,302 +1,1295 @@
-// // Copyright (c) Mysten Labs, Inc.
-// // SPDX-License-Identifier: BSD-3-Clause-Clear
-// 
-// //! A mock implementation of Sui JSON-RPC client.
-// 
-// use super::error::{IkaError, IkaResult};
-// use crate::test_utils::DUMMY_MUTALBE_BRIDGE_OBJECT_ARG;
-// use async_trait::async_trait;
-// use std::collections::{HashMap, VecDeque};
-// use std::sync::atomic::AtomicU64;
-// use std::sync::{Arc, Mutex};
-// use sui_json_rpc_types::SuiTransactionBlockResponse;
-// use sui_json_rpc_types::{EventFilter, EventPage, SuiEvent};
-// use sui_types::base_types::ObjectID;
-// use sui_types::base_types::ObjectRef;
-// use sui_types::bridge::{
-//     BridgeCommitteeSummary, BridgeSummary, MoveTypeParsedTokenTransferMessage,
-// };
-// use sui_types::digests::TransactionDigest;
-// use sui_types::event::EventID;
-// use sui_types::gas_coin::GasCoin;
-// use sui_types::object::Owner;
-// use sui_types::transaction::ObjectArg;
-// use sui_types::transaction::Transaction;
-// use sui_types::Identifier;
-// 
-// use super::sui_client::SuiClientInner;
-// use super::types::{BridgeAction, BridgeActionStatus, IsBridgePaused};
-// 
-// /// Mock client used in test environments.
-// #[allow(clippy::type_complexity)]
-// #[derive(Clone, Debug)]
-// pub struct SuiMockClient {
-//     // the top two fields do not change during tests so we don't need them to be Arc<Mutex>>
-//     chain_identifier: String,
-//     latest_checkpoint_sequence_number: Arc<AtomicU64>,
-//     events: Arc<Mutex<HashMap<(ObjectID, Identifier, Option<EventID>), EventPage>>>,
-//     past_event_query_params: Arc<Mutex<VecDeque<(ObjectID, Identifier, Option<EventID>)>>>,
-//     events_by_tx_digest:
-//         Arc<Mutex<HashMap<TransactionDigest, Result<Vec<SuiEvent>, sui_sdk::error::Error>>>>,
-//     transaction_responses:
-//         Arc<Mutex<HashMap<TransactionDigest, IkaResult<SuiTransactionBlockResponse>>>>,
-//     wildcard_transaction_response: Arc<Mutex<Option<IkaResult<SuiTransactionBlockResponse>>>>,
-//     get_object_info: Arc<Mutex<HashMap<ObjectID, (GasCoin, ObjectRef, Owner)>>>,
-//     onchain_status: Arc<Mutex<HashMap<(u8, u64), BridgeActionStatus>>>,
-//     bridge_committee_summary: Arc<Mutex<Option<BridgeCommitteeSummary>>>,
-//     is_paused: Arc<Mutex<Option<IsBridgePaused>>>,
-//     requested_transactions_tx: tokio::sync::broadcast::Sender<TransactionDigest>,
-// }
-// 
-// impl SuiMockClient {
-//     pub fn default() -> Self {
-//         Self {
-//             chain_identifier: "".to_string(),
-//             latest_checkpoint_sequence_number: Arc::new(AtomicU64::new(0)),
-//             events: Default::default(),
-//             past_event_query_params: Default::default(),
-//             events_by_tx_digest: Default::default(),
-//             transaction_responses: Default::default(),
-//             wildcard_transaction_response: Default::default(),
-//             get_object_info: Default::default(),
-//             onchain_status: Default::default(),
-//             bridge_committee_summary: Default::default(),
-//             is_paused: Default::default(),
-//             requested_transactions_tx: tokio::sync::broadcast::channel(10000).0,
-//         }
-//     }
-// 
-//     pub fn add_event_response(
-//         &self,
-//         package: ObjectID,
-//         module: Identifier,
-//         cursor: EventID,
-//         events: EventPage,
-//     ) {
-//         self.events
-//             .lock()
-//             .unwrap()
-//             .insert((package, module, Some(cursor)), events);
-//     }
-// 
-//     pub fn add_events_by_tx_digest(&self, tx_digest: TransactionDigest, events: Vec<SuiEvent>) {
-//         self.events_by_tx_digest
-//             .lock()
-//             .unwrap()
-//             .insert(tx_digest, Ok(events));
-//     }
-// 
-//     pub fn add_events_by_tx_digest_error(&self, tx_digest: TransactionDigest) {
-//         self.events_by_tx_digest.lock().unwrap().insert(
-//             tx_digest,
-//             Err(sui_sdk::error::Error::DataError("".to_string())),
-//         );
-//     }
-// 
-//     pub fn add_transaction_response(
-//         &self,
-//         tx_digest: TransactionDigest,
-//         response: IkaResult<SuiTransactionBlockResponse>,
-//     ) {
-//         self.transaction_responses
-//             .lock()
-//             .unwrap()
-//             .insert(tx_digest, response);
-//     }
-// 
-//     pub fn set_action_onchain_status(&self, action: &BridgeAction, status: BridgeActionStatus) {
-//         self.onchain_status
-//             .lock()
-//             .unwrap()
-//             .insert((action.chain_id() as u8, action.seq_number()), status);
-//     }
-// 
-//     pub fn set_bridge_committee(&self, committee: BridgeCommitteeSummary) {
-//         self.bridge_committee_summary
-//             .lock()
-//             .unwrap()
-//             .replace(committee);
-//     }
-// 
-//     pub fn set_is_bridge_paused(&self, value: IsBridgePaused) {
-//         self.is_paused.lock().unwrap().replace(value);
-//     }
-// 
-//     pub fn set_wildcard_transaction_response(
-//         &self,
-//         response: IkaResult<SuiTransactionBlockResponse>,
-//     ) {
-//         *self.wildcard_transaction_response.lock().unwrap() = Some(response);
-//     }
-// 
-//     pub fn set_latest_checkpoint_sequence_number(&self, value: u64) {
-//         self.latest_checkpoint_sequence_number
-//             .store(value, std::sync::atomic::Ordering::Relaxed);
-//     }
-// 
-//     pub fn add_gas_object_info(&self, gas_coin: GasCoin, object_ref: ObjectRef, owner: Owner) {
-//         self.get_object_info
-//             .lock()
-//             .unwrap()
-//             .insert(object_ref.0, (gas_coin, object_ref, owner));
-//     }
-// 
-//     pub fn subscribe_to_requested_transactions(
-//         &self,
-//     ) -> tokio::sync::broadcast::Receiver<TransactionDigest> {
-//         self.requested_transactions_tx.subscribe()
-//     }
-// }
-// 
-// #[async_trait]
-// impl SuiClientInner for SuiMockClient {
-//     type Error = sui_sdk::error::Error;
-// 
-//     // Unwraps in this function: We assume the responses are pre-populated
-//     // by the test before calling into this function.
-//     async fn query_events(
-//         &self,
-//         query: EventFilter,
-//         cursor: Option<EventID>,
-//     ) -> Result<EventPage, Self::Error> {
-//         let events = self.events.lock().unwrap();
-//         match query {
-//             EventFilter::MoveEventModule { package, module } => {
-//                 self.past_event_query_params.lock().unwrap().push_back((
-//                     package,
-//                     module.clone(),
-//                     cursor,
-//                 ));
-//                 Ok(events
-//                     .get(&(package, module.clone(), cursor))
-//                     .cloned()
-//                     .unwrap_or_else(|| {
-//                         panic!(
-//                             "No preset events found for package: {:?}, module: {:?}, cursor: {:?}",
-//                             package, module, cursor
-//                         )
-//                     }))
-//             }
-//             _ => unimplemented!(),
-//         }
-//     }
-// 
-//     async fn get_events_by_tx_digest(
-//         &self,
-//         tx_digest: TransactionDigest,
-//     ) -> Result<Vec<SuiEvent>, Self::Error> {
-//         let events = self.events_by_tx_digest.lock().unwrap();
-// 
-//         match events
-//             .get(&tx_digest)
-//             .unwrap_or_else(|| panic!("No preset events found for tx_digest: {:?}", tx_digest))
-//         {
-//             Ok(events) => Ok(events.clone()),
-//             // sui_sdk::error::Error is not Clone
-//             Err(_) => Err(sui_sdk::error::Error::DataError("".to_string())),
-//         }
-//     }
-// 
-//     async fn get_chain_identifier(&self) -> Result<String, Self::Error> {
-//         Ok(self.chain_identifier.clone())
-//     }
-// 
-//     async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, Self::Error> {
-//         Ok(self
-//             .latest_checkpoint_sequence_number
-//             .load(std::sync::atomic::Ordering::Relaxed))
-//     }
-// 
-//     async fn get_mutable_system_arg(&self) -> Result<ObjectArg, Self::Error> {
-//         Ok(DUMMY_MUTALBE_BRIDGE_OBJECT_ARG)
-//     }
-// 
-//     async fn get_reference_gas_price(&self) -> Result<u64, Self::Error> {
-//         Ok(1000)
-//     }
-// 
-//     async fn get_bridge_summary(&self) -> Result<BridgeSummary, Self::Error> {
-//         Ok(BridgeSummary {
-//             bridge_version: 0,
-//             message_version: 0,
-//             chain_id: 0,
-//             sequence_nums: vec![],
-//             bridge_records_id: ObjectID::random(),
-//             is_frozen: self.is_paused.lock().unwrap().unwrap_or_default(),
-//             limiter: Default::default(),
-//             committee: self
-//                 .bridge_committee_summary
-//                 .lock()
-//                 .unwrap()
-//                 .clone()
-//                 .unwrap_or_default(),
-//             treasury: Default::default(),
-//         })
-//     }
-// 
-//     async fn get_token_transfer_action_onchain_status(
-//         &self,
-//         _system_arg: ObjectArg,
-//         source_chain_id: u8,
-//         seq_number: u64,
-//     ) -> Result<BridgeActionStatus, IkaError> {
-//         Ok(self
-//             .onchain_status
-//             .lock()
-//             .unwrap()
-//             .get(&(source_chain_id, seq_number))
-//             .cloned()
-//             .unwrap_or(BridgeActionStatus::Pending))
-//     }
-// 
-//     async fn get_token_transfer_action_onchain_signatures(
-//         &self,
-//         _system_arg: ObjectArg,
-//         _source_chain_id: u8,
-//         _seq_number: u64,
-//     ) -> Result<Option<Vec<Vec<u8>>>, IkaError> {
-//         unimplemented!()
-//     }
-// 
-//     async fn get_parsed_token_transfer_message(
-//         &self,
-//         _system_arg: ObjectArg,
-//         _source_chain_id: u8,
-//         _seq_number: u64,
-//     ) -> Result<Option<MoveTypeParsedTokenTransferMessage>, IkaError> {
-//         unimplemented!()
-//     }
-// 
-//     async fn execute_transaction_block_with_effects(
-//         &self,
-//         tx: Transaction,
-//     ) -> Result<SuiTransactionBlockResponse, IkaError> {
-//         self.requested_transactions_tx.send(*tx.digest()).unwrap();
-//         match self.transaction_responses.lock().unwrap().get(tx.digest()) {
-//             Some(response) => response.clone(),
-//             None => self
-//                 .wildcard_transaction_response
-//                 .lock()
-//                 .unwrap()
-//                 .clone()
-//                 .unwrap_or_else(|| panic!("No preset transaction response found for tx: {:?}", tx)),
-//         }
-//     }
-// 
-//     async fn get_gas_data_panic_if_not_gas(
-//         &self,
-//         gas_object_id: ObjectID,
-//     ) -> (GasCoin, ObjectRef, Owner) {
-//         self.get_object_info
-//             .lock()
-//             .unwrap()
-//             .get(&gas_object_id)
-//             .cloned()
-//             .unwrap_or_else(|| {
-//                 panic!(
-//                     "No preset gas object info found for gas_object_id: {:?}",
-//                     gas_object_id
-//                 )
-//             })
-//     }
-// }
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! An in-memory implementation of [`SuiClientInner`] for tests.
+//!
+//! The mock models a single Ika deployment: the system and the dWallet coordinator objects are
+//! served for any object ID they are requested with. Tests script the on-chain state through
+//! the setters of [`SuiMockClient`], and inspect the transactions the code under test submitted.
+//! Every clone of a [`SuiMockClient`] shares the same state, so a test can keep a handle to
+//! the mock after moving it into a [`crate::SuiClient`].
+
+use crate::SuiClientInner;
+use async_trait::async_trait;
+use dwallet_mpc_types::dwallet_mpc::VersionedMPCData;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use ika_types::error::IkaError;
+use ika_types::messages_consensus::MovePackageDigest;
+use ika_types::messages_dwallet_mpc::{
+    DBSuiEvent, DWalletNetworkEncryptionKey, DWalletNetworkEncryptionKeyData,
+};
+use ika_types::sui::staking::StakingPool;
+use ika_types::sui::system_inner_v1::{
+    BlsCommittee, BlsCommitteeMember, DWalletCoordinatorInnerV1, PricingAndFeeManagement,
+    PricingInfo, ProtocolTreasuryV1, SessionsKeeper, SessionsManager, SupportConfig, SystemInnerV1,
+    SystemObjectCap, ValidatorSetV1,
+};
+use ika_types::sui::{DWalletCoordinator, Element, ExtendedField, System, Validator};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_json_rpc_types::{
+    EventFilter, EventPage, OwnedObjectRef, SuiEvent, SuiExecutionStatus,
+    SuiTransactionBlockEffects, SuiTransactionBlockResponse,
+};
+use sui_sdk::error::Error;
+use sui_types::balance::{Balance, Supply};
+use sui_types::base_types::{
+    EpochId, ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress,
+};
+use sui_types::coin::TreasuryCap;
+use sui_types::collection_types::{Bag, Table, VecMap};
+use sui_types::digests::TransactionDigest;
+use sui_types::dynamic_field::{DynamicFieldInfo, Field, derive_dynamic_field_id};
+use sui_types::effects::TransactionEffects;
+use sui_types::event::EventID;
+use sui_types::gas::GasCostSummary;
+use sui_types::id::UID;
+use sui_types::object::{MoveObject, Object, Owner};
+use sui_types::transaction::{
+    Argument, CallArg, Command, ObjectArg, Transaction, TransactionData, TransactionDataAPI,
+    TransactionKind,
+};
+use sui_types::{Identifier, SUI_CLOCK_OBJECT_ID, TypeTag};
+use tokio::sync::broadcast;
+
+/// The maximum number of events returned by a single `query_events` call, as on a fullnode.
+const EVENT_PAGE_SIZE: usize = 50;
+
+/// The computation cost charged for every executed transaction, in MIST.
+const MOCK_COMPUTATION_COST: u64 = 1_000_000;
+
+const MOCK_REFERENCE_GAS_PRICE: u64 = 1_000;
+
+/// The size of the chunks the bytes of a `TableVec` are split into, as written by the contracts.
+const TABLE_VEC_CHUNK_SIZE: usize = 16 * 1024;
+
+/// The maximal size of a Move object on Sui.
+const MAX_MOVE_OBJECT_SIZE: u64 = 250 * 1024;
+
+/// Applies an executed transaction to the mocked chain, returning the events it emitted,
+/// or the error it aborted with.
+pub type ExecutionHandler =
+    Arc<dyn Fn(&Transaction) -> Result<Vec<SuiEvent>, String> + Send + Sync>;
+
+/// Simulates a dry-run transaction against the mocked chain without changing it,
+/// returning the error it would abort with.
+pub type DryRunHandler = Arc<dyn Fn(&TransactionData) -> Result<(), String> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct SuiMockClient {
+    state: Arc<Mutex<SuiMockState>>,
+    checkpoint_sender: broadcast::Sender<u64>,
+}
+
+#[derive(Default)]
+struct SuiMockState {
+    chain_identifier: String,
+    reference_gas_price: u64,
+    latest_checkpoint_sequence_number: u64,
+    clock_timestamp_ms: u64,
+    /// Serve the system time as the clock, instead of `clock_timestamp_ms`.
+    use_system_clock: bool,
+    /// All the events emitted on the mocked chain, in checkpoint order.
+    events: Vec<SuiEvent>,
+    system_inner: Option<SystemInnerV1>,
+    dwallet_coordinator_inner: Option<DWalletCoordinatorInnerV1>,
+    dwallet_coordinator_version: u64,
+    validators: HashMap<ObjectID, StakingPool>,
+    validator_inners: HashMap<ObjectID, Vec<u8>>,
+    mpc_data: HashMap<ObjectID, VersionedMPCData>,
+    network_encryption_keys: HashMap<ObjectID, DWalletNetworkEncryptionKey>,
+    network_encryption_keys_data: HashMap<ObjectID, DWalletNetworkEncryptionKeyData>,
+    reconfiguration_public_outputs: HashMap<(ObjectID, EpochId), ObjectID>,
+    table_vecs: HashMap<ObjectID, Vec<u8>>,
+    uncompleted_events: HashMap<ObjectID, Vec<DBSuiEvent>>,
+    move_packages: Vec<(ObjectID, MovePackageDigest)>,
+    gas_coins: HashMap<SuiAddress, Vec<(ObjectRef, u64)>>,
+    /// The transactions executed on the mocked chain, including those that failed in execution.
+    executed_transactions: Vec<Transaction>,
+    transaction_events: HashMap<TransactionDigest, Vec<SuiEvent>>,
+    /// Execution errors to fail the next executed transactions with, in order.
+    execution_failures: VecDeque<String>,
+    execution_handler: Option<ExecutionHandler>,
+    /// Execution errors to fail the next dry-run transactions with, in order.
+    dry_run_failures: VecDeque<String>,
+    dry_run_handler: Option<DryRunHandler>,
+    /// The number of upcoming calls of each method to fail with an RPC error.
+    rpc_failures: HashMap<&'static str, usize>,
+    calls: HashMap<&'static str, usize>,
+    latency: Duration,
+}
+
+impl Default for SuiMockClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuiMockClient {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SuiMockState {
+                chain_identifier: "mock".to_string(),
+                reference_gas_price: MOCK_REFERENCE_GAS_PRICE,
+                dwallet_coordinator_version: 1,
+                ..Default::default()
+            })),
+            checkpoint_sender: broadcast::channel(10_000).0,
+        }
+    }
+
+    pub fn set_chain_identifier(&self, chain_identifier: String) {
+        self.state.lock().chain_identifier = chain_identifier;
+    }
+
+    pub fn set_reference_gas_price(&self, reference_gas_price: u64) {
+        self.state.lock().reference_gas_price = reference_gas_price;
+    }
+
+    /// Sets the latest checkpoint, which is sent to the checkpoint subscriptions.
+    pub fn set_latest_checkpoint_sequence_number(&self, sequence_number: u64) {
+        self.state.lock().latest_checkpoint_sequence_number = sequence_number;
+        // There may be no subscriptions.
+        let _ = self.checkpoint_sender.send(sequence_number);
+    }
+
+    pub fn set_clock_timestamp_ms(&self, timestamp_ms: u64) {
+        self.state.lock().clock_timestamp_ms = timestamp_ms;
+    }
+
+    /// Makes the clock follow the system time, as on a live chain.
+    pub fn use_system_clock(&self) {
+        self.state.lock().use_system_clock = true;
+    }
+
+    pub fn set_system_inner(&self, system_inner: SystemInnerV1) {
+        self.state.lock().system_inner = Some(system_inner);
+    }
+
+    /// Applies `update` to the served system inner object.
+    ///
+    /// Panics if no system inner object was set.
+    pub fn update_system_inner(&self, update: impl FnOnce(&mut SystemInnerV1)) {
+        update(
+            self.state
+                .lock()
+                .system_inner
+                .as_mut()
+                .expect("the system inner object must be set before it is updated"),
+        );
+    }
+
+    pub fn set_dwallet_coordinator_inner(&self, coordinator_inner: DWalletCoordinatorInnerV1) {
+        self.state.lock().dwallet_coordinator_inner = Some(coordinator_inner);
+    }
+
+    /// Sets the version of the served dWallet coordinator, as after a package migration.
+    pub fn set_dwallet_coordinator_version(&self, version: u64) {
+        self.state.lock().dwallet_coordinator_version = version;
+    }
+
+    /// Applies `update` to the served dWallet coordinator inner object.
+    ///
+    /// Panics if no coordinator inner object was set.
+    pub fn update_dwallet_coordinator_inner(
+        &self,
+        update: impl FnOnce(&mut DWalletCoordinatorInnerV1),
+    ) {
+        update(
+            self.state
+                .lock()
+                .dwallet_coordinator_inner
+                .as_mut()
+                .expect("the coordinator inner object must be set before it is updated"),
+        );
+    }
+
+    pub fn add_validator(&self, validator: StakingPool) {
+        self.state.lock().validators.insert(validator.id, validator);
+    }
+
+    pub fn set_validator_inner(&self, validator_id: ObjectID, inner_bcs: Vec<u8>) {
+        self.state
+            .lock()
+            .validator_inners
+            .insert(validator_id, inner_bcs);
+    }
+
+    pub fn set_mpc_data(&self, validator_id: ObjectID, mpc_data: VersionedMPCData) {
+        self.state.lock().mpc_data.insert(validator_id, mpc_data);
+    }
+
+    /// Adds a network encryption key, with the data served for it at any epoch.
+    pub fn add_network_encryption_key(
+        &self,
+        key: DWalletNetworkEncryptionKey,
+        key_data: DWalletNetworkEncryptionKeyData,
+    ) {
+        let mut state = self.state.lock();
+        state.network_encryption_keys_data.insert(key.id, key_data);
+        state.network_encryption_keys.insert(key.id, key);
+    }
+
+    pub fn set_reconfiguration_public_output(
+        &self,
+        table_id: ObjectID,
+        epoch: EpochId,
+        output_id: ObjectID,
+    ) {
+        self.state
+            .lock()
+            .reconfiguration_public_outputs
+            .insert((table_id, epoch), output_id);
+    }
+
+    pub fn set_table_vec(&self, table_id: ObjectID, bytes: Vec<u8>) {
+        self.state.lock().table_vecs.insert(table_id, bytes);
+    }
+
+    pub fn set_uncompleted_events(&self, events_bag_id: ObjectID, events: Vec<DBSuiEvent>) {
+        self.state
+            .lock()
+            .uncompleted_events
+            .insert(events_bag_id, events);
+    }
+
+    pub fn set_move_packages(&self, packages: Vec<(ObjectID, MovePackageDigest)>) {
+        self.state.lock().move_packages = packages;
+    }
+
+    /// Adds a gas coin with `balance` MIST owned by `owner`, returning its reference.
+    pub fn add_gas_coin(&self, owner: SuiAddress, balance: u64) -> ObjectRef {
+        let coin = (
+            ObjectID::random(),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        );
+        self.state
+            .lock()
+            .gas_coins
+            .entry(owner)
+            .or_default()
+            .push((coin, balance));
+        coin
+    }
+
+    pub fn gas_coins(&self, owner: SuiAddress) -> Vec<(ObjectRef, u64)> {
+        self.state
+            .lock()
+            .gas_coins
+            .get(&owner)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Emits `event` on the mocked chain: it is returned by `query_events`
+    /// after the previously emitted events.
+    /// The latest checkpoint is sent to the checkpoint subscriptions again once the event
+    /// can be read, like a fullnode announces a checkpoint once it indexed its events.
+    pub fn emit_event(&self, event: SuiEvent) {
+        let latest_checkpoint_sequence_number = {
+            let mut state = self.state.lock();
+            state.events.push(event);
+            state.latest_checkpoint_sequence_number
+        };
+        // There may be no subscriptions.
+        let _ = self
+            .checkpoint_sender
+            .send(latest_checkpoint_sequence_number);
+    }
+
+    /// Sets the events returned by `get_events_by_tx_digest` once `tx_digest` is executed.
+    pub fn set_transaction_events(&self, tx_digest: TransactionDigest, events: Vec<SuiEvent>) {
+        self.state
+            .lock()
+            .transaction_events
+            .insert(tx_digest, events);
+    }
+
+    /// The transactions executed so far, including those that failed in execution, in order.
+    pub fn executed_transactions(&self) -> Vec<Transaction> {
+        self.state.lock().executed_transactions.clone()
+    }
+
+    /// Sets the handler that applies every successfully executed transaction to the mocked chain.
+    /// The events it returns are emitted, and the error it returns fails the transaction in
+    /// execution, like a Move abort.
+    pub fn set_execution_handler(&self, handler: ExecutionHandler) {
+        self.state.lock().execution_handler = Some(handler);
+    }
+
+    /// Makes the next executed transaction fail in execution with `error`,
+    /// like a Move abort, after charging its gas.
+    pub fn fail_next_execution(&self, error: String) {
+        self.state.lock().execution_failures.push_back(error);
+    }
+
+    /// Sets the handler that simulates every dry-run transaction.
+    /// Without one, dry runs succeed unless made to fail with [`Self::fail_next_dry_run`].
+    pub fn set_dry_run_handler(&self, handler: DryRunHandler) {
+        self.state.lock().dry_run_handler = Some(handler);
+    }
+
+    /// Makes the next dry-run transaction fail in execution with `error`, like a Move abort.
+    pub fn fail_next_dry_run(&self, error: String) {
+        self.state.lock().dry_run_failures.push_back(error);
+    }
+
+    /// Makes the next `times` calls of the `SuiClientInner` method `method` fail with an RPC error.
+    pub fn fail_next_calls(&self, method: &'static str, times: usize) {
+        *self.state.lock().rpc_failures.entry(method).or_default() += times;
+    }
+
+    /// Delays every call by `latency`, to simulate a slow fullnode.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().latency = latency;
+    }
+
+    /// The number of times the `SuiClientInner` method `method` was called, including failed calls.
+    pub fn calls(&self, method: &'static str) -> usize {
+        self.state
+            .lock()
+            .calls
+            .get(method)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The chunk objects of the `TableVec` `table_id`, split from the bytes set for it.
+    fn table_vec_chunks(&self, table_id: ObjectID) -> Result<Vec<Object>, Error> {
+        let bytes = self
+            .state
+            .lock()
+            .table_vecs
+            .get(&table_id)
+            .cloned()
+            .ok_or_else(|| Error::DataError(format!("unknown table vec {table_id}")))?;
+        table_vec_chunk_objects(table_id, &bytes)
+    }
+
+    /// Counts the call of `method`, waits for the simulated latency,
+    /// and fails the call if a failure was injected for it.
+    async fn begin_call(&self, method: &'static str) -> Result<(), Error> {
+        let latency = {
+            let mut state = self.state.lock();
+            *state.calls.entry(method).or_default() += 1;
+            state.latency
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let mut state = self.state.lock();
+        match state.rpc_failures.get_mut(method) {
+            Some(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                Err(Error::DataError(format!("injected failure of `{method}`")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn system_inner(&self) -> Result<SystemInnerV1, Error> {
+        self.state
+            .lock()
+            .system_inner
+            .clone()
+            .ok_or_else(|| Error::DataError("no system inner object was set".to_string()))
+    }
+
+    fn dwallet_coordinator_inner(&self) -> Result<DWalletCoordinatorInnerV1, Error> {
+        self.state
+            .lock()
+            .dwallet_coordinator_inner
+            .clone()
+            .ok_or_else(|| {
+                Error::DataError("no dWallet coordinator inner object was set".to_string())
+            })
+    }
+}
+
+fn event_matches(filter: &EventFilter, event: &SuiEvent) -> Result<bool, Error> {
+    match filter {
+        EventFilter::MoveEventModule { package, module } => {
+            Ok(event.type_.address.as_ref() == package.as_ref() && &event.type_.module == module)
+        }
+        _ => Err(Error::DataError(format!(
+            "unsupported event filter {filter:?}"
+        ))),
+    }
+}
+
+/// The `module::function` names of the Move calls in `transaction`, in order.
+pub fn move_calls(transaction: &Transaction) -> Vec<String> {
+    let TransactionKind::ProgrammableTransaction(pt) = transaction.data().transaction_data().kind()
+    else {
+        return vec![];
+    };
+    pt.commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::MoveCall(call) => Some(format!("{}::{}", call.module, call.function)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The amounts `transaction` splits from its gas coin, in order.
+fn gas_coin_split_amounts(transaction: &Transaction) -> Vec<u64> {
+    let TransactionKind::ProgrammableTransaction(pt) = transaction.data().transaction_data().kind()
+    else {
+        return vec![];
+    };
+    pt.commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::SplitCoins(Argument::GasCoin, amounts) => Some(amounts),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|amount| match amount {
+            Argument::Input(index) => match pt.inputs.get(*index as usize) {
+                Some(CallArg::Pure(bytes)) => bcs::from_bytes::<u64>(bytes).ok(),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The owned coins `transaction` merges into its gas coin.
+fn gas_coin_merged_coins(transaction: &Transaction) -> Vec<ObjectRef> {
+    let TransactionKind::ProgrammableTransaction(pt) = transaction.data().transaction_data().kind()
+    else {
+        return vec![];
+    };
+    pt.commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::MergeCoins(Argument::GasCoin, coins) => Some(coins),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|coin| match coin {
+            Argument::Input(index) => match pt.inputs.get(*index as usize) {
+                Some(CallArg::Object(ObjectArg::ImmOrOwnedObject(coin))) => Some(*coin),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The effects of a transaction that was charged [`MOCK_COMPUTATION_COST`] and ended with `status`.
+fn mock_effects(
+    digest: TransactionDigest,
+    status: SuiExecutionStatus,
+) -> Result<SuiTransactionBlockEffects, Error> {
+    let mut effects = SuiTransactionBlockEffects::try_from(TransactionEffects::default())
+        .map_err(|e| Error::DataError(format!("can't build transaction effects: {e}")))?;
+    let SuiTransactionBlockEffects::V1(effects_v1) = &mut effects;
+    effects_v1.status = status;
+    effects_v1.gas_used = GasCostSummary::new(MOCK_COMPUTATION_COST, 0, 0, 0);
+    effects_v1.transaction_digest = digest;
+    Ok(effects)
+}
+
+fn new_bag() -> Bag {
+    Bag {
+        id: UID::new(ObjectID::random()),
+        size: 0,
+    }
+}
+
+fn new_table() -> Table {
+    Table {
+        id: ObjectID::random(),
+        size: 0,
+    }
+}
+
+fn new_sessions_keeper() -> SessionsKeeper {
+    SessionsKeeper {
+        sessions: new_table(),
+        session_events: new_bag(),
+        started_sessions_count: 0,
+        completed_sessions_count: 0,
+        next_session_sequence_number: 1,
+    }
+}
+
+/// A committee of `size` validators with random IDs, equal voting power and empty keys.
+pub fn bls_committee_for_testing(size: usize) -> BlsCommittee {
+    BlsCommittee {
+        members: (0..size)
+            .map(|_| BlsCommitteeMember {
+                validator_id: ObjectID::random(),
+                protocol_pubkey: Element::new(vec![]),
+            })
+            .collect(),
+        aggregated_protocol_pubkey: Element::new(vec![]),
+        quorum_threshold: (2 * size as u64) / 3 + 1,
+        validity_threshold: size as u64 / 3 + 1,
+    }
+}
+
+/// A system inner object at the start of `epoch`, with `committee` as the active committee.
+pub fn system_inner_v1_for_testing(epoch: u64, committee: BlsCommittee) -> SystemInnerV1 {
+    SystemInnerV1 {
+        epoch,
+        epoch_start_tx_digest: vec![],
+        system_object_cap: SystemObjectCap {
+            id: ObjectID::random(),
+        },
+        protocol_version: 1,
+        next_protocol_version: None,
+        upgrade_caps: vec![],
+        approved_upgrades: VecMap { contents: vec![] },
+        validator_set: ValidatorSetV1 {
+            total_stake: 0,
+            reward_slashing_rate: 0,
+            validators: new_table(),
+            active_committee: committee.clone(),
+            next_epoch_committee: None,
+            previous_committee: committee,
+            pending_active_set: ExtendedField {
+                id: ObjectID::random(),
+            },
+            validator_report_records: VecMap { contents: vec![] },
+            extra_fields: new_bag(),
+        },
+        epoch_duration_ms: 24 * 60 * 60 * 1000,
+        stake_subsidy_start_epoch: 0,
+        protocol_treasury: ProtocolTreasuryV1 {
+            treasury_cap: TreasuryCap {
+                id: UID::new(ObjectID::random()),
+                total_supply: Supply { value: 0 },
+            },
+            stake_subsidy_distribution_counter: 0,
+            stake_subsidy_rate: 0,
+            stake_subsidy_amount_per_distribution: 0,
+            stake_subsidy_period_length: 0,
+            total_supply_at_period_start: 0,
+            extra_fields: new_bag(),
+        },
+        epoch_start_timestamp_ms: 0,
+        last_processed_checkpoint_sequence_number: 0,
+        previous_epoch_last_checkpoint_sequence_number: 0,
+        total_messages_processed: 0,
+        remaining_rewards: Balance::new(0),
+        authorized_protocol_cap_ids: vec![],
+        witness_approving_advance_epoch: vec![],
+        received_end_of_publish: false,
+        extra_fields: new_bag(),
+    }
+}
+
+/// A dWallet coordinator inner object at the start of `epoch`,
+/// with `committee` as the active committee.
+pub fn dwallet_coordinator_inner_v1_for_testing(
+    epoch: u64,
+    committee: BlsCommittee,
+) -> DWalletCoordinatorInnerV1 {
+    DWalletCoordinatorInnerV1 {
+        current_epoch: epoch,
+        sessions_manager: SessionsManager {
+            registered_user_session_identifiers: new_table(),
+            user_sessions_keeper: new_sessions_keeper(),
+            system_sessions_keeper: new_sessions_keeper(),
+            last_user_initiated_session_to_complete_in_current_epoch: 0,
+            locked_last_user_initiated_session_to_complete_in_current_epoch: false,
+            max_active_sessions_buffer: 100,
+        },
+        dwallets: new_table(),
+        dwallet_network_encryption_keys: new_table(),
+        epoch_dwallet_network_encryption_keys_reconfiguration_completed: 0,
+        encryption_keys: new_table(),
+        presigns: new_table(),
+        partial_centralized_signed_messages: new_table(),
+        pricing_and_fee_management: PricingAndFeeManagement {
+            current: PricingInfo {
+                pricing_map: VecMap { contents: vec![] },
+            },
+            default: PricingInfo {
+                pricing_map: VecMap { contents: vec![] },
+            },
+            validator_votes: new_table(),
+            calculation_votes: None,
+            gas_fee_reimbursement_sui_system_call_value: 0,
+            gas_fee_reimbursement_sui_system_call_balance: Balance::new(0),
+            fee_charged_ika: Balance::new(0),
+        },
+        active_committee: committee,
+        next_epoch_active_committee: None,
+        total_messages_processed: 0,
+        last_processed_checkpoint_sequence_number: 0,
+        previous_epoch_last_checkpoint_sequence_number: 0,
+        support_config: SupportConfig {
+            supported_curves_to_signature_algorithms_to_hash_schemes: VecMap { contents: vec![] },
+            paused_curves: vec![],
+            paused_signature_algorithms: vec![],
+            paused_hash_schemes: vec![],
+            signature_algorithms_allowed_global_presign: vec![],
+        },
+        received_end_of_publish: false,
+        extra_fields: new_bag(),
+    }
+}
+
+fn to_bcs<T: Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    bcs::to_bytes(value).map_err(|e| Error::DataError(format!("can't serialize: {e}")))
+}
+
+/// The chunk objects of a `TableVec<vector<u8>>` with the ID `table_id` holding `bytes`.
+fn table_vec_chunk_objects(table_id: ObjectID, bytes: &[u8]) -> Result<Vec<Object>, Error> {
+    let chunk_type =
+        DynamicFieldInfo::dynamic_field_type(TypeTag::U64, TypeTag::Vector(Box::new(TypeTag::U8)));
+    bytes
+        .chunks(TABLE_VEC_CHUNK_SIZE)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let index = index as u64;
+            let id = derive_dynamic_field_id(table_id, &TypeTag::U64, &to_bcs(&index)?)
+                .map_err(|e| Error::DataError(format!("can't derive a chunk ID: {e}")))?;
+            let contents = to_bcs(&Field {
+                id: UID::new(id),
+                name: index,
+                value: chunk.to_vec(),
+            })?;
+            // SAFETY: dynamic fields don't have the `store` ability, so they have no public transfer.
+            let move_object = unsafe {
+                MoveObject::new_from_execution_with_limit(
+                    chunk_type.clone().into(),
+                    false,
+                    SequenceNumber::from_u64(1),
+                    contents,
+                    MAX_MOVE_OBJECT_SIZE,
+                )
+            }
+            .map_err(|e| Error::DataError(format!("can't create a chunk object: {e}")))?;
+            Ok(Object::new_move(
+                move_object,
+                Owner::ObjectOwner(table_id.into()),
+                TransactionDigest::genesis_marker(),
+            ))
+        })
+        .collect()
+}
+
+fn shared_object_arg(id: ObjectID, mutable: bool) -> ObjectArg {
+    ObjectArg::SharedObject {
+        id,
+        initial_shared_version: SequenceNumber::from_u64(1),
+        mutable,
+    }
+}
+
+#[async_trait]
+impl SuiClientInner for SuiMockClient {
+    type Error = Error;
+
+    async fn query_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+    ) -> Result<EventPage, Self::Error> {
+        self.begin_call("query_events").await?;
+        let state = self.state.lock();
+        let start = match cursor {
+            Some(cursor) => state
+                .events
+                .iter()
+                .position(|event| event.id == cursor)
+                .map(|index| index + 1)
+                .ok_or_else(|| Error::DataError(format!("unknown event cursor {cursor:?}")))?,
+            None => 0,
+        };
+        let mut data = vec![];
+        let mut has_next_page = false;
+        for event in &state.events[start..] {
+            if !event_matches(&query, event)? {
+                continue;
+            }
+            if data.len() == EVENT_PAGE_SIZE {
+                has_next_page = true;
+                break;
+            }
+            data.push(event.clone());
+        }
+        let next_cursor = data.last().map(|event| event.id).or(cursor);
+        Ok(EventPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn get_events_by_tx_digest(
+        &self,
+        tx_digest: TransactionDigest,
+    ) -> Result<Vec<SuiEvent>, Self::Error> {
+        self.begin_call("get_events_by_tx_digest").await?;
+        let state = self.state.lock();
+        if !state
+            .executed_transactions
+            .iter()
+            .any(|transaction| *transaction.digest() == tx_digest)
+        {
+            return Err(Error::DataError(format!(
+                "transaction {tx_digest} was not executed"
+            )));
+        }
+        Ok(state
+            .transaction_events
+            .get(&tx_digest)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_chain_identifier(&self) -> Result<String, Self::Error> {
+        self.begin_call("get_chain_identifier").await?;
+        Ok(self.state.lock().chain_identifier.clone())
+    }
+
+    async fn get_reference_gas_price(&self) -> Result<u64, Self::Error> {
+        self.begin_call("get_reference_gas_price").await?;
+        Ok(self.state.lock().reference_gas_price)
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, Self::Error> {
+        self.begin_call("get_latest_checkpoint_sequence_number")
+            .await?;
+        Ok(self.state.lock().latest_checkpoint_sequence_number)
+    }
+
+    async fn subscribe_checkpoints(
+        &self,
+    ) -> Result<BoxStream<'static, Result<u64, Self::Error>>, Self::Error> {
+        self.begin_call("subscribe_checkpoints").await?;
+        let receiver = self.checkpoint_sender.subscribe();
+        Ok(
+            futures::stream::unfold(receiver, |mut receiver| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(sequence_number) => return Some((Ok(sequence_number), receiver)),
+                        // Lagged checkpoints are skipped, the next one still signals new events.
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            })
+            .boxed(),
+        )
+    }
+
+    async fn get_system(&self, ika_system_object_id: ObjectID) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("get_system").await?;
+        self.system_inner()?;
+        to_bcs(&System {
+            id: ika_system_object_id,
+            version: 1,
+            package_id: ObjectID::ZERO,
+            new_package_id: None,
+            migration_epoch: None,
+        })
+    }
+
+    async fn get_clock(&self, _clock_obj_id: ObjectID) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("get_clock").await?;
+        let timestamp_ms = {
+            let state = self.state.lock();
+            if state.use_system_clock {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|e| Error::DataError(format!("invalid system time: {e}")))?
+                    .as_millis() as u64
+            } else {
+                state.clock_timestamp_ms
+            }
+        };
+        // The BCS layout of `sui_types::clock::Clock`.
+        to_bcs(&(SUI_CLOCK_OBJECT_ID, timestamp_ms))
+    }
+
+    async fn get_dwallet_coordinator(
+        &self,
+        dwallet_coordinator_id: ObjectID,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("get_dwallet_coordinator").await?;
+        self.dwallet_coordinator_inner()?;
+        to_bcs(&DWalletCoordinator {
+            id: dwallet_coordinator_id,
+            version: self.state.lock().dwallet_coordinator_version,
+            package_id: ObjectID::ZERO,
+            new_package_id: None,
+            migration_epoch: None,
+        })
+    }
+
+    async fn get_mpc_data_from_validators_pool(
+        &self,
+        validators: &Vec<StakingPool>,
+        _read_next_epoch_mpc_data: bool,
+    ) -> Result<HashMap<ObjectID, VersionedMPCData>, Self::Error> {
+        self.begin_call("get_mpc_data_from_validators_pool").await?;
+        let state = self.state.lock();
+        Ok(validators
+            .iter()
+            .filter_map(|validator| {
+                state
+                    .mpc_data
+                    .get(&validator.id)
+                    .map(|mpc_data| (validator.id, mpc_data.clone()))
+            })
+            .collect())
+    }
+
+    async fn get_network_encryption_keys(
+        &self,
+        _dwallet_coordinator_inner: &DWalletCoordinatorInnerV1,
+    ) -> Result<HashMap<ObjectID, DWalletNetworkEncryptionKey>, Self::Error> {
+        self.begin_call("get_network_encryption_keys").await?;
+        Ok(self.state.lock().network_encryption_keys.clone())
+    }
+
+    async fn get_network_encryption_key_with_full_data_by_epoch(
+        &self,
+        network_decryption_key: &DWalletNetworkEncryptionKey,
+        _epoch: EpochId,
+    ) -> Result<DWalletNetworkEncryptionKeyData, Self::Error> {
+        self.begin_call("get_network_encryption_key_with_full_data_by_epoch")
+            .await?;
+        self.state
+            .lock()
+            .network_encryption_keys_data
+            .get(&network_decryption_key.id)
+            .cloned()
+            .ok_or_else(|| {
+                Error::DataError(format!(
+                    "unknown network encryption key {}",
+                    network_decryption_key.id
+                ))
+            })
+    }
+
+    async fn get_current_reconfiguration_public_output(
+        &self,
+        epoch_id: EpochId,
+        table_id: ObjectID,
+    ) -> Result<ObjectID, Self::Error> {
+        self.begin_call("get_current_reconfiguration_public_output")
+            .await?;
+        self.state
+            .lock()
+            .reconfiguration_public_outputs
+            .get(&(table_id, epoch_id))
+            .copied()
+            .ok_or_else(|| {
+                Error::DataError(format!(
+                    "no reconfiguration public output in table {table_id} for epoch {epoch_id}"
+                ))
+            })
+    }
+
+    async fn read_table_vec_as_raw_bytes(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("read_table_vec_as_raw_bytes").await?;
+        self.state
+            .lock()
+            .table_vecs
+            .get(&table_id)
+            .cloned()
+            .ok_or_else(|| Error::DataError(format!("unknown table vec {table_id}")))
+    }
+
+    async fn get_table_vec_chunk_refs(
+        &self,
+        table_id: ObjectID,
+    ) -> Result<Vec<ObjectRef>, Self::Error> {
+        self.begin_call("get_table_vec_chunk_refs").await?;
+        Ok(self
+            .table_vec_chunks(table_id)?
+            .iter()
+            .map(Object::compute_object_reference)
+            .collect())
+    }
+
+    async fn read_table_vec_chunks(&self, table_id: ObjectID) -> Result<Vec<Object>, Self::Error> {
+        self.begin_call("read_table_vec_chunks").await?;
+        self.table_vec_chunks(table_id)
+    }
+
+    async fn get_system_inner(
+        &self,
+        ika_system_object_id: ObjectID,
+        version: u64,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("get_system_inner").await?;
+        to_bcs(&Field {
+            id: UID::new(ika_system_object_id),
+            name: version,
+            value: self.system_inner()?,
+        })
+    }
+
+    async fn get_dwallet_coordinator_inner(
+        &self,
+        dwallet_coordinator_id: ObjectID,
+        version: u64,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.begin_call("get_dwallet_coordinator_inner").await?;
+        to_bcs(&Field {
+            id: UID::new(dwallet_coordinator_id),
+            name: version,
+            value: self.dwallet_coordinator_inner()?,
+        })
+    }
+
+    async fn get_validators(
+        &self,
+        validator_ids: Vec<ObjectID>,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        self.begin_call("get_validators").await?;
+        let state = self.state.lock();
+        validator_ids
+            .iter()
+            .map(|validator_id| {
+                let validator = state
+                    .validators
+                    .get(validator_id)
+                    .ok_or_else(|| Error::DataError(format!("unknown validator {validator_id}")))?;
+                to_bcs(validator)
+            })
+            .collect()
+    }
+
+    async fn get_validator_inners(
+        &self,
+        validators: Vec<Validator>,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        self.begin_call("get_validator_inners").await?;
+        let state = self.state.lock();
+        validators
+            .iter()
+            .map(|validator| {
+                state
+                    .validator_inners
+                    .get(&validator.id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::DataError(format!("unknown validator inner {}", validator.id))
+                    })
+            })
+            .collect()
+    }
+
+    async fn get_mutable_shared_arg(
+        &self,
+        ika_system_object_id: ObjectID,
+    ) -> Result<ObjectArg, Self::Error> {
+        self.begin_call("get_mutable_shared_arg").await?;
+        Ok(shared_object_arg(ika_system_object_id, true))
+    }
+
+    async fn get_shared_arg(&self, obj_id: ObjectID) -> Result<ObjectArg, Self::Error> {
+        self.begin_call("get_shared_arg").await?;
+        Ok(shared_object_arg(obj_id, false))
+    }
+
+    async fn get_available_move_packages(
+        &self,
+        _ika_package_id: ObjectID,
+        _ika_system_package_id: ObjectID,
+    ) -> Result<Vec<(ObjectID, MovePackageDigest)>, Self::Error> {
+        self.begin_call("get_available_move_packages").await?;
+        Ok(self.state.lock().move_packages.clone())
+    }
+
+    /// Executes `tx` by charging its gas, splitting the gas coin as the transaction requests,
+    /// applying it with the execution handler, and recording it.
+    /// As on Sui, the gas payment must consist of the current versions of the owner's coins.
+    /// The coins split from the gas coin are owned by the gas owner.
+    async fn execute_transaction_block_with_effects(
+        &self,
+        tx: Transaction,
+    ) -> Result<SuiTransactionBlockResponse, IkaError> {
+        let digest = *tx.digest();
+        self.begin_call("execute_transaction_block_with_effects")
+            .await
+            .map_err(|e| IkaError::SuiClientTxFailureGeneric(digest, e.to_string()))?;
+
+        let mut state = self.state.lock();
+        let tx_data = tx.data().transaction_data();
+        let gas_owner = tx_data.gas_owner();
+        let gas_payment = tx_data.gas().to_vec();
+        let coins = state.gas_coins.entry(gas_owner).or_default();
+        if gas_payment.is_empty()
+            || gas_payment
+                .iter()
+                .any(|payment| !coins.iter().any(|(coin, _)| coin == payment))
+        {
+            return Err(IkaError::SuiClientTxFailureGeneric(
+                digest,
+                format!("gas payment {gas_payment:?} is not a current coin of {gas_owner}"),
+            ));
+        }
+        let merged_coins = gas_coin_merged_coins(&tx);
+        if merged_coins
+            .iter()
+            .any(|merged| !coins.iter().any(|(coin, _)| coin == merged))
+        {
+            return Err(IkaError::SuiClientTxFailureGeneric(
+                digest,
+                format!("merged coins {merged_coins:?} are not current coins of {gas_owner}"),
+            ));
+        }
+        // The gas payment coins, and the coins merged into the gas coin, are merged into
+        // the first gas payment coin, which pays for the transaction.
+        let mut balance = coins
+            .iter()
+            .filter(|(coin, _)| gas_payment.contains(coin) || merged_coins.contains(coin))
+            .map(|(_, balance)| balance)
+            .sum::<u64>()
+            .saturating_sub(MOCK_COMPUTATION_COST);
+        let split_amounts = gas_coin_split_amounts(&tx);
+        let split_balance = split_amounts.iter().sum::<u64>();
+        if split_balance > balance {
+            return Err(IkaError::SuiClientTxFailureGeneric(
+                digest,
+                format!("can't split {split_balance} from a gas coin of balance {balance}"),
+            ));
+        }
+        balance -= split_balance;
+        coins.retain(|(coin, _)| !gas_payment.contains(coin) && !merged_coins.contains(coin));
+        let gas_object = (
+            gas_payment[0].0,
+            SequenceNumber::from_u64(gas_payment[0].1.value() + 1),
+            ObjectDigest::random(),
+        );
+        coins.push((gas_object, balance));
+        let created = split_amounts
+            .into_iter()
+            .map(|amount| {
+                let coin = (
+                    ObjectID::random(),
+                    SequenceNumber::from_u64(1),
+                    ObjectDigest::random(),
+                );
+                coins.push((coin, amount));
+                coin
+            })
+            .collect::<Vec<_>>();
+
+        let injected_failure = state.execution_failures.pop_front();
+        let execution_handler = state.execution_handler.clone();
+        state.executed_transactions.push(tx.clone());
+        drop(state);
+
+        // The handler may read the mocked chain, so it runs without holding the state lock.
+        let status = match (injected_failure, execution_handler) {
+            (Some(error), _) => SuiExecutionStatus::Failure { error },
+            (None, Some(handler)) => match handler(&tx) {
+                Ok(events) => {
+                    self.set_transaction_events(digest, events.clone());
+                    events.into_iter().for_each(|event| self.emit_event(event));
+                    SuiExecutionStatus::Success
+                }
+                Err(error) => SuiExecutionStatus::Failure { error },
+            },
+            (None, None) => SuiExecutionStatus::Success,
+        };
+
+        let mut effects = mock_effects(digest, status)
+            .map_err(|e| IkaError::SuiClientTxFailureGeneric(digest, e.to_string()))?;
+        let SuiTransactionBlockEffects::V1(effects_v1) = &mut effects;
+        effects_v1.gas_object = OwnedObjectRef {
+            owner: Owner::AddressOwner(gas_owner),
+            reference: gas_object.into(),
+        };
+        effects_v1.created = created
+            .into_iter()
+            .map(|coin| OwnedObjectRef {
+                owner: Owner::AddressOwner(gas_owner),
+                reference: coin.into(),
+            })
+            .collect();
+        let mut response = SuiTransactionBlockResponse::new(digest);
+        response.effects = Some(effects);
+        Ok(response)
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionBlockEffects, Self::Error> {
+        self.begin_call("dry_run_transaction_block").await?;
+        let (injected_failure, dry_run_handler) = {
+            let mut state = self.state.lock();
+            (
+                state.dry_run_failures.pop_front(),
+                state.dry_run_handler.clone(),
+            )
+        };
+        let status = match (injected_failure, dry_run_handler) {
+            (Some(error), _) => SuiExecutionStatus::Failure { error },
+            (None, Some(handler)) => match handler(&tx_data) {
+                Ok(()) => SuiExecutionStatus::Success,
+                Err(error) => SuiExecutionStatus::Failure { error },
+            },
+            (None, None) => SuiExecutionStatus::Success,
+        };
+        mock_effects(tx_data.digest(), status)
+    }
+
+    async fn get_gas_objects(&self, address: SuiAddress) -> Vec<ObjectRef> {
+        self.gas_coins(address)
+            .into_iter()
+            .map(|(coin, _)| coin)
+            .collect()
+    }
+
+    async fn get_gas_coins(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<(ObjectRef, u64)>, Self::Error> {
+        self.begin_call("get_gas_coins").await?;
+        Ok(self.gas_coins(address))
+    }
+
+    async fn get_uncompleted_events(
+        &self,
+        events_bag_id: ObjectID,
+    ) -> Result<Vec<DBSuiEvent>, Self::Error> {
+        self.begin_call("get_uncompleted_events").await?;
+        Ok(self
+            .state
+            .lock()
+            .uncompleted_events
+            .get(&events_bag_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SuiClient;
+    use ika_types::sui::SystemInnerTrait;
+    use sui_json_rpc_types::BcsEvent;
+
+    fn event(package: ObjectID, module: &str, event_seq: u64) -> SuiEvent {
+        let module = Identifier::new(module).unwrap();
+        SuiEvent {
+            id: EventID {
+                tx_digest: TransactionDigest::random(),
+                event_seq,
+            },
+            package_id: package,
+            transaction_module: module.clone(),
+            sender: SuiAddress::random_for_testing_only(),
+            type_: move_core_types::language_storage::StructTag {
+                address: package.into(),
+                module,
+                name: Identifier::new("Event").unwrap(),
+                type_params: vec![],
+            },
+            parsed_json: Default::default(),
+            bcs: BcsEvent::new(vec![]),
+            timestamp_ms: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn query_events_pages_by_module_from_cursor() {
+        let mock = SuiMockClient::new();
+        let package = ObjectID::random();
+        for event_seq in 0..(EVENT_PAGE_SIZE as u64 + 10) {
+            mock.emit_event(event(package, "sessions_manager", event_seq));
+            mock.emit_event(event(package, "other", event_seq));
+        }
+        let filter = EventFilter::MoveEventModule {
+            package,
+            module: Identifier::new("sessions_manager").unwrap(),
+        };
+
+        let first_page = mock.query_events(filter.clone(), None).await.unwrap();
+        assert_eq!(first_page.data.len(), EVENT_PAGE_SIZE);
+        assert!(first_page.has_next_page);
+        let second_page = mock
+            .query_events(filter.clone(), first_page.next_cursor)
+            .await
+            .unwrap();
+        assert_eq!(second_page.data.len(), 10);
+        assert!(!second_page.has_next_page);
+        assert!(
+            second_page
+                .data
+                .iter()
+                .all(|event| event.transaction_module.as_str() == "sessions_manager")
+        );
+
+        // Polling at the end of the stream keeps the cursor.
+        let empty_page = mock
+            .query_events(filter, second_page.next_cursor)
+            .await
+            .unwrap();
+        assert!(empty_page.data.is_empty());
+        assert_eq!(empty_page.next_cursor, second_page.next_cursor);
+    }
+
+    #[tokio::test]
+    async fn serves_system_inner_and_injected_failures() {
+        let mock = SuiMockClient::new();
+        mock.set_system_inner(system_inner_v1_for_testing(7, bls_committee_for_testing(4)));
+        let sui_client = SuiClient::new_for_testing(mock.clone());
+
+        assert_eq!(sui_client.get_system_inner().await.unwrap().epoch(), 7);
+        mock.update_system_inner(|system_inner| system_inner.epoch = 8);
+        assert_eq!(sui_client.get_system_inner().await.unwrap().epoch(), 8);
+
+        mock.fail_next_calls("get_system", 1);
+        assert!(sui_client.get_system_inner().await.is_err());
+        assert!(sui_client.get_system_inner().await.is_ok());
+        assert_eq!(mock.calls("get_system"), 4);
+    }
+
+    #[tokio::test]
+    async fn serves_table_vec_chunks() {
+        let mock = SuiMockClient::new();
+        let table_id = ObjectID::random();
+        let bytes: Vec<u8> = (0..TABLE_VEC_CHUNK_SIZE * 2 + 10)
+            .map(|i| i as u8)
+            .collect();
+        mock.set_table_vec(table_id, bytes.clone());
+
+        let chunks = mock.read_table_vec_chunks(table_id).await.unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            mock.get_table_vec_chunk_refs(table_id).await.unwrap(),
+            chunks
+                .iter()
+                .map(Object::compute_object_reference)
+                .collect::<Vec<_>>()
+        );
+        let chunk_bytes: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| {
+                let contents = chunk.data.try_as_move().unwrap().contents();
+                bcs::from_bytes::<Field<u64, Vec<u8>>>(contents)
+                    .unwrap()
+                    .value
+            })
+            .collect();
+        assert_eq!(chunk_bytes, bytes);
+        assert_eq!(
+            mock.read_table_vec_as_raw_bytes(table_id).await.unwrap(),
+            bytes
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
+#[cfg(feature = "local-sui")]
+pub mod local_sui;
 pub mod network_config;
 pub mod network_config_builder;
 pub mod node_config_builder;
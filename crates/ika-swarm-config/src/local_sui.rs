@@ -0,0 +1,87 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+use crate::validator_initialization_config::ValidatorInitializationConfig;
+use fastcrypto::traits::ToFromBytes;
+use ika_config::initiation::InitiationParameters;
+use ika_sui_client::local_sui::{LocalSuiNetwork, LocalSuiValidator};
+use ika_types::crypto::get_key_pair_from_rng;
+use rand::rngs::OsRng;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::SuiKeyPair;
+
+/// The number of gas coins given to every funded address, like a faucet request.
+const GAS_COINS_PER_ADDRESS: usize = 5;
+/// 200 SUI, in MIST.
+const GAS_COIN_BALANCE: u64 = 200_000_000_000;
+
+/// Starts a [`LocalSuiNetwork`] with the validators as its genesis committee,
+/// in place of publishing the Ika packages to a Sui network with [`crate::sui_client::init_ika_on_sui`].
+/// Returns the same IDs and publisher key pair, funded to pay for the notifier's transactions.
+pub fn init_ika_on_local_sui(
+    validator_initialization_configs: &[ValidatorInitializationConfig],
+    local_sui_url: &str,
+    initiation_parameters: InitiationParameters,
+) -> Result<
+    (
+        ObjectID,
+        ObjectID,
+        ObjectID,
+        ObjectID,
+        ObjectID,
+        ObjectID,
+        SuiKeyPair,
+    ),
+    anyhow::Error,
+> {
+    let Some(name) = local_sui_url.strip_prefix(ika_sui_client::local_sui::LOCAL_SUI_URL_SCHEME)
+    else {
+        anyhow::bail!("{local_sui_url} is not a local Sui network URL");
+    };
+    let validators = validator_initialization_configs
+        .iter()
+        .map(|config| {
+            let validator_info = config.to_validator_info();
+            LocalSuiValidator {
+                name: validator_info.name,
+                protocol_pubkey_bytes: validator_info.protocol_public_key.as_bytes().to_vec(),
+                network_pubkey_bytes: validator_info.network_public_key.as_bytes().to_vec(),
+                consensus_pubkey_bytes: validator_info.consensus_public_key.as_bytes().to_vec(),
+                network_address: validator_info.network_address.to_string(),
+                p2p_address: validator_info.p2p_address.to_string(),
+                consensus_address: validator_info.consensus_address.to_string(),
+                mpc_data: validator_info.mpc_data,
+            }
+        })
+        .collect();
+    let network = LocalSuiNetwork::start(
+        name,
+        validators,
+        initiation_parameters.epoch_duration_ms,
+        initiation_parameters.protocol_version,
+    )?;
+
+    let publisher_keypair = SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut OsRng).1);
+    let publisher_address = SuiAddress::from(&publisher_keypair.public());
+    let validator_addresses = validator_initialization_configs
+        .iter()
+        .map(|config| SuiAddress::from(&config.account_key_pair.public()));
+    for address in std::iter::once(publisher_address).chain(validator_addresses) {
+        for _ in 0..GAS_COINS_PER_ADDRESS {
+            network.fund(address, GAS_COIN_BALANCE);
+        }
+    }
+
+    network.request_network_encryption_key_dkg();
+
+    let ika_network_config = network.ika_network_config();
+    Ok((
+        ika_network_config.packages.ika_package_id,
+        ika_network_config.packages.ika_common_package_id,
+        ika_network_config.packages.ika_dwallet_2pc_mpc_package_id,
+        ika_network_config.packages.ika_system_package_id,
+        ika_network_config.objects.ika_system_object_id,
+        ika_network_config.objects.ika_dwallet_coordinator_object_id,
+        publisher_keypair,
+    ))
+}
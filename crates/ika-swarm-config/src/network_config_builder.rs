@@ -11,6 +11,8 @@ use ika_config::node::{
     RunWithRange,
 };
 use ika_protocol_config::ProtocolVersion;
+#[cfg(feature = "local-sui")]
+use ika_sui_client::local_sui::is_local_sui_url;
 use ika_types::committee::Committee;
 use ika_types::crypto::AuthorityName;
 use ika_types::crypto::{AccountKeyPair, KeypairTraits, get_key_pair_from_rng};
@@ -324,6 +326,18 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
         if let Some(protocol_version) = self.protocol_version {
             initiation_parameters.protocol_version = protocol_version.as_u64();
         }
+        #[cfg(feature = "local-sui")]
+        let local_sui_deployment = is_local_sui_url(&self.sui_fullnode_rpc_url)
+            .then(|| {
+                crate::local_sui::init_ika_on_local_sui(
+                    &validator_initialization_configs,
+                    &self.sui_fullnode_rpc_url,
+                    initiation_parameters.clone(),
+                )
+            })
+            .transpose()?;
+        #[cfg(not(feature = "local-sui"))]
+        let local_sui_deployment = None;
         let (
             ika_package_id,
             ika_common_package_id,
@@ -332,13 +346,18 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
             ika_system_object_id,
             ika_dwallet_coordinator_object_id,
             publisher_keypair,
-        ) = crate::sui_client::init_ika_on_sui(
-            &validator_initialization_configs,
-            self.sui_fullnode_rpc_url.to_string(),
-            self.sui_faucet_url.to_string(),
-            initiation_parameters,
-        )
-        .await?;
+        ) = match local_sui_deployment {
+            Some(deployment) => deployment,
+            None => {
+                crate::sui_client::init_ika_on_sui(
+                    &validator_initialization_configs,
+                    self.sui_fullnode_rpc_url.to_string(),
+                    self.sui_faucet_url.to_string(),
+                    initiation_parameters,
+                )
+                .await?
+            }
+        };
 
         let validator_configs = validator_initialization_configs
             .iter()
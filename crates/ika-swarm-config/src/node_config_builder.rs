@@ -11,7 +11,7 @@ use fastcrypto::traits::KeyPair;
 use ika_config::node::{
     AuthorityKeyPairWithPath, AuthorityOverloadConfig, KeyPairWithPath, RootSeedWithPath,
     RunWithRange, StateArchiveConfig, SuiChainIdentifier, SuiConnectorConfig,
-    default_end_of_epoch_broadcast_channel_capacity,
+    default_end_of_epoch_broadcast_channel_capacity, default_notifier_gas_coin_count,
 };
 use std::path::PathBuf;
 use sui_types::base_types::ObjectID;
@@ -128,6 +128,7 @@ impl ValidatorConfigBuilder {
         };
         NodeConfig {
             root_seed_key_pair: Some(RootSeedWithPath::new(validator.root_seed.clone())),
+            next_root_seed_key_pair: None,
             protocol_key_pair: AuthorityKeyPairWithPath::new(validator.key_pair.copy()),
             network_key_pair: KeyPairWithPath::new(SuiKeyPair::Ed25519(
                 validator.network_key_pair.copy(),
@@ -138,6 +139,7 @@ impl ValidatorConfigBuilder {
             )),
             sui_connector_config: SuiConnectorConfig {
                 sui_rpc_url: sui_rpc_url.to_string(),
+                additional_sui_rpc_urls: vec![],
                 sui_chain_identifier: SuiChainIdentifier::Custom,
                 ika_package_id,
                 ika_common_package_id,
@@ -146,7 +148,10 @@ impl ValidatorConfigBuilder {
                 ika_system_object_id,
                 ika_dwallet_coordinator_object_id,
                 notifier_client_key_pair: None,
+                notifier_remote_signer: None,
                 sui_ika_system_module_last_processed_event_id_override: None,
+                notifier_gas_coin_count: default_notifier_gas_coin_count(),
+                notifier_low_gas_balance_threshold: None,
             },
             db_path,
             network_address,
@@ -160,6 +165,7 @@ impl ValidatorConfigBuilder {
             end_of_epoch_broadcast_channel_capacity:
                 default_end_of_epoch_broadcast_channel_capacity(),
             metrics: None,
+            otlp_tracing: None,
             supported_protocol_versions: self.supported_protocol_versions,
             state_archive_write_config: StateArchiveConfig::default(),
             state_archive_read_config: vec![],
@@ -338,6 +344,7 @@ impl FullnodeConfigBuilder {
 
         NodeConfig {
             root_seed_key_pair: None,
+            next_root_seed_key_pair: None,
             protocol_key_pair: AuthorityKeyPairWithPath::new(validator_config.key_pair),
             account_key_pair: KeyPairWithPath::new(validator_config.account_key_pair),
             consensus_key_pair: KeyPairWithPath::new(SuiKeyPair::Ed25519(
@@ -354,6 +361,7 @@ impl FullnodeConfigBuilder {
                 .unwrap_or(validator_config.network_address),
             sui_connector_config: SuiConnectorConfig {
                 sui_rpc_url: sui_rpc_url.to_string(),
+                additional_sui_rpc_urls: vec![],
                 sui_chain_identifier: SuiChainIdentifier::Custom,
                 ika_package_id,
                 ika_common_package_id,
@@ -362,7 +370,10 @@ impl FullnodeConfigBuilder {
                 ika_system_object_id,
                 ika_dwallet_coordinator_object_id,
                 notifier_client_key_pair,
+                notifier_remote_signer: None,
                 sui_ika_system_module_last_processed_event_id_override: None,
+                notifier_gas_coin_count: default_notifier_gas_coin_count(),
+                notifier_low_gas_balance_threshold: None,
             },
             metrics_address: self
                 .metrics_address
@@ -376,6 +387,7 @@ impl FullnodeConfigBuilder {
             end_of_epoch_broadcast_channel_capacity:
                 default_end_of_epoch_broadcast_channel_capacity(),
             metrics: None,
+            otlp_tracing: None,
             supported_protocol_versions: self.supported_protocol_versions,
             state_archive_write_config: StateArchiveConfig::default(),
             state_archive_read_config: vec![],
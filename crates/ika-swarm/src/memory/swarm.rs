@@ -19,6 +19,8 @@ use ika_config::node::{
 };
 use ika_node::IkaNodeHandle;
 use ika_protocol_config::ProtocolVersion;
+#[cfg(feature = "local-sui")]
+use ika_sui_client::local_sui::{LOCAL_SUI_URL_SCHEME, LocalSuiNetwork};
 use ika_swarm_config::network_config::NetworkConfig;
 use ika_swarm_config::network_config_builder::{
     CommitteeConfig, ConfigBuilder, ProtocolVersionsConfig, SupportedProtocolVersionsCallback,
@@ -46,6 +48,8 @@ pub struct SwarmBuilder<R = OsRng> {
     fullnode_run_with_range: Option<RunWithRange>,
     max_submit_position: Option<usize>,
     submit_delay_step_override_millis: Option<u64>,
+    #[cfg(feature = "local-sui")]
+    local_sui: bool,
 }
 
 impl SwarmBuilder {
@@ -65,6 +69,8 @@ impl SwarmBuilder {
             fullnode_run_with_range: None,
             max_submit_position: None,
             submit_delay_step_override_millis: None,
+            #[cfg(feature = "local-sui")]
+            local_sui: false,
         }
     }
 }
@@ -86,6 +92,8 @@ impl<R> SwarmBuilder<R> {
             fullnode_run_with_range: self.fullnode_run_with_range,
             max_submit_position: self.max_submit_position,
             submit_delay_step_override_millis: self.submit_delay_step_override_millis,
+            #[cfg(feature = "local-sui")]
+            local_sui: self.local_sui,
         }
     }
 
@@ -186,6 +194,14 @@ impl<R> SwarmBuilder<R> {
         self.submit_delay_step_override_millis = Some(submit_delay_step_override_millis);
         self
     }
+
+    /// Run the Swarm against an embedded [`LocalSuiNetwork`] instead of a local Sui network,
+    /// so it needs neither a Sui fullnode nor a faucet.
+    #[cfg(feature = "local-sui")]
+    pub fn with_local_sui(mut self) -> Self {
+        self.local_sui = true;
+        self
+    }
 }
 
 impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
@@ -204,13 +220,23 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
         } else {
             SwarmDirectory::new_temporary()
         };
+        #[cfg(feature = "local-sui")]
+        let sui_fullnode_rpc_url = if self.local_sui {
+            format!(
+                "{LOCAL_SUI_URL_SCHEME}swarm-{:016x}",
+                nondeterministic!(rand::random::<u64>())
+            )
+        } else {
+            LOCAL_DEFAULT_SUI_FULLNODE_RPC_URL.to_string()
+        };
+        #[cfg(not(feature = "local-sui"))]
+        let sui_fullnode_rpc_url = LOCAL_DEFAULT_SUI_FULLNODE_RPC_URL.to_string();
         let network_config = if let Some(network_config) = self.network_config {
             network_config
         } else {
-            let sui_fullnode_rpc_url = LOCAL_DEFAULT_SUI_FULLNODE_RPC_URL.to_string();
             let sui_faucet_url = LOCAL_DEFAULT_SUI_FAUCET_URL.to_string();
             let mut config_builder =
-                ConfigBuilder::new(dir.as_ref(), sui_fullnode_rpc_url, sui_faucet_url);
+                ConfigBuilder::new(dir.as_ref(), sui_fullnode_rpc_url.clone(), sui_faucet_url);
 
             if let Some(epoch_duration_ms) = self.epoch_duration_ms {
                 config_builder = config_builder.with_epoch_duration(epoch_duration_ms);
@@ -282,10 +308,19 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
             );
         }
 
+        #[cfg(feature = "local-sui")]
+        let local_sui = if self.local_sui {
+            LocalSuiNetwork::connect(&sui_fullnode_rpc_url)
+        } else {
+            None
+        };
+
         Ok(Swarm {
             dir,
             network_config,
             nodes,
+            #[cfg(feature = "local-sui")]
+            local_sui,
         })
     }
 }
@@ -296,11 +331,17 @@ pub struct Swarm {
     dir: SwarmDirectory,
     pub network_config: NetworkConfig,
     nodes: HashMap<AuthorityName, Node>,
+    #[cfg(feature = "local-sui")]
+    local_sui: Option<LocalSuiNetwork>,
 }
 
 impl Drop for Swarm {
     fn drop(&mut self) {
         self.nodes_iter_mut().for_each(|node| node.stop());
+        #[cfg(feature = "local-sui")]
+        if let Some(local_sui) = &self.local_sui {
+            local_sui.shutdown();
+        }
     }
 }
 
@@ -326,6 +367,12 @@ impl Swarm {
         self.dir.as_ref()
     }
 
+    /// Return the embedded Sui network this Swarm runs against, if it was built with one.
+    #[cfg(feature = "local-sui")]
+    pub fn local_sui(&self) -> Option<&LocalSuiNetwork> {
+        self.local_sui.as_ref()
+    }
+
     /// Return a reference to this Swarm's `NetworkConfig`.
     pub fn config(&self) -> &NetworkConfig {
         &self.network_config
@@ -0,0 +1,240 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Generates the `ika-light-client` swarm test vectors: the committee handover from epoch 1 to
+//! epoch 2 as certified by a swarm running against an embedded Sui network.
+//!
+//! The network DKG and the reconfiguration alone take minutes, so this test is ignored by
+//! default. Run it in release mode with:
+//! `cargo test --release -p ika-swarm --features local-sui --test light_client_test_vectors \
+//! -- --ignored`
+//! The vectors are written to `crates/ika-light-client/test_vectors/swarm`, or to
+//! `IKA_LIGHT_CLIENT_TEST_VECTORS_DIR` when set.
+
+use ika_light_client::{
+    CheckpointKind, FastCryptoVerifier, LightCertifiedCheckpoint, LightClient, LightCommittee,
+    LightCommitteeMember,
+};
+use ika_node::IkaNodeHandle;
+use ika_swarm::memory::Swarm;
+use ika_types::committee::Committee;
+use ika_types::crypto::AuthorityStrongQuorumSignInfo;
+use ika_types::messages_system_checkpoints::SystemCheckpointMessageKind;
+use serde::Serialize;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const COMMITTEE_SIZE: usize = 4;
+const EPOCH_DURATION_MS: u64 = 60_000;
+const HANDOVER_TIMEOUT: Duration = Duration::from_secs(40 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The certified checkpoints of a committee handover, as stored by a validator.
+struct Handover {
+    committee_epoch_1: LightCommittee,
+    committee_epoch_2: LightCommittee,
+    /// The first dWallet checkpoint of epoch 1.
+    first_dwallet_checkpoint: LightCertifiedCheckpoint,
+    /// The last dWallet checkpoint of epoch 1, which commits to the epoch 2 committee.
+    last_dwallet_checkpoint: LightCertifiedCheckpoint,
+    /// The last system checkpoint of epoch 1, which carries `EndOfPublish`.
+    last_system_checkpoint: LightCertifiedCheckpoint,
+    /// The first dWallet checkpoint of epoch 2.
+    next_epoch_dwallet_checkpoint: LightCertifiedCheckpoint,
+}
+
+fn light_committee(committee: &Committee) -> LightCommittee {
+    let members = committee
+        .voting_rights
+        .iter()
+        .map(|(name, voting_power)| LightCommitteeMember {
+            protocol_public_key: name.0,
+            voting_power: *voting_power,
+        })
+        .collect();
+    LightCommittee::new(committee.epoch, members, committee.quorum_threshold)
+        .expect("on-chain committees are valid")
+}
+
+fn light_checkpoint<T: Serialize>(
+    kind: CheckpointKind,
+    message: &T,
+    auth_sig: &AuthorityStrongQuorumSignInfo,
+) -> LightCertifiedCheckpoint {
+    LightCertifiedCheckpoint {
+        kind,
+        message: bcs::to_bytes(message).unwrap(),
+        epoch: auth_sig.epoch,
+        signature: auth_sig.signature.as_ref().try_into().unwrap(),
+        signers: auth_sig.signers_map.iter().collect(),
+    }
+}
+
+/// Reads the handover from epoch 1 to epoch 2 out of `node`'s stores, once the node
+/// certified a dWallet checkpoint of epoch 2.
+fn read_handover(node: &IkaNodeHandle) -> Option<Handover> {
+    node.with(|node| {
+        let dwallet_checkpoint_store = node.dwallet_checkpoint_store();
+        let system_checkpoint_store = node.system_checkpoint_store();
+        let latest_dwallet_checkpoint = dwallet_checkpoint_store
+            .get_latest_certified_checkpoint()
+            .unwrap()?;
+        if latest_dwallet_checkpoint.epoch < 2 {
+            return None;
+        }
+
+        let dwallet_checkpoints = dwallet_checkpoint_store
+            .multi_get_dwallet_checkpoint_by_sequence_number(
+                &(1..=latest_dwallet_checkpoint.sequence_number).collect::<Vec<_>>(),
+            )
+            .unwrap()
+            .into_iter()
+            .collect::<Option<Vec<_>>>()?;
+        let first_dwallet_checkpoint = dwallet_checkpoints.first()?;
+        let last_dwallet_checkpoint = dwallet_checkpoints
+            .iter()
+            .rfind(|checkpoint| checkpoint.epoch == 1)?;
+        let next_epoch_dwallet_checkpoint = dwallet_checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.epoch == 2)?;
+        assert!(
+            last_dwallet_checkpoint.next_epoch_committee().is_some(),
+            "the last dWallet checkpoint of epoch 1 should commit to the next committee"
+        );
+
+        let latest_system_checkpoint = system_checkpoint_store
+            .get_latest_certified_system_checkpoint()
+            .unwrap()?;
+        let last_system_checkpoint = (1..=latest_system_checkpoint.sequence_number)
+            .rev()
+            .map(|sequence_number| {
+                system_checkpoint_store
+                    .get_system_checkpoint_by_sequence_number(sequence_number)
+                    .unwrap()
+            })
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .find(|checkpoint| {
+                checkpoint.epoch == 1
+                    && checkpoint
+                        .messages
+                        .contains(&SystemCheckpointMessageKind::EndOfPublish)
+            })?;
+
+        let committee_store = node.clone_committee_store();
+        let committee_epoch_1 = committee_store.get_committee(&1).unwrap()?;
+        let committee_epoch_2 = committee_store.get_committee(&2).unwrap()?;
+        Some(Handover {
+            committee_epoch_1: light_committee(&committee_epoch_1),
+            committee_epoch_2: light_committee(&committee_epoch_2),
+            first_dwallet_checkpoint: light_checkpoint(
+                CheckpointKind::DWallet,
+                first_dwallet_checkpoint.data(),
+                first_dwallet_checkpoint.auth_sig(),
+            ),
+            last_dwallet_checkpoint: light_checkpoint(
+                CheckpointKind::DWallet,
+                last_dwallet_checkpoint.data(),
+                last_dwallet_checkpoint.auth_sig(),
+            ),
+            last_system_checkpoint: light_checkpoint(
+                CheckpointKind::System,
+                last_system_checkpoint.data(),
+                last_system_checkpoint.auth_sig(),
+            ),
+            next_epoch_dwallet_checkpoint: light_checkpoint(
+                CheckpointKind::DWallet,
+                next_epoch_dwallet_checkpoint.data(),
+                next_epoch_dwallet_checkpoint.auth_sig(),
+            ),
+        })
+    })
+}
+
+fn output_dir() -> PathBuf {
+    std::env::var("IKA_LIGHT_CLIENT_TEST_VECTORS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../ika-light-client/test_vectors")
+                .join("swarm")
+        })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "takes minutes, run it to regenerate the light client swarm test vectors"]
+async fn generate_light_client_test_vectors() {
+    telemetry_subscribers::init_for_testing();
+    let mut swarm = Swarm::builder()
+        .committee_size(NonZeroUsize::new(COMMITTEE_SIZE).unwrap())
+        .with_fullnode_count(0)
+        .with_epoch_duration_ms(EPOCH_DURATION_MS)
+        .with_local_sui()
+        .build()
+        .await
+        .unwrap();
+    swarm.launch().await.unwrap();
+    let node = swarm.validator_node_handles().remove(0);
+
+    let handover = tokio::time::timeout(HANDOVER_TIMEOUT, async {
+        loop {
+            if let Some(handover) = read_handover(&node) {
+                return handover;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("the swarm did not reach epoch 2 in time");
+
+    // Only write vectors the light client follows across the handover.
+    let mut client = LightClient::new(handover.committee_epoch_1.clone(), FastCryptoVerifier);
+    client
+        .verify_dwallet_checkpoint(&handover.first_dwallet_checkpoint)
+        .unwrap();
+    assert!(
+        client
+            .verify_system_checkpoint(&handover.last_system_checkpoint)
+            .unwrap()
+            .end_of_publish
+    );
+    client
+        .verify_dwallet_checkpoint(&handover.last_dwallet_checkpoint)
+        .unwrap();
+    client.handover(handover.committee_epoch_2.clone()).unwrap();
+    client
+        .verify_dwallet_checkpoint(&handover.next_epoch_dwallet_checkpoint)
+        .unwrap();
+
+    let dir = output_dir();
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, bytes) in [
+        (
+            "committee_epoch_1.bin",
+            handover.committee_epoch_1.to_bytes(),
+        ),
+        (
+            "committee_epoch_2.bin",
+            handover.committee_epoch_2.to_bytes(),
+        ),
+        (
+            "dwallet_checkpoint_epoch_1_first.bin",
+            handover.first_dwallet_checkpoint.to_bytes(),
+        ),
+        (
+            "dwallet_checkpoint_epoch_1_last.bin",
+            handover.last_dwallet_checkpoint.to_bytes(),
+        ),
+        (
+            "system_checkpoint_epoch_1_last.bin",
+            handover.last_system_checkpoint.to_bytes(),
+        ),
+        (
+            "dwallet_checkpoint_epoch_2_first.bin",
+            handover.next_epoch_dwallet_checkpoint.to_bytes(),
+        ),
+    ] {
+        std::fs::write(dir.join(name), bytes).unwrap();
+    }
+}
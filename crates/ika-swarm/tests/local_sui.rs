@@ -0,0 +1,194 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! Runs a swarm against an embedded Sui network, so the dWallet flow is tested end to end
+//! without a Sui fullnode. The network DKG alone takes minutes, so run it in release mode:
+//! `cargo test --release -p ika-swarm --features local-sui --test local_sui`.
+
+use dwallet_mpc_centralized_party::{
+    advance_centralized_sign_party, create_dkg_output, encrypt_secret_key_share_and_prove,
+    generate_secp256k1_cg_keypair_from_seed_internal,
+    network_dkg_public_output_to_protocol_pp_inner, public_key_from_dwallet_output_inner,
+    verify_secp_signature_inner,
+};
+use ika_sui_client::local_sui::LocalSuiNetwork;
+use ika_swarm::memory::Swarm;
+use ika_types::message::DWalletCheckpointMessageKind;
+use ika_types::messages_dwallet_mpc::{
+    DWalletDKGFirstRoundRequestEvent, DWalletDKGSecondRoundRequestEvent, PresignRequestEvent,
+    SessionIdentifier, SessionType, SignRequestEvent,
+};
+use std::num::NonZeroUsize;
+use std::time::Duration;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+const COMMITTEE_SIZE: usize = 4;
+const NETWORK_DKG_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+const SESSION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const SECP256K1: u32 = 0;
+const ECDSA: u32 = 0;
+const KECCAK256: u32 = 0;
+
+/// Waits for the network DKG of the key the swarm requested at genesis, returning its output.
+async fn wait_for_network_dkg(network: &LocalSuiNetwork) -> (ObjectID, Vec<u8>) {
+    let [key_id] = network.network_encryption_key_ids()[..] else {
+        panic!("the swarm should request a single network encryption key");
+    };
+    let output = tokio::time::timeout(NETWORK_DKG_TIMEOUT, async {
+        loop {
+            if let Some(output) = network.network_dkg_public_output(key_id) {
+                return output;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("the network DKG did not complete in time");
+    (key_id, output)
+}
+
+async fn wait_for_user_session_output(
+    network: &LocalSuiNetwork,
+    session_sequence_number: u64,
+) -> DWalletCheckpointMessageKind {
+    network
+        .wait_for_session_output(SessionType::User, session_sequence_number, SESSION_TIMEOUT)
+        .await
+        .expect("the session did not complete in time")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dwallet_dkg_presign_and_sign() {
+    let mut swarm = Swarm::builder()
+        .committee_size(NonZeroUsize::new(COMMITTEE_SIZE).unwrap())
+        .with_fullnode_count(0)
+        .with_local_sui()
+        .build()
+        .await
+        .unwrap();
+    swarm.launch().await.unwrap();
+    let network = swarm.local_sui().unwrap().clone();
+
+    let (key_id, network_dkg_output) = wait_for_network_dkg(&network).await;
+    let protocol_pp = network_dkg_public_output_to_protocol_pp_inner(network_dkg_output).unwrap();
+
+    let dwallet_id = ObjectID::random();
+    let session_sequence_number = network.request_session(
+        SessionType::User,
+        DWalletDKGFirstRoundRequestEvent {
+            dwallet_id,
+            dwallet_cap_id: ObjectID::random(),
+            dwallet_network_encryption_key_id: key_id,
+            curve: SECP256K1,
+        },
+    );
+    let DWalletCheckpointMessageKind::RespondDWalletDKGFirstRoundOutput(first_round) =
+        wait_for_user_session_output(&network, session_sequence_number).await
+    else {
+        panic!("expected a dWallet DKG first round output");
+    };
+    assert!(!first_round.rejected);
+
+    // The user's DKG step commits to the identifier of the second round session.
+    let session_identifier_preimage = ObjectID::random().into_bytes();
+    let session_identifier = SessionIdentifier::new(SessionType::User, session_identifier_preimage);
+    let user_dkg = create_dkg_output(
+        protocol_pp.clone(),
+        first_round.output.clone(),
+        session_identifier.to_vec(),
+    )
+    .unwrap();
+    let (encryption_key, _) = generate_secp256k1_cg_keypair_from_seed_internal([1; 32]).unwrap();
+    let encrypted_secret_share = encrypt_secret_key_share_and_prove(
+        user_dkg.centralized_secret_output.clone(),
+        encryption_key.clone(),
+        protocol_pp.clone(),
+    )
+    .unwrap();
+    let session_sequence_number = network.request_session_with_identifier(
+        SessionType::User,
+        session_identifier_preimage,
+        DWalletDKGSecondRoundRequestEvent {
+            encrypted_user_secret_key_share_id: ObjectID::random(),
+            dwallet_id,
+            first_round_output: first_round.output,
+            centralized_public_key_share_and_proof: user_dkg.public_key_share_and_proof,
+            dwallet_cap_id: ObjectID::random(),
+            encrypted_centralized_secret_share_and_proof: encrypted_secret_share,
+            encryption_key,
+            encryption_key_id: ObjectID::random(),
+            encryption_key_address: SuiAddress::ZERO,
+            user_public_output: user_dkg.public_output,
+            signer_public_key: vec![],
+            dwallet_network_encryption_key_id: key_id,
+            curve: SECP256K1,
+        },
+    );
+    let DWalletCheckpointMessageKind::RespondDWalletDKGSecondRoundOutput(dkg) =
+        wait_for_user_session_output(&network, session_sequence_number).await
+    else {
+        panic!("expected a dWallet DKG second round output");
+    };
+    assert!(!dkg.rejected);
+    let dwallet_output = dkg.output;
+
+    let presign_id = ObjectID::random();
+    let session_sequence_number = network.request_session(
+        SessionType::User,
+        PresignRequestEvent {
+            dwallet_id: Some(dwallet_id),
+            presign_id,
+            dwallet_public_output: Some(dwallet_output.clone()),
+            dwallet_network_encryption_key_id: key_id,
+            curve: SECP256K1,
+            signature_algorithm: ECDSA,
+        },
+    );
+    let DWalletCheckpointMessageKind::RespondDWalletPresign(presign) =
+        wait_for_user_session_output(&network, session_sequence_number).await
+    else {
+        panic!("expected a presign output");
+    };
+    assert!(!presign.rejected);
+
+    let message = b"signed by an offline swarm".to_vec();
+    let message_centralized_signature = advance_centralized_sign_party(
+        protocol_pp.clone(),
+        dwallet_output.clone(),
+        user_dkg.centralized_secret_output,
+        presign.presign.clone(),
+        message.clone(),
+        KECCAK256,
+    )
+    .unwrap();
+    let session_sequence_number = network.request_session(
+        SessionType::User,
+        SignRequestEvent {
+            sign_id: ObjectID::random(),
+            dwallet_id,
+            dwallet_decentralized_public_output: dwallet_output.clone(),
+            curve: SECP256K1,
+            signature_algorithm: ECDSA,
+            hash_scheme: KECCAK256,
+            message: message.clone(),
+            dwallet_network_encryption_key_id: key_id,
+            presign_id,
+            presign: presign.presign,
+            message_centralized_signature,
+            is_future_sign: false,
+        },
+    );
+    let DWalletCheckpointMessageKind::RespondDWalletSign(sign) =
+        wait_for_user_session_output(&network, session_sequence_number).await
+    else {
+        panic!("expected a sign output");
+    };
+    assert!(!sign.rejected);
+
+    let public_key = public_key_from_dwallet_output_inner(dwallet_output).unwrap();
+    assert!(
+        verify_secp_signature_inner(public_key, sign.signature, message, protocol_pp, KECCAK256)
+            .unwrap()
+    );
+}
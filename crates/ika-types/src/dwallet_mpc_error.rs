@@ -101,6 +101,9 @@ pub enum DwalletMPCError {
     #[error("failed to write seed to file: {0}")]
     FailedToWriteSeed(String),
 
+    #[error("invalid root seed shares: {0}")]
+    InvalidRootSeedShares(String),
+
     #[error("missing MPC private session input")]
     MissingMPCPrivateInput,
 
@@ -228,6 +228,18 @@ pub enum IkaError {
     #[error("Sui Client sui transaction {0} failure due to generic error: {1}")]
     SuiClientTxFailureGeneric(TransactionDigest, String),
 
+    #[error(
+        "Sui transaction aborted in `{module}::{}` with code {abort_code} (deterministic: {deterministic}): {error}",
+        function.as_deref().unwrap_or("_")
+    )]
+    SuiTransactionMoveAbort {
+        module: String,
+        function: Option<String>,
+        abort_code: u64,
+        deterministic: bool,
+        error: String,
+    },
+
     // Sui Connector
     #[error("Sui Connector failure to serialize: {0}")]
     SuiConnectorSerializationError(String),
@@ -247,13 +259,22 @@ pub enum IkaError {
 
     #[error("dry run error failed: {0}")]
     DryRunFailed(String),
+
+    #[error("Invalid checkpoint message inclusion proof: {0}")]
+    InvalidInclusionProof(String),
+
+    #[error("Invalid system parameter change: {0}")]
+    InvalidSystemParameterChange(String),
 }
 
 pub type IkaResult<T = ()> = Result<T, IkaError>;
 
 impl From<DwalletMPCError> for IkaError {
     fn from(error: DwalletMPCError) -> Self {
-        IkaError::DwalletMPCError(error.to_string())
+        match error {
+            DwalletMPCError::IkaError(error) => error,
+            error => IkaError::DwalletMPCError(error.to_string()),
+        }
     }
 }
 
@@ -353,6 +374,18 @@ impl IkaError {
         matches!(self, IkaError::ValidatorOverloadedRetryAfter { .. })
     }
 
+    /// Whether this is a Move abort that will recur no matter how many times
+    /// the same transaction is retried against the same on-chain state.
+    pub fn is_deterministic_move_abort(&self) -> bool {
+        matches!(
+            self,
+            IkaError::SuiTransactionMoveAbort {
+                deterministic: true,
+                ..
+            }
+        )
+    }
+
     pub fn retry_after_secs(&self) -> u64 {
         match self {
             IkaError::ValidatorOverloadedRetryAfter { retry_after_secs } => *retry_after_secs,
@@ -15,6 +15,7 @@ pub mod digests;
 pub mod ika_coin;
 pub mod ika_serde;
 pub mod intent;
+pub mod merkle;
 pub mod message;
 pub mod message_envelope;
 pub mod messages_consensus;
@@ -0,0 +1,210 @@
+// Copyright (c) dWallet Labs, Ltd.
+// SPDX-License-Identifier: BSD-3-Clause-Clear
+
+//! A minimal binary Merkle tree used to commit to the ordered list of messages
+//! in a checkpoint, so that a single message can be proven to be part of a
+//! certified checkpoint without shipping the rest of it.
+//!
+//! Leaves and inner nodes are domain separated (`0x00` / `0x01` prefixes).
+//! When a level has an odd number of nodes, the last node is promoted to the
+//! next level unchanged.
+
+use crate::crypto::DefaultHash;
+use crate::digests::Digest;
+use fastcrypto::hash::HashFunction;
+use serde::{Deserialize, Serialize};
+
+const LEAF_PREFIX: u8 = 0x00;
+const INNER_NODE_PREFIX: u8 = 0x01;
+
+/// Hashes the serialized bytes of a single leaf.
+pub fn leaf_hash(leaf_bytes: &[u8]) -> Digest {
+    let mut hasher = DefaultHash::default();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf_bytes);
+    Digest::new(hasher.finalize().digest)
+}
+
+fn inner_node_hash(left: &Digest, right: &Digest) -> Digest {
+    let mut hasher = DefaultHash::default();
+    hasher.update([INNER_NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    Digest::new(hasher.finalize().digest)
+}
+
+/// The root of a tree with no leaves.
+pub fn empty_root() -> Digest {
+    Digest::new(DefaultHash::digest(b"").digest)
+}
+
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// `levels[0]` holds the leaf hashes, the last level holds the root.
+    levels: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    pub fn build_from_leaf_bytes<I, B>(leaves: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let leaf_hashes = leaves
+            .into_iter()
+            .map(|leaf| leaf_hash(leaf.as_ref()))
+            .collect();
+        Self::build_from_leaf_hashes(leaf_hashes)
+    }
+
+    pub fn build_from_leaf_hashes(leaf_hashes: Vec<Digest>) -> Self {
+        let mut levels = vec![leaf_hashes];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next_level = levels
+                .last()
+                .expect("levels is never empty")
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => inner_node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two elements"),
+                })
+                .collect();
+            levels.push(next_level);
+        }
+        Self { levels }
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn root(&self) -> Digest {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_else(empty_root)
+    }
+
+    /// Returns the authentication path for the leaf at `leaf_index`,
+    /// or `None` if the index is out of range.
+    pub fn proof(&self, leaf_index: usize) -> Option<MerkleProof> {
+        if leaf_index >= self.leaf_count() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut index = leaf_index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_index = index ^ 1;
+            if let Some(sibling) = level.get(sibling_index) {
+                siblings.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(MerkleProof {
+            leaf_index: leaf_index as u64,
+            leaf_count: self.leaf_count() as u64,
+            siblings,
+        })
+    }
+}
+
+/// An authentication path from a single leaf to the root of a [`MerkleTree`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<Digest>,
+}
+
+impl MerkleProof {
+    /// Recomputes the root from the hash of the proven leaf.
+    /// Returns `None` if the proof is malformed for the claimed tree shape.
+    pub fn compute_root(&self, leaf: Digest) -> Option<Digest> {
+        if self.leaf_index >= self.leaf_count {
+            return None;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut node = leaf;
+        let mut index = self.leaf_index;
+        let mut level_size = self.leaf_count;
+        while level_size > 1 {
+            let is_unpaired_last_node = index % 2 == 0 && index == level_size - 1;
+            if !is_unpaired_last_node {
+                let sibling = siblings.next()?;
+                node = if index % 2 == 0 {
+                    inner_node_hash(&node, sibling)
+                } else {
+                    inner_node_hash(sibling, &node)
+                };
+            }
+            index /= 2;
+            level_size = level_size.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return None;
+        }
+        Some(node)
+    }
+
+    pub fn verify(&self, leaf_bytes: &[u8], root: &Digest) -> bool {
+        self.compute_root(leaf_hash(leaf_bytes))
+            .is_some_and(|computed_root| &computed_root == root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| format!("leaf-{i}").into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf_and_tree_size() {
+        for count in 1..=17 {
+            let leaves = leaves(count);
+            let tree = MerkleTree::build_from_leaf_bytes(&leaves);
+            let root = tree.root();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(leaf, &root), "count={count} index={index}");
+            }
+            assert!(tree.proof(count).is_none());
+        }
+    }
+
+    #[test]
+    fn proof_rejects_wrong_leaf_index_and_root() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::build_from_leaf_bytes(&leaves);
+        let root = tree.root();
+        let proof = tree.proof(2).unwrap();
+
+        assert!(!proof.verify(&leaves[3], &root));
+        assert!(!proof.verify(&leaves[2], &Digest::ZERO));
+
+        let mut moved = proof.clone();
+        moved.leaf_index = 3;
+        assert!(!moved.verify(&leaves[2], &root));
+
+        let mut truncated = proof;
+        truncated.siblings.pop();
+        assert!(!truncated.verify(&leaves[2], &root));
+    }
+
+    #[test]
+    fn single_leaf_and_empty_roots() {
+        let tree = MerkleTree::build_from_leaf_bytes([b"only".as_slice()]);
+        assert_eq!(tree.root(), leaf_hash(b"only"));
+        assert!(tree.proof(0).unwrap().siblings.is_empty());
+
+        let empty = MerkleTree::build_from_leaf_bytes(Vec::<Vec<u8>>::new());
+        assert_eq!(empty.root(), empty_root());
+        assert!(empty.proof(0).is_none());
+    }
+}
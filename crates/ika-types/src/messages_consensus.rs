@@ -8,7 +8,7 @@ use crate::messages_dwallet_checkpoint::{
 };
 use crate::messages_dwallet_mpc::{DWalletMPCMessage, DWalletMPCOutput, SessionIdentifier};
 use crate::messages_system_checkpoints::{
-    SystemCheckpointSequenceNumber, SystemCheckpointSignatureMessage,
+    SystemCheckpointSequenceNumber, SystemCheckpointSignatureMessage, SystemParameterChange,
 };
 use crate::supported_protocol_versions::{
     SupportedProtocolVersions, SupportedProtocolVersionsWithHashes,
@@ -56,6 +56,7 @@ pub enum ConsensusTransactionKey {
         Vec<AuthorityName>, // malicious authorities
     ),
     SystemCheckpointSignature(AuthorityName, SystemCheckpointSequenceNumber),
+    SystemParameterVote(AuthorityName, SystemParameterChange),
 }
 
 impl Debug for ConsensusTransactionKey {
@@ -103,6 +104,13 @@ impl Debug for ConsensusTransactionKey {
             ConsensusTransactionKey::EndOfPublish(authority) => {
                 write!(f, "EndOfPublish({:?})", authority.concise())
             }
+            ConsensusTransactionKey::SystemParameterVote(authority, change) => {
+                write!(
+                    f,
+                    "SystemParameterVote({:?}, {change})",
+                    authority.concise()
+                )
+            }
         }
     }
 }
@@ -168,6 +176,25 @@ impl Debug for AuthorityCapabilitiesV1 {
     }
 }
 
+/// A validator's vote for a system parameter change.
+/// The change is applied once validators holding a quorum of stake vote for the exact same change
+/// within an epoch.
+#[derive(Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
+pub struct SystemParameterVote {
+    /// Originating authority — must match transaction source authority from consensus.
+    pub authority: AuthorityName,
+    pub change: SystemParameterChange,
+}
+
+impl Debug for SystemParameterVote {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemParameterVote")
+            .field("authority", &self.authority.concise())
+            .field("change", &self.change)
+            .finish()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ConsensusTransactionKind {
     DWalletCheckpointSignature(Box<DWalletCheckpointSignatureMessage>),
@@ -176,6 +203,7 @@ pub enum ConsensusTransactionKind {
     EndOfPublish(AuthorityName),
     DWalletMPCMessage(DWalletMPCMessage),
     DWalletMPCOutput(DWalletMPCOutput),
+    SystemParameterVote(SystemParameterVote),
 }
 
 impl ConsensusTransaction {
@@ -272,6 +300,20 @@ impl ConsensusTransaction {
         }
     }
 
+    pub fn new_system_parameter_vote(
+        authority: AuthorityName,
+        change: SystemParameterChange,
+    ) -> Self {
+        let vote = SystemParameterVote { authority, change };
+        let mut hasher = DefaultHasher::new();
+        vote.hash(&mut hasher);
+        let tracking_id = hasher.finish().to_le_bytes();
+        Self {
+            tracking_id,
+            kind: ConsensusTransactionKind::SystemParameterVote(vote),
+        }
+    }
+
     pub fn get_tracking_id(&self) -> u64 {
         (&self.tracking_id[..])
             .read_u64::<BigEndian>()
@@ -313,6 +355,9 @@ impl ConsensusTransaction {
             ConsensusTransactionKind::EndOfPublish(origin_authority) => {
                 ConsensusTransactionKey::EndOfPublish(*origin_authority)
             }
+            ConsensusTransactionKind::SystemParameterVote(vote) => {
+                ConsensusTransactionKey::SystemParameterVote(vote.authority, vote.change.clone())
+            }
         }
     }
 }
@@ -1,16 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: BSD-3-Clause-Clear
 
-use crate::committee::EpochId;
+use crate::committee::{EpochId, StakeUnit};
 use crate::crypto::{
-    AggregateAuthoritySignature, AuthoritySignInfo, AuthoritySignInfoTrait,
+    AggregateAuthoritySignature, AuthorityName, AuthoritySignInfo, AuthoritySignInfoTrait,
     AuthorityStrongQuorumSignInfo, default_hash,
 };
+use crate::digests::Digest;
 use crate::error::IkaResult;
 use crate::intent::{Intent, IntentScope};
+use crate::merkle::{MerkleProof, MerkleTree};
 use crate::message_envelope::{Envelope, Message, TrustedEnvelope, VerifiedEnvelope};
 use crate::{committee::Committee, error::IkaError};
-use serde::{Deserialize, Serialize};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter};
 
 pub use crate::digests::DWalletCheckpointContentsDigest;
@@ -21,7 +25,22 @@ pub type DWalletCheckpointSequenceNumber = u64;
 
 // The constituent parts of checkpoints, signed and certified
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Starts the BCS encoding of a V2 dwallet checkpoint, in place of the epoch that starts a V1 one.
+/// It is never a valid epoch, so V1 checkpoints keep the exact encoding (and digest) they had
+/// before V2 existed, and both versions can be told apart when decoded.
+pub const DWALLET_CHECKPOINT_MESSAGE_V2_MARKER: u64 = u64::MAX;
+
+/// The encoding version of a [`DWalletCheckpointMessage`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DWalletCheckpointMessageVersion {
+    /// `epoch`, `sequence_number` and `messages`.
+    V1,
+    /// [`DWALLET_CHECKPOINT_MESSAGE_V2_MARKER`], the V1 fields and `checkpoint_commitments`.
+    /// Only built when the `dwallet_checkpoint_messages_merkle_root` protocol feature is enabled.
+    V2,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DWalletCheckpointMessage {
     pub epoch: EpochId,
     pub sequence_number: DWalletCheckpointSequenceNumber,
@@ -29,6 +48,120 @@ pub struct DWalletCheckpointMessage {
     /// DWallet checkpoint timestamps are monotonic, but not strongly monotonic - subsequent
     /// dwallet checkpoints can have same timestamp if they originate from the same underlining consensus commit
     pub messages: Vec<DWalletCheckpointMessageKind>,
+    /// Authenticated commitments to the checkpoint content.
+    /// Empty for V1 checkpoints, and never empty for V2 ones.
+    /// Note: it is encoded after `messages`, the Move code parses the checkpoint up to `messages`
+    /// and ignores any trailing bytes.
+    pub checkpoint_commitments: Vec<DWalletCheckpointCommitment>,
+}
+
+impl Serialize for DWalletCheckpointMessage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.version() {
+            DWalletCheckpointMessageVersion::V1 => {
+                let mut tuple = serializer.serialize_tuple(3)?;
+                tuple.serialize_element(&self.epoch)?;
+                tuple.serialize_element(&self.sequence_number)?;
+                tuple.serialize_element(&self.messages)?;
+                tuple.end()
+            }
+            DWalletCheckpointMessageVersion::V2 => {
+                let mut tuple = serializer.serialize_tuple(5)?;
+                tuple.serialize_element(&DWALLET_CHECKPOINT_MESSAGE_V2_MARKER)?;
+                tuple.serialize_element(&self.epoch)?;
+                tuple.serialize_element(&self.sequence_number)?;
+                tuple.serialize_element(&self.messages)?;
+                tuple.serialize_element(&self.checkpoint_commitments)?;
+                tuple.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DWalletCheckpointMessage {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DWalletCheckpointMessageVisitor;
+
+        impl<'de> Visitor<'de> for DWalletCheckpointMessageVisitor {
+            type Value = DWalletCheckpointMessage;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("a V1 or V2 dwallet checkpoint message")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let missing =
+                    |index: usize| -> A::Error { de::Error::invalid_length(index, &self) };
+                let first: u64 = seq.next_element()?.ok_or_else(|| missing(0))?;
+                let is_v2 = first == DWALLET_CHECKPOINT_MESSAGE_V2_MARKER;
+                let (epoch, fields_start) = if is_v2 {
+                    (seq.next_element()?.ok_or_else(|| missing(1))?, 2)
+                } else {
+                    (first, 1)
+                };
+                let sequence_number = seq.next_element()?.ok_or_else(|| missing(fields_start))?;
+                let messages = seq
+                    .next_element()?
+                    .ok_or_else(|| missing(fields_start + 1))?;
+                let checkpoint_commitments: Vec<DWalletCheckpointCommitment> = if is_v2 {
+                    seq.next_element()?.ok_or_else(|| missing(4))?
+                } else {
+                    Vec::new()
+                };
+                if is_v2 && checkpoint_commitments.is_empty() {
+                    return Err(de::Error::custom(
+                        "a V2 dwallet checkpoint message must have commitments",
+                    ));
+                }
+                Ok(DWalletCheckpointMessage {
+                    epoch,
+                    sequence_number,
+                    messages,
+                    checkpoint_commitments,
+                })
+            }
+        }
+
+        // A V1 message has 3 elements, and a V2 message 5.
+        deserializer.deserialize_tuple(5, DWalletCheckpointMessageVisitor)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DWalletCheckpointCommitment {
+    /// Merkle root over the BCS bytes of `messages`, in order.
+    MessagesMerkleRoot(Digest),
+    /// The committee of the next epoch, committed to by the last dwallet checkpoint of an epoch
+    /// (the one carrying `EndOfPublish`), so light clients can follow the committee handover.
+    NextEpochCommittee(NextEpochCommittee),
+}
+
+/// The part of the next epoch [`Committee`] needed to verify its certificates.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NextEpochCommittee {
+    pub epoch: EpochId,
+    /// In committee order, as certificates refer to signers by their index.
+    pub voting_rights: Vec<(AuthorityName, StakeUnit)>,
+    pub quorum_threshold: StakeUnit,
+}
+
+impl From<&Committee> for NextEpochCommittee {
+    fn from(committee: &Committee) -> Self {
+        Self {
+            epoch: committee.epoch,
+            voting_rights: committee.voting_rights.clone(),
+            quorum_threshold: committee.quorum_threshold,
+        }
+    }
 }
 
 impl Message for DWalletCheckpointMessage {
@@ -50,9 +183,134 @@ impl DWalletCheckpointMessage {
             epoch,
             sequence_number,
             messages,
+            checkpoint_commitments: Vec::new(),
         }
     }
 
+    pub fn version(&self) -> DWalletCheckpointMessageVersion {
+        if self.checkpoint_commitments.is_empty() {
+            DWalletCheckpointMessageVersion::V1
+        } else {
+            DWalletCheckpointMessageVersion::V2
+        }
+    }
+
+    /// Commits to the Merkle root of `messages`, making this a V2 checkpoint, so that individual
+    /// messages can later be proven with [`DWalletCheckpointMessageInclusionProof`].
+    pub fn with_messages_merkle_root(mut self) -> IkaResult<Self> {
+        let root = Self::compute_messages_merkle_root(&self.messages)?;
+        self.checkpoint_commitments.retain(|commitment| {
+            !matches!(
+                commitment,
+                DWalletCheckpointCommitment::MessagesMerkleRoot(_)
+            )
+        });
+        self.checkpoint_commitments
+            .push(DWalletCheckpointCommitment::MessagesMerkleRoot(root));
+        Ok(self)
+    }
+
+    pub fn compute_messages_merkle_root(
+        messages: &[DWalletCheckpointMessageKind],
+    ) -> IkaResult<Digest> {
+        Ok(Self::build_messages_merkle_tree(messages)?.root())
+    }
+
+    fn build_messages_merkle_tree(
+        messages: &[DWalletCheckpointMessageKind],
+    ) -> IkaResult<MerkleTree> {
+        let leaves = messages
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| IkaError::BCSError(e.to_string()))?;
+        Ok(MerkleTree::build_from_leaf_bytes(leaves))
+    }
+
+    /// Commits to the committee of the next epoch. Only valid on a V2 checkpoint, i.e. after
+    /// [`Self::with_messages_merkle_root`], and meant for the last checkpoint of the epoch.
+    pub fn with_next_epoch_committee(
+        mut self,
+        next_epoch_committee: &Committee,
+    ) -> IkaResult<Self> {
+        fp_ensure!(
+            self.version() == DWalletCheckpointMessageVersion::V2,
+            IkaError::Unknown(format!(
+                "dwallet checkpoint {} must be a V2 checkpoint to commit to the next epoch committee",
+                self.sequence_number
+            ))
+        );
+        self.checkpoint_commitments.retain(|commitment| {
+            !matches!(
+                commitment,
+                DWalletCheckpointCommitment::NextEpochCommittee(_)
+            )
+        });
+        self.checkpoint_commitments
+            .push(DWalletCheckpointCommitment::NextEpochCommittee(
+                next_epoch_committee.into(),
+            ));
+        Ok(self)
+    }
+
+    /// The committed Merkle root of `messages`, if this checkpoint carries one.
+    pub fn messages_merkle_root(&self) -> Option<Digest> {
+        self.checkpoint_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                DWalletCheckpointCommitment::MessagesMerkleRoot(root) => Some(*root),
+                _ => None,
+            })
+    }
+
+    /// The committed next epoch committee, if this is the last checkpoint of a V2 epoch.
+    pub fn next_epoch_committee(&self) -> Option<&NextEpochCommittee> {
+        self.checkpoint_commitments
+            .iter()
+            .find_map(|commitment| match commitment {
+                DWalletCheckpointCommitment::NextEpochCommittee(committee) => Some(committee),
+                _ => None,
+            })
+    }
+
+    /// Builds an inclusion proof for the message at `message_index`.
+    /// Fails if the checkpoint does not commit to a messages Merkle root.
+    pub fn message_inclusion_proof(
+        &self,
+        message_index: usize,
+    ) -> IkaResult<DWalletCheckpointMessageInclusionProof> {
+        let Some(root) = self.messages_merkle_root() else {
+            return Err(IkaError::InvalidInclusionProof(format!(
+                "dwallet checkpoint {} does not commit to a messages merkle root",
+                self.sequence_number
+            )));
+        };
+        let message = self.messages.get(message_index).cloned().ok_or_else(|| {
+            IkaError::InvalidInclusionProof(format!(
+                "message index {message_index} out of range for dwallet checkpoint {} with {} messages",
+                self.sequence_number,
+                self.messages.len()
+            ))
+        })?;
+        let tree = Self::build_messages_merkle_tree(&self.messages)?;
+        fp_ensure!(
+            tree.root() == root,
+            IkaError::InvalidInclusionProof(format!(
+                "committed messages merkle root does not match the messages of dwallet checkpoint {}",
+                self.sequence_number
+            ))
+        );
+        let proof = tree
+            .proof(message_index)
+            .expect("message index was checked to be in range");
+        Ok(DWalletCheckpointMessageInclusionProof {
+            epoch: self.epoch,
+            sequence_number: self.sequence_number,
+            message,
+            proof,
+        })
+    }
+
     pub fn verify_epoch(&self, epoch: EpochId) -> IkaResult {
         fp_ensure!(
             self.epoch == epoch,
@@ -157,6 +415,67 @@ impl VerifiedDWalletCheckpointMessage {
     }
 }
 
+/// Proves that a single [`DWalletCheckpointMessageKind`] (e.g. a `RespondDWalletSign`)
+/// was part of a dwallet checkpoint certified by the Ika committee.
+///
+/// A verifier either checks the proof against a certified checkpoint and the committee
+/// (see [`Self::verify`]), or, having verified a checkpoint once, keeps only its
+/// `(epoch, sequence_number, messages_merkle_root)` and uses [`Self::verify_against_root`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DWalletCheckpointMessageInclusionProof {
+    pub epoch: EpochId,
+    pub sequence_number: DWalletCheckpointSequenceNumber,
+    pub message: DWalletCheckpointMessageKind,
+    pub proof: MerkleProof,
+}
+
+impl DWalletCheckpointMessageInclusionProof {
+    pub fn verify_against_root(&self, messages_merkle_root: &Digest) -> IkaResult {
+        let leaf_bytes =
+            bcs::to_bytes(&self.message).map_err(|e| IkaError::BCSError(e.to_string()))?;
+        fp_ensure!(
+            self.proof.verify(&leaf_bytes, messages_merkle_root),
+            IkaError::InvalidInclusionProof(format!(
+                "{} message is not included in dwallet checkpoint {}",
+                self.message.name(),
+                self.sequence_number
+            ))
+        );
+        Ok(())
+    }
+
+    /// Verifies the proof against a checkpoint whose committee signature
+    /// was already verified by the caller.
+    pub fn verify_against_checkpoint(&self, checkpoint: &DWalletCheckpointMessage) -> IkaResult {
+        checkpoint.verify_epoch(self.epoch)?;
+        fp_ensure!(
+            checkpoint.sequence_number == self.sequence_number,
+            IkaError::InvalidInclusionProof(format!(
+                "proof is for dwallet checkpoint {}, got dwallet checkpoint {}",
+                self.sequence_number, checkpoint.sequence_number
+            ))
+        );
+        let root = checkpoint.messages_merkle_root().ok_or_else(|| {
+            IkaError::InvalidInclusionProof(format!(
+                "dwallet checkpoint {} does not commit to a messages merkle root",
+                checkpoint.sequence_number
+            ))
+        })?;
+        self.verify_against_root(&root)
+    }
+
+    /// Verifies the BLS aggregate signature of `checkpoint` against `committee`,
+    /// and then that the proven message is included in it.
+    pub fn verify(
+        &self,
+        checkpoint: &CertifiedDWalletCheckpointMessage,
+        committee: &Committee,
+    ) -> IkaResult {
+        checkpoint.verify_authority_signatures(committee)?;
+        self.verify_against_checkpoint(checkpoint.data())
+    }
+}
+
 /// This is a message validators publish to consensus to sign dwallet checkpoint.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DWalletCheckpointSignatureMessage {
@@ -169,3 +488,179 @@ impl DWalletCheckpointSignatureMessage {
             .verify_authority_signatures(committee)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::leaf_hash;
+    use crate::message::SignOutput;
+
+    /// The layout of dwallet checkpoints before V2 existed.
+    #[derive(Serialize, Deserialize)]
+    struct DWalletCheckpointMessageV1Layout {
+        epoch: EpochId,
+        sequence_number: DWalletCheckpointSequenceNumber,
+        messages: Vec<DWalletCheckpointMessageKind>,
+    }
+
+    fn sign_message(session_sequence_number: u64) -> DWalletCheckpointMessageKind {
+        DWalletCheckpointMessageKind::RespondDWalletSign(SignOutput {
+            dwallet_id: vec![1; 32],
+            sign_id: vec![2; 32],
+            signature: vec![3; 64],
+            is_future_sign: false,
+            rejected: false,
+            session_sequence_number,
+        })
+    }
+
+    fn v2_checkpoint(epoch: EpochId) -> DWalletCheckpointMessage {
+        DWalletCheckpointMessage::new(epoch, 7, (0..5).map(sign_message).collect())
+            .with_messages_merkle_root()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_v1_encoding_is_unchanged() {
+        let checkpoint = DWalletCheckpointMessage::new(3, 7, vec![sign_message(1)]);
+        assert_eq!(checkpoint.version(), DWalletCheckpointMessageVersion::V1);
+        let bytes = bcs::to_bytes(&checkpoint).unwrap();
+        assert_eq!(
+            bytes,
+            bcs::to_bytes(&DWalletCheckpointMessageV1Layout {
+                epoch: 3,
+                sequence_number: 7,
+                messages: vec![sign_message(1)],
+            })
+            .unwrap()
+        );
+        assert_eq!(
+            bcs::from_bytes::<DWalletCheckpointMessage>(&bytes).unwrap(),
+            checkpoint
+        );
+    }
+
+    #[test]
+    fn test_v2_encoding_extends_v1() {
+        let checkpoint = v2_checkpoint(3);
+        assert_eq!(checkpoint.version(), DWalletCheckpointMessageVersion::V2);
+        let bytes = bcs::to_bytes(&checkpoint).unwrap();
+        let v1_bytes = bcs::to_bytes(&DWalletCheckpointMessage::new(
+            3,
+            7,
+            checkpoint.messages.clone(),
+        ))
+        .unwrap();
+        // The marker, then the V1 layout that Move parses, then the commitments it ignores.
+        assert_eq!(
+            bytes[..8],
+            DWALLET_CHECKPOINT_MESSAGE_V2_MARKER.to_le_bytes()
+        );
+        assert_eq!(bytes[8..8 + v1_bytes.len()], v1_bytes[..]);
+        assert_eq!(
+            bcs::from_bytes::<DWalletCheckpointMessage>(&bytes).unwrap(),
+            checkpoint
+        );
+        assert_ne!(
+            checkpoint.digest(),
+            DWalletCheckpointMessage::new(3, 7, checkpoint.messages.clone()).digest()
+        );
+
+        // A V2 message without commitments would re-encode as V1.
+        let mut without_commitments = bcs::to_bytes(&DWALLET_CHECKPOINT_MESSAGE_V2_MARKER).unwrap();
+        without_commitments.extend_from_slice(&v1_bytes);
+        without_commitments.push(0);
+        assert!(bcs::from_bytes::<DWalletCheckpointMessage>(&without_commitments).is_err());
+    }
+
+    #[test]
+    fn test_next_epoch_committee_commitment() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        assert!(
+            DWalletCheckpointMessage::new(3, 7, vec![DWalletCheckpointMessageKind::EndOfPublish])
+                .with_next_epoch_committee(&committee)
+                .is_err()
+        );
+
+        let checkpoint = v2_checkpoint(3)
+            .with_next_epoch_committee(&committee)
+            .unwrap();
+        let next_epoch_committee = checkpoint.next_epoch_committee().unwrap();
+        assert_eq!(next_epoch_committee.voting_rights, committee.voting_rights);
+        assert_eq!(
+            next_epoch_committee.quorum_threshold,
+            committee.quorum_threshold
+        );
+        assert!(checkpoint.messages_merkle_root().is_some());
+        assert_eq!(
+            bcs::from_bytes::<DWalletCheckpointMessage>(&bcs::to_bytes(&checkpoint).unwrap())
+                .unwrap(),
+            checkpoint
+        );
+    }
+
+    #[test]
+    fn test_inclusion_proof_verifies_against_the_certified_checkpoint() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let checkpoint = v2_checkpoint(committee.epoch());
+        let certified = CertifiedDWalletCheckpointMessage::new_from_keypairs_for_testing(
+            checkpoint.clone(),
+            &key_pairs,
+            &committee,
+        );
+        for index in 0..checkpoint.messages.len() {
+            let proof = checkpoint.message_inclusion_proof(index).unwrap();
+            assert_eq!(proof.message, checkpoint.messages[index]);
+            proof.verify(&certified, &committee).unwrap();
+            proof.verify_against_checkpoint(&checkpoint).unwrap();
+        }
+        assert!(checkpoint.message_inclusion_proof(5).is_err());
+
+        let (other_committee, _) = Committee::new_simple_test_committee();
+        let proof = checkpoint.message_inclusion_proof(0).unwrap();
+        assert!(proof.verify(&certified, &other_committee).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_a_changed_leaf() {
+        let checkpoint = v2_checkpoint(0);
+        let mut proof = checkpoint.message_inclusion_proof(2).unwrap();
+        proof.message = sign_message(100);
+        assert!(proof.verify_against_checkpoint(&checkpoint).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_a_wrong_index() {
+        let checkpoint = v2_checkpoint(0);
+        let mut proof = checkpoint.message_inclusion_proof(2).unwrap();
+        proof.proof.leaf_index = 3;
+        assert!(proof.verify_against_checkpoint(&checkpoint).is_err());
+
+        let mut proof = checkpoint.message_inclusion_proof(2).unwrap();
+        proof.sequence_number += 1;
+        assert!(proof.verify_against_checkpoint(&checkpoint).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_a_wrong_root() {
+        let checkpoint = v2_checkpoint(0);
+        let proof = checkpoint.message_inclusion_proof(1).unwrap();
+        let root = checkpoint.messages_merkle_root().unwrap();
+        proof.verify_against_root(&root).unwrap();
+        assert!(
+            proof
+                .verify_against_root(&leaf_hash(b"wrong root"))
+                .is_err()
+        );
+
+        let other_checkpoint = DWalletCheckpointMessage::new(0, 7, vec![sign_message(9)])
+            .with_messages_merkle_root()
+            .unwrap();
+        assert!(proof.verify_against_checkpoint(&other_checkpoint).is_err());
+
+        // A V1 checkpoint commits to no root.
+        let v1_checkpoint = DWalletCheckpointMessage::new(0, 7, checkpoint.messages.clone());
+        assert!(v1_checkpoint.message_inclusion_proof(1).is_err());
+        assert!(proof.verify_against_checkpoint(&v1_checkpoint).is_err());
+    }
+}
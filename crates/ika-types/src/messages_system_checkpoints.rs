@@ -67,6 +67,155 @@ pub enum SystemCheckpointMessageKind {
     },
 }
 
+/// A change to a single system parameter that validators can vote on through consensus.
+/// Once a quorum of stake votes for the exact same change during an epoch, it is
+/// emitted as the matching [`SystemCheckpointMessageKind`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SystemParameterChange {
+    SetEpochDurationMs(u64),
+    SetStakeSubsidyStartEpoch(EpochId),
+    SetStakeSubsidyRate(u16),
+    SetStakeSubsidyPeriodLength(u64),
+    SetMinValidatorCount(u64),
+    SetMaxValidatorCount(u64),
+    SetMinValidatorJoiningStake(u64),
+    SetMaxValidatorChangeCount(u64),
+    SetRewardSlashingRate(u64),
+}
+
+/// Rates are expressed in basis points, so they can never exceed 100%.
+const MAX_BASIS_POINTS: u64 = 10_000;
+
+impl SystemParameterChange {
+    /// The parameter names accepted by [`SystemParameterChange::new`].
+    pub const PARAMETER_NAMES: [&'static str; 9] = [
+        "epoch_duration_ms",
+        "stake_subsidy_start_epoch",
+        "stake_subsidy_rate",
+        "stake_subsidy_period_length",
+        "min_validator_count",
+        "max_validator_count",
+        "min_validator_joining_stake",
+        "max_validator_change_count",
+        "reward_slashing_rate",
+    ];
+
+    pub fn new(parameter: &str, value: u64) -> IkaResult<Self> {
+        let change = match parameter {
+            "epoch_duration_ms" => Self::SetEpochDurationMs(value),
+            "stake_subsidy_start_epoch" => Self::SetStakeSubsidyStartEpoch(value),
+            "stake_subsidy_rate" => {
+                Self::SetStakeSubsidyRate(u16::try_from(value).map_err(|_| {
+                    IkaError::InvalidSystemParameterChange(format!(
+                        "stake_subsidy_rate {value} does not fit in u16"
+                    ))
+                })?)
+            }
+            "stake_subsidy_period_length" => Self::SetStakeSubsidyPeriodLength(value),
+            "min_validator_count" => Self::SetMinValidatorCount(value),
+            "max_validator_count" => Self::SetMaxValidatorCount(value),
+            "min_validator_joining_stake" => Self::SetMinValidatorJoiningStake(value),
+            "max_validator_change_count" => Self::SetMaxValidatorChangeCount(value),
+            "reward_slashing_rate" => Self::SetRewardSlashingRate(value),
+            _ => {
+                return Err(IkaError::InvalidSystemParameterChange(format!(
+                    "unknown parameter `{parameter}`, expected one of {:?}",
+                    Self::PARAMETER_NAMES
+                )));
+            }
+        };
+        change.validate()?;
+        Ok(change)
+    }
+
+    /// Rejects values that the Move contracts would abort on.
+    pub fn validate(&self) -> IkaResult {
+        match self {
+            Self::SetEpochDurationMs(0) | Self::SetStakeSubsidyPeriodLength(0) => {
+                Err(IkaError::InvalidSystemParameterChange(format!(
+                    "{} must be greater than zero",
+                    self.parameter_name()
+                )))
+            }
+            Self::SetStakeSubsidyRate(_) | Self::SetRewardSlashingRate(_)
+                if self.value() > MAX_BASIS_POINTS =>
+            {
+                Err(IkaError::InvalidSystemParameterChange(format!(
+                    "{} is in basis points and cannot exceed {MAX_BASIS_POINTS}",
+                    self.parameter_name()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn parameter_name(&self) -> &'static str {
+        let index = match self {
+            Self::SetEpochDurationMs(_) => 0,
+            Self::SetStakeSubsidyStartEpoch(_) => 1,
+            Self::SetStakeSubsidyRate(_) => 2,
+            Self::SetStakeSubsidyPeriodLength(_) => 3,
+            Self::SetMinValidatorCount(_) => 4,
+            Self::SetMaxValidatorCount(_) => 5,
+            Self::SetMinValidatorJoiningStake(_) => 6,
+            Self::SetMaxValidatorChangeCount(_) => 7,
+            Self::SetRewardSlashingRate(_) => 8,
+        };
+        Self::PARAMETER_NAMES[index]
+    }
+
+    pub fn value(&self) -> u64 {
+        match self {
+            Self::SetStakeSubsidyRate(value) => u64::from(*value),
+            Self::SetEpochDurationMs(value)
+            | Self::SetStakeSubsidyStartEpoch(value)
+            | Self::SetStakeSubsidyPeriodLength(value)
+            | Self::SetMinValidatorCount(value)
+            | Self::SetMaxValidatorCount(value)
+            | Self::SetMinValidatorJoiningStake(value)
+            | Self::SetMaxValidatorChangeCount(value)
+            | Self::SetRewardSlashingRate(value) => *value,
+        }
+    }
+
+    /// Whether both changes target the same parameter, regardless of the value.
+    pub fn is_same_parameter(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl Display for SystemParameterChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.parameter_name(), self.value())
+    }
+}
+
+impl From<SystemParameterChange> for SystemCheckpointMessageKind {
+    fn from(change: SystemParameterChange) -> Self {
+        match change {
+            SystemParameterChange::SetEpochDurationMs(value) => Self::SetEpochDurationMs(value),
+            SystemParameterChange::SetStakeSubsidyStartEpoch(value) => {
+                Self::SetStakeSubsidyStartEpoch(value)
+            }
+            SystemParameterChange::SetStakeSubsidyRate(value) => Self::SetStakeSubsidyRate(value),
+            SystemParameterChange::SetStakeSubsidyPeriodLength(value) => {
+                Self::SetStakeSubsidyPeriodLength(value)
+            }
+            SystemParameterChange::SetMinValidatorCount(value) => Self::SetMinValidatorCount(value),
+            SystemParameterChange::SetMaxValidatorCount(value) => Self::SetMaxValidatorCount(value),
+            SystemParameterChange::SetMinValidatorJoiningStake(value) => {
+                Self::SetMinValidatorJoiningStake(value)
+            }
+            SystemParameterChange::SetMaxValidatorChangeCount(value) => {
+                Self::SetMaxValidatorChangeCount(value)
+            }
+            SystemParameterChange::SetRewardSlashingRate(value) => {
+                Self::SetRewardSlashingRate(value)
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SystemCheckpointMessage {
     pub epoch: EpochId,
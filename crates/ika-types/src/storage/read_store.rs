@@ -19,6 +19,9 @@ pub trait ReadStore {
 
     fn get_committee(&self, epoch: EpochId) -> Result<Option<Arc<Committee>>>;
 
+    /// The epoch of the earliest committee in the store, if any.
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>>;
+
     //
     // Checkpoint Getters
     //
@@ -111,6 +114,10 @@ impl<T: ReadStore + ?Sized> ReadStore for &T {
         (*self).get_committee(epoch)
     }
 
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>> {
+        (*self).get_lowest_committee_epoch()
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         (*self).get_latest_dwallet_checkpoint()
     }
@@ -193,6 +200,10 @@ impl<T: ReadStore + ?Sized> ReadStore for Box<T> {
         (**self).get_committee(epoch)
     }
 
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>> {
+        (**self).get_lowest_committee_epoch()
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         (**self).get_latest_dwallet_checkpoint()
     }
@@ -275,6 +286,10 @@ impl<T: ReadStore + ?Sized> ReadStore for Arc<T> {
         (**self).get_committee(epoch)
     }
 
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>> {
+        (**self).get_lowest_committee_epoch()
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         (**self).get_latest_dwallet_checkpoint()
     }
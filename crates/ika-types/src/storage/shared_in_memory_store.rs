@@ -41,6 +41,10 @@ impl ReadStore for SharedInMemoryStore {
             .pipe(Ok)
     }
 
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>> {
+        Ok(self.inner().get_lowest_committee_epoch())
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         todo!()
     }
@@ -385,6 +389,13 @@ impl InMemoryStore {
         self.epoch_to_committee.get(epoch as usize)
     }
 
+    pub fn get_lowest_committee_epoch(&self) -> Option<EpochId> {
+        // The committees are inserted in order, from the genesis committee.
+        self.epoch_to_committee
+            .first()
+            .map(|committee| committee.epoch)
+    }
+
     pub fn insert_committee(&mut self, committee: Committee) {
         let epoch = committee.epoch as usize;
 
@@ -545,6 +556,10 @@ impl ReadStore for SingleCheckpointSharedInMemoryStore {
         self.0.get_committee(epoch)
     }
 
+    fn get_lowest_committee_epoch(&self) -> Result<Option<EpochId>> {
+        self.0.get_lowest_committee_epoch()
+    }
+
     fn get_latest_dwallet_checkpoint(&self) -> Result<VerifiedDWalletCheckpointMessage> {
         todo!()
     }
@@ -11,6 +11,7 @@ use serde::Deserialize;
 use sui_types::balance::Balance;
 use sui_types::base_types::ObjectID;
 use sui_types::collection_types::{Bag, Table, TableVec, VecMap};
+use sui_types::id::UID;
 
 #[allow(dead_code)]
 const E_METADATA_INVALID_POP: u64 = 0;
@@ -52,6 +53,44 @@ impl VerifiedValidatorInfo {
 }
 
 impl ValidatorInfo {
+    /// The info of a validator that has no pending changes for the next epoch.
+    pub fn new(
+        name: String,
+        validator_id: ObjectID,
+        network_address: String,
+        p2p_address: String,
+        consensus_address: String,
+        protocol_pubkey_bytes: Vec<u8>,
+        network_pubkey_bytes: Vec<u8>,
+        consensus_pubkey_bytes: Vec<u8>,
+        mpc_data_bytes: TableVec,
+    ) -> Self {
+        let protocol_pubkey = Element::new(protocol_pubkey_bytes.clone());
+        Self {
+            name,
+            validator_id,
+            network_address,
+            p2p_address,
+            consensus_address,
+            protocol_pubkey_bytes,
+            protocol_pubkey,
+            network_pubkey_bytes,
+            consensus_pubkey_bytes,
+            mpc_data_bytes: Some(mpc_data_bytes),
+            next_epoch_protocol_pubkey_bytes: None,
+            next_epoch_network_pubkey_bytes: None,
+            next_epoch_consensus_pubkey_bytes: None,
+            next_epoch_mpc_datd_bytes: None,
+            next_epoch_network_address: None,
+            next_epoch_p2p_address: None,
+            next_epoch_consensus_address: None,
+            previous_mpc_data_bytes: None,
+            metadata: ExtendedField {
+                id: ObjectID::random(),
+            },
+        }
+    }
+
     /// Verify validator info and return a verified version (on success) or error code (on failure)
     pub fn verify(&self) -> anyhow::Result<VerifiedValidatorInfo, u64> {
         let protocol_pubkey = AuthorityPublicKey::from_bytes(self.protocol_pubkey_bytes.as_ref())
@@ -235,6 +274,45 @@ pub enum PoolState {
 }
 
 impl StakingPool {
+    /// An active staking pool holding `ika_balance` of self-stake, with fresh capability IDs.
+    pub fn new_active(
+        validator_info: ValidatorInfo,
+        activation_epoch: u64,
+        ika_balance: u64,
+    ) -> Self {
+        let empty_pending_values = || PendingValues {
+            values: VecMap { contents: vec![] },
+        };
+        Self {
+            id: validator_info.validator_id,
+            validator_info,
+            verified_validator_info: OnceCell::new(),
+            state: PoolState::Active,
+            activation_epoch: Some(activation_epoch),
+            latest_epoch: activation_epoch,
+            ika_balance,
+            num_shares: ika_balance,
+            pending_shares_withdraw: empty_pending_values(),
+            pre_active_withdrawals: empty_pending_values(),
+            pending_commission_rate: empty_pending_values(),
+            commission_rate: 0,
+            exchange_rates: Table {
+                id: ObjectID::random(),
+                size: 0,
+            },
+            pending_stake: empty_pending_values(),
+            rewards_pool: Balance::new(0),
+            commission: Balance::new(0),
+            validator_cap_id: ObjectID::random(),
+            operation_cap_id: ObjectID::random(),
+            commission_cap_id: ObjectID::random(),
+            extra_fields: Bag {
+                id: UID::new(ObjectID::random()),
+                size: 0,
+            },
+        }
+    }
+
     pub fn verified_validator_info(&self) -> &VerifiedValidatorInfo {
         // Todo (#1298): Remove unwrap.
         self.verified_validator_info
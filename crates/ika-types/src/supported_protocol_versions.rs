@@ -49,6 +49,26 @@ impl SupportedProtocolVersions {
         let min = std::cmp::max(self.min, v);
         Self { min, max: self.max }
     }
+
+    /// Drops the versions above `min` whose checkpoints the on-chain `DWalletCoordinator`, at
+    /// `coordinator_version`, doesn't accept yet.
+    pub fn truncate_above_dwallet_coordinator_version(
+        self,
+        coordinator_version: u64,
+        chain: Chain,
+    ) -> Self {
+        let mut max = self.max;
+        while max > self.min
+            && ProtocolConfig::get_for_version_if_supported(max, chain)
+                .and_then(|config| config.min_dwallet_coordinator_version_as_option())
+                .is_some_and(|min_coordinator_version| {
+                    min_coordinator_version > coordinator_version
+                })
+        {
+            max = max - 1;
+        }
+        Self { min: self.min, max }
+    }
 }
 
 /// Models the set of protocol versions supported by a validator.
@@ -92,3 +112,30 @@ impl SupportedProtocolVersionsWithHashes {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_above_dwallet_coordinator_version() {
+        let supported = SupportedProtocolVersions::new_for_testing(1, 3);
+
+        // V2 checkpoints, from protocol version 2, need coordinator version 2.
+        assert_eq!(
+            supported.truncate_above_dwallet_coordinator_version(1, Chain::Unknown),
+            SupportedProtocolVersions::new_for_testing(1, 1)
+        );
+        assert_eq!(
+            supported.truncate_above_dwallet_coordinator_version(2, Chain::Unknown),
+            supported
+        );
+
+        // The current version is always kept.
+        assert_eq!(
+            SupportedProtocolVersions::new_for_testing(2, 3)
+                .truncate_above_dwallet_coordinator_version(1, Chain::Unknown),
+            SupportedProtocolVersions::new_for_testing(2, 2)
+        );
+    }
+}
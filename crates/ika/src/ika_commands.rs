@@ -5,6 +5,7 @@ use anyhow::{bail, ensure};
 use clap::*;
 use colored::Colorize;
 use fastcrypto::traits::KeyPair;
+use ika_config::node::{RemoteSignerConfig, default_remote_signer_request_timeout_ms};
 use ika_config::{
     Config, IKA_NETWORK_CONFIG, PersistedConfig, ika_config_dir, network_config_exists,
 };
@@ -96,6 +97,13 @@ pub enum IkaCommand {
         /// Start the network without a fullnode
         #[clap(long = "no-full-node")]
         no_full_node: bool,
+
+        /// Run against an embedded stand-in for Sui instead of a Sui network, so neither a Sui
+        /// fullnode nor a faucet is needed. The stand-in's state lives in memory,
+        /// so this flag requires `--force-reinitiation`,
+        /// and `ika` to be built with the `local-sui` feature.
+        #[clap(long)]
+        local_sui: bool,
     },
     #[clap(name = "network")]
     Network {
@@ -118,6 +126,16 @@ pub enum IkaCommand {
         json: bool,
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
+        /// Sign the validator operation transactions with a key held by the signing service
+        /// at this url, rather than with the key of the wallet's active address.
+        #[clap(long, global = true, requires = "remote_signer_key_id")]
+        remote_signer_url: Option<String>,
+        /// The ID of the key in the signing service.
+        #[clap(long, global = true, requires = "remote_signer_url")]
+        remote_signer_key_id: Option<String>,
+        /// Path of a file holding a bearer token that authenticates to the signing service.
+        #[clap(long, global = true, requires = "remote_signer_url")]
+        remote_signer_auth_token_path: Option<PathBuf>,
     },
 
     #[cfg(feature = "protocol-commands")]
@@ -178,6 +196,7 @@ impl IkaCommand {
                 sui_faucet_url,
                 no_full_node,
                 epoch_duration_ms,
+                local_sui,
             } => {
                 let thread_builder = thread::Builder::new();
                 const SIXTEEN_MB: usize = 16777216;
@@ -195,6 +214,7 @@ impl IkaCommand {
                             sui_fullnode_rpc_url,
                             sui_faucet_url,
                             no_full_node,
+                            local_sui,
                         )
                         .await
                         {
@@ -210,17 +230,33 @@ impl IkaCommand {
                 Ok(())
             }
             IkaCommand::Validator {
-                config, cmd, json, ..
+                config,
+                cmd,
+                json,
+                remote_signer_url,
+                remote_signer_key_id,
+                remote_signer_auth_token_path,
+                ..
             } => {
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 let mut context = WalletContext::new(&config_path)?;
+                let remote_signer =
+                    remote_signer_url
+                        .zip(remote_signer_key_id)
+                        .map(|(url, key_id)| RemoteSignerConfig {
+                            url,
+                            key_id,
+                            address: None,
+                            auth_token_path: remote_signer_auth_token_path,
+                            request_timeout_ms: default_remote_signer_request_timeout_ms(),
+                        });
                 if let Some(cmd) = cmd {
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
                             eprintln!("{}", format!("[warning] {e}").yellow().bold());
                         }
                     }
-                    cmd.execute(&mut context).await?.print(!json);
+                    cmd.execute(&mut context, remote_signer).await?.print(!json);
                 } else {
                     // Print help
                     let mut app: Command = IkaCommand::command();
@@ -262,6 +298,7 @@ async fn start(
     _sui_fullnode_rpc_url: String,
     _sui_faucet_url: String,
     no_full_node: bool,
+    local_sui: bool,
 ) -> Result<(), anyhow::Error> {
     if force_reinitiation {
         ensure!(
@@ -269,7 +306,18 @@ async fn start(
             "Cannot pass `--force-reinitiation` and `--network.config` at the same time."
         );
     }
-    tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
+    ensure!(
+        !local_sui || force_reinitiation,
+        "`--local-sui` can only be used together with `--force-reinitiation`."
+    );
+    ensure!(
+        !local_sui || cfg!(feature = "local-sui"),
+        "`--local-sui` requires `ika` to be built with the `local-sui` feature."
+    );
+    if !local_sui {
+        // Give the local Sui network time to start.
+        tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
+    }
 
     if epoch_duration_ms.is_some() && network_config_exists(config.clone()) && !force_reinitiation {
         bail!(
@@ -326,6 +374,11 @@ async fn start(
         }
     }
 
+    #[cfg(feature = "local-sui")]
+    if local_sui {
+        swarm_builder = swarm_builder.with_local_sui();
+    }
+
     if no_full_node {
         swarm_builder = swarm_builder.with_fullnode_count(0);
     } else {
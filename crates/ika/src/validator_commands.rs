@@ -6,7 +6,7 @@ use std::{
     fmt,
     fmt::{Debug, Display, Formatter, Write},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 use sui_config::Config;
 use sui_types::{base_types::SuiAddress, multiaddr::Multiaddr};
@@ -15,10 +15,12 @@ use crate::{IkaPackagesConfigFile, read_ika_sui_config_yaml};
 use clap::*;
 use colored::Colorize;
 use dwallet_classgroups_types::ClassGroupsKeyPairAndProof;
-use dwallet_mpc_types::dwallet_mpc::{MPCDataV1, VersionedMPCData};
-use dwallet_rng::RootSeed;
+use dwallet_mpc_types::dwallet_mpc::{MPCDataTrait, MPCDataV1, VersionedMPCData};
+use dwallet_rng::{KeyEncryptionKey, RootSeed, RootSeedShare, RootSeedUnlock};
 use fastcrypto::traits::{KeyPair, ToFromBytes};
-use ika_config::node::read_authority_keypair_from_file;
+use ika_config::node::{
+    RemoteSignerConfig, default_admin_interface_port, read_authority_keypair_from_file,
+};
 use ika_config::validator_info::ValidatorInfo;
 use ika_config::{IKA_SUI_CONFIG, ika_config_dir};
 use ika_sui_client::SuiClient;
@@ -34,9 +36,15 @@ use ika_sui_client::ika_validator_transactions::{
     verify_commission_cap, verify_operation_cap, verify_validator_cap, withdraw_stake,
 };
 use ika_sui_client::metrics::SuiClientMetrics;
+use ika_sui_client::signer::{FileSigner, RemoteSigner, Signer};
+use ika_types::committee::ClassGroupsEncryptionKeyAndProof;
 use ika_types::crypto::generate_proof_of_possession;
 use ika_types::messages_dwallet_mpc::IkaNetworkConfig;
-use ika_types::sui::{DEFAULT_COMMISSION_RATE, PricingInfoKey, PricingInfoValue};
+use ika_types::messages_system_checkpoints::SystemParameterChange;
+use ika_types::sui::{
+    DEFAULT_COMMISSION_RATE, PricingInfoKey, PricingInfoValue, SystemInner, ValidatorOperationCapV1,
+};
+use prometheus::Registry;
 use serde::Serialize;
 use sui::validator_commands::write_transaction_response;
 use sui_config::PersistedConfig;
@@ -316,6 +324,24 @@ pub enum IkaValidatorCommand {
         #[clap(name = "ika-sui-config", long)]
         ika_sui_config: Option<PathBuf>,
     },
+    /// Rotate the validator's class-groups key: generate a new root seed, save it to
+    /// `next-seed-path`, and publish its class-groups key as the next epoch MPC data.
+    /// Must run before the mid-epoch, after which the next epoch MPC data can't change.
+    /// If `next-seed-path` exists, its seed is published again, to retry a failed rotation.
+    /// The unlock options are given to save the new seed encrypted.
+    #[clap(name = "rotate-class-groups-key")]
+    RotateClassGroupsKey {
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+        #[clap(name = "validator-operation-cap-id", long)]
+        validator_operation_cap_id: ObjectID,
+        #[clap(name = "next-seed-path", long)]
+        next_seed_path: PathBuf,
+        #[clap(flatten)]
+        unlock: RootSeedUnlockArgs,
+        #[clap(name = "ika-sui-config", long)]
+        ika_sui_config: Option<PathBuf>,
+    },
     #[clap(name = "verify-validator-cap")]
     VerifyValidatorCap {
         #[clap(name = "gas-budget", long)]
@@ -359,6 +385,105 @@ pub enum IkaValidatorCommand {
         #[clap(name = "ika-sui-config", long)]
         ika_sui_config: Option<PathBuf>,
     },
+    /// Vote for a system parameter change through the local validator node.
+    /// The change is applied once validators holding a quorum of stake vote for the same value
+    /// within an epoch.
+    #[clap(name = "propose-parameter")]
+    ProposeParameter {
+        /// One of: epoch_duration_ms, stake_subsidy_start_epoch, stake_subsidy_rate,
+        /// stake_subsidy_period_length, min_validator_count, max_validator_count,
+        /// min_validator_joining_stake, max_validator_change_count, reward_slashing_rate.
+        #[clap(name = "parameter", long)]
+        parameter: String,
+        #[clap(name = "value", long)]
+        value: u64,
+        /// The admin interface port of the validator node, which only listens on localhost.
+        #[clap(name = "admin-port", long, default_value_t = default_admin_interface_port())]
+        admin_port: u16,
+    },
+    /// Encrypt a plain root seed file, so it can only be read with a passphrase
+    /// or a key-encryption key. Exactly one of the unlock options must be given.
+    #[clap(name = "encrypt-root-seed")]
+    EncryptRootSeed {
+        #[clap(name = "seed-path", long)]
+        seed_path: PathBuf,
+        #[clap(name = "output-path", long)]
+        output_path: PathBuf,
+        #[clap(flatten)]
+        unlock: RootSeedUnlockArgs,
+    },
+    /// Split the root seed into Shamir shares, any `threshold` of which recover it.
+    /// The unlock options are given if the root seed file is encrypted.
+    #[clap(name = "split-root-seed")]
+    SplitRootSeed {
+        #[clap(name = "seed-path", long)]
+        seed_path: PathBuf,
+        #[clap(flatten)]
+        unlock: RootSeedUnlockArgs,
+        #[clap(name = "threshold", long)]
+        threshold: u8,
+        #[clap(name = "shares", long)]
+        shares: u8,
+        /// The directory to write the share files to, each of which should then be moved
+        /// to a different location.
+        #[clap(name = "output-dir", long)]
+        output_dir: PathBuf,
+    },
+    /// Recover the root seed from its Shamir shares. The recovered seed is only written
+    /// if it derives the class-groups encryption key of the validator, read either from
+    /// its `validator.info` file or from its current or next epoch MPC data on-chain.
+    /// The unlock options are given to write the seed encrypted.
+    #[clap(name = "recover-root-seed")]
+    RecoverRootSeed {
+        #[clap(name = "share-path", long, num_args = 1.., required = true)]
+        share_paths: Vec<PathBuf>,
+        #[clap(name = "output-path", long)]
+        output_path: PathBuf,
+        #[clap(flatten)]
+        unlock: RootSeedUnlockArgs,
+        #[clap(name = "validator-info-path", long, conflicts_with = "validator-id")]
+        validator_info_file: Option<PathBuf>,
+        #[clap(name = "validator-id", long)]
+        validator_id: Option<ObjectID>,
+        #[clap(name = "ika-sui-config", long)]
+        ika_sui_config: Option<PathBuf>,
+    },
+}
+
+/// How an encrypted root seed file is unlocked, at most one of which may be given.
+#[derive(Args)]
+pub struct RootSeedUnlockArgs {
+    /// The name of an environment variable holding the passphrase.
+    #[clap(name = "passphrase-env", long)]
+    passphrase_env: Option<String>,
+    /// A file holding the passphrase.
+    #[clap(name = "passphrase-file", long)]
+    passphrase_file: Option<PathBuf>,
+    /// A file holding the key-encryption key.
+    /// When encrypting a seed, it is generated if it doesn't exist.
+    #[clap(name = "key-encryption-key-file", long)]
+    key_encryption_key_file: Option<PathBuf>,
+}
+
+impl RootSeedUnlockArgs {
+    fn into_unlock(self) -> Result<Option<RootSeedUnlock>> {
+        Ok(
+            match (
+                self.passphrase_env,
+                self.passphrase_file,
+                self.key_encryption_key_file,
+            ) {
+                (None, None, None) => None,
+                (Some(name), None, None) => Some(RootSeedUnlock::PassphraseEnv(name)),
+                (None, Some(path), None) => Some(RootSeedUnlock::PassphraseFile(path)),
+                (None, None, Some(path)) => Some(RootSeedUnlock::KeyEncryptionKeyFile(path)),
+                _ => anyhow::bail!(
+                    "only one of --passphrase-env, --passphrase-file \
+                     and --key-encryption-key-file can be given"
+                ),
+            },
+        )
+    }
 }
 
 #[derive(Serialize)]
@@ -389,17 +514,33 @@ pub enum IkaValidatorCommandResponse {
     SetNextEpochNetworkPubkey(SuiTransactionBlockResponse),
     SetNextEpochConsensusPubkey(SuiTransactionBlockResponse),
     SetNextEpochMPCData(SuiTransactionBlockResponse),
+    RotateClassGroupsKey {
+        response: SuiTransactionBlockResponse,
+        next_seed_path: PathBuf,
+        unlock: Option<RootSeedUnlock>,
+    },
     VerifyValidatorCap(SuiTransactionBlockResponse),
     VerifyOperationCap(SuiTransactionBlockResponse),
     VerifyCommissionCap(SuiTransactionBlockResponse),
     SetPricingVote(SuiTransactionBlockResponse),
     FetchCurrentPricingInfo(PathBuf),
+    ProposeParameter {
+        submitted: String,
+        pending_votes: String,
+    },
+    EncryptRootSeed {
+        seed_path: PathBuf,
+        unlock: RootSeedUnlock,
+    },
+    SplitRootSeed(Vec<PathBuf>),
+    RecoverRootSeed(PathBuf),
 }
 
 impl IkaValidatorCommand {
     pub async fn execute(
         self,
         context: &mut WalletContext,
+        remote_signer: Option<RemoteSignerConfig>,
     ) -> Result<IkaValidatorCommandResponse, anyhow::Error> {
         Ok(match self {
             IkaValidatorCommand::MakeValidatorInfo {
@@ -510,8 +651,11 @@ impl IkaValidatorCommand {
                 let validator_info_bytes = fs::read_to_string(validator_info_file)?;
                 let validator_info: ValidatorInfo = serde_yaml::from_str(&validator_info_bytes)?;
 
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
+
                 let (res, validator_caps) = request_add_validator_candidate(
                     context,
+                    signer.as_ref(),
                     &validator_info,
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
@@ -530,8 +674,11 @@ impl IkaValidatorCommand {
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
 
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
+
                 let response = request_add_validator(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -551,8 +698,11 @@ impl IkaValidatorCommand {
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
 
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
+
                 let res = stake_ika(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     ika_supply_id,
@@ -572,8 +722,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = request_remove_validator(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -590,8 +742,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = request_remove_validator_candidate(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -609,8 +763,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_commission(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -628,8 +784,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = withdraw_stake(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     staked_ika_id,
@@ -646,8 +804,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = request_withdraw_stake(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     staked_ika_id,
@@ -665,8 +825,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = report_validator(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -685,8 +847,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = undo_report_validator(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -704,8 +868,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = rotate_operation_cap(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -722,8 +888,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = rotate_commission_cap(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -741,8 +909,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = collect_commission(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_commission_cap_id,
@@ -761,8 +931,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_validator_name(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -780,8 +952,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = validator_metadata(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_id,
@@ -799,8 +973,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_validator_metadata(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -819,8 +995,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_network_address(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -839,8 +1017,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_p2p_address(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -859,8 +1039,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_consensus_address(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -881,8 +1063,10 @@ impl IkaValidatorCommand {
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
                 let validator_info_bytes = fs::read_to_string(validator_info_file)?;
                 let validator_info: ValidatorInfo = serde_yaml::from_str(&validator_info_bytes)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_protocol_pubkey_bytes(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -904,8 +1088,10 @@ impl IkaValidatorCommand {
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
                 let validator_info_bytes = fs::read_to_string(validator_info_file)?;
                 let validator_info: ValidatorInfo = serde_yaml::from_str(&validator_info_bytes)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_network_pubkey_bytes(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -926,8 +1112,10 @@ impl IkaValidatorCommand {
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
                 let validator_info_bytes = fs::read_to_string(validator_info_file)?;
                 let validator_info: ValidatorInfo = serde_yaml::from_str(&validator_info_bytes)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_next_epoch_consensus_pubkey_bytes(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -955,8 +1143,11 @@ impl IkaValidatorCommand {
                     class_groups_public_key_and_proof: bcs::to_bytes(&new_class_groups_key)?,
                 });
 
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
+
                 let response = set_next_epoch_mpc_data_bytes(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -975,6 +1166,105 @@ impl IkaValidatorCommand {
 
                 IkaValidatorCommandResponse::SetNextEpochMPCData(response)
             }
+            IkaValidatorCommand::RotateClassGroupsKey {
+                gas_budget,
+                validator_operation_cap_id,
+                next_seed_path,
+                unlock,
+                ika_sui_config,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
+                let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let unlock = unlock.into_unlock()?;
+
+                // The next epoch MPC data can't be set once the mid-epoch reconfiguration
+                // selected a next committee holding the validator, as the network keys are
+                // re-shared to its current class-groups key.
+                let client = SuiClient::new(
+                    &[context.get_active_env()?.rpc.clone()],
+                    SuiClientMetrics::new(&Registry::new()),
+                    config.packages.ika_package_id,
+                    config.packages.ika_common_package_id,
+                    config.packages.ika_dwallet_2pc_mpc_package_id,
+                    config.packages.ika_system_package_id,
+                    config.objects.ika_system_object_id,
+                    config.objects.ika_dwallet_coordinator_object_id,
+                )
+                .await?;
+                let operation_cap: ValidatorOperationCapV1 = bcs::from_bytes(
+                    &context
+                        .get_client()
+                        .await?
+                        .read_api()
+                        .get_move_object_bcs(validator_operation_cap_id)
+                        .await?,
+                )?;
+                let SystemInner::V1(system_inner) = client.get_system_inner().await?;
+                let in_next_epoch_committee = system_inner
+                    .validator_set
+                    .next_epoch_committee
+                    .as_ref()
+                    .is_some_and(|committee| {
+                        committee
+                            .members
+                            .iter()
+                            .any(|member| member.validator_id == operation_cap.validator_id)
+                    });
+                if in_next_epoch_committee {
+                    anyhow::bail!(
+                        "the mid-epoch reconfiguration of epoch {} already selected the next \
+                         committee with the validator's current class-groups key, \
+                         run the command again in epoch {} before its mid-epoch",
+                        system_inner.epoch,
+                        system_inner.epoch + 1
+                    );
+                }
+
+                // The seed is saved before its key is published,
+                // so a published key can't be left without its seed.
+                let next_seed = if next_seed_path.exists() {
+                    println!("Use existing next root seed: {next_seed_path:?}.");
+                    match &unlock {
+                        Some(unlock) => RootSeed::from_encrypted_file(&next_seed_path, unlock)?,
+                        None => RootSeed::from_file(&next_seed_path)?,
+                    }
+                } else {
+                    let next_seed = RootSeed::random_seed();
+                    match &unlock {
+                        Some(unlock) => {
+                            write_encrypted_root_seed(&next_seed, &next_seed_path, unlock)?
+                        }
+                        None => {
+                            next_seed.save_to_file(next_seed_path.clone())?;
+                        }
+                    }
+                    println!("Generated next root seed file: {next_seed_path:?}.");
+                    next_seed
+                };
+                let next_class_groups_key =
+                    ClassGroupsKeyPairAndProof::from_seed(&next_seed).encryption_key_and_proof();
+                let mpc_data = VersionedMPCData::V1(MPCDataV1 {
+                    class_groups_public_key_and_proof: bcs::to_bytes(&next_class_groups_key)?,
+                });
+
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
+                let response = set_next_epoch_mpc_data_bytes(
+                    context,
+                    signer.as_ref(),
+                    config.packages.ika_system_package_id,
+                    config.objects.ika_system_object_id,
+                    validator_operation_cap_id,
+                    mpc_data,
+                    gas_budget,
+                )
+                .await?;
+                IkaValidatorCommandResponse::RotateClassGroupsKey {
+                    response,
+                    next_seed_path,
+                    unlock,
+                }
+            }
             IkaValidatorCommand::VerifyValidatorCap {
                 gas_budget,
                 validator_cap_id,
@@ -983,8 +1273,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = verify_validator_cap(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_cap_id,
@@ -1001,8 +1293,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = verify_operation_cap(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_operation_cap_id,
@@ -1019,8 +1313,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = verify_commission_cap(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     validator_commission_cap_id,
@@ -1041,8 +1337,10 @@ impl IkaValidatorCommand {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let config_path = ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
+                let signer = operation_signer(context, remote_signer.as_ref()).await?;
                 let response = set_pricing_vote(
                     context,
+                    signer.as_ref(),
                     config.packages.ika_system_package_id,
                     config.objects.ika_system_object_id,
                     config.packages.ika_dwallet_2pc_mpc_package_id,
@@ -1059,7 +1357,7 @@ impl IkaValidatorCommand {
                 let config = read_ika_sui_config_yaml(context, &config_path)?;
 
                 let client = SuiClient::new(
-                    &context.get_active_env()?.rpc,
+                    &[context.get_active_env()?.rpc.clone()],
                     SuiClientMetrics::new_for_testing(),
                     config.packages.ika_package_id,
                     config.packages.ika_common_package_id,
@@ -1075,6 +1373,175 @@ impl IkaValidatorCommand {
                 serde_yaml::to_writer(file, &current_pricing_info)?;
                 IkaValidatorCommandResponse::FetchCurrentPricingInfo(PathBuf::from(path))
             }
+            IkaValidatorCommand::ProposeParameter {
+                parameter,
+                value,
+                admin_port,
+            } => {
+                // Validate locally first so typos are reported without reaching the node.
+                let change = SystemParameterChange::new(&parameter, value)?;
+                let admin_url = format!("http://127.0.0.1:{admin_port}");
+                let client = reqwest::Client::new();
+
+                let response = client
+                    .post(format!("{admin_url}/propose-system-parameter"))
+                    .query(&[
+                        ("parameter", change.parameter_name().to_string()),
+                        ("value", change.value().to_string()),
+                    ])
+                    .send()
+                    .await?;
+                let status = response.status();
+                let submitted = response.text().await?;
+                if !status.is_success() {
+                    anyhow::bail!("validator node rejected the vote ({status}): {submitted}");
+                }
+
+                let pending_votes = client
+                    .get(format!("{admin_url}/system-parameter-votes"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?;
+                IkaValidatorCommandResponse::ProposeParameter {
+                    submitted,
+                    pending_votes,
+                }
+            }
+            IkaValidatorCommand::EncryptRootSeed {
+                seed_path,
+                output_path,
+                unlock,
+            } => {
+                let unlock = unlock.into_unlock()?.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "one of --passphrase-env, --passphrase-file \
+                         and --key-encryption-key-file must be given"
+                    )
+                })?;
+                if output_path.exists() {
+                    anyhow::bail!("{output_path:?} already exists, refusing to overwrite it");
+                }
+                let seed = RootSeed::from_file(&seed_path)?;
+                write_encrypted_root_seed(&seed, &output_path, &unlock)?;
+                IkaValidatorCommandResponse::EncryptRootSeed {
+                    seed_path: output_path,
+                    unlock,
+                }
+            }
+            IkaValidatorCommand::SplitRootSeed {
+                seed_path,
+                unlock,
+                threshold,
+                shares,
+                output_dir,
+            } => {
+                let seed = match unlock.into_unlock()? {
+                    Some(unlock) => RootSeed::from_encrypted_file(&seed_path, &unlock)?,
+                    None => RootSeed::from_file(&seed_path)?,
+                };
+                let shares = seed.split_into_shares(threshold, shares)?;
+
+                fs::create_dir_all(&output_dir)?;
+                let share_paths = shares
+                    .iter()
+                    .map(|share| {
+                        output_dir.join(format!(
+                            "root-seed-share-{}-of-{}.txt",
+                            share.index(),
+                            shares.len()
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(path) = share_paths.iter().find(|path| path.exists()) {
+                    anyhow::bail!("{path:?} already exists, refusing to overwrite it");
+                }
+                for (share, path) in shares.iter().zip(&share_paths) {
+                    share.save_to_file(path)?;
+                }
+                IkaValidatorCommandResponse::SplitRootSeed(share_paths)
+            }
+            IkaValidatorCommand::RecoverRootSeed {
+                share_paths,
+                output_path,
+                unlock,
+                validator_info_file,
+                validator_id,
+                ika_sui_config,
+            } => {
+                let unlock = unlock.into_unlock()?;
+                if output_path.exists() {
+                    anyhow::bail!("{output_path:?} already exists, refusing to overwrite it");
+                }
+                let shares = share_paths
+                    .iter()
+                    .map(|path| {
+                        RootSeedShare::from_file(path)
+                            .map_err(|e| anyhow::anyhow!("failed to read share {path:?}: {e}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let seed = RootSeed::combine_shares(&shares)?;
+
+                let expected_mpc_data = match (validator_info_file, validator_id) {
+                    (Some(validator_info_file), None) => {
+                        let validator_info_bytes = fs::read_to_string(validator_info_file)?;
+                        let validator_info: ValidatorInfo =
+                            serde_yaml::from_str(&validator_info_bytes)?;
+                        vec![validator_info.mpc_data]
+                    }
+                    (None, Some(validator_id)) => {
+                        let config_path =
+                            ika_sui_config.unwrap_or(ika_config_dir()?.join(IKA_SUI_CONFIG));
+                        let config = read_ika_sui_config_yaml(context, &config_path)?;
+                        let client = SuiClient::new(
+                            &[context.get_active_env()?.rpc.clone()],
+                            SuiClientMetrics::new(&Registry::new()),
+                            config.packages.ika_package_id,
+                            config.packages.ika_common_package_id,
+                            config.packages.ika_dwallet_2pc_mpc_package_id,
+                            config.packages.ika_system_package_id,
+                            config.objects.ika_system_object_id,
+                            config.objects.ika_dwallet_coordinator_object_id,
+                        )
+                        .await?;
+                        let validators = client
+                            .get_validators_info_by_ids(vec![validator_id])
+                            .await?;
+                        // The seed may have been rotated for the next epoch.
+                        let mut mpc_data = Vec::new();
+                        for read_next_mpc_data in [false, true] {
+                            mpc_data.extend(
+                                client
+                                    .get_mpc_data_from_validators_pool(
+                                        &validators,
+                                        read_next_mpc_data,
+                                    )
+                                    .await?
+                                    .remove(&validator_id),
+                            );
+                        }
+                        mpc_data
+                    }
+                    _ => anyhow::bail!(
+                        "exactly one of --validator-info-path and --validator-id must be given"
+                    ),
+                };
+                if !root_seed_matches_mpc_data(&seed, &expected_mpc_data)? {
+                    anyhow::bail!(
+                        "the recovered root seed doesn't derive the validator's class-groups \
+                         encryption key, the shares are of a different seed"
+                    );
+                }
+
+                match unlock {
+                    Some(unlock) => write_encrypted_root_seed(&seed, &output_path, &unlock)?,
+                    None => {
+                        seed.save_to_file(output_path.clone())?;
+                    }
+                }
+                IkaValidatorCommandResponse::RecoverRootSeed(output_path)
+            }
         })
     }
 }
@@ -1146,12 +1613,88 @@ impl Display for IkaValidatorCommandResponse {
                     write_transaction_response_without_transaction_data(response)?
                 )?;
             }
+            IkaValidatorCommandResponse::RotateClassGroupsKey {
+                response,
+                next_seed_path,
+                unlock,
+            } => {
+                writeln!(
+                    writer,
+                    "{}",
+                    write_transaction_response_without_transaction_data(response)?
+                )?;
+                if response.status_ok() != Some(true) {
+                    writeln!(
+                        writer,
+                        "Publishing the new class-groups key failed, run the command again to retry."
+                    )?;
+                } else {
+                    writeln!(
+                        writer,
+                        "Add the next root seed to the node config, and restart the node before the epoch ends:"
+                    )?;
+                    writeln!(writer, "next-root-seed-key-pair:")?;
+                    writeln!(writer, "  path: {}", next_seed_path.display())?;
+                    if let Some(unlock) = unlock {
+                        writeln!(writer, "  unlock:")?;
+                        let unlock = serde_yaml::to_string(unlock).map_err(|_| fmt::Error)?;
+                        for line in unlock.lines() {
+                            writeln!(writer, "    {line}")?;
+                        }
+                    }
+                    writeln!(
+                        writer,
+                        "Once the node logs that it switched to the next root seed, \
+                         set it as the `root-seed-key-pair`, remove `next-root-seed-key-pair`, \
+                         and retire the previous root seed."
+                    )?;
+                }
+            }
             IkaValidatorCommandResponse::FetchCurrentPricingInfo(path) => {
                 writeln!(
                     writer,
                     "Fetched current pricing info from Sui, you can view & edit it at: {path:?}"
                 )?;
             }
+            IkaValidatorCommandResponse::ProposeParameter {
+                submitted,
+                pending_votes,
+            } => {
+                writeln!(writer, "{}", submitted.trim_end())?;
+                writeln!(writer, "System parameter votes in the current epoch:")?;
+                write!(writer, "{pending_votes}")?;
+            }
+            IkaValidatorCommandResponse::EncryptRootSeed { seed_path, unlock } => {
+                writeln!(writer, "Encrypted root seed file: {seed_path:?}")?;
+                writeln!(writer, "To use it, set in the node config:")?;
+                writeln!(writer, "root-seed-key-pair:")?;
+                writeln!(writer, "  path: {}", seed_path.display())?;
+                writeln!(writer, "  unlock:")?;
+                let unlock = serde_yaml::to_string(unlock).map_err(|_| fmt::Error)?;
+                for line in unlock.lines() {
+                    writeln!(writer, "    {line}")?;
+                }
+                writeln!(
+                    writer,
+                    "Once the node runs with the encrypted seed, securely delete the plain seed file."
+                )?;
+            }
+            IkaValidatorCommandResponse::SplitRootSeed(share_paths) => {
+                writeln!(writer, "Root seed share files:")?;
+                for path in share_paths {
+                    writeln!(writer, "  {path:?}")?;
+                }
+                writeln!(
+                    writer,
+                    "Move each share to a different secure location, and delete it from this machine."
+                )?;
+            }
+            IkaValidatorCommandResponse::RecoverRootSeed(path) => {
+                writeln!(
+                    writer,
+                    "Recovered the root seed, which derives the validator's class-groups key: {path:?}"
+                )?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
@@ -1183,6 +1726,18 @@ impl IkaValidatorCommandResponse {
     }
 }
 
+/// The signer of the validator operation transactions: the signing service, if configured,
+/// or else the key of the wallet's active address.
+async fn operation_signer(
+    context: &WalletContext,
+    remote_signer: Option<&RemoteSignerConfig>,
+) -> Result<Box<dyn Signer>> {
+    Ok(match remote_signer {
+        Some(remote_signer) => Box::new(RemoteSigner::connect(remote_signer).await?),
+        None => Box::new(FileSigner::from_wallet(context)?),
+    })
+}
+
 fn make_key_files(
     file_name: PathBuf,
     is_protocol_key: bool,
@@ -1212,20 +1767,55 @@ fn make_key_files(
     Ok(())
 }
 
+/// Encrypts the seed to `path`, generating the key-encryption key if its file doesn't exist,
+/// and reads it back to check it can be decrypted.
+fn write_encrypted_root_seed(seed: &RootSeed, path: &Path, unlock: &RootSeedUnlock) -> Result<()> {
+    if let RootSeedUnlock::KeyEncryptionKeyFile(key_path) = unlock {
+        if !key_path.exists() {
+            KeyEncryptionKey::random().save_to_file(key_path)?;
+            println!("Generated new key-encryption key file: {key_path:?}.");
+        }
+    }
+    seed.save_to_encrypted_file(path, unlock)?;
+    if RootSeed::from_encrypted_file(path, unlock)? != *seed {
+        anyhow::bail!("the encrypted root seed at {path:?} doesn't match");
+    }
+    Ok(())
+}
+
+/// Whether the seed derives the class-groups encryption key in any of `mpc_data`.
+fn root_seed_matches_mpc_data(seed: &RootSeed, mpc_data: &[VersionedMPCData]) -> Result<bool> {
+    let encryption_key_and_proof =
+        ClassGroupsKeyPairAndProof::from_seed(seed).encryption_key_and_proof();
+    for mpc_data in mpc_data {
+        let expected: ClassGroupsEncryptionKeyAndProof =
+            bcs::from_bytes(&mpc_data.class_groups_public_key_and_proof())?;
+        if expected
+            .iter()
+            .map(|(encryption_key, _)| encryption_key)
+            .eq(encryption_key_and_proof
+                .iter()
+                .map(|(encryption_key, _)| encryption_key))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Generates the class groups a key pair and proof from a seed file if it exists,
 /// otherwise generates and saves the seed.
 fn read_or_generate_root_seed(seed_path: PathBuf) -> Result<Box<ClassGroupsKeyPairAndProof>> {
-    let seed = match RootSeed::from_file(seed_path.clone()) {
-        Ok(seed) => {
-            println!("Use existing seed: {seed_path:?}.",);
-            seed
-        }
-        Err(_) => {
-            let seed = RootSeed::random_seed();
-            seed.save_to_file(seed_path.clone())?;
-            println!("Generated root seed () file: {seed_path:?}.",);
-            seed
-        }
+    // Don't replace a seed file that can't be read, e.g. because it is encrypted.
+    let seed = if seed_path.exists() {
+        let seed = RootSeed::from_file(seed_path.clone())?;
+        println!("Use existing seed: {seed_path:?}.",);
+        seed
+    } else {
+        let seed = RootSeed::random_seed();
+        seed.save_to_file(seed_path.clone())?;
+        println!("Generated root seed () file: {seed_path:?}.",);
+        seed
     };
 
     let class_groups_public_key_and_proof = Box::new(ClassGroupsKeyPairAndProof::from_seed(&seed));